@@ -0,0 +1,21 @@
+//! Generate `rvemu_bindings.h`, the C header of the exported functions and the types they take,
+//! so the C, C++ and C# consumers share one description of the ABI instead of hand-written copies.
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=../rvemu/src");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("the C header is generated")
+        .write_to_file(crate_dir.join("rvemu_bindings.h"));
+}
@@ -0,0 +1,2386 @@
+/* Generated by cbindgen from rvemu-bindings. Don't edit it: run `cargo build` instead. */
+
+#ifndef RVEMU_BINDINGS_H
+#define RVEMU_BINDINGS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// The register index of the program counter in `emulator_get_register` and
+// `emulator_set_register`, after x0 to x31.
+#define REGISTER_PC 32
+
+// The number of arguments of a `DrawCommand`, repeated for the C header.
+#define DRAW_ARGS 6
+
+// The size of the disassembly buffer of `StepAnnotation`, including the NUL. It's repeated here
+// for the C header, which only gets the constants of this crate.
+#define ANNOTATION_TEXT_SIZE 64
+
+// The most stores a `ReferenceState` reports for one step, repeated for the C header.
+#define MAX_REFERENCE_STORES 4
+
+// The number of results a session keeps before it forgets the oldest.
+#define MAX_CACHED_RESULTS 256
+
+// Assemble micro-ISA sources instead of RISC-V assembly.
+#define ASSEMBLE_MICRO_ISA (1 << 0)
+
+// The step limit of levels that don't set one.
+#define DEFAULT_MAX_STEPS 1000000
+
+// The most runs spent shrinking the inputs of a lost case.
+#define MAX_SHRINK_RUNS 1000
+
+// The most instructions a sandboxed run can retire.
+#define MAX_SANDBOX_STEPS 100000000
+
+// The most memory a sandboxed program can use, from `DRAM_BASE`.
+#define MAX_SANDBOX_MEMORY ((16 * 1024) * 1024)
+
+// The UART, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_UART 1
+
+// The keyboard controller, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_KEYBOARD (1 << 1)
+
+// The text-mode buffer, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_VGA_TEXT (1 << 2)
+
+// The draw command queue, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_DRAW_QUEUE (1 << 3)
+
+// The frame synchronization device, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_VSYNC (1 << 4)
+
+// The audio sample ring buffer, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_AUDIO (1 << 5)
+
+// The save memory, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_SAVE_FILE (1 << 6)
+
+// The message channel, as a bit of the devices of a sandboxed run.
+#define SANDBOX_DEVICE_CHANNEL (1 << 7)
+
+// The outcome of an FFI call.
+enum RvStatus
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The call succeeded.
+  RV_STATUS_OK = 0,
+  // A pointer argument was null.
+  RV_STATUS_NULL_POINTER = 1,
+  // An argument was invalid, e.g. an unknown mode or an address outside DRAM.
+  RV_STATUS_INVALID_ARGUMENT = 2,
+  // The emulator panicked. It may be left in an inconsistent state and should be destroyed.
+  RV_STATUS_PANICKED = 3,
+  // The host had no memory for a new emulator, e.g. because too many are alive.
+  RV_STATUS_ALLOCATION_FAILED = 4,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum RvStatus RvStatus;
+#else
+typedef uint32_t RvStatus;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// Why the emulator stopped running. Every run mode reports one of these, so the host never has
+// to infer it from side channels.
+enum StopReason
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The requested number of steps was executed.
+  STOP_REASON_STEP_LIMIT = 0,
+  // An instruction raised an exception. The cause and the trap value are in the summary.
+  STOP_REASON_TRAPPED = 1,
+  // The guest made an environment call (`ecall`) for the host to serve. The program counter
+  // is already past the `ecall`.
+  STOP_REASON_YIELDED = 2,
+  // The guest executed an `ebreak`. The program counter points at it.
+  STOP_REASON_BREAKPOINT = 3,
+  // A watched memory location was accessed. The program counter is past the instruction that
+  // accessed it.
+  STOP_REASON_WATCHPOINT = 4,
+  // The host time budget of the run ran out.
+  STOP_REASON_TIME_BUDGET = 5,
+  // The guest called `exit`. The exit code is in the summary.
+  STOP_REASON_EXITED = 6,
+  // The host cancelled the run.
+  STOP_REASON_CANCELLED = 7,
+  // A resource limit other than the step count of this run was exceeded.
+  STOP_REASON_LIMIT_EXCEEDED = 8,
+  // The guest executed `mret`, `sret` or `uret` in `run_until_trap_return`. The program
+  // counter is where the instruction returned to.
+  STOP_REASON_TRAP_RETURN = 9,
+  // The program counter reached a breakpoint set by the host. The instruction there isn't
+  // executed yet; the next run executes it without stopping.
+  STOP_REASON_HOST_BREAKPOINT = 10,
+  // The guest presented a frame through the vsync device. The program counter is past the
+  // store, so the next run starts the next frame.
+  STOP_REASON_FRAME_PRESENTED = 11,
+  // A guest assert failed. The program counter is past the `ecall`, the line number of the
+  // assert is in the summary and the failure is in `guest_asserts`.
+  STOP_REASON_GUEST_ASSERTION_FAILED = 12,
+  // The run executed as many steps as the fuel of the emulator allows, before its own step
+  // limit, e.g. because the program is stuck in an infinite loop.
+  STOP_REASON_FUEL_EXHAUSTED = 13,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum StopReason StopReason;
+#else
+typedef uint32_t StopReason;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The kind of a memory access.
+enum AccessKind
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // A load, or the read of an atomic memory operation.
+  ACCESS_KIND_LOAD = 0,
+  // A store, or the write of an atomic memory operation.
+  ACCESS_KIND_STORE = 1,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum AccessKind AccessKind;
+#else
+typedef uint32_t AccessKind;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The atomic instruction of an event.
+enum AtomicOp
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // `lr.w` or `lr.d`: the address is reserved.
+  ATOMIC_OP_LOAD_RESERVED = 0,
+  // `sc.w` or `sc.d`: the store happens only if the reservation is still held.
+  ATOMIC_OP_STORE_CONDITIONAL = 1,
+  // `amoswap`.
+  ATOMIC_OP_SWAP = 2,
+  // `amoadd`.
+  ATOMIC_OP_ADD = 3,
+  // `amoxor`.
+  ATOMIC_OP_XOR = 4,
+  // `amoand`.
+  ATOMIC_OP_AND = 5,
+  // `amoor`.
+  ATOMIC_OP_OR = 6,
+  // `amomin`.
+  ATOMIC_OP_MIN = 7,
+  // `amomax`.
+  ATOMIC_OP_MAX = 8,
+  // `amominu`.
+  ATOMIC_OP_MINU = 9,
+  // `amomaxu`.
+  ATOMIC_OP_MAXU = 10,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum AtomicOp AtomicOp;
+#else
+typedef uint32_t AtomicOp;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The instruction of a trap return.
+enum ReturnInstruction
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // `mret`: return from a machine-mode trap handler to `mepc`.
+  RETURN_INSTRUCTION_MRET = 0,
+  // `sret`: return from a supervisor-mode trap handler to `sepc`.
+  RETURN_INSTRUCTION_SRET = 1,
+  // `uret`: return from a user-mode trap handler to `uepc`.
+  RETURN_INSTRUCTION_URET = 2,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum ReturnInstruction ReturnInstruction;
+#else
+typedef uint32_t ReturnInstruction;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The kind of a corner encoding.
+enum EncodingKind
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // A hint: it executes as a no-op, but may be given a meaning by future extensions.
+  ENCODING_KIND_HINT = 0,
+  // A reserved encoding: the behavior is undefined.
+  ENCODING_KIND_RESERVED = 1,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum EncodingKind EncodingKind;
+#else
+typedef uint32_t EncodingKind;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// How a previewed instruction affects where execution goes.
+enum Flow
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // Execution goes on with the next instruction.
+  FLOW_SEQUENTIAL = 0,
+  // A conditional branch to `target`. The preview goes on with the next instruction, as if
+  // the branch weren't taken.
+  FLOW_BRANCH = 1,
+  // A jump to `target`, where the preview goes on.
+  FLOW_JUMP = 2,
+  // A jump through a register to `target`, where the preview goes on. The target is predicted
+  // from the current register values, which earlier instructions may still change.
+  FLOW_INDIRECT_JUMP = 3,
+  // The instruction hands control to a trap handler or returns from one, e.g. `ecall` or
+  // `mret`, or isn't a valid instruction. The preview ends with it.
+  FLOW_STOP = 4,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum Flow Flow;
+#else
+typedef uint32_t Flow;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The plain-language template explaining a step. The ids are stable, so the overlay can keep
+// its localized texts by id.
+enum Narration
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // No template fits, e.g. a floating-point or compressed instruction.
+  NARRATION_OTHER = 0,
+  // "`rd` = `rs1` op `rs2`"
+  NARRATION_COMPUTE = 1,
+  // "`rd` = `rs1` op an immediate"
+  NARRATION_COMPUTE_IMMEDIATE = 2,
+  // "`rd` = an upper immediate", `lui` and `auipc`.
+  NARRATION_UPPER_IMMEDIATE = 3,
+  // "`rd` = the value loaded from `addr`"
+  NARRATION_LOAD = 4,
+  // "`rs2` was stored to `addr`"
+  NARRATION_STORE = 5,
+  // "`rs1` and `rs2` compared true, so the branch was taken"
+  NARRATION_BRANCH_TAKEN = 6,
+  // "`rs1` and `rs2` compared false, so execution went on with the next instruction"
+  NARRATION_BRANCH_NOT_TAKEN = 7,
+  // "the program jumped, saving the return address in `rd`"
+  NARRATION_JUMP = 8,
+  // "`rd` = the old value of a CSR"
+  NARRATION_CSR = 9,
+  // "the program asked the environment for a service", `ecall` and `ebreak`.
+  NARRATION_ENVIRONMENT_CALL = 10,
+  // "the instruction raised an exception"
+  NARRATION_TRAP = 11,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum Narration Narration;
+#else
+typedef uint32_t Narration;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// How a sandboxed run ended.
+enum SandboxStatus
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The program ran. Why it stopped is in the summary.
+  SANDBOX_STATUS_RAN = 0,
+  // The program doesn't fit in the memory limit and didn't run.
+  SANDBOX_STATUS_TOO_LARGE = 1,
+  // The emulator panicked. The summary is empty.
+  SANDBOX_STATUS_PANICKED = 2,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum SandboxStatus SandboxStatus;
+#else
+typedef uint32_t SandboxStatus;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The state of a bot in a match.
+enum BotState
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The bot takes turns.
+  BOT_STATE_RUNNING = 0,
+  // The bot called `exit`.
+  BOT_STATE_EXITED = 1,
+  // An instruction of the bot raised an exception or executed `ebreak`.
+  BOT_STATE_TRAPPED = 2,
+  // The bot used up its steps.
+  BOT_STATE_OUT_OF_STEPS = 3,
+  // The game eliminated the bot.
+  BOT_STATE_ELIMINATED = 4,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum BotState BotState;
+#else
+typedef uint32_t BotState;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The state of a node.
+enum NodeState
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The node runs every round.
+  NODE_STATE_RUNNING = 0,
+  // The node called `exit`.
+  NODE_STATE_EXITED = 1,
+  // An instruction of the node raised an exception or executed `ebreak`.
+  NODE_STATE_TRAPPED = 2,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum NodeState NodeState;
+#else
+typedef uint32_t NodeState;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// A part of a step delta that's compared.
+enum Field
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // The program counter before the step.
+  FIELD_PC = 0,
+  // The executed instruction.
+  FIELD_INST = 1,
+  // The program counter after the step.
+  FIELD_NEXT_PC = 2,
+  // The privilege mode after the step.
+  FIELD_MODE = 3,
+  // The changed integer registers.
+  FIELD_XREGS = 4,
+  // The changed floating-point registers.
+  FIELD_FREGS = 5,
+  // The changed CSRs.
+  FIELD_CSRS = 6,
+  // The stores.
+  FIELD_MEMORY = 7,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum Field Field;
+#else
+typedef uint32_t Field;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The kind of a draw command.
+enum DrawKind
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // Copy the `width` x `height` pixels at guest address `arg0`, 4 bytes each in RGBA order,
+  // to (`arg1`, `arg2`). The width and height are `arg3` and `arg4`.
+  DRAW_KIND_BLIT = 1,
+  // Fill the `arg2` x `arg3` rectangle at (`arg0`, `arg1`) with the RGBA color `arg4`.
+  DRAW_KIND_FILL_RECT = 2,
+  // Set the palette entry `arg0` to the RGBA color `arg1`.
+  DRAW_KIND_SET_PALETTE = 3,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum DrawKind DrawKind;
+#else
+typedef uint32_t DrawKind;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// The accesses a watchpoint stops at.
+enum WatchKind
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : uint32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  // Loads.
+  WATCH_KIND_READ = 1,
+  // Stores.
+  WATCH_KIND_WRITE = 2,
+  // Loads and stores.
+  WATCH_KIND_ACCESS = 3,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum WatchKind WatchKind;
+#else
+typedef uint32_t WatchKind;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+// Two or more bots taking turns on one emulator.
+typedef struct Arena Arena;
+
+// The cache of an assembler session.
+typedef struct AssemblerSession AssemblerSession;
+
+// A token to cancel a long operation. Clones share the same state, so the host can keep one and
+// cancel from another thread while the emulator is running.
+typedef struct CancelToken CancelToken;
+
+// An emulator configured for a level, with what's needed to judge its runs.
+typedef struct ConfiguredEmulator ConfiguredEmulator;
+
+// The emulator and a reference model, stepped in lockstep.
+typedef struct Cosim Cosim;
+
+// The emulator to hold a CPU.
+typedef struct Emulator Emulator;
+
+// Harts sharing one emulator, scheduled by a seeded scheduler.
+typedef struct Harts Harts;
+
+// A level spec parsed and validated once, from which `instantiate` creates emulators configured
+// for the level, e.g. one per retry of the player or per graded submission. The template itself
+// never changes, and its instances don't share any state.
+typedef struct LevelTemplate LevelTemplate;
+
+// Why a lockstep run stopped.
+typedef struct Lockstep Lockstep;
+
+// Plays a bundle back on the real emulator.
+typedef struct ReplayPlayer ReplayPlayer;
+
+// Records a solution run. The host steps the emulator and feeds it inputs through the
+// recorder.
+typedef struct ReplayRecorder ReplayRecorder;
+
+// The host memory held by an emulator in bytes, by use. The layout is C-compatible so it can be
+// returned over the FFI as is.
+typedef struct MemoryUsage {
+  // The DRAM allocated. The host only commits the pages the guest writes, so less of it may
+  // be resident.
+  uint64_t dram;
+  // The snapshot storage: the rewind checkpoints, and the DRAM pages saved to undo a
+  // speculative run.
+  uint64_t snapshots;
+  // The instruction trace, the logs of memory accesses, CSR writes, atomic instructions,
+  // privilege returns and debug prints, and the memory statistics.
+  uint64_t traces;
+  // The lines of the cache model of the memory statistics.
+  uint64_t caches;
+  // The sum of the others.
+  uint64_t total;
+} MemoryUsage;
+
+// A register or CSR whose value changed. The layout is C-compatible so it can be copied over the
+// FFI as is.
+typedef struct RegisterChange {
+  // The register number, or the address of a CSR.
+  uint64_t index;
+  // The value before the step. Floating-point registers hold the bits of the value.
+  uint64_t old;
+  // The value after the step.
+  uint64_t new_;
+} RegisterChange;
+
+// The effect of one instruction executed by `emulator_cpu_execute_traced`, e.g. to animate the
+// register it changed.
+typedef struct ExecuteTrace {
+  // What `emulator_cpu_execute` reports: the instruction, or a code for its exception.
+  uint32_t executed_instruction;
+  // The number of valid entries in `changes`.
+  uint32_t change_count;
+  // The program counter before the instruction.
+  uint64_t old_pc;
+  // The program counter after the instruction.
+  uint64_t new_pc;
+  // The integer registers that changed, by register number.
+  struct RegisterChange changes[2];
+} ExecuteTrace;
+
+// The outcome of a run. The layout is C-compatible so it can be returned over the FFI as is.
+typedef struct RunSummary {
+  // Why the emulator stopped.
+  StopReason reason;
+  // The number of instructions retired in this run.
+  uint64_t steps;
+  // The program counter when the emulator stopped. For `Trapped`, it's the address of the
+  // instruction that raised the exception.
+  uint64_t stop_pc;
+  // The exception code (mcause) for `Trapped`, `Yielded` and `Breakpoint`, otherwise 0.
+  uint64_t cause;
+  // The trap value (mtval) for `Trapped`, the accessed address for `Watchpoint`, the line
+  // number of the assert for `GuestAssertionFailed`, otherwise 0.
+  uint64_t tval;
+  // The exit code for `Exited`, otherwise 0.
+  uint64_t exit_code;
+  // The host time spent in this run in nanoseconds.
+  uint64_t wall_time_ns;
+  // The number of interrupts taken in this run.
+  uint64_t interrupts;
+  // The number of instructions retired since the emulator was created.
+  uint64_t total_retired;
+  // The value of the timer (mtime) when the emulator stopped.
+  uint64_t mtime;
+  // The nominal clock frequency of the core in hertz.
+  uint64_t clock_hz;
+  // The simulated time of the instructions retired in this run at `clock_hz`, in
+  // microseconds, e.g. to tell the player their routine takes 4.2µs on a 100 MHz core.
+  double simulated_us;
+} RunSummary;
+
+// An access that hit a watchpoint.
+typedef struct WatchHit {
+  // The address of the instruction.
+  uint64_t pc;
+  // The address the instruction accessed.
+  uint64_t addr;
+  // The size of the access in bytes.
+  uint64_t size;
+  // The value at `addr` before the access, `size` bytes wide, or 0 if it's unknown.
+  uint64_t old_value;
+  // The value at `addr` after the access, `size` bytes wide: the stored value, or the same
+  // as `old_value` for a load.
+  uint64_t new_value;
+  // Whether the instruction loaded or stored.
+  AccessKind kind;
+  // 1 if `old_value` is known, 0 for a store to a device: reading its registers can have side
+  // effects, e.g. popping a byte from a FIFO, so they aren't read before the store.
+  uint32_t old_value_known;
+} WatchHit;
+
+// An executed instruction. The layout is C-compatible so entries can be copied over the FFI as
+// they are.
+typedef struct TraceEntry {
+  // The address of the instruction.
+  uint64_t pc;
+  // The instruction word. Compressed instructions are 16 bits.
+  uint64_t inst;
+} TraceEntry;
+
+// A load or a store executed by the program. The layout is C-compatible so entries can be
+// copied over the FFI as they are.
+typedef struct MemoryAccess {
+  // The address of the instruction.
+  uint64_t pc;
+  // The accessed address.
+  uint64_t addr;
+  // The size of the access in bytes.
+  uint64_t size;
+  // The loaded or stored value.
+  uint64_t value;
+  // Whether the instruction loaded or stored.
+  AccessKind kind;
+} MemoryAccess;
+
+// An executed atomic instruction. The layout is C-compatible so events can be copied over the
+// FFI as they are.
+typedef struct AtomicEvent {
+  // The ID of the hart that executed the instruction.
+  uint64_t hart;
+  // The address of the instruction.
+  uint64_t pc;
+  // The accessed address.
+  uint64_t addr;
+  // The value in memory before the instruction.
+  uint64_t old_value;
+  // The value in memory after the instruction. Same as `old_value` for `lr` and failed `sc`.
+  uint64_t new_value;
+  // The size of the access in bytes: 4 or 8.
+  uint32_t size;
+  // The instruction.
+  AtomicOp op;
+  // 1 if the instruction took effect, 0 for a failed `sc`.
+  uint32_t success;
+} AtomicEvent;
+
+// A write to a CSR, by an instruction or by the core itself when it takes a trap or returns
+// from one. The layout is C-compatible so writes can be copied over the FFI as they are.
+typedef struct CsrWrite {
+  // The address of the instruction that wrote the CSR, or the interrupted instruction for the
+  // writes of an interrupt.
+  uint64_t pc;
+  // The address of the CSR. Writes to `sstatus`, `sie` and `sip` are recorded as writes to
+  // `mstatus`, `mie` and `mip`, which hold their bits.
+  uint64_t addr;
+  // The value before the write.
+  uint64_t old;
+  // The value after the write. Read-only bits keep their old value.
+  uint64_t new_;
+} CsrWrite;
+
+// An executed privilege-return instruction. The layout is C-compatible so events can be copied
+// over the FFI as they are.
+typedef struct TrapReturn {
+  // The address of the instruction.
+  uint64_t pc;
+  // The address execution continues at.
+  uint64_t target;
+  // The privilege level before the instruction: 0 for user, 1 for supervisor and 3 for
+  // machine mode.
+  uint64_t from;
+  // The privilege level after the instruction.
+  uint64_t to;
+  // The instruction.
+  ReturnInstruction instruction;
+} TrapReturn;
+
+// An executed corner encoding. The layout is C-compatible so events can be copied over the FFI
+// as they are.
+typedef struct EncodingEvent {
+  // The address of the instruction.
+  uint64_t pc;
+  // The instruction word. Compressed instructions are 16 bits.
+  uint64_t inst;
+  // The number of times it was executed, or trapped.
+  uint64_t count;
+  // Whether it's a hint or reserved.
+  EncodingKind kind;
+} EncodingEvent;
+
+// A single-precision instruction that read a register that isn't properly NaN-boxed. The
+// layout is C-compatible so reads can be copied over the FFI as they are.
+typedef struct UnboxedRead {
+  // The address of the instruction.
+  uint64_t pc;
+  // The instruction word.
+  uint64_t inst;
+  // The floating-point register it read.
+  uint64_t reg;
+  // The number of times it was executed.
+  uint64_t count;
+} UnboxedRead;
+
+// The memory accesses of one instruction. The layout is C-compatible so it can be copied over
+// the FFI as is.
+typedef struct SiteStats {
+  // The address of the instruction.
+  uint64_t pc;
+  // The number of loads it made.
+  uint64_t loads;
+  // The number of stores it made.
+  uint64_t stores;
+  // The number of its accesses that hit the cache model. 0 without a cache model.
+  uint64_t cache_hits;
+  // The number of its accesses that missed the cache model. 0 without a cache model.
+  uint64_t cache_misses;
+} SiteStats;
+
+// What gates an interrupt. The layout is C-compatible so gates can be copied over the FFI as
+// they are. The flags are 0 or 1.
+typedef struct InterruptGate {
+  // The exception code of the interrupt, also its bit in mie and mip.
+  uint64_t code;
+  // The bit in mip is set.
+  uint32_t pending;
+  // The bit in mie is set.
+  uint32_t enabled;
+  // Interrupts are globally enabled in the current mode: by mstatus.MIE in machine mode, by
+  // mstatus.SIE in supervisor mode, and always in user mode.
+  uint32_t global;
+  // The bit in mideleg is set, so the trap goes to supervisor mode.
+  uint32_t delegated;
+  // The interrupt is the one taken before the next instruction.
+  uint32_t taken;
+} InterruptGate;
+
+// An instruction the program is about to execute. The layout is C-compatible so previews can
+// be copied over the FFI as they are.
+typedef struct PreviewedInstruction {
+  // The address of the instruction.
+  uint64_t pc;
+  // The instruction. Compressed instructions are 16 bits.
+  uint64_t inst;
+  // How the instruction affects where execution goes.
+  Flow flow;
+  // Where a branch or jump goes, otherwise 0.
+  uint64_t target;
+} PreviewedInstruction;
+
+// A register an instruction read and its value before the step.
+typedef struct OperandValue {
+  uint64_t reg;
+  uint64_t value;
+} OperandValue;
+
+// Everything the tutorial overlay narrates about one step. The layout is C-compatible so it
+// can be copied over the FFI as is.
+typedef struct StepAnnotation {
+  // Why the step stopped, as for a run of one step.
+  StopReason reason;
+  // The template explaining the step.
+  Narration narration;
+  // The `ANNOTATION_*` flags.
+  uint32_t flags;
+  // The number of valid entries in `sources`.
+  uint32_t source_count;
+  // The program counter before the step.
+  uint64_t pc;
+  // The instruction at `pc`. Compressed instructions are 16 bits.
+  uint64_t inst;
+  // The program counter after the step.
+  uint64_t next_pc;
+  // The integer registers the instruction read, `rs1` first.
+  struct OperandValue sources[2];
+  // The register written with `ANNOTATION_WRITES_RD`, otherwise 0.
+  uint64_t rd;
+  // The value written to `rd`, otherwise 0.
+  uint64_t result;
+  // The accessed address with `ANNOTATION_MEMORY`, otherwise 0.
+  uint64_t addr;
+  // The disassembly of the instruction, NUL-terminated and truncated to fit.
+  uint8_t disassembly[ANNOTATION_TEXT_SIZE];
+} StepAnnotation;
+
+// The state of a reference model after a step, for models that report their state rather than
+// the changes. The layout is C-compatible so models behind the FFI can fill it in.
+typedef struct ReferenceState {
+  // The program counter after the step.
+  uint64_t pc;
+  // The integer registers.
+  uint64_t xregs[32];
+  // The bits of the floating-point registers.
+  uint64_t fregs[32];
+  // The number of valid entries in `stores`.
+  uint64_t store_count;
+  // The stores of the step, in program order. Only `addr`, `size` and `value` are compared.
+  struct MemoryAccess stores[MAX_REFERENCE_STORES];
+} ReferenceState;
+
+// A reference model behind the FFI. It's called once per step with the user data given with it,
+// executes one instruction, and writes its state after the instruction into `state`.
+typedef void (*ReferenceStepFn)(void *user_data, struct ReferenceState *state);
+
+// The result of grading a submission on the generated cases of a level. The layout is
+// C-compatible so it can be returned over the FFI as is.
+typedef struct GradeReport {
+  // The number of cases run.
+  uint64_t cases;
+  // The number of cases won.
+  uint64_t passed;
+  // 1 if a case was lost, otherwise 0.
+  uint32_t failed;
+  // The index of the first lost case, or 0. Grading with the same seed generates it again.
+  uint64_t failed_case;
+  // The summary of the run of the first lost case, or of the last case if every case was won.
+  struct RunSummary summary;
+} GradeReport;
+
+// The outcome of a sandboxed run. The layout is C-compatible so it can be returned over the
+// FFI as is.
+typedef struct SandboxResult {
+  SandboxStatus status;
+  struct RunSummary summary;
+} SandboxResult;
+
+// The status of a bot. The layout is C-compatible so it can be copied over the FFI as is.
+typedef struct BotStatus {
+  // Whether the bot still takes turns, and why not.
+  BotState state;
+  // The number of instructions the bot retired.
+  uint64_t steps;
+  // The program counter of the bot.
+  uint64_t pc;
+  // The exit code for `Exited`, otherwise 0.
+  uint64_t exit_code;
+  // The exception code for `Trapped`, otherwise 0.
+  uint64_t cause;
+} BotStatus;
+
+// The status of a node. The layout is C-compatible so it can be copied over the FFI as is.
+typedef struct NodeStatus {
+  // Whether the node still runs, and why not.
+  NodeState state;
+  // The number of instructions the node retired in lockstep.
+  uint64_t steps;
+  // The program counter of the node.
+  uint64_t pc;
+  // The exit code for `Exited`, otherwise 0.
+  uint64_t exit_code;
+  // The exception code for `Trapped`, otherwise 0.
+  uint64_t cause;
+} NodeStatus;
+
+// One side of a data race.
+typedef struct RaceAccess {
+  // The hart that accessed the memory.
+  uint64_t hart;
+  // The address of the instruction.
+  uint64_t pc;
+  // Whether the instruction loaded or stored.
+  AccessKind kind;
+} RaceAccess;
+
+// Two conflicting accesses that aren't ordered by synchronization. The layout is C-compatible
+// so races can be copied over the FFI as they are.
+typedef struct DataRace {
+  // The first byte both accesses touched.
+  uint64_t addr;
+  // The earlier access.
+  struct RaceAccess first;
+  // The later access.
+  struct RaceAccess second;
+} DataRace;
+
+// The arbiter of a match. It's called after every quantum with the user data given with it, the
+// emulator, the index of the bot that ran and the summary of its quantum, and returns an
+// `Arbitration`: 0 to continue, 1 to eliminate the bot, 2 to end the match.
+typedef uint32_t (*ArbiterFn)(void *user_data,
+                              struct Emulator *emu,
+                              uint64_t bot,
+                              const struct RunSummary *summary);
+
+// A draw command enqueued by the guest. The layout is C-compatible so it can be returned over
+// the FFI as is.
+typedef struct DrawCommand {
+  DrawKind kind;
+  uint32_t args[DRAW_ARGS];
+} DrawCommand;
+
+// A renderer behind the FFI. It's called once per draw command with the user data given with
+// it. The command is only valid during the call.
+typedef void (*DrawFn)(void *user_data, const struct DrawCommand *command);
+
+// The handler of the `ebreak`s of the guest. It's called with the user data given with it and
+// the address of the `ebreak`, and returns 0 to resume after it or anything else to stop the run
+// with `StopReason::Breakpoint`.
+typedef uint32_t (*EbreakFn)(void *user_data, uint64_t pc);
+
+// The registers of an environment call: the call number in a7 and the arguments in a0 to a5.
+typedef struct EcallArgs {
+  uint64_t number;
+  uint64_t args[6];
+} EcallArgs;
+
+// The handler of the environment calls of the guest. It's called with the user data given with
+// it, the call number in a7 and the arguments in a0 to a5, and the return value, initially a0.
+// It returns what the run does next: 0 writes the return value into a0 and resumes, 1 exits
+// with the return value as the exit code, and anything else stops the run for the host as if
+// there were no handler.
+typedef uint32_t (*EcallFn)(void *user_data, const struct EcallArgs *call, uint64_t *a0);
+
+// The callback of the evictions of the history. It's called with the user data given with it
+// and the numbers of checkpoints and of trace entries evicted at once.
+typedef void (*EvictionFn)(void *user_data, uint64_t checkpoints, uint64_t entries);
+
+// The progress callback of long operations. It's called with the user data given with it, the
+// amount of work done and the total amount of work.
+typedef void (*ProgressFn)(void *user_data, uint64_t done, uint64_t total);
+
+// Every field, in the order they're compared.
+#define Field_ALL { Field_Pc, Field_Inst, Field_NextPc, Field_Mode, Field_Xregs, Field_Fregs, Field_Csrs, Field_Memory, }
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create an emulator and write it into `emu`. Fails with `RvStatus::AllocationFailed` if the
+// host has no memory for it, instead of aborting the process.
+RvStatus emulator_create(struct Emulator **emu);
+
+// Write the bytes of memory allocated by every emulator of the process into `bytes`, e.g. to
+// decide whether there's room for another before creating it. Each takes up `DRAM_SIZE` bytes of
+// DRAM, most of the host memory it uses, though the host only commits the pages the guest
+// writes.
+RvStatus emulator_total_memory_usage(uint64_t *bytes);
+
+// Write the host memory the emulator holds into `usage`, in bytes: its DRAM, snapshot storage,
+// traces and caches, and their total, e.g. to show the footprint of the plugin or to disable
+// traces when it grows too large.
+RvStatus emulator_memory_usage(struct Emulator *emu, struct MemoryUsage *usage);
+
+RvStatus emulator_destroy(struct Emulator *emu);
+
+// Load `program_bytes` at the start of DRAM and start executing it there.
+RvStatus emulator_load_program(struct Emulator *emu, const uint8_t *program_bytes, size_t len);
+
+// Restart the loaded program without reloading it, e.g. for the "Restart" button: zero the
+// registers and CSRs and move the program counter back to the entry point. If `restore_memory`
+// isn't 0, the program loaded by `emulator_load_program` is also copied back over its text and
+// the injected instructions are removed, undoing what the program or the player wrote there. The
+// rest of memory and the devices are kept.
+RvStatus emulator_reset(struct Emulator *emu, uint32_t restore_memory);
+
+// Replace the text of the running program, the program loaded by `emulator_load_program`, with
+// the edited `bytes` of `len` bytes, keeping the rest of memory, the registers and the devices,
+// e.g. to apply a small code edit without resetting the data the program built up. `symbols` of
+// `symbols_len` bytes is the symbols JSON of the new text as `riscv_assemble_with_symbols` writes
+// it, or null. If it's given, the program counter moves to the same offset in the same symbol of
+// the new text, which needs the symbols of the old text from `emulator_add_symbol`, and the new
+// symbols replace them. Fails without changing anything if the program counter can't be moved.
+RvStatus emulator_reload_text(struct Emulator *emu,
+                              const uint8_t *bytes,
+                              size_t len,
+                              const uint8_t *symbols,
+                              size_t symbols_len);
+
+// Execute one step and write its instruction into `executed_instruction`. Like `emulator_step`,
+// the step runs a cycle on the devices and takes a pending interrupt first, so the instruction is
+// the first of the trap handler when one is taken. If the instruction raised an exception, a
+// code for it is written instead: 0x73 for an `ecall`, which is skipped, and 12 to 22 for the
+// others, in the order of their exception codes.
+RvStatus emulator_cpu_execute(struct Emulator *emu, uint32_t *executed_instruction);
+
+// Same as `emulator_cpu_execute`, but write the executed instruction, the program counter
+// before and after it, and the integer registers it changed into `trace`.
+RvStatus emulator_cpu_execute_traced(struct Emulator *emu, struct ExecuteTrace *trace);
+
+// Execute one instruction and write the outcome into `summary`. Unlike `emulator_cpu_execute`,
+// the stop reason tells why the step didn't complete, if it didn't.
+RvStatus emulator_step(struct Emulator *emu, struct RunSummary *summary);
+
+// Execute up to `max_steps` instructions and write the outcome into `summary`. The run stops
+// early on an exception, an `ebreak`, or an `ecall`; see `StopReason` for the reasons.
+RvStatus emulator_run(struct Emulator *emu, uint64_t max_steps, struct RunSummary *summary);
+
+// Limit every run to `instructions_per_call` instructions, whatever its own limit, so a program
+// stuck in an infinite loop can't hang the frame. A run that reaches it stops with
+// `StopReason::FuelExhausted`, e.g. to show a "program seems stuck" prompt. The budget is
+// refilled for each call. 0 removes the limit.
+RvStatus emulator_set_fuel(struct Emulator *emu, uint64_t instructions_per_call);
+
+// Same as `emulator_run`, but writes only the stop reason and the number of instructions
+// executed, for game loops that run a batch of instructions per frame and don't need the rest of
+// the summary.
+RvStatus emulator_run_batch(struct Emulator *emu,
+                            uint64_t max_instructions,
+                            StopReason *stop_reason,
+                            uint64_t *executed);
+
+// Same as `emulator_run`, but also stops with `StopReason::TimeBudget` after about
+// `time_budget_ns` nanoseconds of host time.
+RvStatus emulator_run_for(struct Emulator *emu,
+                          uint64_t max_steps,
+                          uint64_t time_budget_ns,
+                          struct RunSummary *summary);
+
+// Same as `emulator_run`, but also stops with `StopReason::TrapReturn` right after `mret`,
+// `sret` or `uret`, with the program counter at the instruction's target.
+RvStatus emulator_run_until_trap_return(struct Emulator *emu,
+                                        uint64_t max_steps,
+                                        struct RunSummary *summary);
+
+// Start running up to `max_steps` instructions on a background thread, e.g. so a long program
+// doesn't freeze the frame, and return at once. The run stops as `emulator_run` does, or when
+// `emulator_pause` is called. While it runs, only `emulator_get_register`,
+// `emulator_set_register`, `emulator_get_pc`, `emulator_set_pc`, `emulator_get_csr`,
+// `emulator_set_csr`, `emulator_read_memory`, `emulator_write_memory`, the
+// `emulator_read_u*` and `emulator_write_u*` functions and the background run functions may be
+// called on the emulator, from any thread: they wait for the run to be between two slices of
+// instructions. Destroying the emulator pauses the run first. Fails if it's already running.
+RvStatus emulator_run_async(struct Emulator *emu, uint64_t max_steps);
+
+// Pause the background run of the emulator and wait until it stopped, after at most a slice of
+// instructions. `emulator_run_async` resumes it. Fails if it was never run in the background.
+RvStatus emulator_pause(struct Emulator *emu);
+
+// Write 1 into `running` if the emulator is running in the background, or 0 once its run was
+// paused or stopped, e.g. to poll for the end of a run every frame.
+RvStatus emulator_is_running(struct Emulator *emu, uint32_t *running);
+
+// Write the outcome of the last background run of the emulator so far into `summary`: why it
+// stopped, if it did, and the instructions it executed in total. Fails if it was never run in the
+// background or hasn't executed its first slice of instructions yet, and with
+// `RvStatus::Panicked` if the emulator panicked during the run.
+RvStatus emulator_async_summary(struct Emulator *emu, struct RunSummary *summary);
+
+// Copy the message of the last failed call on this thread into `out` as UTF-8 (not
+// NUL-terminated), e.g. for a call that returned an `RvStatus` other than `Ok`, and write its
+// full length into `full_len`, 0 if no call failed.
+RvStatus emulator_last_error_message(uint8_t *out, size_t len, uint64_t *full_len);
+
+// Create an emulator and write its handle id into `handle`. The `emulator_handle_*` functions
+// take the id instead of a pointer. An id is a plain number, so a managed host can store it with
+// its objects and keep using it after a Unity domain reload. It's never 0 and never reused, so a
+// stale id fails with a status instead of resolving to another emulator. Fails as
+// `emulator_create` does if the host has no memory for it.
+RvStatus emulator_handle_create(uint64_t *handle);
+
+// Destroy the emulator with the handle id `handle`.
+RvStatus emulator_handle_destroy(uint64_t handle);
+
+// Destroy every live handle created through the FFI: the emulators, levels and templates,
+// assembler sessions and the other objects, with the ones borrowed from them, and forget every
+// handle id. Call it before the host unloads the library or, in Unity, before a domain reload,
+// which would otherwise leak them. No other thread may call in while it runs. The stale ids, and
+// in debug builds the stale pointers, fail with a status afterwards. The background runs are
+// paused first. Writes the number of handles destroyed into `destroyed`.
+RvStatus rvj_shutdown_all(uint64_t *destroyed);
+
+// Write the pointer of the emulator with the handle id `handle` into `emu`, to call the
+// `emulator_*` functions that have no handle variant. The pointer must not be kept: it's only
+// valid until the emulator is destroyed, and not across a domain reload.
+RvStatus emulator_handle_pointer(uint64_t handle, struct Emulator **emu);
+
+// Same as `emulator_load_program`, with a handle id.
+RvStatus emulator_handle_load_program(uint64_t handle, const uint8_t *program_bytes, size_t len);
+
+// Same as `emulator_step`, with a handle id.
+RvStatus emulator_handle_step(uint64_t handle, struct RunSummary *summary);
+
+// Same as `emulator_run`, with a handle id.
+RvStatus emulator_handle_run(uint64_t handle, uint64_t max_steps, struct RunSummary *summary);
+
+// Same as `emulator_get_register`, with a handle id.
+RvStatus emulator_handle_get_register(uint64_t handle, uint64_t index, uint64_t *value);
+
+// Same as `emulator_set_register`, with a handle id.
+RvStatus emulator_handle_set_register(uint64_t handle, uint64_t index, uint64_t value);
+
+// Same as `emulator_read_memory`, with a handle id.
+RvStatus emulator_handle_read_memory(uint64_t handle, uint64_t addr, uint8_t *buf, size_t len);
+
+// Same as `emulator_write_memory`, with a handle id.
+RvStatus emulator_handle_write_memory(uint64_t handle,
+                                      uint64_t addr,
+                                      const uint8_t *buf,
+                                      size_t len);
+
+// Save the registers, the program counter, the CSRs and DRAM of `emu` into a new buffer, e.g. for
+// a rewind feature. Writes the buffer into `out` and its length into `len`. The buffer must be
+// freed with `free_state_buffer`.
+RvStatus emulator_save_state(struct Emulator *emu, uint8_t **out, size_t *len);
+
+// Restore `emu` to the state saved by `emulator_save_state` in the `len` bytes at `buf`. Returns
+// `RvStatus::InvalidArgument`, leaving `emu` as it is, if they aren't a snapshot of this version.
+RvStatus emulator_restore_state(struct Emulator *emu, const uint8_t *buf, size_t len);
+
+// Free a buffer of `len` bytes returned by `emulator_save_state`.
+RvStatus free_state_buffer(uint8_t *buf, size_t len);
+
+// Take a rewind checkpoint of the emulator, a snapshot kept inside it, and write its id for
+// `emulator_rewind` into `id`. Ids are never 0. The checkpoint counts toward the budget set with
+// `emulator_set_history_budget`, and may be evicted at once if it doesn't fit.
+RvStatus emulator_checkpoint(struct Emulator *emu, uint64_t *id);
+
+// Restore the emulator to the checkpoint `id`, e.g. for a rewind button. The checkpoint is kept,
+// so it can be rewound to again. Fails if it was evicted or never taken.
+RvStatus emulator_rewind(struct Emulator *emu, uint64_t id);
+
+// Copy the rewind checkpoints of the emulator, oldest first, into `out` as JSON: a list of
+// `{"id", "retired", "size"}` objects with the instructions retired when each was taken and the
+// bytes of its snapshot. Writes the full length of the JSON into `full_len`.
+RvStatus emulator_list_checkpoints(struct Emulator *emu,
+                                   uint8_t *out,
+                                   size_t len,
+                                   uint64_t *full_len);
+
+// Cap the bytes of the rewind checkpoints and the traces of the emulator at `bytes`, so a long
+// play session doesn't grow memory without bound. 0 removes the cap. When they exceed it, after
+// a checkpoint or a run, the least recently taken or rewound-to checkpoints are evicted first,
+// then the oldest entries of the memory access trace and of the logs of CSR writes, atomic
+// instructions and privilege returns. `emulator_history_truncated` tells how many were.
+RvStatus emulator_set_history_budget(struct Emulator *emu, uint64_t bytes);
+
+// Write the number of checkpoints and trace entries evicted to fit in the budget since the last
+// call into `evicted`, e.g. to tell the player once a frame that the history was truncated.
+RvStatus emulator_history_truncated(struct Emulator *emu, uint64_t *evicted);
+
+// Call `evicted` with `user_data` whenever checkpoints or trace entries are evicted to fit in the
+// budget set with `emulator_set_history_budget`, so the host can tell the player the history was
+// truncated without polling `emulator_history_truncated`, which still counts them. A null
+// `evicted` removes the callback.
+RvStatus emulator_set_eviction_callback(struct Emulator *emu, EvictionFn evicted, void *user_data);
+
+// Select whether `emulator_step` takes a pending interrupt: 0 = take it and execute the first
+// instruction of the trap handler (the default), 1 = defer it, so a step always executes the
+// next instruction of the program. Fails if `mode` is unknown.
+RvStatus emulator_set_step_interrupts(struct Emulator *emu, uint32_t mode);
+
+// Set the nominal clock frequency of the core to `hz` hertz, 100 MHz by default. Run summaries
+// report the simulated time of their steps at this frequency in `simulated_us`, one cycle per
+// instruction. Fails if `hz` is 0.
+RvStatus emulator_set_clock_frequency(struct Emulator *emu, uint64_t hz);
+
+// Write the nominal clock frequency of the core in hertz into `hz`.
+RvStatus emulator_clock_frequency(struct Emulator *emu, uint64_t *hz);
+
+// Write the value of the integer register `index` (0-31), or of the program counter for
+// `REGISTER_PC`, into `value`. In RV32, it's the 32-bit value, zero-extended.
+RvStatus emulator_get_register(struct Emulator *emu, uint64_t index, uint64_t *value);
+
+// Set the integer register `index` (0-31), or the program counter for `REGISTER_PC`, to
+// `value`. Writes to x0 are ignored, as x0 is always 0. In RV32, only the low 32 bits are
+// written.
+RvStatus emulator_set_register(struct Emulator *emu, uint64_t index, uint64_t value);
+
+// Write the program counter into `pc`, e.g. to highlight the current line in a debugger.
+RvStatus emulator_get_pc(struct Emulator *emu, uint64_t *pc);
+
+// Move the program counter to `addr`, so the next step executes the instruction there, e.g. for
+// "set next statement" or "run to cursor". A hart waiting in `wfi` wakes up. Fails if `addr`
+// isn't 2-byte aligned.
+RvStatus emulator_set_pc(struct Emulator *emu, uint64_t addr);
+
+// Copy the value of the register `index` of the register file `file` (0 for x0 to x31, 1 for
+// f0 to f31) into `out` as JSON, in every interpretation the UI shows: `hex`, plus `signed` and
+// `unsigned` for an integer register, or `f64`, `f32` and `nan_boxed` for a floating-point one,
+// e.g. `{"hex": "0xffffffffffffffff", "signed": "-1", "unsigned": "18446744073709551615"}`. The
+// values are strings so that 64-bit integers survive any JSON parser. Writes the full length of
+// the JSON into `full_len`. Fails if there's no such register.
+RvStatus emulator_format_register(struct Emulator *emu,
+                                  uint32_t file,
+                                  uint64_t index,
+                                  uint8_t *out,
+                                  size_t len,
+                                  uint64_t *full_len);
+
+// Copy the `len` bytes of guest memory at `addr` into `buf`, e.g. for a memory view. Injected
+// instructions read as the bytes they replaced. Fails without copying anything if they aren't
+// all in DRAM.
+RvStatus emulator_read_memory(struct Emulator *emu, uint64_t addr, uint8_t *buf, size_t len);
+
+// Copy the `len` bytes of `buf` to guest memory at `addr`, e.g. to inject the input of a level
+// at runtime. Fails without writing anything if they don't fit in DRAM.
+RvStatus emulator_write_memory(struct Emulator *emu, uint64_t addr, const uint8_t *buf, size_t len);
+
+// Back the `len` bytes of the address space at `addr` with the host buffer `buf`, e.g. a texture
+// the guest draws into, so the guest reads and writes it directly instead of the host copying it
+// every frame. The mapping hides the DRAM or devices beneath it from the guest, but not from
+// `emulator_read_memory`. The buffer must stay valid until it's unmapped or the emulator is
+// destroyed. Fails if `len` is 0 or the region overlaps another mapping.
+RvStatus emulator_map_host_buffer(struct Emulator *emu, uint64_t addr, uint8_t *buf, size_t len);
+
+// Back the address space at `addr` with the file at `path`, e.g. the dataset of a level, as
+// `emulator_map_host_buffer` does with a buffer. The file is mapped read-only if `copy_on_write`
+// is 0, so stores to it fault, and copy-on-write otherwise, so stores go to private pages and
+// never reach the file. Read-only pages are shared by every emulator mapping the file. Fails if
+// the file can't be mapped or is empty, or the region overlaps another mapping.
+RvStatus emulator_map_file(struct Emulator *emu,
+                           uint64_t addr,
+                           const char *path,
+                           uint32_t copy_on_write);
+
+// Remove the buffer or file mapped at `addr`. The host can free its buffer afterwards. Fails if
+// nothing is mapped at `addr`.
+RvStatus emulator_unmap_host_buffer(struct Emulator *emu, uint64_t addr);
+
+// Read the 16-bit value at `addr` into `value`, in the byte order `order`: 0 = little-endian,
+// as the guest stores it, 1 = big-endian. The result doesn't depend on the byte order of the
+// host, so it can be used as is. Fails if the value isn't in DRAM or `order` is unknown.
+RvStatus emulator_read_u16(struct Emulator *emu, uint64_t addr, uint32_t order, uint16_t *value);
+
+// Write the 16-bit `value` at `addr` in the byte order `order`, as for `emulator_read_u16`.
+// Fails if the value doesn't fit in DRAM or `order` is unknown.
+RvStatus emulator_write_u16(struct Emulator *emu, uint64_t addr, uint32_t order, uint16_t value);
+
+// Same as `emulator_read_u16`, but for a 32-bit value.
+RvStatus emulator_read_u32(struct Emulator *emu, uint64_t addr, uint32_t order, uint32_t *value);
+
+// Same as `emulator_write_u16`, but for a 32-bit value.
+RvStatus emulator_write_u32(struct Emulator *emu, uint64_t addr, uint32_t order, uint32_t value);
+
+// Same as `emulator_read_u16`, but for a 64-bit value.
+RvStatus emulator_read_u64(struct Emulator *emu, uint64_t addr, uint32_t order, uint64_t *value);
+
+// Same as `emulator_write_u16`, but for a 64-bit value.
+RvStatus emulator_write_u64(struct Emulator *emu, uint64_t addr, uint32_t order, uint64_t value);
+
+// Copy the `count` little-endian `i8` elements at `addr` into `out`, a typed view of guest
+// memory for levels that e.g. sort an array or multiply matrices. Fails if the elements aren't
+// all in DRAM.
+RvStatus emulator_read_array_i8(struct Emulator *emu, uint64_t addr, int8_t *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `u8` elements.
+RvStatus emulator_read_array_u8(struct Emulator *emu, uint64_t addr, uint8_t *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `i16` elements.
+RvStatus emulator_read_array_i16(struct Emulator *emu, uint64_t addr, int16_t *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `u16` elements.
+RvStatus emulator_read_array_u16(struct Emulator *emu,
+                                 uint64_t addr,
+                                 uint16_t *out,
+                                 uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `i32` elements.
+RvStatus emulator_read_array_i32(struct Emulator *emu, uint64_t addr, int32_t *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `u32` elements.
+RvStatus emulator_read_array_u32(struct Emulator *emu,
+                                 uint64_t addr,
+                                 uint32_t *out,
+                                 uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `i64` elements.
+RvStatus emulator_read_array_i64(struct Emulator *emu, uint64_t addr, int64_t *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `u64` elements.
+RvStatus emulator_read_array_u64(struct Emulator *emu,
+                                 uint64_t addr,
+                                 uint64_t *out,
+                                 uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `f32` elements.
+RvStatus emulator_read_array_f32(struct Emulator *emu, uint64_t addr, float *out, uint64_t count);
+
+// Same as `emulator_read_array_i8`, but for `f64` elements.
+RvStatus emulator_read_array_f64(struct Emulator *emu, uint64_t addr, double *out, uint64_t count);
+
+RvStatus emulator_keyboard_push_scancode(struct Emulator *emu, uint8_t code);
+
+RvStatus emulator_keyboard_pending(struct Emulator *emu, uint64_t *pending);
+
+// Copy the text-mode character buffer (character and attribute byte per cell, 80x25 cells) into
+// `out`, up to `len` bytes. Writes the number of bytes copied into `copied`.
+RvStatus emulator_get_text_buffer(struct Emulator *emu, uint8_t *out, size_t len, uint64_t *copied);
+
+// Write 1 into `changed` if the guest wrote to the text-mode buffer since the last call,
+// otherwise 0.
+RvStatus emulator_text_buffer_changed(struct Emulator *emu, uint32_t *changed);
+
+// Remove the draw commands the guest queued since the last call and pass them to `draw`, oldest
+// first. A null `draw` discards them. Writes the number of commands removed into `removed`.
+RvStatus emulator_dispatch_draw_commands(struct Emulator *emu,
+                                         DrawFn draw,
+                                         void *user_data,
+                                         uint64_t *removed);
+
+// Write the number of draw commands dropped because the queue was full or their opcode is
+// unknown into `dropped`.
+RvStatus emulator_draw_commands_dropped(struct Emulator *emu, uint64_t *dropped);
+
+// Write the number of frames the guest presented through the vsync device into `frames`. A run
+// stops with `StopReason::FramePresented` after each one; run again to start the next frame.
+RvStatus emulator_get_frame_count(struct Emulator *emu, uint64_t *frames);
+
+// Move up to `max` PCM samples the guest wrote to the audio device into `out`, oldest first.
+// Writes the number of samples moved into `moved`. Fewer than `max` count as an underrun.
+RvStatus emulator_read_audio(struct Emulator *emu, int16_t *out, size_t max, uint64_t *moved);
+
+// Write the number of times `emulator_read_audio` got fewer samples than it asked for into
+// `underruns`.
+RvStatus emulator_audio_underruns(struct Emulator *emu, uint64_t *underruns);
+
+// Move up to `len` bytes the guest wrote to the UART into `out`, oldest first, e.g. to show
+// them in the console window of the game. Writes the number of bytes moved into `moved`.
+RvStatus emulator_read_uart(struct Emulator *emu, uint8_t *out, size_t len, uint64_t *moved);
+
+// Write the number of bytes the guest wrote to the UART that were dropped because
+// `emulator_read_uart` wasn't called in time into `dropped`.
+RvStatus emulator_uart_output_dropped(struct Emulator *emu, uint64_t *dropped);
+
+// Queue the `len` bytes at `data` for the guest to receive through the UART, as if they were
+// typed on stdin. Writes the number of bytes the guest hasn't received yet into `pending`.
+RvStatus emulator_write_uart_input(struct Emulator *emu,
+                                   const uint8_t *data,
+                                   size_t len,
+                                   uint64_t *pending);
+
+// Supply the `len` bytes at `data` as the contents of the save memory, e.g. the save data of the
+// player from the last session. The rest of the memory is zeroed. Fails if `len` is larger than
+// the memory.
+RvStatus emulator_load_save_data(struct Emulator *emu, const uint8_t *data, size_t len);
+
+// Copy the contents of the save memory into `out`, up to `len` bytes, and write the size of the
+// memory into `size`.
+RvStatus emulator_get_save_data(struct Emulator *emu, uint8_t *out, size_t len, uint64_t *size);
+
+// Write 1 into `changed` if the guest wrote to the save memory since the last call, otherwise 0,
+// so the host only persists it when needed.
+RvStatus emulator_save_data_changed(struct Emulator *emu, uint32_t *changed);
+
+// Deliver the message `message` from the host to the message channel of `emu`. Writes 1 into
+// `delivered`, or 0 if the inbox of the guest is full.
+RvStatus emulator_channel_send(struct Emulator *emu, uint32_t message, uint32_t *delivered);
+
+// Write the oldest message the guest sent through its message channel into `message`, remove it
+// and write 1 into `received`. Writes 0 into `received` if there's none.
+RvStatus emulator_channel_receive(struct Emulator *emu, uint32_t *message, uint32_t *received);
+
+// Connect the message channels of `a` and `b`: move the messages each one sent to the other, in
+// order, as long as the inbox of the other has room. Call it between runs, e.g. every frame.
+// Writes the number of messages moved into `moved`.
+RvStatus emulator_channel_exchange(struct Emulator *a, struct Emulator *b, uint64_t *moved);
+
+// Select what advances the timer (mtime): 0 = one tick per executed instruction, 1 = the host
+// wall-clock at `frequency` ticks per second, 2 = only `emulator_advance_timer`. Fails if `mode`
+// is unknown.
+RvStatus emulator_set_timer_mode(struct Emulator *emu, uint32_t mode, uint64_t frequency);
+
+RvStatus emulator_advance_timer(struct Emulator *emu, uint64_t ticks);
+
+// Freeze the timer, e.g. while the debugger is stopped at a breakpoint.
+RvStatus emulator_pause_timer(struct Emulator *emu);
+
+// Unfreeze the timer. Host time spent paused is not seen by the guest.
+RvStatus emulator_resume_timer(struct Emulator *emu);
+
+// Arm the watchdog with `timeout` cycles. `action` is 0 to raise an interrupt or 1 to reset the
+// core on expiry. A `timeout` of 0 disables the watchdog. Fails if `action` is unknown.
+RvStatus emulator_set_watchdog(struct Emulator *emu, uint32_t timeout, uint32_t action);
+
+RvStatus emulator_watchdog_expirations(struct Emulator *emu, uint64_t *expirations);
+
+// Allow (`allow` = 1) or forbid (`allow` = 0) executing code from the ROM and device regions.
+// It's forbidden by default: fetching an instruction outside DRAM raises an instruction access
+// fault, which `emulator_explain_last_trap` describes.
+RvStatus emulator_set_device_fetch(struct Emulator *emu, uint32_t allow);
+
+// Stop runs before the instruction at `addr` is executed, with `StopReason::HostBreakpoint`.
+// A run starting at a breakpoint executes the instruction. Fails if there's already a
+// breakpoint at `addr`.
+RvStatus emulator_add_breakpoint(struct Emulator *emu, uint64_t addr);
+
+// Remove a breakpoint added by `emulator_add_breakpoint`. Fails if it doesn't exist.
+RvStatus emulator_remove_breakpoint(struct Emulator *emu, uint64_t addr);
+
+RvStatus emulator_clear_breakpoints(struct Emulator *emu);
+
+// Replace the instruction at `addr` with `ebreak`, or `c.ebreak` if it's compressed, for a
+// software breakpoint: a run reaching it stops with `StopReason::Breakpoint`. Reading memory
+// still shows the original instruction. Fails if `addr` isn't in DRAM or already has an
+// injected instruction.
+RvStatus emulator_inject_ebreak(struct Emulator *emu, uint64_t addr);
+
+// Replace the `len` bytes at `addr` with a NOP sled, e.g. to skip over a part of the program.
+// Reading memory still shows the original instructions. Fails if `len` is 0 or odd, or the bytes
+// aren't in DRAM or overlap an injected instruction.
+RvStatus emulator_inject_nops(struct Emulator *emu, uint64_t addr, uint64_t len);
+
+// Put back the instructions replaced by the injection at `addr`, e.g. to step over a software
+// breakpoint before injecting it again. Fails if nothing was injected at `addr`.
+RvStatus emulator_restore_injection(struct Emulator *emu, uint64_t addr);
+
+// Put back the instructions replaced by every injection.
+RvStatus emulator_restore_injections(struct Emulator *emu);
+
+// Stop runs right after the guest accesses `[addr, addr + len)` with `StopReason::Watchpoint`
+// and the accessed address as the trap value. `kind` 1 watches loads, 2 stores and 3 both;
+// `emulator_get_watch_hit` tells which one stopped the run. Fails if `kind` is unknown, or the
+// range is empty or already watched for `kind`.
+RvStatus emulator_add_watchpoint(struct Emulator *emu, uint64_t addr, uint64_t len, uint32_t kind);
+
+// Remove a watchpoint added by `emulator_add_watchpoint` with the same arguments. Fails if it
+// doesn't exist.
+RvStatus emulator_remove_watchpoint(struct Emulator *emu,
+                                    uint64_t addr,
+                                    uint64_t len,
+                                    uint32_t kind);
+
+// Stop runs right after the guest stores to `[addr, addr + len)`, with
+// `StopReason::Watchpoint`. Fails if the range is empty or already watched. Same as
+// `emulator_add_watchpoint` with `kind` 2.
+RvStatus emulator_add_write_watchpoint(struct Emulator *emu, uint64_t addr, uint64_t len);
+
+// Remove a watchpoint added by `emulator_add_write_watchpoint`. Fails if it doesn't exist.
+RvStatus emulator_remove_write_watchpoint(struct Emulator *emu, uint64_t addr, uint64_t len);
+
+// Write the access that stopped the last run at a watchpoint into `hit`: the address of the
+// instruction, the address and size of the access, the values before and after it, and whether
+// it was a load or a store. The value before a store to a device is unknown, as reading it can
+// have side effects. Fails if no run stopped at a watchpoint yet.
+RvStatus emulator_get_watch_hit(struct Emulator *emu, struct WatchHit *hit);
+
+// Keep the last `capacity` executed instructions in a ring buffer, e.g. to see how a program got
+// to a crash without stepping it one instruction at a time. 0 disables the trace. It keeps the
+// last 32 by default; shrinking it drops the oldest entries.
+RvStatus emulator_enable_trace(struct Emulator *emu, uint64_t capacity);
+
+// Copy the newest `len` traced instructions, oldest first, into `out`. Writes the number of
+// entries copied into `copied`.
+RvStatus emulator_get_trace(struct Emulator *emu,
+                            struct TraceEntry *out,
+                            size_t len,
+                            uint64_t *copied);
+
+// Start (`enable` = 1) or stop (`enable` = 0) recording loads and stores to
+// `[addr, addr + len)`, e.g. to check that a program writes each element of its output array
+// exactly once.
+RvStatus emulator_trace_region(struct Emulator *emu, uint64_t addr, uint64_t len, uint32_t enable);
+
+// Write the number of recorded memory accesses into `len`.
+RvStatus emulator_access_trace_len(struct Emulator *emu, uint64_t *len);
+
+// Copy up to `len` recorded memory accesses, oldest first, into `out`. Writes the number of
+// entries copied into `copied`.
+RvStatus emulator_get_access_trace(struct Emulator *emu,
+                                   struct MemoryAccess *out,
+                                   size_t len,
+                                   uint64_t *copied);
+
+// Forget the recorded memory accesses. The traced regions are kept.
+RvStatus emulator_clear_access_trace(struct Emulator *emu);
+
+// Start (`enable` = 1) or stop (`enable` = 0) logging the atomic instructions (`lr`, `sc` and
+// the AMOs) with the values in memory before and after them.
+RvStatus emulator_enable_atomic_events(struct Emulator *emu, uint32_t enable);
+
+// Write the number of logged atomic instructions into `len`.
+RvStatus emulator_atomic_events_len(struct Emulator *emu, uint64_t *len);
+
+// Move up to `len` logged atomic instructions, oldest first, into `out` and forget them, e.g.
+// once per frame. The events that don't fit are kept for the next call. Writes the number of
+// events moved into `moved`.
+RvStatus emulator_take_atomic_events(struct Emulator *emu,
+                                     struct AtomicEvent *out,
+                                     size_t len,
+                                     uint64_t *moved);
+
+// Start (`enable` = 1) or stop (`enable` = 0) logging the writes to CSRs, by instructions and by
+// traps, with the values before and after them.
+RvStatus emulator_enable_csr_trace(struct Emulator *emu, uint32_t enable);
+
+// Write the number of logged CSR writes into `len`.
+RvStatus emulator_csr_trace_len(struct Emulator *emu, uint64_t *len);
+
+// Move up to `len` logged CSR writes, oldest first, into `out` and forget them. The writes that
+// don't fit are kept for the next call. Writes the number of writes moved into `moved`.
+RvStatus emulator_take_csr_writes(struct Emulator *emu,
+                                  struct CsrWrite *out,
+                                  size_t len,
+                                  uint64_t *moved);
+
+// Start (`enable` = 1) or stop (`enable` = 0) serving the debug print environment calls: an
+// `ecall` with a7 = 0x7e0 prints a0 in decimal, 0x7e1 in hexadecimal, and 0x7e2 prints the
+// string at the address in a0. The program continues after the `ecall` instead of yielding.
+RvStatus emulator_enable_debug_print(struct Emulator *emu, uint32_t enable);
+
+// Copy the debug prints of the guest into `out` as a JSON array of `{"pc", "kind", "value",
+// "text", "line"}` objects, oldest first. `kind` is "int", "hex" or "text", `text` is null
+// unless it's a string in DRAM, and `line` is the print as shown in the log. Writes the full
+// length of the JSON into `full_len`.
+RvStatus emulator_get_debug_prints(struct Emulator *emu,
+                                   uint8_t *out,
+                                   size_t len,
+                                   uint64_t *full_len);
+
+// Forget the debug prints, e.g. once they are shown.
+RvStatus emulator_clear_debug_prints(struct Emulator *emu);
+
+// Start (`enable` = 1) or stop (`enable` = 0) serving the guest asserts: an `ecall` with
+// a7 = 0x7e3 asserts that a0 isn't zero, with the address of a NUL-terminated message in a1, or
+// 0, and the line number in a2. A passing assert continues after the `ecall`; a failing one
+// stops the run with `StopReason::GuestAssertionFailed` and the line number in `tval`.
+RvStatus emulator_enable_guest_asserts(struct Emulator *emu, uint32_t enable);
+
+// Copy the results of the guest asserts into `out` as a JSON object `{"passed", "failed",
+// "failure"}`. `failure` is null unless an assert failed, else the latest failure as
+// `{"pc", "line", "message", "text"}`, where `message` is null if the guest passed none and
+// `text` is the failure as shown to the learner. Writes the full length of the JSON into
+// `full_len`.
+RvStatus emulator_get_guest_asserts(struct Emulator *emu,
+                                    uint8_t *out,
+                                    size_t len,
+                                    uint64_t *full_len);
+
+// Forget the results of the guest asserts, e.g. before the learner runs the program again.
+RvStatus emulator_clear_guest_asserts(struct Emulator *emu);
+
+// Start (`enable` = 1) or stop (`enable` = 0) serving the RISC-V semihosting calls of the
+// guest: an `ebreak` between `slli zero, zero, 0x1f` and `srai zero, zero, 7` with the
+// operation in a0. `SYS_WRITE0` prints to the console, `SYS_OPEN` opens ":tt", `SYS_READ` reads
+// the input from `emulator_write_semihosting_input`, and `SYS_EXIT` stops the run with
+// `StopReason::Exited`. Other operations fail with -1 in a0.
+RvStatus emulator_enable_semihosting(struct Emulator *emu, uint32_t enable);
+
+// Move up to `len` bytes the guest printed through semihosting into `out`, oldest first.
+// Writes the number of bytes moved into `moved`.
+RvStatus emulator_read_semihosting_output(struct Emulator *emu,
+                                          uint8_t *out,
+                                          size_t len,
+                                          uint64_t *moved);
+
+// Write the number of bytes the guest printed through semihosting that were dropped because
+// `emulator_read_semihosting_output` wasn't called in time into `dropped`.
+RvStatus emulator_semihosting_output_dropped(struct Emulator *emu, uint64_t *dropped);
+
+// Queue the `len` bytes at `data` for the guest to read from the console with `SYS_READ`.
+// Writes the number of bytes the guest hasn't read yet into `pending`.
+RvStatus emulator_write_semihosting_input(struct Emulator *emu,
+                                          const uint8_t *data,
+                                          size_t len,
+                                          uint64_t *pending);
+
+// Start (`enable` = 1) or stop (`enable` = 0) logging the privilege-return instructions
+// (`mret`, `sret` and `uret`) with the privilege levels before and after them.
+RvStatus emulator_enable_trap_returns(struct Emulator *emu, uint32_t enable);
+
+// Write the number of logged trap returns into `len`.
+RvStatus emulator_trap_returns_len(struct Emulator *emu, uint64_t *len);
+
+// Move up to `len` logged trap returns, oldest first, into `out` and forget them. The events
+// that don't fit are kept for the next call. Writes the number of events moved into `moved`.
+RvStatus emulator_take_trap_returns(struct Emulator *emu,
+                                    struct TrapReturn *out,
+                                    size_t len,
+                                    uint64_t *moved);
+
+// Start (`enable` = 1) or stop (`enable` = 0) logging executed instructions whose encodings are
+// hints or reserved.
+RvStatus emulator_enable_encoding_audit(struct Emulator *emu, uint32_t enable);
+
+// Set what the CPU does with the encodings of `kind` (0 = hints, 1 = reserved): `policy` is 0 to
+// execute them as it always did, or 1 to raise an illegal instruction exception. Fails if
+// `kind` or `policy` is unknown.
+RvStatus emulator_set_encoding_policy(struct Emulator *emu, uint32_t kind, uint32_t policy);
+
+// Copy up to `len` logged hint and reserved instructions, in the order they were first
+// executed, into `out`. Each instruction is logged once with its execution count. Writes the
+// number of entries copied into `copied`.
+RvStatus emulator_get_encoding_events(struct Emulator *emu,
+                                      struct EncodingEvent *out,
+                                      size_t len,
+                                      uint64_t *copied);
+
+// Forget the logged hint and reserved instructions. The policies are kept.
+RvStatus emulator_clear_encoding_events(struct Emulator *emu);
+
+// Write the rule of the specification that makes `inst` a hint or a reserved encoding into
+// `out` (not NUL-terminated), e.g. "c.lui with a zero immediate", and its full length into
+// `full_len`, 0 if `inst` is an ordinary instruction.
+RvStatus riscv_encoding_rule(uint64_t inst, uint8_t *out, size_t len, uint64_t *full_len);
+
+// Select how single-precision values are held in the floating-point registers: 0 = as the
+// doubles they convert to (legacy), 1 = NaN-boxed as the specification requires, where
+// single-precision instructions read improperly boxed registers as the canonical NaN. Fails if
+// `mode` is unknown.
+RvStatus emulator_set_nan_boxing(struct Emulator *emu, uint32_t mode);
+
+// Select the width of the integer registers, `xlen` = 32 or 64 (the default), so the emulator
+// runs programs assembled for RV32I with its semantics: the registers are 32 bits wide, the
+// program counter and the addresses wrap around at 4 GiB, the instructions only RV64 has are
+// illegal, and the MXL field of misa says RV32. The registers are truncated to the width.
+RvStatus emulator_set_xlen(struct Emulator *emu, uint32_t xlen);
+
+// Start (`enable` = 1) or stop (`enable` = 0) logging single-precision reads of registers that
+// aren't properly NaN-boxed. Only strict NaN-boxing checks the boxes.
+RvStatus emulator_enable_nan_box_diagnostics(struct Emulator *emu, uint32_t enable);
+
+// Copy up to `len` logged unboxed reads, in the order they first happened, into `out`. Each
+// instruction and register is logged once with its execution count. Writes the number of
+// entries copied into `copied`.
+RvStatus emulator_get_unboxed_reads(struct Emulator *emu,
+                                    struct UnboxedRead *out,
+                                    size_t len,
+                                    uint64_t *copied);
+
+// Forget the logged unboxed reads.
+RvStatus emulator_clear_unboxed_reads(struct Emulator *emu);
+
+// Start (`enable` = 1) or stop (`enable` = 0) counting loads and stores by the address of the
+// instruction that made them.
+RvStatus emulator_enable_memory_stats(struct Emulator *emu, uint32_t enable);
+
+// Count cache hits and misses of loads and stores with a direct-mapped cache of `lines` lines
+// of `line_size` bytes. A size of 0 removes the cache model. Fails if `line_size` isn't a power
+// of two or `lines` is more than 2^20.
+RvStatus emulator_set_cache_model(struct Emulator *emu, uint64_t line_size, uint64_t lines);
+
+// Copy the statistics of up to `len` instructions with the most loads and stores, the hottest
+// first, into `out`. Writes the number of entries copied into `copied`.
+RvStatus emulator_get_hot_memory_sites(struct Emulator *emu,
+                                       struct SiteStats *out,
+                                       size_t len,
+                                       uint64_t *copied);
+
+// Forget the load/store statistics and empty the cache model.
+RvStatus emulator_clear_memory_stats(struct Emulator *emu);
+
+// Configure the core for a kind of guest program in one call: 0 = machine mode with every
+// instruction allowed (the default), 1 = the user-mode sandbox for untrusted programs, where CSR
+// and privileged instructions fault and every `ecall` returns to the host. Fails if `profile`
+// is unknown.
+RvStatus emulator_set_profile(struct Emulator *emu, uint32_t profile);
+
+// Configure the physical memory protection entry `index` (0-15). `cfg` is the pmpcfg byte
+// (R = 1, W = 2, X = 4, A = 0x18, L = 0x80) and `addr` is the pmpaddr value, e.g. from
+// `emulator_pmp_napot`. Fails if the index is out of range or the entry is locked.
+RvStatus emulator_set_pmp_entry(struct Emulator *emu, uint32_t index, uint8_t cfg, uint64_t addr);
+
+// Read the configuration and the address of the physical memory protection entry `index`.
+// Fails if the index is out of range.
+RvStatus emulator_get_pmp_entry(struct Emulator *emu, uint32_t index, uint8_t *cfg, uint64_t *addr);
+
+// Write the pmpaddr value of a naturally aligned power-of-two region into `addr`. Fails unless
+// `size` is a power of two of at least 8 bytes and `base` is aligned to it.
+RvStatus emulator_pmp_napot(uint64_t base, uint64_t size, uint64_t *addr);
+
+// Make `name` a friendly alias of the register `index` (0-31), e.g. `score` for a0 (10). The
+// assembler of `emulator_assemble` accepts it as an operand and crash reports list it. Fails if
+// `name` isn't an identifier, is a register name itself, or `index` is out of range.
+RvStatus emulator_add_register_alias(struct Emulator *emu, const char *name, uint64_t index);
+
+// Remove all register aliases, e.g. before another level is loaded.
+RvStatus emulator_clear_register_aliases(struct Emulator *emu);
+
+// Name the custom CSR at `addr` (0-0xfff) `name` for the level. The assembler of
+// `emulator_assemble` accepts it in CSR instructions and `emulator_disassemble` shows it.
+// Fails if `name` isn't an identifier or is a standard CSR name, or if `addr` is out of range
+// or is a standard CSR.
+RvStatus emulator_register_csr(struct Emulator *emu, const char *name, uint64_t addr);
+
+// Forget the custom CSR names, e.g. before another level is loaded.
+RvStatus emulator_clear_custom_csrs(struct Emulator *emu);
+
+// Write the value of the CSR at `addr` (0-0xfff) into `value`, as the guest would read it with
+// `csrr` in machine mode.
+RvStatus emulator_get_csr(struct Emulator *emu, uint64_t addr, uint64_t *value);
+
+// Write `value` to the CSR at `addr` (0-0xfff) as the guest would with `csrw` in machine mode.
+// Read-only CSRs and bits keep their values.
+RvStatus emulator_set_csr(struct Emulator *emu, uint64_t addr, uint64_t value);
+
+// Copy the CSRs the UI can show into `out` as JSON: a list of `{"name", "addr"}` objects of the
+// standard CSRs and the custom CSRs of the level, ordered by address. Writes the full length of
+// the JSON into `full_len`.
+RvStatus emulator_list_csrs(struct Emulator *emu, uint8_t *out, size_t len, uint64_t *full_len);
+
+// Copy the disassembly of the instruction word `inst` at `pc` into `out`, with CSRs shown by
+// their standard or custom names and registers by their aliases, if any. Writes the full length
+// of the disassembly into `full_len`.
+RvStatus emulator_disassemble(struct Emulator *emu,
+                              uint64_t inst,
+                              uint64_t pc,
+                              uint8_t *out,
+                              size_t len,
+                              uint64_t *full_len);
+
+// Add a symbol of the loaded program, used to describe addresses in explanations.
+RvStatus emulator_add_symbol(struct Emulator *emu, const char *name, uint64_t addr);
+
+// Record that the instruction at `addr` was assembled from the 1-based source line `line`.
+RvStatus emulator_add_source_line(struct Emulator *emu, uint64_t addr, uint32_t line);
+
+RvStatus emulator_clear_debug_info(struct Emulator *emu);
+
+// Load the program `bytes` named `name` at `base` next to the programs already loaded, starting
+// at `entry`. Programs are numbered from 0 in load order. Fails if the name is taken or the
+// program overlaps another one or doesn't fit in DRAM.
+RvStatus emulator_load_program_at(struct Emulator *emu,
+                                  const char *name,
+                                  const uint8_t *bytes,
+                                  size_t len,
+                                  uint64_t base,
+                                  uint64_t entry);
+
+// Add a symbol to the symbol table of the loaded program `program`. Fails if there's no such
+// program.
+RvStatus emulator_add_program_symbol(struct Emulator *emu,
+                                     uint64_t program,
+                                     const char *name,
+                                     uint64_t addr);
+
+// Same as `emulator_add_source_line`, but for the loaded program `program`. Fails if there's no
+// such program.
+RvStatus emulator_add_program_source_line(struct Emulator *emu,
+                                          uint64_t program,
+                                          uint64_t addr,
+                                          uint32_t line);
+
+// Write the jump table of the loaded programs at `addr`: calling `addr + 8 * i` calls the entry
+// point of program `i`. Writes the size of the table into `size`. Fails if it doesn't fit in
+// DRAM.
+RvStatus emulator_write_jump_table(struct Emulator *emu, uint64_t addr, uint64_t *size);
+
+// Start the loaded program `program` from its entry point. Fails if there's no such program.
+RvStatus emulator_launch_program(struct Emulator *emu, uint64_t program);
+
+// Write a beginner-friendly explanation of a trap into `out` as UTF-8 (not NUL-terminated).
+// `cause` and `tval` are the mcause and mtval values and `pc` is the address of the trapping
+// instruction. Writes the full length of the explanation into `full_len`.
+RvStatus emulator_explain_trap(struct Emulator *emu,
+                               uint64_t cause,
+                               uint64_t tval,
+                               uint64_t pc,
+                               uint8_t *out,
+                               size_t len,
+                               uint64_t *full_len);
+
+// Same as `emulator_explain_trap` for the last exception raised by `emulator_cpu_execute`.
+// Writes 0 into `full_len` if no exception was raised yet.
+RvStatus emulator_explain_last_trap(struct Emulator *emu,
+                                    uint8_t *out,
+                                    size_t len,
+                                    uint64_t *full_len);
+
+// Write a JSON crash report into `out` (not NUL-terminated). It bundles the last trap with its
+// explanation, a backtrace, the registers, the trap CSRs, and the recently executed instructions,
+// each mapped to source lines and symbols when they are known. Writes the full length of the
+// report into `full_len`, so the caller can retry with a larger buffer.
+RvStatus emulator_generate_crash_report(struct Emulator *emu,
+                                        uint8_t *out,
+                                        size_t len,
+                                        uint64_t *full_len);
+
+// Copy the fields of the CSR at `addr` (mstatus, sstatus, mie, mip, sie or sip) into `out` as
+// JSON: a list of `{"name", "lsb", "width", "value"}` objects from the lowest bit up, e.g.
+// `{"name": "MPP", "lsb": 11, "width": 2, "value": 3}`. Writes the full length of the JSON into
+// `full_len`. Fails if the CSR can't be decoded.
+RvStatus emulator_decode_csr(struct Emulator *emu,
+                             uint16_t addr,
+                             uint8_t *out,
+                             size_t len,
+                             uint64_t *full_len);
+
+// Copy what gates each interrupt into `out`, from the highest priority to the lowest: whether
+// it's pending in mip, enabled in mie, globally enabled in the current mode, delegated, and
+// taken before the next instruction. Writes the number of gates copied, at most `len` and 6,
+// into `copied`.
+RvStatus emulator_get_interrupt_gates(struct Emulator *emu,
+                                      struct InterruptGate *out,
+                                      size_t len,
+                                      uint64_t *copied);
+
+// Decode, without executing them, up to `len` of the next instructions into `out`, in the order
+// they would execute if no branch were taken. Branches carry their taken target so the UI can
+// draw both paths. Writes the number of instructions decoded into `decoded`, fewer than `len` if
+// the preview reaches an `ecall`, a trap return or an address outside DRAM.
+RvStatus emulator_peek_next(struct Emulator *emu,
+                            struct PreviewedInstruction *out,
+                            size_t len,
+                            uint64_t *decoded);
+
+// Execute one step like `emulator_step`, write its summary into `summary`, and record its
+// complete architectural effect for `emulator_get_last_delta`.
+RvStatus emulator_step_delta(struct Emulator *emu, struct RunSummary *summary);
+
+// Copy the architectural effect of the last `emulator_step_delta` into `out` as JSON: `pc`,
+// `inst`, `next_pc`, `mode`, `reason`, `cause` and `tval`, the changed `xregs`, `fregs` and
+// `csrs` as `{"index", "old", "new"}` objects, and the stores in `memory` as
+// `{"addr", "size", "value"}` objects. Writes the full length of the JSON into `full_len`, or 0
+// if no step was recorded.
+RvStatus emulator_get_last_delta(struct Emulator *emu,
+                                 uint8_t *out,
+                                 size_t len,
+                                 uint64_t *full_len);
+
+// Simulate up to `steps` steps on a throwaway copy of the state of `emu` and copy what each one
+// would do into `out` as a JSON array of the objects of `emulator_get_last_delta`. `emu` is left
+// as it was, so the call can be repeated with a larger buffer. The simulation ends early at a
+// trap, an environment call or an access to a device, which it can't undo. Writes the full
+// length of the JSON into `full_len`.
+RvStatus emulator_simulate(struct Emulator *emu,
+                           uint64_t steps,
+                           uint8_t *out,
+                           size_t len,
+                           uint64_t *full_len);
+
+// Execute one step like `emulator_step_delta` and write what the tutorial overlay narrates about
+// it into `out`: the disassembly, the registers read and written, whether a branch was taken,
+// and the id of the template explaining it.
+RvStatus emulator_step_with_annotations(struct Emulator *emu, struct StepAnnotation *out);
+
+// Create a co-simulation that steps the reference model `step` in lockstep with `emu`. The model
+// must start in the current state of `emu`. The instruction, the mode and the CSRs aren't
+// compared, as the model only reports its registers and its stores. Writes the co-simulation
+// into `cosim`.
+RvStatus cosim_create(const struct Emulator *emu,
+                      ReferenceStepFn step,
+                      void *user_data,
+                      struct Cosim **cosim);
+
+RvStatus cosim_destroy(struct Cosim *cosim);
+
+// Run up to `max_steps` steps on the emulator and the reference model and compare their
+// effects, stopping at the first mismatch or when the emulator stops for the host. Writes the
+// summary of the step that stopped the emulator, if any, into `summary`. Writes 1 into
+// `mismatch` if the effects differ; `cosim_get_mismatch` tells how.
+RvStatus cosim_run(struct Cosim *cosim,
+                   struct Emulator *emu,
+                   uint64_t max_steps,
+                   struct RunSummary *summary,
+                   uint32_t *mismatch);
+
+// Copy the first mismatch into `out` as JSON: the number of `step`s that matched before it,
+// the names of the `fields` that differ, and both deltas as `ours` and `theirs`, in the format
+// of `emulator_get_last_delta`. Writes the full length of the JSON into `full_len`, or 0 if
+// every step matched.
+RvStatus cosim_get_mismatch(const struct Cosim *cosim,
+                            uint8_t *out,
+                            size_t len,
+                            uint64_t *full_len);
+
+// Load the `program_len` bytes of `program`, assembled from `source`, into `emu` with the core in
+// its power-on state at `entry`, and start recording a solution run. Step the emulator and feed
+// it inputs through the recorder until the run is complete. Writes the recorder into
+// `recorder`.
+RvStatus replay_recorder_start(struct Emulator *emu,
+                               const char *source,
+                               const uint8_t *program,
+                               size_t program_len,
+                               uint64_t entry,
+                               struct ReplayRecorder **recorder);
+
+RvStatus replay_recorder_destroy(struct ReplayRecorder *recorder);
+
+// Same as `emulator_run`, but every step is recorded.
+RvStatus replay_recorder_run(struct ReplayRecorder *recorder,
+                             struct Emulator *emu,
+                             uint64_t max_steps,
+                             struct RunSummary *summary);
+
+// Push the keyboard scancode `code` to `emu` and record it as an input of the next step.
+RvStatus replay_recorder_push_scancode(struct ReplayRecorder *recorder,
+                                       struct Emulator *emu,
+                                       uint8_t code);
+
+// Write `value` to the integer register `index` of `emu`, e.g. to serve an environment call, and
+// record it as an input of the next step. Fails if `index` is out of range.
+RvStatus replay_recorder_write_register(struct ReplayRecorder *recorder,
+                                        struct Emulator *emu,
+                                        uint64_t index,
+                                        uint64_t value);
+
+// Attach the annotation `text` to the next step. The walkthrough shows it before the step.
+RvStatus replay_recorder_annotate(struct ReplayRecorder *recorder, const char *text);
+
+// Copy the recorded bundle into `out` as JSON: the `source`, the `program` as a hexadecimal
+// string, the `entry`, the `inputs` and `annotations` with the `step` they belong to, and the
+// `trace` of program counters. Writes the full length of the JSON into `full_len`.
+RvStatus replay_recorder_get_bundle(const struct ReplayRecorder *recorder,
+                                    uint8_t *out,
+                                    size_t len,
+                                    uint64_t *full_len);
+
+// Create a player of the bundle `bundle`, made by `replay_recorder_get_bundle`, and write it
+// into `player`. Fails if the bundle is invalid.
+RvStatus replay_player_create(const char *bundle, struct ReplayPlayer **player);
+
+RvStatus replay_player_destroy(struct ReplayPlayer *player);
+
+// Load the recorded program into `emu` and rewind to the first step.
+RvStatus replay_player_start(struct ReplayPlayer *player, struct Emulator *emu);
+
+// Feed the recorded inputs of the next step to `emu` and execute it, writing its summary into
+// `summary`. Writes 0 into `playback` if the step was executed, 1 if every step was played, and
+// 2 if the run diverged from the recording. Nothing is executed unless it's 0.
+RvStatus replay_player_step(struct ReplayPlayer *player,
+                            struct Emulator *emu,
+                            struct RunSummary *summary,
+                            uint32_t *playback);
+
+// Write the index of the next step of the playback into `index`.
+RvStatus replay_player_step_index(const struct ReplayPlayer *player, uint64_t *index);
+
+// Copy the annotations of the next step into `out` as a JSON array of strings. Writes the full
+// length of the JSON into `full_len`.
+RvStatus replay_player_get_annotations(const struct ReplayPlayer *player,
+                                       uint8_t *out,
+                                       size_t len,
+                                       uint64_t *full_len);
+
+// Load the level spec `spec`, a JSON document of `len` bytes, create an emulator configured for
+// it, and write the level into `level`. Fails if the spec is invalid; `level_explain_error`
+// tells why. Also fails if the host has no memory for the emulator, as `emulator_create` does.
+RvStatus level_load(const uint8_t *spec, size_t len, struct ConfiguredEmulator **level);
+
+// Copy why the level spec `spec` of `len` bytes can't be loaded into `out`. Writes the length of
+// the message into `full_len`, or 0 if the spec is valid.
+RvStatus level_explain_error(const uint8_t *spec,
+                             size_t len,
+                             uint8_t *out,
+                             size_t out_len,
+                             uint64_t *full_len);
+
+RvStatus level_destroy(struct ConfiguredEmulator *level);
+
+// Load the level spec `spec`, a JSON document of `len` bytes, into a template that
+// `emulator_instantiate` creates configured levels from, so the spec is parsed and validated
+// once for all the retries and graded runs of the level. Writes the template into `template`.
+// Fails if the spec is invalid; `level_explain_error` tells why.
+RvStatus emulator_template_create(const uint8_t *spec,
+                                  size_t len,
+                                  struct LevelTemplate **template_);
+
+// Create a level configured from `template`, as `level_load` would from its spec, ready for the
+// program to be loaded, and write it into `level`. It's independent from the template and its
+// other instances, and destroyed with `level_destroy`. Fails if the host has no memory for it,
+// as `emulator_create` does.
+RvStatus emulator_instantiate(const struct LevelTemplate *template_,
+                              struct ConfiguredEmulator **level);
+
+RvStatus emulator_template_destroy(struct LevelTemplate *template_);
+
+// Write the emulator of `level` into `emu`, e.g. to load the program or to step it with the
+// `emulator_*` functions. It's owned by the level and destroyed with it.
+RvStatus level_emulator(struct ConfiguredEmulator *level, struct Emulator **emu);
+
+// Run the emulator of `level` within the limits of the level and write the summary into
+// `summary`. Writes 1 into `won` if the run wins the level.
+RvStatus level_run(struct ConfiguredEmulator *level, struct RunSummary *summary, uint32_t *won);
+
+// Write 1 into `won` if the run summarized by `summary` wins `level`, e.g. after stepping it
+// manually.
+RvStatus level_is_won(const struct ConfiguredEmulator *level,
+                      const struct RunSummary *summary,
+                      uint32_t *won);
+
+// Copy the ISA policy of `level` into `out` in the canonical form of `riscv_format_isa_policy`,
+// to assemble solutions with `riscv_assemble_with_policy`. Writes the length of the JSON into
+// `full_len`, or 0 if the level has no policy.
+RvStatus level_get_policy(const struct ConfiguredEmulator *level,
+                          uint8_t *out,
+                          size_t len,
+                          uint64_t *full_len);
+
+// Grade the program image `program` of `len` bytes on the generated cases of `level`, seeded by
+// `seed`, and write the report into `report`. The emulator of the level isn't touched. Writes 1
+// into `won` if every case is won. Fails if the program doesn't fit in DRAM.
+RvStatus level_grade(const struct ConfiguredEmulator *level,
+                     const uint8_t *program,
+                     size_t len,
+                     uint64_t seed,
+                     struct GradeReport *report,
+                     uint32_t *won);
+
+// Score the program image `program` of `len` bytes on the rubric of `level`, running it on the
+// cases generated from `seed`, and copy the breakdown into `out` as JSON: `{"earned", "total",
+// "cases", "criteria"}`, with whether each case was won and a list of `{"criterion", "weight",
+// "earned", "measured"}` objects. Writes the full length of the JSON into `full_len`. Fails if
+// the program doesn't fit in DRAM.
+RvStatus level_evaluate(const struct ConfiguredEmulator *level,
+                        const uint8_t *program,
+                        size_t len,
+                        uint64_t seed,
+                        uint8_t *out,
+                        size_t out_len,
+                        uint64_t *full_len);
+
+// Grade the program image `program` of `len` bytes on the cases of `level` generated from
+// `seed`, shrink the inputs of the first lost case while the program still loses, and copy the
+// result into `out` as JSON: `{"case", "inputs", "reason", "steps", "exit_code"}`, with the
+// inputs in the order of the generators. Writes the full length of the JSON into `full_len`, or
+// 0 if every case is won. Fails if the program doesn't fit in DRAM.
+RvStatus level_shrink_failure(const struct ConfiguredEmulator *level,
+                              const uint8_t *program,
+                              size_t len,
+                              uint64_t seed,
+                              uint8_t *out,
+                              size_t out_len,
+                              uint64_t *full_len);
+
+// Run the program image `program` of `len` bytes on the case `case` of `level` generated from
+// `seed`, and copy the comparison with what the level expected into `out` as JSON: `{"reason",
+// "steps", "exit_code", "conditions", "output", "divergence"}`. Each condition has `expected`
+// and `actual` values, `output` has the debug prints of the reference run and of the program,
+// and `divergence` is the first store that differs from the reference run, or null. Writes the
+// full length of the JSON into `full_len`. Fails if the program doesn't fit in DRAM.
+RvStatus level_explain_case(const struct ConfiguredEmulator *level,
+                            const uint8_t *program,
+                            size_t len,
+                            uint64_t seed,
+                            uint64_t case_,
+                            uint8_t *out,
+                            size_t out_len,
+                            uint64_t *full_len);
+
+// Run the untrusted program image `program` of `len` bytes at `DRAM_BASE` in the sandbox and
+// write the outcome into `result`. The program runs in user mode, can only access the first
+// `memory_limit` bytes of DRAM and the `devices`, a set of `SANDBOX_DEVICE_*` bits, and stops
+// after `max_steps` instructions or `timeout_ms` milliseconds; each limit is capped. `policy`,
+// a JSON document of `policy_len` bytes in the format of the level specs, restricts its
+// instructions unless it's null. Fails if the policy is invalid.
+RvStatus riscv_run_sandboxed(const uint8_t *program,
+                             size_t len,
+                             uint64_t max_steps,
+                             uint64_t timeout_ms,
+                             uint64_t memory_limit,
+                             uint32_t devices,
+                             const uint8_t *policy,
+                             size_t policy_len,
+                             struct SandboxResult *result);
+
+// Copy the instruction ranges that differ between the program images `a` and `b` into `out` as
+// JSON: a list of `{"a_addr", "a", "b_addr", "b"}` objects with the addresses of the ranges and
+// the disassembly of both sides, as if both programs were loaded at `DRAM_BASE`. Identical
+// programs give `[]`. Writes the full length of the JSON into `full_len`.
+RvStatus riscv_diff_programs(const uint8_t *a,
+                             size_t a_len,
+                             const uint8_t *b,
+                             size_t b_len,
+                             uint8_t *out,
+                             size_t len,
+                             uint64_t *full_len);
+
+// Call `progress` every `interval` steps of `emulator_run*` with `user_data`, the number of steps
+// done and the step limit. A null `progress` or an `interval` of 0 removes the callback.
+RvStatus emulator_set_progress_callback(struct Emulator *emu,
+                                        ProgressFn progress,
+                                        void *user_data,
+                                        uint64_t interval);
+
+// Serve the environment calls of the guest during `emulator_run*` with `handler` and
+// `user_data`, so the host can implement its own system calls without stopping the run. A null
+// `handler` removes it, and the calls stop the run again with `StopReason::Yielded`.
+RvStatus emulator_set_ecall_handler(struct Emulator *emu, EcallFn handler, void *user_data);
+
+// Choose what `ebreak` does during `emulator_run*`: `behavior` 0 stops the run with
+// `StopReason::Breakpoint`, as a debugger does, which is the default; 1 raises a breakpoint
+// exception the guest handles at its trap vector; 2 calls `handler` with `user_data`. Fails if
+// `behavior` is unknown, or is 2 without a handler.
+RvStatus emulator_set_ebreak_behavior(struct Emulator *emu,
+                                      uint32_t behavior,
+                                      EbreakFn handler,
+                                      void *user_data);
+
+// Create a cancel token and write it into `token`. It can be cancelled from any thread while an
+// operation using it runs.
+RvStatus cancel_token_create(struct CancelToken **token);
+
+// Cancel the current or the next operation using the token.
+RvStatus cancel_token_cancel(const struct CancelToken *token);
+
+RvStatus cancel_token_destroy(struct CancelToken *token);
+
+// Make `emulator_run*` stop with `StopReason::Cancelled` when `token` is cancelled. The emulator
+// keeps its own reference, so the token may be destroyed before the emulator.
+RvStatus emulator_set_cancel_token(struct Emulator *emu, const struct CancelToken *token);
+
+// Create an arena where bots run `quantum` steps per turn and write it into `arena`. `memory` is
+// an `ArenaMemory`: 0 for shared memory, 1 for memory partitioned with PMP. Fails for other
+// values.
+RvStatus arena_create(uint64_t quantum, uint32_t memory, struct Arena **arena);
+
+RvStatus arena_destroy(struct Arena *arena);
+
+// Let every bot access `[base, base + size)` when memory is partitioned.
+RvStatus arena_set_shared_region(struct Arena *arena, uint64_t base, uint64_t size);
+
+// Add the loaded program `program` of `emu` as a bot owning the `region_size` bytes from the
+// start of its image, limited to `max_steps` steps over the match. Writes the index of the bot
+// into `bot`. Fails if there's no such program.
+RvStatus arena_add_bot(struct Arena *arena,
+                       const struct Emulator *emu,
+                       uint64_t program,
+                       uint64_t region_size,
+                       uint64_t max_steps,
+                       uint64_t *bot);
+
+// Run up to `rounds` rounds of the match, calling `arbiter` after every quantum. A null
+// `arbiter` lets every bot continue. Writes the number of rounds played into `played`.
+RvStatus arena_run(struct Arena *arena,
+                   struct Emulator *emu,
+                   uint64_t rounds,
+                   ArbiterFn arbiter,
+                   void *user_data,
+                   uint64_t *played);
+
+// Copy the status of the bot `bot` into `out`. Fails if there's no such bot.
+RvStatus arena_get_bot_status(const struct Arena *arena, uint64_t bot, struct BotStatus *out);
+
+// Change the number of steps the bot `bot` may run over the whole match. A bot that was out of
+// steps takes turns again if the new limit allows it. Fails if there's no such bot.
+RvStatus arena_set_bot_max_steps(struct Arena *arena, uint64_t bot, uint64_t max_steps);
+
+// Write the register `reg` of the bot `bot` while it waits for its turn, e.g. to pass it the
+// state of the game. Fails if there's no such bot.
+RvStatus arena_write_bot_register(struct Arena *arena, uint64_t bot, uint64_t reg, uint64_t value);
+
+// Create a lockstep run of `nodes` emulators that run `quantum` steps per round and write it
+// into `lockstep`. No node is linked to another yet.
+RvStatus lockstep_create(uint64_t nodes, uint64_t quantum, struct Lockstep **lockstep);
+
+RvStatus lockstep_destroy(struct Lockstep *lockstep);
+
+// Deliver the messages the node `from` sends to the node `to` as well. Fails if either node
+// doesn't exist or they're the same node.
+RvStatus lockstep_link(struct Lockstep *lockstep, uint64_t from, uint64_t to);
+
+// Link every node to every other node, so that each message is broadcast.
+RvStatus lockstep_link_all(struct Lockstep *lockstep);
+
+// Run up to `rounds` rounds on `emus`, the `count` emulators of the nodes in order, and write the
+// number of rounds played to `played`. Every running node runs one quantum per round, then the
+// messages sent through the channels are delivered along the links. Returns
+// `RvStatus::InvalidArgument` if `count` isn't the number of nodes or an emulator is given twice.
+RvStatus lockstep_run(struct Lockstep *lockstep,
+                      struct Emulator *const *emus,
+                      size_t count,
+                      uint64_t rounds,
+                      uint64_t *played);
+
+// Copy the status of the node `node` into `out`. Fails if there's no such node.
+RvStatus lockstep_get_node_status(const struct Lockstep *lockstep,
+                                  uint64_t node,
+                                  struct NodeStatus *out);
+
+// Create a multi-hart mode without harts whose interleaving is derived from `seed` and write it
+// into `harts`.
+RvStatus harts_create(uint64_t seed, struct Harts **harts);
+
+RvStatus harts_destroy(struct Harts *harts);
+
+// Set the longest burst of instructions a hart runs before the scheduler picks again. 1
+// switches harts after every instruction.
+RvStatus harts_set_max_burst(struct Harts *harts, uint64_t max_burst);
+
+// Add a hart starting at `pc` with its stack pointer at `sp`. Writes its ID, which is also in
+// its `mhartid` and `a0`, into `hartid`.
+RvStatus harts_add_hart(struct Harts *harts, uint64_t pc, uint64_t sp, uint64_t *hartid);
+
+// Save the `size` bytes of DRAM at `base` so `harts_restart` restores them. Fails if they
+// aren't in DRAM.
+RvStatus harts_save_memory(struct Harts *harts,
+                           const struct Emulator *emu,
+                           uint64_t base,
+                           uint64_t size);
+
+// Put every hart back at its start, restore the saved memory, and schedule with `seed`, so the
+// next runs replay the interleaving of `seed`.
+RvStatus harts_restart(struct Harts *harts, struct Emulator *emu, uint64_t seed);
+
+// Run the harts for up to `max_steps` steps in total. If a hart stopped the run, e.g. with an
+// `ecall` or by exiting, writes its ID into `hartid` and the summary of its last burst into
+// `summary`. Writes -1 into `hartid` if the step limit was reached or no hart is runnable.
+RvStatus harts_run(struct Harts *harts,
+                   struct Emulator *emu,
+                   uint64_t max_steps,
+                   struct RunSummary *summary,
+                   int64_t *hartid);
+
+// Write the `HartState` of the hart `hartid` into `state`: 0 = runnable, 1 = exited,
+// 2 = trapped, 3 = out of steps. Fails if there's no such hart.
+RvStatus harts_get_state(const struct Harts *harts, uint64_t hartid, uint32_t *state);
+
+// Write the register `reg` of the hart `hartid` into `value`. Fails if there's no such hart.
+RvStatus harts_get_register(struct Harts *harts, uint64_t hartid, uint64_t reg, uint64_t *value);
+
+// Limit the number of instructions the hart `hartid` retires in total to `quota`, or lift the
+// limit if `quota` is 0. Fails if there's no such hart.
+RvStatus harts_set_quota(struct Harts *harts, uint64_t hartid, uint64_t quota);
+
+// Copy the bursts run since the last restart into `out` as pairs of the hart ID and the number
+// of instructions it retired, up to `len` bursts. Writes the number of bursts into `bursts`.
+RvStatus harts_get_schedule(const struct Harts *harts, uint64_t *out, size_t len, uint64_t *bursts);
+
+// Start (`enable` = 1) or stop (0) detecting data races between the harts. Stopping forgets
+// the races found.
+RvStatus harts_set_race_detection(struct Harts *harts, uint32_t enable);
+
+// Copy up to `len` of the data races found since the last restart into `out`, each pair of
+// racing instructions once. Writes the number of races found into `found`.
+RvStatus harts_get_races(const struct Harts *harts,
+                         struct DataRace *out,
+                         size_t len,
+                         uint64_t *found);
+
+// Write the disassembly of the instruction word `inst` at `pc` into `out` as a NUL-terminated
+// string, e.g. "addi a0, a0, -1", truncated to fit in `len` bytes. Branch and jump targets are
+// resolved against `pc` and CSRs are shown as addresses. Writes the full length of the
+// disassembly without the NUL into `full_len`, so a length of `len` or more means it was
+// truncated.
+RvStatus riscv_disassemble(uint32_t inst, uint64_t pc, char *out, size_t len, uint64_t *full_len);
+
+RvStatus free_riscv_assemble(uint8_t *bytes);
+
+// Assemble `instruction`, a RV32I source, and hand the program image over to the host through
+// `out`, with its length written into `len`. It's freed with `free_riscv_assemble`. Fails if the
+// source doesn't assemble, with the line of the error written into `error_line`, or 0 if the
+// error isn't about a line, e.g. because the source isn't UTF-8.
+RvStatus riscv_assemble(const char *instruction,
+                        uint8_t **out,
+                        uint64_t *len,
+                        uint64_t *error_line);
+
+// Same as `riscv_assemble`, which assembles RV32I, but for the ISA string `isa`, e.g. "rv32im" to
+// also assemble the multiplication and division instructions of the M extension, or "rv64im" for
+// RV64 and its `w` variants of them. With the C extension, e.g. "rv32ic", instructions that have
+// a compressed encoding take 2 bytes instead of 4. An invalid ISA string fails with `error_line`
+// set to 0.
+RvStatus riscv_assemble_isa(const char *source,
+                            const char *isa,
+                            uint8_t **out,
+                            uint64_t *len,
+                            uint64_t *error_line);
+
+// Same as `riscv_assemble_isa`, but keeps the data section apart from the text: the text is
+// linked at `DRAM_BASE` and handed over through `text_out`, with its length written into
+// `text_len`, and the data is linked at `data_addr`, e.g. in a RAM region of its own, and handed
+// over through `data_out` with its length written into `data_len`. `data_out` is set to null if
+// there's no data. Both are freed with `free_riscv_assemble`. Fails with `error_line` set to the
+// line of the error, including a label that `data_addr` puts out of reach.
+RvStatus riscv_assemble_sections(const char *source,
+                                 const char *isa,
+                                 uint64_t data_addr,
+                                 uint8_t **text_out,
+                                 uint64_t *text_len,
+                                 uint8_t **data_out,
+                                 uint64_t *data_len,
+                                 uint64_t *error_line);
+
+// Same as `riscv_assemble`, but for programs written in the micro-ISA of the first levels. See
+// `riscv_explain_micro_error` for why a program doesn't assemble.
+RvStatus riscv_assemble_micro(const char *source,
+                              uint8_t **out,
+                              uint64_t *len,
+                              uint64_t *error_line);
+
+// Copy the message for the player about the first line of a micro-ISA program that can't be
+// translated, e.g. an instruction that isn't part of the level, into `out`. Writes the length
+// of the message into `full_len`, or 0 if every line is valid.
+RvStatus riscv_explain_micro_error(const char *source,
+                                   uint8_t *out,
+                                   size_t len,
+                                   uint64_t *full_len);
+
+// Restrict execution to the instructions micro-ISA programs are made of (`enable` = 1), or
+// allow every instruction again (`enable` = 0).
+RvStatus emulator_set_micro_isa(struct Emulator *emu, uint32_t enable);
+
+// Same as `riscv_assemble`, but the source must follow the ISA policy `policy`, a JSON string.
+// A source that breaks the policy, or an invalid policy, doesn't assemble. See
+// `riscv_explain_policy_violation` for why.
+RvStatus riscv_assemble_with_policy(const char *source,
+                                    const char *policy,
+                                    uint8_t **out,
+                                    uint64_t *len,
+                                    uint64_t *error_line);
+
+// Copy the message for the player about the first line of `source` that breaks the ISA policy
+// `policy` into `out`, followed by a suggestion when a legal equivalent exists. Writes the
+// length of the message into `full_len`, or 0 if the source follows the policy.
+RvStatus riscv_explain_policy_violation(const char *source,
+                                        const char *policy,
+                                        uint8_t *out,
+                                        size_t len,
+                                        uint64_t *full_len);
+
+// Copy `source` into `out` with the first line that breaks the ISA policy `policy` replaced with
+// a line that does the same within the policy, e.g. `li a0, 5` with `addi a0, zero, 5`. Writes
+// the length of the fixed source into `full_len`, or 0 if there's nothing to fix or no fix is
+// known. Fails if the policy is invalid.
+RvStatus riscv_fix_policy_violation(const char *source,
+                                    const char *policy,
+                                    uint8_t *out,
+                                    size_t len,
+                                    uint64_t *full_len);
+
+// Copy the hint about doing without the instruction `mnemonic` into `out`, e.g. "multiply using
+// shifts and adds" for `mul`. Writes the length of the hint into `full_len`, or 0 if there's
+// none.
+RvStatus riscv_instruction_hint(const char *mnemonic, uint8_t *out, size_t len, uint64_t *full_len);
+
+// Copy the ISA policy `policy`, a JSON string, into `out` in its canonical form: unrestricted
+// constraints are left out. Level editors use it to validate policies before saving them.
+// Writes the length of the JSON into `full_len`. Fails if the policy is invalid.
+RvStatus riscv_format_isa_policy(const char *policy, uint8_t *out, size_t len, uint64_t *full_len);
+
+// Enforce the ISA policy `policy`, a JSON string, when executing: instructions and registers it
+// doesn't allow raise an illegal instruction exception. A null `policy` removes the
+// restrictions. Fails if the policy is invalid.
+RvStatus emulator_set_isa_policy(struct Emulator *emu, const char *policy);
+
+// Same as `riscv_assemble`, but the register aliases of `emu` can be used as operands and its
+// custom CSR names in CSR instructions.
+RvStatus emulator_assemble(struct Emulator *emu,
+                           const char *instruction,
+                           uint8_t **out,
+                           uint64_t *len,
+                           uint64_t *error_line);
+
+// Assemble the single source line `source_line` as `emulator_assemble` does and write it over
+// the instruction at `addr`, e.g. when the player tweaks an instruction of the running program.
+// Branch and jump targets can be the labels of the loaded symbols, and the line is assembled for
+// the XLEN of `emu`. The CPU fetches every instruction from memory, so the next time it reaches
+// `addr` it executes the patch. Fails without writing anything if the line doesn't assemble,
+// `addr` isn't 2-byte aligned or the instructions don't fit in DRAM.
+RvStatus emulator_patch_instruction(struct Emulator *emu, uint64_t addr, const char *source_line);
+
+// Create an assembler session and write it into `session`. Its cache returns the result of
+// assembling an unchanged source with the same policy and options instantly.
+RvStatus assembler_session_create(struct AssemblerSession **session);
+
+RvStatus assembler_session_destroy(struct AssemblerSession *session);
+
+// Same as `riscv_assemble`, but the result is cached in `session`. `policy` is an ISA policy as
+// JSON the source must follow, or null. `options` is a combination of the `ASSEMBLE_*` flags,
+// e.g. 1 for micro-ISA sources. An invalid policy fails with `error_line` set to 0.
+RvStatus assembler_session_assemble(struct AssemblerSession *session,
+                                    const char *source,
+                                    const char *policy,
+                                    uint32_t options,
+                                    uint8_t **out,
+                                    uint64_t *len,
+                                    uint64_t *error_line);
+
+// Keep the results of `session` in the directory `dir` too, using at most `max_bytes` bytes,
+// so they survive restarts. The directory is created if needed and can be shared by several
+// processes. A null `dir` keeps results in memory only. Fails if the directory can't be
+// created.
+RvStatus assembler_session_set_disk_cache(struct AssemblerSession *session,
+                                          const char *dir,
+                                          uint64_t max_bytes);
+
+// Copy the number of cache hits and misses of `session` into `hits` and `misses`.
+RvStatus assembler_session_stats(struct AssemblerSession *session,
+                                 uint64_t *hits,
+                                 uint64_t *misses);
+
+// Forget the results cached in `session`.
+RvStatus assembler_session_clear(struct AssemblerSession *session);
+
+// Same as `riscv_assemble`, but calls `progress` (if not null) before each line with the number
+// of lines done and the number of lines, and gives up when `token` (if not null) is cancelled.
+// A cancelled call fails with `error_line` set to 0.
+RvStatus riscv_assemble_cancellable(const char *instruction,
+                                    uint8_t **out,
+                                    uint64_t *len,
+                                    uint64_t *error_line,
+                                    ProgressFn progress,
+                                    void *user_data,
+                                    const struct CancelToken *token);
+
+// Same as `riscv_assemble`, but also copies the labels of the program into `symbols` as JSON: a
+// list of `{"name", "addr", "section", "line", "referenced"}` objects ordered by address, where
+// `addr` is relative to `DRAM_BASE`, `section` is "text" or "data" and `referenced` tells
+// whether the program refers to the label, e.g.
+// `{"name": "loop", "addr": 8, "section": "text", "line": 3, "referenced": true}`. The full
+// length of the JSON is written to `symbols_len_out`, or 0 if the source doesn't assemble.
+RvStatus riscv_assemble_with_symbols(const char *source,
+                                     uint8_t **out,
+                                     uint64_t *len,
+                                     uint64_t *error_line,
+                                     uint8_t *symbols,
+                                     size_t symbols_len,
+                                     uint64_t *symbols_len_out);
+
+// Copy the metrics of the engine, counted across every emulator of the process, into `out` as
+// UTF-8 JSON (not NUL-terminated): an object of the counters, e.g. `"runs": 3`, with the
+// average `mips` of the runs and the `cache_hit_rate` of the assembler sessions. Writes the
+// full length of the JSON into `full_len`.
+RvStatus metrics_get_json(uint8_t *out, size_t len, uint64_t *full_len);
+
+// Copy the metrics of the engine into `out` in the Prometheus text exposition format, ready to
+// be served to a scraper. Writes the full length of the text into `full_len`.
+RvStatus metrics_get_prometheus(uint8_t *out, size_t len, uint64_t *full_len);
+
+// Set every metric of the engine back to 0.
+RvStatus metrics_reset(void);
+
+extern void *mmap(void *addr, size_t len, int prot, int flags, int fd, int64_t offset);
+
+extern int munmap(void *addr, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RVEMU_BINDINGS_H */
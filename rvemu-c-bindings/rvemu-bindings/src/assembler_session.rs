@@ -0,0 +1,206 @@
+//! An assembler session caches assembled programs by their content: the source, the ISA policy
+//! and the assembler options. Validating an unchanged file again, e.g. on every editor autosave
+//! or grading retry, returns the cached result without encoding anything. Results can also be
+//! kept on disk to survive restarts.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::disk_cache::DiskCache;
+
+/// The number of results a session keeps before it forgets the oldest.
+pub const MAX_CACHED_RESULTS: usize = 256;
+
+/// Assemble micro-ISA sources instead of RISC-V assembly.
+pub const ASSEMBLE_MICRO_ISA: u32 = 1 << 0;
+
+/// The result of assembling a source: the program image, or the 1-based line of the first error.
+pub type AssembleResult = Result<Vec<u8>, u64>;
+
+/// Return the FNV-1a hash of `bytes`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Return the cache key of a source assembled with `policy` (in its canonical JSON form) and
+/// `options`.
+pub fn cache_key(source: &str, policy: Option<&str>, options: u32) -> String {
+    format!("{}\0{}\0{}", options, policy.unwrap_or(""), source)
+}
+
+/// A cached result and the key it was stored under, to tell hash collisions apart.
+struct CacheEntry {
+    key: String,
+    result: AssembleResult,
+}
+
+/// The cache of an assembler session.
+#[derive(Default)]
+pub struct AssemblerSession {
+    cache: HashMap<u64, CacheEntry>,
+    /// The hashes in the cache, the oldest first.
+    order: VecDeque<u64>,
+    /// The on-disk layer behind the in-memory cache, if any.
+    disk: Option<DiskCache>,
+    hits: u64,
+    misses: u64,
+}
+
+impl AssemblerSession {
+    /// Create a session with an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep results in `disk` too, or only in memory if it's `None`.
+    pub fn set_disk_cache(&mut self, disk: Option<DiskCache>) {
+        self.disk = disk;
+    }
+
+    /// Return the number of lookups that found a cached result.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Return the number of lookups that had to assemble.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Return the number of cached results.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Return true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forget every result cached in memory. The disk cache and the hit and miss counts are kept.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+
+    /// Return the cached result for `key`, from memory or else from disk.
+    pub fn lookup(&mut self, key: &str) -> Option<AssembleResult> {
+        if let Some(entry) = self.cache.get(&fnv1a(key.as_bytes())) {
+            if entry.key == key {
+                self.hits += 1;
+                rvemu::metrics::record_cache_lookup(true);
+                return Some(entry.result.clone());
+            }
+        }
+        let result = self.disk.as_ref().and_then(|disk| disk.load(key));
+        match result {
+            Some(result) => {
+                self.hits += 1;
+                rvemu::metrics::record_cache_lookup(true);
+                self.remember(key.to_string(), result.clone());
+                Some(result)
+            }
+            None => {
+                self.misses += 1;
+                rvemu::metrics::record_cache_lookup(false);
+                None
+            }
+        }
+    }
+
+    /// Cache `result` under `key` in memory and on disk.
+    pub fn store(&mut self, key: String, result: AssembleResult) {
+        if let Some(disk) = &self.disk {
+            disk.store(&key, &result);
+        }
+        self.remember(key, result);
+    }
+
+    /// Cache `result` under `key` in memory, forgetting the oldest result if the cache is full.
+    fn remember(&mut self, key: String, result: AssembleResult) {
+        let hash = fnv1a(key.as_bytes());
+        if self
+            .cache
+            .insert(hash, CacheEntry { key, result })
+            .is_none()
+        {
+            self.order.push_back(hash);
+        }
+        while self.order.len() > MAX_CACHED_RESULTS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.cache.remove(&oldest);
+            }
+        }
+    }
+
+    /// Return the cached result for `key`, or call `assemble` and cache what it returns.
+    /// `assemble` returns `None` for failures that mustn't be cached, like a cancelled call.
+    pub fn assemble<F>(&mut self, key: String, assemble: F) -> Option<AssembleResult>
+    where
+        F: FnOnce() -> Option<AssembleResult>,
+    {
+        if let Some(result) = self.lookup(&key) {
+            return Some(result);
+        }
+        let result = assemble()?;
+        self.store(key, result.clone());
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_sources_are_assembled_once() {
+        let mut session = AssemblerSession::new();
+        let mut calls = 0;
+        for _ in 0..3 {
+            let result = session.assemble(cache_key("nop", None, 0), || {
+                calls += 1;
+                Some(Ok(vec![0x13, 0, 0, 0]))
+            });
+            assert_eq!(Some(Ok(vec![0x13, 0, 0, 0])), result);
+        }
+        assert_eq!(1, calls);
+        assert_eq!((2, 1), (session.hits(), session.misses()));
+
+        // The policy and the options are part of the key.
+        let result = session.assemble(cache_key("nop", Some("{}"), 0), || Some(Err(1)));
+        assert_eq!(Some(Err(1)), result);
+        let result = session.assemble(cache_key("nop", None, ASSEMBLE_MICRO_ISA), || None);
+        assert_eq!(None, result);
+        assert_eq!(2, session.len());
+    }
+
+    #[test]
+    fn results_are_loaded_from_disk() {
+        let dir = std::env::temp_dir().join(format!("rvasm-session-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut session = AssemblerSession::new();
+        session.set_disk_cache(Some(DiskCache::open(&dir, 1 << 20).unwrap()));
+        session.store(cache_key("nop", None, 0), Ok(vec![0x13, 0, 0, 0]));
+
+        // A new session, e.g. after a restart, starts warm.
+        let mut session = AssemblerSession::new();
+        session.set_disk_cache(Some(DiskCache::open(&dir, 1 << 20).unwrap()));
+        let result = session.assemble(cache_key("nop", None, 0), || None);
+        assert_eq!(Some(Ok(vec![0x13, 0, 0, 0])), result);
+        assert_eq!(1, session.hits());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_oldest_results_are_forgotten() {
+        let mut session = AssemblerSession::new();
+        for i in 0..MAX_CACHED_RESULTS + 1 {
+            session.store(cache_key(&i.to_string(), None, 0), Ok(vec![]));
+        }
+        assert_eq!(MAX_CACHED_RESULTS, session.len());
+        assert_eq!(None, session.lookup(&cache_key("0", None, 0)));
+        assert!(session.lookup(&cache_key("1", None, 0)).is_some());
+    }
+}
@@ -0,0 +1,203 @@
+//! Background runs: an emulator run on a worker thread of its own, e.g. so Unity keeps rendering
+//! frames while a long program runs, with the main thread reading its registers and memory.
+//!
+//! The worker runs the emulator in slices of `SLICE` steps, each with the lock of the run held.
+//! The accessors the FFI documents as safe to call during a run take the same lock, so they see
+//! the emulator between two instructions and never while it steps. A waiting accessor gets the
+//! lock before the next slice. Any other call on the emulator must wait until the run is paused
+//! or done. A panic of the emulator ends the run and is reported by its summary.
+
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use rvemu::emulator::Emulator;
+use rvemu::run::{RunSummary, StopReason};
+
+use crate::status::FfiError;
+
+/// The number of steps the worker executes with the lock held, which bounds how long an
+/// accessor waits for it.
+const SLICE: u64 = 10_000;
+
+/// The state the worker of a run shares with the other threads.
+#[derive(Default)]
+struct Shared {
+    /// Held by the worker while it steps and by the accessors while they run.
+    lock: Mutex<()>,
+    /// The number of accessors waiting for the lock. The worker lets them go first.
+    waiting: AtomicUsize,
+    /// Set to make the worker stop after its current slice.
+    pause: AtomicBool,
+    running: AtomicBool,
+    /// The outcome of the run so far: that of its last slice, with the steps, time and
+    /// interrupts of all of them.
+    summary: Mutex<Option<RunSummary>>,
+    /// The panic that ended the run, if any.
+    panic: Mutex<Option<FfiError>>,
+}
+
+/// A run of an emulator, kept after it's done for its summary.
+struct Run {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Run {
+    /// Make the worker stop and wait until it did.
+    fn pause(&mut self) {
+        self.shared.pause.store(true, Ordering::Release);
+        if let Some(worker) = self.worker.take() {
+            // The worker catches the panics of the emulator, so it can't fail.
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The runs by address of their emulator.
+static RUNS: Mutex<BTreeMap<usize, Run>> = Mutex::new(BTreeMap::new());
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The emulator of a run, sent to its worker. The worker is the only thread stepping it and only
+/// borrows it with the lock held, and the run is paused before the emulator is destroyed.
+struct EmulatorPtr(*mut Emulator);
+
+unsafe impl Send for EmulatorPtr {}
+
+/// Start running `emu` on a worker thread for up to `max_steps` steps. Fails if it's already
+/// running.
+pub fn start(emu: *mut Emulator, max_steps: u64) -> Result<(), FfiError> {
+    let mut runs = lock(&RUNS);
+    if let Some(run) = runs.get_mut(&(emu as usize)) {
+        if run.shared.running.load(Ordering::Acquire) {
+            return Err(FfiError::invalid("the emulator is already running".to_string()));
+        }
+        run.pause();
+    }
+
+    let shared = Arc::new(Shared::default());
+    shared.running.store(true, Ordering::Release);
+    let worker = {
+        let shared = Arc::clone(&shared);
+        let emu = EmulatorPtr(emu);
+        thread::spawn(move || work(emu, &shared, max_steps))
+    };
+    let run = Run {
+        shared,
+        worker: Some(worker),
+    };
+    runs.insert(emu as usize, run);
+    Ok(())
+}
+
+fn work(emu: EmulatorPtr, shared: &Shared, max_steps: u64) {
+    let mut left = max_steps;
+    while !shared.pause.load(Ordering::Acquire) {
+        while shared.waiting.load(Ordering::Acquire) > 0 {
+            thread::yield_now();
+        }
+        let slice = {
+            let _guard = lock(&shared.lock);
+            // The accessors only use the emulator with the lock held, so it isn't aliased.
+            let emu = unsafe { &mut *emu.0 };
+            panic::catch_unwind(AssertUnwindSafe(|| emu.run(left.min(SLICE))))
+        };
+        let slice = match slice {
+            Ok(slice) => slice,
+            Err(payload) => {
+                *lock(&shared.panic) = Some(FfiError::panicked(payload.as_ref()));
+                break;
+            }
+        };
+        left -= slice.steps.min(left);
+
+        let mut summary = lock(&shared.summary);
+        *summary = Some(match *summary {
+            Some(before) => RunSummary {
+                steps: before.steps + slice.steps,
+                wall_time_ns: before.wall_time_ns + slice.wall_time_ns,
+                interrupts: before.interrupts + slice.interrupts,
+                simulated_us: before.simulated_us + slice.simulated_us,
+                ..slice
+            },
+            None => slice,
+        });
+        if slice.reason != StopReason::StepLimit || left == 0 {
+            break;
+        }
+    }
+    shared.running.store(false, Ordering::Release);
+}
+
+/// Pause the run of `emu` after its current slice and wait until it stopped. Fails if it was
+/// never run in the background.
+pub fn pause(emu: *mut Emulator) -> Result<(), FfiError> {
+    match lock(&RUNS).get_mut(&(emu as usize)) {
+        Some(run) => {
+            run.pause();
+            Ok(())
+        }
+        None => Err(not_run()),
+    }
+}
+
+/// Return true if `emu` is running in the background.
+pub fn is_running(emu: *mut Emulator) -> bool {
+    lock(&RUNS)
+        .get(&(emu as usize))
+        .is_some_and(|run| run.shared.running.load(Ordering::Acquire))
+}
+
+/// Return the summary of the last background run of `emu` so far. Fails if it was never run in
+/// the background, its first slice isn't done yet or the emulator panicked.
+pub fn summary(emu: *mut Emulator) -> Result<RunSummary, FfiError> {
+    let shared = match lock(&RUNS).get(&(emu as usize)) {
+        Some(run) => Arc::clone(&run.shared),
+        None => return Err(not_run()),
+    };
+    if let Some(error) = lock(&shared.panic).clone() {
+        return Err(error);
+    }
+    let summary = *lock(&shared.summary);
+    summary.ok_or_else(|| FfiError::invalid("the run hasn't executed a slice yet".to_string()))
+}
+
+fn not_run() -> FfiError {
+    FfiError::invalid("the emulator was never run in the background".to_string())
+}
+
+/// Run `f` on `emu` with the lock of its run, if it's running, so it doesn't race the worker.
+pub fn locked<R>(emu: *mut Emulator, f: impl FnOnce() -> R) -> R {
+    let shared = lock(&RUNS).get(&(emu as usize)).map(|run| Arc::clone(&run.shared));
+    let shared = match shared {
+        Some(shared) => shared,
+        None => return f(),
+    };
+    shared.waiting.fetch_add(1, Ordering::AcqRel);
+    let guard = lock(&shared.lock);
+    shared.waiting.fetch_sub(1, Ordering::AcqRel);
+    let result = f();
+    drop(guard);
+    result
+}
+
+/// Pause the run of `emu`, if any, and forget it, before the emulator is destroyed.
+pub fn stop(emu: *mut Emulator) {
+    let run = lock(&RUNS).remove(&(emu as usize));
+    if let Some(mut run) = run {
+        run.pause();
+    }
+}
+
+/// Pause and forget every run, before every emulator is destroyed.
+pub fn stop_all() {
+    let runs = std::mem::take(&mut *lock(&RUNS));
+    for (_, mut run) in runs {
+        run.pause();
+    }
+}
@@ -0,0 +1,209 @@
+//! Crash reports bundle everything known about an unrecovered trap into a single JSON document,
+//! so the game can display it and players can attach it to bug reports.
+
+use rvemu::bus::{DRAM_BASE, DRAM_END};
+use rvemu::cpu::ABI_NAMES;
+use rvemu::csr::{MCAUSE, MEPC, MSTATUS, MTVAL, MTVEC, SCAUSE, SEPC, STVAL, STVEC};
+use rvemu::debug_info::DebugInfo;
+use rvemu::emulator::Emulator;
+use serde_json::{json, Value};
+
+/// The version of the report layout. Bump it when fields change meaning.
+const CRASH_REPORT_VERSION: u64 = 1;
+
+/// The maximum number of frames in a backtrace.
+const MAX_FRAMES: usize = 16;
+
+/// Format a value as a hexadecimal string. JSON numbers can't hold every 64-bit value exactly.
+fn hex(value: u64) -> Value {
+    Value::String(format!("{:#x}", value))
+}
+
+/// Describe `addr` with the source line and the symbol it belongs to, when they are known.
+fn location(debug_info: &DebugInfo, addr: u64) -> Value {
+    let symbol = debug_info
+        .symbol_at(addr)
+        .map(|(name, offset)| match offset {
+            0 => name.to_string(),
+            _ => format!("{}+{:#x}", name, offset),
+        });
+
+    json!({
+        "address": hex(addr),
+        "line": debug_info.line_at(addr),
+        "symbol": symbol,
+    })
+}
+
+/// Return true if `addr` can hold an instruction or a stack slot.
+fn in_dram(addr: u64) -> bool {
+    (DRAM_BASE..DRAM_END).contains(&addr)
+}
+
+/// Walk the call stack and return the return addresses, innermost first. The first frame is the
+/// current pc and the second is `ra`. Outer frames are found by following the frame pointer
+/// (`s0`) chain, where the return address is saved in the register-sized slot below the frame
+/// pointer and the caller's frame pointer in the one below it: at `fp - 8` and `fp - 16` in RV64,
+/// at `fp - 4` and `fp - 8` in RV32. Programs that don't maintain a frame pointer get the first two
+/// frames only.
+fn backtrace(emu: &mut Emulator) -> Vec<u64> {
+    let xlen = emu.cpu.xlen();
+    let size = xlen.bits() as u8;
+    let slot = u64::from(xlen.bits() / 8);
+    let mut frames = vec![xlen.truncate(emu.cpu.pc)];
+
+    let ra = xlen.truncate(emu.cpu.xregs.read(1));
+    if in_dram(ra) {
+        frames.push(ra);
+    }
+
+    let mut fp = xlen.truncate(emu.cpu.xregs.read(8));
+    while frames.len() < MAX_FRAMES
+        && fp.is_multiple_of(slot)
+        && in_dram(fp.wrapping_sub(2 * slot))
+    {
+        let ret = match emu.cpu.bus.read(fp.wrapping_sub(slot), size) {
+            Ok(ret) if in_dram(ret) => ret,
+            _ => break,
+        };
+        let prev = match emu.cpu.bus.read(fp.wrapping_sub(2 * slot), size) {
+            Ok(prev) => prev,
+            Err(_) => break,
+        };
+
+        // A leaf function doesn't save `ra`, so the saved one can be the same as the live one.
+        if frames.last() != Some(&ret) {
+            frames.push(ret);
+        }
+        // The stack grows downward, so the caller's frame must be above this one.
+        if prev <= fp {
+            break;
+        }
+        fp = prev;
+    }
+
+    frames
+}
+
+/// Build the crash report of the emulator. `trap` is null if no exception was raised yet.
+pub fn generate(emu: &mut Emulator) -> Value {
+    let trap = emu.last_trap.map(|trap| {
+        json!({
+            "cause": trap.cause,
+            "tval": hex(trap.tval),
+            "location": location(emu.debug_info_at(trap.pc), trap.pc),
+            "explanation": emu.explain_trap(trap.cause, trap.tval, trap.pc),
+        })
+    });
+
+    let backtrace = backtrace(emu)
+        .into_iter()
+        .map(|addr| location(emu.debug_info_at(addr), addr))
+        .collect::<Vec<Value>>();
+
+    let mut registers = serde_json::Map::new();
+    registers.insert("pc".to_string(), hex(emu.cpu.pc));
+    for (i, name) in ABI_NAMES.iter().enumerate() {
+        registers.insert(name.to_string(), hex(emu.cpu.xregs.read(i as u64)));
+    }
+
+    let mut aliases = serde_json::Map::new();
+    for (i, name) in ABI_NAMES.iter().enumerate() {
+        for alias in emu.register_aliases.aliases_of(i as u64) {
+            aliases.insert(alias.to_string(), Value::String(name.to_string()));
+        }
+    }
+
+    let mut csrs = serde_json::Map::new();
+    for &(name, addr) in [
+        ("mstatus", MSTATUS),
+        ("mtvec", MTVEC),
+        ("mepc", MEPC),
+        ("mcause", MCAUSE),
+        ("mtval", MTVAL),
+        ("stvec", STVEC),
+        ("sepc", SEPC),
+        ("scause", SCAUSE),
+        ("stval", STVAL),
+    ]
+    .iter()
+    {
+        csrs.insert(name.to_string(), hex(emu.cpu.state.read(addr)));
+    }
+
+    let trace = emu
+        .trace
+        .entries()
+        .map(|entry| {
+            json!({
+                "inst": hex(entry.inst),
+                "location": location(emu.debug_info_at(entry.pc), entry.pc),
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    json!({
+        "version": CRASH_REPORT_VERSION,
+        "trap": trap,
+        "mode": format!("{:?}", emu.cpu.mode).to_lowercase(),
+        "backtrace": backtrace,
+        "registers": registers,
+        "register_aliases": aliases,
+        "csrs": csrs,
+        "trace": trace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rvemu::cpu::WORD;
+    use rvemu::xlen::Xlen;
+
+    #[test]
+    fn report_of_null_pointer_load() {
+        let mut emu = Emulator::new();
+        emu.initialize_dram(vec![
+            0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+            0x83, 0x20, 0x00, 0x00, // lw x1, 0(x0)
+        ]);
+        emu.initialize_pc(DRAM_BASE);
+        emu.debug_info.add_symbol("main", DRAM_BASE);
+        emu.debug_info.add_line(DRAM_BASE + 4, 2);
+
+        emu.step().unwrap();
+        assert!(emu.step().is_err());
+
+        let report = generate(&mut emu);
+        assert_eq!(5, report["trap"]["cause"]);
+        assert_eq!(2, report["trap"]["location"]["line"]);
+        assert_eq!("main+0x4", report["trap"]["location"]["symbol"]);
+        assert_eq!(
+            format!("{:#x}", DRAM_BASE + 4),
+            report["backtrace"][0]["address"]
+        );
+        assert_eq!("0x0", report["registers"]["zero"]);
+        assert_eq!(1, report["trace"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn backtrace_follows_rv32_frames() {
+        let mut emu = Emulator::new();
+        emu.initialize_dram(vec![0; 0x100]);
+        emu.initialize_pc(DRAM_BASE);
+        emu.cpu.set_xlen(Xlen::Rv32);
+        // The frame of the caller at DRAM_BASE + 0x80 returns to DRAM_BASE + 0x20, and that of its
+        // caller at DRAM_BASE + 0xc0 to DRAM_BASE + 0x30.
+        let fp = DRAM_BASE + 0x80;
+        emu.cpu.bus.write(fp - 4, DRAM_BASE + 0x20, WORD).unwrap();
+        emu.cpu.bus.write(fp - 8, DRAM_BASE + 0xc0, WORD).unwrap();
+        emu.cpu.bus.write(DRAM_BASE + 0xc0 - 4, DRAM_BASE + 0x30, WORD).unwrap();
+        emu.cpu.xregs.write(1, Xlen::Rv32.sign_extend(DRAM_BASE + 0x10));
+        emu.cpu.xregs.write(8, Xlen::Rv32.sign_extend(fp));
+
+        assert_eq!(
+            vec![DRAM_BASE, DRAM_BASE + 0x10, DRAM_BASE + 0x20, DRAM_BASE + 0x30],
+            backtrace(&mut emu)
+        );
+    }
+}
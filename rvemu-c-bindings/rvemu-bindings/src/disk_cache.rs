@@ -0,0 +1,222 @@
+//! The disk cache keeps assembled programs in a directory, so a restarted grading service starts
+//! with a warm cache and identical boilerplate from a whole classroom assembles once. It's best
+//! effort: unreadable or corrupt entries are misses, and failed writes are ignored.
+
+use std::convert::TryInto;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::assembler_session::{fnv1a, AssembleResult};
+
+/// The first bytes of every entry. Entries of other formats are ignored.
+const MAGIC: &[u8] = b"RVASM1\n";
+
+/// The extension of entry files.
+const EXTENSION: &str = "rvasm";
+
+/// The extension of entries being written.
+const TEMP_EXTENSION: &str = "tmp";
+
+/// The number of temporary files created by this process, which makes their names unique across
+/// its threads.
+static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// A directory of cached results whose files take at most `max_bytes` bytes in total.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+/// Encode an entry: the magic, the key length and key, then either 0 and the program image or 1
+/// and the error line.
+fn encode(key: &str, result: &AssembleResult) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend((key.len() as u64).to_le_bytes());
+    bytes.extend(key.as_bytes());
+    match result {
+        Ok(image) => {
+            bytes.push(0);
+            bytes.extend(image);
+        }
+        Err(line) => {
+            bytes.push(1);
+            bytes.extend(line.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decode an entry, returning `None` if it's corrupt or stored under another key.
+fn decode(bytes: &[u8], key: &str) -> Option<AssembleResult> {
+    let rest = bytes.strip_prefix(MAGIC)?;
+    if rest.len() < 8 {
+        return None;
+    }
+    let (len, rest) = rest.split_at(8);
+    let len = u64::from_le_bytes(len.try_into().ok()?) as usize;
+    if rest.len() <= len || &rest[..len] != key.as_bytes() {
+        return None;
+    }
+    let payload = &rest[len + 1..];
+    match rest[len] {
+        0 => Some(Ok(payload.to_vec())),
+        1 => Some(Err(u64::from_le_bytes(payload.try_into().ok()?))),
+        _ => None,
+    }
+}
+
+impl DiskCache {
+    /// Use `dir` as a cache of at most `max_bytes` bytes, creating it if needed. Temporary files
+    /// left behind by writers that crashed are removed; a writer still running loses its entry.
+    pub fn open<P: AsRef<Path>>(dir: P, max_bytes: u64) -> std::io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        for entry in fs::read_dir(dir.as_ref())?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some(TEMP_EXTENSION) {
+                let _ = fs::remove_file(path);
+            }
+        }
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_bytes,
+        })
+    }
+
+    /// Return the path of the entry for `key`.
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.{}", fnv1a(key.as_bytes()), EXTENSION))
+    }
+
+    /// Return the cached result for `key`.
+    pub fn load(&self, key: &str) -> Option<AssembleResult> {
+        decode(&fs::read(self.path(key)).ok()?, key)
+    }
+
+    /// Cache `result` under `key`. The entry is written to a temporary file of its own first and
+    /// renamed, so concurrent readers never see a partial entry, and concurrent writers of the
+    /// same key, in any thread or process, don't write to the same file. The oldest entries are
+    /// then removed until the cache fits in its size.
+    pub fn store(&self, key: &str, result: &AssembleResult) {
+        let path = self.path(key);
+        let temp = path.with_extension(format!(
+            "{}.{}.{}",
+            process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed),
+            TEMP_EXTENSION
+        ));
+        let written = fs::File::create(&temp)
+            .and_then(|mut file| file.write_all(&encode(key, result)))
+            .and_then(|_| fs::rename(&temp, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+            return;
+        }
+        self.evict(&path);
+    }
+
+    /// Remove the least recently modified entries other than `keep` until the total size is at
+    /// most `max_bytes`.
+    fn evict(&self, keep: &Path) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut files = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str()) == Some(EXTENSION)
+            })
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+
+        let mut total = files.iter().map(|(_, len, _)| len).sum::<u64>();
+        for (_, len, path) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            if path != keep && fs::remove_file(path).is_ok() {
+                total -= len;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return an empty directory for the test `name`.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rvasm-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn results_survive_a_restart() {
+        let dir = temp_dir("restart");
+        let cache = DiskCache::open(&dir, 1 << 20).unwrap();
+        cache.store("nop", &Ok(vec![0x13, 0, 0, 0]));
+        cache.store("bad", &Err(3));
+
+        let cache = DiskCache::open(&dir, 1 << 20).unwrap();
+        assert_eq!(Some(Ok(vec![0x13, 0, 0, 0])), cache.load("nop"));
+        assert_eq!(Some(Err(3)), cache.load("bad"));
+        assert_eq!(None, cache.load("ecall"));
+
+        // Corrupt entries are misses.
+        fs::write(cache.path("nop"), b"garbage").unwrap();
+        assert_eq!(None, cache.load("nop"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stale_temporary_files_are_removed() {
+        let dir = temp_dir("stale");
+        fs::create_dir_all(&dir).unwrap();
+        let stale = dir.join("0123456789abcdef.42.7.tmp");
+        fs::write(&stale, b"partial").unwrap();
+
+        let cache = DiskCache::open(&dir, 1 << 20).unwrap();
+        assert!(!stale.exists());
+        let threads = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                std::thread::spawn(move || cache.store("nop", &Ok(vec![0x13, 0, 0, 0])))
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(Some(Ok(vec![0x13, 0, 0, 0])), cache.load("nop"));
+        assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_size_is_limited() {
+        let dir = temp_dir("limit");
+        let entry_size = encode("a", &Ok(vec![0; 100])).len() as u64;
+        let cache = DiskCache::open(&dir, 2 * entry_size).unwrap();
+        for key in ["a", "b", "c"].iter() {
+            cache.store(key, &Ok(vec![0; 100]));
+        }
+
+        let kept = ["a", "b", "c"]
+            .iter()
+            .filter(|key| cache.load(key).is_some())
+            .count();
+        assert_eq!(2, kept);
+        assert!(cache.load("c").is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
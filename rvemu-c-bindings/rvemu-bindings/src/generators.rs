@@ -0,0 +1,352 @@
+//! Input generators fill a level with random inputs for each graded case: integers in a range,
+//! arrays and strings. Grading runs a submission against many generated cases, so a solution
+//! that hard-codes the answer of the example input fails. The cases only depend on the seed, so
+//! a failed case can be reproduced.
+
+use rvemu::bus::{DRAM_BASE, DRAM_END};
+use rvemu::emulator::Emulator;
+use rvemu::isa::register_index;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// The characters of generated strings that don't set an alphabet.
+const DEFAULT_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// A SplitMix64 generator. Every seed is usable, and the inputs only depend on the seed.
+#[derive(Debug, Clone)]
+pub struct CaseRng {
+    state: u64,
+}
+
+impl CaseRng {
+    /// Create the generator of the case `case` of a grading run seeded by `seed`.
+    pub fn new(seed: u64, case: u64) -> Self {
+        Self {
+            state: seed ^ case.wrapping_mul(0xd1b5_4a32_d192_ed03),
+        }
+    }
+
+    /// Return the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a random number in `min..=max`.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        let span = max.wrapping_sub(min) as u64;
+        match span.checked_add(1) {
+            Some(count) => min.wrapping_add((self.next_u64() % count) as i64),
+            None => self.next_u64() as i64,
+        }
+    }
+}
+
+/// An input generator declared in a level spec.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Generator {
+    /// A number in `min..=max` in the register `register`.
+    Range {
+        register: String,
+        min: i64,
+        max: i64,
+    },
+    /// `min_len` to `max_len` elements of `element_size` bytes (1, 2, 4 or 8), each in
+    /// `min..=max`, at `addr`. The address and the number of elements are written to
+    /// `address_register` and `length_register`, if set.
+    Array {
+        addr: u64,
+        min_len: u64,
+        max_len: u64,
+        element_size: u64,
+        min: i64,
+        max: i64,
+        #[serde(default)]
+        address_register: Option<String>,
+        #[serde(default)]
+        length_register: Option<String>,
+    },
+    /// A NUL-terminated string of `min_len` to `max_len` characters of `alphabet` at `addr`.
+    /// The alphabet is ASCII and defaults to the lowercase letters. The address and the length
+    /// are written to `address_register` and `length_register`, if set.
+    String {
+        addr: u64,
+        min_len: u64,
+        max_len: u64,
+        #[serde(default)]
+        alphabet: Option<String>,
+        #[serde(default)]
+        address_register: Option<String>,
+        #[serde(default)]
+        length_register: Option<String>,
+    },
+}
+
+/// Return the index of the register `name`.
+fn register(name: &str) -> Result<u64, String> {
+    register_index(name).ok_or(format!("`{}` is not a register", name))
+}
+
+/// Check that `len` bytes at `addr` are in DRAM.
+fn check_dram(addr: u64, len: u64) -> Result<(), String> {
+    if addr < DRAM_BASE || addr.saturating_add(len) > DRAM_END {
+        return Err(format!("generated data at {:#x} is outside DRAM", addr));
+    }
+    Ok(())
+}
+
+/// Write `value` to the register `name`, if any.
+fn write_register(emu: &mut Emulator, name: Option<&String>, value: u64) {
+    if let Some(index) = name.and_then(|name| register_index(name)) {
+        emu.cpu.xregs.write(index, value);
+    }
+}
+
+impl Generator {
+    /// Check that the generator can be applied to every case.
+    pub fn validate(&self) -> Result<(), String> {
+        let registers = match self {
+            Generator::Range {
+                register: name,
+                min,
+                max,
+            } => {
+                if min > max {
+                    return Err(format!("empty range for `{}`", name));
+                }
+                vec![Some(name)]
+            }
+            Generator::Array {
+                addr,
+                min_len,
+                max_len,
+                element_size,
+                min,
+                max,
+                address_register,
+                length_register,
+            } => {
+                if ![1, 2, 4, 8].contains(element_size) {
+                    return Err(format!("invalid element size {}", element_size));
+                }
+                if min_len > max_len || min > max {
+                    return Err("empty range in an array generator".to_string());
+                }
+                check_dram(*addr, max_len.saturating_mul(*element_size))?;
+                vec![address_register.as_ref(), length_register.as_ref()]
+            }
+            Generator::String {
+                addr,
+                min_len,
+                max_len,
+                alphabet,
+                address_register,
+                length_register,
+            } => {
+                if min_len > max_len || alphabet.as_deref() == Some("") {
+                    return Err("empty range in a string generator".to_string());
+                }
+                if !alphabet.as_deref().unwrap_or("").is_ascii() {
+                    return Err("the alphabet of a string generator must be ASCII".to_string());
+                }
+                check_dram(*addr, max_len.saturating_add(1))?;
+                vec![address_register.as_ref(), length_register.as_ref()]
+            }
+        };
+        for name in registers.into_iter().flatten() {
+            register(name)?;
+        }
+        Ok(())
+    }
+
+    /// Generate an input with `rng`. The generator must be valid.
+    pub fn generate(&self, rng: &mut CaseRng) -> Input {
+        match self {
+            Generator::Range { min, max, .. } => Input::Value(rng.range(*min, *max)),
+            Generator::Array {
+                min_len,
+                max_len,
+                min,
+                max,
+                ..
+            } => {
+                let len = rng.range(*min_len as i64, *max_len as i64);
+                Input::Elements((0..len).map(|_| rng.range(*min, *max)).collect())
+            }
+            Generator::String {
+                min_len,
+                max_len,
+                alphabet,
+                ..
+            } => {
+                let alphabet = alphabet.as_deref().unwrap_or(DEFAULT_ALPHABET).as_bytes();
+                let len = rng.range(*min_len as i64, *max_len as i64);
+                let text = (0..len)
+                    .map(|_| alphabet[rng.range(0, alphabet.len() as i64 - 1) as usize])
+                    .collect();
+                Input::Text(text)
+            }
+        }
+    }
+
+    /// Write `input`, generated by this generator, into `emu`.
+    pub fn write(&self, emu: &mut Emulator, input: &Input) {
+        match (self, input) {
+            (Generator::Range { register: name, .. }, Input::Value(value)) => {
+                write_register(emu, Some(name), *value as u64);
+            }
+            (
+                Generator::Array {
+                    addr,
+                    element_size,
+                    address_register,
+                    length_register,
+                    ..
+                },
+                Input::Elements(elements),
+            ) => {
+                let bytes = elements
+                    .iter()
+                    .flat_map(|value| value.to_le_bytes()[..*element_size as usize].to_vec())
+                    .collect::<Vec<u8>>();
+                emu.cpu.bus.write_dram(*addr, &bytes);
+                write_register(emu, address_register.as_ref(), *addr);
+                write_register(emu, length_register.as_ref(), elements.len() as u64);
+            }
+            (
+                Generator::String {
+                    addr,
+                    address_register,
+                    length_register,
+                    ..
+                },
+                Input::Text(text),
+            ) => {
+                let mut bytes = text.clone();
+                bytes.push(0);
+                emu.cpu.bus.write_dram(*addr, &bytes);
+                write_register(emu, address_register.as_ref(), *addr);
+                write_register(emu, length_register.as_ref(), text.len() as u64);
+            }
+            _ => {}
+        }
+    }
+
+    /// Return the inputs simpler than `input` this generator could also have generated, the
+    /// simplest first: shorter arrays and strings, and values closer to 0.
+    pub fn shrink(&self, input: &Input) -> Vec<Input> {
+        match (self, input) {
+            (Generator::Range { min, max, .. }, Input::Value(value)) => {
+                shrink_value(*value, *min, *max)
+                    .into_iter()
+                    .map(Input::Value)
+                    .collect()
+            }
+            (
+                Generator::Array {
+                    min_len, min, max, ..
+                },
+                Input::Elements(elements),
+            ) => {
+                let mut candidates = shorter(elements, *min_len as usize)
+                    .into_iter()
+                    .map(Input::Elements)
+                    .collect::<Vec<Input>>();
+                for (i, &value) in elements.iter().enumerate() {
+                    for simpler in shrink_value(value, *min, *max) {
+                        let mut elements = elements.clone();
+                        elements[i] = simpler;
+                        candidates.push(Input::Elements(elements));
+                    }
+                }
+                candidates
+            }
+            (
+                Generator::String {
+                    min_len, alphabet, ..
+                },
+                Input::Text(text),
+            ) => {
+                let first = alphabet.as_deref().unwrap_or(DEFAULT_ALPHABET).as_bytes()[0];
+                let mut candidates = shorter(text, *min_len as usize)
+                    .into_iter()
+                    .map(Input::Text)
+                    .collect::<Vec<Input>>();
+                for (i, &c) in text.iter().enumerate() {
+                    if c != first {
+                        let mut text = text.clone();
+                        text[i] = first;
+                        candidates.push(Input::Text(text));
+                    }
+                }
+                candidates
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// An input generated by a generator, for one case.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Input {
+    /// The value of a range.
+    Value(i64),
+    /// The elements of an array.
+    Elements(Vec<i64>),
+    /// The characters of a string, without the terminating NUL.
+    Text(Vec<u8>),
+}
+
+impl Input {
+    /// Return the input as JSON: a number, a list of numbers, or a string.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Input::Value(value) => json!(value),
+            Input::Elements(elements) => json!(elements),
+            Input::Text(text) => json!(String::from_utf8_lossy(text)),
+        }
+    }
+}
+
+/// Return the values in `min..=max` simpler than `value`: the closest to 0 of the range, then
+/// halfway there and one step closer.
+fn shrink_value(value: i64, min: i64, max: i64) -> Vec<i64> {
+    let target = 0.max(min).min(max) as i128;
+    let value = value as i128;
+    let mut candidates = Vec::new();
+    for candidate in [
+        target,
+        (target + value) / 2,
+        value - (value - target).signum(),
+    ] {
+        if candidate != value && !candidates.contains(&(candidate as i64)) {
+            candidates.push(candidate as i64);
+        }
+    }
+    candidates
+}
+
+/// Return the prefixes, suffixes and single removals of `items` that keep at least `min_len`
+/// items, the shortest first.
+fn shorter<T: Clone + PartialEq>(items: &[T], min_len: usize) -> Vec<Vec<T>> {
+    let mut candidates = Vec::new();
+    let mut len = min_len;
+    while len < items.len() {
+        candidates.push(items[..len].to_vec());
+        candidates.push(items[items.len() - len..].to_vec());
+        len = (len + items.len()).div_ceil(2).max(len + 1);
+    }
+    if items.len() > min_len {
+        for i in 0..items.len() {
+            let mut shorter = items.to_vec();
+            shorter.remove(i);
+            candidates.push(shorter);
+        }
+    }
+    candidates.dedup();
+    candidates
+}
@@ -0,0 +1,290 @@
+//! The registry of the live handles of the FFI: the emulators and the other objects the host
+//! creates and destroys through it. Destroying a handle twice, e.g. after a Unity domain reload
+//! kept a stale pointer, fails with a status instead of freeing the memory again. In debug builds
+//! every call also checks that its handles are alive, so a use after destroy fails the same way
+//! instead of crashing the host.
+//!
+//! A handle can also be given an id, a `u64` the host can store and marshal instead of the
+//! pointer, e.g. in a serialized Unity object that survives a domain reload. Ids count up from 1
+//! and are never reused, so a stale id fails instead of resolving to another handle.
+//!
+//! `destroy_all` frees every live handle at once, e.g. before Unity unloads the library, which
+//! would otherwise leak them, or reloads its domain and loses the pointers.
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use crate::status::FfiError;
+
+/// Free the handle at an address, of the type the function was instantiated for.
+type Destructor = unsafe fn(usize);
+
+unsafe fn drop_box<T>(ptr: usize) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+/// How a live handle is freed.
+#[derive(Copy, Clone)]
+enum Ownership {
+    /// The FFI owns it and frees it with the destructor of its type.
+    Owned(Destructor),
+    /// It's a part of another handle and freed with it.
+    Borrowed,
+}
+
+/// The live handles by type and address, with how they're freed, the types that were ever
+/// registered, and the handles with an id by id.
+struct Registry {
+    live: BTreeMap<(TypeId, usize), Ownership>,
+    types: BTreeSet<TypeId>,
+    ids: BTreeMap<u64, (TypeId, usize)>,
+    next_id: u64,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Registry {
+            live: BTreeMap::new(),
+            types: BTreeSet::new(),
+            ids: BTreeMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Forget the live handle `key` and its ids, so they don't resolve to it once it's freed.
+    fn forget(&mut self, key: &(TypeId, usize)) {
+        self.live.remove(key);
+        self.ids.retain(|_, handle| handle != key);
+    }
+
+    /// Forget every live handle and id, and return the destructors of the handles the FFI owns
+    /// with their addresses. The types stay registered and ids keep counting up, so the stale
+    /// handles and ids still fail.
+    fn clear(&mut self) -> Vec<(Destructor, usize)> {
+        self.ids.clear();
+        std::mem::take(&mut self.live)
+            .into_iter()
+            .filter_map(|((_, ptr), ownership)| match ownership {
+                Ownership::Owned(destructor) => Some((destructor, ptr)),
+                Ownership::Borrowed => None,
+            })
+            .collect()
+    }
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+/// Run `f` on the registry. A panic while it was locked can't leave it inconsistent, so a
+/// poisoned lock is used as is.
+fn with_registry<R>(f: impl FnOnce(&mut Registry) -> R) -> R {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut registry)
+}
+
+/// Move `value` to the heap and return it as a live handle.
+pub fn create<T: 'static>(value: T) -> *mut T {
+    let ptr = Box::into_raw(Box::new(value));
+    register(ptr, Ownership::Owned(drop_box::<T>));
+    ptr
+}
+
+/// Register `ptr`, a part of another handle, as a live handle that can't be destroyed, e.g. the
+/// emulator of a level. It must be released before its owner is destroyed.
+pub fn borrow<T: 'static>(ptr: *mut T) -> *mut T {
+    register(ptr, Ownership::Borrowed);
+    ptr
+}
+
+/// Unregister the handle `ptr` that was borrowed, with its ids.
+pub fn release<T: 'static>(ptr: *mut T) {
+    with_registry(|registry| registry.forget(&(TypeId::of::<T>(), ptr as usize)));
+}
+
+fn register<T: 'static>(ptr: *mut T, ownership: Ownership) {
+    with_registry(|registry| {
+        registry.types.insert(TypeId::of::<T>());
+        registry.live.insert((TypeId::of::<T>(), ptr as usize), ownership);
+    });
+}
+
+/// Give the live handle `ptr` a new id and return it.
+pub fn assign_id<T: 'static>(ptr: *mut T) -> u64 {
+    with_registry(|registry| {
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.ids.insert(id, (TypeId::of::<T>(), ptr as usize));
+        id
+    })
+}
+
+/// Return the handle of type `T` with the id argument `name`. Fails if no such handle has it,
+/// e.g. because the id was removed or belongs to a handle of another type.
+pub fn resolve<T: 'static>(id: u64, name: &str) -> Result<*mut T, FfiError> {
+    match with_registry(|registry| registry.ids.get(&id).copied()) {
+        Some((type_id, ptr)) if type_id == TypeId::of::<T>() => Ok(ptr as *mut T),
+        _ => Err(unknown_id(id, name)),
+    }
+}
+
+/// Remove the id argument `name` of a handle of type `T` and return the handle.
+pub fn remove_id<T: 'static>(id: u64, name: &str) -> Result<*mut T, FfiError> {
+    let ptr = resolve(id, name)?;
+    with_registry(|registry| registry.ids.remove(&id));
+    Ok(ptr)
+}
+
+/// Return the error of the id argument `name` that doesn't resolve.
+fn unknown_id(id: u64, name: &str) -> FfiError {
+    FfiError::invalid(format!("`{}` {} was destroyed or never created", name, id))
+}
+
+/// Free the handle argument `name` and remove its ids. Fails without freeing anything if it's
+/// null, isn't a live handle, e.g. because it was already destroyed, or is borrowed from another
+/// handle.
+///
+/// # Safety
+///
+/// A live `ptr` must not be used by another call while it's destroyed.
+pub unsafe fn destroy<T: 'static>(ptr: *mut T, name: &str) -> Result<(), FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::null(name));
+    }
+    let key = (TypeId::of::<T>(), ptr as usize);
+    match with_registry(|registry| registry.live.get(&key).copied()) {
+        None => return Err(destroyed(name)),
+        Some(Ownership::Borrowed) => {
+            let message = format!("`{}` is owned by another handle and destroyed with it", name);
+            return Err(FfiError::invalid(message));
+        }
+        Some(Ownership::Owned(_)) => with_registry(|registry| registry.forget(&key)),
+    };
+    drop(Box::from_raw(ptr));
+    Ok(())
+}
+
+/// Free every live handle the FFI owns, with the handles borrowed from them, and forget every
+/// id. Returns the number of handles freed.
+///
+/// # Safety
+///
+/// No handle may be used by another call while they're destroyed.
+pub unsafe fn destroy_all() -> u64 {
+    // The handles are freed with the registry unlocked, so their destructors can use it.
+    let owned = with_registry(Registry::clear);
+    for &(destructor, ptr) in &owned {
+        destructor(ptr);
+    }
+    owned.len() as u64
+}
+
+/// Return the error of the handle argument `name` that isn't alive.
+fn destroyed(name: &str) -> FfiError {
+    FfiError::invalid(format!("`{}` was destroyed or never created", name))
+}
+
+/// Check the pointer argument `name`: it must not be null and, in debug builds, if it's of a type
+/// of handle, it must be a live handle.
+pub fn check<T: 'static>(ptr: *const T, name: &str) -> Result<(), FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::null(name));
+    }
+    if cfg!(debug_assertions) {
+        let id = TypeId::of::<T>();
+        let alive = with_registry(|registry| {
+            !registry.types.contains(&id) || registry.live.contains_key(&(id, ptr as usize))
+        });
+        if !alive {
+            return Err(destroyed(name));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct Session(u32);
+
+    /// A handle that counts how many times it's dropped.
+    struct Counted(Rc<Cell<u32>>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn handles_are_destroyed_once() {
+        let session = create(Session(7));
+        assert_eq!(Ok(()), check(session, "session"));
+        assert_eq!(7, unsafe { (*session).0 });
+
+        assert_eq!(Ok(()), unsafe { destroy(session, "session") });
+        let error = unsafe { destroy(session, "session") }.unwrap_err();
+        assert_eq!("`session` was destroyed or never created", error.message);
+        assert_eq!(Err(destroyed("session")), check(session, "session"));
+
+        let error = unsafe { destroy(std::ptr::null_mut::<Session>(), "session") }.unwrap_err();
+        assert_eq!("`session` is null", error.message);
+        let mut inner = Session(3);
+        let borrowed = borrow(&mut inner as *mut Session);
+        assert_eq!(Ok(()), check(borrowed, "inner"));
+        let error = unsafe { destroy(borrowed, "inner") }.unwrap_err();
+        assert_eq!("`inner` is owned by another handle and destroyed with it", error.message);
+        release(borrowed);
+        assert_eq!(Err(destroyed("inner")), check(borrowed, "inner"));
+
+        let session = create(Session(9));
+        let id = assign_id(session);
+        assert_ne!(id, assign_id(create(Session(10))));
+        assert_eq!(Ok(session), resolve(id, "id"));
+        assert_eq!(Err(unknown_id(id, "id")), resolve::<u64>(id, "id"));
+        assert_eq!(Ok(session), remove_id(id, "id"));
+        assert_eq!(Err(unknown_id(id, "id")), resolve::<Session>(id, "id"));
+        assert_eq!(Err(unknown_id(0, "id")), resolve::<Session>(0, "id"));
+
+        // Destroying a handle by its pointer removes its ids.
+        let session = create(Session(11));
+        let id = assign_id(session);
+        assert_eq!(Ok(()), unsafe { destroy(session, "session") });
+        assert_eq!(Err(unknown_id(id, "id")), resolve::<Session>(id, "id"));
+
+        // Pointers to other types aren't handles.
+        let mut value = 0u64;
+        assert_eq!(Ok(()), check(&mut value as *const u64, "value"));
+    }
+
+    #[test]
+    fn clearing_the_registry_frees_the_owned_handles() {
+        let drops = Rc::new(Cell::new(0));
+        let mut registry = Registry::new();
+        let key = |ptr| (TypeId::of::<Counted>(), ptr as usize);
+        let mut owned = Vec::new();
+        for _ in 0..3 {
+            let ptr = Box::into_raw(Box::new(Counted(Rc::clone(&drops))));
+            let destructor = drop_box::<Counted> as Destructor;
+            registry.live.insert(key(ptr), Ownership::Owned(destructor));
+            owned.push(ptr);
+        }
+        let mut inner = Counted(Rc::clone(&drops));
+        registry.live.insert(key(&mut inner as *mut Counted), Ownership::Borrowed);
+        registry.ids.insert(1, key(owned[0]));
+        registry.next_id = 2;
+
+        let destructors = registry.clear();
+        assert_eq!(3, destructors.len());
+        assert!(registry.live.is_empty() && registry.ids.is_empty());
+        assert_eq!(2, registry.next_id);
+        for (destructor, ptr) in destructors {
+            unsafe { destructor(ptr) };
+        }
+        assert_eq!(3, drops.get());
+        drop(inner);
+        assert_eq!(4, drops.get());
+    }
+}
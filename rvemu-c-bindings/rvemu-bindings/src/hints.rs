@@ -0,0 +1,223 @@
+//! The hint engine tells players how to do without an instruction their level hasn't unlocked,
+//! e.g. how to multiply with shifts and adds. The same table feeds the suggestions of the ISA
+//! policy checker, so the assembler and the hint screen never disagree.
+
+use rvemu::expression::evaluate;
+
+/// How to replace a locked instruction.
+pub struct Hint {
+    /// The mnemonic of the locked instruction.
+    pub mnemonic: &'static str,
+    /// The instructions the replacement needs. The hint only applies if all of them are allowed.
+    pub alternatives: &'static [&'static str],
+    /// The advice for the player, completing "`mnemonic` is locked; ...".
+    pub advice: &'static str,
+    /// An equivalent line, if the replacement is a single instruction. `{0}`, `{1}`, ... are
+    /// replaced with the operands of the original line.
+    pub rewrite: Option<&'static str>,
+    /// The number of operands `rewrite` expects.
+    pub operands: usize,
+    /// The operand that `rewrite` puts in the 12-bit immediate of an I-type instruction, if any.
+    /// The rewrite only applies if it's a constant from -2048 to 2047.
+    pub immediate: Option<usize>,
+}
+
+const HINTS: &[Hint] = &[
+    Hint {
+        mnemonic: "mul",
+        alternatives: &["slli", "add"],
+        advice: "multiply using shifts and adds",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "div",
+        alternatives: &["sub", "blt"],
+        advice: "divide by subtracting the divisor in a loop and counting the rounds",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "divu",
+        alternatives: &["sub", "bltu"],
+        advice: "divide by subtracting the divisor in a loop and counting the rounds",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "rem",
+        alternatives: &["sub", "blt"],
+        advice: "subtract the divisor in a loop; what is left is the remainder",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "remu",
+        alternatives: &["sub", "bltu"],
+        advice: "subtract the divisor in a loop; what is left is the remainder",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "slli",
+        alternatives: &["add"],
+        advice: "double a value by adding it to itself, once per bit of shift",
+        rewrite: None,
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "li",
+        alternatives: &["addi"],
+        advice: "load a small constant by adding it to `zero`",
+        rewrite: Some("addi {0}, zero, {1}"),
+        operands: 2,
+        immediate: Some(1),
+    },
+    Hint {
+        mnemonic: "mv",
+        alternatives: &["addi"],
+        advice: "copy a register by adding 0 to it",
+        rewrite: Some("addi {0}, {1}, 0"),
+        operands: 2,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "nop",
+        alternatives: &["addi"],
+        advice: "do nothing by adding 0 to `zero`",
+        rewrite: Some("addi zero, zero, 0"),
+        operands: 0,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "neg",
+        alternatives: &["sub"],
+        advice: "negate a value by subtracting it from `zero`",
+        rewrite: Some("sub {0}, zero, {1}"),
+        operands: 2,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "not",
+        alternatives: &["xori"],
+        advice: "flip every bit by xoring with -1",
+        rewrite: Some("xori {0}, {1}, -1"),
+        operands: 2,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "j",
+        alternatives: &["jal"],
+        advice: "jump without saving a return address by linking to `zero`",
+        rewrite: Some("jal zero, {0}"),
+        operands: 1,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "ret",
+        alternatives: &["jalr"],
+        advice: "return by jumping to `ra`",
+        rewrite: Some("jalr zero, 0(ra)"),
+        operands: 0,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "beqz",
+        alternatives: &["beq"],
+        advice: "compare with the `zero` register",
+        rewrite: Some("beq {0}, zero, {1}"),
+        operands: 2,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "bnez",
+        alternatives: &["bne"],
+        advice: "compare with the `zero` register",
+        rewrite: Some("bne {0}, zero, {1}"),
+        operands: 2,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "bgt",
+        alternatives: &["blt"],
+        advice: "swap the registers and branch if less than",
+        rewrite: Some("blt {1}, {0}, {2}"),
+        operands: 3,
+        immediate: None,
+    },
+    Hint {
+        mnemonic: "ble",
+        alternatives: &["bge"],
+        advice: "swap the registers and branch if greater or equal",
+        rewrite: Some("bge {1}, {0}, {2}"),
+        operands: 3,
+        immediate: None,
+    },
+];
+
+/// Return the hint for the locked instruction `mnemonic`, if there's one.
+pub fn hint_for(mnemonic: &str) -> Option<&'static Hint> {
+    HINTS.iter().find(|hint| hint.mnemonic == mnemonic)
+}
+
+impl Hint {
+    /// Return the line replacing an instruction with `operands`, or `None` if the hint has no
+    /// single-instruction replacement or the operands don't fit it, e.g. `li` of a constant too
+    /// large for `addi`.
+    pub fn rewrite_line(&self, operands: &[&str]) -> Option<String> {
+        let mut line = self.rewrite?.to_string();
+        if operands.len() != self.operands {
+            return None;
+        }
+        if let Some(index) = self.immediate {
+            match evaluate(operands[index]) {
+                Ok(value) if (-2048..=2047).contains(&value) => {}
+                _ => return None,
+            }
+        }
+        for (i, operand) in operands.iter().enumerate() {
+            line = line.replace(&format!("{{{}}}", i), operand);
+        }
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_rewrite_lines() {
+        let hint = hint_for("li").unwrap();
+        assert_eq!(
+            Some("addi a0, zero, 5".to_string()),
+            hint.rewrite_line(&["a0", "5"])
+        );
+        assert_eq!(None, hint.rewrite_line(&["a0"]));
+        assert_eq!(
+            Some("addi a0, zero, -2048".to_string()),
+            hint.rewrite_line(&["a0", "-2048"])
+        );
+        // `addi` can't hold these.
+        assert_eq!(None, hint.rewrite_line(&["a0", "2048"]));
+        assert_eq!(None, hint.rewrite_line(&["a0", "0x12345"]));
+        assert_eq!(None, hint.rewrite_line(&["a0", "label"]));
+
+        let hint = hint_for("bgt").unwrap();
+        assert_eq!(
+            Some("blt t0, a0, loop".to_string()),
+            hint.rewrite_line(&["a0", "t0", "loop"])
+        );
+        assert_eq!(
+            None,
+            hint_for("mul").unwrap().rewrite_line(&["a0", "a0", "a1"])
+        );
+        assert!(hint_for("add").is_none());
+    }
+}
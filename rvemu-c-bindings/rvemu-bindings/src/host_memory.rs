@@ -0,0 +1,192 @@
+//! Host memory the game lends to the guest through the FFI: buffers, e.g. a Unity texture or
+//! audio buffer the guest renders into directly, and memory-mapped files.
+
+use std::io;
+use std::path::Path;
+
+use rvemu::mapping::HostMemory;
+
+/// A buffer owned by the host. The host keeps it alive, and doesn't resize or free it, until it's
+/// unmapped or the emulator is destroyed.
+pub struct HostBuffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// The host is responsible for making the buffer usable from the thread the emulator runs on.
+unsafe impl Send for HostBuffer {}
+
+impl HostBuffer {
+    /// Wrap the `len` bytes at `ptr`, which must not be null.
+    pub fn new(ptr: *mut u8, len: usize) -> Self {
+        assert!(!ptr.is_null());
+        Self { ptr, len }
+    }
+}
+
+impl HostMemory for HostBuffer {
+    fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        Some(unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) })
+    }
+}
+
+/// How a file is mapped.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FileMapping {
+    /// The guest can only read the file. Stores fault.
+    ReadOnly,
+    /// The guest can write, but its writes go to a private copy of the pages it touches and never
+    /// reach the file.
+    CopyOnWrite,
+}
+
+#[cfg(unix)]
+mod mmap {
+    use std::ffi::c_void;
+    use std::os::raw::c_int;
+
+    pub const PROT_READ: c_int = 1;
+    pub const PROT_WRITE: c_int = 2;
+    pub const MAP_PRIVATE: c_int = 2;
+    pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+    extern "C" {
+        pub fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+}
+
+/// A file mapped into memory, e.g. the large dataset of a level. The pages of a read-only file
+/// are shared by every emulator mapping it, instead of being copied into each one. Where memory
+/// mapping isn't available, the file is read into memory instead.
+pub struct MappedFile {
+    #[cfg(unix)]
+    ptr: *mut u8,
+    #[cfg(unix)]
+    len: usize,
+    #[cfg(not(unix))]
+    bytes: Vec<u8>,
+    mode: FileMapping,
+}
+
+// The mapping is private to the emulator and can be used from any thread.
+unsafe impl Send for MappedFile {}
+
+impl MappedFile {
+    /// Map the file at `path`. Fails if it can't be opened or mapped, or is empty.
+    #[cfg(unix)]
+    pub fn open<P: AsRef<Path>>(path: P, mode: FileMapping) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file is empty",
+            ));
+        }
+        let prot = match mode {
+            FileMapping::ReadOnly => mmap::PROT_READ,
+            FileMapping::CopyOnWrite => mmap::PROT_READ | mmap::PROT_WRITE,
+        };
+        // The mapping outlives the file descriptor.
+        let ptr = unsafe {
+            mmap::mmap(
+                std::ptr::null_mut(),
+                len,
+                prot,
+                mmap::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == mmap::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len,
+            mode,
+        })
+    }
+
+    /// Read the file at `path`. Fails if it can't be read or is empty.
+    #[cfg(not(unix))]
+    pub fn open<P: AsRef<Path>>(path: P, mode: FileMapping) -> io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        if bytes.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file is empty",
+            ));
+        }
+        Ok(Self { bytes, mode })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        unsafe { mmap::munmap(self.ptr as *mut std::ffi::c_void, self.len) };
+    }
+}
+
+impl HostMemory for MappedFile {
+    #[cfg(unix)]
+    fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    #[cfg(not(unix))]
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        if self.mode == FileMapping::ReadOnly {
+            return None;
+        }
+        #[cfg(unix)]
+        let bytes = unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) };
+        #[cfg(not(unix))]
+        let bytes = &mut self.bytes[..];
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_mapped_read_only_or_copy_on_write() {
+        let path = std::env::temp_dir().join(format!("rvemu-mapped-{}", std::process::id()));
+        std::fs::write(&path, [1, 2, 3, 4]).unwrap();
+
+        let mut file = MappedFile::open(&path, FileMapping::ReadOnly).unwrap();
+        assert_eq!(&[1, 2, 3, 4], file.bytes());
+        assert!(file.bytes_mut().is_none());
+
+        let mut file = MappedFile::open(&path, FileMapping::CopyOnWrite).unwrap();
+        file.bytes_mut().unwrap()[0] = 9;
+        assert_eq!(&[9, 2, 3, 4], file.bytes());
+        drop(file);
+        assert_eq!(vec![1, 2, 3, 4], std::fs::read(&path).unwrap());
+
+        std::fs::write(&path, []).unwrap();
+        assert!(MappedFile::open(&path, FileMapping::ReadOnly).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
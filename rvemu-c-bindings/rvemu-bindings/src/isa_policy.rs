@@ -0,0 +1,520 @@
+//! An ISA policy holds the constraints of a game level in one place: the mnemonics, registers
+//! and directives a program may use and how long it may be. Level files store it as JSON, the
+//! assembler checks sources against it, and the CPU enforces it at runtime through the
+//! instruction set it's converted to.
+
+use std::collections::BTreeMap;
+
+use rvemu::assembler;
+use rvemu::cpu::ABI_NAMES;
+use rvemu::isa::{register_index, registers, InstructionSet, MNEMONICS};
+use rvemu::xlen::Xlen;
+use serde_json::{json, Value};
+
+use crate::hints::hint_for;
+
+/// The real instructions each pseudo-instruction assembles to, so a policy allowing `li` also
+/// lets the CPU execute it.
+const PSEUDO_INSTRUCTIONS: &[(&str, &[&str])] = &[
+    ("nop", &["addi"]),
+    ("li", &["addi", "lui"]),
+    ("mv", &["addi"]),
+    ("not", &["xori"]),
+    ("neg", &["sub"]),
+    ("seqz", &["sltiu"]),
+    ("snez", &["sltu"]),
+    ("j", &["jal"]),
+    ("jr", &["jalr"]),
+    ("ret", &["jalr"]),
+    ("call", &["auipc", "jalr"]),
+    ("beqz", &["beq"]),
+    ("bnez", &["bne"]),
+    ("blez", &["bge"]),
+    ("bgez", &["bge"]),
+    ("bltz", &["blt"]),
+    ("bgtz", &["blt"]),
+    ("bgt", &["blt"]),
+    ("ble", &["bge"]),
+];
+
+/// The constraints of a level. `None` means unrestricted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IsaPolicy {
+    /// The mnemonics a program may use, including pseudo-instructions.
+    pub mnemonics: Option<Vec<String>>,
+    /// The registers a program may use, by name as written in the level file. `zero` is always
+    /// allowed.
+    pub registers: Option<Vec<String>>,
+    /// The directives a program may use, with the leading dot.
+    pub directives: Option<Vec<String>>,
+    /// The maximum number of instructions.
+    pub max_instructions: Option<u64>,
+    /// The level whose hint explains how to do without each locked mnemonic.
+    pub hint_levels: BTreeMap<String, u64>,
+}
+
+/// A line of a source that breaks a policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    /// The 1-based line number.
+    pub line: u64,
+    /// The message for the player.
+    pub message: String,
+    /// How to do without the locked construct, when a legal equivalent exists.
+    pub suggestion: Option<String>,
+    /// A line that does the same within the policy, when there's one.
+    pub fix: Option<String>,
+}
+
+impl PolicyViolation {
+    /// Return the message followed by the suggestion, if any.
+    pub fn describe(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("{} {}", self.message, suggestion),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// The part of a violation `check_line` finds, before the line number is known.
+type LineViolation = (String, Option<String>, Option<String>);
+
+/// Return the instruction words the code of a line, without its label, assembles to, with every
+/// label at address 0. Returns none if it doesn't assemble.
+fn instruction_words(code: &str) -> Vec<u64> {
+    match assembler::assemble_line(code, Xlen::Rv32, 0, |_| Some(0)) {
+        Ok(image) => image
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]) as u64)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Read an optional list of strings from `value[key]`.
+fn string_list(value: &Value, key: &str) -> Result<Option<Vec<String>>, String> {
+    match value.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item.as_str() {
+                Some(item) => Ok(item.to_string()),
+                None => Err(format!("`{}` must be a list of strings", key)),
+            })
+            .collect::<Result<Vec<String>, String>>()
+            .map(Some),
+        Some(_) => Err(format!("`{}` must be a list of strings", key)),
+    }
+}
+
+impl IsaPolicy {
+    /// Parse a policy from JSON, e.g.
+    /// `{"mnemonics": ["addi", "beq"], "registers": ["a0", "t0"], "max_instructions": 8}`.
+    /// Missing keys are unrestricted.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<Value>(text).map_err(|err| err.to_string())?;
+        if !value.is_object() {
+            return Err("the policy must be a JSON object".to_string());
+        }
+
+        let registers = string_list(&value, "registers")?;
+        if let Some(name) = registers
+            .iter()
+            .flatten()
+            .find(|name| register_index(name).is_none())
+        {
+            return Err(format!("`{}` is not a register", name));
+        }
+        let max_instructions = match value.get("max_instructions") {
+            None | Some(Value::Null) => None,
+            Some(max) => match max.as_u64() {
+                Some(max) => Some(max),
+                None => return Err("`max_instructions` must be a number".to_string()),
+            },
+        };
+
+        let mut hint_levels = BTreeMap::new();
+        match value.get("hint_levels") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(levels)) => {
+                for (mnemonic, level) in levels {
+                    match level.as_u64() {
+                        Some(level) => hint_levels.insert(mnemonic.clone(), level),
+                        None => {
+                            return Err("`hint_levels` must map mnemonics to levels".to_string())
+                        }
+                    };
+                }
+            }
+            Some(_) => return Err("`hint_levels` must map mnemonics to levels".to_string()),
+        }
+
+        Ok(Self {
+            mnemonics: string_list(&value, "mnemonics")?,
+            registers,
+            directives: string_list(&value, "directives")?,
+            max_instructions,
+            hint_levels,
+        })
+    }
+
+    /// Return the policy as JSON. Unrestricted constraints are left out.
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({});
+        if let Some(mnemonics) = &self.mnemonics {
+            value["mnemonics"] = json!(mnemonics);
+        }
+        if let Some(registers) = &self.registers {
+            value["registers"] = json!(registers);
+        }
+        if let Some(directives) = &self.directives {
+            value["directives"] = json!(directives);
+        }
+        if let Some(max) = self.max_instructions {
+            value["max_instructions"] = json!(max);
+        }
+        if !self.hint_levels.is_empty() {
+            value["hint_levels"] = json!(self.hint_levels);
+        }
+        value
+    }
+
+    /// Return true if the policy allows the mnemonic `name`.
+    fn allows_mnemonic(&self, name: &str) -> bool {
+        match &self.mnemonics {
+            Some(mnemonics) => mnemonics.iter().any(|m| m == name),
+            None => true,
+        }
+    }
+
+    /// Return the suggestion and the fixed line for the locked mnemonic `name` with `operands`,
+    /// from the hint table. There's none if the replacement needs locked instructions too.
+    fn suggest(&self, name: &str, operands: &[&str]) -> (Option<String>, Option<String>) {
+        let hint = match hint_for(name) {
+            Some(hint)
+                if hint
+                    .alternatives
+                    .iter()
+                    .all(|alt| self.allows_mnemonic(alt)) =>
+            {
+                hint
+            }
+            _ => return (None, None),
+        };
+        let suggestion = match self.hint_levels.get(name) {
+            Some(level) => format!(
+                "`{}` is locked; {} — see the hint for level {}.",
+                name, hint.advice, level
+            ),
+            None => format!("`{}` is locked; {}.", name, hint.advice),
+        };
+        (Some(suggestion), hint.rewrite_line(operands))
+    }
+
+    /// Return true if the policy allows the register `name`. Names that aren't registers, like
+    /// labels and numbers, are always allowed.
+    fn allows_register(&self, name: &str) -> bool {
+        match register_index(name) {
+            Some(index) => self.allows_register_index(index),
+            None => true,
+        }
+    }
+
+    /// Return true if the policy allows the register `index`.
+    fn allows_register_index(&self, index: u64) -> bool {
+        match &self.registers {
+            Some(registers) => {
+                index == 0 || registers.iter().any(|r| register_index(r) == Some(index))
+            }
+            None => true,
+        }
+    }
+
+    /// Return the suggestion for a register the policy doesn't allow.
+    fn register_suggestion(&self) -> Option<String> {
+        self.registers.as_ref().map(|registers| {
+            format!("Use one of the registers {} instead.", registers.join(", "))
+        })
+    }
+
+    /// Check one line, counting its instruction in `count`.
+    fn check_line(&self, line: &str, count: &mut u64) -> Result<(), LineViolation> {
+        let code = line.split('#').next().unwrap_or("");
+        let (label, code) = match code.find(':') {
+            Some(i) => code.split_at(i + 1),
+            None => ("", code),
+        };
+        let mut words = code
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .filter(|word| !word.is_empty());
+        let name = match words.next() {
+            Some(name) => name,
+            None => return Ok(()),
+        };
+
+        if name.starts_with('.') {
+            return match &self.directives {
+                Some(directives) if !directives.iter().any(|d| d == name) => Err((
+                    format!("The directive `{}` is not unlocked in this level.", name),
+                    None,
+                    None,
+                )),
+                _ => Ok(()),
+            };
+        }
+
+        if let Some(mnemonics) = &self.mnemonics {
+            if !self.allows_mnemonic(name) {
+                let message = format!(
+                    "The instruction `{}` is not unlocked in this level. You can use: {}.",
+                    name,
+                    mnemonics.join(", ")
+                );
+                // Operands are separated by commas, spaces, or both.
+                let operands = code
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|word| !word.is_empty())
+                    .skip(1)
+                    .collect::<Vec<&str>>();
+                let (suggestion, fix) = self.suggest(name, &operands);
+                let fix = fix.map(|fix| match label.trim() {
+                    "" => fix,
+                    label => format!("{} {}", label, fix),
+                });
+                return Err((message, suggestion, fix));
+            }
+        }
+        if let Some(register) = words.find(|word| !self.allows_register(word)) {
+            return Err((
+                format!("The register `{}` is not unlocked in this level.", register),
+                self.register_suggestion(),
+                None,
+            ));
+        }
+
+        // Check the registers the instructions use implicitly, e.g. `ra` of `ret`, as the CPU
+        // does, and count them rather than the line, as a pseudo-instruction can assemble to
+        // several. A line that doesn't assemble counts as one; the assembler reports it.
+        let instructions = instruction_words(code);
+        if let Some(index) = instructions
+            .iter()
+            .flat_map(|&inst| registers(inst))
+            .find(|&index| !self.allows_register_index(index))
+        {
+            return Err((
+                format!(
+                    "The instruction `{}` uses the register `{}`, which is not unlocked in this \
+                     level.",
+                    name, ABI_NAMES[index as usize]
+                ),
+                self.register_suggestion(),
+                None,
+            ));
+        }
+
+        *count += instructions.len().max(1) as u64;
+        match self.max_instructions {
+            Some(max) if *count > max => Err((
+                format!(
+                    "The program is longer than the {} instruction(s) this level allows.",
+                    max
+                ),
+                None,
+                None,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an assembly source against the policy and return the first line that breaks it.
+    pub fn check_source(&self, source: &str) -> Result<(), PolicyViolation> {
+        let mut count = 0;
+        for (i, line) in source.replace("\r\n", "\n").split('\n').enumerate() {
+            if let Err((message, suggestion, fix)) = self.check_line(line, &mut count) {
+                return Err(PolicyViolation {
+                    line: (i + 1) as u64,
+                    message,
+                    suggestion,
+                    fix,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return `source` with the first line that breaks the policy replaced with its fix, or
+    /// `None` if there's no violation or no fix for it.
+    pub fn fix_source(&self, source: &str) -> Option<String> {
+        let violation = self.check_source(source).err()?;
+        let fix = violation.fix.clone()?;
+        let fixed = source
+            .replace("\r\n", "\n")
+            .split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                if (i + 1) as u64 == violation.line {
+                    fix.clone()
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<String>>();
+        Some(fixed.join("\n"))
+    }
+
+    /// Return the instruction set the CPU enforces the policy with, or `None` if it doesn't
+    /// restrict mnemonics or registers. Pseudo-instructions are replaced with the real
+    /// instructions they assemble to.
+    pub fn instruction_set(&self) -> Option<InstructionSet> {
+        if self.mnemonics.is_none() && self.registers.is_none() {
+            return None;
+        }
+
+        let mnemonics = match &self.mnemonics {
+            Some(mnemonics) => mnemonics
+                .iter()
+                .flat_map(|name| {
+                    match PSEUDO_INSTRUCTIONS
+                        .iter()
+                        .find(|(pseudo, _)| pseudo == name)
+                    {
+                        Some((_, real)) => real.to_vec(),
+                        None => vec![name.as_str()],
+                    }
+                })
+                .collect::<Vec<&str>>(),
+            None => MNEMONICS.to_vec(),
+        };
+
+        let set = InstructionSet::new(mnemonics);
+        Some(match &self.registers {
+            Some(registers) => {
+                set.with_registers(registers.iter().filter_map(|r| register_index(r)))
+            }
+            None => set,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"{
+        "mnemonics": ["addi", "li", "bne"],
+        "registers": ["a0", "t0"],
+        "directives": [".text"],
+        "max_instructions": 3
+    }"#;
+
+    #[test]
+    fn policies_round_trip_through_json() {
+        let policy = IsaPolicy::from_json(POLICY).unwrap();
+        assert_eq!(Some(3), policy.max_instructions);
+        let json = policy.to_json().to_string();
+        assert_eq!(policy, IsaPolicy::from_json(&json).unwrap());
+
+        assert_eq!(
+            Err("`q0` is not a register".to_string()),
+            IsaPolicy::from_json(r#"{"registers": ["q0"]}"#)
+        );
+    }
+
+    #[test]
+    fn sources_are_checked_line_by_line() {
+        let policy = IsaPolicy::from_json(POLICY).unwrap();
+        assert_eq!(
+            Ok(()),
+            policy.check_source(".text\nli a0, 3\nloop: addi a0, a0, -1\nbne a0, zero, loop")
+        );
+
+        let violation = policy.check_source("li a0, 3\nmul a0, a0, a0").unwrap_err();
+        assert_eq!(2, violation.line);
+        assert!(violation
+            .message
+            .starts_with("The instruction `mul` is not unlocked in this level."));
+
+        let violation = policy.check_source("addi t1, t0, 1").unwrap_err();
+        assert_eq!(1, violation.line);
+        assert_eq!(
+            "The register `t1` is not unlocked in this level. Use one of the registers a0, t0 \
+             instead.",
+            violation.describe()
+        );
+
+        assert_eq!(1, policy.check_source(".data").unwrap_err().line);
+        let violation = policy
+            .check_source("li a0, 1\nli a0, 2\nli a0, 3\nli a0, 4")
+            .unwrap_err();
+        assert_eq!(4, violation.line);
+        assert_eq!(
+            "The program is longer than the 3 instruction(s) this level allows.",
+            violation.message
+        );
+        // `li` of a large value assembles to `lui` and `addi`.
+        let violation = policy
+            .check_source("li a0, 0x12345\nli a0, 0x12345")
+            .unwrap_err();
+        assert_eq!(2, violation.line);
+    }
+
+    #[test]
+    fn implicit_registers_are_checked() {
+        let policy =
+            IsaPolicy::from_json(r#"{"mnemonics": ["addi", "ret"], "registers": ["a0"]}"#)
+                .unwrap();
+        let violation = policy.check_source("addi a0, a0, 1\nret").unwrap_err();
+        assert_eq!(2, violation.line);
+        assert_eq!(
+            "The instruction `ret` uses the register `ra`, which is not unlocked in this level.",
+            violation.message
+        );
+        // The CPU agrees: ret is jalr zero, 0(ra).
+        assert!(!policy.instruction_set().unwrap().allows(0x0000_8067));
+    }
+
+    #[test]
+    fn locked_instructions_come_with_suggestions() {
+        let policy = IsaPolicy::from_json(
+            r#"{"mnemonics": ["addi", "add", "slli", "bne"], "hint_levels": {"mul": 7}}"#,
+        )
+        .unwrap();
+
+        let violation = policy.check_source("mul a0, a0, a1").unwrap_err();
+        assert_eq!(
+            Some(
+                "`mul` is locked; multiply using shifts and adds — see the hint for level 7."
+                    .to_string()
+            ),
+            violation.suggestion
+        );
+        assert_eq!(None, violation.fix);
+
+        let violation = policy.check_source("start: li a0, 5").unwrap_err();
+        assert_eq!(Some("start: addi a0, zero, 5".to_string()), violation.fix);
+        assert_eq!(
+            Some("add a0, a0, a0\nstart: addi a0, zero, 5".to_string()),
+            policy.fix_source("add a0, a0, a0\nstart: li a0, 5")
+        );
+
+        // `div` has a hint, but its replacement needs locked instructions too.
+        let violation = policy.check_source("div a0, a0, a1").unwrap_err();
+        assert_eq!(None, violation.suggestion);
+    }
+
+    #[test]
+    fn the_runtime_set_matches_the_policy() {
+        let set = IsaPolicy::from_json(POLICY)
+            .unwrap()
+            .instruction_set()
+            .unwrap();
+        assert!(set.contains("lui"));
+        assert!(!set.contains("mul"));
+        // addi a0, a0, 1
+        assert!(set.allows(0x00150513));
+        // addi a1, a1, 1
+        assert!(!set.allows(0x00158593));
+
+        assert_eq!(None, IsaPolicy::default().instruction_set());
+    }
+}
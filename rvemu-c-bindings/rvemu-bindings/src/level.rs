@@ -0,0 +1,899 @@
+//! A level spec describes a game level as data: the initial memory and registers, the devices,
+//! the ISA policy, the win conditions and the limits of a run. The game and the grader both load
+//! levels with `load_level`, so they configure the emulator and judge solutions identically.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rvemu::bus::{DRAM_BASE, DRAM_END};
+use rvemu::devices::clint::TimerMode;
+use rvemu::devices::watchdog::WatchdogAction;
+use rvemu::emulator::Emulator;
+use rvemu::isa::register_index;
+use rvemu::metrics;
+use rvemu::profile::Profile;
+use rvemu::run::{RunLimits, RunSummary, StopReason};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::generators::{CaseRng, Generator, Input};
+use crate::isa_policy::IsaPolicy;
+use crate::rubric::{Criterion, Evaluation};
+use crate::wrong_answer::WrongAnswer;
+
+/// The step limit of levels that don't set one.
+pub const DEFAULT_MAX_STEPS: u64 = 1_000_000;
+
+/// The most runs spent shrinking the inputs of a lost case.
+pub const MAX_SHRINK_RUNS: u64 = 1000;
+
+fn default_entry() -> u64 {
+    DRAM_BASE
+}
+
+fn default_cases() -> u64 {
+    1
+}
+
+/// A level as stored in a level file. Every field but `name` is optional.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelSpec {
+    /// The name of the level.
+    #[serde(default)]
+    pub name: String,
+    /// The address execution starts at, `DRAM_BASE` by default.
+    #[serde(default = "default_entry")]
+    pub entry: u64,
+    /// The bytes written to memory before the program runs.
+    #[serde(default)]
+    pub memory: Vec<MemoryPreset>,
+    /// The initial values of integer registers, by name, e.g. `{"a0": 5}`.
+    #[serde(default)]
+    pub registers: BTreeMap<String, u64>,
+    /// The configuration of the core and the devices.
+    #[serde(default)]
+    pub devices: DeviceConfig,
+    /// The ISA policy of the level, in the format of `IsaPolicy::from_json`.
+    #[serde(default)]
+    pub policy: Option<Value>,
+    /// The conditions a run must meet to win, all of them.
+    #[serde(default)]
+    pub win: Vec<WinCondition>,
+    /// The limits of a run.
+    #[serde(default)]
+    pub limits: LevelLimits,
+    /// The generators of the random inputs of each graded case, applied in order after the
+    /// presets.
+    #[serde(default)]
+    pub generators: Vec<Generator>,
+    /// The number of cases a submission is graded on, 1 by default.
+    #[serde(default = "default_cases")]
+    pub cases: u64,
+    /// The program image of the reference solution. The `reference_*` win conditions compare
+    /// the submission with it on the same case.
+    #[serde(default)]
+    pub reference: Option<Vec<u8>>,
+    /// The weighted criteria of `evaluate`. A level without a rubric scores correctness only.
+    #[serde(default)]
+    pub rubric: Vec<Criterion>,
+}
+
+/// Bytes written to memory at `addr`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryPreset {
+    pub addr: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// The preset of `Emulator::set_profile`.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSpec {
+    #[default]
+    Machine,
+    UserSandbox,
+}
+
+/// How the timer advances. See `TimerMode`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerModeSpec {
+    Instructions,
+    WallClock,
+    Manual,
+}
+
+/// The timer of a level.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimerSpec {
+    pub mode: TimerModeSpec,
+    /// The ticks per second of `wall_clock`.
+    #[serde(default)]
+    pub frequency: u64,
+}
+
+/// What the watchdog does when it expires. See `WatchdogAction`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchdogActionSpec {
+    Interrupt,
+    Reset,
+}
+
+/// The watchdog of a level, armed from the start.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogSpec {
+    /// The number of cycles before it expires.
+    pub timeout: u32,
+    pub action: WatchdogActionSpec,
+}
+
+/// The configuration of the core and the devices.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct DeviceConfig {
+    pub profile: ProfileSpec,
+    pub timer: Option<TimerSpec>,
+    pub watchdog: Option<WatchdogSpec>,
+    /// Serve the debug print calls, the output of the program, instead of yielding on them.
+    pub debug_print: bool,
+    /// The nominal clock frequency of the core in hertz, which the run summaries report the
+    /// simulated time at. `DEFAULT_CLOCK_HZ` by default.
+    pub clock_hz: Option<u64>,
+}
+
+/// The limits of a run.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LevelLimits {
+    /// The maximum number of steps, `DEFAULT_MAX_STEPS` by default.
+    pub max_steps: Option<u64>,
+    /// The maximum host time in milliseconds, if any.
+    pub time_budget_ms: Option<u64>,
+}
+
+/// A condition for winning a level.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WinCondition {
+    /// The register named `register` holds `equals` when the run stops.
+    Register { register: String, equals: u64 },
+    /// The `size` bytes at `addr` (1, 2, 4 or 8) hold `equals` when the run stops.
+    Memory { addr: u64, size: u64, equals: u64 },
+    /// The program calls `exit` with `code`.
+    Exit { code: u64 },
+    /// The register named `register` holds the same value as after the reference solution.
+    ReferenceRegister { register: String },
+    /// The `size` bytes at `addr` hold the same bytes as after the reference solution.
+    ReferenceMemory { addr: u64, size: u64 },
+}
+
+impl WinCondition {
+    /// Check that the condition can be evaluated. `reference` is true if the level has a
+    /// reference solution.
+    fn validate(&self, reference: bool) -> Result<(), String> {
+        match self {
+            WinCondition::ReferenceRegister { .. } | WinCondition::ReferenceMemory { .. }
+                if !reference =>
+            {
+                Err("a reference condition needs a reference solution".to_string())
+            }
+            WinCondition::Register { register, .. }
+            | WinCondition::ReferenceRegister { register }
+                if register_index(register).is_none() =>
+            {
+                Err(format!("`{}` is not a register", register))
+            }
+            WinCondition::ReferenceMemory { addr, size } => {
+                if *addr < DRAM_BASE || addr.saturating_add(*size) > DRAM_END {
+                    return Err(format!("{:#x} is outside DRAM", addr));
+                }
+                Ok(())
+            }
+            WinCondition::Memory { addr, size, .. } => {
+                if ![1, 2, 4, 8].contains(size) {
+                    return Err(format!("invalid memory size {}", size));
+                }
+                if *addr < DRAM_BASE || addr.saturating_add(*size) > DRAM_END {
+                    return Err(format!("{:#x} is outside DRAM", addr));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Return true if the condition holds for the run of `emu` summarized by `summary`.
+    /// `reference` is the emulator that ran the reference solution on the same case, if any.
+    pub fn holds(
+        &self,
+        emu: &Emulator,
+        summary: &RunSummary,
+        reference: Option<&Emulator>,
+    ) -> bool {
+        match self {
+            WinCondition::Register { register, equals } => register_index(register)
+                .map(|index| emu.cpu.xregs.read(index) == *equals)
+                .unwrap_or(false),
+            WinCondition::Memory { addr, size, equals } => read_value(emu, *addr, *size) == *equals,
+            WinCondition::Exit { code } => {
+                summary.reason == StopReason::Exited && summary.exit_code == *code
+            }
+            WinCondition::ReferenceRegister { register } => {
+                match (register_index(register), reference) {
+                    (Some(index), Some(reference)) => {
+                        emu.cpu.xregs.read(index) == reference.cpu.xregs.read(index)
+                    }
+                    _ => false,
+                }
+            }
+            WinCondition::ReferenceMemory { addr, size } => match reference {
+                Some(reference) => {
+                    emu.cpu.bus.read_dram(*addr, *size) == reference.cpu.bus.read_dram(*addr, *size)
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Return what the condition wants and what the run of `emu` summarized by `summary` left,
+    /// as JSON values: numbers, lists of bytes for `reference_memory`, or null if unknown.
+    pub fn compare(
+        &self,
+        emu: &Emulator,
+        summary: &RunSummary,
+        reference: Option<&Emulator>,
+    ) -> (Value, Value) {
+        let register = |emu: &Emulator, name: &str| {
+            register_index(name).map(|index| emu.cpu.xregs.read(index))
+        };
+        match self {
+            WinCondition::Register {
+                register: name,
+                equals,
+            } => (json!(equals), json!(register(emu, name))),
+            WinCondition::Memory { addr, size, equals } => {
+                (json!(equals), json!(read_value(emu, *addr, *size)))
+            }
+            WinCondition::Exit { code } => {
+                let exited = summary.reason == StopReason::Exited;
+                (
+                    json!(code),
+                    json!(Some(summary.exit_code).filter(|_| exited)),
+                )
+            }
+            WinCondition::ReferenceRegister { register: name } => (
+                json!(reference.and_then(|reference| register(reference, name))),
+                json!(register(emu, name)),
+            ),
+            WinCondition::ReferenceMemory { addr, size } => (
+                json!(reference.map(|reference| reference.cpu.bus.read_dram(*addr, *size))),
+                json!(emu.cpu.bus.read_dram(*addr, *size)),
+            ),
+        }
+    }
+}
+
+/// Return the little-endian value of the `size` bytes (1 to 8) at `addr`.
+fn read_value(emu: &Emulator, addr: u64, size: u64) -> u64 {
+    let mut bytes = [0; 8];
+    bytes[..size as usize].copy_from_slice(emu.cpu.bus.read_dram(addr, size));
+    u64::from_le_bytes(bytes)
+}
+
+/// The result of grading a submission on the generated cases of a level. The layout is
+/// C-compatible so it can be returned over the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct GradeReport {
+    /// The number of cases run.
+    pub cases: u64,
+    /// The number of cases won.
+    pub passed: u64,
+    /// 1 if a case was lost, otherwise 0.
+    pub failed: u32,
+    /// The index of the first lost case, or 0. Grading with the same seed generates it again.
+    pub failed_case: u64,
+    /// The summary of the run of the first lost case, or of the last case if every case was won.
+    pub summary: RunSummary,
+}
+
+/// The simplest inputs found on which a submission still loses a case.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShrunkCase {
+    /// The index of the lost case the inputs were generated for.
+    pub case: u64,
+    /// The shrunk inputs, one per generator of the level.
+    pub inputs: Vec<Input>,
+    /// The summary of the run on the shrunk inputs.
+    pub summary: RunSummary,
+    /// The number of runs spent shrinking.
+    pub runs: u64,
+}
+
+impl ShrunkCase {
+    /// Return the case as a JSON document for the player.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "case": self.case,
+            "inputs": self.inputs.iter().map(Input::to_json).collect::<Vec<Value>>(),
+            "reason": self.summary.reason as u32,
+            "steps": self.summary.steps,
+            "exit_code": self.summary.exit_code,
+        })
+    }
+}
+
+/// An emulator configured for a level, with what's needed to judge its runs.
+pub struct ConfiguredEmulator {
+    pub emulator: Emulator,
+    /// The name of the level.
+    pub name: String,
+    /// The ISA policy solutions must follow, if any.
+    pub policy: Option<IsaPolicy>,
+    pub limits: RunLimits,
+    spec: Arc<LevelSpec>,
+}
+
+impl ConfiguredEmulator {
+    /// Return the win conditions of the level.
+    pub fn win(&self) -> &[WinCondition] {
+        &self.spec.win
+    }
+
+    /// Run within the limits of the level.
+    pub fn run(&mut self) -> RunSummary {
+        self.emulator.run_with(self.limits)
+    }
+
+    /// Return true if the run summarized by `summary` wins the level: every condition holds. A
+    /// level without conditions is won by exiting. Conditions on the reference solution don't
+    /// hold outside `grade`.
+    pub fn is_won(&self, summary: &RunSummary) -> bool {
+        self.wins(&self.emulator, summary, None)
+    }
+
+    fn wins(&self, emu: &Emulator, summary: &RunSummary, reference: Option<&Emulator>) -> bool {
+        if self.spec.win.is_empty() {
+            return summary.reason == StopReason::Exited;
+        }
+        self.spec
+            .win
+            .iter()
+            .all(|condition| condition.holds(emu, summary, reference))
+    }
+
+    /// Fail if the program image `program` doesn't fit in DRAM at the entry point.
+    fn check_fits(&self, program: &[u8]) -> Result<(), String> {
+        let end = self.spec.entry.saturating_add(program.len() as u64);
+        if self.spec.entry < DRAM_BASE || end > DRAM_END {
+            return Err("the program doesn't fit in DRAM".to_string());
+        }
+        Ok(())
+    }
+
+    /// Generate the inputs of the case `case` of a grading run seeded by `seed`, one per
+    /// generator.
+    fn generate(&self, seed: u64, case: u64) -> Vec<Input> {
+        let mut rng = CaseRng::new(seed, case);
+        self.spec
+            .generators
+            .iter()
+            .map(|generator| generator.generate(&mut rng))
+            .collect()
+    }
+
+    /// Run `program` on `inputs` in a new emulator. If `traced`, the stores to DRAM are traced.
+    fn run_inputs(&self, program: &[u8], inputs: &[Input], traced: bool) -> (Emulator, RunSummary) {
+        let mut emu = configure(Emulator::new(), &self.spec, self.policy.as_ref());
+        if traced {
+            emu.cpu
+                .access_trace
+                .set_region(DRAM_BASE, DRAM_END - DRAM_BASE, true);
+        }
+        for (generator, input) in self.spec.generators.iter().zip(inputs) {
+            generator.write(&mut emu, input);
+        }
+        emu.cpu.bus.write_dram(self.spec.entry, program);
+        let summary = emu.run_with(self.limits);
+        (emu, summary)
+    }
+
+    /// Run `program`, and the reference solution if any, on `inputs`, and return whether the
+    /// run wins and its summary.
+    fn judge(&self, program: &[u8], inputs: &[Input]) -> (bool, RunSummary) {
+        let (emu, summary) = self.run_inputs(program, inputs, false);
+        let reference = self
+            .spec
+            .reference
+            .as_ref()
+            .map(|reference| self.run_inputs(reference, inputs, false).0);
+        (self.wins(&emu, &summary, reference.as_ref()), summary)
+    }
+
+    /// Run the program image `program`, loaded at the entry point, on every case of the level
+    /// with inputs generated from `seed`, and return whether each case was won and its summary.
+    /// Fails if the program doesn't fit in DRAM.
+    fn run_cases(&self, program: &[u8], seed: u64) -> Result<Vec<(bool, RunSummary)>, String> {
+        self.check_fits(program)?;
+        Ok((0..self.spec.cases)
+            .map(|case| self.judge(program, &self.generate(seed, case)))
+            .collect())
+    }
+
+    /// Grade the program image `program`, loaded at the entry point, on every case of the level
+    /// with inputs generated from `seed`. The emulator of the level isn't touched. Fails if the
+    /// program doesn't fit in DRAM.
+    pub fn grade(&self, program: &[u8], seed: u64) -> Result<GradeReport, String> {
+        let cases = self.run_cases(program, seed)?;
+        let passed = cases.iter().filter(|(won, _)| *won).count() as u64;
+        let failed = cases.iter().position(|(won, _)| !won);
+        metrics::record_grade(cases.len() as u64, passed);
+
+        // A level has at least one case.
+        let (failed_case, summary) = match failed {
+            Some(case) => (case as u64, cases[case].1),
+            None => (0, cases[cases.len() - 1].1),
+        };
+        Ok(GradeReport {
+            cases: self.spec.cases,
+            passed,
+            failed: failed.is_some() as u32,
+            failed_case,
+            summary,
+        })
+    }
+
+    /// Score the program image `program` on the rubric of the level, running it on every case
+    /// generated from `seed` as `grade` does.
+    pub fn evaluate(&self, program: &[u8], seed: u64) -> Result<Evaluation, String> {
+        let cases = self.run_cases(program, seed)?;
+        let default = [Criterion::Correctness { weight: 1.0 }];
+        let rubric = match self.spec.rubric.as_slice() {
+            [] => &default[..],
+            rubric => rubric,
+        };
+        Ok(Evaluation::new(rubric, &cases, program.len() as u64))
+    }
+
+    /// Run the program image `program` on the case `case` of a grading run seeded by `seed`, and
+    /// compare the run with the win conditions and with the reference solution on the same
+    /// case. Fails if the program doesn't fit in DRAM.
+    pub fn explain_case(
+        &self,
+        program: &[u8],
+        seed: u64,
+        case: u64,
+    ) -> Result<WrongAnswer, String> {
+        self.check_fits(program)?;
+        let inputs = self.generate(seed, case);
+        let (emu, summary) = self.run_inputs(program, &inputs, true);
+        let reference = self
+            .spec
+            .reference
+            .as_ref()
+            .map(|reference| self.run_inputs(reference, &inputs, true).0);
+        Ok(WrongAnswer::new(
+            &self.spec.win,
+            &emu,
+            &summary,
+            reference.as_ref(),
+        ))
+    }
+
+    /// Grade the program image `program` as `grade` does and shrink the inputs of the first lost
+    /// case while the program still loses: arrays and strings get shorter and values get closer
+    /// to 0, within the bounds of the generators. Returns `None` if every case is won.
+    pub fn shrink_failure(&self, program: &[u8], seed: u64) -> Result<Option<ShrunkCase>, String> {
+        let cases = self.run_cases(program, seed)?;
+        let case = match cases.iter().position(|(won, _)| !won) {
+            Some(case) => case,
+            None => return Ok(None),
+        };
+        let mut inputs = self.generate(seed, case as u64);
+        let mut summary = cases[case].1;
+
+        let mut runs = 0;
+        'shrinking: loop {
+            for (i, generator) in self.spec.generators.iter().enumerate() {
+                for candidate in generator.shrink(&inputs[i]) {
+                    if runs == MAX_SHRINK_RUNS {
+                        break 'shrinking;
+                    }
+                    runs += 1;
+                    let mut simpler = inputs.clone();
+                    simpler[i] = candidate;
+                    let (won, run) = self.judge(program, &simpler);
+                    if !won {
+                        inputs = simpler;
+                        summary = run;
+                        continue 'shrinking;
+                    }
+                }
+            }
+            // No simpler input loses.
+            break;
+        }
+        Ok(Some(ShrunkCase {
+            case: case as u64,
+            inputs,
+            summary,
+            runs,
+        }))
+    }
+}
+
+/// Configure `emu`, a new emulator, for `spec` and `policy`, the policy of the spec. The spec
+/// must be valid.
+fn configure(mut emu: Emulator, spec: &LevelSpec, policy: Option<&IsaPolicy>) -> Emulator {
+    emu.set_profile(match spec.devices.profile {
+        ProfileSpec::Machine => Profile::Machine,
+        ProfileSpec::UserSandbox => Profile::UserSandbox,
+    });
+    emu.initialize_pc(spec.entry);
+
+    for preset in &spec.memory {
+        emu.cpu.bus.write_dram(preset.addr, &preset.bytes);
+    }
+    for (name, value) in &spec.registers {
+        if let Some(index) = register_index(name) {
+            emu.cpu.xregs.write(index, *value);
+        }
+    }
+
+    if let Some(timer) = &spec.devices.timer {
+        let mode = match timer.mode {
+            TimerModeSpec::Instructions => TimerMode::Instructions,
+            TimerModeSpec::WallClock => TimerMode::WallClock,
+            TimerModeSpec::Manual => TimerMode::Manual,
+        };
+        emu.cpu.bus.clint.set_mode(mode, timer.frequency);
+    }
+    if let Some(watchdog) = &spec.devices.watchdog {
+        let action = match watchdog.action {
+            WatchdogActionSpec::Interrupt => WatchdogAction::Interrupt,
+            WatchdogActionSpec::Reset => WatchdogAction::Reset,
+        };
+        emu.cpu.bus.watchdog.configure(watchdog.timeout, action);
+    }
+    emu.debug_prints.set_enabled(spec.devices.debug_print);
+    if let Some(hz) = spec.devices.clock_hz {
+        emu.set_clock_frequency(hz);
+    }
+    emu.cpu.instruction_set = policy.and_then(IsaPolicy::instruction_set);
+    emu
+}
+
+/// A level spec parsed and validated once, from which `instantiate` creates emulators configured
+/// for the level, e.g. one per retry of the player or per graded submission. The template itself
+/// never changes, and its instances don't share any state.
+pub struct LevelTemplate {
+    name: String,
+    policy: Option<IsaPolicy>,
+    limits: RunLimits,
+    spec: Arc<LevelSpec>,
+}
+
+impl LevelTemplate {
+    /// Return the name of the level.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Create an emulator configured for the level, ready for the program to be loaded. Fails if
+    /// the host has no memory for it.
+    pub fn instantiate(&self) -> Result<ConfiguredEmulator, String> {
+        let emu = Emulator::try_new()?;
+        Ok(ConfiguredEmulator {
+            emulator: configure(emu, &self.spec, self.policy.as_ref()),
+            name: self.name.clone(),
+            policy: self.policy.clone(),
+            limits: self.limits,
+            spec: Arc::clone(&self.spec),
+        })
+    }
+}
+
+/// Parse the level spec `spec`, a JSON document, and create an emulator configured for it. The
+/// program is loaded separately, e.g. with `Emulator::load_program_at`.
+pub fn load_level(spec: &[u8]) -> Result<ConfiguredEmulator, String> {
+    load_template(spec)?.instantiate()
+}
+
+/// Parse the level spec `spec`, a JSON document, into a template for `LevelTemplate::instantiate`.
+pub fn load_template(spec: &[u8]) -> Result<LevelTemplate, String> {
+    let spec = serde_json::from_slice::<LevelSpec>(spec).map_err(|err| err.to_string())?;
+
+    for preset in &spec.memory {
+        let end = preset.addr.saturating_add(preset.bytes.len() as u64);
+        if preset.addr < DRAM_BASE || end > DRAM_END {
+            return Err(format!("memory at {:#x} is outside DRAM", preset.addr));
+        }
+    }
+    if let Some(name) = spec
+        .registers
+        .keys()
+        .find(|name| register_index(name).is_none())
+    {
+        return Err(format!("`{}` is not a register", name));
+    }
+    let policy = match &spec.policy {
+        Some(policy) => Some(IsaPolicy::from_json(&policy.to_string())?),
+        None => None,
+    };
+    for condition in &spec.win {
+        condition.validate(spec.reference.is_some())?;
+    }
+    for generator in &spec.generators {
+        generator.validate()?;
+    }
+    for criterion in &spec.rubric {
+        criterion.validate()?;
+    }
+    if spec.devices.clock_hz == Some(0) {
+        return Err("the clock frequency must not be 0".to_string());
+    }
+    if spec.cases == 0 {
+        return Err("a level has at least one case".to_string());
+    }
+    if let Some(reference) = &spec.reference {
+        if spec.entry.saturating_add(reference.len() as u64) > DRAM_END {
+            return Err("the reference solution doesn't fit in DRAM".to_string());
+        }
+    }
+
+    Ok(LevelTemplate {
+        name: spec.name.clone(),
+        policy,
+        limits: RunLimits {
+            max_steps: spec.limits.max_steps.unwrap_or(DEFAULT_MAX_STEPS),
+            time_budget: spec.limits.time_budget_ms.map(Duration::from_millis),
+        },
+        spec: Arc::new(spec),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rvemu::run::DEFAULT_CLOCK_HZ;
+
+    const LEVEL: &str = r#"{
+        "name": "Double it",
+        "memory": [{"addr": 2147483904, "bytes": [21, 0, 0, 0]}],
+        "registers": {"a1": 2147483904},
+        "devices": {"timer": {"mode": "manual"}},
+        "policy": {"mnemonics": ["lw", "add", "sw", "addi", "ecall"]},
+        "win": [
+            {"kind": "memory", "addr": 2147483908, "size": 4, "equals": 42},
+            {"kind": "exit", "code": 0}
+        ],
+        "limits": {"max_steps": 100}
+    }"#;
+
+    /// lw a0, 0(a1); add a0, a0, a0; sw a0, 4(a1); addi a0, zero, 0; addi a7, zero, 93; ecall
+    const SOLUTION: [u32; 6] = [
+        0x0005_a503,
+        0x00a5_0533,
+        0x00a5_a223,
+        0x0000_0513,
+        0x05d0_0893,
+        0x0000_0073,
+    ];
+
+    #[test]
+    fn a_level_configures_the_emulator_and_judges_runs() {
+        let mut level = load_level(LEVEL.as_bytes()).unwrap();
+        assert_eq!("Double it", level.name);
+        assert_eq!(100, level.limits.max_steps);
+        assert_eq!(TimerMode::Manual, level.emulator.cpu.bus.clint.mode());
+        assert!(level.policy.is_some());
+        assert_eq!(DEFAULT_CLOCK_HZ, level.emulator.clock_frequency());
+
+        let program = SOLUTION
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+        level.emulator.cpu.bus.write_dram(DRAM_BASE, &program);
+        let summary = level.run();
+        assert_eq!(StopReason::Exited, summary.reason);
+        assert!(level.is_won(&summary));
+
+        // The policy is enforced: `sub` isn't allowed.
+        let mut level = load_level(LEVEL.as_bytes()).unwrap();
+        let mut program = program;
+        program[4..8].copy_from_slice(&0x40a5_0533u32.to_le_bytes());
+        level.emulator.cpu.bus.write_dram(DRAM_BASE, &program);
+        let summary = level.run();
+        assert_eq!(StopReason::Trapped, summary.reason);
+        assert!(!level.is_won(&summary));
+    }
+
+    #[test]
+    fn templates_instantiate_independent_levels() {
+        let template = load_template(LEVEL.as_bytes()).unwrap();
+        assert_eq!("Double it", template.name());
+        let mut first = template.instantiate().unwrap();
+        let mut second = template.instantiate().unwrap();
+        assert_eq!(100, second.limits.max_steps);
+        assert!(second.policy.is_some());
+
+        first.emulator.cpu.bus.write_dram(DRAM_BASE, &image(&SOLUTION));
+        let summary = first.run();
+        assert!(first.is_won(&summary));
+        assert_eq!(42, read_value(&first.emulator, DRAM_BASE + 0x104, 4));
+
+        // The run changed nothing in the other instance nor in the next one.
+        for level in [&mut second, &mut template.instantiate().unwrap()].iter_mut() {
+            assert_eq!(0, read_value(&level.emulator, DRAM_BASE + 0x104, 4));
+            assert_eq!(21, read_value(&level.emulator, DRAM_BASE + 0x100, 4));
+            assert_eq!(0, level.emulator.cpu.xregs.read(10));
+            assert_eq!(DRAM_BASE, level.emulator.cpu.pc);
+        }
+        assert!(load_template(br#"{"cases": 0}"#).is_err());
+    }
+
+    /// a2 = the sum of the a1 words at a0; addi a0, zero, 0; addi a7, zero, 93; ecall
+    const SUM: [u32; 10] = [
+        0x0000_0613,
+        0x0005_8c63,
+        0x0005_2283,
+        0x0056_0633,
+        0x0045_0513,
+        0xfff5_8593,
+        0xfedf_f06f,
+        0x0000_0513,
+        0x05d0_0893,
+        0x0000_0073,
+    ];
+
+    fn image(words: &[u32]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    /// A level whose answer is the sum of a random array, checked against `SUM`.
+    fn sum_level() -> ConfiguredEmulator {
+        let spec = format!(
+            r#"{{
+                "generators": [{{
+                    "kind": "array", "addr": 2147487744, "min_len": 1, "max_len": 8,
+                    "element_size": 4, "min": 0, "max": 1000,
+                    "address_register": "a0", "length_register": "a1"
+                }}],
+                "cases": 16,
+                "reference": {:?},
+                "win": [
+                    {{"kind": "reference_register", "register": "a2"}},
+                    {{"kind": "exit", "code": 0}}
+                ],
+                "rubric": [
+                    {{"kind": "correctness", "weight": 6}},
+                    {{"kind": "steps", "weight": 2, "max": 100}},
+                    {{"kind": "code_size", "weight": 2, "max": 40}}
+                ],
+                "limits": {{"max_steps": 1000}}
+            }}"#,
+            image(&SUM)
+        );
+        load_level(spec.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn submissions_are_graded_on_generated_cases() {
+        let level = sum_level();
+        let report = level.grade(&image(&SUM), 7).unwrap();
+        assert_eq!(16, report.cases);
+        assert_eq!(16, report.passed);
+        assert_eq!(0, report.failed);
+
+        // addi a2, zero, 42 answers a single case at best.
+        let hard_coded = image(&[0x02a0_0613, SUM[7], SUM[8], SUM[9]]);
+        let report = level.grade(&hard_coded, 7).unwrap();
+        assert_eq!(1, report.failed);
+        assert!(report.passed < 16);
+        assert_eq!(StopReason::Exited, report.summary.reason);
+
+        // The same seed generates the same cases.
+        let again = level.grade(&hard_coded, 7).unwrap();
+        assert_eq!(
+            (report.passed, report.failed_case),
+            (again.passed, again.failed_case)
+        );
+
+        assert!(level.grade(&vec![0; DRAM_END as usize], 7).is_err());
+    }
+
+    #[test]
+    fn lost_cases_are_shrunk() {
+        let level = sum_level();
+        assert_eq!(None, level.shrink_failure(&image(&SUM), 7).unwrap());
+
+        // The hard-coded answer is wrong for the shortest array of the smallest values.
+        let hard_coded = image(&[0x02a0_0613, SUM[7], SUM[8], SUM[9]]);
+        let shrunk = level.shrink_failure(&hard_coded, 7).unwrap().unwrap();
+        assert_eq!(vec![Input::Elements(vec![0])], shrunk.inputs);
+        assert_eq!(json!([[0]]), shrunk.to_json()["inputs"]);
+        assert!(shrunk.runs <= MAX_SHRINK_RUNS);
+    }
+
+    #[test]
+    fn lost_cases_are_explained() {
+        let level = sum_level();
+        let hard_coded = image(&[0x02a0_0613, SUM[7], SUM[8], SUM[9]]);
+        let shrunk = level.shrink_failure(&hard_coded, 7).unwrap().unwrap();
+        let explanation = level.explain_case(&hard_coded, 7, shrunk.case).unwrap();
+
+        let check = &explanation.conditions[0];
+        assert!(!check.holds);
+        assert_eq!(json!(42), check.actual);
+        assert_ne!(check.expected, check.actual);
+        assert!(explanation.conditions[1].holds);
+        assert_eq!(json!(0), explanation.conditions[1].actual);
+        assert_eq!(None, explanation.divergence);
+
+        let json = explanation.to_json();
+        assert_eq!(
+            json!("reference_register"),
+            json["conditions"][0]["condition"]["kind"]
+        );
+        assert_eq!(json!([]), json["output"]["expected"]);
+    }
+
+    #[test]
+    fn submissions_are_scored_on_the_rubric() {
+        let level = sum_level();
+        let evaluation = level.evaluate(&image(&SUM), 7).unwrap();
+        assert_eq!(vec![true; 16], evaluation.cases);
+        assert_eq!(10.0, evaluation.earned());
+        assert_eq!(10.0, evaluation.total());
+
+        // A correct program padded with a nop is too large.
+        let mut padded = image(&SUM);
+        padded.extend_from_slice(&0x0000_0013u32.to_le_bytes());
+        let evaluation = level.evaluate(&padded, 7).unwrap();
+        assert_eq!(8.0, evaluation.earned());
+        let code_size = evaluation.criteria[2];
+        assert_eq!(
+            ("code_size", 0.0, 44),
+            (code_size.name, code_size.earned, code_size.measured)
+        );
+        assert_eq!(json!(8.0), evaluation.to_json()["earned"]);
+    }
+
+    #[test]
+    fn a_level_sets_the_clock_frequency() {
+        let mut level = load_level(br#"{"devices": {"clock_hz": 50000000}}"#).unwrap();
+        level.emulator.cpu.bus.write_dram(DRAM_BASE, &image(&[0x0000_006f]));
+        let summary = level.run();
+        assert_eq!(50_000_000, summary.clock_hz);
+        assert_eq!(DEFAULT_MAX_STEPS as f64 / 50.0, summary.simulated_us);
+    }
+
+    #[test]
+    fn invalid_levels_are_rejected() {
+        assert!(load_level(br#"{"registers": {"q0": 1}}"#).is_err());
+        assert!(load_level(br#"{"memory": [{"addr": 0, "bytes": [1]}]}"#).is_err());
+        assert!(load_level(br#"{"win": [{"kind": "register", "register": "a0"}]}"#).is_err());
+        assert!(load_level(br#"{"devices": {"gpu": true}}"#).is_err());
+        assert!(load_level(br#"{"policy": {"registers": ["q0"]}}"#).is_err());
+        assert!(
+            load_level(br#"{"win": [{"kind": "reference_register", "register": "a0"}]}"#).is_err()
+        );
+        assert!(load_level(
+            br#"{"generators": [{"kind": "range", "register": "a0", "min": 2, "max": 1}]}"#
+        )
+        .is_err());
+        assert!(load_level(br#"{"cases": 0}"#).is_err());
+        assert!(load_level(br#"{"devices": {"clock_hz": 0}}"#).is_err());
+        assert!(load_level(br#"{"rubric": [{"kind": "correctness", "weight": -1}]}"#).is_err());
+        assert!(load_level(b"{}").is_ok());
+    }
+}
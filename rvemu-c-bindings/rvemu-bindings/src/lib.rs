@@ -1,5 +1,71 @@
-use rvemu::bus::DRAM_BASE;
+use rvemu::annotation::{self, StepAnnotation};
+use rvemu::arena::{Arbitration, Arena, ArenaMemory, BotStatus};
+use rvemu::atomics::AtomicEvent;
+use rvemu::bus::{DRAM_BASE, DRAM_END};
+use rvemu::cosim::{self, Cosim, ReferenceState, StateModel};
+use rvemu::csr_names::CsrNames;
+use rvemu::csr_trace::CsrWrite;
+use rvemu::csr_view::{self, InterruptGate};
+use rvemu::debug_info::DebugInfo;
+use rvemu::delta::{self, RegisterChange, StepDelta};
+use rvemu::devices::clint::TimerMode;
+use rvemu::devices::draw_queue::{self, DrawCommand};
+use rvemu::devices::watchdog::WatchdogAction;
+use rvemu::disasm::{disassemble, disassemble_with_names};
+use rvemu::dram::{self, ByteOrder};
 use rvemu::emulator::Emulator;
+use rvemu::encoding_audit::{self, EncodingEvent, EncodingKind, EncodingPolicy};
+use rvemu::exception::Exception;
+use rvemu::harts::Harts;
+use rvemu::history;
+use rvemu::hot_reload;
+use rvemu::injection;
+use rvemu::lockstep::{Lockstep, NodeStatus};
+use rvemu::memory_stats::SiteStats;
+use rvemu::memory_usage::{self, MemoryUsage};
+use rvemu::metrics;
+use rvemu::nan_boxing::{NanBoxing, UnboxedRead};
+use rvemu::pmp;
+use rvemu::preview::{self, PreviewedInstruction};
+use rvemu::profile::Profile;
+use rvemu::program_diff::diff_programs;
+use rvemu::race::DataRace;
+use rvemu::register_view::{register_view, RegisterFile};
+use rvemu::run::{
+    CancelToken, EbreakBehavior, EcallAction, EcallArgs, RunLimits, RunSummary, StepInterrupts,
+    StopReason,
+};
+use rvemu::snapshot;
+use rvemu::speculation;
+use rvemu::trace::{MemoryAccess, TraceEntry};
+use rvemu::trap_return::TrapReturn;
+use rvemu::watchpoint::{WatchHit, WatchKind};
+use rvemu::xlen::Xlen;
+use std::ffi::c_void;
+use std::time::Duration;
+
+mod assembler_session;
+mod background;
+mod crash_report;
+mod disk_cache;
+mod generators;
+mod handles;
+mod hints;
+mod host_memory;
+mod isa_policy;
+mod level;
+mod micro_isa;
+mod replay;
+mod rubric;
+mod sandbox;
+mod status;
+mod wrong_answer;
+
+use host_memory::{FileMapping, HostBuffer, MappedFile};
+use level::{load_level, load_template, ConfiguredEmulator, GradeReport, LevelTemplate};
+use replay::{ReplayBundle, ReplayInput, ReplayPlayer, ReplayRecorder};
+use sandbox::{run_sandboxed, SandboxConfig, SandboxResult};
+use status::{non_null, FfiError, RvStatus};
 
 pub fn new_emulator(program_bytes: Option<Vec<u8>>) -> Box<Emulator> {
     let mut emulator = Box::new(Emulator::new());
@@ -13,215 +79,6568 @@ pub fn new_emulator(program_bytes: Option<Vec<u8>>) -> Box<Emulator> {
     return emulator;
 }
 
+/// Create an emulator and write it into `emu`. Fails with `RvStatus::AllocationFailed` if the
+/// host has no memory for it, instead of aborting the process.
 #[no_mangle]
-pub extern "C" fn emulator_create() -> *mut Emulator {
-    let emu = Box::new(Emulator::new());
+pub extern "C" fn emulator_create(emu: *mut *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
 
-    Box::into_raw(emu)
+        *emu = handles::create(Emulator::try_new().map_err(FfiError::allocation)?);
+        Ok(())
+    })
 }
 
+/// Write the bytes of memory allocated by every emulator of the process into `bytes`, e.g. to
+/// decide whether there's room for another before creating it. Each takes up `DRAM_SIZE` bytes of
+/// DRAM, most of the host memory it uses, though the host only commits the pages the guest
+/// writes.
 #[no_mangle]
-pub extern "C" fn emulator_destroy(emu: *mut Emulator) {
-    assert!(!emu.is_null());
-    unsafe {
-        let _ = Box::from_raw(emu);
-    };
+pub extern "C" fn emulator_total_memory_usage(bytes: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let bytes = unsafe { non_null(bytes, "bytes")? };
+
+        *bytes = dram::total_allocated();
+        Ok(())
+    })
 }
 
+/// Write the host memory the emulator holds into `usage`, in bytes: its DRAM, snapshot storage,
+/// traces and caches, and their total, e.g. to show the footprint of the plugin or to disable
+/// traces when it grows too large.
 #[no_mangle]
-pub extern "C" fn emulator_load_program(emu: *mut Emulator, program_bytes: *const u8, len: usize) {
-    assert!(!emu.is_null());
+pub extern "C" fn emulator_memory_usage(emu: *mut Emulator, usage: *mut MemoryUsage) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let usage = unsafe { non_null(usage, "usage")? };
 
-    let mut program = vec![0; len];
+        *usage = memory_usage::measure(emu);
+        Ok(())
+    })
+}
 
-    let slice = unsafe { std::slice::from_raw_parts(program_bytes, len) };
+#[no_mangle]
+pub extern "C" fn emulator_destroy(emu: *mut Emulator) -> RvStatus {
+    background::stop(emu);
+    status::guard(|| unsafe { handles::destroy(emu, "emu") })
+}
 
-    program.clone_from_slice(slice);
+/// Load `program_bytes` at the start of DRAM and start executing it there.
+#[no_mangle]
+pub extern "C" fn emulator_load_program(
+    emu: *mut Emulator,
+    program_bytes: *const u8,
+    len: usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if program_bytes.is_null() {
+            return Err(FfiError::null("program_bytes"));
+        }
+        if len as u64 > DRAM_END - DRAM_BASE {
+            return Err(FfiError::invalid(format!("the program of {} bytes is too large", len)));
+        }
 
-    unsafe {
-        emu.as_mut().unwrap().initialize_dram(program);
-        emu.as_mut().unwrap().initialize_pc(DRAM_BASE);
-    }
+        let program = unsafe { std::slice::from_raw_parts(program_bytes, len) };
+        emu.initialize_dram(program.to_vec());
+        emu.initialize_pc(DRAM_BASE);
+        Ok(())
+    })
 }
 
+/// Restart the loaded program without reloading it, e.g. for the "Restart" button: zero the
+/// registers and CSRs and move the program counter back to the entry point. If `restore_memory`
+/// isn't 0, the program loaded by `emulator_load_program` is also copied back over its text and
+/// the injected instructions are removed, undoing what the program or the player wrote there. The
+/// rest of memory and the devices are kept.
 #[no_mangle]
-pub extern "C" fn emulator_cpu_execute(emu: *mut Emulator, executed_instruction: *mut u32) -> u32 {
-    assert!(!emu.is_null());
+pub extern "C" fn emulator_reset(emu: *mut Emulator, restore_memory: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        emu.restart(restore_memory != 0);
+        Ok(())
+    })
+}
 
-    unsafe {
-        match emu.as_mut().unwrap().cpu.execute() {
-            Ok(v) => *executed_instruction = v as u32,
-            Err(err) => {
-                match err{
-                    rvemu::exception::Exception::EnvironmentCallFromMMode
-                    | rvemu::exception::Exception::EnvironmentCallFromSMode
-                    | rvemu::exception::Exception::EnvironmentCallFromUMode 
-                    =>{ 
-                        *executed_instruction = 0x73 as u32;
-                        emu.as_mut().unwrap().cpu.pc += 4;
-                    },
-                    rvemu::exception::Exception::InstructionAddressMisaligned => {*executed_instruction = 12 as u32},
-                    rvemu::exception::Exception::InstructionAccessFault => *executed_instruction = 13 as u32,
-                    rvemu::exception::Exception::IllegalInstruction(_) =>       *executed_instruction = 14 as u32,
-                    rvemu::exception::Exception::Breakpoint =>      *executed_instruction = 15 as u32,
-                    rvemu::exception::Exception::LoadAddressMisaligned =>       *executed_instruction = 16 as u32,
-                    rvemu::exception::Exception::LoadAccessFault =>         *executed_instruction = 17 as u32,
-                    rvemu::exception::Exception::StoreAMOAddressMisaligned =>       *executed_instruction = 18 as u32,
-                    rvemu::exception::Exception::StoreAMOAccessFault =>         *executed_instruction = 19 as u32,
-                    rvemu::exception::Exception::InstructionPageFault(_) =>         *executed_instruction = 20 as u32,
-                    rvemu::exception::Exception::LoadPageFault(_) =>        *executed_instruction = 21 as u32,
-                    rvemu::exception::Exception::StoreAMOPageFault(_) =>        *executed_instruction = 22 as u32,
-                    
-                }
+/// A label of the symbols JSON of `riscv_assemble_with_symbols`, at `addr` past `DRAM_BASE`.
+#[derive(serde::Deserialize)]
+struct Label {
+    name: String,
+    addr: u64,
+}
+
+/// Replace the text of the running program, the program loaded by `emulator_load_program`, with
+/// the edited `bytes` of `len` bytes, keeping the rest of memory, the registers and the devices,
+/// e.g. to apply a small code edit without resetting the data the program built up. `symbols` of
+/// `symbols_len` bytes is the symbols JSON of the new text as `riscv_assemble_with_symbols` writes
+/// it, or null. If it's given, the program counter moves to the same offset in the same symbol of
+/// the new text, which needs the symbols of the old text from `emulator_add_symbol`, and the new
+/// symbols replace them. Fails without changing anything if the program counter can't be moved.
+#[no_mangle]
+pub extern "C" fn emulator_reload_text(
+    emu: *mut Emulator,
+    bytes: *const u8,
+    len: usize,
+    symbols: *const u8,
+    symbols_len: usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if bytes.is_null() {
+            return Err(FfiError::null("bytes"));
+        }
+
+        let mut debug_info = None;
+        if !symbols.is_null() {
+            let symbols = unsafe { std::slice::from_raw_parts(symbols, symbols_len) };
+            let labels = serde_json::from_slice::<Vec<Label>>(symbols)
+                .map_err(|err| FfiError::invalid(format!("invalid symbols: {}", err)))?;
+            let new = debug_info.get_or_insert_with(DebugInfo::new);
+            for label in labels {
+                new.add_symbol(&label.name, DRAM_BASE + label.addr);
             }
+        }
+        let image = unsafe { std::slice::from_raw_parts(bytes, len) };
+        hot_reload::reload_text(emu, image, debug_info).map_err(FfiError::invalid)
+    })
+}
+
+/// Execute one step and write its instruction into `executed_instruction`. Like `emulator_step`,
+/// the step runs a cycle on the devices and takes a pending interrupt first, so the instruction is
+/// the first of the trap handler when one is taken. If the instruction raised an exception, a
+/// code for it is written instead: 0x73 for an `ecall`, which is skipped, and 12 to 22 for the
+/// others, in the order of their exception codes.
+#[no_mangle]
+pub extern "C" fn emulator_cpu_execute(
+    emu: *mut Emulator,
+    executed_instruction: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let executed_instruction =
+            unsafe { non_null(executed_instruction, "executed_instruction")? };
+
+        *executed_instruction = execute(emu);
+        Ok(())
+    })
+}
+
+/// Execute one instruction and return what `emulator_cpu_execute` reports for it.
+fn execute(emu: &mut Emulator) -> u32 {
+    match emu.step() {
+        Ok(inst) => inst as u32,
+        Err(
+            Exception::EnvironmentCallFromMMode
+            | Exception::EnvironmentCallFromSMode
+            | Exception::EnvironmentCallFromUMode,
+        ) => {
+            emu.cpu.pc += 4;
+            0x73
+        }
+        Err(Exception::InstructionAddressMisaligned) => 12,
+        Err(Exception::InstructionAccessFault(_)) => 13,
+        Err(Exception::IllegalInstruction(_)) => 14,
+        Err(Exception::Breakpoint) => 15,
+        Err(Exception::LoadAddressMisaligned(_)) => 16,
+        Err(Exception::LoadAccessFault(_)) => 17,
+        Err(Exception::StoreAMOAddressMisaligned(_)) => 18,
+        Err(Exception::StoreAMOAccessFault(_)) => 19,
+        Err(Exception::InstructionPageFault(_)) => 20,
+        Err(Exception::LoadPageFault(_)) => 21,
+        Err(Exception::StoreAMOPageFault(_)) => 22,
+    }
+}
+
+/// The effect of one instruction executed by `emulator_cpu_execute_traced`, e.g. to animate the
+/// register it changed.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ExecuteTrace {
+    /// What `emulator_cpu_execute` reports: the instruction, or a code for its exception.
+    pub executed_instruction: u32,
+    /// The number of valid entries in `changes`.
+    pub change_count: u32,
+    /// The program counter before the instruction.
+    pub old_pc: u64,
+    /// The program counter after the instruction.
+    pub new_pc: u64,
+    /// The integer registers that changed, by register number.
+    pub changes: [RegisterChange; 2],
+}
+
+/// Same as `emulator_cpu_execute`, but write the executed instruction, the program counter
+/// before and after it, and the integer registers it changed into `trace`.
+#[no_mangle]
+pub extern "C" fn emulator_cpu_execute_traced(
+    emu: *mut Emulator,
+    trace: *mut ExecuteTrace,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let trace = unsafe { non_null(trace, "trace")? };
+
+        let old_pc = emu.cpu.pc;
+        let before = (0..32).map(|i| emu.cpu.xregs.read(i)).collect::<Vec<u64>>();
+        let executed_instruction = execute(emu);
+        let after = (0..32).map(|i| emu.cpu.xregs.read(i)).collect::<Vec<u64>>();
+
+        let mut changes = [RegisterChange::default(); 2];
+        let changed = delta::changes(&before, &after);
+        for (slot, change) in changes.iter_mut().zip(&changed) {
+            *slot = *change;
+        }
+        *trace = ExecuteTrace {
+            executed_instruction,
+            change_count: changed.len().min(changes.len()) as u32,
+            old_pc,
+            new_pc: emu.cpu.pc,
+            changes,
+        };
+        Ok(())
+    })
+}
+
+/// Execute one instruction and write the outcome into `summary`. Unlike `emulator_cpu_execute`,
+/// the stop reason tells why the step didn't complete, if it didn't.
+#[no_mangle]
+pub extern "C" fn emulator_step(emu: *mut Emulator, summary: *mut RunSummary) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = emu.run(1);
+        Ok(())
+    })
+}
+
+/// Execute up to `max_steps` instructions and write the outcome into `summary`. The run stops
+/// early on an exception, an `ebreak`, or an `ecall`; see `StopReason` for the reasons.
+#[no_mangle]
+pub extern "C" fn emulator_run(
+    emu: *mut Emulator,
+    max_steps: u64,
+    summary: *mut RunSummary,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = emu.run(max_steps);
+        Ok(())
+    })
+}
+
+/// Limit every run to `instructions_per_call` instructions, whatever its own limit, so a program
+/// stuck in an infinite loop can't hang the frame. A run that reaches it stops with
+/// `StopReason::FuelExhausted`, e.g. to show a "program seems stuck" prompt. The budget is
+/// refilled for each call. 0 removes the limit.
+#[no_mangle]
+pub extern "C" fn emulator_set_fuel(emu: *mut Emulator, instructions_per_call: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.fuel = match instructions_per_call {
+            0 => None,
+            fuel => Some(fuel),
+        };
+        Ok(())
+    })
+}
+
+/// Same as `emulator_run`, but writes only the stop reason and the number of instructions
+/// executed, for game loops that run a batch of instructions per frame and don't need the rest of
+/// the summary.
+#[no_mangle]
+pub extern "C" fn emulator_run_batch(
+    emu: *mut Emulator,
+    max_instructions: u64,
+    stop_reason: *mut StopReason,
+    executed: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let stop_reason = unsafe { non_null(stop_reason, "stop_reason")? };
+        let executed = unsafe { non_null(executed, "executed")? };
+
+        let summary = emu.run(max_instructions);
+        *stop_reason = summary.reason;
+        *executed = summary.steps;
+        Ok(())
+    })
+}
+
+/// Same as `emulator_run`, but also stops with `StopReason::TimeBudget` after about
+/// `time_budget_ns` nanoseconds of host time.
+#[no_mangle]
+pub extern "C" fn emulator_run_for(
+    emu: *mut Emulator,
+    max_steps: u64,
+    time_budget_ns: u64,
+    summary: *mut RunSummary,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        let limits = RunLimits {
+            max_steps,
+            time_budget: Some(Duration::from_nanos(time_budget_ns)),
         };
+        *summary = emu.run_with(limits);
+        Ok(())
+    })
+}
+
+/// Same as `emulator_run`, but also stops with `StopReason::TrapReturn` right after `mret`,
+/// `sret` or `uret`, with the program counter at the instruction's target.
+#[no_mangle]
+pub extern "C" fn emulator_run_until_trap_return(
+    emu: *mut Emulator,
+    max_steps: u64,
+    summary: *mut RunSummary,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = emu.run_until_trap_return(max_steps);
+        Ok(())
+    })
+}
+
+/// Start running up to `max_steps` instructions on a background thread, e.g. so a long program
+/// doesn't freeze the frame, and return at once. The run stops as `emulator_run` does, or when
+/// `emulator_pause` is called. While it runs, only `emulator_get_register`,
+/// `emulator_set_register`, `emulator_get_pc`, `emulator_set_pc`, `emulator_get_csr`,
+/// `emulator_set_csr`, `emulator_read_memory`, `emulator_write_memory`, the
+/// `emulator_read_u*` and `emulator_write_u*` functions and the background run functions may be
+/// called on the emulator, from any thread: they wait for the run to be between two slices of
+/// instructions. Destroying the emulator pauses the run first. Fails if it's already running.
+#[no_mangle]
+pub extern "C" fn emulator_run_async(emu: *mut Emulator, max_steps: u64) -> RvStatus {
+    status::guard(|| {
+        handles::check(emu, "emu")?;
+        background::start(emu, max_steps)
+    })
+}
+
+/// Pause the background run of the emulator and wait until it stopped, after at most a slice of
+/// instructions. `emulator_run_async` resumes it. Fails if it was never run in the background.
+#[no_mangle]
+pub extern "C" fn emulator_pause(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        handles::check(emu, "emu")?;
+        background::pause(emu)
+    })
+}
+
+/// Write 1 into `running` if the emulator is running in the background, or 0 once its run was
+/// paused or stopped, e.g. to poll for the end of a run every frame.
+#[no_mangle]
+pub extern "C" fn emulator_is_running(emu: *mut Emulator, running: *mut u32) -> RvStatus {
+    status::guard(|| {
+        handles::check(emu, "emu")?;
+        let running = unsafe { non_null(running, "running")? };
+
+        *running = background::is_running(emu) as u32;
+        Ok(())
+    })
+}
+
+/// Write the outcome of the last background run of the emulator so far into `summary`: why it
+/// stopped, if it did, and the instructions it executed in total. Fails if it was never run in the
+/// background or hasn't executed its first slice of instructions yet, and with
+/// `RvStatus::Panicked` if the emulator panicked during the run.
+#[no_mangle]
+pub extern "C" fn emulator_async_summary(emu: *mut Emulator, summary: *mut RunSummary) -> RvStatus {
+    status::guard(|| {
+        handles::check(emu, "emu")?;
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = background::summary(emu)?;
+        Ok(())
+    })
+}
+
+/// Copy the message of the last failed call on this thread into `out` as UTF-8 (not
+/// NUL-terminated), e.g. for a call that returned an `RvStatus` other than `Ok`, and write its
+/// full length into `full_len`, 0 if no call failed.
+#[no_mangle]
+pub extern "C" fn emulator_last_error_message(
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    let message = status::last_error();
+    status::guard(|| write_string(&message, out, len, full_len))
+}
+
+/// Return the emulator with the handle id `handle`.
+unsafe fn resolve_emulator<'a>(handle: u64) -> Result<&'a mut Emulator, FfiError> {
+    Ok(&mut *handles::resolve(handle, "handle")?)
+}
+
+/// Create an emulator and write its handle id into `handle`. The `emulator_handle_*` functions
+/// take the id instead of a pointer. An id is a plain number, so a managed host can store it with
+/// its objects and keep using it after a Unity domain reload. It's never 0 and never reused, so a
+/// stale id fails with a status instead of resolving to another emulator. Fails as
+/// `emulator_create` does if the host has no memory for it.
+#[no_mangle]
+pub extern "C" fn emulator_handle_create(handle: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let handle = unsafe { non_null(handle, "handle")? };
+
+        let emu = handles::create(Emulator::try_new().map_err(FfiError::allocation)?);
+        *handle = handles::assign_id(emu);
+        Ok(())
+    })
+}
+
+/// Destroy the emulator with the handle id `handle`.
+#[no_mangle]
+pub extern "C" fn emulator_handle_destroy(handle: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = handles::remove_id::<Emulator>(handle, "handle")?;
+        background::stop(emu);
+        unsafe { handles::destroy(emu, "handle") }
+    })
+}
+
+/// Destroy every live handle created through the FFI: the emulators, levels and templates,
+/// assembler sessions and the other objects, with the ones borrowed from them, and forget every
+/// handle id. Call it before the host unloads the library or, in Unity, before a domain reload,
+/// which would otherwise leak them. No other thread may call in while it runs. The stale ids, and
+/// in debug builds the stale pointers, fail with a status afterwards. The background runs are
+/// paused first. Writes the number of handles destroyed into `destroyed`.
+#[no_mangle]
+pub extern "C" fn rvj_shutdown_all(destroyed: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let destroyed = unsafe { non_null(destroyed, "destroyed")? };
+
+        background::stop_all();
+        *destroyed = unsafe { handles::destroy_all() };
+        Ok(())
+    })
+}
+
+/// Write the pointer of the emulator with the handle id `handle` into `emu`, to call the
+/// `emulator_*` functions that have no handle variant. The pointer must not be kept: it's only
+/// valid until the emulator is destroyed, and not across a domain reload.
+#[no_mangle]
+pub extern "C" fn emulator_handle_pointer(handle: u64, emu: *mut *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        *emu = unsafe { resolve_emulator(handle)? };
+        Ok(())
+    })
+}
+
+/// Same as `emulator_load_program`, with a handle id.
+#[no_mangle]
+pub extern "C" fn emulator_handle_load_program(
+    handle: u64,
+    program_bytes: *const u8,
+    len: usize,
+) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_load_program(emu, program_bytes, len),
+        Err(error) => status::fail(error),
     }
+}
 
-    0
+/// Same as `emulator_step`, with a handle id.
+#[no_mangle]
+pub extern "C" fn emulator_handle_step(handle: u64, summary: *mut RunSummary) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_step(emu, summary),
+        Err(error) => status::fail(error),
+    }
 }
 
+/// Same as `emulator_run`, with a handle id.
 #[no_mangle]
-pub extern "C" fn emulator_get_register(emu: *mut Emulator, index: u64) -> u64 {
-    unsafe { emu.as_mut().unwrap().cpu.xregs.read(index) }
+pub extern "C" fn emulator_handle_run(
+    handle: u64,
+    max_steps: u64,
+    summary: *mut RunSummary,
+) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_run(emu, max_steps, summary),
+        Err(error) => status::fail(error),
+    }
 }
 
+/// Same as `emulator_get_register`, with a handle id.
 #[no_mangle]
-pub extern "C" fn emulator_set_register(emu: *mut Emulator, index: u64, value: u64) {
-    unsafe {
-        emu.as_mut().unwrap().cpu.xregs.write(index, value);
+pub extern "C" fn emulator_handle_get_register(
+    handle: u64,
+    index: u64,
+    value: *mut u64,
+) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_get_register(emu, index, value),
+        Err(error) => status::fail(error),
     }
 }
 
-/* ASSEMBLER */
-use deno_core::v8;
-use deno_core::FastString;
-use deno_core::JsRuntime;
-use deno_core::RuntimeOptions;
-use serde_json;
-use serde_v8;
-use std::collections::HashMap;
-use std::ffi::c_char;
-use std::ffi::CStr;
-use std::ffi::CString;
+/// Same as `emulator_set_register`, with a handle id.
+#[no_mangle]
+pub extern "C" fn emulator_handle_set_register(handle: u64, index: u64, value: u64) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_set_register(emu, index, value),
+        Err(error) => status::fail(error),
+    }
+}
 
+/// Same as `emulator_read_memory`, with a handle id.
 #[no_mangle]
-pub extern "C" fn free_riscv_assemble(bytes: *mut u8) {
-    assert!(!bytes.is_null());
+pub extern "C" fn emulator_handle_read_memory(
+    handle: u64,
+    addr: u64,
+    buf: *mut u8,
+    len: usize,
+) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_read_memory(emu, addr, buf, len),
+        Err(error) => status::fail(error),
+    }
+}
 
-    unsafe {
-        let _ = Box::from_raw(bytes);
-    };
+/// Same as `emulator_write_memory`, with a handle id.
+#[no_mangle]
+pub extern "C" fn emulator_handle_write_memory(
+    handle: u64,
+    addr: u64,
+    buf: *const u8,
+    len: usize,
+) -> RvStatus {
+    match unsafe { resolve_emulator(handle) } {
+        Ok(emu) => emulator_write_memory(emu, addr, buf, len),
+        Err(error) => status::fail(error),
+    }
+}
+
+/// Copy `entries` into `out`, which must have room for them, and write their number into
+/// `copied`.
+fn copy_entries<T: Copy>(entries: &[T], out: *mut T, copied: &mut u64) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::null("out"));
+    }
+    unsafe { std::slice::from_raw_parts_mut(out, entries.len()) }.copy_from_slice(entries);
+    *copied = entries.len() as u64;
+    Ok(())
 }
 
+/// Return the error of the `len` bytes at `addr` that aren't all in DRAM.
+fn outside_dram(addr: u64, len: usize) -> FfiError {
+    FfiError::invalid(format!("the {} bytes at {:#x} aren't all in DRAM", len, addr))
+}
+
+/// Save the registers, the program counter, the CSRs and DRAM of `emu` into a new buffer, e.g. for
+/// a rewind feature. Writes the buffer into `out` and its length into `len`. The buffer must be
+/// freed with `free_state_buffer`.
 #[no_mangle]
-pub extern "C" fn riscv_assemble(
-    instruction: *const c_char,
+pub extern "C" fn emulator_save_state(
+    emu: *mut Emulator,
     out: *mut *mut u8,
-    error_line: *mut u64,
-) -> u64 {
-    unsafe { *error_line = 0 };
+    len: *mut usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let out = unsafe { non_null(out, "out")? };
+        let len = unsafe { non_null(len, "len")? };
 
-    let instructions = unsafe { CString::from(CStr::from_ptr(instruction)).into_string() };
+        let blob = snapshot::save(emu).into_boxed_slice();
+        *len = blob.len();
+        *out = Box::into_raw(blob) as *mut u8;
+        Ok(())
+    })
+}
 
-    if instructions.is_err() {
-        return 0;
-    }
+/// Restore `emu` to the state saved by `emulator_save_state` in the `len` bytes at `buf`. Returns
+/// `RvStatus::InvalidArgument`, leaving `emu` as it is, if they aren't a snapshot of this version.
+#[no_mangle]
+pub extern "C" fn emulator_restore_state(
+    emu: *mut Emulator,
+    buf: *const u8,
+    len: usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if buf.is_null() {
+            return Err(FfiError::null("buf"));
+        }
+
+        let blob = unsafe { std::slice::from_raw_parts(buf, len) };
+        snapshot::restore(emu, blob).map_err(FfiError::invalid)
+    })
+}
+
+/// Free a buffer of `len` bytes returned by `emulator_save_state`.
+#[no_mangle]
+pub extern "C" fn free_state_buffer(buf: *mut u8, len: usize) -> RvStatus {
+    status::guard(|| {
+        handles::check(buf, "buf")?;
+
+        unsafe {
+            let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(buf, len));
+        }
+        Ok(())
+    })
+}
 
-    let instructions = instructions.unwrap();
-    // dbg!(instructions);
+/// Take a rewind checkpoint of the emulator, a snapshot kept inside it, and write its id for
+/// `emulator_rewind` into `id`. Ids are never 0. The checkpoint counts toward the budget set with
+/// `emulator_set_history_budget`, and may be evicted at once if it doesn't fit.
+#[no_mangle]
+pub extern "C" fn emulator_checkpoint(emu: *mut Emulator, id: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let id = unsafe { non_null(id, "id")? };
 
-    let mut runtime = JsRuntime::new(RuntimeOptions::default());
+        *id = history::checkpoint(emu);
+        Ok(())
+    })
+}
 
-    let instruction_setup = String::from(include_str!("../encoder/Instruction.js"));
+/// Restore the emulator to the checkpoint `id`, e.g. for a rewind button. The checkpoint is kept,
+/// so it can be rewound to again. Fails if it was evicted or never taken.
+#[no_mangle]
+pub extern "C" fn emulator_rewind(emu: *mut Emulator, id: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
 
-    if let Err(_) = eval(&mut runtime, instruction_setup.into()) {
-        return 0;
-    }
+        history::rewind(emu, id).map_err(FfiError::invalid)
+    })
+}
 
-    let mut instr_memory = Vec::new();
+/// Copy the rewind checkpoints of the emulator, oldest first, into `out` as JSON: a list of
+/// `{"id", "retired", "size"}` objects with the instructions retired when each was taken and the
+/// bytes of its snapshot. Writes the full length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_list_checkpoints(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
 
-    let mut instructions_filtered: Vec<&str> = Vec::new();
-    let mut labels: HashMap<&str, usize> = HashMap::new();
+        let json = emu
+            .history
+            .checkpoints()
+            .map(|checkpoint| {
+                serde_json::json!({
+                    "id": checkpoint.id,
+                    "retired": checkpoint.retired,
+                    "size": checkpoint.size(),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(json).to_string(), out, len, full_len)
+    })
+}
 
-    let clean_instrs = instructions.replace("\r\n", "\n");
-    let instrs = clean_instrs
-        .split('\n')
-        .map(|x| String::from(x.trim()))
-        .filter(|x| !x.is_empty())
-        .collect::<Vec<String>>();
+/// Cap the bytes of the rewind checkpoints and the traces of the emulator at `bytes`, so a long
+/// play session doesn't grow memory without bound. 0 removes the cap. When they exceed it, after
+/// a checkpoint or a run, the least recently taken or rewound-to checkpoints are evicted first,
+/// then the oldest entries of the memory access trace and of the logs of CSR writes, atomic
+/// instructions and privilege returns. `emulator_history_truncated` tells how many were.
+#[no_mangle]
+pub extern "C" fn emulator_set_history_budget(emu: *mut Emulator, bytes: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
 
-    for instr in instrs.iter() {
-        if instr.contains(":") {
-            let label_name = instr.split(":").collect::<Vec<&str>>()[0];
+        let budget = match bytes {
+            0 => None,
+            bytes => Some(bytes),
+        };
+        history::set_budget(emu, budget);
+        Ok(())
+    })
+}
 
-            labels.get(label_name).expect("Label name not found.");
-            labels.insert(&label_name, instructions_filtered.len() * 4);
-        } else {
-            instructions_filtered.push(instr);
+/// Write the number of checkpoints and trace entries evicted to fit in the budget since the last
+/// call into `evicted`, e.g. to tell the player once a frame that the history was truncated.
+#[no_mangle]
+pub extern "C" fn emulator_history_truncated(emu: *mut Emulator, evicted: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let evicted = unsafe { non_null(evicted, "evicted")? };
+
+        *evicted = emu.history.take_truncated();
+        Ok(())
+    })
+}
+
+/// The callback of the evictions of the history. It's called with the user data given with it
+/// and the numbers of checkpoints and of trace entries evicted at once.
+pub type EvictionFn = extern "C" fn(user_data: *mut c_void, checkpoints: u64, entries: u64);
+
+/// Call `evicted` with `user_data` whenever checkpoints or trace entries are evicted to fit in the
+/// budget set with `emulator_set_history_budget`, so the host can tell the player the history was
+/// truncated without polling `emulator_history_truncated`, which still counts them. A null
+/// `evicted` removes the callback.
+#[no_mangle]
+pub extern "C" fn emulator_set_eviction_callback(
+    emu: *mut Emulator,
+    evicted: Option<EvictionFn>,
+    user_data: *mut c_void,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let callback = evicted.map(|evicted| {
+            let user_data = UserData(user_data);
+            Box::new(move |checkpoints, entries| evicted(user_data.0, checkpoints, entries)) as _
+        });
+        history::set_eviction_callback(emu, callback);
+        Ok(())
+    })
+}
+
+/// Select whether `emulator_step` takes a pending interrupt: 0 = take it and execute the first
+/// instruction of the trap handler (the default), 1 = defer it, so a step always executes the
+/// next instruction of the program. Fails if `mode` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_step_interrupts(emu: *mut Emulator, mode: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.step_interrupts = match mode {
+            0 => StepInterrupts::Deliver,
+            1 => StepInterrupts::Defer,
+            _ => return Err(FfiError::invalid(format!("unknown step interrupt mode {}", mode))),
+        };
+        Ok(())
+    })
+}
+
+/// Set the nominal clock frequency of the core to `hz` hertz, 100 MHz by default. Run summaries
+/// report the simulated time of their steps at this frequency in `simulated_us`, one cycle per
+/// instruction. Fails if `hz` is 0.
+#[no_mangle]
+pub extern "C" fn emulator_set_clock_frequency(emu: *mut Emulator, hz: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if hz == 0 {
+            return Err(FfiError::invalid("the clock frequency must not be 0".to_string()));
         }
+        emu.set_clock_frequency(hz);
+        Ok(())
+    })
+}
+
+/// Write the nominal clock frequency of the core in hertz into `hz`.
+#[no_mangle]
+pub extern "C" fn emulator_clock_frequency(emu: *mut Emulator, hz: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let hz = unsafe { non_null(hz, "hz")? };
+
+        *hz = emu.clock_frequency();
+        Ok(())
+    })
+}
+
+/// The register index of the program counter in `emulator_get_register` and
+/// `emulator_set_register`, after x0 to x31.
+pub const REGISTER_PC: u64 = 32;
+
+/// Return an error if `index` is neither an integer register nor `REGISTER_PC`.
+fn check_register(index: u64) -> Result<(), FfiError> {
+    match index {
+        0..=REGISTER_PC => Ok(()),
+        _ => Err(FfiError::invalid(format!(
+            "register {} doesn't exist, expected 0 to 31 or {} for the pc",
+            index, REGISTER_PC
+        ))),
     }
+}
+
+/// Write the value of the integer register `index` (0-31), or of the program counter for
+/// `REGISTER_PC`, into `value`. In RV32, it's the 32-bit value, zero-extended.
+#[no_mangle]
+pub extern "C" fn emulator_get_register(
+    emu: *mut Emulator,
+    index: u64,
+    value: *mut u64,
+) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            let value = unsafe { non_null(value, "value")? };
+            check_register(index)?;
+
+            let xlen = emu.cpu.xlen();
+            *value = match index {
+                REGISTER_PC => xlen.truncate(emu.cpu.pc),
+                _ => xlen.truncate(emu.cpu.xregs.read(index)),
+            };
+            Ok(())
+        })
+    })
+}
+
+/// Set the integer register `index` (0-31), or the program counter for `REGISTER_PC`, to
+/// `value`. Writes to x0 are ignored, as x0 is always 0. In RV32, only the low 32 bits are
+/// written.
+#[no_mangle]
+pub extern "C" fn emulator_set_register(emu: *mut Emulator, index: u64, value: u64) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            check_register(index)?;
+
+            let xlen = emu.cpu.xlen();
+            match index {
+                REGISTER_PC => emu.cpu.pc = xlen.truncate(value),
+                _ => emu.cpu.xregs.write(index, xlen.sign_extend(value)),
+            }
+            Ok(())
+        })
+    })
+}
+
+/// Write the program counter into `pc`, e.g. to highlight the current line in a debugger.
+#[no_mangle]
+pub extern "C" fn emulator_get_pc(emu: *mut Emulator, pc: *mut u64) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            let pc = unsafe { non_null(pc, "pc")? };
+
+            *pc = emu.cpu.xlen().truncate(emu.cpu.pc);
+            Ok(())
+        })
+    })
+}
+
+/// Move the program counter to `addr`, so the next step executes the instruction there, e.g. for
+/// "set next statement" or "run to cursor". A hart waiting in `wfi` wakes up. Fails if `addr`
+/// isn't 2-byte aligned.
+#[no_mangle]
+pub extern "C" fn emulator_set_pc(emu: *mut Emulator, addr: u64) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            if !addr.is_multiple_of(2) {
+                return Err(FfiError::invalid(format!("{:#x} isn't 2-byte aligned", addr)));
+            }
+
+            emu.cpu.pc = emu.cpu.xlen().truncate(addr);
+            emu.cpu.idle = false;
+            Ok(())
+        })
+    })
+}
 
-    for (i, instr) in instrs.iter().enumerate() {
-        let mut tokens = instr.split_whitespace().collect::<Vec<&str>>();
+/// Copy the value of the register `index` of the register file `file` (0 for x0 to x31, 1 for
+/// f0 to f31) into `out` as JSON, in every interpretation the UI shows: `hex`, plus `signed` and
+/// `unsigned` for an integer register, or `f64`, `f32` and `nan_boxed` for a floating-point one,
+/// e.g. `{"hex": "0xffffffffffffffff", "signed": "-1", "unsigned": "18446744073709551615"}`. The
+/// values are strings so that 64-bit integers survive any JSON parser. Writes the full length of
+/// the JSON into `full_len`. Fails if there's no such register.
+#[no_mangle]
+pub extern "C" fn emulator_format_register(
+    emu: *mut Emulator,
+    file: u32,
+    index: u64,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
 
-        if tokens.len() > 0 && tokens[0] == "bne" {
-            let label_name = tokens[tokens.len() - 1];
-            let offset = labels.get(label_name).expect("label name not found 2.");
-            let len = &tokens.len();
-            tokens[len - 1] = &format!("{}", offset);
+        let no_register = || {
+            FfiError::invalid(format!("register {} of the file {} doesn't exist", index, file))
+        };
+        let file = match file {
+            0 => RegisterFile::Integer,
+            1 => RegisterFile::Float,
+            _ => return Err(no_register()),
+        };
+        let view = register_view(emu, file, index).ok_or_else(no_register)?;
+        let mut json = serde_json::Map::new();
+        json.insert("hex".to_string(), view.hex.into());
+        let strings = [
+            ("signed", view.signed),
+            ("unsigned", view.unsigned),
+            ("f64", view.f64),
+            ("f32", view.f32),
+        ];
+        for (name, value) in strings.iter().cloned() {
+            if let Some(value) = value {
+                json.insert(name.to_string(), value.into());
+            }
+        }
+        if let Some(nan_boxed) = view.nan_boxed {
+            json.insert("nan_boxed".to_string(), nan_boxed.into());
         }
 
-        dbg!(instr);
+        write_string(&serde_json::Value::from(json).to_string(), out, len, full_len)
+    })
+}
 
-        let wrapped_instr = format!(
-            "\n;new Instruction('{}', {{ 'ISA': COPTS_ISA.RV32I }}).bin",
-            instr
-        );
+/// Copy the `len` bytes of guest memory at `addr` into `buf`, e.g. for a memory view. Injected
+/// instructions read as the bytes they replaced. Fails without copying anything if they aren't
+/// all in DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_read_memory(
+    emu: *mut Emulator,
+    addr: u64,
+    buf: *mut u8,
+    len: usize,
+) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            if buf.is_null() {
+                return Err(FfiError::null("buf"));
+            }
+
+            let bytes = injection::visible_bytes(emu, addr, len as u64)
+                .ok_or_else(|| outside_dram(addr, len))?;
+            unsafe { std::slice::from_raw_parts_mut(buf, len) }.copy_from_slice(&bytes);
+            Ok(())
+        })
+    })
+}
+
+/// Copy the `len` bytes of `buf` to guest memory at `addr`, e.g. to inject the input of a level
+/// at runtime. Fails without writing anything if they don't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_write_memory(
+    emu: *mut Emulator,
+    addr: u64,
+    buf: *const u8,
+    len: usize,
+) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            if buf.is_null() {
+                return Err(FfiError::null("buf"));
+            }
+
+            let bytes = unsafe { std::slice::from_raw_parts(buf, len) };
+            emu.write_dram(addr, bytes).map_err(|_| outside_dram(addr, len))
+        })
+    })
+}
+
+/// Back the `len` bytes of the address space at `addr` with the host buffer `buf`, e.g. a texture
+/// the guest draws into, so the guest reads and writes it directly instead of the host copying it
+/// every frame. The mapping hides the DRAM or devices beneath it from the guest, but not from
+/// `emulator_read_memory`. The buffer must stay valid until it's unmapped or the emulator is
+/// destroyed. Fails if `len` is 0 or the region overlaps another mapping.
+#[no_mangle]
+pub extern "C" fn emulator_map_host_buffer(
+    emu: *mut Emulator,
+    addr: u64,
+    buf: *mut u8,
+    len: usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if buf.is_null() {
+            return Err(FfiError::null("buf"));
+        }
+
+        let buffer = Box::new(HostBuffer::new(buf, len));
+        emu.cpu.bus.mappings.map(addr, buffer).map_err(FfiError::invalid)
+    })
+}
+
+/// Back the address space at `addr` with the file at `path`, e.g. the dataset of a level, as
+/// `emulator_map_host_buffer` does with a buffer. The file is mapped read-only if `copy_on_write`
+/// is 0, so stores to it fault, and copy-on-write otherwise, so stores go to private pages and
+/// never reach the file. Read-only pages are shared by every emulator mapping the file. Fails if
+/// the file can't be mapped or is empty, or the region overlaps another mapping.
+#[no_mangle]
+pub extern "C" fn emulator_map_file(
+    emu: *mut Emulator,
+    addr: u64,
+    path: *const c_char,
+    copy_on_write: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if path.is_null() {
+            return Err(FfiError::null("path"));
+        }
 
-        if let Ok(eval_result) = eval(&mut runtime, wrapped_instr.into()) {
-            let instr_word = u32::from_str_radix(eval_result.as_str().unwrap(), 2).unwrap();
-            instr_memory.extend(instr_word.to_le_bytes());
+        let path = unsafe { CStr::from_ptr(path) }.to_string_lossy();
+        let mode = if copy_on_write == 0 {
+            FileMapping::ReadOnly
         } else {
-            unsafe { *error_line = (i + 1) as u64 }
-            return 0;
+            FileMapping::CopyOnWrite
+        };
+        let file = MappedFile::open(path.as_ref(), mode)
+            .map_err(|err| FfiError::invalid(format!("can't map {}: {}", path, err)))?;
+        emu.cpu.bus.mappings.map(addr, Box::new(file)).map_err(FfiError::invalid)
+    })
+}
+
+/// Remove the buffer or file mapped at `addr`. The host can free its buffer afterwards. Fails if
+/// nothing is mapped at `addr`.
+#[no_mangle]
+pub extern "C" fn emulator_unmap_host_buffer(emu: *mut Emulator, addr: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        match emu.cpu.bus.mappings.unmap(addr) {
+            Some(_) => Ok(()),
+            None => Err(FfiError::invalid(format!("nothing is mapped at {:#x}", addr))),
         }
+    })
+}
+
+/// Return the byte order `order` of the FFI: 0 = little-endian, 1 = big-endian.
+fn byte_order(order: u32) -> Result<ByteOrder, FfiError> {
+    match order {
+        0 => Ok(ByteOrder::Little),
+        1 => Ok(ByteOrder::Big),
+        _ => Err(FfiError::invalid(format!("{} isn't a byte order", order))),
     }
+}
 
-    let len = instr_memory.len();
+/// Return the `size` bytes at `addr` in the byte order `order`. Fails if they aren't all in DRAM
+/// or `order` is unknown.
+fn read_value(emu: *mut Emulator, addr: u64, size: u64, order: u32) -> Result<u64, FfiError> {
+    let order = byte_order(order)?;
+    background::locked(emu, || {
+        let emu = unsafe { non_null(emu, "emu")? };
+        emu.read_value(addr, size, order).ok_or_else(|| outside_dram(addr, size as usize))
+    })
+}
 
-    unsafe {
-        let mut boxed = instr_memory.into_boxed_slice();
-        *out = boxed.as_mut_ptr();
-        std::mem::forget(boxed);
-    }
+/// Write the `size` low bytes of `value` at `addr` in the byte order `order`. Fails if they
+/// aren't all in DRAM or `order` is unknown.
+fn write_value(
+    emu: *mut Emulator,
+    addr: u64,
+    size: u64,
+    order: u32,
+    value: u64,
+) -> Result<(), FfiError> {
+    let order = byte_order(order)?;
+    background::locked(emu, || {
+        let emu = unsafe { non_null(emu, "emu")? };
+        emu.write_value(addr, size, value, order).map_err(|_| outside_dram(addr, size as usize))
+    })
+}
 
-    len as u64
+/// Read the 16-bit value at `addr` into `value`, in the byte order `order`: 0 = little-endian,
+/// as the guest stores it, 1 = big-endian. The result doesn't depend on the byte order of the
+/// host, so it can be used as is. Fails if the value isn't in DRAM or `order` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_read_u16(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: *mut u16,
+) -> RvStatus {
+    status::guard(|| {
+        let value = unsafe { non_null(value, "value")? };
+
+        *value = read_value(emu, addr, 2, order)? as u16;
+        Ok(())
+    })
+}
+
+/// Write the 16-bit `value` at `addr` in the byte order `order`, as for `emulator_read_u16`.
+/// Fails if the value doesn't fit in DRAM or `order` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_write_u16(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: u16,
+) -> RvStatus {
+    status::guard(|| write_value(emu, addr, 2, order, value as u64))
 }
 
-fn eval(context: &mut JsRuntime, code: FastString) -> Result<serde_json::Value, String> {
-    let res = context.execute_script("<anon>", code);
-    match res {
-        Ok(global) => {
-            let scope = &mut context.handle_scope();
-            let local = v8::Local::new(scope, global);
-            // Deserialize a `v8` object into a Rust type using `serde_v8`,
-            // in this case deserialize to a JSON `Value`.
-            let deserialized_value = serde_v8::from_v8::<serde_json::Value>(scope, local);
+/// Same as `emulator_read_u16`, but for a 32-bit value.
+#[no_mangle]
+pub extern "C" fn emulator_read_u32(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let value = unsafe { non_null(value, "value")? };
 
-            match deserialized_value {
-                Ok(value) => Ok(value),
-                Err(err) => Err(format!("Cannot deserialize value: {err:?}")),
-            }
+        *value = read_value(emu, addr, 4, order)? as u32;
+        Ok(())
+    })
+}
+
+/// Same as `emulator_write_u16`, but for a 32-bit value.
+#[no_mangle]
+pub extern "C" fn emulator_write_u32(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: u32,
+) -> RvStatus {
+    status::guard(|| write_value(emu, addr, 4, order, value as u64))
+}
+
+/// Same as `emulator_read_u16`, but for a 64-bit value.
+#[no_mangle]
+pub extern "C" fn emulator_read_u64(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let value = unsafe { non_null(value, "value")? };
+
+        *value = read_value(emu, addr, 8, order)?;
+        Ok(())
+    })
+}
+
+/// Same as `emulator_write_u16`, but for a 64-bit value.
+#[no_mangle]
+pub extern "C" fn emulator_write_u64(
+    emu: *mut Emulator,
+    addr: u64,
+    order: u32,
+    value: u64,
+) -> RvStatus {
+    status::guard(|| write_value(emu, addr, 8, order, value))
+}
+
+/// Copy the `count` little-endian elements at `addr` into `out`, decoding each with `decode`.
+/// Fails without copying anything if they aren't all in DRAM.
+fn read_array<T: 'static, const N: usize>(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut T,
+    count: u64,
+    decode: fn([u8; N]) -> T,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
         }
-        Err(err) => Err(format!("Evaling error: {err:?}")),
-    }
+
+        let bytes = count
+            .checked_mul(N as u64)
+            .and_then(|len| emu.dram_bytes(addr, len))
+            .ok_or_else(|| {
+                let message = format!("the {} elements at {:#x} aren't all in DRAM", count, addr);
+                FfiError::invalid(message)
+            })?;
+        let out = unsafe { std::slice::from_raw_parts_mut(out, count as usize) };
+        for (element, chunk) in out.iter_mut().zip(bytes.chunks_exact(N)) {
+            let mut raw = [0; N];
+            raw.copy_from_slice(chunk);
+            *element = decode(raw);
+        }
+        Ok(())
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use std::ptr::null;
+/// Copy the `count` little-endian `i8` elements at `addr` into `out`, a typed view of guest
+/// memory for levels that e.g. sort an array or multiply matrices. Fails if the elements aren't
+/// all in DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_i8(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut i8,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, i8::from_le_bytes)
+}
 
-    use crate::*;
+/// Same as `emulator_read_array_i8`, but for `u8` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_u8(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut u8,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, u8::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `i16` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_i16(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut i16,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, i16::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `u16` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_u16(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut u16,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, u16::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `i32` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_i32(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut i32,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, i32::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `u32` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_u32(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut u32,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, u32::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `i64` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_i64(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut i64,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, i64::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `u64` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_u64(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut u64,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, u64::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `f32` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_f32(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut f32,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, f32::from_le_bytes)
+}
+
+/// Same as `emulator_read_array_i8`, but for `f64` elements.
+#[no_mangle]
+pub extern "C" fn emulator_read_array_f64(
+    emu: *mut Emulator,
+    addr: u64,
+    out: *mut f64,
+    count: u64,
+) -> RvStatus {
+    read_array(emu, addr, out, count, f64::from_le_bytes)
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_keyboard_push_scancode(emu: *mut Emulator, code: u8) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.bus.keyboard.push_scancode(code);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_keyboard_pending(emu: *mut Emulator, pending: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let pending = unsafe { non_null(pending, "pending")? };
+
+        *pending = emu.cpu.bus.keyboard.pending() as u64;
+        Ok(())
+    })
+}
+
+/// Copy the text-mode character buffer (character and attribute byte per cell, 80x25 cells) into
+/// `out`, up to `len` bytes. Writes the number of bytes copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_text_buffer(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let buffer = emu.cpu.bus.vga_text.buffer();
+        let len = len.min(buffer.len());
+        let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+        out.copy_from_slice(&buffer[..len]);
+        *copied = len as u64;
+        Ok(())
+    })
+}
+
+/// Write 1 into `changed` if the guest wrote to the text-mode buffer since the last call,
+/// otherwise 0.
+#[no_mangle]
+pub extern "C" fn emulator_text_buffer_changed(emu: *mut Emulator, changed: *mut u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let changed = unsafe { non_null(changed, "changed")? };
+
+        *changed = emu.cpu.bus.vga_text.take_dirty() as u32;
+        Ok(())
+    })
+}
+
+/// The number of arguments of a `DrawCommand`, repeated for the C header.
+pub const DRAW_ARGS: usize = 6;
+const _: () = assert!(DRAW_ARGS == draw_queue::DRAW_ARGS);
+
+/// A renderer behind the FFI. It's called once per draw command with the user data given with
+/// it. The command is only valid during the call.
+pub type DrawFn = extern "C" fn(user_data: *mut c_void, command: *const DrawCommand);
+
+/// Remove the draw commands the guest queued since the last call and pass them to `draw`, oldest
+/// first. A null `draw` discards them. Writes the number of commands removed into `removed`.
+#[no_mangle]
+pub extern "C" fn emulator_dispatch_draw_commands(
+    emu: *mut Emulator,
+    draw: Option<DrawFn>,
+    user_data: *mut c_void,
+    removed: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let removed = unsafe { non_null(removed, "removed")? };
+
+        let commands = emu.cpu.bus.draw_queue.take();
+        if let Some(draw) = draw {
+            for command in &commands {
+                draw(user_data, command);
+            }
+        }
+        *removed = commands.len() as u64;
+        Ok(())
+    })
+}
+
+/// Write the number of draw commands dropped because the queue was full or their opcode is
+/// unknown into `dropped`.
+#[no_mangle]
+pub extern "C" fn emulator_draw_commands_dropped(
+    emu: *mut Emulator,
+    dropped: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let dropped = unsafe { non_null(dropped, "dropped")? };
+
+        *dropped = emu.cpu.bus.draw_queue.dropped() as u64;
+        Ok(())
+    })
+}
+
+/// Write the number of frames the guest presented through the vsync device into `frames`. A run
+/// stops with `StopReason::FramePresented` after each one; run again to start the next frame.
+#[no_mangle]
+pub extern "C" fn emulator_get_frame_count(emu: *mut Emulator, frames: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let frames = unsafe { non_null(frames, "frames")? };
+
+        *frames = emu.cpu.bus.vsync.frames() as u64;
+        Ok(())
+    })
+}
+
+/// Move up to `max` PCM samples the guest wrote to the audio device into `out`, oldest first.
+/// Writes the number of samples moved into `moved`. Fewer than `max` count as an underrun.
+#[no_mangle]
+pub extern "C" fn emulator_read_audio(
+    emu: *mut Emulator,
+    out: *mut i16,
+    max: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+        let moved = unsafe { non_null(moved, "moved")? };
+
+        let out = unsafe { std::slice::from_raw_parts_mut(out, max) };
+        *moved = emu.cpu.bus.audio.drain(out) as u64;
+        Ok(())
+    })
+}
+
+/// Write the number of times `emulator_read_audio` got fewer samples than it asked for into
+/// `underruns`.
+#[no_mangle]
+pub extern "C" fn emulator_audio_underruns(emu: *mut Emulator, underruns: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let underruns = unsafe { non_null(underruns, "underruns")? };
+
+        *underruns = emu.cpu.bus.audio.underruns() as u64;
+        Ok(())
+    })
+}
+
+/// Move up to `len` bytes the guest wrote to the UART into `out`, oldest first, e.g. to show
+/// them in the console window of the game. Writes the number of bytes moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_read_uart(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+        let moved = unsafe { non_null(moved, "moved")? };
+
+        let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+        *moved = emu.cpu.bus.uart.take_output(out) as u64;
+        Ok(())
+    })
+}
+
+/// Write the number of bytes the guest wrote to the UART that were dropped because
+/// `emulator_read_uart` wasn't called in time into `dropped`.
+#[no_mangle]
+pub extern "C" fn emulator_uart_output_dropped(emu: *mut Emulator, dropped: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let dropped = unsafe { non_null(dropped, "dropped")? };
+
+        *dropped = emu.cpu.bus.uart.dropped_output();
+        Ok(())
+    })
+}
+
+/// Queue the `len` bytes at `data` for the guest to receive through the UART, as if they were
+/// typed on stdin. Writes the number of bytes the guest hasn't received yet into `pending`.
+#[no_mangle]
+pub extern "C" fn emulator_write_uart_input(
+    emu: *mut Emulator,
+    data: *const u8,
+    len: usize,
+    pending: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if data.is_null() {
+            return Err(FfiError::null("data"));
+        }
+        let pending = unsafe { non_null(pending, "pending")? };
+
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        let uart = &mut emu.cpu.bus.uart;
+        uart.push_input(data);
+        *pending = uart.pending_input() as u64;
+        Ok(())
+    })
+}
+
+/// Supply the `len` bytes at `data` as the contents of the save memory, e.g. the save data of the
+/// player from the last session. The rest of the memory is zeroed. Fails if `len` is larger than
+/// the memory.
+#[no_mangle]
+pub extern "C" fn emulator_load_save_data(
+    emu: *mut Emulator,
+    data: *const u8,
+    len: usize,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if data.is_null() {
+            return Err(FfiError::null("data"));
+        }
+
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        emu.cpu.bus.save_file.load(data).map_err(FfiError::invalid)
+    })
+}
+
+/// Copy the contents of the save memory into `out`, up to `len` bytes, and write the size of the
+/// memory into `size`.
+#[no_mangle]
+pub extern "C" fn emulator_get_save_data(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    size: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let size = unsafe { non_null(size, "size")? };
+
+        let data = emu.cpu.bus.save_file.data();
+        let copied = len.min(data.len());
+        if copied > 0 {
+            if out.is_null() {
+                return Err(FfiError::null("out"));
+            }
+            let out = unsafe { std::slice::from_raw_parts_mut(out, copied) };
+            out.copy_from_slice(&data[..copied]);
+        }
+        *size = data.len() as u64;
+        Ok(())
+    })
+}
+
+/// Write 1 into `changed` if the guest wrote to the save memory since the last call, otherwise 0,
+/// so the host only persists it when needed.
+#[no_mangle]
+pub extern "C" fn emulator_save_data_changed(emu: *mut Emulator, changed: *mut u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let changed = unsafe { non_null(changed, "changed")? };
+
+        *changed = emu.cpu.bus.save_file.take_dirty() as u32;
+        Ok(())
+    })
+}
+
+/// Deliver the message `message` from the host to the message channel of `emu`. Writes 1 into
+/// `delivered`, or 0 if the inbox of the guest is full.
+#[no_mangle]
+pub extern "C" fn emulator_channel_send(
+    emu: *mut Emulator,
+    message: u32,
+    delivered: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let delivered = unsafe { non_null(delivered, "delivered")? };
+
+        *delivered = emu.cpu.bus.channel.deliver(message) as u32;
+        Ok(())
+    })
+}
+
+/// Write the oldest message the guest sent through its message channel into `message`, remove it
+/// and write 1 into `received`. Writes 0 into `received` if there's none.
+#[no_mangle]
+pub extern "C" fn emulator_channel_receive(
+    emu: *mut Emulator,
+    message: *mut u32,
+    received: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let message = unsafe { non_null(message, "message")? };
+        let received = unsafe { non_null(received, "received")? };
+
+        let sent = emu.cpu.bus.channel.take_sent();
+        if let Some(sent) = sent {
+            *message = sent;
+        }
+        *received = sent.is_some() as u32;
+        Ok(())
+    })
+}
+
+/// Connect the message channels of `a` and `b`: move the messages each one sent to the other, in
+/// order, as long as the inbox of the other has room. Call it between runs, e.g. every frame.
+/// Writes the number of messages moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_channel_exchange(
+    a: *mut Emulator,
+    b: *mut Emulator,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        if a == b {
+            handles::check(a, "a")?;
+            return Err(FfiError::invalid("`a` and `b` are the same emulator".to_string()));
+        }
+        let a = unsafe { non_null(a, "a")? };
+        let b = unsafe { non_null(b, "b")? };
+        let moved = unsafe { non_null(moved, "moved")? };
+
+        *moved = a.cpu.bus.channel.exchange(&mut b.cpu.bus.channel) as u64;
+        Ok(())
+    })
+}
+
+/// Select what advances the timer (mtime): 0 = one tick per executed instruction, 1 = the host
+/// wall-clock at `frequency` ticks per second, 2 = only `emulator_advance_timer`. Fails if `mode`
+/// is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_timer_mode(
+    emu: *mut Emulator,
+    mode: u32,
+    frequency: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let mode = match mode {
+            0 => TimerMode::Instructions,
+            1 => TimerMode::WallClock,
+            2 => TimerMode::Manual,
+            _ => return Err(FfiError::invalid(format!("unknown timer mode {}", mode))),
+        };
+        emu.cpu.bus.clint.set_mode(mode, frequency);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_advance_timer(emu: *mut Emulator, ticks: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.bus.clint.advance(ticks);
+        Ok(())
+    })
+}
+
+/// Freeze the timer, e.g. while the debugger is stopped at a breakpoint.
+#[no_mangle]
+pub extern "C" fn emulator_pause_timer(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.bus.clint.pause();
+        Ok(())
+    })
+}
+
+/// Unfreeze the timer. Host time spent paused is not seen by the guest.
+#[no_mangle]
+pub extern "C" fn emulator_resume_timer(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.bus.clint.resume();
+        Ok(())
+    })
+}
+
+/// Arm the watchdog with `timeout` cycles. `action` is 0 to raise an interrupt or 1 to reset the
+/// core on expiry. A `timeout` of 0 disables the watchdog. Fails if `action` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_watchdog(emu: *mut Emulator, timeout: u32, action: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let action = match action {
+            0 => WatchdogAction::Interrupt,
+            1 => WatchdogAction::Reset,
+            _ => return Err(FfiError::invalid(format!("unknown watchdog action {}", action))),
+        };
+        let watchdog = &mut emu.cpu.bus.watchdog;
+        if timeout == 0 {
+            watchdog.disable();
+        } else {
+            watchdog.configure(timeout, action);
+        }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_watchdog_expirations(
+    emu: *mut Emulator,
+    expirations: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let expirations = unsafe { non_null(expirations, "expirations")? };
+
+        *expirations = emu.cpu.bus.watchdog.expirations();
+        Ok(())
+    })
+}
+
+/// Allow (`allow` = 1) or forbid (`allow` = 0) executing code from the ROM and device regions.
+/// It's forbidden by default: fetching an instruction outside DRAM raises an instruction access
+/// fault, which `emulator_explain_last_trap` describes.
+#[no_mangle]
+pub extern "C" fn emulator_set_device_fetch(emu: *mut Emulator, allow: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.bus.device_fetch = allow != 0;
+        Ok(())
+    })
+}
+
+/// Stop runs before the instruction at `addr` is executed, with `StopReason::HostBreakpoint`.
+/// A run starting at a breakpoint executes the instruction. Fails if there's already a
+/// breakpoint at `addr`.
+#[no_mangle]
+pub extern "C" fn emulator_add_breakpoint(emu: *mut Emulator, addr: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        if !emu.breakpoints.add(addr) {
+            return Err(FfiError::invalid(format!("there's a breakpoint at {:#x}", addr)));
+        }
+        Ok(())
+    })
+}
+
+/// Remove a breakpoint added by `emulator_add_breakpoint`. Fails if it doesn't exist.
+#[no_mangle]
+pub extern "C" fn emulator_remove_breakpoint(emu: *mut Emulator, addr: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        if !emu.breakpoints.remove(addr) {
+            return Err(FfiError::invalid(format!("there's no breakpoint at {:#x}", addr)));
+        }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_clear_breakpoints(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.breakpoints.clear();
+        Ok(())
+    })
+}
+
+/// Replace the instruction at `addr` with `ebreak`, or `c.ebreak` if it's compressed, for a
+/// software breakpoint: a run reaching it stops with `StopReason::Breakpoint`. Reading memory
+/// still shows the original instruction. Fails if `addr` isn't in DRAM or already has an
+/// injected instruction.
+#[no_mangle]
+pub extern "C" fn emulator_inject_ebreak(emu: *mut Emulator, addr: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        injection::inject_ebreak(emu, addr).map_err(FfiError::invalid)
+    })
+}
+
+/// Replace the `len` bytes at `addr` with a NOP sled, e.g. to skip over a part of the program.
+/// Reading memory still shows the original instructions. Fails if `len` is 0 or odd, or the bytes
+/// aren't in DRAM or overlap an injected instruction.
+#[no_mangle]
+pub extern "C" fn emulator_inject_nops(emu: *mut Emulator, addr: u64, len: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        injection::inject_nops(emu, addr, len).map_err(FfiError::invalid)
+    })
+}
+
+/// Put back the instructions replaced by the injection at `addr`, e.g. to step over a software
+/// breakpoint before injecting it again. Fails if nothing was injected at `addr`.
+#[no_mangle]
+pub extern "C" fn emulator_restore_injection(emu: *mut Emulator, addr: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        injection::restore(emu, addr).map_err(FfiError::invalid)
+    })
+}
+
+/// Put back the instructions replaced by every injection.
+#[no_mangle]
+pub extern "C" fn emulator_restore_injections(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        injection::restore_all(emu);
+        Ok(())
+    })
+}
+
+/// Return the watchpoint kind with the code `kind`.
+fn watch_kind(kind: u32) -> Result<WatchKind, FfiError> {
+    WatchKind::from_code(kind)
+        .ok_or_else(|| FfiError::invalid(format!("{} isn't a watchpoint kind", kind)))
+}
+
+/// Stop runs right after the guest accesses `[addr, addr + len)` with `StopReason::Watchpoint`
+/// and the accessed address as the trap value. `kind` 1 watches loads, 2 stores and 3 both;
+/// `emulator_get_watch_hit` tells which one stopped the run. Fails if `kind` is unknown, or the
+/// range is empty or already watched for `kind`.
+#[no_mangle]
+pub extern "C" fn emulator_add_watchpoint(
+    emu: *mut Emulator,
+    addr: u64,
+    len: u64,
+    kind: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let kind = watch_kind(kind)?;
+
+        if !emu.cpu.watchpoints.add(addr, len, kind) {
+            let end = addr.saturating_add(len);
+            let message = format!("{:#x}..{:#x} is empty or already watched", addr, end);
+            return Err(FfiError::invalid(message));
+        }
+        Ok(())
+    })
+}
+
+/// Remove a watchpoint added by `emulator_add_watchpoint` with the same arguments. Fails if it
+/// doesn't exist.
+#[no_mangle]
+pub extern "C" fn emulator_remove_watchpoint(
+    emu: *mut Emulator,
+    addr: u64,
+    len: u64,
+    kind: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let kind = watch_kind(kind)?;
+
+        if !emu.cpu.watchpoints.remove(addr, len, kind) {
+            let end = addr.saturating_add(len);
+            return Err(FfiError::invalid(format!("{:#x}..{:#x} isn't watched", addr, end)));
+        }
+        Ok(())
+    })
+}
+
+/// Stop runs right after the guest stores to `[addr, addr + len)`, with
+/// `StopReason::Watchpoint`. Fails if the range is empty or already watched. Same as
+/// `emulator_add_watchpoint` with `kind` 2.
+#[no_mangle]
+pub extern "C" fn emulator_add_write_watchpoint(
+    emu: *mut Emulator,
+    addr: u64,
+    len: u64,
+) -> RvStatus {
+    emulator_add_watchpoint(emu, addr, len, WatchKind::Write as u32)
+}
+
+/// Remove a watchpoint added by `emulator_add_write_watchpoint`. Fails if it doesn't exist.
+#[no_mangle]
+pub extern "C" fn emulator_remove_write_watchpoint(
+    emu: *mut Emulator,
+    addr: u64,
+    len: u64,
+) -> RvStatus {
+    emulator_remove_watchpoint(emu, addr, len, WatchKind::Write as u32)
+}
+
+/// Write the access that stopped the last run at a watchpoint into `hit`: the address of the
+/// instruction, the address and size of the access, the values before and after it, and whether
+/// it was a load or a store. The value before a store to a device is unknown, as reading it can
+/// have side effects. Fails if no run stopped at a watchpoint yet.
+#[no_mangle]
+pub extern "C" fn emulator_get_watch_hit(emu: *mut Emulator, hit: *mut WatchHit) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let hit = unsafe { non_null(hit, "hit")? };
+
+        *hit = emu.last_watch_hit.ok_or_else(|| {
+            FfiError::invalid("no run stopped at a watchpoint yet".to_string())
+        })?;
+        Ok(())
+    })
+}
+
+/// Keep the last `capacity` executed instructions in a ring buffer, e.g. to see how a program got
+/// to a crash without stepping it one instruction at a time. 0 disables the trace. It keeps the
+/// last 32 by default; shrinking it drops the oldest entries.
+#[no_mangle]
+pub extern "C" fn emulator_enable_trace(emu: *mut Emulator, capacity: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.trace.set_capacity(capacity as usize);
+        Ok(())
+    })
+}
+
+/// Copy the newest `len` traced instructions, oldest first, into `out`. Writes the number of
+/// entries copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_trace(
+    emu: *mut Emulator,
+    out: *mut TraceEntry,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let skipped = emu.trace.len().saturating_sub(len);
+        let entries = emu.trace.entries().skip(skipped).copied().collect::<Vec<TraceEntry>>();
+        copy_entries(&entries, out, copied)
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) recording loads and stores to
+/// `[addr, addr + len)`, e.g. to check that a program writes each element of its output array
+/// exactly once.
+#[no_mangle]
+pub extern "C" fn emulator_trace_region(
+    emu: *mut Emulator,
+    addr: u64,
+    len: u64,
+    enable: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.access_trace.set_region(addr, len, enable != 0);
+        Ok(())
+    })
+}
+
+/// Write the number of recorded memory accesses into `len`.
+#[no_mangle]
+pub extern "C" fn emulator_access_trace_len(emu: *mut Emulator, len: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let len = unsafe { non_null(len, "len")? };
+
+        *len = emu.cpu.access_trace.entries().len() as u64;
+        Ok(())
+    })
+}
+
+/// Copy up to `len` recorded memory accesses, oldest first, into `out`. Writes the number of
+/// entries copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_access_trace(
+    emu: *mut Emulator,
+    out: *mut MemoryAccess,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let entries = emu.cpu.access_trace.entries();
+        copy_entries(&entries[..len.min(entries.len())], out, copied)
+    })
+}
+
+/// Forget the recorded memory accesses. The traced regions are kept.
+#[no_mangle]
+pub extern "C" fn emulator_clear_access_trace(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.access_trace.clear();
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) logging the atomic instructions (`lr`, `sc` and
+/// the AMOs) with the values in memory before and after them.
+#[no_mangle]
+pub extern "C" fn emulator_enable_atomic_events(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.atomic_events.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Write the number of logged atomic instructions into `len`.
+#[no_mangle]
+pub extern "C" fn emulator_atomic_events_len(emu: *mut Emulator, len: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let len = unsafe { non_null(len, "len")? };
+
+        *len = emu.atomic_events.events().len() as u64;
+        Ok(())
+    })
+}
+
+/// Move up to `len` logged atomic instructions, oldest first, into `out` and forget them, e.g.
+/// once per frame. The events that don't fit are kept for the next call. Writes the number of
+/// events moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_take_atomic_events(
+    emu: *mut Emulator,
+    out: *mut AtomicEvent,
+    len: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let moved = unsafe { non_null(moved, "moved")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+
+        copy_entries(&emu.atomic_events.take_oldest(len), out, moved)
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) logging the writes to CSRs, by instructions and by
+/// traps, with the values before and after them.
+#[no_mangle]
+pub extern "C" fn emulator_enable_csr_trace(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.csr_trace.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Write the number of logged CSR writes into `len`.
+#[no_mangle]
+pub extern "C" fn emulator_csr_trace_len(emu: *mut Emulator, len: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let len = unsafe { non_null(len, "len")? };
+
+        *len = emu.csr_trace.writes().len() as u64;
+        Ok(())
+    })
+}
+
+/// Move up to `len` logged CSR writes, oldest first, into `out` and forget them. The writes that
+/// don't fit are kept for the next call. Writes the number of writes moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_take_csr_writes(
+    emu: *mut Emulator,
+    out: *mut CsrWrite,
+    len: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let moved = unsafe { non_null(moved, "moved")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+
+        copy_entries(&emu.csr_trace.take_oldest(len), out, moved)
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) serving the debug print environment calls: an
+/// `ecall` with a7 = 0x7e0 prints a0 in decimal, 0x7e1 in hexadecimal, and 0x7e2 prints the
+/// string at the address in a0. The program continues after the `ecall` instead of yielding.
+#[no_mangle]
+pub extern "C" fn emulator_enable_debug_print(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.debug_prints.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Copy the debug prints of the guest into `out` as a JSON array of `{"pc", "kind", "value",
+/// "text", "line"}` objects, oldest first. `kind` is "int", "hex" or "text", `text` is null
+/// unless it's a string in DRAM, and `line` is the print as shown in the log. Writes the full
+/// length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_get_debug_prints(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let prints = emu
+            .debug_prints
+            .prints()
+            .iter()
+            .map(|print| {
+                serde_json::json!({
+                    "pc": print.pc,
+                    "kind": print.kind.name(),
+                    "value": print.value,
+                    "text": print.text,
+                    "line": print.render(),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(prints).to_string(), out, len, full_len)
+    })
+}
+
+/// Forget the debug prints, e.g. once they are shown.
+#[no_mangle]
+pub extern "C" fn emulator_clear_debug_prints(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.debug_prints.clear();
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) serving the guest asserts: an `ecall` with
+/// a7 = 0x7e3 asserts that a0 isn't zero, with the address of a NUL-terminated message in a1, or
+/// 0, and the line number in a2. A passing assert continues after the `ecall`; a failing one
+/// stops the run with `StopReason::GuestAssertionFailed` and the line number in `tval`.
+#[no_mangle]
+pub extern "C" fn emulator_enable_guest_asserts(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.guest_asserts.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Copy the results of the guest asserts into `out` as a JSON object `{"passed", "failed",
+/// "failure"}`. `failure` is null unless an assert failed, else the latest failure as
+/// `{"pc", "line", "message", "text"}`, where `message` is null if the guest passed none and
+/// `text` is the failure as shown to the learner. Writes the full length of the JSON into
+/// `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_get_guest_asserts(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let asserts = &emu.guest_asserts;
+        let failure = asserts.last_failure().map(|failure| {
+            serde_json::json!({
+                "pc": failure.pc,
+                "line": failure.line,
+                "message": failure.message,
+                "text": failure.render(),
+            })
+        });
+        let json = serde_json::json!({
+            "passed": asserts.passed(),
+            "failed": asserts.failed(),
+            "failure": failure,
+        });
+        write_string(&json.to_string(), out, len, full_len)
+    })
+}
+
+/// Forget the results of the guest asserts, e.g. before the learner runs the program again.
+#[no_mangle]
+pub extern "C" fn emulator_clear_guest_asserts(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.guest_asserts.clear();
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) serving the RISC-V semihosting calls of the
+/// guest: an `ebreak` between `slli zero, zero, 0x1f` and `srai zero, zero, 7` with the
+/// operation in a0. `SYS_WRITE0` prints to the console, `SYS_OPEN` opens ":tt", `SYS_READ` reads
+/// the input from `emulator_write_semihosting_input`, and `SYS_EXIT` stops the run with
+/// `StopReason::Exited`. Other operations fail with -1 in a0.
+#[no_mangle]
+pub extern "C" fn emulator_enable_semihosting(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.semihosting.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Move up to `len` bytes the guest printed through semihosting into `out`, oldest first.
+/// Writes the number of bytes moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_read_semihosting_output(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+        let moved = unsafe { non_null(moved, "moved")? };
+
+        let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+        *moved = emu.semihosting.take_output(out) as u64;
+        Ok(())
+    })
+}
+
+/// Write the number of bytes the guest printed through semihosting that were dropped because
+/// `emulator_read_semihosting_output` wasn't called in time into `dropped`.
+#[no_mangle]
+pub extern "C" fn emulator_semihosting_output_dropped(
+    emu: *mut Emulator,
+    dropped: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let dropped = unsafe { non_null(dropped, "dropped")? };
+
+        *dropped = emu.semihosting.dropped_output();
+        Ok(())
+    })
+}
+
+/// Queue the `len` bytes at `data` for the guest to read from the console with `SYS_READ`.
+/// Writes the number of bytes the guest hasn't read yet into `pending`.
+#[no_mangle]
+pub extern "C" fn emulator_write_semihosting_input(
+    emu: *mut Emulator,
+    data: *const u8,
+    len: usize,
+    pending: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        if data.is_null() {
+            return Err(FfiError::null("data"));
+        }
+        let pending = unsafe { non_null(pending, "pending")? };
+
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        emu.semihosting.push_input(data);
+        *pending = emu.semihosting.pending_input() as u64;
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) logging the privilege-return instructions
+/// (`mret`, `sret` and `uret`) with the privilege levels before and after them.
+#[no_mangle]
+pub extern "C" fn emulator_enable_trap_returns(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.trap_returns.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Write the number of logged trap returns into `len`.
+#[no_mangle]
+pub extern "C" fn emulator_trap_returns_len(emu: *mut Emulator, len: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let len = unsafe { non_null(len, "len")? };
+
+        *len = emu.trap_returns.events().len() as u64;
+        Ok(())
+    })
+}
+
+/// Move up to `len` logged trap returns, oldest first, into `out` and forget them. The events
+/// that don't fit are kept for the next call. Writes the number of events moved into `moved`.
+#[no_mangle]
+pub extern "C" fn emulator_take_trap_returns(
+    emu: *mut Emulator,
+    out: *mut TrapReturn,
+    len: usize,
+    moved: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let moved = unsafe { non_null(moved, "moved")? };
+        if out.is_null() {
+            return Err(FfiError::null("out"));
+        }
+
+        copy_entries(&emu.trap_returns.take_oldest(len), out, moved)
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) logging executed instructions whose encodings are
+/// hints or reserved.
+#[no_mangle]
+pub extern "C" fn emulator_enable_encoding_audit(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.encoding_audit.set_logging(enable != 0);
+        Ok(())
+    })
+}
+
+/// Set what the CPU does with the encodings of `kind` (0 = hints, 1 = reserved): `policy` is 0 to
+/// execute them as it always did, or 1 to raise an illegal instruction exception. Fails if
+/// `kind` or `policy` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_encoding_policy(
+    emu: *mut Emulator,
+    kind: u32,
+    policy: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let kind = match kind {
+            0 => EncodingKind::Hint,
+            1 => EncodingKind::Reserved,
+            _ => return Err(FfiError::invalid(format!("unknown encoding kind {}", kind))),
+        };
+        let policy = match policy {
+            0 => EncodingPolicy::Execute,
+            1 => EncodingPolicy::Trap,
+            _ => return Err(FfiError::invalid(format!("unknown encoding policy {}", policy))),
+        };
+        emu.cpu.encoding_audit.set_policy(kind, policy);
+        Ok(())
+    })
+}
+
+/// Copy up to `len` logged hint and reserved instructions, in the order they were first
+/// executed, into `out`. Each instruction is logged once with its execution count. Writes the
+/// number of entries copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_encoding_events(
+    emu: *mut Emulator,
+    out: *mut EncodingEvent,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let events = emu.cpu.encoding_audit.events();
+        copy_entries(&events[..len.min(events.len())], out, copied)
+    })
+}
+
+/// Forget the logged hint and reserved instructions. The policies are kept.
+#[no_mangle]
+pub extern "C" fn emulator_clear_encoding_events(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.encoding_audit.clear();
+        Ok(())
+    })
+}
+
+/// Write the rule of the specification that makes `inst` a hint or a reserved encoding into
+/// `out` (not NUL-terminated), e.g. "c.lui with a zero immediate", and its full length into
+/// `full_len`, 0 if `inst` is an ordinary instruction.
+#[no_mangle]
+pub extern "C" fn riscv_encoding_rule(
+    inst: u64,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let rule = encoding_audit::classify(inst).map_or("", |class| class.rule);
+        write_string(rule, out, len, full_len)
+    })
+}
+
+/// Select how single-precision values are held in the floating-point registers: 0 = as the
+/// doubles they convert to (legacy), 1 = NaN-boxed as the specification requires, where
+/// single-precision instructions read improperly boxed registers as the canonical NaN. Fails if
+/// `mode` is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_nan_boxing(emu: *mut Emulator, mode: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.nan_boxing = match mode {
+            0 => NanBoxing::Legacy,
+            1 => NanBoxing::Strict,
+            _ => return Err(FfiError::invalid(format!("unknown NaN-boxing mode {}", mode))),
+        };
+        Ok(())
+    })
+}
+
+/// Select the width of the integer registers, `xlen` = 32 or 64 (the default), so the emulator
+/// runs programs assembled for RV32I with its semantics: the registers are 32 bits wide, the
+/// program counter and the addresses wrap around at 4 GiB, the instructions only RV64 has are
+/// illegal, and the MXL field of misa says RV32. The registers are truncated to the width.
+#[no_mangle]
+pub extern "C" fn emulator_set_xlen(emu: *mut Emulator, xlen: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let xlen = Xlen::from_bits(xlen)
+            .ok_or_else(|| FfiError::invalid(format!("XLEN {} isn't 32 or 64", xlen)))?;
+
+        emu.cpu.set_xlen(xlen);
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) logging single-precision reads of registers that
+/// aren't properly NaN-boxed. Only strict NaN-boxing checks the boxes.
+#[no_mangle]
+pub extern "C" fn emulator_enable_nan_box_diagnostics(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.nan_box_diagnostics.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Copy up to `len` logged unboxed reads, in the order they first happened, into `out`. Each
+/// instruction and register is logged once with its execution count. Writes the number of
+/// entries copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_unboxed_reads(
+    emu: *mut Emulator,
+    out: *mut UnboxedRead,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let reads = emu.cpu.nan_box_diagnostics.reads();
+        copy_entries(&reads[..len.min(reads.len())], out, copied)
+    })
+}
+
+/// Forget the logged unboxed reads.
+#[no_mangle]
+pub extern "C" fn emulator_clear_unboxed_reads(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.nan_box_diagnostics.clear();
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (`enable` = 0) counting loads and stores by the address of the
+/// instruction that made them.
+#[no_mangle]
+pub extern "C" fn emulator_enable_memory_stats(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.memory_stats.set_enabled(enable != 0);
+        Ok(())
+    })
+}
+
+/// Count cache hits and misses of loads and stores with a direct-mapped cache of `lines` lines
+/// of `line_size` bytes. A size of 0 removes the cache model. Fails if `line_size` isn't a power
+/// of two or `lines` is more than 2^20.
+#[no_mangle]
+pub extern "C" fn emulator_set_cache_model(
+    emu: *mut Emulator,
+    line_size: u64,
+    lines: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        emu.cpu
+            .memory_stats
+            .set_cache(line_size, lines)
+            .map_err(FfiError::invalid)
+    })
+}
+
+/// Copy the statistics of up to `len` instructions with the most loads and stores, the hottest
+/// first, into `out`. Writes the number of entries copied into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_hot_memory_sites(
+    emu: *mut Emulator,
+    out: *mut SiteStats,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        copy_entries(&emu.cpu.memory_stats.hottest(len), out, copied)
+    })
+}
+
+/// Forget the load/store statistics and empty the cache model.
+#[no_mangle]
+pub extern "C" fn emulator_clear_memory_stats(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.memory_stats.clear();
+        Ok(())
+    })
+}
+
+/// Configure the core for a kind of guest program in one call: 0 = machine mode with every
+/// instruction allowed (the default), 1 = the user-mode sandbox for untrusted programs, where CSR
+/// and privileged instructions fault and every `ecall` returns to the host. Fails if `profile`
+/// is unknown.
+#[no_mangle]
+pub extern "C" fn emulator_set_profile(emu: *mut Emulator, profile: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let profile = match profile {
+            0 => Profile::Machine,
+            1 => Profile::UserSandbox,
+            _ => return Err(FfiError::invalid(format!("unknown profile {}", profile))),
+        };
+        emu.set_profile(profile);
+        Ok(())
+    })
+}
+
+/// Configure the physical memory protection entry `index` (0-15). `cfg` is the pmpcfg byte
+/// (R = 1, W = 2, X = 4, A = 0x18, L = 0x80) and `addr` is the pmpaddr value, e.g. from
+/// `emulator_pmp_napot`. Fails if the index is out of range or the entry is locked.
+#[no_mangle]
+pub extern "C" fn emulator_set_pmp_entry(
+    emu: *mut Emulator,
+    index: u32,
+    cfg: u8,
+    addr: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        if !pmp::set_entry(&mut emu.cpu.state, index as usize, cfg, addr) {
+            let message = format!("PMP entry {} is out of range or locked", index);
+            return Err(FfiError::invalid(message));
+        }
+        Ok(())
+    })
+}
+
+/// Read the configuration and the address of the physical memory protection entry `index`.
+/// Fails if the index is out of range.
+#[no_mangle]
+pub extern "C" fn emulator_get_pmp_entry(
+    emu: *mut Emulator,
+    index: u32,
+    cfg: *mut u8,
+    addr: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let cfg = unsafe { non_null(cfg, "cfg")? };
+        let addr = unsafe { non_null(addr, "addr")? };
+
+        if index as usize >= pmp::PMP_ENTRIES {
+            return Err(FfiError::invalid(format!("PMP entry {} is out of range", index)));
+        }
+        *cfg = pmp::cfg(&emu.cpu.state, index as usize);
+        *addr = pmp::addr(&emu.cpu.state, index as usize);
+        Ok(())
+    })
+}
+
+/// Write the pmpaddr value of a naturally aligned power-of-two region into `addr`. Fails unless
+/// `size` is a power of two of at least 8 bytes and `base` is aligned to it.
+#[no_mangle]
+pub extern "C" fn emulator_pmp_napot(base: u64, size: u64, addr: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let addr = unsafe { non_null(addr, "addr")? };
+
+        if !size.is_power_of_two() || size < 8 || !base.is_multiple_of(size) {
+            let message = format!("{:#x} bytes at {:#x} isn't a NAPOT region", size, base);
+            return Err(FfiError::invalid(message));
+        }
+        *addr = pmp::napot_addr(base, size);
+        Ok(())
+    })
+}
+
+/// Make `name` a friendly alias of the register `index` (0-31), e.g. `score` for a0 (10). The
+/// assembler of `emulator_assemble` accepts it as an operand and crash reports list it. Fails if
+/// `name` isn't an identifier, is a register name itself, or `index` is out of range.
+#[no_mangle]
+pub extern "C" fn emulator_add_register_alias(
+    emu: *mut Emulator,
+    name: *const c_char,
+    index: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let name = string_arg(name, "name")?;
+
+        if !emu.register_aliases.add(&name, index) {
+            let message = format!("`{}` can't be an alias of register {}", name, index);
+            return Err(FfiError::invalid(message));
+        }
+        Ok(())
+    })
+}
+
+/// Remove all register aliases, e.g. before another level is loaded.
+#[no_mangle]
+pub extern "C" fn emulator_clear_register_aliases(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.register_aliases.clear();
+        Ok(())
+    })
+}
+
+/// Name the custom CSR at `addr` (0-0xfff) `name` for the level. The assembler of
+/// `emulator_assemble` accepts it in CSR instructions and `emulator_disassemble` shows it.
+/// Fails if `name` isn't an identifier or is a standard CSR name, or if `addr` is out of range
+/// or is a standard CSR.
+#[no_mangle]
+pub extern "C" fn emulator_register_csr(
+    emu: *mut Emulator,
+    name: *const c_char,
+    addr: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let name = string_arg(name, "name")?;
+
+        if !emu.csr_names.register(&name, addr) {
+            let message = format!("`{}` can't name the CSR {:#x}", name, addr);
+            return Err(FfiError::invalid(message));
+        }
+        Ok(())
+    })
+}
+
+/// Forget the custom CSR names, e.g. before another level is loaded.
+#[no_mangle]
+pub extern "C" fn emulator_clear_custom_csrs(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.csr_names.clear();
+        Ok(())
+    })
+}
+
+/// Return the CSR address `addr` as a `CsrAddress`, or an error if it's out of range.
+fn csr_address(addr: u64) -> Result<u16, FfiError> {
+    match addr {
+        0..=0xfff => Ok(addr as u16),
+        _ => Err(FfiError::invalid(format!("{:#x} isn't a CSR address", addr))),
+    }
+}
+
+/// Write the value of the CSR at `addr` (0-0xfff) into `value`, as the guest would read it with
+/// `csrr` in machine mode.
+#[no_mangle]
+pub extern "C" fn emulator_get_csr(emu: *mut Emulator, addr: u64, value: *mut u64) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+            let value = unsafe { non_null(value, "value")? };
+
+            *value = emu.cpu.state.read(csr_address(addr)?);
+            Ok(())
+        })
+    })
+}
+
+/// Write `value` to the CSR at `addr` (0-0xfff) as the guest would with `csrw` in machine mode.
+/// Read-only CSRs and bits keep their values.
+#[no_mangle]
+pub extern "C" fn emulator_set_csr(emu: *mut Emulator, addr: u64, value: u64) -> RvStatus {
+    background::locked(emu, || {
+        status::guard(|| {
+            let emu = unsafe { non_null(emu, "emu")? };
+
+            emu.cpu.write_csr(csr_address(addr)?, value);
+            Ok(())
+        })
+    })
+}
+
+/// Copy the CSRs the UI can show into `out` as JSON: a list of `{"name", "addr"}` objects of the
+/// standard CSRs and the custom CSRs of the level, ordered by address. Writes the full length of
+/// the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_list_csrs(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let json = emu
+            .csr_names
+            .all()
+            .iter()
+            .map(|(name, addr)| serde_json::json!({"name": name, "addr": addr}))
+            .collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(json).to_string(), out, len, full_len)
+    })
+}
+
+/// Copy the disassembly of the instruction word `inst` at `pc` into `out`, with CSRs shown by
+/// their standard or custom names and registers by their aliases, if any. Writes the full length
+/// of the disassembly into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_disassemble(
+    emu: *mut Emulator,
+    inst: u64,
+    pc: u64,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let disassembly = disassemble_with_names(inst, pc, &emu.csr_names, &emu.register_aliases);
+        write_string(&disassembly, out, len, full_len)
+    })
+}
+
+/// Add a symbol of the loaded program, used to describe addresses in explanations.
+#[no_mangle]
+pub extern "C" fn emulator_add_symbol(
+    emu: *mut Emulator,
+    name: *const c_char,
+    addr: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let name = string_arg(name, "name")?;
+
+        emu.debug_info.add_symbol(&name, addr);
+        Ok(())
+    })
+}
+
+/// Record that the instruction at `addr` was assembled from the 1-based source line `line`.
+#[no_mangle]
+pub extern "C" fn emulator_add_source_line(emu: *mut Emulator, addr: u64, line: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.debug_info.add_line(addr, line);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_clear_debug_info(emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.debug_info.clear();
+        Ok(())
+    })
+}
+
+/// Load the program `bytes` named `name` at `base` next to the programs already loaded, starting
+/// at `entry`. Programs are numbered from 0 in load order. Fails if the name is taken or the
+/// program overlaps another one or doesn't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_load_program_at(
+    emu: *mut Emulator,
+    name: *const c_char,
+    bytes: *const u8,
+    len: usize,
+    base: u64,
+    entry: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let name = string_arg(name, "name")?;
+        if bytes.is_null() {
+            return Err(FfiError::null("bytes"));
+        }
+
+        let image = unsafe { std::slice::from_raw_parts(bytes, len) };
+        emu.load_program_at(&name, base, image, entry).map_err(FfiError::invalid)?;
+        Ok(())
+    })
+}
+
+/// Return the error of the loaded program `program` that doesn't exist.
+fn no_program(program: u64) -> FfiError {
+    FfiError::invalid(format!("there's no program {}", program))
+}
+
+/// Add a symbol to the symbol table of the loaded program `program`. Fails if there's no such
+/// program.
+#[no_mangle]
+pub extern "C" fn emulator_add_program_symbol(
+    emu: *mut Emulator,
+    program: u64,
+    name: *const c_char,
+    addr: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let name = string_arg(name, "name")?;
+
+        let loaded = emu.programs.get_mut(program as usize).ok_or_else(|| no_program(program))?;
+        loaded.debug_info.add_symbol(&name, addr);
+        Ok(())
+    })
+}
+
+/// Same as `emulator_add_source_line`, but for the loaded program `program`. Fails if there's no
+/// such program.
+#[no_mangle]
+pub extern "C" fn emulator_add_program_source_line(
+    emu: *mut Emulator,
+    program: u64,
+    addr: u64,
+    line: u32,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let loaded = emu.programs.get_mut(program as usize).ok_or_else(|| no_program(program))?;
+        loaded.debug_info.add_line(addr, line);
+        Ok(())
+    })
+}
+
+/// Write the jump table of the loaded programs at `addr`: calling `addr + 8 * i` calls the entry
+/// point of program `i`. Writes the size of the table into `size`. Fails if it doesn't fit in
+/// DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_write_jump_table(
+    emu: *mut Emulator,
+    addr: u64,
+    size: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let size = unsafe { non_null(size, "size")? };
+
+        *size = emu.write_jump_table(addr).map_err(FfiError::invalid)?;
+        Ok(())
+    })
+}
+
+/// Start the loaded program `program` from its entry point. Fails if there's no such program.
+#[no_mangle]
+pub extern "C" fn emulator_launch_program(emu: *mut Emulator, program: u64) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        if !emu.launch(program as usize) {
+            return Err(no_program(program));
+        }
+        Ok(())
+    })
+}
+
+/// Copy a UTF-8 string into `out`, truncated to `len` bytes, or nothing if `out` is null. Returns
+/// the full length of the string, so the caller can retry with a larger buffer.
+fn copy_string(string: &str, out: *mut u8, len: usize) -> u64 {
+    let bytes = string.as_bytes();
+    let copied = len.min(bytes.len());
+
+    if copied > 0 && !out.is_null() {
+        let out = unsafe { std::slice::from_raw_parts_mut(out, copied) };
+        out.copy_from_slice(&bytes[..copied]);
+    }
+
+    bytes.len() as u64
+}
+
+/// Return the NUL-terminated string argument `ptr` named `name`, with invalid UTF-8 replaced, or
+/// an error if it's null.
+fn string_arg(ptr: *const c_char, name: &str) -> Result<String, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::null(name));
+    }
+    Ok(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+}
+
+/// Copy `string` into `out` as `copy_string` does and write its full length into `full_len`.
+fn write_string(
+    string: &str,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> Result<(), FfiError> {
+    let full_len = unsafe { non_null(full_len, "full_len")? };
+    *full_len = copy_string(string, out, len);
+    Ok(())
+}
+
+/// Write a beginner-friendly explanation of a trap into `out` as UTF-8 (not NUL-terminated).
+/// `cause` and `tval` are the mcause and mtval values and `pc` is the address of the trapping
+/// instruction. Writes the full length of the explanation into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_explain_trap(
+    emu: *mut Emulator,
+    cause: u64,
+    tval: u64,
+    pc: u64,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        write_string(&emu.explain_trap(cause, tval, pc), out, len, full_len)
+    })
+}
+
+/// Same as `emulator_explain_trap` for the last exception raised by `emulator_cpu_execute`.
+/// Writes 0 into `full_len` if no exception was raised yet.
+#[no_mangle]
+pub extern "C" fn emulator_explain_last_trap(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let explanation = match emu.last_trap {
+            Some(trap) => emu.explain_trap(trap.cause, trap.tval, trap.pc),
+            None => String::new(),
+        };
+        write_string(&explanation, out, len, full_len)
+    })
+}
+
+/// Write a JSON crash report into `out` (not NUL-terminated). It bundles the last trap with its
+/// explanation, a backtrace, the registers, the trap CSRs, and the recently executed instructions,
+/// each mapped to source lines and symbols when they are known. Writes the full length of the
+/// report into `full_len`, so the caller can retry with a larger buffer.
+#[no_mangle]
+pub extern "C" fn emulator_generate_crash_report(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        write_string(&crash_report::generate(emu).to_string(), out, len, full_len)
+    })
+}
+
+/// Copy the fields of the CSR at `addr` (mstatus, sstatus, mie, mip, sie or sip) into `out` as
+/// JSON: a list of `{"name", "lsb", "width", "value"}` objects from the lowest bit up, e.g.
+/// `{"name": "MPP", "lsb": 11, "width": 2, "value": 3}`. Writes the full length of the JSON into
+/// `full_len`. Fails if the CSR can't be decoded.
+#[no_mangle]
+pub extern "C" fn emulator_decode_csr(
+    emu: *mut Emulator,
+    addr: u16,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let value = emu.cpu.state.read(addr);
+        let fields = csr_view::decode(addr, value)
+            .ok_or_else(|| FfiError::invalid(format!("the CSR {:#x} can't be decoded", addr)))?;
+        let json = fields
+            .iter()
+            .map(|field| {
+                serde_json::json!({
+                    "name": field.name,
+                    "lsb": field.lsb,
+                    "width": field.width,
+                    "value": field.value,
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(json).to_string(), out, len, full_len)
+    })
+}
+
+/// Copy what gates each interrupt into `out`, from the highest priority to the lowest: whether
+/// it's pending in mip, enabled in mie, globally enabled in the current mode, delegated, and
+/// taken before the next instruction. Writes the number of gates copied, at most `len` and 6,
+/// into `copied`.
+#[no_mangle]
+pub extern "C" fn emulator_get_interrupt_gates(
+    emu: *mut Emulator,
+    out: *mut InterruptGate,
+    len: usize,
+    copied: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let copied = unsafe { non_null(copied, "copied")? };
+
+        let gates = csr_view::interrupt_gates(&emu.cpu);
+        copy_entries(&gates[..len.min(gates.len())], out, copied)
+    })
+}
+
+/// Decode, without executing them, up to `len` of the next instructions into `out`, in the order
+/// they would execute if no branch were taken. Branches carry their taken target so the UI can
+/// draw both paths. Writes the number of instructions decoded into `decoded`, fewer than `len` if
+/// the preview reaches an `ecall`, a trap return or an address outside DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_peek_next(
+    emu: *mut Emulator,
+    out: *mut PreviewedInstruction,
+    len: usize,
+    decoded: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let decoded = unsafe { non_null(decoded, "decoded")? };
+
+        copy_entries(&preview::peek_next(emu, len), out, decoded)
+    })
+}
+
+/// Execute one step like `emulator_step`, write its summary into `summary`, and record its
+/// complete architectural effect for `emulator_get_last_delta`.
+#[no_mangle]
+pub extern "C" fn emulator_step_delta(emu: *mut Emulator, summary: *mut RunSummary) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = emu.step_delta();
+        Ok(())
+    })
+}
+
+/// Copy the architectural effect of the last `emulator_step_delta` into `out` as JSON: `pc`,
+/// `inst`, `next_pc`, `mode`, `reason`, `cause` and `tval`, the changed `xregs`, `fregs` and
+/// `csrs` as `{"index", "old", "new"}` objects, and the stores in `memory` as
+/// `{"addr", "size", "value"}` objects. Writes the full length of the JSON into `full_len`, or 0
+/// if no step was recorded.
+#[no_mangle]
+pub extern "C" fn emulator_get_last_delta(
+    emu: *mut Emulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let json = emu.last_delta.as_ref().map(|delta| delta_json(delta).to_string());
+        write_string(json.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Return the JSON object of `emulator_get_last_delta` for `delta`.
+fn delta_json(delta: &StepDelta) -> serde_json::Value {
+    let registers = |changes: &[RegisterChange]| {
+        changes
+            .iter()
+            .map(|change| {
+                serde_json::json!({"index": change.index, "old": change.old, "new": change.new})
+            })
+            .collect::<Vec<serde_json::Value>>()
+    };
+    let memory = delta
+        .memory
+        .iter()
+        .map(|store| {
+            serde_json::json!({"addr": store.addr, "size": store.size, "value": store.value})
+        })
+        .collect::<Vec<serde_json::Value>>();
+    serde_json::json!({
+        "pc": delta.pc,
+        "inst": delta.inst,
+        "next_pc": delta.next_pc,
+        "mode": delta.mode as u64,
+        "reason": delta.reason as u32,
+        "cause": delta.cause,
+        "tval": delta.tval,
+        "xregs": registers(&delta.xregs),
+        "fregs": registers(&delta.fregs),
+        "csrs": registers(&delta.csrs),
+        "memory": memory,
+    })
+}
+
+/// Simulate up to `steps` steps on a throwaway copy of the state of `emu` and copy what each one
+/// would do into `out` as a JSON array of the objects of `emulator_get_last_delta`. `emu` is left
+/// as it was, so the call can be repeated with a larger buffer. The simulation ends early at a
+/// trap, an environment call or an access to a device, which it can't undo. Writes the full
+/// length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn emulator_simulate(
+    emu: *mut Emulator,
+    steps: u64,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let deltas = speculation::simulate(emu, steps);
+        let json = deltas.iter().map(delta_json).collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(json).to_string(), out, len, full_len)
+    })
+}
+
+/// The size of the disassembly buffer of `StepAnnotation`, including the NUL. It's repeated here
+/// for the C header, which only gets the constants of this crate.
+pub const ANNOTATION_TEXT_SIZE: usize = 64;
+const _: () = assert!(ANNOTATION_TEXT_SIZE == annotation::ANNOTATION_TEXT_SIZE);
+
+/// Execute one step like `emulator_step_delta` and write what the tutorial overlay narrates about
+/// it into `out`: the disassembly, the registers read and written, whether a branch was taken,
+/// and the id of the template explaining it.
+#[no_mangle]
+pub extern "C" fn emulator_step_with_annotations(
+    emu: *mut Emulator,
+    out: *mut StepAnnotation,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let out = unsafe { non_null(out, "out")? };
+
+        *out = annotation::step_with_annotations(emu);
+        Ok(())
+    })
+}
+
+/// The most stores a `ReferenceState` reports for one step, repeated for the C header.
+pub const MAX_REFERENCE_STORES: usize = 4;
+const _: () = assert!(MAX_REFERENCE_STORES == cosim::MAX_REFERENCE_STORES);
+
+/// A reference model behind the FFI. It's called once per step with the user data given with it,
+/// executes one instruction, and writes its state after the instruction into `state`.
+pub type ReferenceStepFn = extern "C" fn(user_data: *mut c_void, state: *mut ReferenceState);
+
+/// Create a co-simulation that steps the reference model `step` in lockstep with `emu`. The model
+/// must start in the current state of `emu`. The instruction, the mode and the CSRs aren't
+/// compared, as the model only reports its registers and its stores. Writes the co-simulation
+/// into `cosim`.
+#[no_mangle]
+pub extern "C" fn cosim_create(
+    emu: *const Emulator,
+    step: ReferenceStepFn,
+    user_data: *mut c_void,
+    cosim: *mut *mut Cosim,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu as *mut Emulator, "emu")? };
+        let cosim = unsafe { non_null(cosim, "cosim")? };
+
+        let model = StateModel::new(emu, move |state| step(user_data, state));
+        *cosim = handles::create(Cosim::new(Box::new(model)));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn cosim_destroy(cosim: *mut Cosim) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(cosim, "cosim") })
+}
+
+/// Run up to `max_steps` steps on the emulator and the reference model and compare their
+/// effects, stopping at the first mismatch or when the emulator stops for the host. Writes the
+/// summary of the step that stopped the emulator, if any, into `summary`. Writes 1 into
+/// `mismatch` if the effects differ; `cosim_get_mismatch` tells how.
+#[no_mangle]
+pub extern "C" fn cosim_run(
+    cosim: *mut Cosim,
+    emu: *mut Emulator,
+    max_steps: u64,
+    summary: *mut RunSummary,
+    mismatch: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let cosim = unsafe { non_null(cosim, "cosim")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+        let mismatch = unsafe { non_null(mismatch, "mismatch")? };
+
+        let lockstep = cosim.run(emu, max_steps);
+        if let Some(stop) = lockstep.stop {
+            *summary = stop;
+        }
+        *mismatch = lockstep.mismatch as u32;
+        Ok(())
+    })
+}
+
+/// Copy the first mismatch into `out` as JSON: the number of `step`s that matched before it,
+/// the names of the `fields` that differ, and both deltas as `ours` and `theirs`, in the format
+/// of `emulator_get_last_delta`. Writes the full length of the JSON into `full_len`, or 0 if
+/// every step matched.
+#[no_mangle]
+pub extern "C" fn cosim_get_mismatch(
+    cosim: *const Cosim,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let cosim = unsafe { non_null(cosim as *mut Cosim, "cosim")? };
+
+        let json = cosim.mismatch().map(|mismatch| {
+            serde_json::json!({
+                "step": mismatch.step,
+                "fields": mismatch.fields.iter().map(|field| field.name()).collect::<Vec<&str>>(),
+                "ours": delta_json(&mismatch.ours),
+                "theirs": delta_json(&mismatch.theirs),
+            })
+            .to_string()
+        });
+        write_string(json.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Load the `program_len` bytes of `program`, assembled from `source`, into `emu` with the core in
+/// its power-on state at `entry`, and start recording a solution run. Step the emulator and feed
+/// it inputs through the recorder until the run is complete. Writes the recorder into
+/// `recorder`.
+#[no_mangle]
+pub extern "C" fn replay_recorder_start(
+    emu: *mut Emulator,
+    source: *const c_char,
+    program: *const u8,
+    program_len: usize,
+    entry: u64,
+    recorder: *mut *mut ReplayRecorder,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let source = string_arg(source, "source")?;
+        if program.is_null() {
+            return Err(FfiError::null("program"));
+        }
+        let recorder = unsafe { non_null(recorder, "recorder")? };
+
+        let program = unsafe { std::slice::from_raw_parts(program, program_len) }.to_vec();
+        *recorder = handles::create(ReplayRecorder::start(emu, &source, program, entry));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn replay_recorder_destroy(recorder: *mut ReplayRecorder) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(recorder, "recorder") })
+}
+
+/// Same as `emulator_run`, but every step is recorded.
+#[no_mangle]
+pub extern "C" fn replay_recorder_run(
+    recorder: *mut ReplayRecorder,
+    emu: *mut Emulator,
+    max_steps: u64,
+    summary: *mut RunSummary,
+) -> RvStatus {
+    status::guard(|| {
+        let recorder = unsafe { non_null(recorder, "recorder")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+
+        *summary = recorder.run(emu, max_steps);
+        Ok(())
+    })
+}
+
+/// Push the keyboard scancode `code` to `emu` and record it as an input of the next step.
+#[no_mangle]
+pub extern "C" fn replay_recorder_push_scancode(
+    recorder: *mut ReplayRecorder,
+    emu: *mut Emulator,
+    code: u8,
+) -> RvStatus {
+    status::guard(|| {
+        let recorder = unsafe { non_null(recorder, "recorder")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        recorder.input(emu, ReplayInput::Scancode(code));
+        Ok(())
+    })
+}
+
+/// Write `value` to the integer register `index` of `emu`, e.g. to serve an environment call, and
+/// record it as an input of the next step. Fails if `index` is out of range.
+#[no_mangle]
+pub extern "C" fn replay_recorder_write_register(
+    recorder: *mut ReplayRecorder,
+    emu: *mut Emulator,
+    index: u64,
+    value: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let recorder = unsafe { non_null(recorder, "recorder")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        if index >= 32 {
+            return Err(FfiError::invalid(format!("register {} doesn't exist", index)));
+        }
+        recorder.input(emu, ReplayInput::Register { index, value });
+        Ok(())
+    })
+}
+
+/// Attach the annotation `text` to the next step. The walkthrough shows it before the step.
+#[no_mangle]
+pub extern "C" fn replay_recorder_annotate(
+    recorder: *mut ReplayRecorder,
+    text: *const c_char,
+) -> RvStatus {
+    status::guard(|| {
+        let recorder = unsafe { non_null(recorder, "recorder")? };
+        let text = string_arg(text, "text")?;
+
+        recorder.annotate(&text);
+        Ok(())
+    })
+}
+
+/// Copy the recorded bundle into `out` as JSON: the `source`, the `program` as a hexadecimal
+/// string, the `entry`, the `inputs` and `annotations` with the `step` they belong to, and the
+/// `trace` of program counters. Writes the full length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn replay_recorder_get_bundle(
+    recorder: *const ReplayRecorder,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let recorder = unsafe { non_null(recorder as *mut ReplayRecorder, "recorder")? };
+
+        write_string(&recorder.bundle().to_json().to_string(), out, len, full_len)
+    })
+}
+
+/// Create a player of the bundle `bundle`, made by `replay_recorder_get_bundle`, and write it
+/// into `player`. Fails if the bundle is invalid.
+#[no_mangle]
+pub extern "C" fn replay_player_create(
+    bundle: *const c_char,
+    player: *mut *mut ReplayPlayer,
+) -> RvStatus {
+    status::guard(|| {
+        let bundle = string_arg(bundle, "bundle")?;
+        let player = unsafe { non_null(player, "player")? };
+
+        let bundle = ReplayBundle::from_json(&bundle).map_err(FfiError::invalid)?;
+        *player = handles::create(ReplayPlayer::new(bundle));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn replay_player_destroy(player: *mut ReplayPlayer) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(player, "player") })
+}
+
+/// Load the recorded program into `emu` and rewind to the first step.
+#[no_mangle]
+pub extern "C" fn replay_player_start(player: *mut ReplayPlayer, emu: *mut Emulator) -> RvStatus {
+    status::guard(|| {
+        let player = unsafe { non_null(player, "player")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        player.start(emu);
+        Ok(())
+    })
+}
+
+/// Feed the recorded inputs of the next step to `emu` and execute it, writing its summary into
+/// `summary`. Writes 0 into `playback` if the step was executed, 1 if every step was played, and
+/// 2 if the run diverged from the recording. Nothing is executed unless it's 0.
+#[no_mangle]
+pub extern "C" fn replay_player_step(
+    player: *mut ReplayPlayer,
+    emu: *mut Emulator,
+    summary: *mut RunSummary,
+    playback: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let player = unsafe { non_null(player, "player")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+        let playback = unsafe { non_null(playback, "playback")? };
+
+        let (status, step) = player.step(emu);
+        if let Some(step) = step {
+            *summary = step;
+        }
+        *playback = status as u32;
+        Ok(())
+    })
+}
+
+/// Write the index of the next step of the playback into `index`.
+#[no_mangle]
+pub extern "C" fn replay_player_step_index(
+    player: *const ReplayPlayer,
+    index: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let player = unsafe { non_null(player as *mut ReplayPlayer, "player")? };
+        let index = unsafe { non_null(index, "index")? };
+
+        *index = player.step_index();
+        Ok(())
+    })
+}
+
+/// Copy the annotations of the next step into `out` as a JSON array of strings. Writes the full
+/// length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn replay_player_get_annotations(
+    player: *const ReplayPlayer,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let player = unsafe { non_null(player as *mut ReplayPlayer, "player")? };
+
+        let annotations = player.annotations().collect::<Vec<&str>>();
+        write_string(&serde_json::Value::from(annotations).to_string(), out, len, full_len)
+    })
+}
+
+/// Load the level spec `spec`, a JSON document of `len` bytes, create an emulator configured for
+/// it, and write the level into `level`. Fails if the spec is invalid; `level_explain_error`
+/// tells why. Also fails if the host has no memory for the emulator, as `emulator_create` does.
+#[no_mangle]
+pub extern "C" fn level_load(
+    spec: *const u8,
+    len: usize,
+    level: *mut *mut ConfiguredEmulator,
+) -> RvStatus {
+    status::guard(|| {
+        if spec.is_null() {
+            return Err(FfiError::null("spec"));
+        }
+        let level = unsafe { non_null(level, "level")? };
+
+        let spec = unsafe { std::slice::from_raw_parts(spec, len) };
+        let template = load_template(spec).map_err(FfiError::invalid)?;
+        *level = instantiate_level(&template)?;
+        Ok(())
+    })
+}
+
+/// Create a level from `template` and return it as a live handle. Fails if the host has no
+/// memory for it.
+fn instantiate_level(template: &LevelTemplate) -> Result<*mut ConfiguredEmulator, FfiError> {
+    let level = template.instantiate().map_err(FfiError::allocation)?;
+    Ok(level_handle(level))
+}
+
+/// Move `level` to the heap and return it as a live handle, with its emulator borrowed.
+fn level_handle(level: ConfiguredEmulator) -> *mut ConfiguredEmulator {
+    let level = handles::create(level);
+    handles::borrow(unsafe { &mut (*level).emulator as *mut Emulator });
+    level
+}
+
+/// Copy why the level spec `spec` of `len` bytes can't be loaded into `out`. Writes the length of
+/// the message into `full_len`, or 0 if the spec is valid.
+#[no_mangle]
+pub extern "C" fn level_explain_error(
+    spec: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        if spec.is_null() {
+            return Err(FfiError::null("spec"));
+        }
+
+        let spec = unsafe { std::slice::from_raw_parts(spec, len) };
+        let message = load_level(spec).err().unwrap_or_default();
+        write_string(&message, out, out_len, full_len)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn level_destroy(level: *mut ConfiguredEmulator) -> RvStatus {
+    status::guard(|| {
+        if level.is_null() {
+            return Err(FfiError::null("level"));
+        }
+        // The emulator is only released once the level was destroyed, so not if it's stale.
+        let emulator = unsafe { std::ptr::addr_of_mut!((*level).emulator) };
+        background::stop(emulator);
+        unsafe { handles::destroy(level, "level") }?;
+        handles::release(emulator);
+        Ok(())
+    })
+}
+
+/// Load the level spec `spec`, a JSON document of `len` bytes, into a template that
+/// `emulator_instantiate` creates configured levels from, so the spec is parsed and validated
+/// once for all the retries and graded runs of the level. Writes the template into `template`.
+/// Fails if the spec is invalid; `level_explain_error` tells why.
+#[no_mangle]
+pub extern "C" fn emulator_template_create(
+    spec: *const u8,
+    len: usize,
+    template: *mut *mut LevelTemplate,
+) -> RvStatus {
+    status::guard(|| {
+        if spec.is_null() {
+            return Err(FfiError::null("spec"));
+        }
+        let template = unsafe { non_null(template, "template")? };
+
+        let spec = unsafe { std::slice::from_raw_parts(spec, len) };
+        *template = handles::create(load_template(spec).map_err(FfiError::invalid)?);
+        Ok(())
+    })
+}
+
+/// Create a level configured from `template`, as `level_load` would from its spec, ready for the
+/// program to be loaded, and write it into `level`. It's independent from the template and its
+/// other instances, and destroyed with `level_destroy`. Fails if the host has no memory for it,
+/// as `emulator_create` does.
+#[no_mangle]
+pub extern "C" fn emulator_instantiate(
+    template: *const LevelTemplate,
+    level: *mut *mut ConfiguredEmulator,
+) -> RvStatus {
+    status::guard(|| {
+        let template = unsafe { non_null(template as *mut LevelTemplate, "template")? };
+        let level = unsafe { non_null(level, "level")? };
+
+        *level = instantiate_level(template)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn emulator_template_destroy(template: *mut LevelTemplate) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(template, "template") })
+}
+
+/// Write the emulator of `level` into `emu`, e.g. to load the program or to step it with the
+/// `emulator_*` functions. It's owned by the level and destroyed with it.
+#[no_mangle]
+pub extern "C" fn level_emulator(
+    level: *mut ConfiguredEmulator,
+    emu: *mut *mut Emulator,
+) -> RvStatus {
+    status::guard(|| {
+        let level = unsafe { non_null(level, "level")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        *emu = &mut level.emulator;
+        Ok(())
+    })
+}
+
+/// Run the emulator of `level` within the limits of the level and write the summary into
+/// `summary`. Writes 1 into `won` if the run wins the level.
+#[no_mangle]
+pub extern "C" fn level_run(
+    level: *mut ConfiguredEmulator,
+    summary: *mut RunSummary,
+    won: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let level = unsafe { non_null(level, "level")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+        let won = unsafe { non_null(won, "won")? };
+
+        *summary = level.run();
+        *won = level.is_won(summary) as u32;
+        Ok(())
+    })
+}
+
+/// Write 1 into `won` if the run summarized by `summary` wins `level`, e.g. after stepping it
+/// manually.
+#[no_mangle]
+pub extern "C" fn level_is_won(
+    level: *const ConfiguredEmulator,
+    summary: *const RunSummary,
+    won: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let level = unsafe { non_null(level as *mut ConfiguredEmulator, "level")? };
+        let summary = unsafe { non_null(summary as *mut RunSummary, "summary")? };
+        let won = unsafe { non_null(won, "won")? };
+
+        *won = level.is_won(summary) as u32;
+        Ok(())
+    })
+}
+
+/// Copy the ISA policy of `level` into `out` in the canonical form of `riscv_format_isa_policy`,
+/// to assemble solutions with `riscv_assemble_with_policy`. Writes the length of the JSON into
+/// `full_len`, or 0 if the level has no policy.
+#[no_mangle]
+pub extern "C" fn level_get_policy(
+    level: *const ConfiguredEmulator,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let level = unsafe { non_null(level as *mut ConfiguredEmulator, "level")? };
+
+        let json = level.policy.as_ref().map(|policy| policy.to_json().to_string());
+        write_string(json.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Return the level `level` and the program image `program` of `len` bytes of a grading call.
+///
+/// # Safety
+///
+/// As for `non_null`, and `program` must be null or valid for `len` bytes.
+unsafe fn graded<'a>(
+    level: *const ConfiguredEmulator,
+    program: *const u8,
+    len: usize,
+) -> Result<(&'a ConfiguredEmulator, &'a [u8]), FfiError> {
+    let level = non_null(level as *mut ConfiguredEmulator, "level")?;
+    if program.is_null() {
+        return Err(FfiError::null("program"));
+    }
+    Ok((level, std::slice::from_raw_parts(program, len)))
+}
+
+/// Grade the program image `program` of `len` bytes on the generated cases of `level`, seeded by
+/// `seed`, and write the report into `report`. The emulator of the level isn't touched. Writes 1
+/// into `won` if every case is won. Fails if the program doesn't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn level_grade(
+    level: *const ConfiguredEmulator,
+    program: *const u8,
+    len: usize,
+    seed: u64,
+    report: *mut GradeReport,
+    won: *mut u32,
+) -> RvStatus {
+    status::guard(|| {
+        let (level, program) = unsafe { graded(level, program, len)? };
+        let report = unsafe { non_null(report, "report")? };
+        let won = unsafe { non_null(won, "won")? };
+
+        *report = level.grade(program, seed).map_err(FfiError::invalid)?;
+        *won = (report.failed == 0) as u32;
+        Ok(())
+    })
+}
+
+/// Score the program image `program` of `len` bytes on the rubric of `level`, running it on the
+/// cases generated from `seed`, and copy the breakdown into `out` as JSON: `{"earned", "total",
+/// "cases", "criteria"}`, with whether each case was won and a list of `{"criterion", "weight",
+/// "earned", "measured"}` objects. Writes the full length of the JSON into `full_len`. Fails if
+/// the program doesn't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn level_evaluate(
+    level: *const ConfiguredEmulator,
+    program: *const u8,
+    len: usize,
+    seed: u64,
+    out: *mut u8,
+    out_len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let (level, program) = unsafe { graded(level, program, len)? };
+
+        let evaluation = level.evaluate(program, seed).map_err(FfiError::invalid)?;
+        write_string(&evaluation.to_json().to_string(), out, out_len, full_len)
+    })
+}
+
+/// Grade the program image `program` of `len` bytes on the cases of `level` generated from
+/// `seed`, shrink the inputs of the first lost case while the program still loses, and copy the
+/// result into `out` as JSON: `{"case", "inputs", "reason", "steps", "exit_code"}`, with the
+/// inputs in the order of the generators. Writes the full length of the JSON into `full_len`, or
+/// 0 if every case is won. Fails if the program doesn't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn level_shrink_failure(
+    level: *const ConfiguredEmulator,
+    program: *const u8,
+    len: usize,
+    seed: u64,
+    out: *mut u8,
+    out_len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let (level, program) = unsafe { graded(level, program, len)? };
+
+        let shrunk = level.shrink_failure(program, seed).map_err(FfiError::invalid)?;
+        let json = shrunk.map(|shrunk| shrunk.to_json().to_string());
+        write_string(json.as_deref().unwrap_or(""), out, out_len, full_len)
+    })
+}
+
+/// Run the program image `program` of `len` bytes on the case `case` of `level` generated from
+/// `seed`, and copy the comparison with what the level expected into `out` as JSON: `{"reason",
+/// "steps", "exit_code", "conditions", "output", "divergence"}`. Each condition has `expected`
+/// and `actual` values, `output` has the debug prints of the reference run and of the program,
+/// and `divergence` is the first store that differs from the reference run, or null. Writes the
+/// full length of the JSON into `full_len`. Fails if the program doesn't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn level_explain_case(
+    level: *const ConfiguredEmulator,
+    program: *const u8,
+    len: usize,
+    seed: u64,
+    case: u64,
+    out: *mut u8,
+    out_len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let (level, program) = unsafe { graded(level, program, len)? };
+
+        let wrong_answer = level.explain_case(program, seed, case).map_err(FfiError::invalid)?;
+        write_string(&wrong_answer.to_json().to_string(), out, out_len, full_len)
+    })
+}
+
+/// Run the untrusted program image `program` of `len` bytes at `DRAM_BASE` in the sandbox and
+/// write the outcome into `result`. The program runs in user mode, can only access the first
+/// `memory_limit` bytes of DRAM and the `devices`, a set of `SANDBOX_DEVICE_*` bits, and stops
+/// after `max_steps` instructions or `timeout_ms` milliseconds; each limit is capped. `policy`,
+/// a JSON document of `policy_len` bytes in the format of the level specs, restricts its
+/// instructions unless it's null. Fails if the policy is invalid.
+#[no_mangle]
+pub extern "C" fn riscv_run_sandboxed(
+    program: *const u8,
+    len: usize,
+    max_steps: u64,
+    timeout_ms: u64,
+    memory_limit: u64,
+    devices: u32,
+    policy: *const u8,
+    policy_len: usize,
+    result: *mut SandboxResult,
+) -> RvStatus {
+    status::guard(|| {
+        if program.is_null() {
+            return Err(FfiError::null("program"));
+        }
+        let result = unsafe { non_null(result, "result")? };
+
+        let policy = if policy.is_null() {
+            None
+        } else {
+            let policy = unsafe { std::slice::from_raw_parts(policy, policy_len) };
+            let policy = std::str::from_utf8(policy)
+                .map_err(|_| FfiError::invalid("the policy isn't UTF-8".to_string()))?;
+            Some(isa_policy::IsaPolicy::from_json(policy).map_err(FfiError::invalid)?)
+        };
+        let config = SandboxConfig {
+            max_steps,
+            timeout: Duration::from_millis(timeout_ms),
+            memory_limit,
+            devices,
+            policy,
+        };
+        let program = unsafe { std::slice::from_raw_parts(program, len) };
+        *result = run_sandboxed(program, &config);
+        Ok(())
+    })
+}
+
+/// Copy the instruction ranges that differ between the program images `a` and `b` into `out` as
+/// JSON: a list of `{"a_addr", "a", "b_addr", "b"}` objects with the addresses of the ranges and
+/// the disassembly of both sides, as if both programs were loaded at `DRAM_BASE`. Identical
+/// programs give `[]`. Writes the full length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn riscv_diff_programs(
+    a: *const u8,
+    a_len: usize,
+    b: *const u8,
+    b_len: usize,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        if a.is_null() {
+            return Err(FfiError::null("a"));
+        }
+        if b.is_null() {
+            return Err(FfiError::null("b"));
+        }
+
+        let a = unsafe { std::slice::from_raw_parts(a, a_len) };
+        let b = unsafe { std::slice::from_raw_parts(b, b_len) };
+        let ranges = diff_programs(a, b, DRAM_BASE)
+            .into_iter()
+            .map(|range| {
+                serde_json::json!({
+                    "a_addr": range.a_addr,
+                    "a": range.a,
+                    "b_addr": range.b_addr,
+                    "b": range.b,
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        write_string(&serde_json::Value::from(ranges).to_string(), out, len, full_len)
+    })
+}
+
+/// The progress callback of long operations. It's called with the user data given with it, the
+/// amount of work done and the total amount of work.
+pub type ProgressFn = extern "C" fn(user_data: *mut c_void, done: u64, total: u64);
+
+/// The user data of a callback. The host is responsible for making it usable from the thread
+/// the emulator runs on.
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+
+/// Call `progress` every `interval` steps of `emulator_run*` with `user_data`, the number of steps
+/// done and the step limit. A null `progress` or an `interval` of 0 removes the callback.
+#[no_mangle]
+pub extern "C" fn emulator_set_progress_callback(
+    emu: *mut Emulator,
+    progress: Option<ProgressFn>,
+    user_data: *mut c_void,
+    interval: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        match progress {
+            Some(progress) => {
+                let user_data = UserData(user_data);
+                emu.set_progress_callback(
+                    interval,
+                    Box::new(move |done, total| progress(user_data.0, done, total)),
+                );
+            }
+            None => emu.set_progress_callback(0, Box::new(|_, _| {})),
+        }
+        Ok(())
+    })
+}
+
+/// The handler of the environment calls of the guest. It's called with the user data given with
+/// it, the call number in a7 and the arguments in a0 to a5, and the return value, initially a0.
+/// It returns what the run does next: 0 writes the return value into a0 and resumes, 1 exits
+/// with the return value as the exit code, and anything else stops the run for the host as if
+/// there were no handler.
+pub type EcallFn =
+    extern "C" fn(user_data: *mut c_void, call: *const EcallArgs, a0: *mut u64) -> u32;
+
+/// Serve the environment calls of the guest during `emulator_run*` with `handler` and
+/// `user_data`, so the host can implement its own system calls without stopping the run. A null
+/// `handler` removes it, and the calls stop the run again with `StopReason::Yielded`.
+#[no_mangle]
+pub extern "C" fn emulator_set_ecall_handler(
+    emu: *mut Emulator,
+    handler: Option<EcallFn>,
+    user_data: *mut c_void,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let handler = handler.map(|handler| {
+            let user_data = UserData(user_data);
+            Box::new(move |call: &EcallArgs, a0: &mut u64| {
+                let code = handler(user_data.0, call, a0);
+                EcallAction::from_code(code).unwrap_or(EcallAction::Yield)
+            }) as _
+        });
+        emu.set_ecall_handler(handler);
+        Ok(())
+    })
+}
+
+/// The handler of the `ebreak`s of the guest. It's called with the user data given with it and
+/// the address of the `ebreak`, and returns 0 to resume after it or anything else to stop the run
+/// with `StopReason::Breakpoint`.
+pub type EbreakFn = extern "C" fn(user_data: *mut c_void, pc: u64) -> u32;
+
+/// Choose what `ebreak` does during `emulator_run*`: `behavior` 0 stops the run with
+/// `StopReason::Breakpoint`, as a debugger does, which is the default; 1 raises a breakpoint
+/// exception the guest handles at its trap vector; 2 calls `handler` with `user_data`. Fails if
+/// `behavior` is unknown, or is 2 without a handler.
+#[no_mangle]
+pub extern "C" fn emulator_set_ebreak_behavior(
+    emu: *mut Emulator,
+    behavior: u32,
+    handler: Option<EbreakFn>,
+    user_data: *mut c_void,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        let behavior = match (behavior, handler) {
+            (0, _) => EbreakBehavior::Stop,
+            (1, _) => EbreakBehavior::Trap,
+            (2, Some(handler)) => {
+                let user_data = UserData(user_data);
+                EbreakBehavior::Callback(Box::new(move |pc| handler(user_data.0, pc) == 0))
+            }
+            (2, None) => return Err(FfiError::null("handler")),
+            (behavior, _) => {
+                let message = format!("{} isn't an ebreak behavior", behavior);
+                return Err(FfiError::invalid(message));
+            }
+        };
+        emu.set_ebreak_behavior(behavior);
+        Ok(())
+    })
+}
+
+/// Create a cancel token and write it into `token`. It can be cancelled from any thread while an
+/// operation using it runs.
+#[no_mangle]
+pub extern "C" fn cancel_token_create(token: *mut *mut CancelToken) -> RvStatus {
+    status::guard(|| {
+        let token = unsafe { non_null(token, "token")? };
+
+        *token = handles::create(CancelToken::new());
+        Ok(())
+    })
+}
+
+/// Cancel the current or the next operation using the token.
+#[no_mangle]
+pub extern "C" fn cancel_token_cancel(token: *const CancelToken) -> RvStatus {
+    status::guard(|| {
+        let token = unsafe { non_null(token as *mut CancelToken, "token")? };
+
+        token.cancel();
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn cancel_token_destroy(token: *mut CancelToken) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(token, "token") })
+}
+
+/// Make `emulator_run*` stop with `StopReason::Cancelled` when `token` is cancelled. The emulator
+/// keeps its own reference, so the token may be destroyed before the emulator.
+#[no_mangle]
+pub extern "C" fn emulator_set_cancel_token(
+    emu: *mut Emulator,
+    token: *const CancelToken,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let token = unsafe { non_null(token as *mut CancelToken, "token")? };
+
+        emu.cancel_token = token.clone();
+        Ok(())
+    })
+}
+
+/// Create an arena where bots run `quantum` steps per turn and write it into `arena`. `memory` is
+/// an `ArenaMemory`: 0 for shared memory, 1 for memory partitioned with PMP. Fails for other
+/// values.
+#[no_mangle]
+pub extern "C" fn arena_create(quantum: u64, memory: u32, arena: *mut *mut Arena) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+
+        let memory = match memory {
+            0 => ArenaMemory::Shared,
+            1 => ArenaMemory::Partitioned,
+            _ => return Err(FfiError::invalid(format!("unknown arena memory {}", memory))),
+        };
+        *arena = handles::create(Arena::new(quantum, memory));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn arena_destroy(arena: *mut Arena) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(arena, "arena") })
+}
+
+/// Let every bot access `[base, base + size)` when memory is partitioned.
+#[no_mangle]
+pub extern "C" fn arena_set_shared_region(arena: *mut Arena, base: u64, size: u64) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+
+        arena.set_shared_region(base, size);
+        Ok(())
+    })
+}
+
+/// Add the loaded program `program` of `emu` as a bot owning the `region_size` bytes from the
+/// start of its image, limited to `max_steps` steps over the match. Writes the index of the bot
+/// into `bot`. Fails if there's no such program.
+#[no_mangle]
+pub extern "C" fn arena_add_bot(
+    arena: *mut Arena,
+    emu: *const Emulator,
+    program: u64,
+    region_size: u64,
+    max_steps: u64,
+    bot: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+        let emu = unsafe { non_null(emu as *mut Emulator, "emu")? };
+        let bot = unsafe { non_null(bot, "bot")? };
+
+        let index = arena
+            .add_bot(emu, program as usize, region_size, max_steps)
+            .ok_or_else(|| no_program(program))?;
+        *bot = index as u64;
+        Ok(())
+    })
+}
+
+/// The arbiter of a match. It's called after every quantum with the user data given with it, the
+/// emulator, the index of the bot that ran and the summary of its quantum, and returns an
+/// `Arbitration`: 0 to continue, 1 to eliminate the bot, 2 to end the match.
+pub type ArbiterFn = extern "C" fn(
+    user_data: *mut c_void,
+    emu: *mut Emulator,
+    bot: u64,
+    summary: *const RunSummary,
+) -> u32;
+
+/// Run up to `rounds` rounds of the match, calling `arbiter` after every quantum. A null
+/// `arbiter` lets every bot continue. Writes the number of rounds played into `played`.
+#[no_mangle]
+pub extern "C" fn arena_run(
+    arena: *mut Arena,
+    emu: *mut Emulator,
+    rounds: u64,
+    arbiter: Option<ArbiterFn>,
+    user_data: *mut c_void,
+    played: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+        let played = unsafe { non_null(played, "played")? };
+
+        *played = arena.run(emu, rounds, |emu, bot, summary| {
+            match arbiter.map(|arbiter| arbiter(user_data, emu, bot as u64, summary)) {
+                Some(1) => Arbitration::Eliminate,
+                Some(2) => Arbitration::EndMatch,
+                _ => Arbitration::Continue,
+            }
+        });
+        Ok(())
+    })
+}
+
+/// Return the error of the bot `bot` that doesn't exist.
+fn no_bot(bot: u64) -> FfiError {
+    FfiError::invalid(format!("there's no bot {}", bot))
+}
+
+/// Copy the status of the bot `bot` into `out`. Fails if there's no such bot.
+#[no_mangle]
+pub extern "C" fn arena_get_bot_status(
+    arena: *const Arena,
+    bot: u64,
+    out: *mut BotStatus,
+) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena as *mut Arena, "arena")? };
+        let out = unsafe { non_null(out, "out")? };
+
+        *out = arena.status(bot as usize).ok_or_else(|| no_bot(bot))?;
+        Ok(())
+    })
+}
+
+/// Change the number of steps the bot `bot` may run over the whole match. A bot that was out of
+/// steps takes turns again if the new limit allows it. Fails if there's no such bot.
+#[no_mangle]
+pub extern "C" fn arena_set_bot_max_steps(
+    arena: *mut Arena,
+    bot: u64,
+    max_steps: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+
+        if !arena.set_max_steps(bot as usize, max_steps) {
+            return Err(no_bot(bot));
+        }
+        Ok(())
+    })
+}
+
+/// Write the register `reg` of the bot `bot` while it waits for its turn, e.g. to pass it the
+/// state of the game. Fails if there's no such bot.
+#[no_mangle]
+pub extern "C" fn arena_write_bot_register(
+    arena: *mut Arena,
+    bot: u64,
+    reg: u64,
+    value: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let arena = unsafe { non_null(arena, "arena")? };
+
+        let context = arena.context_mut(bot as usize).ok_or_else(|| no_bot(bot))?;
+        context.xregs.write(reg, value);
+        Ok(())
+    })
+}
+
+/// Create a lockstep run of `nodes` emulators that run `quantum` steps per round and write it
+/// into `lockstep`. No node is linked to another yet.
+#[no_mangle]
+pub extern "C" fn lockstep_create(
+    nodes: u64,
+    quantum: u64,
+    lockstep: *mut *mut Lockstep,
+) -> RvStatus {
+    status::guard(|| {
+        let lockstep = unsafe { non_null(lockstep, "lockstep")? };
+
+        *lockstep = handles::create(Lockstep::new(nodes as usize, quantum));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn lockstep_destroy(lockstep: *mut Lockstep) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(lockstep, "lockstep") })
+}
+
+/// Deliver the messages the node `from` sends to the node `to` as well. Fails if either node
+/// doesn't exist or they're the same node.
+#[no_mangle]
+pub extern "C" fn lockstep_link(lockstep: *mut Lockstep, from: u64, to: u64) -> RvStatus {
+    status::guard(|| {
+        let lockstep = unsafe { non_null(lockstep, "lockstep")? };
+
+        if !lockstep.link(from as usize, to as usize) {
+            let message = format!("node {} can't be linked to node {}", from, to);
+            return Err(FfiError::invalid(message));
+        }
+        Ok(())
+    })
+}
+
+/// Link every node to every other node, so that each message is broadcast.
+#[no_mangle]
+pub extern "C" fn lockstep_link_all(lockstep: *mut Lockstep) -> RvStatus {
+    status::guard(|| {
+        let lockstep = unsafe { non_null(lockstep, "lockstep")? };
+
+        lockstep.link_all();
+        Ok(())
+    })
+}
+
+/// Run up to `rounds` rounds on `emus`, the `count` emulators of the nodes in order, and write the
+/// number of rounds played to `played`. Every running node runs one quantum per round, then the
+/// messages sent through the channels are delivered along the links. Returns
+/// `RvStatus::InvalidArgument` if `count` isn't the number of nodes or an emulator is given twice.
+#[no_mangle]
+pub extern "C" fn lockstep_run(
+    lockstep: *mut Lockstep,
+    emus: *const *mut Emulator,
+    count: usize,
+    rounds: u64,
+    played: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let lockstep = unsafe { non_null(lockstep, "lockstep")? };
+        let played = unsafe { non_null(played, "played")? };
+        if emus.is_null() {
+            return Err(FfiError::null("emus"));
+        }
+        if count != lockstep.nodes() {
+            return Err(FfiError::invalid(format!(
+                "{} emulators were given for {} nodes",
+                count,
+                lockstep.nodes()
+            )));
+        }
+
+        let ptrs = unsafe { std::slice::from_raw_parts(emus, count) };
+        for (i, ptr) in ptrs.iter().enumerate() {
+            if ptr.is_null() {
+                return Err(FfiError::null(&format!("emus[{}]", i)));
+            }
+            if let Some(other) = ptrs[..i].iter().position(|other| other == ptr) {
+                return Err(FfiError::invalid(format!(
+                    "the emulator of node {} is also the one of node {}",
+                    i, other
+                )));
+            }
+        }
+        // The pointers are distinct, so the references don't alias.
+        let mut emus = ptrs
+            .iter()
+            .map(|ptr| unsafe { &mut **ptr })
+            .collect::<Vec<&mut Emulator>>();
+        *played = lockstep.run(&mut emus, rounds);
+        Ok(())
+    })
+}
+
+/// Copy the status of the node `node` into `out`. Fails if there's no such node.
+#[no_mangle]
+pub extern "C" fn lockstep_get_node_status(
+    lockstep: *const Lockstep,
+    node: u64,
+    out: *mut NodeStatus,
+) -> RvStatus {
+    status::guard(|| {
+        let lockstep = unsafe { non_null(lockstep as *mut Lockstep, "lockstep")? };
+        let out = unsafe { non_null(out, "out")? };
+
+        *out = lockstep
+            .status(node as usize)
+            .ok_or_else(|| FfiError::invalid(format!("there's no node {}", node)))?;
+        Ok(())
+    })
+}
+
+/// Create a multi-hart mode without harts whose interleaving is derived from `seed` and write it
+/// into `harts`.
+#[no_mangle]
+pub extern "C" fn harts_create(seed: u64, harts: *mut *mut Harts) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+
+        *harts = handles::create(Harts::new(seed));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn harts_destroy(harts: *mut Harts) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(harts, "harts") })
+}
+
+/// Set the longest burst of instructions a hart runs before the scheduler picks again. 1
+/// switches harts after every instruction.
+#[no_mangle]
+pub extern "C" fn harts_set_max_burst(harts: *mut Harts, max_burst: u64) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+
+        harts.set_max_burst(max_burst);
+        Ok(())
+    })
+}
+
+/// Add a hart starting at `pc` with its stack pointer at `sp`. Writes its ID, which is also in
+/// its `mhartid` and `a0`, into `hartid`.
+#[no_mangle]
+pub extern "C" fn harts_add_hart(
+    harts: *mut Harts,
+    pc: u64,
+    sp: u64,
+    hartid: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+        let hartid = unsafe { non_null(hartid, "hartid")? };
+
+        *hartid = harts.add_hart(pc, sp) as u64;
+        Ok(())
+    })
+}
+
+/// Save the `size` bytes of DRAM at `base` so `harts_restart` restores them. Fails if they
+/// aren't in DRAM.
+#[no_mangle]
+pub extern "C" fn harts_save_memory(
+    harts: *mut Harts,
+    emu: *const Emulator,
+    base: u64,
+    size: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+        let emu = unsafe { non_null(emu as *mut Emulator, "emu")? };
+
+        if !harts.save_memory(emu, base, size) {
+            return Err(outside_dram(base, size as usize));
+        }
+        Ok(())
+    })
+}
+
+/// Put every hart back at its start, restore the saved memory, and schedule with `seed`, so the
+/// next runs replay the interleaving of `seed`.
+#[no_mangle]
+pub extern "C" fn harts_restart(harts: *mut Harts, emu: *mut Emulator, seed: u64) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        harts.restart(emu, seed);
+        Ok(())
+    })
+}
+
+/// Run the harts for up to `max_steps` steps in total. If a hart stopped the run, e.g. with an
+/// `ecall` or by exiting, writes its ID into `hartid` and the summary of its last burst into
+/// `summary`. Writes -1 into `hartid` if the step limit was reached or no hart is runnable.
+#[no_mangle]
+pub extern "C" fn harts_run(
+    harts: *mut Harts,
+    emu: *mut Emulator,
+    max_steps: u64,
+    summary: *mut RunSummary,
+    hartid: *mut i64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+        let emu = unsafe { non_null(emu, "emu")? };
+        let summary = unsafe { non_null(summary, "summary")? };
+        let hartid = unsafe { non_null(hartid, "hartid")? };
+
+        *hartid = match harts.run(emu, max_steps).stop {
+            Some((stopped, stop)) => {
+                *summary = stop;
+                stopped as i64
+            }
+            None => -1,
+        };
+        Ok(())
+    })
+}
+
+/// Return the error of the hart `hartid` that doesn't exist.
+fn no_hart(hartid: u64) -> FfiError {
+    FfiError::invalid(format!("there's no hart {}", hartid))
+}
+
+/// Write the `HartState` of the hart `hartid` into `state`: 0 = runnable, 1 = exited,
+/// 2 = trapped, 3 = out of steps. Fails if there's no such hart.
+#[no_mangle]
+pub extern "C" fn harts_get_state(harts: *const Harts, hartid: u64, state: *mut u32) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts as *mut Harts, "harts")? };
+        let state = unsafe { non_null(state, "state")? };
+
+        *state = harts.state(hartid as usize).ok_or_else(|| no_hart(hartid))? as u32;
+        Ok(())
+    })
+}
+
+/// Write the register `reg` of the hart `hartid` into `value`. Fails if there's no such hart.
+#[no_mangle]
+pub extern "C" fn harts_get_register(
+    harts: *mut Harts,
+    hartid: u64,
+    reg: u64,
+    value: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+        let value = unsafe { non_null(value, "value")? };
+
+        let context = harts.context_mut(hartid as usize).ok_or_else(|| no_hart(hartid))?;
+        *value = context.xregs.read(reg);
+        Ok(())
+    })
+}
+
+/// Limit the number of instructions the hart `hartid` retires in total to `quota`, or lift the
+/// limit if `quota` is 0. Fails if there's no such hart.
+#[no_mangle]
+pub extern "C" fn harts_set_quota(harts: *mut Harts, hartid: u64, quota: u64) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+
+        let context = harts.context_mut(hartid as usize).ok_or_else(|| no_hart(hartid))?;
+        context.set_quota(Some(quota).filter(|&quota| quota > 0));
+        Ok(())
+    })
+}
+
+/// Copy the bursts run since the last restart into `out` as pairs of the hart ID and the number
+/// of instructions it retired, up to `len` bursts. Writes the number of bursts into `bursts`.
+#[no_mangle]
+pub extern "C" fn harts_get_schedule(
+    harts: *const Harts,
+    out: *mut u64,
+    len: usize,
+    bursts: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts as *mut Harts, "harts")? };
+        let bursts = unsafe { non_null(bursts, "bursts")? };
+
+        let schedule = harts.schedule();
+        let copied = len.min(schedule.len());
+        if copied > 0 {
+            if out.is_null() {
+                return Err(FfiError::null("out"));
+            }
+            let out = unsafe { std::slice::from_raw_parts_mut(out, 2 * copied) };
+            for (pair, burst) in out.chunks_mut(2).zip(schedule) {
+                pair[0] = burst.hart as u64;
+                pair[1] = burst.steps;
+            }
+        }
+        *bursts = schedule.len() as u64;
+        Ok(())
+    })
+}
+
+/// Start (`enable` = 1) or stop (0) detecting data races between the harts. Stopping forgets
+/// the races found.
+#[no_mangle]
+pub extern "C" fn harts_set_race_detection(harts: *mut Harts, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts, "harts")? };
+
+        harts.set_race_detection(enable != 0);
+        Ok(())
+    })
+}
+
+/// Copy up to `len` of the data races found since the last restart into `out`, each pair of
+/// racing instructions once. Writes the number of races found into `found`.
+#[no_mangle]
+pub extern "C" fn harts_get_races(
+    harts: *const Harts,
+    out: *mut DataRace,
+    len: usize,
+    found: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let harts = unsafe { non_null(harts as *mut Harts, "harts")? };
+        let found = unsafe { non_null(found, "found")? };
+
+        let races = harts.races();
+        let copied = len.min(races.len());
+        if copied > 0 {
+            if out.is_null() {
+                return Err(FfiError::null("out"));
+            }
+            let out = unsafe { std::slice::from_raw_parts_mut(out, copied) };
+            out.copy_from_slice(&races[..copied]);
+        }
+        *found = races.len() as u64;
+        Ok(())
+    })
+}
+
+/* ASSEMBLER */
+use assembler_session::{cache_key, AssembleResult, AssemblerSession, ASSEMBLE_MICRO_ISA};
+use disk_cache::DiskCache;
+use rvemu::assembler::{self, AssembleError, Isa};
+use rvemu::object::Section;
+use std::ffi::c_char;
+use std::ffi::CStr;
+
+/// Write the disassembly of the instruction word `inst` at `pc` into `out` as a NUL-terminated
+/// string, e.g. "addi a0, a0, -1", truncated to fit in `len` bytes. Branch and jump targets are
+/// resolved against `pc` and CSRs are shown as addresses. Writes the full length of the
+/// disassembly without the NUL into `full_len`, so a length of `len` or more means it was
+/// truncated.
+#[no_mangle]
+pub extern "C" fn riscv_disassemble(
+    inst: u32,
+    pc: u64,
+    out: *mut c_char,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let full_len = unsafe { non_null(full_len, "full_len")? };
+
+        let disassembly = disassemble(inst as u64, pc);
+        if len > 0 {
+            if out.is_null() {
+                return Err(FfiError::null("out"));
+            }
+            let bytes = disassembly.as_bytes();
+            let copied = bytes.len().min(len - 1);
+            let out = unsafe { std::slice::from_raw_parts_mut(out as *mut u8, copied + 1) };
+            out[..copied].copy_from_slice(&bytes[..copied]);
+            out[copied] = 0;
+        }
+        *full_len = disassembly.len() as u64;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn free_riscv_assemble(bytes: *mut u8) -> RvStatus {
+    status::guard(|| {
+        if bytes.is_null() {
+            return Err(FfiError::null("bytes"));
+        }
+
+        unsafe {
+            let _ = Box::from_raw(bytes);
+        };
+        Ok(())
+    })
+}
+
+/// The out-parameters of the functions that assemble a program image: the image handed over to
+/// the host, its length, and the line of the error if it doesn't assemble.
+struct ImageOut<'a> {
+    out: &'a mut *mut u8,
+    len: &'a mut u64,
+    error_line: &'a mut u64,
+}
+
+impl<'a> ImageOut<'a> {
+    /// Check the out-parameters and clear the length and the error line.
+    ///
+    /// # Safety
+    ///
+    /// As for `non_null`.
+    unsafe fn new(
+        out: *mut *mut u8,
+        len: *mut u64,
+        error_line: *mut u64,
+    ) -> Result<Self, FfiError> {
+        let image = Self {
+            out: non_null(out, "out")?,
+            len: non_null(len, "len")?,
+            error_line: non_null(error_line, "error_line")?,
+        };
+        *image.len = 0;
+        *image.error_line = 0;
+        Ok(image)
+    }
+
+    /// Fail with `message` about the line `line` of the source.
+    fn fail_at(self, line: u64, message: String) -> Result<(), FfiError> {
+        *self.error_line = line;
+        Err(FfiError::invalid(message))
+    }
+
+    /// Hand the image of `assembled` over to the host, or fail with its error.
+    fn hand_over(self, assembled: Result<Vec<u8>, AssembleError>) -> Result<(), FfiError> {
+        match assembled {
+            Ok(image) => {
+                *self.len = leak_image(image, self.out);
+                Ok(())
+            }
+            Err(error) => self.fail_at(error.line, error.message),
+        }
+    }
+}
+
+/// Assemble `instruction`, a RV32I source, and hand the program image over to the host through
+/// `out`, with its length written into `len`. It's freed with `free_riscv_assemble`. Fails if the
+/// source doesn't assemble, with the line of the error written into `error_line`, or 0 if the
+/// error isn't about a line, e.g. because the source isn't UTF-8.
+#[no_mangle]
+pub extern "C" fn riscv_assemble(
+    instruction: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    riscv_assemble_cancellable(
+        instruction,
+        out,
+        len,
+        error_line,
+        None,
+        std::ptr::null_mut(),
+        std::ptr::null(),
+    )
+}
+
+/// Same as `riscv_assemble`, which assembles RV32I, but for the ISA string `isa`, e.g. "rv32im" to
+/// also assemble the multiplication and division instructions of the M extension, or "rv64im" for
+/// RV64 and its `w` variants of them. With the C extension, e.g. "rv32ic", instructions that have
+/// a compressed encoding take 2 bytes instead of 4. An invalid ISA string fails with `error_line`
+/// set to 0.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_isa(
+    source: *const c_char,
+    isa: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let isa = isa_arg(isa)?;
+        let source = prepare_source(source)?;
+
+        image.hand_over(assembler::assemble_isa(&source, isa))
+    })
+}
+
+/// Return the ISA of the ISA string argument `isa`, or an error if it's null or invalid.
+fn isa_arg(isa: *const c_char) -> Result<Isa, FfiError> {
+    Isa::parse(&string_arg(isa, "isa")?).map_err(FfiError::invalid)
+}
+
+/// Same as `riscv_assemble_isa`, but keeps the data section apart from the text: the text is
+/// linked at `DRAM_BASE` and handed over through `text_out`, with its length written into
+/// `text_len`, and the data is linked at `data_addr`, e.g. in a RAM region of its own, and handed
+/// over through `data_out` with its length written into `data_len`. `data_out` is set to null if
+/// there's no data. Both are freed with `free_riscv_assemble`. Fails with `error_line` set to the
+/// line of the error, including a label that `data_addr` puts out of reach.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_sections(
+    source: *const c_char,
+    isa: *const c_char,
+    data_addr: u64,
+    text_out: *mut *mut u8,
+    text_len: *mut u64,
+    data_out: *mut *mut u8,
+    data_len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let text = unsafe { ImageOut::new(text_out, text_len, error_line)? };
+        let data_out = unsafe { non_null(data_out, "data_out")? };
+        let data_len = unsafe { non_null(data_len, "data_len")? };
+        *data_out = std::ptr::null_mut();
+        *data_len = 0;
+        let isa = isa_arg(isa)?;
+        let source = prepare_source(source)?;
+
+        let linked = assembler::assemble_object(&source, isa)
+            .and_then(|object| object.link(DRAM_BASE, data_addr));
+        text.hand_over(linked.map(|(text, data)| {
+            if !data.is_empty() {
+                *data_len = leak_image(data, data_out);
+            }
+            text
+        }))
+    })
+}
+
+/// Same as `riscv_assemble`, but for programs written in the micro-ISA of the first levels. See
+/// `riscv_explain_micro_error` for why a program doesn't assemble.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_micro(
+    source: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let source = string_arg(source, "source")?;
+
+        match micro_isa::translate(&source) {
+            Ok(translated) => image.hand_over(assemble_rv32(&translated)),
+            Err((line, message)) => image.fail_at(line, message),
+        }
+    })
+}
+
+/// Copy the message for the player about the first line of a micro-ISA program that can't be
+/// translated, e.g. an instruction that isn't part of the level, into `out`. Writes the length
+/// of the message into `full_len`, or 0 if every line is valid.
+#[no_mangle]
+pub extern "C" fn riscv_explain_micro_error(
+    source: *const c_char,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let source = string_arg(source, "source")?;
+
+        let message = micro_isa::translate(&source).err().map(|(_, message)| message);
+        write_string(message.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Restrict execution to the instructions micro-ISA programs are made of (`enable` = 1), or
+/// allow every instruction again (`enable` = 0).
+#[no_mangle]
+pub extern "C" fn emulator_set_micro_isa(emu: *mut Emulator, enable: u32) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.instruction_set = match enable {
+            0 => None,
+            _ => Some(micro_isa::instruction_set()),
+        };
+        Ok(())
+    })
+}
+
+/// Parse the ISA policy argument `policy`, a JSON string. Fails if it's null or invalid.
+fn policy_arg(policy: *const c_char) -> Result<isa_policy::IsaPolicy, FfiError> {
+    let policy = string_arg(policy, "policy")?;
+    isa_policy::IsaPolicy::from_json(&policy)
+        .map_err(|message| FfiError::invalid(format!("invalid policy: {}", message)))
+}
+
+/// Same as `riscv_assemble`, but the source must follow the ISA policy `policy`, a JSON string.
+/// A source that breaks the policy, or an invalid policy, doesn't assemble. See
+/// `riscv_explain_policy_violation` for why.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_with_policy(
+    source: *const c_char,
+    policy: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let policy = policy_arg(policy)?;
+        let source = string_arg(source, "source")?;
+
+        match policy.check_source(&source) {
+            Ok(()) => image.hand_over(assemble_rv32(&source)),
+            Err(violation) => image.fail_at(violation.line, violation.describe()),
+        }
+    })
+}
+
+/// Copy the message for the player about the first line of `source` that breaks the ISA policy
+/// `policy` into `out`, followed by a suggestion when a legal equivalent exists. Writes the
+/// length of the message into `full_len`, or 0 if the source follows the policy.
+#[no_mangle]
+pub extern "C" fn riscv_explain_policy_violation(
+    source: *const c_char,
+    policy: *const c_char,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let source = string_arg(source, "source")?;
+        let policy = string_arg(policy, "policy")?;
+
+        let message = match isa_policy::IsaPolicy::from_json(&policy) {
+            Ok(policy) => policy
+                .check_source(&source)
+                .err()
+                .map(|violation| violation.describe()),
+            Err(message) => Some(format!("Invalid policy: {}", message)),
+        };
+        write_string(message.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Copy `source` into `out` with the first line that breaks the ISA policy `policy` replaced with
+/// a line that does the same within the policy, e.g. `li a0, 5` with `addi a0, zero, 5`. Writes
+/// the length of the fixed source into `full_len`, or 0 if there's nothing to fix or no fix is
+/// known. Fails if the policy is invalid.
+#[no_mangle]
+pub extern "C" fn riscv_fix_policy_violation(
+    source: *const c_char,
+    policy: *const c_char,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let source = string_arg(source, "source")?;
+        let policy = policy_arg(policy)?;
+
+        let fixed = policy.fix_source(&source);
+        write_string(fixed.as_deref().unwrap_or(""), out, len, full_len)
+    })
+}
+
+/// Copy the hint about doing without the instruction `mnemonic` into `out`, e.g. "multiply using
+/// shifts and adds" for `mul`. Writes the length of the hint into `full_len`, or 0 if there's
+/// none.
+#[no_mangle]
+pub extern "C" fn riscv_instruction_hint(
+    mnemonic: *const c_char,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let mnemonic = string_arg(mnemonic, "mnemonic")?;
+
+        let advice = hints::hint_for(&mnemonic).map_or("", |hint| hint.advice);
+        write_string(advice, out, len, full_len)
+    })
+}
+
+/// Copy the ISA policy `policy`, a JSON string, into `out` in its canonical form: unrestricted
+/// constraints are left out. Level editors use it to validate policies before saving them.
+/// Writes the length of the JSON into `full_len`. Fails if the policy is invalid.
+#[no_mangle]
+pub extern "C" fn riscv_format_isa_policy(
+    policy: *const c_char,
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let policy = policy_arg(policy)?;
+
+        write_string(&policy.to_json().to_string(), out, len, full_len)
+    })
+}
+
+/// Enforce the ISA policy `policy`, a JSON string, when executing: instructions and registers it
+/// doesn't allow raise an illegal instruction exception. A null `policy` removes the
+/// restrictions. Fails if the policy is invalid.
+#[no_mangle]
+pub extern "C" fn emulator_set_isa_policy(emu: *mut Emulator, policy: *const c_char) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+
+        emu.cpu.instruction_set = if policy.is_null() {
+            None
+        } else {
+            policy_arg(policy)?.instruction_set()
+        };
+        Ok(())
+    })
+}
+
+/// Same as `riscv_assemble`, but the register aliases of `emu` can be used as operands and its
+/// custom CSR names in CSR instructions.
+#[no_mangle]
+pub extern "C" fn emulator_assemble(
+    emu: *mut Emulator,
+    instruction: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let source = string_arg(instruction, "instruction")?;
+
+        let substituted = source
+            .split('\n')
+            .map(|line| emu.csr_names.substitute(&emu.register_aliases.substitute(line)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        image.hand_over(assemble_rv32(&substituted))
+    })
+}
+
+/// Assemble the single source line `source_line` as `emulator_assemble` does and write it over
+/// the instruction at `addr`, e.g. when the player tweaks an instruction of the running program.
+/// Branch and jump targets can be the labels of the loaded symbols, and the line is assembled for
+/// the XLEN of `emu`. The CPU fetches every instruction from memory, so the next time it reaches
+/// `addr` it executes the patch. Fails without writing anything if the line doesn't assemble,
+/// `addr` isn't 2-byte aligned or the instructions don't fit in DRAM.
+#[no_mangle]
+pub extern "C" fn emulator_patch_instruction(
+    emu: *mut Emulator,
+    addr: u64,
+    source_line: *const c_char,
+) -> RvStatus {
+    status::guard(|| {
+        let emu = unsafe { non_null(emu, "emu")? };
+        handles::check(source_line, "source_line")?;
+        let line = unsafe { CStr::from_ptr(source_line) }
+            .to_str()
+            .map_err(|_| FfiError::invalid("`source_line` isn't UTF-8".to_string()))?;
+        if !addr.is_multiple_of(2) {
+            return Err(FfiError::invalid(format!("{:#x} isn't 2-byte aligned", addr)));
+        }
+
+        let line = emu.csr_names.substitute(&emu.register_aliases.substitute(line));
+        let debug_info = &emu.debug_info;
+        let image = assembler::assemble_line(&line, emu.cpu.xlen(), addr, |name| {
+            debug_info.address_of(name)
+        })
+        .map_err(FfiError::invalid)?;
+        emu.write_dram(addr, &image)
+            .map_err(|_| outside_dram(addr, image.len()))
+    })
+}
+
+/// Assemble `source` into a program image as `riscv_assemble` does. Returns `None` if it failed
+/// without an error line, e.g. because it was cancelled.
+fn assemble_source(source: &str) -> Option<AssembleResult> {
+    match assemble_rv32(source) {
+        Ok(image) => Some(Ok(image)),
+        Err(error) if error.line == 0 => None,
+        Err(error) => Some(Err(error.line)),
+    }
+}
+
+/// Create an assembler session and write it into `session`. Its cache returns the result of
+/// assembling an unchanged source with the same policy and options instantly.
+#[no_mangle]
+pub extern "C" fn assembler_session_create(session: *mut *mut AssemblerSession) -> RvStatus {
+    status::guard(|| {
+        let session = unsafe { non_null(session, "session")? };
+
+        *session = handles::create(AssemblerSession::new());
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn assembler_session_destroy(session: *mut AssemblerSession) -> RvStatus {
+    status::guard(|| unsafe { handles::destroy(session, "session") })
+}
+
+/// Same as `riscv_assemble`, but the result is cached in `session`. `policy` is an ISA policy as
+/// JSON the source must follow, or null. `options` is a combination of the `ASSEMBLE_*` flags,
+/// e.g. 1 for micro-ISA sources. An invalid policy fails with `error_line` set to 0.
+#[no_mangle]
+pub extern "C" fn assembler_session_assemble(
+    session: *mut AssemblerSession,
+    source: *const c_char,
+    policy: *const c_char,
+    options: u32,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let session = unsafe { non_null(session, "session")? };
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let policy = if policy.is_null() {
+            None
+        } else {
+            Some(policy_arg(policy)?)
+        };
+        let text = string_arg(source, "source")?;
+
+        // Equivalent policies share results whatever their formatting.
+        let canonical = policy.as_ref().map(|policy| policy.to_json().to_string());
+        let key = cache_key(&text, canonical.as_deref(), options);
+        let result = session.assemble(key, || {
+            let source = if options & ASSEMBLE_MICRO_ISA != 0 {
+                match micro_isa::translate(&text) {
+                    Ok(translated) => translated,
+                    Err((line, _)) => return Some(Err(line)),
+                }
+            } else {
+                text.clone()
+            };
+            if let Some(Err(violation)) = policy.as_ref().map(|policy| policy.check_source(&source))
+            {
+                return Some(Err(violation.line));
+            }
+            assemble_source(&source)
+        });
+
+        match result {
+            Some(Ok(bytes)) => {
+                *image.len = leak_image(bytes, image.out);
+                Ok(())
+            }
+            Some(Err(line)) => image.fail_at(line, format!("line {} doesn't assemble", line)),
+            None => Err(FfiError::invalid("the source doesn't assemble".to_string())),
+        }
+    })
+}
+
+/// Keep the results of `session` in the directory `dir` too, using at most `max_bytes` bytes,
+/// so they survive restarts. The directory is created if needed and can be shared by several
+/// processes. A null `dir` keeps results in memory only. Fails if the directory can't be
+/// created.
+#[no_mangle]
+pub extern "C" fn assembler_session_set_disk_cache(
+    session: *mut AssemblerSession,
+    dir: *const c_char,
+    max_bytes: u64,
+) -> RvStatus {
+    status::guard(|| {
+        let session = unsafe { non_null(session, "session")? };
+
+        let disk = if dir.is_null() {
+            None
+        } else {
+            let dir = string_arg(dir, "dir")?;
+            let disk = DiskCache::open(&dir, max_bytes)
+                .map_err(|err| FfiError::invalid(format!("can't open {}: {}", dir, err)))?;
+            Some(disk)
+        };
+        session.set_disk_cache(disk);
+        Ok(())
+    })
+}
+
+/// Copy the number of cache hits and misses of `session` into `hits` and `misses`.
+#[no_mangle]
+pub extern "C" fn assembler_session_stats(
+    session: *mut AssemblerSession,
+    hits: *mut u64,
+    misses: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let session = unsafe { non_null(session, "session")? };
+        let hits = unsafe { non_null(hits, "hits")? };
+        let misses = unsafe { non_null(misses, "misses")? };
+
+        *hits = session.hits();
+        *misses = session.misses();
+        Ok(())
+    })
+}
+
+/// Forget the results cached in `session`.
+#[no_mangle]
+pub extern "C" fn assembler_session_clear(session: *mut AssemblerSession) -> RvStatus {
+    status::guard(|| {
+        let session = unsafe { non_null(session, "session")? };
+
+        session.clear();
+        Ok(())
+    })
+}
+
+/// Same as `riscv_assemble`, but calls `progress` (if not null) before each line with the number
+/// of lines done and the number of lines, and gives up when `token` (if not null) is cancelled.
+/// A cancelled call fails with `error_line` set to 0.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_cancellable(
+    instruction: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+    progress: Option<ProgressFn>,
+    user_data: *mut c_void,
+    token: *const CancelToken,
+) -> RvStatus {
+    status::guard(|| {
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let token = unsafe { token.as_ref() };
+        let source = prepare_source(instruction)?;
+
+        let assembled = assembler::assemble_with(&source, Xlen::Rv32, |done, total| {
+            if token.is_some_and(|token| token.take()) {
+                return false;
+            }
+            if let Some(progress) = progress {
+                progress(user_data, done, total);
+            }
+            true
+        });
+        image.hand_over(assembled)
+    })
+}
+
+/// Same as `riscv_assemble`, but also copies the labels of the program into `symbols` as JSON: a
+/// list of `{"name", "addr", "section", "line", "referenced"}` objects ordered by address, where
+/// `addr` is relative to `DRAM_BASE`, `section` is "text" or "data" and `referenced` tells
+/// whether the program refers to the label, e.g.
+/// `{"name": "loop", "addr": 8, "section": "text", "line": 3, "referenced": true}`. The full
+/// length of the JSON is written to `symbols_len_out`, or 0 if the source doesn't assemble.
+#[no_mangle]
+pub extern "C" fn riscv_assemble_with_symbols(
+    source: *const c_char,
+    out: *mut *mut u8,
+    len: *mut u64,
+    error_line: *mut u64,
+    symbols: *mut u8,
+    symbols_len: usize,
+    symbols_len_out: *mut u64,
+) -> RvStatus {
+    status::guard(|| {
+        let image = unsafe { ImageOut::new(out, len, error_line)? };
+        let symbols_len_out = unsafe { non_null(symbols_len_out, "symbols_len_out")? };
+        *symbols_len_out = 0;
+        let source = prepare_source(source)?;
+
+        let (program, labels) = match assembler::assemble_with_symbols(&source, Xlen::Rv32) {
+            Ok(assembled) => assembled,
+            Err(error) => return image.fail_at(error.line, error.message),
+        };
+        let json = labels
+            .iter()
+            .map(|symbol| {
+                serde_json::json!({
+                    "name": symbol.name,
+                    "addr": symbol.addr,
+                    "section": match symbol.section {
+                        Section::Text => "text",
+                        Section::Data => "data",
+                    },
+                    "line": symbol.line,
+                    "referenced": symbol.referenced,
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+        let json = serde_json::Value::from(json).to_string();
+        *symbols_len_out = copy_string(&json, symbols, symbols_len);
+        image.hand_over(Ok(program))
+    })
+}
+
+/// Copy the metrics of the engine, counted across every emulator of the process, into `out` as
+/// UTF-8 JSON (not NUL-terminated): an object of the counters, e.g. `"runs": 3`, with the
+/// average `mips` of the runs and the `cache_hit_rate` of the assembler sessions. Writes the
+/// full length of the JSON into `full_len`.
+#[no_mangle]
+pub extern "C" fn metrics_get_json(out: *mut u8, len: usize, full_len: *mut u64) -> RvStatus {
+    status::guard(|| {
+        let metrics = metrics::snapshot();
+        let json = serde_json::json!({
+            "assemblies": metrics.assemblies,
+            "failed_assemblies": metrics.failed_assemblies,
+            "runs": metrics.runs,
+            "run_steps": metrics.run_steps,
+            "run_time_ns": metrics.run_time_ns,
+            "mips": metrics.mips(),
+            "cache_hits": metrics.cache_hits,
+            "cache_misses": metrics.cache_misses,
+            "cache_hit_rate": metrics.cache_hit_rate(),
+            "grades": metrics.grades,
+            "passed_grades": metrics.passed_grades,
+            "graded_cases": metrics.graded_cases,
+            "passed_cases": metrics.passed_cases,
+        });
+        write_string(&json.to_string(), out, len, full_len)
+    })
+}
+
+/// Copy the metrics of the engine into `out` in the Prometheus text exposition format, ready to
+/// be served to a scraper. Writes the full length of the text into `full_len`.
+#[no_mangle]
+pub extern "C" fn metrics_get_prometheus(
+    out: *mut u8,
+    len: usize,
+    full_len: *mut u64,
+) -> RvStatus {
+    status::guard(|| write_string(&metrics::snapshot().to_prometheus(), out, len, full_len))
+}
+
+/// Set every metric of the engine back to 0.
+#[no_mangle]
+pub extern "C" fn metrics_reset() -> RvStatus {
+    status::guard(|| {
+        metrics::reset();
+        Ok(())
+    })
+}
+
+/// Return the source argument `source` with standard CSR names replaced with their addresses,
+/// which is how the assembler takes them. Fails if it's null or isn't UTF-8.
+fn prepare_source(source: *const c_char) -> Result<String, FfiError> {
+    if source.is_null() {
+        return Err(FfiError::null("source"));
+    }
+    let source = unsafe { CStr::from_ptr(source) }
+        .to_str()
+        .map_err(|_| FfiError::invalid("the source isn't UTF-8".to_string()))?;
+    Ok(substitute_csr_names(source))
+}
+
+/// Return `source` with standard CSR names replaced with their addresses.
+fn substitute_csr_names(source: &str) -> String {
+    let csr_names = CsrNames::new();
+    source
+        .lines()
+        .map(|line| csr_names.substitute(line.trim()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Assemble the RV32I source `source` as `riscv_assemble` does.
+fn assemble_rv32(source: &str) -> Result<Vec<u8>, AssembleError> {
+    assembler::assemble_with(&substitute_csr_names(source), Xlen::Rv32, |_, _| true)
+}
+
+/// Hand the program image `image` over to the host through `out`. Returns its length.
+fn leak_image(image: Vec<u8>, out: &mut *mut u8) -> u64 {
+    let len = image.len();
+    let mut boxed = image.into_boxed_slice();
+    *out = boxed.as_mut_ptr();
+    std::mem::forget(boxed);
+
+    len as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+
+    use rvemu::devices::draw_queue::DrawKind;
+    use rvemu::lockstep::NodeState;
+    use rvemu::trace::AccessKind;
+    use crate::*;
+
+    /// Create an emulator.
+    fn create() -> *mut Emulator {
+        fetch(|emu| emulator_create(emu))
+    }
+
+    /// Return what `get` writes into its out-parameter, checking that it succeeds.
+    fn fetch<T>(get: impl FnOnce(*mut T) -> RvStatus) -> T {
+        let mut value = MaybeUninit::uninit();
+        assert_eq!(RvStatus::Ok, get(value.as_mut_ptr()));
+        unsafe { value.assume_init() }
+    }
+
+    /// Return the value of the integer register `index`.
+    fn register(emu: *mut Emulator, index: u64) -> u64 {
+        fetch(|value| emulator_get_register(emu, index, value))
+    }
+
+    /// Return the pc.
+    fn pc(emu: *mut Emulator) -> u64 {
+        fetch(|pc| emulator_get_pc(emu, pc))
+    }
+
+    #[test]
+    fn memory_is_read_as_typed_arrays() {
+        let emu = create();
+        let values = [3i32, -1, 7];
+        let bytes = values
+            .iter()
+            .flat_map(|v| v.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+        unsafe { emu.as_mut().unwrap().cpu.bus.write_dram(DRAM_BASE, &bytes) };
+
+        let mut out = [0i32; 3];
+        assert_eq!(RvStatus::Ok, emulator_read_array_i32(emu, DRAM_BASE, out.as_mut_ptr(), 3));
+        assert_eq!(values, out);
+        let mut halves = [0u16; 2];
+        let status = emulator_read_array_u16(emu, DRAM_BASE + 4, halves.as_mut_ptr(), 2);
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!([0xffff, 0xffff], halves);
+
+        let floats = 1.5f64.to_le_bytes();
+        unsafe { emu.as_mut().unwrap().cpu.bus.write_dram(DRAM_BASE + 16, &floats) };
+        let mut out = [0f64; 1];
+        let status = emulator_read_array_f64(emu, DRAM_BASE + 16, out.as_mut_ptr(), 1);
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!([1.5], out);
+
+        // Elements past the end of DRAM aren't read.
+        let end = DRAM_END - 4;
+        let status = emulator_read_array_f64(emu, end, out.as_mut_ptr(), 1);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn values_are_read_and_written_in_either_byte_order() {
+        let emu = create();
+        assert_eq!(RvStatus::Ok, emulator_write_u32(emu, DRAM_BASE, 0, 0x1122_3344));
+        let mut bytes = [0u8; 4];
+        emulator_read_array_u8(emu, DRAM_BASE, bytes.as_mut_ptr(), 4);
+        assert_eq!([0x44, 0x33, 0x22, 0x11], bytes);
+
+        let value = fetch(|value| emulator_read_u32(emu, DRAM_BASE, 1, value));
+        assert_eq!(0x4433_2211, value);
+        assert_eq!(RvStatus::Ok, emulator_write_u16(emu, DRAM_BASE, 1, 0xabcd));
+        let half = fetch(|half| emulator_read_u16(emu, DRAM_BASE, 0, half));
+        assert_eq!(0xcdab, half);
+
+        let mut wide = 0u64;
+        let invalid = RvStatus::InvalidArgument;
+        assert_eq!(invalid, emulator_read_u64(emu, DRAM_BASE, 2, &mut wide));
+        assert_eq!(invalid, emulator_read_u64(emu, 0, 0, &mut wide));
+        assert_eq!(invalid, emulator_write_u64(emu, DRAM_END - 4, 0, 0));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn memory_is_read_and_written_within_dram() {
+        let emu = create();
+        let data = [1u8, 2, 3, 4];
+        assert_eq!(RvStatus::Ok, emulator_write_memory(emu, DRAM_BASE + 8, data.as_ptr(), 4));
+        let mut buf = [0u8; 6];
+        assert_eq!(RvStatus::Ok, emulator_read_memory(emu, DRAM_BASE + 7, buf.as_mut_ptr(), 6));
+        assert_eq!([0, 1, 2, 3, 4, 0], buf);
+
+        let (end, invalid) = (DRAM_END, RvStatus::InvalidArgument);
+        assert_eq!(invalid, emulator_write_memory(emu, end - 2, data.as_ptr(), 4));
+        assert_eq!(invalid, emulator_read_memory(emu, end - 2, buf.as_mut_ptr(), 4));
+        assert_eq!(invalid, emulator_read_memory(emu, 0x1000, buf.as_mut_ptr(), 1));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_guest_shares_host_buffers() {
+        let emu = create();
+        let program = assembler::assemble(
+            "lui a0, 0x40000\nlw a1, 0(a0)\naddi a1, a1, 1\nsw a1, 4(a0)",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+
+        let mut buffer = [41u8, 0, 0, 0, 0, 0, 0, 0];
+        let status = emulator_map_host_buffer(emu, 0x4000_0000, buffer.as_mut_ptr(), 8);
+        assert_eq!(RvStatus::Ok, status);
+        let status = emulator_map_host_buffer(emu, 0x4000_0004, buffer.as_mut_ptr(), 8);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        for _ in 0..4 {
+            unsafe { emu.as_mut().unwrap().step().unwrap() };
+        }
+        assert_eq!(RvStatus::Ok, emulator_unmap_host_buffer(emu, 0x4000_0000));
+        assert_eq!([41, 0, 0, 0, 42, 0, 0, 0], buffer);
+        assert_eq!(RvStatus::InvalidArgument, emulator_unmap_host_buffer(emu, 0x4000_0000));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_guest_reads_mapped_files() {
+        let path = std::env::temp_dir().join(format!("rvemu-dataset-{}", std::process::id()));
+        std::fs::write(&path, 1234u32.to_le_bytes()).unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        let emu = create();
+        let program = assembler::assemble(
+            "lui a0, 0x40000\nlw a1, 0(a0)\nsw a1, 0(a0)",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_map_file(emu, 0x4000_0000, c_path.as_ptr(), 0));
+        let status = emulator_map_file(emu, 0x4000_0000, c_path.as_ptr(), 1);
+        assert_eq!(RvStatus::InvalidArgument, status);
+
+        let emu = unsafe { emu.as_mut().unwrap() };
+        emu.step().unwrap();
+        emu.step().unwrap();
+        assert_eq!(1234, emu.cpu.xregs.read(11));
+        // The file is read-only.
+        assert!(emu.step().is_err());
+        emulator_destroy(emu);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failures_are_returned_as_status_codes() {
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        let status = emulator_run(std::ptr::null_mut(), 1, summary.as_mut_ptr());
+        assert_eq!(RvStatus::NullPointer, status);
+        let mut message = [0u8; 32];
+        let len = fetch(|len| emulator_last_error_message(message.as_mut_ptr(), 32, len));
+        assert_eq!(b"`emu` is null", &message[..len as usize]);
+
+        let emu = create();
+        assert_eq!(RvStatus::Ok, emulator_load_program(emu, [0; 4].as_ptr(), 4));
+        let mut inst = 0;
+        assert_eq!(RvStatus::Ok, emulator_cpu_execute(emu, &mut inst));
+        // An all-zero word is an illegal instruction.
+        assert_eq!(14, inst);
+        assert_eq!(
+            RvStatus::NullPointer,
+            emulator_cpu_execute(emu, std::ptr::null_mut())
+        );
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn instructions_are_run_in_batches() {
+        let emu = create();
+        let program =
+            assembler::assemble("addi a0, a0, 1\naddi a0, a0, 1\necall", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+
+        let mut reason = StopReason::Cancelled;
+        let mut executed = 0;
+        assert_eq!(RvStatus::Ok, emulator_run_batch(emu, 1, &mut reason, &mut executed));
+        assert_eq!((StopReason::StepLimit, 1), (reason, executed));
+        assert_eq!(RvStatus::Ok, emulator_run_batch(emu, 100, &mut reason, &mut executed));
+        assert_eq!((StopReason::Yielded, 2), (reason, executed));
+        assert_eq!(2, register(emu, 10));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn states_are_saved_and_restored() {
+        let emu = create();
+        let program = assembler::assemble("addi a0, a0, 1\necall", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+
+        let mut buf = std::ptr::null_mut();
+        let mut len = 0;
+        assert_eq!(RvStatus::Ok, emulator_save_state(emu, &mut buf, &mut len));
+        emulator_run(emu, 10, MaybeUninit::uninit().as_mut_ptr());
+        assert_eq!(1, register(emu, 10));
+
+        assert_eq!(RvStatus::Ok, emulator_restore_state(emu, buf, len));
+        assert_eq!(0, register(emu, 10));
+        assert_eq!(RvStatus::InvalidArgument, emulator_restore_state(emu, buf, len - 1));
+        assert_eq!("the snapshot is truncated", status::last_error());
+        free_state_buffer(buf, len);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn instructions_are_disassembled_into_c_strings() {
+        let mut buf = [0x7f as c_char; 32];
+        let len = fetch(|len| riscv_disassemble(0xfff5_0513, 0, buf.as_mut_ptr(), buf.len(), len));
+        assert_eq!(15, len);
+        let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!("addi a0, a0, -1", text.to_str().unwrap());
+
+        let len = fetch(|len| riscv_disassemble(0xfff5_0513, 0, buf.as_mut_ptr(), 5, len));
+        assert_eq!(15, len);
+        let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!("addi", text.to_str().unwrap());
+
+        fetch(|len| riscv_disassemble(0xfe05_1ee3, 0x8000_0010, buf.as_mut_ptr(), buf.len(), len));
+        let text = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!("bne a0, zero, 0x8000000c", text.to_str().unwrap());
+    }
+
+    extern "C" fn collect_command(user_data: *mut c_void, command: *const DrawCommand) {
+        let commands = unsafe { (user_data as *mut Vec<DrawCommand>).as_mut().unwrap() };
+        commands.push(unsafe { *command });
+    }
+
+    #[test]
+    fn draw_commands_are_dispatched_to_the_host() {
+        let emu = create();
+        let program = assembler::assemble(
+            "lui a0, 0x10005\naddi t0, zero, 3\nsw t0, 0(a0)\nsw zero, 28(a0)\nsw zero, 28(a0)",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut reason = StopReason::Cancelled;
+        let mut executed = 0;
+        emulator_run_batch(emu, 5, &mut reason, &mut executed);
+
+        let mut commands: Vec<DrawCommand> = Vec::new();
+        let user_data = &mut commands as *mut Vec<DrawCommand> as *mut c_void;
+        let dispatch =
+            |draw| fetch(|removed| emulator_dispatch_draw_commands(emu, draw, user_data, removed));
+        assert_eq!(2, dispatch(Some(collect_command)));
+        assert_eq!(2, commands.len());
+        assert_eq!(DrawKind::SetPalette, commands[0].kind);
+        assert_eq!(0, dispatch(None));
+        assert_eq!(0, fetch(|dropped| emulator_draw_commands_dropped(emu, dropped)));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn emulators_run_in_lockstep() {
+        let lockstep = fetch(|lockstep| lockstep_create(2, 10, lockstep));
+        assert_eq!(RvStatus::Ok, lockstep_link(lockstep, 0, 1));
+        assert_eq!(RvStatus::InvalidArgument, lockstep_link(lockstep, 0, 2));
+        let (a, b) = (create(), create());
+        let mut played = 0;
+        assert_eq!(
+            RvStatus::InvalidArgument,
+            lockstep_run(lockstep, [a].as_ptr(), 1, 1, &mut played)
+        );
+        assert_eq!(
+            RvStatus::InvalidArgument,
+            lockstep_run(lockstep, [a, a].as_ptr(), 2, 1, &mut played)
+        );
+
+        // Both emulators run an empty program and trap at once.
+        assert_eq!(RvStatus::Ok, lockstep_run(lockstep, [a, b].as_ptr(), 2, 3, &mut played));
+        assert_eq!(1, played);
+        let status = fetch(|status| lockstep_get_node_status(lockstep, 1, status));
+        assert_eq!(NodeState::Trapped, status.state);
+        let mut status = MaybeUninit::<NodeStatus>::uninit();
+        assert_eq!(
+            RvStatus::InvalidArgument,
+            lockstep_get_node_status(lockstep, 2, status.as_mut_ptr())
+        );
+        emulator_destroy(a);
+        emulator_destroy(b);
+        lockstep_destroy(lockstep);
+    }
+
+    #[test]
+    fn registers_are_formatted_as_json() {
+        let emu = create();
+        assert_eq!(RvStatus::Ok, emulator_set_register(emu, 5, -2i64 as u64));
+        let mut out = [0u8; 128];
+        let mut format = |file, index| {
+            let mut len = 0;
+            let status =
+                emulator_format_register(emu, file, index, out.as_mut_ptr(), 128, &mut len);
+            (status, serde_json::from_slice(&out[..len as usize]).ok())
+        };
+        let (status, json): (_, Option<serde_json::Value>) = format(0, 5);
+        let json = json.unwrap();
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!("-2", json["signed"]);
+        assert_eq!("0xfffffffffffffffe", json["hex"]);
+
+        let json = format(1, 0).1.unwrap();
+        assert_eq!("0.0", json["f64"]);
+        assert_eq!(false, json["nan_boxed"]);
+        assert_eq!(RvStatus::InvalidArgument, format(2, 0).0);
+        assert_eq!(RvStatus::InvalidArgument, format(0, 32).0);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn labels_are_returned_with_the_program() {
+        let source = CString::new(
+            "start: j end\nunused:\naddi a0, a0, 1\nend: ecall\n.data\nvalue: .word end",
+        )
+        .unwrap();
+        let mut out = std::ptr::null_mut();
+        let (mut len, mut error_line) = (0, 0);
+        let mut symbols = [0u8; 512];
+        let mut symbols_len = 0;
+        let status = riscv_assemble_with_symbols(
+            source.as_ptr(),
+            &mut out,
+            &mut len,
+            &mut error_line,
+            symbols.as_mut_ptr(),
+            symbols.len(),
+            &mut symbols_len,
+        );
+        assert_eq!((RvStatus::Ok, 20), (status, len));
+        let _ = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(out, len as usize)) };
+        let json: serde_json::Value =
+            serde_json::from_slice(&symbols[..symbols_len as usize]).unwrap();
+        assert_eq!(
+            serde_json::json!([
+                {"name": "start", "addr": 0, "section": "text", "line": 1, "referenced": false},
+                {"name": "unused", "addr": 4, "section": "text", "line": 2, "referenced": false},
+                {"name": "end", "addr": 8, "section": "text", "line": 4, "referenced": true},
+                {"name": "value", "addr": 16, "section": "data", "line": 6, "referenced": false},
+            ]),
+            json
+        );
+
+        let source = CString::new("j nowhere").unwrap();
+        let status = riscv_assemble_with_symbols(
+            source.as_ptr(),
+            &mut out,
+            &mut len,
+            &mut error_line,
+            symbols.as_mut_ptr(),
+            symbols.len(),
+            &mut symbols_len,
+        );
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!((0, 1, 0), (len, error_line, symbols_len));
+    }
+
+    #[test]
+    fn the_data_section_is_linked_apart() {
+        let source = CString::new("la a0, value\nlw a0, 0(a0)\n.data\nvalue: .word 7").unwrap();
+        let isa = CString::new("rv32i").unwrap();
+        let (mut text, mut data) = (std::ptr::null_mut(), std::ptr::null_mut());
+        let (mut len, mut data_len, mut error_line) = (0, 0, 0);
+        let data_addr = DRAM_BASE + 0x1000;
+        let status = riscv_assemble_sections(
+            source.as_ptr(),
+            isa.as_ptr(),
+            data_addr,
+            &mut text,
+            &mut len,
+            &mut data,
+            &mut data_len,
+            &mut error_line,
+        );
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!((12, 4, 0), (len, data_len, error_line));
+        let text = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(text, len as usize)) };
+        let data = std::ptr::slice_from_raw_parts_mut(data, data_len as usize);
+        let data = unsafe { Box::from_raw(data) };
+        let emu = create();
+        emulator_load_program(emu, text.as_ptr(), text.len());
+        let status = emulator_write_memory(emu, data_addr, data.as_ptr(), data.len());
+        assert_eq!(RvStatus::Ok, status);
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        for _ in 0..3 {
+            assert_eq!(RvStatus::Ok, emulator_step(emu, summary.as_mut_ptr()));
+        }
+        assert_eq!(7, register(emu, 10));
+        emulator_destroy(emu);
+
+        let source = CString::new("li a0, 1").unwrap();
+        let mut text = std::ptr::null_mut();
+        let mut data = std::ptr::NonNull::dangling().as_ptr();
+        let status = riscv_assemble_sections(
+            source.as_ptr(),
+            isa.as_ptr(),
+            data_addr,
+            &mut text,
+            &mut len,
+            &mut data,
+            &mut data_len,
+            &mut error_line,
+        );
+        assert_eq!((RvStatus::Ok, 4, 0), (status, len, data_len));
+        assert!(data.is_null());
+        let _ = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(text, len as usize)) };
+    }
+
+    #[test]
+    fn emulators_count_toward_the_total_memory_usage() {
+        let emu = create();
+        let handle = fetch(|handle| emulator_handle_create(handle));
+        assert!(!emu.is_null());
+        assert_ne!(0, handle);
+        assert!(fetch(|bytes| emulator_total_memory_usage(bytes)) >= 2 * rvemu::dram::DRAM_SIZE);
+        emulator_destroy(emu);
+        emulator_handle_destroy(handle);
+    }
+
+    #[test]
+    fn metrics_count_runs_across_emulators() {
+        let emu = create();
+        let program = [0x13, 0, 0, 0, 0x13, 0, 0, 0];
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        emulator_destroy(emu);
+
+        let mut out = [0u8; 512];
+        let len = fetch(|len| metrics_get_json(out.as_mut_ptr(), out.len(), len));
+        let json: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        assert!(json["runs"].as_u64().unwrap() >= 1);
+        assert!(json["run_steps"].as_u64().unwrap() >= 2);
+        assert!(json["mips"].is_f64());
+
+        let mut out = [0u8; 4096];
+        let len = fetch(|len| metrics_get_prometheus(out.as_mut_ptr(), out.len(), len));
+        let text = std::str::from_utf8(&out[..len as usize]).unwrap();
+        assert!(text.contains("# TYPE rvemu_runs_total counter\n"));
+    }
+
+    #[test]
+    fn the_isa_string_selects_the_m_extension() {
+        let source = CString::new("mul a0, a0, a1\nremu a0, a0, a1").unwrap();
+        let mut out = std::ptr::null_mut();
+        let (mut len, mut error_line) = (0, 0);
+        let status = riscv_assemble(source.as_ptr(), &mut out, &mut len, &mut error_line);
+        assert_eq!((RvStatus::InvalidArgument, 1), (status, error_line));
+
+        let isa = CString::new("rv32im").unwrap();
+        let status =
+            riscv_assemble_isa(source.as_ptr(), isa.as_ptr(), &mut out, &mut len, &mut error_line);
+        assert_eq!((RvStatus::Ok, 8, 0), (status, len, error_line));
+        let image = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(out, len as usize)) };
+        assert_eq!([0x33, 0x05, 0xb5, 0x02], image[..4]);
+
+        let isa = CString::new("rv32imafd").unwrap();
+        let status =
+            riscv_assemble_isa(source.as_ptr(), isa.as_ptr(), &mut out, &mut len, &mut error_line);
+        assert_eq!((RvStatus::InvalidArgument, 0), (status, error_line));
+    }
+
+    #[test]
+    fn the_c_extension_emits_compressed_instructions() {
+        let source = CString::new("addi a0, a0, 1\nlui a1, 0x12345").unwrap();
+        let isa = CString::new("rv32ic").unwrap();
+        let mut out = std::ptr::null_mut();
+        let (mut len, mut error_line) = (0, 0);
+        let status =
+            riscv_assemble_isa(source.as_ptr(), isa.as_ptr(), &mut out, &mut len, &mut error_line);
+        assert_eq!((RvStatus::Ok, 6, 0), (status, len, error_line));
+        let image = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(out, len as usize)) };
+        assert_eq!([0x05, 0x05, 0xb7, 0x55, 0x34, 0x12], image[..]);
+    }
+
+    #[test]
+    fn steps_are_annotated() {
+        let emu = create();
+        let program = assembler::assemble("addi a0, zero, 7", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut annotation = MaybeUninit::<StepAnnotation>::uninit();
+        assert_eq!(
+            RvStatus::Ok,
+            emulator_step_with_annotations(emu, annotation.as_mut_ptr())
+        );
+        let annotation = unsafe { annotation.assume_init() };
+        assert_eq!("addi a0, zero, 7", annotation.disassembly());
+        assert_eq!((10, 7), (annotation.rd, annotation.result));
+        assert_eq!(
+            RvStatus::NullPointer,
+            emulator_step_with_annotations(emu, std::ptr::null_mut())
+        );
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn executed_instructions_are_traced() {
+        let emu = create();
+        let program =
+            assembler::assemble("addi a0, zero, 7\nbeq zero, zero, 8", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut trace = MaybeUninit::<ExecuteTrace>::uninit();
+        assert_eq!(RvStatus::Ok, emulator_cpu_execute_traced(emu, trace.as_mut_ptr()));
+        let trace = unsafe { trace.assume_init() };
+        assert_eq!(0x0070_0513, trace.executed_instruction);
+        assert_eq!((DRAM_BASE, DRAM_BASE + 4), (trace.old_pc, trace.new_pc));
+        assert_eq!(1, trace.change_count);
+        let change = RegisterChange {
+            index: 10,
+            old: 0,
+            new: 7,
+        };
+        assert_eq!(change, trace.changes[0]);
+
+        let mut trace = MaybeUninit::<ExecuteTrace>::uninit();
+        emulator_cpu_execute_traced(emu, trace.as_mut_ptr());
+        let trace = unsafe { trace.assume_init() };
+        assert_eq!((0, DRAM_BASE + 12), (trace.change_count, trace.new_pc));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_next_instructions_are_previewed() {
+        let emu = create();
+        let program = [0x13u8, 0, 0, 0, 0x73, 0, 0, 0, 0x13, 0, 0, 0];
+        emulator_load_program(emu, program.as_ptr(), program.len());
+
+        let mut out = [MaybeUninit::<PreviewedInstruction>::uninit(); 4];
+        let out_ptr = out.as_mut_ptr() as *mut PreviewedInstruction;
+        assert_eq!(2, fetch(|decoded| emulator_peek_next(emu, out_ptr, 4, decoded)));
+        let ecall = unsafe { out[1].assume_init() };
+        assert_eq!((DRAM_BASE + 4, 0x73), (ecall.pc, ecall.inst));
+        assert_eq!(preview::Flow::Stop, ecall.flow);
+        assert_eq!(DRAM_BASE, unsafe { emu.as_ref().unwrap().cpu.pc });
+
+        emulator_destroy(emu);
+    }
+
+    extern "C" fn serve_ecall(user_data: *mut c_void, call: *const EcallArgs, a0: *mut u64) -> u32 {
+        let call = unsafe { *call };
+        unsafe { *(user_data as *mut u64) += 1 };
+        match call.number {
+            // Add the first two arguments.
+            1 => {
+                unsafe { *a0 = call.args[0] + call.args[1] };
+                0
+            }
+            2 => 1,
+            _ => 2,
+        }
+    }
+
+    #[test]
+    fn the_host_serves_environment_calls() {
+        let emu = create();
+        let program = assembler::assemble(
+            "addi a7, zero, 1\naddi a0, zero, 40\naddi a1, zero, 2\necall\n\
+             addi a7, zero, 3\necall\naddi a7, zero, 2\necall\n",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut calls = 0u64;
+        let user_data = &mut calls as *mut u64 as *mut c_void;
+        assert_eq!(
+            RvStatus::Ok,
+            emulator_set_ecall_handler(emu, Some(serve_ecall), user_data)
+        );
+
+        let summary = unsafe { emu.as_mut().unwrap().run(100) };
+        assert_eq!(StopReason::Yielded, summary.reason);
+        assert_eq!((2, 42), (calls, register(emu, 10)));
+        let summary = unsafe { emu.as_mut().unwrap().run(100) };
+        assert_eq!((StopReason::Exited, 42), (summary.reason, summary.exit_code));
+        assert_eq!(3, calls);
+
+        assert_eq!(RvStatus::Ok, emulator_set_ecall_handler(emu, None, std::ptr::null_mut()));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_uart_is_read_and_written_by_the_host() {
+        let emu = create();
+        // Echo the two received bytes, incremented.
+        let program = assembler::assemble(
+            "lui a0, 0x10000\nlbu t0, 0(a0)\naddi t0, t0, 1\nsb t0, 0(a0)\n\
+             lbu t0, 0(a0)\naddi t0, t0, 1\nsb t0, 0(a0)\n",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+
+        // The first byte is received right away, the second when the first is read.
+        let pending = fetch(|pending| emulator_write_uart_input(emu, b"AH".as_ptr(), 2, pending));
+        assert_eq!(1, pending);
+        unsafe { emu.as_mut().unwrap().run(7) };
+
+        let mut out = [0u8; 4];
+        assert_eq!(2, fetch(|moved| emulator_read_uart(emu, out.as_mut_ptr(), 4, moved)));
+        assert_eq!(b"BI", &out[..2]);
+        assert_eq!(0, fetch(|moved| emulator_read_uart(emu, out.as_mut_ptr(), 4, moved)));
+        assert_eq!(0, fetch(|dropped| emulator_uart_output_dropped(emu, dropped)));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn steps_are_simulated_without_changing_the_emulator() {
+        let emu = create();
+        let program =
+            assembler::assemble("addi a0, zero, 0\nsd a0, -8(sp)\necall\n", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_set_register(emu, 10, 7));
+
+        let mut out = vec![0u8; 4096];
+        let len = fetch(|len| emulator_simulate(emu, 8, out.as_mut_ptr(), out.len(), len));
+        let deltas: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        let deltas = deltas.as_array().unwrap();
+        assert_eq!(3, deltas.len());
+        assert_eq!(
+            serde_json::json!([{"index": 10, "old": 7, "new": 0}]),
+            deltas[0]["xregs"]
+        );
+        assert_eq!(StopReason::Yielded as u32 as u64, deltas[2]["reason"]);
+
+        assert_eq!(7, register(emu, 10));
+        assert_eq!(DRAM_BASE, unsafe { emu.as_ref().unwrap().cpu.pc });
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn csrs_are_read_written_and_listed() {
+        let emu = create();
+        assert_eq!(RvStatus::Ok, emulator_set_csr(emu, 0x305, 0x8000_0100));
+        let mut value = 0;
+        assert_eq!(RvStatus::Ok, emulator_get_csr(emu, 0x305, &mut value));
+        assert_eq!(0x8000_0100, value);
+
+        // mhartid is read-only.
+        assert_eq!(RvStatus::Ok, emulator_set_csr(emu, 0xf14, 3));
+        assert_eq!(RvStatus::Ok, emulator_get_csr(emu, 0xf14, &mut value));
+        assert_eq!(0, value);
+
+        assert_eq!(RvStatus::InvalidArgument, emulator_get_csr(emu, 0x1000, &mut value));
+        assert_eq!("0x1000 isn't a CSR address", status::last_error());
+        assert_eq!(RvStatus::NullPointer, emulator_get_csr(emu, 0x305, std::ptr::null_mut()));
+
+        let name = CString::new("score").unwrap();
+        assert_eq!(RvStatus::Ok, emulator_register_csr(emu, name.as_ptr(), 0x7c0));
+        let mut out = vec![0u8; 8192];
+        let len = fetch(|len| emulator_list_csrs(emu, out.as_mut_ptr(), out.len(), len));
+        let csrs: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        let csrs = csrs.as_array().unwrap();
+        assert_eq!(serde_json::json!({"name": "ustatus", "addr": 0}), csrs[0]);
+        assert!(csrs.contains(&serde_json::json!({"name": "mtvec", "addr": 0x305})));
+        assert!(csrs.contains(&serde_json::json!({"name": "pmpaddr63", "addr": 0x3ef})));
+        assert!(csrs.contains(&serde_json::json!({"name": "score", "addr": 0x7c0})));
+
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn register_indices_are_checked() {
+        let emu = create();
+        let mut value = 0;
+        for index in (0..=64).chain([u32::MAX as u64, i64::MAX as u64, u64::MAX].iter().copied()) {
+            let read = emulator_get_register(emu, index, &mut value);
+            let written = emulator_set_register(emu, index, 0x8000_0000u64.wrapping_add(index));
+            if index <= REGISTER_PC {
+                assert_eq!((RvStatus::Ok, RvStatus::Ok), (read, written));
+                let expected = if index == 0 { 0 } else { 0x8000_0000 + index };
+                assert_eq!(expected, register(emu, index));
+            } else {
+                assert_eq!((RvStatus::InvalidArgument, RvStatus::InvalidArgument), (read, written));
+                assert!(status::last_error().starts_with(&format!("register {} ", index)));
+            }
+        }
+        assert_eq!(0x8000_0000 + REGISTER_PC, unsafe { emu.as_ref().unwrap().cpu.pc });
+        assert_eq!(RvStatus::NullPointer, emulator_get_register(emu, 1, std::ptr::null_mut()));
+        assert_eq!(RvStatus::NullPointer, emulator_set_register(std::ptr::null_mut(), 1, 0));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn stale_and_null_handles_fail_with_a_status() {
+        let emu = create();
+        assert_eq!(RvStatus::Ok, emulator_destroy(emu));
+        assert_eq!(RvStatus::InvalidArgument, emulator_destroy(emu));
+        assert_eq!("`emu` was destroyed or never created", status::last_error());
+        let mut frames = 0;
+        if cfg!(debug_assertions) {
+            assert_eq!(RvStatus::InvalidArgument, emulator_get_frame_count(emu, &mut frames));
+            assert_eq!("`emu` was destroyed or never created", status::last_error());
+        }
+
+        let status = emulator_get_frame_count(std::ptr::null_mut(), &mut frames);
+        assert_eq!(RvStatus::NullPointer, status);
+        assert_eq!("`emu` is null", status::last_error());
+        assert_eq!(RvStatus::NullPointer, emulator_destroy(std::ptr::null_mut()));
+
+        let spec = b"{}";
+        let level = fetch(|level| level_load(spec.as_ptr(), spec.len(), level));
+        let emu = fetch(|emu| level_emulator(level, emu));
+        assert_eq!(0, register(emu, 0));
+        assert_eq!(RvStatus::InvalidArgument, emulator_destroy(emu));
+        assert_eq!(
+            "`emu` is owned by another handle and destroyed with it",
+            status::last_error()
+        );
+        assert_eq!(RvStatus::Ok, level_destroy(level));
+        assert_eq!(RvStatus::InvalidArgument, level_destroy(level));
+    }
+
+    #[test]
+    fn templates_instantiate_levels() {
+        let spec = br#"{"registers": {"a1": 5}, "win": [{"kind": "exit", "code": 5}]}"#;
+        let template =
+            fetch(|template| emulator_template_create(spec.as_ptr(), spec.len(), template));
+        // addi a0, a1, 0; addi a7, zero, 93; ecall
+        let program = [0x0005_8513u32, 0x05d0_0893, 0x0000_0073]
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect::<Vec<u8>>();
+        let instantiate = || fetch(|level| emulator_instantiate(template, level));
+        let levels = [instantiate(), instantiate()];
+        assert_eq!(RvStatus::Ok, emulator_template_destroy(template));
+        for &level in levels.iter() {
+            let emu = fetch(|emu| level_emulator(level, emu));
+            assert_eq!(5, register(emu, 11));
+            emulator_load_program(emu, program.as_ptr(), program.len());
+            let mut summary = MaybeUninit::<RunSummary>::uninit();
+            assert_eq!(1, fetch(|won| level_run(level, summary.as_mut_ptr(), won)));
+            assert_eq!(RvStatus::Ok, level_destroy(level));
+        }
+
+        let spec = br#"{"cases": 0}"#;
+        let mut out = std::ptr::null_mut();
+        let status = emulator_template_create(spec.as_ptr(), spec.len(), &mut out);
+        assert_eq!((RvStatus::InvalidArgument, true), (status, out.is_null()));
+        let mut level = std::ptr::null_mut();
+        assert_eq!(RvStatus::NullPointer, emulator_instantiate(std::ptr::null(), &mut level));
+        assert_eq!(RvStatus::InvalidArgument, emulator_template_destroy(template));
+    }
+
+    #[test]
+    fn emulators_are_used_through_handle_ids() {
+        let handle = fetch(|handle| emulator_handle_create(handle));
+        assert_ne!(0, handle);
+        // addi a0, zero, 7
+        let program = 0x0070_0513u32.to_le_bytes();
+        let status = emulator_handle_load_program(handle, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, status);
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        assert_eq!(RvStatus::Ok, emulator_handle_step(handle, summary.as_mut_ptr()));
+        let mut value = 0;
+        assert_eq!(RvStatus::Ok, emulator_handle_get_register(handle, 10, &mut value));
+        assert_eq!(7, value);
+        assert_eq!(RvStatus::Ok, emulator_handle_set_register(handle, 11, 9));
+
+        let mut emu = std::ptr::null_mut();
+        assert_eq!(RvStatus::Ok, emulator_handle_pointer(handle, &mut emu));
+        assert_eq!(9, register(emu, 11));
+
+        let mut bytes = [0u8; 4];
+        let status = emulator_handle_read_memory(handle, DRAM_BASE, bytes.as_mut_ptr(), 4);
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!(program, bytes);
+        let status = emulator_handle_write_memory(handle, DRAM_END, bytes.as_ptr(), 4);
+        assert_eq!(RvStatus::InvalidArgument, status);
+
+        assert_eq!(RvStatus::Ok, emulator_handle_destroy(handle));
+        let stale = format!("`handle` {} was destroyed or never created", handle);
+        assert_eq!(RvStatus::InvalidArgument, emulator_handle_destroy(handle));
+        assert_eq!(stale, status::last_error());
+        let status = emulator_handle_run(handle, 10, summary.as_mut_ptr());
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!(stale, status::last_error());
+        let other = fetch(|handle| emulator_handle_create(handle));
+        assert_ne!(handle, other);
+        assert_eq!(RvStatus::Ok, emulator_handle_destroy(other));
+
+        // An id doesn't outlive its emulator destroyed by pointer.
+        let handle = fetch(|handle| emulator_handle_create(handle));
+        let emu = fetch(|emu| emulator_handle_pointer(handle, emu));
+        assert_eq!(RvStatus::Ok, emulator_destroy(emu));
+        let status = emulator_handle_get_register(handle, 10, &mut value);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        let stale = format!("`handle` {} was destroyed or never created", handle);
+        assert_eq!(stale, status::last_error());
+    }
+
+    #[test]
+    fn registers_are_32_bits_wide_in_rv32() {
+        let emu = create();
+        assert_eq!(RvStatus::InvalidArgument, emulator_set_xlen(emu, 16));
+        assert_eq!("XLEN 16 isn't 32 or 64", status::last_error());
+        assert_eq!(RvStatus::Ok, emulator_set_xlen(emu, 32));
+
+        let program = assembler::assemble("add a1, a0, a0", Xlen::Rv32).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_set_register(emu, 10, 0x1_8000_0000));
+        assert_eq!(0x8000_0000, register(emu, 10));
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        assert_eq!(RvStatus::Ok, emulator_step(emu, summary.as_mut_ptr()));
+        assert_eq!(0, register(emu, 11));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn text_is_reloaded_with_its_symbols() {
+        let emu = create();
+        let program = assembler::assemble("loop:\naddi a0, a0, 1\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let name = CString::new("loop").unwrap();
+        emulator_add_symbol(emu, name.as_ptr(), DRAM_BASE);
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 3, summary.as_mut_ptr());
+        assert_eq!(DRAM_BASE + 4, register(emu, REGISTER_PC));
+
+        let program =
+            assembler::assemble("nop\nloop:\naddi a0, a0, 2\nj loop", Xlen::Rv64).unwrap();
+        let symbols = br#"[{"name": "loop", "addr": 4, "line": 2, "referenced": true}]"#;
+        let status = emulator_reload_text(
+            emu,
+            program.as_ptr(),
+            program.len(),
+            symbols.as_ptr(),
+            symbols.len(),
+        );
+        assert_eq!(RvStatus::Ok, status);
+        assert_eq!(DRAM_BASE + 8, register(emu, REGISTER_PC));
+        assert_eq!(2, register(emu, 10));
+
+        let symbols = b"[{\"addr\": 4}]";
+        let status = emulator_reload_text(
+            emu,
+            program.as_ptr(),
+            program.len(),
+            symbols.as_ptr(),
+            symbols.len(),
+        );
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert!(status::last_error().starts_with("invalid symbols: missing field `name`"));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_pc_is_read_and_moved() {
+        let emu = create();
+        let program = assembler::assemble("addi a0, a0, 1\naddi a0, a0, 2", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(DRAM_BASE, pc(emu));
+
+        assert_eq!(RvStatus::Ok, emulator_set_pc(emu, DRAM_BASE + 4));
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_step(emu, summary.as_mut_ptr());
+        assert_eq!(2, register(emu, 10));
+        assert_eq!(DRAM_BASE + 8, pc(emu));
+
+        assert_eq!(RvStatus::InvalidArgument, emulator_set_pc(emu, DRAM_BASE + 1));
+        assert_eq!("0x80000001 isn't 2-byte aligned", status::last_error());
+        assert_eq!(DRAM_BASE + 8, pc(emu));
+        let mut value = 0;
+        assert_eq!(RvStatus::NullPointer, emulator_get_pc(std::ptr::null_mut(), &mut value));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn a_background_run_is_read_while_it_runs_and_paused() {
+        let emu = create();
+        let program = assembler::assemble("loop:\naddi a0, a0, 1\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let running = |emu| fetch(|running| emulator_is_running(emu, running));
+        assert_eq!(RvStatus::InvalidArgument, emulator_pause(emu));
+        assert_eq!(0, running(emu));
+
+        assert_eq!(RvStatus::Ok, emulator_run_async(emu, u64::MAX));
+        assert_eq!(1, running(emu));
+        assert_eq!(RvStatus::InvalidArgument, emulator_run_async(emu, u64::MAX));
+        let address = emu as usize;
+        let reader = std::thread::spawn(move || {
+            let emu = address as *mut Emulator;
+            let mut last = 0;
+            for _ in 0..100 {
+                let a0 = register(emu, 10);
+                assert!(a0 >= last);
+                last = a0;
+            }
+        });
+        reader.join().unwrap();
+        let mut word = 0;
+        assert_eq!(RvStatus::Ok, emulator_read_u32(emu, DRAM_BASE, 0, &mut word));
+
+        assert_eq!(RvStatus::Ok, emulator_pause(emu));
+        assert_eq!(0, running(emu));
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        assert_eq!(RvStatus::Ok, emulator_async_summary(emu, summary.as_mut_ptr()));
+        let summary = unsafe { summary.assume_init() };
+        assert_eq!(StopReason::StepLimit, summary.reason);
+        assert_eq!(summary.steps.div_ceil(2), register(emu, 10));
+
+        // A run stops by itself at its step limit.
+        assert_eq!(RvStatus::Ok, emulator_run_async(emu, 2));
+        while running(emu) == 1 {
+            std::thread::yield_now();
+        }
+        let mut last = MaybeUninit::<RunSummary>::uninit();
+        assert_eq!(RvStatus::Ok, emulator_async_summary(emu, last.as_mut_ptr()));
+        assert_eq!(2, unsafe { last.assume_init() }.steps);
+        assert_eq!((summary.steps + 3) / 2, register(emu, 10));
+
+        // Destroying a running emulator pauses it first.
+        assert_eq!(RvStatus::Ok, emulator_run_async(emu, u64::MAX));
+        assert_eq!(RvStatus::Ok, emulator_destroy(emu));
+    }
+
+    #[test]
+    fn a_panic_ends_a_background_run() {
+        let emu = create();
+        let program = assembler::assemble("loop:\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let callback = Box::new(|_, _| panic!("progress"));
+        unsafe { emu.as_mut().unwrap() }.set_progress_callback(100, callback);
+
+        assert_eq!(RvStatus::Ok, emulator_run_async(emu, u64::MAX));
+        while fetch(|running| emulator_is_running(emu, running)) == 1 {
+            std::thread::yield_now();
+        }
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        assert_eq!(RvStatus::Panicked, emulator_async_summary(emu, summary.as_mut_ptr()));
+        assert_eq!("the emulator panicked: progress", status::last_error());
+        assert_eq!(RvStatus::Ok, emulator_destroy(emu));
+    }
+
+    #[test]
+    fn the_last_instructions_are_kept_in_a_ring_buffer() {
+        let emu = create();
+        let program = assembler::assemble("loop:\naddi a0, a0, 1\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        emulator_enable_trace(emu, 3);
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 6, summary.as_mut_ptr());
+
+        let mut out = [MaybeUninit::<TraceEntry>::uninit(); 4];
+        let out_ptr = out.as_mut_ptr() as *mut TraceEntry;
+        let count = fetch(|copied| emulator_get_trace(emu, out_ptr, 4, copied));
+        let entries = out[..count as usize]
+            .iter()
+            .map(|entry| unsafe { entry.assume_init() })
+            .map(|entry| (entry.pc - DRAM_BASE, entry.inst))
+            .collect::<Vec<(u64, u64)>>();
+        let addi = u32::from_le_bytes([program[0], program[1], program[2], program[3]]) as u64;
+        let j = u32::from_le_bytes([program[4], program[5], program[6], program[7]]) as u64;
+        assert_eq!(vec![(4, j), (0, addi), (4, j)], entries);
+
+        // Only the newest fit.
+        assert_eq!(1, fetch(|copied| emulator_get_trace(emu, out_ptr, 1, copied)));
+        assert_eq!(DRAM_BASE + 4, unsafe { out[0].assume_init() }.pc);
+
+        emulator_enable_trace(emu, 0);
+        assert_eq!(0, fetch(|copied| emulator_get_trace(emu, out_ptr, 4, copied)));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn an_instruction_is_patched_live() {
+        let emu = create();
+        let source = "loop:\naddi a0, a0, 1\nj loop\nskip:\nj skip";
+        let program = assembler::assemble(source, Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let name = CString::new("skip").unwrap();
+        emulator_add_symbol(emu, name.as_ptr(), DRAM_BASE + 8);
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        assert_eq!(1, register(emu, 10));
+
+        let line = CString::new("addi a0, a0, 10").unwrap();
+        assert_eq!(RvStatus::Ok, emulator_patch_instruction(emu, DRAM_BASE, line.as_ptr()));
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        assert_eq!(11, register(emu, 10));
+
+        // A jump target can be a loaded symbol.
+        let line = CString::new("j skip").unwrap();
+        assert_eq!(RvStatus::Ok, emulator_patch_instruction(emu, DRAM_BASE + 4, line.as_ptr()));
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        assert_eq!(DRAM_BASE + 8, register(emu, REGISTER_PC));
+
+        let line = CString::new("j nowhere").unwrap();
+        let status = emulator_patch_instruction(emu, DRAM_BASE, line.as_ptr());
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!("unknown label `nowhere`", status::last_error());
+        let line = CString::new("nop").unwrap();
+        let status = emulator_patch_instruction(emu, DRAM_BASE + 1, line.as_ptr());
+        assert_eq!(RvStatus::InvalidArgument, status);
+        let status = emulator_patch_instruction(emu, 0, line.as_ptr());
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!("the 4 bytes at 0x0 aren't all in DRAM", status::last_error());
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn an_injected_ebreak_is_hidden_from_memory_reads() {
+        let emu = create();
+        let program = assembler::assemble("addi a0, a0, 1\naddi a0, a0, 2", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_inject_ebreak(emu, DRAM_BASE + 4));
+        assert_eq!(RvStatus::InvalidArgument, emulator_inject_nops(emu, DRAM_BASE, 8));
+        assert_eq!("0x80000000 already has an injected instruction", status::last_error());
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 4, summary.as_mut_ptr());
+        assert_eq!(StopReason::Breakpoint, unsafe { summary.assume_init() }.reason);
+        let mut bytes = [0; 8];
+        assert_eq!(RvStatus::Ok, emulator_read_memory(emu, DRAM_BASE, bytes.as_mut_ptr(), 8));
+        assert_eq!(program[..], bytes[..]);
+
+        assert_eq!(RvStatus::Ok, emulator_restore_injection(emu, DRAM_BASE + 4));
+        assert_eq!(RvStatus::InvalidArgument, emulator_restore_injection(emu, DRAM_BASE + 4));
+        assert_eq!("nothing is injected at 0x80000004", status::last_error());
+        emulator_run(emu, 1, summary.as_mut_ptr());
+        assert_eq!(3, register(emu, 10));
+        assert_eq!(RvStatus::Ok, emulator_restore_injections(emu));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn a_reset_restarts_the_loaded_program() {
+        let emu = create();
+        let program = assembler::assemble("addi a0, a0, 1\naddi a1, a1, 1", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let line = CString::new("addi a0, a0, 5").unwrap();
+        assert_eq!(RvStatus::Ok, emulator_patch_instruction(emu, DRAM_BASE, line.as_ptr()));
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 1, summary.as_mut_ptr());
+        assert_eq!(5, register(emu, 10));
+
+        // Without restoring memory, the patch stays.
+        assert_eq!(RvStatus::Ok, emulator_reset(emu, 0));
+        assert_eq!(0, register(emu, 10));
+        assert_eq!(DRAM_BASE, pc(emu));
+        emulator_run(emu, 1, summary.as_mut_ptr());
+        assert_eq!(5, register(emu, 10));
+
+        assert_eq!(RvStatus::Ok, emulator_inject_ebreak(emu, DRAM_BASE + 4));
+        assert_eq!(RvStatus::Ok, emulator_reset(emu, 1));
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        assert_eq!(1, register(emu, 10));
+        assert_eq!(DRAM_BASE + 8, pc(emu));
+        assert_eq!(RvStatus::NullPointer, emulator_reset(std::ptr::null_mut(), 1));
+        emulator_destroy(emu);
+    }
+
+    extern "C" fn resume_twice(user_data: *mut c_void, pc: u64) -> u32 {
+        let calls = unsafe { &mut *(user_data as *mut Vec<u64>) };
+        calls.push(pc);
+        (calls.len() > 2) as u32
+    }
+
+    #[test]
+    fn ebreak_stops_traps_or_calls_the_host() {
+        let emu = create();
+        let program = assembler::assemble(
+            "loop:\n\
+             addi a0, a0, 1\n\
+             ebreak\n\
+             j loop",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 10, summary.as_mut_ptr());
+        assert_eq!(StopReason::Breakpoint, unsafe { summary.assume_init() }.reason);
+        assert_eq!(DRAM_BASE + 4, pc(emu));
+
+        let mut calls = Vec::<u64>::new();
+        let user_data = &mut calls as *mut Vec<u64> as *mut c_void;
+        let status = emulator_set_ebreak_behavior(emu, 2, Some(resume_twice), user_data);
+        assert_eq!(RvStatus::Ok, status);
+        emulator_run(emu, 100, summary.as_mut_ptr());
+        assert_eq!(StopReason::Breakpoint, unsafe { summary.assume_init() }.reason);
+        assert_eq!(vec![DRAM_BASE + 4; 3], calls);
+        assert_eq!(3, register(emu, 10));
+
+        // The guest takes the trap at mtvec, 0 here.
+        assert_eq!(RvStatus::Ok, emulator_set_ebreak_behavior(emu, 1, None, std::ptr::null_mut()));
+        emulator_run(emu, 1, summary.as_mut_ptr());
+        assert_eq!(0, pc(emu));
+
+        let status = emulator_set_ebreak_behavior(emu, 2, None, std::ptr::null_mut());
+        assert_eq!(RvStatus::NullPointer, status);
+        let status = emulator_set_ebreak_behavior(emu, 3, None, std::ptr::null_mut());
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!("3 isn't an ebreak behavior", status::last_error());
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn semihosting_prints_and_exits() {
+        let emu = create();
+        let program = assembler::assemble(
+            "auipc a1, 0\n\
+             addi a1, a1, 0x100\n\
+             addi a0, zero, 4\n\
+             slli zero, zero, 0x1f\n\
+             ebreak\n\
+             srai zero, zero, 7\n\
+             addi a0, zero, 0x18\n\
+             addi a1, a1, 0x10\n\
+             slli zero, zero, 0x1f\n\
+             ebreak\n\
+             srai zero, zero, 7",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let text = b"hi\0";
+        emulator_write_memory(emu, DRAM_BASE + 0x100, text.as_ptr(), text.len());
+        let block = [0x20026u64, 3].iter().flat_map(|f| f.to_le_bytes()).collect::<Vec<u8>>();
+        emulator_write_memory(emu, DRAM_BASE + 0x110, block.as_ptr(), block.len());
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 100, summary.as_mut_ptr());
+        assert_eq!(StopReason::Breakpoint, unsafe { summary.assume_init() }.reason);
+
+        emulator_reset(emu, 0);
+        emulator_enable_semihosting(emu, 1);
+        emulator_run(emu, 100, summary.as_mut_ptr());
+        let summary = unsafe { summary.assume_init() };
+        assert_eq!((StopReason::Exited, 3), (summary.reason, summary.exit_code));
+        let mut out = [0u8; 4];
+        let moved =
+            fetch(|moved| emulator_read_semihosting_output(emu, out.as_mut_ptr(), 4, moved));
+        assert_eq!(2, moved);
+        assert_eq!(b"hi", &out[..2]);
+        assert_eq!(0, fetch(|dropped| emulator_semihosting_output_dropped(emu, dropped)));
+        let input = b"ok";
+        let pending =
+            fetch(|pending| emulator_write_semihosting_input(emu, input.as_ptr(), 2, pending));
+        assert_eq!(2, pending);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn runs_report_the_simulated_time_at_the_clock_frequency() {
+        let emu = create();
+        let program = assembler::assemble("nop\nnop\nnop\nnop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(100_000_000, fetch(|hz| emulator_clock_frequency(emu, hz)));
+        assert_eq!(RvStatus::InvalidArgument, emulator_set_clock_frequency(emu, 0));
+        assert_eq!(RvStatus::Ok, emulator_set_clock_frequency(emu, 2_000_000));
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 3, summary.as_mut_ptr());
+        let summary = unsafe { summary.assume_init() };
+        assert_eq!((3, 2_000_000), (summary.steps, summary.clock_hz));
+        assert_eq!(1.5, summary.simulated_us);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_memory_usage_of_an_emulator_adds_up() {
+        let emu = create();
+        let mut usage = MemoryUsage::default();
+        assert_eq!(RvStatus::Ok, emulator_memory_usage(emu, &mut usage));
+        assert_eq!((dram::DRAM_SIZE, 0), (usage.dram, usage.traces));
+
+        let program = assembler::assemble("nop\nnop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        assert_eq!(RvStatus::Ok, emulator_memory_usage(emu, &mut usage));
+        assert_eq!(2 * std::mem::size_of::<TraceEntry>() as u64, usage.traces);
+        assert_eq!(usage.dram + usage.traces, usage.total);
+
+        let status = emulator_memory_usage(emu, std::ptr::null_mut());
+        assert_eq!(RvStatus::NullPointer, status);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn checkpoints_are_rewound_to_and_evicted_over_the_budget() {
+        let emu = create();
+        let program = assembler::assemble("loop:\naddi a0, a0, 1\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 2, summary.as_mut_ptr());
+        let mut evictions = Vec::<(u64, u64)>::new();
+        let user_data = &mut evictions as *mut Vec<(u64, u64)> as *mut c_void;
+        let status = emulator_set_eviction_callback(emu, Some(record_eviction), user_data);
+        assert_eq!(RvStatus::Ok, status);
+        let first = fetch(|id| emulator_checkpoint(emu, id));
+        emulator_run(emu, 4, summary.as_mut_ptr());
+        let second = fetch(|id| emulator_checkpoint(emu, id));
+        assert_eq!(3, register(emu, 10));
+
+        assert_eq!(RvStatus::Ok, emulator_rewind(emu, first));
+        assert_eq!(1, register(emu, 10));
+        let mut out = [0u8; 256];
+        let len = fetch(|len| emulator_list_checkpoints(emu, out.as_mut_ptr(), out.len(), len));
+        let json: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        assert_eq!(vec![first, second], ids(&json));
+        assert_eq!(2, json[0]["retired"]);
+
+        // The second checkpoint is the least recently used.
+        let size = json[1]["size"].as_u64().unwrap();
+        assert_eq!(RvStatus::Ok, emulator_set_history_budget(emu, size + 1024));
+        assert_eq!(1, fetch(|evicted| emulator_history_truncated(emu, evicted)));
+        assert_eq!(0, fetch(|evicted| emulator_history_truncated(emu, evicted)));
+        assert_eq!(vec![(1, 0)], evictions);
+        let len = fetch(|len| emulator_list_checkpoints(emu, out.as_mut_ptr(), out.len(), len));
+        let json: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        assert_eq!(vec![first], ids(&json));
+        assert_eq!(RvStatus::InvalidArgument, emulator_rewind(emu, second));
+        assert_eq!(
+            format!("checkpoint {} was evicted or never taken", second),
+            status::last_error()
+        );
+        let mut id = 0;
+        assert_eq!(RvStatus::NullPointer, emulator_checkpoint(std::ptr::null_mut(), &mut id));
+        emulator_destroy(emu);
+    }
+
+    extern "C" fn record_eviction(user_data: *mut c_void, checkpoints: u64, entries: u64) {
+        let evictions = unsafe { &mut *(user_data as *mut Vec<(u64, u64)>) };
+        evictions.push((checkpoints, entries));
+    }
+
+    fn ids(json: &serde_json::Value) -> Vec<u64> {
+        let checkpoints = json.as_array().unwrap();
+        checkpoints.iter().map(|c| c["id"].as_u64().unwrap()).collect()
+    }
+
+    #[test]
+    fn fuel_stops_an_infinite_loop() {
+        let emu = create();
+        let program = assembler::assemble("loop:\nj loop", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_set_fuel(emu, 1000));
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, u64::MAX, summary.as_mut_ptr());
+        let summary = unsafe { summary.assume_init() };
+        assert_eq!((StopReason::FuelExhausted, 1000), (summary.reason, summary.steps));
+
+        assert_eq!(RvStatus::Ok, emulator_set_fuel(emu, 0));
+        let (mut reason, mut executed) = (StopReason::StepLimit, 0);
+        emulator_run_batch(emu, 2000, &mut reason, &mut executed);
+        assert_eq!((StopReason::StepLimit, 2000), (reason, executed));
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn guest_asserts_count_passes_and_stop_at_failures() {
+        let emu = create();
+        let program = assembler::assemble(
+            "addi a7, zero, 0x7e3\n\
+             addi a0, zero, 1\n\
+             addi a2, zero, 3\n\
+             ecall\n\
+             addi a0, zero, 0\n\
+             auipc a1, 0\n\
+             addi a1, a1, 0x100\n\
+             addi a2, zero, 8\n\
+             ecall",
+            Xlen::Rv64,
+        )
+        .unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        let text = b"sum is wrong\0";
+        emulator_write_memory(emu, DRAM_BASE + 0x114, text.as_ptr(), text.len());
+        emulator_enable_guest_asserts(emu, 1);
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        emulator_run(emu, 100, summary.as_mut_ptr());
+        let summary = unsafe { summary.assume_init() };
+        assert_eq!(StopReason::GuestAssertionFailed, summary.reason);
+        assert_eq!((8, DRAM_BASE + 0x24), (summary.tval, summary.stop_pc));
+
+        let mut out = [0u8; 256];
+        let len = fetch(|len| emulator_get_guest_asserts(emu, out.as_mut_ptr(), out.len(), len));
+        let json: serde_json::Value = serde_json::from_slice(&out[..len as usize]).unwrap();
+        assert_eq!(1, json["passed"]);
+        assert_eq!(1, json["failed"]);
+        assert_eq!(DRAM_BASE + 0x20, json["failure"]["pc"]);
+        assert_eq!("line 8: sum is wrong", json["failure"]["text"]);
+
+        emulator_clear_guest_asserts(emu);
+        let len = fetch(|len| emulator_get_guest_asserts(emu, out.as_mut_ptr(), out.len(), len));
+        assert_eq!(
+            r#"{"failed":0,"failure":null,"passed":0}"#,
+            std::str::from_utf8(&out[..len as usize]).unwrap()
+        );
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn watchpoints_stop_at_loads_and_stores() {
+        let emu = create();
+        let program = assembler::assemble("lw a0, 0(sp)\nsw a0, 0(sp)\n", Xlen::Rv64).unwrap();
+        emulator_load_program(emu, program.as_ptr(), program.len());
+        assert_eq!(RvStatus::Ok, emulator_set_register(emu, 2, DRAM_BASE + 0x100));
+        assert_eq!(RvStatus::Ok, emulator_add_watchpoint(emu, DRAM_BASE + 0x100, 4, 3));
+
+        let mut summary = MaybeUninit::<RunSummary>::uninit();
+        let mut hit = MaybeUninit::<WatchHit>::uninit();
+        for (kind, pc) in [(AccessKind::Load, DRAM_BASE), (AccessKind::Store, DRAM_BASE + 4)] {
+            emulator_run(emu, 10, summary.as_mut_ptr());
+            let summary = unsafe { summary.assume_init() };
+            assert_eq!(StopReason::Watchpoint, summary.reason);
+            assert_eq!(DRAM_BASE + 0x100, summary.tval);
+            assert_eq!(RvStatus::Ok, emulator_get_watch_hit(emu, hit.as_mut_ptr()));
+            let hit = unsafe { hit.assume_init() };
+            assert_eq!((kind, pc), (hit.kind, hit.pc));
+        }
+
+        let status = emulator_add_watchpoint(emu, DRAM_BASE + 0x100, 4, 3);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!("0x80000100..0x80000104 is empty or already watched", status::last_error());
+        let status = emulator_add_watchpoint(emu, DRAM_BASE, 4, 4);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!("4 isn't a watchpoint kind", status::last_error());
+        let status = emulator_remove_watchpoint(emu, DRAM_BASE + 0x100, 4, 1);
+        assert_eq!(RvStatus::InvalidArgument, status);
+        assert_eq!(RvStatus::Ok, emulator_remove_watchpoint(emu, DRAM_BASE + 0x100, 4, 3));
+        let status = emulator_add_watchpoint(std::ptr::null_mut(), DRAM_BASE, 4, 1);
+        assert_eq!(RvStatus::NullPointer, status);
+        emulator_destroy(emu);
+    }
+
+    #[test]
+    fn the_c_header_declares_every_exported_function() {
+        let header = include_str!("../rvemu_bindings.h");
+        let mut lines = include_str!("lib.rs").lines();
+        let mut exported = 0;
+        while let Some(line) = lines.next() {
+            if line.trim() != "#[no_mangle]" {
+                continue;
+            }
+            let signature = lines.next().unwrap();
+            let name = signature.split("fn ").nth(1).unwrap().split('(').next().unwrap();
+            let declared = [" ", "*"]
+                .iter()
+                .any(|before| header.contains(&format!("{}{}(", before, name)));
+            assert!(declared, "{} isn't declared", name);
+            exported += 1;
+        }
+        assert!(exported > 200);
+        assert!(header.contains("typedef struct RunSummary {"));
+        assert!(header.contains("typedef struct TraceEntry {"));
+        assert!(header.contains("RV_STATUS_NULL_POINTER = 1,"));
+    }
 
     // #[test]
     // fn it_works() {
@@ -0,0 +1,209 @@
+//! The micro-ISA is the tiny instruction set of the first levels. Its instructions have simple
+//! names like `set` and `ifeq` and are translated line by line into real RV32I instructions, so
+//! programs are assembled, run and debugged like any other program.
+
+use rvemu::isa::InstructionSet;
+
+/// An instruction of the micro-ISA.
+struct MicroInstruction {
+    /// The mnemonic players write.
+    name: &'static str,
+    /// The operands, for error messages.
+    operands: &'static [&'static str],
+    /// The RV32I instruction it's translated to. `{0}`, `{1}`, ... are replaced with the
+    /// operands.
+    template: &'static str,
+    /// The mnemonic of the RV32I instruction.
+    real: &'static str,
+}
+
+const MICRO_INSTRUCTIONS: &[MicroInstruction] = &[
+    MicroInstruction {
+        name: "set",
+        operands: &["register", "value"],
+        template: "addi {0}, x0, {1}",
+        real: "addi",
+    },
+    MicroInstruction {
+        name: "copy",
+        operands: &["register", "register"],
+        template: "addi {0}, {1}, 0",
+        real: "addi",
+    },
+    MicroInstruction {
+        name: "add",
+        operands: &["register", "register", "register"],
+        template: "add {0}, {1}, {2}",
+        real: "add",
+    },
+    MicroInstruction {
+        name: "sub",
+        operands: &["register", "register", "register"],
+        template: "sub {0}, {1}, {2}",
+        real: "sub",
+    },
+    MicroInstruction {
+        name: "inc",
+        operands: &["register"],
+        template: "addi {0}, {0}, 1",
+        real: "addi",
+    },
+    MicroInstruction {
+        name: "dec",
+        operands: &["register"],
+        template: "addi {0}, {0}, -1",
+        real: "addi",
+    },
+    MicroInstruction {
+        name: "load",
+        operands: &["register", "address register"],
+        template: "lw {0}, 0({1})",
+        real: "lw",
+    },
+    MicroInstruction {
+        name: "store",
+        operands: &["register", "address register"],
+        template: "sw {0}, 0({1})",
+        real: "sw",
+    },
+    MicroInstruction {
+        name: "jump",
+        operands: &["label"],
+        template: "jal x0, {0}",
+        real: "jal",
+    },
+    MicroInstruction {
+        name: "ifeq",
+        operands: &["register", "register", "label"],
+        template: "beq {0}, {1}, {2}",
+        real: "beq",
+    },
+    MicroInstruction {
+        name: "ifne",
+        operands: &["register", "register", "label"],
+        template: "bne {0}, {1}, {2}",
+        real: "bne",
+    },
+    MicroInstruction {
+        name: "iflt",
+        operands: &["register", "register", "label"],
+        template: "blt {0}, {1}, {2}",
+        real: "blt",
+    },
+    MicroInstruction {
+        name: "halt",
+        operands: &[],
+        template: "ecall",
+        real: "ecall",
+    },
+];
+
+/// Return the RV32I instructions micro-ISA programs are translated to. Restricting the CPU to
+/// them keeps hand-written RV32I out of micro-ISA levels.
+pub fn instruction_set() -> InstructionSet {
+    InstructionSet::new(MICRO_INSTRUCTIONS.iter().map(|inst| inst.real))
+}
+
+/// Translate one line. Labels are kept, and blank lines stay blank. A `#` starts a comment that
+/// runs to the end of the line, as in RV32I assembly, and is dropped.
+fn translate_line(line: &str) -> Result<String, String> {
+    let line = match line.find('#') {
+        Some(comment) => &line[..comment],
+        None => line,
+    };
+    let (label, code) = match line.find(':') {
+        Some(i) => line.split_at(i + 1),
+        None => ("", line),
+    };
+
+    // Operands are separated by commas, spaces, or both.
+    let mut words = code
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|word| !word.is_empty());
+    let name = match words.next() {
+        Some(name) => name,
+        None => return Ok(line.trim().to_string()),
+    };
+    let operands = words.collect::<Vec<&str>>();
+
+    let inst = match MICRO_INSTRUCTIONS.iter().find(|inst| inst.name == name) {
+        Some(inst) => inst,
+        None => {
+            let names = MICRO_INSTRUCTIONS
+                .iter()
+                .map(|inst| inst.name)
+                .collect::<Vec<&str>>();
+            return Err(format!(
+                "`{}` is not an instruction of this level. You can use: {}.",
+                name,
+                names.join(", ")
+            ));
+        }
+    };
+    if operands.len() != inst.operands.len() {
+        return Err(format!(
+            "`{}` takes {} operand(s): {} {}",
+            name,
+            inst.operands.len(),
+            name,
+            inst.operands.join(", ")
+        ));
+    }
+
+    let mut translated = inst.template.to_string();
+    for (i, operand) in operands.iter().enumerate() {
+        translated = translated.replace(&format!("{{{}}}", i), operand);
+    }
+    match label.trim() {
+        "" => Ok(translated),
+        label => Ok(format!("{} {}", label, translated)),
+    }
+}
+
+/// Translate a micro-ISA program into RV32I assembly with the same line numbers. On failure,
+/// returns the 1-based line number and a message for the player.
+pub fn translate(source: &str) -> Result<String, (u64, String)> {
+    source
+        .replace("\r\n", "\n")
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| translate_line(line).map_err(|message| ((i + 1) as u64, message)))
+        .collect::<Result<Vec<String>, (u64, String)>>()
+        .map(|lines| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn micro_instructions_are_translated() {
+        let source = "set a0, 5\nloop: dec a0\n\nifne a0 x0 loop\nhalt";
+        assert_eq!(
+            "addi a0, x0, 5\nloop: addi a0, a0, -1\n\nbne a0, x0, loop\necall",
+            translate(source).unwrap()
+        );
+        assert!(instruction_set().contains("bne"));
+        assert!(!instruction_set().contains("mul"));
+    }
+
+    #[test]
+    fn comments_are_dropped() {
+        let source = "# count down: from 5\nset a0, 5 # start\nloop: dec a0 # a0: counter";
+        assert_eq!(
+            "\naddi a0, x0, 5\nloop: addi a0, a0, -1",
+            translate(source).unwrap()
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let (line, message) = translate("set a0, 5\nmul a0, a0, a0").unwrap_err();
+        assert_eq!(2, line);
+        assert!(message.starts_with("`mul` is not an instruction of this level."));
+
+        let (line, message) = translate("inc").unwrap_err();
+        assert_eq!(1, line);
+        assert_eq!("`inc` takes 1 operand(s): inc register", message);
+    }
+}
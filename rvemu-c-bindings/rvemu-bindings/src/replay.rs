@@ -0,0 +1,360 @@
+//! Replays let designers author tutorials by playing the level themselves. A recorder follows
+//! the solution run on the real emulator and keeps the source, the program, the inputs the host
+//! fed it and the annotations of the designer, keyed to step indexes. The bundle is JSON. The
+//! game plays it back as a guided walkthrough by stepping the emulator again with the same
+//! inputs, and notices if the run diverges from the recorded trace.
+
+use rvemu::cpu::XRegisters;
+use rvemu::emulator::Emulator;
+use rvemu::run::{self, RunSummary, StopReason};
+use serde_json::{json, Value};
+
+/// The version of the bundle layout. Bump it when fields change meaning.
+const REPLAY_VERSION: u64 = 1;
+
+/// An input fed to the emulator by the host before a step.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ReplayInput {
+    /// A scancode pushed to the keyboard.
+    Scancode(u8),
+    /// A value written to an integer register, e.g. the result of an environment call.
+    Register { index: u64, value: u64 },
+}
+
+impl ReplayInput {
+    /// Feed the input to `emu`.
+    fn apply(self, emu: &mut Emulator) {
+        match self {
+            ReplayInput::Scancode(code) => emu.cpu.bus.keyboard.push_scancode(code),
+            ReplayInput::Register { index, value } => emu.cpu.xregs.write(index, value),
+        }
+    }
+
+    fn to_json(self, step: u64) -> Value {
+        match self {
+            ReplayInput::Scancode(code) => json!({ "step": step, "scancode": code }),
+            ReplayInput::Register { index, value } => {
+                json!({ "step": step, "register": index, "value": value })
+            }
+        }
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        if let Some(code) = value.get("scancode") {
+            return Some(ReplayInput::Scancode(
+                code.as_u64().filter(|&c| c < 256)? as u8
+            ));
+        }
+        let index = value.get("register")?.as_u64().filter(|&i| i < 32)?;
+        let value = value.get("value")?.as_u64()?;
+        Some(ReplayInput::Register { index, value })
+    }
+}
+
+/// A recorded solution run.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ReplayBundle {
+    /// The source of the solution.
+    pub source: String,
+    /// The program image, loaded at the start of DRAM.
+    pub program: Vec<u8>,
+    /// The address execution starts at.
+    pub entry: u64,
+    /// The inputs, each with the index of the step it's fed before.
+    pub inputs: Vec<(u64, ReplayInput)>,
+    /// The annotations of the designer, each with the index of the step it's shown before.
+    pub annotations: Vec<(u64, String)>,
+    /// The program counter before each step.
+    pub trace: Vec<u64>,
+}
+
+impl ReplayBundle {
+    /// Return the bundle as JSON. The program is a hexadecimal string.
+    pub fn to_json(&self) -> Value {
+        let program = self
+            .program
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        json!({
+            "version": REPLAY_VERSION,
+            "source": self.source,
+            "program": program,
+            "entry": self.entry,
+            "inputs": self
+                .inputs
+                .iter()
+                .map(|(step, input)| input.to_json(*step))
+                .collect::<Vec<Value>>(),
+            "annotations": self
+                .annotations
+                .iter()
+                .map(|(step, text)| json!({ "step": step, "text": text }))
+                .collect::<Vec<Value>>(),
+            "trace": self.trace,
+        })
+    }
+
+    /// Parse a bundle made by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<Value>(json).map_err(|e| e.to_string())?;
+        if value.get("version").and_then(Value::as_u64) != Some(REPLAY_VERSION) {
+            return Err("unsupported replay version".to_string());
+        }
+        let field = |name: &str| value.get(name).ok_or(format!("missing \"{}\"", name));
+        let array = |name: &str| {
+            field(name)?
+                .as_array()
+                .ok_or(format!("\"{}\" isn't an array", name))
+        };
+
+        let hex = field("program")?.as_str().unwrap_or("");
+        let program = (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|b| u8::from_str_radix(b, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or("\"program\" isn't a hexadecimal string")?;
+        let step = |value: &Value| value.get("step").and_then(Value::as_u64);
+        let inputs = array("inputs")?
+            .iter()
+            .map(|input| Some((step(input)?, ReplayInput::from_json(input)?)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("invalid input")?;
+        let annotations = array("annotations")?
+            .iter()
+            .map(|note| Some((step(note)?, note.get("text")?.as_str()?.to_string())))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("invalid annotation")?;
+        let trace = array("trace")?
+            .iter()
+            .map(Value::as_u64)
+            .collect::<Option<Vec<u64>>>()
+            .ok_or("invalid trace")?;
+
+        Ok(Self {
+            source: field("source")?.as_str().unwrap_or("").to_string(),
+            program,
+            entry: field("entry")?.as_u64().ok_or("invalid entry")?,
+            inputs,
+            annotations,
+            trace,
+        })
+    }
+}
+
+/// Load `program` into `emu` and put the core in its power-on state at `entry`, so a recording
+/// and its playback start from the same state.
+fn load(emu: &mut Emulator, program: &[u8], entry: u64) {
+    emu.cpu.reset();
+    emu.cpu.xregs = XRegisters::new();
+    emu.cpu.idle = false;
+    emu.initialize_dram(program.to_vec());
+    emu.initialize_pc(entry);
+}
+
+/// Records a solution run. The host steps the emulator and feeds it inputs through the
+/// recorder.
+#[derive(Debug, Default)]
+pub struct ReplayRecorder {
+    bundle: ReplayBundle,
+}
+
+impl ReplayRecorder {
+    /// Load `program`, assembled from `source`, into `emu` and start recording at `entry`.
+    pub fn start(emu: &mut Emulator, source: &str, program: Vec<u8>, entry: u64) -> Self {
+        load(emu, &program, entry);
+        Self {
+            bundle: ReplayBundle {
+                source: source.to_string(),
+                program,
+                entry,
+                ..ReplayBundle::default()
+            },
+        }
+    }
+
+    /// Return the number of steps recorded.
+    pub fn steps(&self) -> u64 {
+        self.bundle.trace.len() as u64
+    }
+
+    /// Feed `input` to `emu` before the next step.
+    pub fn input(&mut self, emu: &mut Emulator, input: ReplayInput) {
+        input.apply(emu);
+        self.bundle.inputs.push((self.steps(), input));
+    }
+
+    /// Attach `text` to the next step.
+    pub fn annotate(&mut self, text: &str) {
+        self.bundle
+            .annotations
+            .push((self.steps(), text.to_string()));
+    }
+
+    /// Run `emu` one step at a time for up to `max_steps` steps, like `Emulator::run`.
+    pub fn run(&mut self, emu: &mut Emulator, max_steps: u64) -> RunSummary {
+        let mut summary = emu.run(0);
+        let mut steps = 0;
+        for _ in 0..max_steps {
+            self.bundle.trace.push(emu.cpu.pc);
+            let step = emu.run(1);
+            steps += step.steps;
+            summary = RunSummary {
+                steps,
+                simulated_us: run::simulated_us(steps, step.clock_hz),
+                ..step
+            };
+            if step.reason != StopReason::StepLimit {
+                break;
+            }
+        }
+        summary
+    }
+
+    /// Return the recorded bundle.
+    pub fn bundle(&self) -> &ReplayBundle {
+        &self.bundle
+    }
+}
+
+/// The state of a playback after a step.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PlaybackStatus {
+    /// The step matched the recording.
+    Stepped = 0,
+    /// Every recorded step was played.
+    Finished = 1,
+    /// The program counter differs from the recording, e.g. because the program changed.
+    Diverged = 2,
+}
+
+/// Plays a bundle back on the real emulator.
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    bundle: ReplayBundle,
+    step: u64,
+}
+
+impl ReplayPlayer {
+    /// Create a player of `bundle`.
+    pub fn new(bundle: ReplayBundle) -> Self {
+        Self { bundle, step: 0 }
+    }
+
+    /// Return the index of the next step.
+    pub fn step_index(&self) -> u64 {
+        self.step
+    }
+
+    /// Load the program into `emu` and rewind to the first step.
+    pub fn start(&mut self, emu: &mut Emulator) {
+        load(emu, &self.bundle.program, self.bundle.entry);
+        self.step = 0;
+    }
+
+    /// Return the annotations of the next step.
+    pub fn annotations(&self) -> impl Iterator<Item = &str> {
+        let step = self.step;
+        self.bundle
+            .annotations
+            .iter()
+            .filter(move |(s, _)| *s == step)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Feed the recorded inputs of the next step to `emu` and execute it. The summary is that of
+    /// the step, or `None` if nothing was executed.
+    pub fn step(&mut self, emu: &mut Emulator) -> (PlaybackStatus, Option<RunSummary>) {
+        let expected = match self.bundle.trace.get(self.step as usize) {
+            Some(&pc) => pc,
+            None => return (PlaybackStatus::Finished, None),
+        };
+        if emu.cpu.pc != expected {
+            return (PlaybackStatus::Diverged, None);
+        }
+        for (_, input) in self.bundle.inputs.iter().filter(|(s, _)| *s == self.step) {
+            input.apply(emu);
+        }
+        let summary = emu.run(1);
+        self.step += 1;
+        (PlaybackStatus::Stepped, Some(summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rvemu::bus::DRAM_BASE;
+
+    /// addi a0, zero, 1; addi a7, zero, 64; ecall; add a0, a0, a0; nop
+    const PROGRAM: [u32; 5] = [0x0010_0513, 0x0400_0893, 0x0000_0073, 0x00a5_0533, 0x13];
+
+    fn program() -> Vec<u8> {
+        PROGRAM
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn a_recording_plays_back_with_its_inputs() {
+        let mut emu = Emulator::new();
+        let mut recorder = ReplayRecorder::start(&mut emu, "...", program(), DRAM_BASE);
+        recorder.annotate("load 1 into a0");
+        assert_eq!(StopReason::Yielded, recorder.run(&mut emu, 10).reason);
+        // The host serves the environment call.
+        recorder.input(
+            &mut emu,
+            ReplayInput::Register {
+                index: 10,
+                value: 20,
+            },
+        );
+        recorder.annotate("double the result");
+        assert_eq!(2, recorder.run(&mut emu, 2).steps);
+        assert_eq!(40, emu.cpu.xregs.read(10));
+
+        let json = recorder.bundle().to_json().to_string();
+        let bundle = ReplayBundle::from_json(&json).unwrap();
+        assert_eq!(recorder.bundle(), &bundle);
+
+        let mut emu = Emulator::new();
+        let mut player = ReplayPlayer::new(bundle);
+        player.start(&mut emu);
+        assert_eq!(
+            vec!["load 1 into a0"],
+            player.annotations().collect::<Vec<_>>()
+        );
+        for _ in 0..3 {
+            assert_eq!(PlaybackStatus::Stepped, player.step(&mut emu).0);
+        }
+        assert_eq!(
+            vec!["double the result"],
+            player.annotations().collect::<Vec<_>>()
+        );
+        assert_eq!(PlaybackStatus::Stepped, player.step(&mut emu).0);
+        assert_eq!(PlaybackStatus::Stepped, player.step(&mut emu).0);
+        assert_eq!(PlaybackStatus::Finished, player.step(&mut emu).0);
+        assert_eq!(40, emu.cpu.xregs.read(10));
+    }
+
+    #[test]
+    fn a_changed_program_diverges() {
+        let mut emu = Emulator::new();
+        let mut recorder = ReplayRecorder::start(&mut emu, "", program(), DRAM_BASE);
+        recorder.run(&mut emu, 2);
+
+        let mut bundle = recorder.bundle().clone();
+        // jal zero, 8
+        bundle.program[..4].copy_from_slice(&0x0080_006fu32.to_le_bytes());
+        let mut player = ReplayPlayer::new(bundle);
+        player.start(&mut emu);
+        assert_eq!(PlaybackStatus::Stepped, player.step(&mut emu).0);
+        assert_eq!((PlaybackStatus::Diverged, None), player.step(&mut emu));
+        assert!(ReplayBundle::from_json("{\"version\": 0}").is_err());
+    }
+}
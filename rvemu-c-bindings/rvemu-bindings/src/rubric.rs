@@ -0,0 +1,151 @@
+//! A rubric scores a submission on weighted criteria instead of pass/fail: the share of the
+//! generated cases it wins, the instructions it retires and the size of its code. Classrooms
+//! grade on rubrics, and the breakdown tells the student where the points went.
+
+use rvemu::run::RunSummary;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// A weighted criterion of a rubric, as declared in a level spec.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Criterion {
+    /// Credit in proportion to the number of cases won.
+    Correctness { weight: f64 },
+    /// Full credit if every case was won and none retired more than `max` instructions.
+    Steps { weight: f64, max: u64 },
+    /// Full credit if the program image is at most `max` bytes and a case was won.
+    CodeSize { weight: f64, max: u64 },
+}
+
+impl Criterion {
+    /// Return the name of the criterion, as in a level spec.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Criterion::Correctness { .. } => "correctness",
+            Criterion::Steps { .. } => "steps",
+            Criterion::CodeSize { .. } => "code_size",
+        }
+    }
+
+    /// Return the weight of the criterion.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Criterion::Correctness { weight }
+            | Criterion::Steps { weight, .. }
+            | Criterion::CodeSize { weight, .. } => *weight,
+        }
+    }
+
+    /// Check that the weight is a non-negative number.
+    pub fn validate(&self) -> Result<(), String> {
+        let weight = self.weight();
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!("invalid weight {} for `{}`", weight, self.name()));
+        }
+        Ok(())
+    }
+
+    /// Score the criterion for the runs of the cases, where each case is won or not, and a
+    /// program of `code_size` bytes.
+    fn score(&self, cases: &[(bool, RunSummary)], code_size: u64) -> CriterionScore {
+        let won = cases.iter().filter(|(won, _)| *won).count() as u64;
+        let (measured, credit) = match self {
+            Criterion::Correctness { .. } => {
+                let credit = match cases.len() {
+                    0 => 0.0,
+                    len => won as f64 / len as f64,
+                };
+                (won, credit)
+            }
+            Criterion::Steps { max, .. } => {
+                let steps = cases
+                    .iter()
+                    .map(|(_, summary)| summary.steps)
+                    .max()
+                    .unwrap_or(0);
+                let all_won = won == cases.len() as u64;
+                (steps, if all_won && steps <= *max { 1.0 } else { 0.0 })
+            }
+            Criterion::CodeSize { max, .. } => {
+                let fits = won > 0 && code_size <= *max;
+                (code_size, if fits { 1.0 } else { 0.0 })
+            }
+        };
+        CriterionScore {
+            name: self.name(),
+            weight: self.weight(),
+            earned: self.weight() * credit,
+            measured,
+        }
+    }
+}
+
+/// The score of one criterion.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct CriterionScore {
+    /// The name of the criterion.
+    pub name: &'static str,
+    /// The weight of the criterion: the most it can earn.
+    pub weight: f64,
+    /// What the submission earned, from 0 to `weight`.
+    pub earned: f64,
+    /// What was measured: the cases won, the most instructions retired in a case, or the size of
+    /// the program in bytes.
+    pub measured: u64,
+}
+
+/// The breakdown of the score of a submission.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Evaluation {
+    /// Whether each case was won, in order.
+    pub cases: Vec<bool>,
+    /// The score of each criterion, in the order of the rubric.
+    pub criteria: Vec<CriterionScore>,
+}
+
+impl Evaluation {
+    /// Score the criteria of `rubric` for the runs of the cases and a program of `code_size`
+    /// bytes.
+    pub fn new(rubric: &[Criterion], cases: &[(bool, RunSummary)], code_size: u64) -> Self {
+        Self {
+            cases: cases.iter().map(|(won, _)| *won).collect(),
+            criteria: rubric
+                .iter()
+                .map(|criterion| criterion.score(cases, code_size))
+                .collect(),
+        }
+    }
+
+    /// Return the points earned over all criteria.
+    pub fn earned(&self) -> f64 {
+        self.criteria.iter().map(|score| score.earned).sum()
+    }
+
+    /// Return the most points the criteria can earn.
+    pub fn total(&self) -> f64 {
+        self.criteria.iter().map(|score| score.weight).sum()
+    }
+
+    /// Return the evaluation as a JSON document: the total, the cases and the criteria.
+    pub fn to_json(&self) -> Value {
+        let criteria = self
+            .criteria
+            .iter()
+            .map(|score| {
+                json!({
+                    "criterion": score.name,
+                    "weight": score.weight,
+                    "earned": score.earned,
+                    "measured": score.measured,
+                })
+            })
+            .collect::<Vec<Value>>();
+        json!({
+            "earned": self.earned(),
+            "total": self.total(),
+            "cases": self.cases,
+            "criteria": criteria,
+        })
+    }
+}
@@ -0,0 +1,272 @@
+//! The sandbox runs fully untrusted programs, e.g. those of the public web playground, in one
+//! call. Every protection is always on and every limit is capped, so there's nothing to forget:
+//! the program runs in user mode, PMP confines it to the start of DRAM and the devices the host
+//! allows, the ISA policy is enforced by the CPU, the run stops after a number of instructions
+//! and a wall-clock timeout, and a panic of the emulator is reported instead of unwinding into
+//! the host.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use rvemu::bus::{
+    AUDIO_BASE, CHANNEL_BASE, DRAM_BASE, DRAM_END, DRAW_QUEUE_BASE, KEYBOARD_BASE,
+    SAVE_FILE_BASE, UART_BASE, UART_SIZE, VGA_TEXT_BASE, VSYNC_BASE,
+};
+use rvemu::devices::save_file::SAVE_FILE_SIZE;
+use rvemu::devices::vga_text::VGA_TEXT_SIZE;
+use rvemu::emulator::Emulator;
+use rvemu::pmp::{self, PMP_A_NAPOT, PMP_A_TOR, PMP_R, PMP_W, PMP_X};
+use rvemu::profile::Profile;
+use rvemu::run::{RunLimits, RunSummary, StopReason, DEFAULT_CLOCK_HZ};
+
+use crate::isa_policy::IsaPolicy;
+
+/// The most instructions a sandboxed run can retire.
+pub const MAX_SANDBOX_STEPS: u64 = 100_000_000;
+
+/// The longest wall-clock timeout of a sandboxed run.
+pub const MAX_SANDBOX_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most memory a sandboxed program can use, from `DRAM_BASE`.
+pub const MAX_SANDBOX_MEMORY: u64 = 16 * 1024 * 1024;
+
+/// The UART, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_UART: u32 = 1;
+/// The keyboard controller, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_KEYBOARD: u32 = 1 << 1;
+/// The text-mode buffer, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_VGA_TEXT: u32 = 1 << 2;
+/// The draw command queue, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_DRAW_QUEUE: u32 = 1 << 3;
+/// The frame synchronization device, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_VSYNC: u32 = 1 << 4;
+/// The audio sample ring buffer, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_AUDIO: u32 = 1 << 5;
+/// The save memory, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_SAVE_FILE: u32 = 1 << 6;
+/// The message channel, as a bit of the devices of a sandboxed run.
+pub const SANDBOX_DEVICE_CHANNEL: u32 = 1 << 7;
+
+/// The devices a sandboxed program can be allowed to access: their bit, base and size, rounded
+/// up to a power of two so each takes a single PMP entry. The rest of the page of each device is
+/// unmapped, so the rounding allows nothing more. The CLINT, the PLIC, virtio and the watchdog
+/// control the machine rather than the game and are never allowed.
+const DEVICES: [(u32, u64, u64); 8] = [
+    (SANDBOX_DEVICE_UART, UART_BASE, UART_SIZE),
+    (SANDBOX_DEVICE_KEYBOARD, KEYBOARD_BASE, 0x8),
+    (SANDBOX_DEVICE_VGA_TEXT, VGA_TEXT_BASE, VGA_TEXT_SIZE.next_power_of_two()),
+    (SANDBOX_DEVICE_DRAW_QUEUE, DRAW_QUEUE_BASE, 0x40),
+    (SANDBOX_DEVICE_VSYNC, VSYNC_BASE, 0x8),
+    (SANDBOX_DEVICE_AUDIO, AUDIO_BASE, 0x10),
+    (SANDBOX_DEVICE_SAVE_FILE, SAVE_FILE_BASE, SAVE_FILE_SIZE),
+    (SANDBOX_DEVICE_CHANNEL, CHANNEL_BASE, 0x10),
+];
+
+/// The limits of a sandboxed run. Values above the caps are lowered to them.
+#[derive(Debug, Clone)]
+pub struct SandboxConfig {
+    /// The most instructions the program can retire.
+    pub max_steps: u64,
+    /// The wall-clock timeout of the run.
+    pub timeout: Duration,
+    /// The bytes of memory from `DRAM_BASE` the program can access, rounded down to a multiple
+    /// of 4.
+    pub memory_limit: u64,
+    /// The devices the program can read and write, as a set of `SANDBOX_DEVICE_*` bits. Every
+    /// other device is denied.
+    pub devices: u32,
+    /// The instructions the program may use, if restricted.
+    pub policy: Option<IsaPolicy>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 10_000_000,
+            timeout: Duration::from_secs(1),
+            memory_limit: 1024 * 1024,
+            devices: 0,
+            policy: None,
+        }
+    }
+}
+
+/// How a sandboxed run ended.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SandboxStatus {
+    /// The program ran. Why it stopped is in the summary.
+    Ran = 0,
+    /// The program doesn't fit in the memory limit and didn't run.
+    TooLarge = 1,
+    /// The emulator panicked. The summary is empty.
+    Panicked = 2,
+}
+
+/// The outcome of a sandboxed run. The layout is C-compatible so it can be returned over the
+/// FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct SandboxResult {
+    pub status: SandboxStatus,
+    pub summary: RunSummary,
+}
+
+/// Return the summary of a run that didn't happen.
+fn no_run() -> RunSummary {
+    RunSummary {
+        reason: StopReason::LimitExceeded,
+        steps: 0,
+        stop_pc: DRAM_BASE,
+        cause: 0,
+        tval: 0,
+        exit_code: 0,
+        wall_time_ns: 0,
+        interrupts: 0,
+        total_retired: 0,
+        mtime: 0,
+        clock_hz: DEFAULT_CLOCK_HZ,
+        simulated_us: 0.0,
+    }
+}
+
+/// Create an emulator running `program` at `DRAM_BASE` in user mode, confined to `memory_limit`
+/// bytes of DRAM and the `devices`.
+fn confine(
+    program: &[u8],
+    memory_limit: u64,
+    devices: u32,
+    policy: Option<&IsaPolicy>,
+) -> Emulator {
+    let mut emu = Emulator::new();
+    emu.cpu.bus.write_dram(DRAM_BASE, program);
+    emu.initialize_pc(DRAM_BASE);
+
+    // The first `memory_limit` bytes of DRAM can be read, written and executed, and the allowed
+    // devices can be read and written. Any other access of user mode is denied. The first entry
+    // is off and only marks the start of DRAM for the second.
+    let state = &mut emu.cpu.state;
+    pmp::set_entry(state, 0, 0, DRAM_BASE >> 2);
+    let end = DRAM_BASE + memory_limit;
+    pmp::set_entry(state, 1, PMP_A_TOR | PMP_R | PMP_W | PMP_X, end >> 2);
+    let allowed = DEVICES.iter().filter(|(bit, _, _)| devices & bit != 0);
+    for (index, &(_, base, size)) in (2..).zip(allowed) {
+        let addr = pmp::napot_addr(base, size);
+        pmp::set_entry(state, index, PMP_A_NAPOT | PMP_R | PMP_W, addr);
+    }
+
+    emu.set_profile(Profile::UserSandbox);
+    emu.cpu.instruction_set = policy.and_then(IsaPolicy::instruction_set);
+    emu
+}
+
+/// Run the untrusted program image `program` at `DRAM_BASE` within `config`.
+pub fn run_sandboxed(program: &[u8], config: &SandboxConfig) -> SandboxResult {
+    let memory_limit = config
+        .memory_limit
+        .min(MAX_SANDBOX_MEMORY)
+        .min(DRAM_END - DRAM_BASE)
+        & !3;
+    if program.len() as u64 > memory_limit {
+        return SandboxResult {
+            status: SandboxStatus::TooLarge,
+            summary: no_run(),
+        };
+    }
+    let limits = RunLimits {
+        max_steps: config.max_steps.min(MAX_SANDBOX_STEPS),
+        time_budget: Some(config.timeout.min(MAX_SANDBOX_TIMEOUT)),
+    };
+
+    let run = panic::catch_unwind(AssertUnwindSafe(|| {
+        confine(program, memory_limit, config.devices, config.policy.as_ref()).run_with(limits)
+    }));
+    match run {
+        Ok(summary) => SandboxResult {
+            status: SandboxStatus::Ran,
+            summary,
+        },
+        Err(_) => SandboxResult {
+            status: SandboxStatus::Panicked,
+            summary: no_run(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(words: &[u32]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|w| w.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    /// addi a0, zero, 7; addi a7, zero, 93; ecall
+    const EXIT: [u32; 3] = [0x0070_0513, 0x05d0_0893, 0x0000_0073];
+
+    #[test]
+    fn programs_run_confined() {
+        let result = run_sandboxed(&image(&EXIT), &SandboxConfig::default());
+        assert_eq!(SandboxStatus::Ran, result.status);
+        assert_eq!(StopReason::Exited, result.summary.reason);
+        assert_eq!(7, result.summary.exit_code);
+
+        // lui a0, 0x80100; sw zero, 0(a0): a store past the memory limit traps.
+        let result = run_sandboxed(
+            &image(&[0x8010_0537, 0x0005_2023]),
+            &SandboxConfig::default(),
+        );
+        assert_eq!(StopReason::Trapped, result.summary.reason);
+        assert_eq!(7, result.summary.cause);
+
+        // csrr a0, mstatus is privileged.
+        let result = run_sandboxed(&image(&[0x3000_2573]), &SandboxConfig::default());
+        assert_eq!(StopReason::Trapped, result.summary.reason);
+        assert_eq!(2, result.summary.cause);
+
+        // j . loops until the timeout.
+        let config = SandboxConfig {
+            max_steps: u64::MAX,
+            timeout: Duration::from_millis(20),
+            ..SandboxConfig::default()
+        };
+        let result = run_sandboxed(&image(&[0x0000_006f]), &config);
+        assert_eq!(StopReason::TimeBudget, result.summary.reason);
+    }
+
+    #[test]
+    fn devices_are_denied_unless_allowed() {
+        // lui a0, 0x10000; sb zero, 0(a0); addi a7, zero, 93; ecall: a store to the UART.
+        let program = image(&[0x1000_0537, 0x0005_0023, 0x05d0_0893, 0x0000_0073]);
+        let result = run_sandboxed(&program, &SandboxConfig::default());
+        assert_eq!(StopReason::Trapped, result.summary.reason);
+        assert_eq!(7, result.summary.cause);
+
+        let config = SandboxConfig {
+            devices: SANDBOX_DEVICE_UART,
+            ..SandboxConfig::default()
+        };
+        let result = run_sandboxed(&program, &config);
+        assert_eq!(StopReason::Exited, result.summary.reason);
+
+        // Other devices stay denied: lui a0, 0x2000; lw a0, 0(a0) loads from the CLINT.
+        let result = run_sandboxed(&image(&[0x0200_0537, 0x0005_2503]), &config);
+        assert_eq!(StopReason::Trapped, result.summary.reason);
+        assert_eq!(5, result.summary.cause);
+    }
+
+    #[test]
+    fn large_programs_are_rejected() {
+        let config = SandboxConfig {
+            memory_limit: 8,
+            ..SandboxConfig::default()
+        };
+        assert_eq!(
+            SandboxStatus::TooLarge,
+            run_sandboxed(&image(&EXIT), &config).status
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! The status codes of the FFI. Every function returns an `RvStatus`, writes its results through
+//! out-parameters and never unwinds into the host: a null pointer, an invalid argument or a panic
+//! of the emulator becomes a status, and its message is kept for `emulator_last_error_message`.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::handles;
+
+/// The outcome of an FFI call.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RvStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A pointer argument was null.
+    NullPointer = 1,
+    /// An argument was invalid, e.g. an unknown mode or an address outside DRAM.
+    InvalidArgument = 2,
+    /// The emulator panicked. It may be left in an inconsistent state and should be destroyed.
+    Panicked = 3,
+    /// The host had no memory for a new emulator, e.g. because too many are alive.
+    AllocationFailed = 4,
+}
+
+/// Why an FFI call failed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FfiError {
+    pub status: RvStatus,
+    pub message: String,
+}
+
+impl FfiError {
+    /// Return the error of the null pointer argument `name`.
+    pub fn null(name: &str) -> Self {
+        Self {
+            status: RvStatus::NullPointer,
+            message: format!("`{}` is null", name),
+        }
+    }
+
+    /// Return the error of an invalid argument.
+    pub fn invalid(message: String) -> Self {
+        Self {
+            status: RvStatus::InvalidArgument,
+            message,
+        }
+    }
+
+    /// Return the error of a failed allocation.
+    pub fn allocation(message: String) -> Self {
+        Self {
+            status: RvStatus::AllocationFailed,
+            message,
+        }
+    }
+
+    /// Return the error of a panic with `payload`.
+    pub fn panicked(payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        Self {
+            status: RvStatus::Panicked,
+            message: format!("the emulator panicked: {}", message),
+        }
+    }
+}
+
+thread_local! {
+    /// The message of the last failed call on this thread.
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Return the message of the last failed call on this thread, or an empty string.
+pub fn last_error() -> String {
+    LAST_ERROR.with(|last| last.borrow().clone())
+}
+
+/// Keep the message of `error` for `last_error` and return its status.
+pub fn fail(error: FfiError) -> RvStatus {
+    let FfiError { status, message } = error;
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+/// Run the body of an FFI call, turning its error or panic into a status. The message of a
+/// failure is recorded for `last_error`.
+pub fn guard<F>(body: F) -> RvStatus
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => RvStatus::Ok,
+        Ok(Err(error)) => fail(error),
+        Err(payload) => fail(FfiError::panicked(payload.as_ref())),
+    }
+}
+
+/// Return the pointee of the pointer argument `name`, or an error if it's null or, in debug
+/// builds, a destroyed handle.
+///
+/// # Safety
+///
+/// `ptr` must be null, a destroyed handle or valid for the lifetime `'a`, as for
+/// `<*mut T>::as_mut`.
+pub unsafe fn non_null<'a, T: 'static>(ptr: *mut T, name: &str) -> Result<&'a mut T, FfiError> {
+    handles::check(ptr, name)?;
+    Ok(&mut *ptr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_reported_with_a_message() {
+        assert_eq!(RvStatus::Ok, guard(|| Ok(())));
+
+        let status = guard(|| unsafe { non_null(std::ptr::null_mut::<u8>(), "emu") }.map(|_| ()));
+        assert_eq!(RvStatus::NullPointer, status);
+        assert_eq!("`emu` is null", last_error());
+
+        let status = guard(|| panic!("index out of bounds"));
+        assert_eq!(RvStatus::Panicked, status);
+        assert_eq!("the emulator panicked: index out of bounds", last_error());
+    }
+}
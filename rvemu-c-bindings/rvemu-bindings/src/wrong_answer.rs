@@ -0,0 +1,197 @@
+//! A wrong answer compares a lost run with what the level expected: the value each win
+//! condition wanted and the one the run left, the output of both runs, and the first store where
+//! the run parted from the reference solution. The game renders it as the "here's where your run
+//! went wrong" panel.
+
+use rvemu::emulator::Emulator;
+use rvemu::run::RunSummary;
+use rvemu::trace::{AccessKind, MemoryAccess};
+use serde_json::{json, Value};
+
+use crate::level::WinCondition;
+
+/// A win condition, whether it held, and the values it compared.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConditionCheck {
+    pub condition: WinCondition,
+    pub holds: bool,
+    /// What the condition wanted, or null if it isn't known, e.g. without a reference run.
+    pub expected: Value,
+    /// What the run left, or null if nothing, e.g. the exit code of a run that didn't exit.
+    pub actual: Value,
+}
+
+/// The first store that differs between the run and the reference run.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct StoreDivergence {
+    /// The index of the store in both runs, from 0.
+    pub index: u64,
+    /// The store of the reference run, or `None` if it stored less.
+    pub expected: Option<MemoryAccess>,
+    /// The store of the run, or `None` if it stored less.
+    pub actual: Option<MemoryAccess>,
+}
+
+/// The comparison of a run with what the level expected.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WrongAnswer {
+    pub summary: RunSummary,
+    /// Every win condition of the level, in order.
+    pub conditions: Vec<ConditionCheck>,
+    /// The debug prints of the reference run, one per line, if the level has a reference.
+    pub expected_output: Option<Vec<String>>,
+    /// The debug prints of the run, one per line.
+    pub actual_output: Vec<String>,
+    /// The first store that differs from the reference run, if any.
+    pub divergence: Option<StoreDivergence>,
+}
+
+/// Return the messages of the debug prints of `emu`.
+fn output(emu: &Emulator) -> Vec<String> {
+    emu.debug_prints
+        .prints()
+        .iter()
+        .map(|print| print.message())
+        .collect()
+}
+
+/// Return the stores traced in `emu`, in program order.
+fn stores(emu: &Emulator) -> Vec<MemoryAccess> {
+    emu.cpu
+        .access_trace
+        .entries()
+        .iter()
+        .filter(|access| access.kind == AccessKind::Store)
+        .copied()
+        .collect()
+}
+
+/// Return the first store that differs between `actual` and `expected`, ignoring the address
+/// of the instructions.
+fn first_divergence(actual: &[MemoryAccess], expected: &[MemoryAccess]) -> Option<StoreDivergence> {
+    let same =
+        |a: &MemoryAccess, b: &MemoryAccess| (a.addr, a.size, a.value) == (b.addr, b.size, b.value);
+    let index = match actual.iter().zip(expected).position(|(a, b)| !same(a, b)) {
+        Some(index) => index,
+        None if actual.len() == expected.len() => return None,
+        None => actual.len().min(expected.len()),
+    };
+    Some(StoreDivergence {
+        index: index as u64,
+        expected: expected.get(index).copied(),
+        actual: actual.get(index).copied(),
+    })
+}
+
+/// Return a store as JSON, or null.
+fn store_json(store: Option<MemoryAccess>) -> Value {
+    match store {
+        Some(store) => json!({
+            "pc": store.pc,
+            "addr": store.addr,
+            "size": store.size,
+            "value": store.value,
+        }),
+        None => Value::Null,
+    }
+}
+
+impl WrongAnswer {
+    /// Compare the run of `emu` summarized by `summary` with the win conditions `win`, and with
+    /// `reference`, the emulator that ran the reference solution on the same case, if any. Both
+    /// runs must have traced their stores to DRAM.
+    pub fn new(
+        win: &[WinCondition],
+        emu: &Emulator,
+        summary: &RunSummary,
+        reference: Option<&Emulator>,
+    ) -> Self {
+        let conditions = win
+            .iter()
+            .map(|condition| {
+                let (expected, actual) = condition.compare(emu, summary, reference);
+                ConditionCheck {
+                    condition: condition.clone(),
+                    holds: condition.holds(emu, summary, reference),
+                    expected,
+                    actual,
+                }
+            })
+            .collect();
+        Self {
+            summary: *summary,
+            conditions,
+            expected_output: reference.map(output),
+            actual_output: output(emu),
+            divergence: reference
+                .and_then(|reference| first_divergence(&stores(emu), &stores(reference))),
+        }
+    }
+
+    /// Return the comparison as a JSON document.
+    pub fn to_json(&self) -> Value {
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|check| {
+                json!({
+                    "condition": serde_json::to_value(&check.condition).unwrap_or(Value::Null),
+                    "holds": check.holds,
+                    "expected": check.expected,
+                    "actual": check.actual,
+                })
+            })
+            .collect::<Vec<Value>>();
+        let divergence = match &self.divergence {
+            Some(divergence) => json!({
+                "store": divergence.index,
+                "expected": store_json(divergence.expected),
+                "actual": store_json(divergence.actual),
+            }),
+            None => Value::Null,
+        };
+        json!({
+            "reason": self.summary.reason as u32,
+            "steps": self.summary.steps,
+            "exit_code": self.summary.exit_code,
+            "conditions": conditions,
+            "output": {
+                "expected": self.expected_output,
+                "actual": self.actual_output,
+            },
+            "divergence": divergence,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(pc: u64, addr: u64, value: u64) -> MemoryAccess {
+        MemoryAccess {
+            pc,
+            addr,
+            size: 4,
+            value,
+            kind: AccessKind::Store,
+        }
+    }
+
+    #[test]
+    fn the_first_different_store_is_found() {
+        let expected = [store(0x10, 0x100, 1), store(0x14, 0x104, 2)];
+        // The address of the instruction doesn't matter.
+        let same = [store(0x20, 0x100, 1), store(0x28, 0x104, 2)];
+        assert_eq!(None, first_divergence(&same, &expected));
+
+        let wrong = [store(0x20, 0x100, 1), store(0x28, 0x104, 3)];
+        let divergence = first_divergence(&wrong, &expected).unwrap();
+        assert_eq!(1, divergence.index);
+        assert_eq!(Some(expected[1]), divergence.expected);
+        assert_eq!(Some(wrong[1]), divergence.actual);
+
+        let short = first_divergence(&wrong[..1], &expected).unwrap();
+        assert_eq!((1, None), (short.index, short.actual));
+    }
+}
@@ -0,0 +1,150 @@
+//! The aliases module contains friendly names for registers, e.g. `score` for a0 or `pos_x` for
+//! s1. Early game levels use them before raw register names are taught: the assembler accepts
+//! them as operands, the disassembler prints them instead of the register names and diagnostics
+//! print them next to the register names.
+
+use std::collections::BTreeMap;
+
+use crate::cpu::{ABI_NAMES, REGISTERS_COUNT};
+use crate::isa::register_index;
+
+/// Return true if `name` can be used as an alias: an identifier that isn't a register name.
+fn is_valid_alias(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && register_index(name).is_none()
+}
+
+/// Return the length of the label definitions `line` starts with, e.g. `loop: ` in
+/// `loop: addi a0, a0, 1`, or 0 if it doesn't start with one. A colon after anything else, e.g.
+/// in a comment, doesn't end a label.
+fn labels_len(line: &str) -> usize {
+    let mut end = 0;
+    loop {
+        let rest = &line[end..];
+        let name_start = rest.len() - rest.trim_start().len();
+        let name_len = rest[name_start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len() - name_start);
+        let after = &rest[name_start + name_len..];
+        let colon = name_start + name_len + after.len() - after.trim_start().len();
+        if name_len == 0 || !rest[colon..].starts_with(':') {
+            return end;
+        }
+        end += colon + 1;
+    }
+}
+
+/// The register aliases of a game level.
+#[derive(Debug, Default, Clone)]
+pub struct RegisterAliases {
+    /// The register index of each alias.
+    aliases: BTreeMap<String, u64>,
+}
+
+impl RegisterAliases {
+    /// Create an empty set of aliases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if no alias is defined.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Make `name` an alias of the register `index`. Returns false if `name` isn't an identifier,
+    /// is a register name itself, or `index` is out of range. An existing alias is replaced.
+    pub fn add(&mut self, name: &str, index: u64) -> bool {
+        if !is_valid_alias(name) || index >= REGISTERS_COUNT as u64 {
+            return false;
+        }
+        self.aliases.insert(name.to_string(), index);
+        true
+    }
+
+    /// Remove the alias `name`. Returns false if it isn't defined.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.aliases.remove(name).is_some()
+    }
+
+    /// Remove all aliases, e.g. before another level is loaded.
+    pub fn clear(&mut self) {
+        self.aliases.clear();
+    }
+
+    /// Return the register index of the alias `name`.
+    pub fn index_of(&self, name: &str) -> Option<u64> {
+        self.aliases.get(name).copied()
+    }
+
+    /// Return the aliases of the register `index` in alphabetical order.
+    pub fn aliases_of(&self, index: u64) -> impl Iterator<Item = &str> {
+        self.aliases
+            .iter()
+            .filter(move |(_, &i)| i == index)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Return the name of the register `index` for diagnostics: the ABI name followed by its
+    /// aliases, e.g. "a0 (score)".
+    pub fn describe(&self, index: u64) -> String {
+        let name = ABI_NAMES
+            .get(index as usize)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("x{}", index));
+        let aliases = self.aliases_of(index).collect::<Vec<&str>>();
+        match aliases.len() {
+            0 => name,
+            _ => format!("{} ({})", name, aliases.join(", ")),
+        }
+    }
+
+    /// Replace the aliases in the operands of an assembly line with ABI register names. The
+    /// mnemonic, the leading labels and a comment are left as they are.
+    pub fn substitute(&self, line: &str) -> String {
+        if self.aliases.is_empty() {
+            return line.to_string();
+        }
+
+        // Skip the label definitions and the mnemonic.
+        let label_end = labels_len(line);
+        let rest = &line[label_end..];
+        let mnemonic_start = label_end + rest.len() - rest.trim_start().len();
+        let operands_start = line[mnemonic_start..]
+            .find(char::is_whitespace)
+            .map(|i| mnemonic_start + i)
+            .unwrap_or(line.len());
+
+        let comment_start = line[operands_start..]
+            .find('#')
+            .map(|i| operands_start + i)
+            .unwrap_or(line.len());
+
+        let mut result = line[..operands_start].to_string();
+        let mut word = String::new();
+        for c in line[operands_start..comment_start].chars() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                word.push(c);
+                continue;
+            }
+            self.push_word(&mut result, &mut word);
+            result.push(c);
+        }
+        self.push_word(&mut result, &mut word);
+        result.push_str(&line[comment_start..]);
+        result
+    }
+
+    /// Append `word` to `result`, replaced with the register name if it's an alias, and clear it.
+    fn push_word(&self, result: &mut String, word: &mut String) {
+        match self.index_of(word) {
+            Some(index) => result.push_str(ABI_NAMES[index as usize]),
+            None => result.push_str(word),
+        }
+        word.clear();
+    }
+}
@@ -0,0 +1,211 @@
+//! The annotation module describes a step in the terms the tutorial overlay narrates: the
+//! disassembly of the instruction, the registers it read and wrote, whether a branch was taken,
+//! and the id of the plain-language template that explains what happened.
+
+use crate::disasm::disassemble;
+use crate::dram::ByteOrder;
+use crate::emulator::Emulator;
+use crate::run::StopReason;
+
+/// The size of the disassembly buffer, including the NUL.
+pub const ANNOTATION_TEXT_SIZE: usize = 64;
+
+/// Flag: the instruction is a conditional branch.
+pub const ANNOTATION_BRANCH: u32 = 1;
+/// Flag: the branch was taken.
+pub const ANNOTATION_TAKEN: u32 = 1 << 1;
+/// Flag: the instruction loaded from or stored to `addr`.
+pub const ANNOTATION_MEMORY: u32 = 1 << 2;
+/// Flag: the instruction wrote the register `rd` with `result`.
+pub const ANNOTATION_WRITES_RD: u32 = 1 << 3;
+
+/// The plain-language template explaining a step. The ids are stable, so the overlay can keep
+/// its localized texts by id.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Narration {
+    /// No template fits, e.g. a floating-point or compressed instruction.
+    Other = 0,
+    /// "`rd` = `rs1` op `rs2`"
+    Compute = 1,
+    /// "`rd` = `rs1` op an immediate"
+    ComputeImmediate = 2,
+    /// "`rd` = an upper immediate", `lui` and `auipc`.
+    UpperImmediate = 3,
+    /// "`rd` = the value loaded from `addr`"
+    Load = 4,
+    /// "`rs2` was stored to `addr`"
+    Store = 5,
+    /// "`rs1` and `rs2` compared true, so the branch was taken"
+    BranchTaken = 6,
+    /// "`rs1` and `rs2` compared false, so execution went on with the next instruction"
+    BranchNotTaken = 7,
+    /// "the program jumped, saving the return address in `rd`"
+    Jump = 8,
+    /// "`rd` = the old value of a CSR"
+    Csr = 9,
+    /// "the program asked the environment for a service", `ecall` and `ebreak`.
+    EnvironmentCall = 10,
+    /// "the instruction raised an exception"
+    Trap = 11,
+}
+
+/// A register an instruction read and its value before the step.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct OperandValue {
+    pub reg: u64,
+    pub value: u64,
+}
+
+/// Everything the tutorial overlay narrates about one step. The layout is C-compatible so it
+/// can be copied over the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct StepAnnotation {
+    /// Why the step stopped, as for a run of one step.
+    pub reason: StopReason,
+    /// The template explaining the step.
+    pub narration: Narration,
+    /// The `ANNOTATION_*` flags.
+    pub flags: u32,
+    /// The number of valid entries in `sources`.
+    pub source_count: u32,
+    /// The program counter before the step.
+    pub pc: u64,
+    /// The instruction at `pc`. Compressed instructions are 16 bits.
+    pub inst: u64,
+    /// The program counter after the step.
+    pub next_pc: u64,
+    /// The integer registers the instruction read, `rs1` first.
+    pub sources: [OperandValue; 2],
+    /// The register written with `ANNOTATION_WRITES_RD`, otherwise 0.
+    pub rd: u64,
+    /// The value written to `rd`, otherwise 0.
+    pub result: u64,
+    /// The accessed address with `ANNOTATION_MEMORY`, otherwise 0.
+    pub addr: u64,
+    /// The disassembly of the instruction, NUL-terminated and truncated to fit.
+    pub disassembly: [u8; ANNOTATION_TEXT_SIZE],
+}
+
+impl StepAnnotation {
+    /// Return the disassembly of the instruction.
+    pub fn disassembly(&self) -> &str {
+        let len = self
+            .disassembly
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(ANNOTATION_TEXT_SIZE);
+        std::str::from_utf8(&self.disassembly[..len]).unwrap_or_default()
+    }
+}
+
+/// Return the integer registers the 32-bit instruction `inst` reads, `rs1` first.
+fn sources(inst: u64) -> Vec<u64> {
+    let rs1 = (inst >> 15) & 0x1f;
+    let rs2 = (inst >> 20) & 0x1f;
+    let funct3 = (inst >> 12) & 0x7;
+    match inst & 0x7f {
+        // lr.w and lr.d
+        0x2f if (inst >> 27) == 0b00010 => vec![rs1],
+        0x33 | 0x3b | 0x2f | 0x23 | 0x63 => vec![rs1, rs2],
+        0x13 | 0x1b | 0x03 | 0x67 => vec![rs1],
+        // csrrw, csrrs and csrrc
+        0x73 if (1..=3).contains(&funct3) => vec![rs1],
+        _ => Vec::new(),
+    }
+}
+
+/// Return the template of the 32-bit instruction `inst`, assuming it completed and a branch
+/// wasn't taken.
+fn narration(inst: u64) -> Narration {
+    match inst & 0x7f {
+        0x33 | 0x3b => Narration::Compute,
+        0x13 | 0x1b => Narration::ComputeImmediate,
+        0x37 | 0x17 => Narration::UpperImmediate,
+        0x03 | 0x2f => Narration::Load,
+        0x23 => Narration::Store,
+        0x63 => Narration::BranchNotTaken,
+        0x6f | 0x67 => Narration::Jump,
+        0x73 if (inst >> 12) & 0x7 == 0 => Narration::EnvironmentCall,
+        0x73 => Narration::Csr,
+        _ => Narration::Other,
+    }
+}
+
+/// Return the address the 32-bit load, store or atomic `inst` accesses, given the value of its
+/// `rs1`.
+fn memory_addr(inst: u64, base: u64) -> Option<u64> {
+    let offset = match inst & 0x7f {
+        0x03 => (inst as i32 as i64) >> 20,
+        0x23 => (((inst & 0xfe00_0000) as i32 as i64) >> 20) | ((inst >> 7) & 0x1f) as i64,
+        0x2f => 0,
+        _ => return None,
+    };
+    Some(base.wrapping_add(offset as u64))
+}
+
+/// Execute one step like `Emulator::step_delta` and describe it for the tutorial overlay.
+/// Compressed instructions are disassembled, but their operands aren't decoded.
+pub fn step_with_annotations(emu: &mut Emulator) -> StepAnnotation {
+    let pc = emu.cpu.pc;
+    let inst = emu.read_value(pc, 4, ByteOrder::Little).unwrap_or(0);
+    let compressed = inst & 0b11 != 0b11;
+    let read = if compressed {
+        Vec::new()
+    } else {
+        sources(inst)
+    };
+    let mut sources = [OperandValue::default(); 2];
+    for (source, &reg) in sources.iter_mut().zip(&read) {
+        *source = OperandValue {
+            reg,
+            value: emu.cpu.xregs.read(reg),
+        };
+    }
+
+    let summary = emu.step_delta();
+    let delta = emu.last_delta.as_ref().expect("a recorded step");
+    let mut annotation = StepAnnotation {
+        reason: summary.reason,
+        narration: Narration::Other,
+        flags: 0,
+        source_count: read.len() as u32,
+        pc,
+        inst: delta.inst,
+        next_pc: delta.next_pc,
+        sources,
+        rd: 0,
+        result: 0,
+        addr: 0,
+        disassembly: [0; ANNOTATION_TEXT_SIZE],
+    };
+    let text = disassemble(delta.inst, pc);
+    let len = text.len().min(ANNOTATION_TEXT_SIZE - 1);
+    annotation.disassembly[..len].copy_from_slice(&text.as_bytes()[..len]);
+    annotation.narration = match (summary.reason, compressed) {
+        (StopReason::Trapped, _) => Narration::Trap,
+        (_, true) => return annotation,
+        _ => narration(inst),
+    };
+    if annotation.narration == Narration::BranchNotTaken {
+        annotation.flags |= ANNOTATION_BRANCH;
+        if delta.next_pc != pc.wrapping_add(4) {
+            annotation.flags |= ANNOTATION_TAKEN;
+            annotation.narration = Narration::BranchTaken;
+        }
+    }
+    if let Some(addr) = memory_addr(inst, sources[0].value) {
+        annotation.flags |= ANNOTATION_MEMORY;
+        annotation.addr = addr;
+    }
+    // Only the destination counts as the result, e.g. not a register a trap handler changed.
+    let rd = (inst >> 7) & 0x1f;
+    if let Some(change) = delta.xregs.iter().find(|change| change.index == rd) {
+        annotation.flags |= ANNOTATION_WRITES_RD;
+        annotation.rd = rd;
+        annotation.result = change.new;
+    }
+    annotation
+}
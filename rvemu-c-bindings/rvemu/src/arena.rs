@@ -0,0 +1,237 @@
+//! The arena module runs bots against each other on one emulator. The bots are programs loaded
+//! with `Emulator::load_program_at` that take turns in quanta of steps, each with its own
+//! registers. After every quantum the game arbitrates: it reads the shared state, serves the
+//! bot's environment call if it made one, and decides whether the match goes on.
+
+use crate::context::HartContext;
+use crate::cpu::Mode;
+use crate::emulator::Emulator;
+use crate::pmp::{self, PMP_A_TOR, PMP_R, PMP_W, PMP_X};
+use crate::run::{RunSummary, StopReason};
+
+/// How the bots share memory.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ArenaMemory {
+    /// Bots run in machine mode and can access all memory.
+    Shared = 0,
+    /// Bots run in user mode and PMP confines each to its own region and the shared region.
+    /// The arena uses PMP entries 0 to 3 while a bot runs.
+    Partitioned = 1,
+}
+
+/// The state of a bot in a match.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BotState {
+    /// The bot takes turns.
+    Running = 0,
+    /// The bot called `exit`.
+    Exited = 1,
+    /// An instruction of the bot raised an exception or executed `ebreak`.
+    Trapped = 2,
+    /// The bot used up its steps.
+    OutOfSteps = 3,
+    /// The game eliminated the bot.
+    Eliminated = 4,
+}
+
+/// The status of a bot. The layout is C-compatible so it can be copied over the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct BotStatus {
+    /// Whether the bot still takes turns, and why not.
+    pub state: BotState,
+    /// The number of instructions the bot retired.
+    pub steps: u64,
+    /// The program counter of the bot.
+    pub pc: u64,
+    /// The exit code for `Exited`, otherwise 0.
+    pub exit_code: u64,
+    /// The exception code for `Trapped`, otherwise 0.
+    pub cause: u64,
+}
+
+/// What the game decides after a quantum of a bot.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Arbitration {
+    /// Go on with the next bot.
+    Continue = 0,
+    /// Stop running the bot that just ran.
+    Eliminate = 1,
+    /// End the match now.
+    EndMatch = 2,
+}
+
+/// A bot taking part in a match.
+#[derive(Debug)]
+struct Bot {
+    /// Its registers while another bot runs.
+    context: HartContext,
+    /// The memory it owns, `[start, end)`.
+    region: (u64, u64),
+    status: BotStatus,
+}
+
+/// Two or more bots taking turns on one emulator.
+#[derive(Debug)]
+pub struct Arena {
+    bots: Vec<Bot>,
+    quantum: u64,
+    memory: ArenaMemory,
+    shared: Option<(u64, u64)>,
+}
+
+impl Arena {
+    /// Create an arena where bots run `quantum` steps per turn.
+    pub fn new(quantum: u64, memory: ArenaMemory) -> Self {
+        Self {
+            bots: Vec::new(),
+            quantum,
+            memory,
+            shared: None,
+        }
+    }
+
+    /// Let every bot access `[base, base + size)` in partitioned mode, e.g. the game board.
+    pub fn set_shared_region(&mut self, base: u64, size: u64) {
+        self.shared = Some((base, base + size));
+    }
+
+    /// Add the loaded program `program` of `emu` as a bot and return its index. It owns the
+    /// `region_size` bytes from the start of its image, and its stack starts at the end of
+    /// them. It may run `max_steps` steps over the whole match. Returns `None` if there's no
+    /// such program.
+    pub fn add_bot(
+        &mut self,
+        emu: &Emulator,
+        program: usize,
+        region_size: u64,
+        max_steps: u64,
+    ) -> Option<usize> {
+        let program = emu.programs.get(program)?;
+        let region = (program.base, program.base + region_size);
+        let mode = match self.memory {
+            ArenaMemory::Shared => Mode::Machine,
+            ArenaMemory::Partitioned => Mode::User,
+        };
+        let mut context = HartContext::new(program.entry, region.1, mode);
+        context.set_quota(Some(max_steps));
+        self.bots.push(Bot {
+            context,
+            region,
+            status: BotStatus {
+                state: BotState::Running,
+                steps: 0,
+                pc: program.entry,
+                exit_code: 0,
+                cause: 0,
+            },
+        });
+        Some(self.bots.len() - 1)
+    }
+
+    /// Return the status of the bot `index`.
+    pub fn status(&self, index: usize) -> Option<BotStatus> {
+        self.bots.get(index).map(|bot| bot.status)
+    }
+
+    /// Change the number of steps the bot `index` may run over the whole match, e.g. to reward
+    /// it. A bot that was out of steps takes turns again if the new limit allows it. Returns
+    /// false if there's no such bot.
+    pub fn set_max_steps(&mut self, index: usize, max_steps: u64) -> bool {
+        let bot = match self.bots.get_mut(index) {
+            Some(bot) => bot,
+            None => return false,
+        };
+        bot.context.set_quota(Some(max_steps));
+        if bot.status.state == BotState::OutOfSteps && !bot.context.is_exhausted() {
+            bot.status.state = BotState::Running;
+        }
+        true
+    }
+
+    /// Return the saved registers of the bot `index`, e.g. to pass it the state of the game.
+    /// They're up to date whenever `run` isn't running.
+    pub fn context_mut(&mut self, index: usize) -> Option<&mut HartContext> {
+        self.bots.get_mut(index).map(|bot| &mut bot.context)
+    }
+
+    /// Confine the running bot to `region` and the shared region with PMP, or lift the
+    /// confinement if `region` is `None`.
+    fn set_partition(&self, emu: &mut Emulator, region: Option<(u64, u64)>) {
+        let state = &mut emu.cpu.state;
+        let entries = [region, self.shared.filter(|_| region.is_some())];
+        for (i, entry) in entries.iter().enumerate() {
+            let (start, end, cfg) = match (i, entry) {
+                (0, Some((start, end))) => (*start, *end, PMP_A_TOR | PMP_R | PMP_W | PMP_X),
+                (_, Some((start, end))) => (*start, *end, PMP_A_TOR | PMP_R | PMP_W),
+                (_, None) => (0, 0, 0),
+            };
+            pmp::set_entry(state, 2 * i, 0, start >> 2);
+            pmp::set_entry(state, 2 * i + 1, cfg, end >> 2);
+        }
+    }
+
+    /// Run one quantum of the bot `index` and update its status.
+    fn run_quantum(&mut self, emu: &mut Emulator, index: usize) -> RunSummary {
+        let partitioned = self.memory == ArenaMemory::Partitioned;
+        if partitioned {
+            self.set_partition(emu, Some(self.bots[index].region));
+        }
+        let summary = self.bots[index].context.run(emu, self.quantum);
+        if partitioned {
+            self.set_partition(emu, None);
+        }
+
+        let bot = &mut self.bots[index];
+        let status = &mut bot.status;
+        status.steps = bot.context.retired();
+        status.pc = bot.context.pc;
+        match summary.reason {
+            StopReason::Exited => {
+                status.state = BotState::Exited;
+                status.exit_code = summary.exit_code;
+            }
+            StopReason::Trapped | StopReason::Breakpoint => {
+                status.state = BotState::Trapped;
+                status.cause = summary.cause;
+            }
+            StopReason::LimitExceeded if bot.context.is_exhausted() => {
+                status.state = BotState::OutOfSteps
+            }
+            _ => {}
+        }
+        summary
+    }
+
+    /// Run up to `rounds` rounds, in which every running bot runs one quantum in the order they
+    /// were added. After each quantum, `arbiter` is called with the emulator, the bot and the
+    /// summary of the quantum; the bot's registers are in its context by then. The match ends
+    /// early when no bot is running or the arbiter ends it. Returns the number of rounds played.
+    pub fn run<F>(&mut self, emu: &mut Emulator, rounds: u64, mut arbiter: F) -> u64
+    where
+        F: FnMut(&mut Emulator, usize, &RunSummary) -> Arbitration,
+    {
+        for round in 0..rounds {
+            let mut ran = false;
+            for index in 0..self.bots.len() {
+                if self.bots[index].status.state != BotState::Running {
+                    continue;
+                }
+                ran = true;
+                let summary = self.run_quantum(emu, index);
+                match arbiter(emu, index, &summary) {
+                    Arbitration::Continue => {}
+                    Arbitration::Eliminate => self.bots[index].status.state = BotState::Eliminated,
+                    Arbitration::EndMatch => return round + 1,
+                }
+            }
+            if !ran {
+                return round;
+            }
+        }
+        rounds
+    }
+}
@@ -0,0 +1,1346 @@
+//! The assembler module turns RISC-V assembly into machine code, one instruction per line. It
+//! encodes RV32I or RV64I with Zicsr, Zifencei and the trap-return instructions, plus the M
+//! extension if the ISA has it, and accepts everything the disassembler prints, so a program can
+//! be assembled, disassembled and assembled again. If the ISA has the C extension, every
+//! instruction with a compressed encoding is emitted in 2 bytes.
+//!
+//! Programs can also use labels as branch and jump targets and the common pseudo-instructions
+//! `nop`, `mv`, `li`, `la`, `j`, `call` and `ret`. They're assembled in two passes: the first
+//! expands the pseudo-instructions and finds the address of every label, the second encodes the
+//! instructions with the labels resolved. With the C extension, the size of a branch or a jump
+//! depends on the distance to its label, so the layout is settled in between: every instruction
+//! starts compressed, and the ones that can't be grow to 4 bytes until no more do.
+//!
+//! Data is defined with the directives `.byte`, `.half`, `.word` and `.dword`, which take
+//! comma-separated numbers, or labels for the last two, `.ascii` and `.asciz`, which take
+//! strings, `.space`, which takes a size and an optional fill byte, and `.align`, which pads
+//! with zeros to a multiple of 2 to the power of its operand. What follows `.data` goes into the
+//! data section, placed after the text, until `.text` switches back. `assemble_object` keeps the
+//! sections apart, with relocations to load them anywhere.
+//!
+//! Immediates are constant expressions, e.g. `4*8+2` or `'a'`, which the expression module
+//! evaluates. `%hi(label)` and `%lo(label)` take the upper 20 and lower 12 bits of the address of
+//! a label for a `lui` and the `addi`, load or store after it. `lui` sign-extends in RV64I, so
+//! they only reach labels in the lower 2 GiB there, as with other assemblers.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::bus::DRAM_BASE;
+use crate::compress::compress;
+use crate::csr_names::standard_address;
+use crate::expression::{evaluate, unescape};
+use crate::isa::register_index;
+use crate::metrics;
+use crate::object::{Object, Relocation, RelocationKind, Section};
+
+pub use crate::xlen::Xlen;
+
+/// The instruction set a program is assembled for.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Isa {
+    pub xlen: Xlen,
+    /// Whether the multiplication and division instructions of the M extension are available.
+    pub m: bool,
+    /// Whether instructions are emitted in their compressed encoding of the C extension when
+    /// they have one.
+    pub c: bool,
+}
+
+impl Isa {
+    /// Return the base integer ISA of `xlen` without extensions, RV32I or RV64I.
+    pub fn base(xlen: Xlen) -> Self {
+        Self {
+            xlen,
+            m: false,
+            c: false,
+        }
+    }
+
+    /// Parse an ISA string such as `rv32im` or `RV64I_Zicsr_Zifencei`. Zicsr and Zifencei are
+    /// always available, so naming them changes nothing.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.to_ascii_lowercase();
+        let (xlen, rest) = match (text.strip_prefix("rv32"), text.strip_prefix("rv64")) {
+            (Some(rest), _) => (Xlen::Rv32, rest),
+            (_, Some(rest)) => (Xlen::Rv64, rest),
+            _ => return Err(format!("`{}` doesn't start with rv32 or rv64", text)),
+        };
+        let mut parts = rest.split('_');
+        let letters = match parts.next().unwrap_or_default().strip_prefix('i') {
+            Some(letters) => letters,
+            None => return Err(format!("`{}` doesn't have the I base", text)),
+        };
+        let mut isa = Self::base(xlen);
+        for letter in letters.chars() {
+            match letter {
+                'm' => isa.m = true,
+                'c' => isa.c = true,
+                _ => return Err(format!("the {} extension isn't supported", letter)),
+            }
+        }
+        for extension in parts {
+            if extension != "zicsr" && extension != "zifencei" {
+                return Err(format!("the {} extension isn't supported", extension));
+            }
+        }
+        Ok(isa)
+    }
+}
+
+/// Why a program doesn't assemble.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AssembleError {
+    /// The 1-based number of the line, or 0 if the error isn't about a line, e.g. because the
+    /// assembly was cancelled.
+    pub line: u64,
+    /// A message for the player.
+    pub message: String,
+}
+
+/// A label of an assembled program.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Symbol {
+    pub name: String,
+    /// The offset of the label in the program image, i.e. its address relative to where the
+    /// program is loaded.
+    pub addr: u64,
+    /// The section the label is in.
+    pub section: Section,
+    /// The 1-based number of the line the label is defined on.
+    pub line: u64,
+    /// Whether an instruction refers to the label.
+    pub referenced: bool,
+}
+
+/// The labels of a program in the order they're defined, which is also the order of their
+/// addresses.
+#[derive(Default)]
+struct Labels<'a> {
+    /// The index of each label in `symbols`, by name.
+    index: HashMap<&'a str, usize>,
+    symbols: Vec<Symbol>,
+    /// The index of the statement of its section each label of `symbols` is defined before,
+    /// which is the number of statements of the section if it's at the end.
+    statements: Vec<usize>,
+}
+
+impl<'a> Labels<'a> {
+    /// Define `name` in `section`, before its statement `statement`, on `line`. Returns false if
+    /// it's already defined. It's at offset 0 until the layout moves it.
+    fn define(&mut self, name: &'a str, section: Section, statement: usize, line: u64) -> bool {
+        if self.index.contains_key(name) {
+            return false;
+        }
+        self.index.insert(name, self.symbols.len());
+        self.symbols.push(Symbol {
+            name: name.to_string(),
+            addr: 0,
+            section,
+            line,
+            referenced: false,
+        });
+        self.statements.push(statement);
+        true
+    }
+
+    /// Return the section of `name`, or `None` if it isn't defined.
+    fn section(&self, name: &str) -> Option<Section> {
+        self.index.get(name).map(|&index| self.symbols[index].section)
+    }
+
+    /// Return the address of `name` and mark it as referenced.
+    fn addr(&mut self, name: &str) -> Result<i64, String> {
+        let symbol = match self.index.get(name) {
+            Some(&index) => &mut self.symbols[index],
+            None => return Err(format!("unknown label `{}`", name)),
+        };
+        symbol.referenced = true;
+        Ok(symbol.addr as i64)
+    }
+}
+
+/// The operands of an instruction, which also decide how they're encoded.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Format {
+    /// `rd, rs1, rs2`
+    Register,
+    /// `rd, rs1, imm`
+    Immediate,
+    /// `rd, rs1, shamt`, where the shift amount is below XLEN.
+    Shift,
+    /// `rd, rs1, shamt` of a 32-bit shift of RV64I, where the shift amount is below 32.
+    ShiftWord,
+    /// `rd, offset(rs1)`
+    Load,
+    /// `rs2, offset(rs1)`
+    Store,
+    /// `rs1, rs2, offset`, where the offset is relative to the branch.
+    Branch,
+    /// `rd, imm`, where the immediate is the upper 20 bits.
+    Upper,
+    /// `rd, offset` or `offset`, where the offset is relative to the jump.
+    Jump,
+    /// `rd, offset(rs1)` or `rd, rs1, offset`
+    JumpRegister,
+    /// `rd, csr, rs1`
+    Csr,
+    /// `rd, csr, uimm`
+    CsrImmediate,
+    /// `pred, succ`, or nothing for `iorw, iorw`.
+    Fence,
+    /// No operands.
+    None,
+}
+
+/// An instruction the assembler knows.
+struct Opcode {
+    name: &'static str,
+    format: Format,
+    /// The fixed bits of the encoding: the opcode, funct3, funct7 and any constant fields.
+    bits: u32,
+}
+
+/// Return the fixed bits of an encoding with `opcode`, `funct3` and `funct7`.
+const fn bits(opcode: u32, funct3: u32, funct7: u32) -> u32 {
+    opcode | funct3 << 12 | funct7 << 25
+}
+
+const fn op(name: &'static str, format: Format, bits: u32) -> Opcode {
+    Opcode { name, format, bits }
+}
+
+/// The instructions of RV32I, Zicsr, Zifencei and the trap-return instructions.
+const RV32_OPCODES: &[Opcode] = &[
+    op("lui", Format::Upper, 0x37),
+    op("auipc", Format::Upper, 0x17),
+    op("jal", Format::Jump, 0x6f),
+    op("jalr", Format::JumpRegister, 0x67),
+    op("beq", Format::Branch, bits(0x63, 0, 0)),
+    op("bne", Format::Branch, bits(0x63, 1, 0)),
+    op("blt", Format::Branch, bits(0x63, 4, 0)),
+    op("bge", Format::Branch, bits(0x63, 5, 0)),
+    op("bltu", Format::Branch, bits(0x63, 6, 0)),
+    op("bgeu", Format::Branch, bits(0x63, 7, 0)),
+    op("lb", Format::Load, bits(0x03, 0, 0)),
+    op("lh", Format::Load, bits(0x03, 1, 0)),
+    op("lw", Format::Load, bits(0x03, 2, 0)),
+    op("lbu", Format::Load, bits(0x03, 4, 0)),
+    op("lhu", Format::Load, bits(0x03, 5, 0)),
+    op("sb", Format::Store, bits(0x23, 0, 0)),
+    op("sh", Format::Store, bits(0x23, 1, 0)),
+    op("sw", Format::Store, bits(0x23, 2, 0)),
+    op("addi", Format::Immediate, bits(0x13, 0, 0)),
+    op("slti", Format::Immediate, bits(0x13, 2, 0)),
+    op("sltiu", Format::Immediate, bits(0x13, 3, 0)),
+    op("xori", Format::Immediate, bits(0x13, 4, 0)),
+    op("ori", Format::Immediate, bits(0x13, 6, 0)),
+    op("andi", Format::Immediate, bits(0x13, 7, 0)),
+    op("slli", Format::Shift, bits(0x13, 1, 0x00)),
+    op("srli", Format::Shift, bits(0x13, 5, 0x00)),
+    op("srai", Format::Shift, bits(0x13, 5, 0x20)),
+    op("add", Format::Register, bits(0x33, 0, 0x00)),
+    op("sub", Format::Register, bits(0x33, 0, 0x20)),
+    op("sll", Format::Register, bits(0x33, 1, 0x00)),
+    op("slt", Format::Register, bits(0x33, 2, 0x00)),
+    op("sltu", Format::Register, bits(0x33, 3, 0x00)),
+    op("xor", Format::Register, bits(0x33, 4, 0x00)),
+    op("srl", Format::Register, bits(0x33, 5, 0x00)),
+    op("sra", Format::Register, bits(0x33, 5, 0x20)),
+    op("or", Format::Register, bits(0x33, 6, 0x00)),
+    op("and", Format::Register, bits(0x33, 7, 0x00)),
+    op("fence", Format::Fence, bits(0x0f, 0, 0)),
+    op("fence.i", Format::None, bits(0x0f, 1, 0)),
+    op("ecall", Format::None, 0x0000_0073),
+    op("ebreak", Format::None, 0x0010_0073),
+    op("sret", Format::None, 0x1020_0073),
+    op("mret", Format::None, 0x3020_0073),
+    op("wfi", Format::None, 0x1050_0073),
+    op("csrrw", Format::Csr, bits(0x73, 1, 0)),
+    op("csrrs", Format::Csr, bits(0x73, 2, 0)),
+    op("csrrc", Format::Csr, bits(0x73, 3, 0)),
+    op("csrrwi", Format::CsrImmediate, bits(0x73, 5, 0)),
+    op("csrrsi", Format::CsrImmediate, bits(0x73, 6, 0)),
+    op("csrrci", Format::CsrImmediate, bits(0x73, 7, 0)),
+];
+
+/// The instructions RV64I adds.
+const RV64_OPCODES: &[Opcode] = &[
+    op("lwu", Format::Load, bits(0x03, 6, 0)),
+    op("ld", Format::Load, bits(0x03, 3, 0)),
+    op("sd", Format::Store, bits(0x23, 3, 0)),
+    op("addiw", Format::Immediate, bits(0x1b, 0, 0)),
+    op("slliw", Format::ShiftWord, bits(0x1b, 1, 0x00)),
+    op("srliw", Format::ShiftWord, bits(0x1b, 5, 0x00)),
+    op("sraiw", Format::ShiftWord, bits(0x1b, 5, 0x20)),
+    op("addw", Format::Register, bits(0x3b, 0, 0x00)),
+    op("subw", Format::Register, bits(0x3b, 0, 0x20)),
+    op("sllw", Format::Register, bits(0x3b, 1, 0x00)),
+    op("srlw", Format::Register, bits(0x3b, 5, 0x00)),
+    op("sraw", Format::Register, bits(0x3b, 5, 0x20)),
+];
+
+/// The instructions of the M extension.
+const M_OPCODES: &[Opcode] = &[
+    op("mul", Format::Register, bits(0x33, 0, 0x01)),
+    op("mulh", Format::Register, bits(0x33, 1, 0x01)),
+    op("mulhsu", Format::Register, bits(0x33, 2, 0x01)),
+    op("mulhu", Format::Register, bits(0x33, 3, 0x01)),
+    op("div", Format::Register, bits(0x33, 4, 0x01)),
+    op("divu", Format::Register, bits(0x33, 5, 0x01)),
+    op("rem", Format::Register, bits(0x33, 6, 0x01)),
+    op("remu", Format::Register, bits(0x33, 7, 0x01)),
+];
+
+/// The instructions the M extension adds in RV64.
+const RV64M_OPCODES: &[Opcode] = &[
+    op("mulw", Format::Register, bits(0x3b, 0, 0x01)),
+    op("divw", Format::Register, bits(0x3b, 4, 0x01)),
+    op("divuw", Format::Register, bits(0x3b, 5, 0x01)),
+    op("remw", Format::Register, bits(0x3b, 6, 0x01)),
+    op("remuw", Format::Register, bits(0x3b, 7, 0x01)),
+];
+
+/// Return the instruction `name` of `isa`.
+fn opcode(name: &str, isa: Isa) -> Result<&'static Opcode, String> {
+    let find = |opcodes: &'static [Opcode]| opcodes.iter().find(|opcode| opcode.name == name);
+    if let Some(opcode) = find(RV32_OPCODES) {
+        return Ok(opcode);
+    }
+    if let Some(opcode) = find(RV64_OPCODES) {
+        return match isa.xlen {
+            Xlen::Rv64 => Ok(opcode),
+            Xlen::Rv32 => Err(format!("`{}` is only available in RV64I", name)),
+        };
+    }
+    if let Some(opcode) = find(M_OPCODES) {
+        if !isa.m {
+            return Err(format!("`{}` needs the M extension", name));
+        }
+        return Ok(opcode);
+    }
+    match find(RV64M_OPCODES) {
+        Some(_) if !isa.m => Err(format!("`{}` needs the M extension", name)),
+        Some(opcode) if isa.xlen == Xlen::Rv64 => Ok(opcode),
+        Some(_) => Err(format!("`{}` is only available in RV64", name)),
+        None => Err(format!("unknown instruction `{}`", name)),
+    }
+}
+
+/// Return the number of the register `name`.
+fn register(name: &str) -> Result<u32, String> {
+    register_index(&name.to_ascii_lowercase())
+        .map(|index| index as u32)
+        .ok_or_else(|| format!("unknown register `{}`", name))
+}
+
+/// Parse an immediate, a constant expression, that must lie within `min..=max`.
+fn immediate(text: &str, min: i64, max: i64) -> Result<i64, String> {
+    let value = evaluate(text)?;
+    if value < min || value > max {
+        return Err(format!(
+            "immediate {} is out of range ({} to {})",
+            text, min, max
+        ));
+    }
+    Ok(value)
+}
+
+/// Parse the offset of a branch or jump, which is even and within `bits` signed bits.
+fn offset(text: &str, bits: u32) -> Result<i64, String> {
+    let max = (1 << (bits - 1)) - 2;
+    let value = immediate(text, -max - 2, max)?;
+    if value & 1 != 0 {
+        return Err(format!("offset {} is odd", text));
+    }
+    Ok(value)
+}
+
+/// Parse a memory operand, `offset(rs1)` or `(rs1)`, into the offset and the register. The
+/// offset can have parentheses of its own, e.g. `(4*2)(a0)`.
+fn memory(text: &str) -> Result<(i64, u32), String> {
+    let invalid = || format!("expected `offset(register)`, found `{}`", text);
+    let open = text.rfind('(').ok_or_else(invalid)?;
+    let base = text[open + 1..].strip_suffix(')').ok_or_else(invalid)?;
+    let offset = match text[..open].trim() {
+        "" => 0,
+        offset => immediate(offset, -2048, 2047)?,
+    };
+    Ok((offset, register(base.trim())?))
+}
+
+/// Parse a CSR, by address or by its standard name.
+fn csr(text: &str) -> Result<u32, String> {
+    if let Some(addr) = standard_address(&text.to_ascii_lowercase()) {
+        return Ok(addr as u32);
+    }
+    if !text.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("unknown CSR `{}`", text));
+    }
+    immediate(text, 0, 0xfff).map(|addr| addr as u32)
+}
+
+/// Parse the predecessor or successor set of a fence, e.g. `rw`.
+fn fence_set(text: &str) -> Result<u32, String> {
+    let mut set = 0;
+    for c in text.to_ascii_lowercase().chars() {
+        let bit = match c {
+            'i' => 8,
+            'o' => 4,
+            'r' => 2,
+            'w' => 1,
+            _ => return Err(format!("invalid fence set `{}`", text)),
+        };
+        set |= bit;
+    }
+    if set == 0 {
+        return Err(format!("invalid fence set `{}`", text));
+    }
+    Ok(set)
+}
+
+fn r_type(bits: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    bits | rd << 7 | rs1 << 15 | rs2 << 20
+}
+
+fn i_type(bits: u32, rd: u32, rs1: u32, imm: i64) -> u32 {
+    bits | rd << 7 | rs1 << 15 | (imm as u32 & 0xfff) << 20
+}
+
+fn s_type(bits: u32, rs1: u32, rs2: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits | (imm & 0x1f) << 7 | rs1 << 15 | rs2 << 20 | (imm >> 5 & 0x7f) << 25
+}
+
+fn b_type(bits: u32, rs1: u32, rs2: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits | (imm >> 11 & 1) << 7
+        | (imm >> 1 & 0xf) << 8
+        | rs1 << 15
+        | rs2 << 20
+        | (imm >> 5 & 0x3f) << 25
+        | (imm >> 12 & 1) << 31
+}
+
+fn u_type(bits: u32, rd: u32, imm: i64) -> u32 {
+    bits | rd << 7 | (imm as u32 & 0xfffff) << 12
+}
+
+fn j_type(bits: u32, rd: u32, imm: i64) -> u32 {
+    let imm = imm as u32;
+    bits | rd << 7
+        | (imm >> 12 & 0xff) << 12
+        | (imm >> 11 & 1) << 20
+        | (imm >> 1 & 0x3ff) << 21
+        | (imm >> 20 & 1) << 31
+}
+
+/// Encode the operands of `opcode`.
+fn encode_operands(opcode: &Opcode, operands: &[&str], xlen: Xlen) -> Result<u32, String> {
+    let (counts, usage): (&[usize], &str) = match opcode.format {
+        Format::Register => (&[3], "rd, rs1, rs2"),
+        Format::Immediate => (&[3], "rd, rs1, imm"),
+        Format::Shift | Format::ShiftWord => (&[3], "rd, rs1, shamt"),
+        Format::Load => (&[2], "rd, offset(rs1)"),
+        Format::Store => (&[2], "rs2, offset(rs1)"),
+        Format::Branch => (&[3], "rs1, rs2, offset"),
+        Format::Upper => (&[2], "rd, imm"),
+        Format::Jump => (&[1, 2], "rd, offset"),
+        Format::JumpRegister => (&[2, 3], "rd, offset(rs1)"),
+        Format::Csr => (&[3], "rd, csr, rs1"),
+        Format::CsrImmediate => (&[3], "rd, csr, uimm"),
+        Format::Fence => (&[0, 2], "pred, succ"),
+        Format::None => (&[0], ""),
+    };
+    if !counts.contains(&operands.len()) {
+        return Err(match usage {
+            "" => format!("`{}` takes no operands", opcode.name),
+            usage => format!("expected `{} {}`", opcode.name, usage),
+        });
+    }
+
+    let bits = opcode.bits;
+    Ok(match opcode.format {
+        Format::Register => r_type(
+            bits,
+            register(operands[0])?,
+            register(operands[1])?,
+            register(operands[2])?,
+        ),
+        Format::Immediate => i_type(
+            bits,
+            register(operands[0])?,
+            register(operands[1])?,
+            immediate(operands[2], -2048, 2047)?,
+        ),
+        Format::Shift | Format::ShiftWord => {
+            let width = match opcode.format {
+                Format::Shift => xlen.bits(),
+                _ => 32,
+            };
+            let shamt = immediate(operands[2], 0, width as i64 - 1)?;
+            // The shift amount takes the low bits of funct7 in RV64I.
+            r_type(
+                bits,
+                register(operands[0])?,
+                register(operands[1])?,
+                shamt as u32,
+            )
+        }
+        Format::Load => {
+            let (imm, rs1) = memory(operands[1])?;
+            i_type(bits, register(operands[0])?, rs1, imm)
+        }
+        Format::Store => {
+            let (imm, rs1) = memory(operands[1])?;
+            s_type(bits, rs1, register(operands[0])?, imm)
+        }
+        Format::Branch => b_type(
+            bits,
+            register(operands[0])?,
+            register(operands[1])?,
+            offset(operands[2], 13)?,
+        ),
+        Format::Upper => u_type(
+            bits,
+            register(operands[0])?,
+            immediate(operands[1], -(1 << 19), (1 << 20) - 1)?,
+        ),
+        Format::Jump => match operands {
+            [target] => j_type(bits, 1, offset(target, 21)?),
+            _ => j_type(bits, register(operands[0])?, offset(operands[1], 21)?),
+        },
+        Format::JumpRegister => match operands {
+            [rd, base] => {
+                let (imm, rs1) = memory(base)?;
+                i_type(bits, register(rd)?, rs1, imm)
+            }
+            _ => i_type(
+                bits,
+                register(operands[0])?,
+                register(operands[1])?,
+                immediate(operands[2], -2048, 2047)?,
+            ),
+        },
+        Format::Csr => {
+            let csr = csr(operands[1])?;
+            r_type(bits, register(operands[0])?, register(operands[2])?, 0) | csr << 20
+        }
+        Format::CsrImmediate => {
+            let csr = csr(operands[1])?;
+            let uimm = immediate(operands[2], 0, 31)? as u32;
+            r_type(bits, register(operands[0])?, uimm, 0) | csr << 20
+        }
+        Format::Fence => {
+            let (pred, succ) = match operands {
+                [pred, succ] => (fence_set(pred)?, fence_set(succ)?),
+                _ => (0xf, 0xf),
+            };
+            bits | pred << 24 | succ << 20
+        }
+        Format::None => bits,
+    })
+}
+
+/// Encode the instruction `line`, e.g. `addi a0, a0, 1`. Operands are separated by commas,
+/// spaces, or both. Branch and jump targets are offsets in bytes from the instruction.
+pub fn encode(line: &str, xlen: Xlen) -> Result<u32, String> {
+    let (name, operands) = words(line)?;
+    encode_operands(opcode(&name, Isa::base(xlen))?, &operands, xlen)
+}
+
+/// Split the instruction `line` into its lowercase name and its operands. Operands separated by
+/// commas can have spaces in them, e.g. `4 * 8` or `' '`.
+fn words(line: &str) -> Result<(String, Vec<&str>), String> {
+    let line = line.trim();
+    let (name, operands) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim()),
+        None => (line, ""),
+    };
+    if name.is_empty() {
+        return Err("expected an instruction".to_string());
+    }
+    let operands = if unquoted(operands, ',').next().is_some() {
+        split_operands(operands)
+    } else {
+        operands.split_whitespace().collect()
+    };
+    Ok((name.to_ascii_lowercase(), operands))
+}
+
+/// Return the indices of the `target` characters of `text` outside of string and character
+/// literals.
+fn unquoted(text: &str, target: char) -> impl Iterator<Item = usize> + '_ {
+    let mut quote = None;
+    let mut escaped = false;
+    text.char_indices().filter_map(move |(i, c)| {
+        match (c, quote) {
+            _ if escaped => escaped = false,
+            ('\\', Some(_)) => escaped = true,
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (c, None) if c == target => return Some(i),
+            _ => {}
+        }
+        None
+    })
+}
+
+/// Split `text` at the commas outside of literals into its trimmed, non-empty operands.
+fn split_operands(text: &str) -> Vec<&str> {
+    let mut operands = Vec::new();
+    let mut start = 0;
+    for comma in unquoted(text, ',').chain(std::iter::once(text.len())) {
+        let operand = text[start..comma].trim();
+        if !operand.is_empty() {
+            operands.push(operand);
+        }
+        start = comma + 1;
+    }
+    operands
+}
+
+/// Return the code of a source line: the line without its comment, which starts with a `#`
+/// outside of a string or character literal, and surrounding whitespace.
+fn code(line: &str) -> &str {
+    match unquoted(line, '#').next() {
+        Some(comment) => line[..comment].trim(),
+        None => line.trim(),
+    }
+}
+
+/// Return the index of the colon ending the label that `code` starts with, if it does. Colons in
+/// literals don't end labels.
+fn label_colon(code: &str) -> Option<usize> {
+    unquoted(code, ':').next()
+}
+
+/// An operand of an instruction, resolved once the address of every label is known.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Operand<'a> {
+    /// An operand assembled as it is.
+    Text(String),
+    /// The target of a branch or jump: a label, or an offset in bytes.
+    Target(&'a str),
+    /// The upper 20 bits of the distance from the first instruction of a pseudo-instruction to
+    /// a label, for its `auipc`.
+    PcRelHi(&'a str),
+    /// The lower 12 bits of that distance, for the instruction after the `auipc`.
+    PcRelLo(&'a str),
+    /// The upper 20 bits of the address of a label, `%hi(label)`, for a `lui`.
+    Hi(&'a str),
+    /// The lower 12 bits of the address of a label, `%lo(label)`, followed by the base register
+    /// of a memory operand, e.g. `(a0)`, if there's one.
+    Lo(&'a str, &'a str),
+}
+
+/// An instruction of the program, after pseudo-instructions are expanded.
+struct Instruction<'a> {
+    opcode: &'static Opcode,
+    operands: Vec<Operand<'a>>,
+    /// The size of its encoding in bytes: 2 if it's emitted compressed, otherwise 4.
+    size: u64,
+}
+
+/// A value of a data directive, resolved once the address of every label is known.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Value<'a> {
+    Number(i64),
+    /// The address of a label.
+    Label(&'a str),
+}
+
+/// What a statement assembles to.
+enum Contents<'a> {
+    Instructions(Vec<Instruction<'a>>),
+    /// The values of `.byte`, `.half`, `.word` or `.dword`, of `size` bytes each.
+    Values { size: u64, values: Vec<Value<'a>> },
+    /// The bytes of a string or of `.space`.
+    Bytes(Vec<u8>),
+    /// Zeros up to the next multiple of the alignment.
+    Align(u64),
+}
+
+/// A source line with an instruction or a data directive, and what it assembles to.
+struct Statement<'a> {
+    /// The 1-based number of the line.
+    line: u64,
+    /// The offset of the statement in the program image.
+    addr: u64,
+    contents: Contents<'a>,
+}
+
+impl Statement<'_> {
+    /// Return the size of the statement in bytes, at its offset.
+    fn size(&self) -> u64 {
+        match &self.contents {
+            Contents::Instructions(instructions) => instructions.iter().map(|i| i.size).sum(),
+            Contents::Values { size, values } => size * values.len() as u64,
+            Contents::Bytes(bytes) => bytes.len() as u64,
+            Contents::Align(align) => align_up(self.addr, *align) - self.addr,
+        }
+    }
+}
+
+/// Return `addr` rounded up to a multiple of `align`, a power of 2.
+fn align_up(addr: u64, align: u64) -> u64 {
+    (addr + align - 1) & !(align - 1)
+}
+
+/// The alignment of the data section when it doesn't ask for more with `.align`.
+pub const DATA_ALIGNMENT: u64 = 8;
+
+/// The largest `.space`, in bytes.
+pub const MAX_SPACE: u64 = 1 << 24;
+
+/// What a directive does.
+enum Directive<'a> {
+    /// Switch to a section.
+    Section(Section),
+    /// Add a statement to the current section.
+    Contents(Contents<'a>),
+}
+
+/// Parse the comma-separated string literals of `text`, e.g. `"a:\t", "b\n"`, into their bytes.
+/// Strings take the escapes `\n`, `\t`, `\r`, `\0`, `\\`, `\"` and `\'`.
+fn strings(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("expected strings, found `{}`", text);
+    let mut bytes = Vec::new();
+    let mut chars = text.chars();
+    loop {
+        if chars.by_ref().find(|c| !c.is_whitespace()) != Some('"') {
+            return Err(invalid());
+        }
+        loop {
+            let c = match chars.next().ok_or_else(invalid)? {
+                '"' => break,
+                '\\' => unescape(chars.next().ok_or_else(invalid)?)?,
+                c => c,
+            };
+            let mut utf8 = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+        }
+        match chars.find(|c| !c.is_whitespace()) {
+            None => return Ok(bytes),
+            Some(',') => {}
+            Some(_) => return Err(invalid()),
+        }
+    }
+}
+
+/// Parse the directive `code`, e.g. `.word 1, 2`.
+fn directive(code: &str) -> Result<Directive<'_>, String> {
+    let (name, rest) = match code.find(char::is_whitespace) {
+        Some(i) => (&code[..i], code[i..].trim()),
+        None => (code, ""),
+    };
+    let name = name.to_ascii_lowercase();
+    let operands = split_operands(rest);
+    let usage = |usage| format!("expected `{} {}`", name, usage);
+    let contents = match name.as_str() {
+        ".text" | ".data" if !operands.is_empty() => {
+            return Err(format!("`{}` takes no operands", name))
+        }
+        ".text" => return Ok(Directive::Section(Section::Text)),
+        ".data" => return Ok(Directive::Section(Section::Data)),
+        ".byte" | ".half" | ".word" | ".dword" => {
+            let (size, min, max) = match name.as_str() {
+                ".byte" => (1, i8::MIN as i64, u8::MAX as i64),
+                ".half" => (2, i16::MIN as i64, u16::MAX as i64),
+                ".word" => (4, i32::MIN as i64, u32::MAX as i64),
+                _ => (8, i64::MIN, i64::MAX),
+            };
+            if operands.is_empty() {
+                return Err(usage("values"));
+            }
+            let values = operands
+                .iter()
+                .map(|operand| match operand {
+                    _ if is_label(operand) && size >= 4 => Ok(Value::Label(operand)),
+                    _ => immediate(operand, min, max).map(Value::Number),
+                })
+                .collect::<Result<Vec<Value>, String>>()?;
+            Contents::Values { size, values }
+        }
+        ".ascii" | ".asciz" => {
+            let mut bytes = strings(rest)?;
+            if name == ".asciz" {
+                bytes.push(0);
+            }
+            Contents::Bytes(bytes)
+        }
+        ".space" => {
+            let (size, fill) = match operands.as_slice() {
+                [size] => (size, 0),
+                [size, fill] => (size, immediate(fill, i8::MIN as i64, u8::MAX as i64)?),
+                _ => return Err(usage("size, fill")),
+            };
+            let size = immediate(size, 0, MAX_SPACE as i64)?;
+            Contents::Bytes(vec![fill as u8; size as usize])
+        }
+        ".align" => match operands.as_slice() {
+            [power] => Contents::Align(1 << immediate(power, 0, 12)?),
+            _ => return Err(usage("power")),
+        },
+        _ => return Err(format!("unknown directive `{}`", name)),
+    };
+    Ok(Directive::Contents(contents))
+}
+
+/// Return the instruction `name`, which must be in RV32I or RV64I, with `operands`.
+fn base<'a>(name: &str, operands: Vec<Operand<'a>>) -> Instruction<'a> {
+    Instruction {
+        opcode: opcode(name, Isa::base(Xlen::Rv64)).expect("a base instruction"),
+        operands,
+        size: 4,
+    }
+}
+
+/// Return the operand `text` assembled as it is.
+fn text<T: ToString>(text: T) -> Operand<'static> {
+    Operand::Text(text.to_string())
+}
+
+/// Return the operand `text` of an instruction, which can refer to a label with `%hi(label)` or
+/// `%lo(label)`. `%hi` and `%lo` of a constant are evaluated as it is.
+fn parse_operand(text: &str) -> Operand<'_> {
+    let split = |prefix| {
+        let inner = text.strip_prefix(prefix)?;
+        let close = inner.find(')')?;
+        let label = inner[..close].trim();
+        Some((label, &inner[close + 1..])).filter(|_| is_label(label))
+    };
+    match (split("%hi("), split("%lo(")) {
+        (Some((label, "")), _) => Operand::Hi(label),
+        (_, Some((label, base))) if base.is_empty() || base.starts_with('(') => {
+            Operand::Lo(label, base)
+        }
+        _ => Operand::Text(text.to_string()),
+    }
+}
+
+/// Append the shortest sequence of `lui`, `addi`, `addiw` and `slli` that loads `value` into
+/// `rd` to `out`.
+fn load_immediate<'a>(rd: &'a str, value: i64, xlen: Xlen, out: &mut Vec<Instruction<'a>>) {
+    let lo = value << 52 >> 52;
+    if value == value as i32 as i64 {
+        let hi = (value.wrapping_sub(lo) >> 12) & 0xfffff;
+        if hi != 0 {
+            out.push(base("lui", vec![text(rd), text(hi)]));
+        }
+        if lo != 0 || hi == 0 {
+            // `addiw` keeps the sum of `lui` and the lower bits within 32 bits in RV64I.
+            let (name, rs1) = match (hi, xlen) {
+                (0, _) => ("addi", "zero"),
+                (_, Xlen::Rv32) => ("addi", rd),
+                (_, Xlen::Rv64) => ("addiw", rd),
+            };
+            out.push(base(name, vec![text(rd), text(rs1), text(lo)]));
+        }
+        return;
+    }
+
+    // Load the upper bits without their trailing zeros, shift them into place and add the lower
+    // 12 bits.
+    let hi = (value as u64).wrapping_add(0x800) >> 12;
+    let shift = 12 + hi.trailing_zeros();
+    let hi = ((hi >> (shift - 12)) << shift) as i64 >> shift;
+    load_immediate(rd, hi, xlen, out);
+    out.push(base("slli", vec![text(rd), text(rd), text(shift)]));
+    if lo != 0 {
+        out.push(base("addi", vec![text(rd), text(rd), text(lo)]));
+    }
+}
+
+/// Expand the pseudo-instruction `name` with `operands`, or return `None` if it isn't one.
+fn expand_pseudo<'a>(
+    name: &str,
+    operands: &[&'a str],
+    xlen: Xlen,
+) -> Option<Result<Vec<Instruction<'a>>, String>> {
+    let usage = match name {
+        "nop" | "ret" => "",
+        "mv" => "rd, rs",
+        "li" => "rd, imm",
+        "la" => "rd, label",
+        "j" | "call" => "label",
+        _ => return None,
+    };
+    let instructions = match (name, operands) {
+        ("nop", []) => vec![base("addi", vec![text("zero"), text("zero"), text(0)])],
+        ("mv", [rd, rs]) => vec![base("addi", vec![text(rd), text(rs), text(0)])],
+        ("li", [rd, imm]) => {
+            let value = match xlen {
+                Xlen::Rv32 => immediate(imm, i32::MIN as i64, u32::MAX as i64)
+                    .map(|value| value as i32 as i64),
+                Xlen::Rv64 => evaluate(imm),
+            };
+            let mut instructions = Vec::new();
+            match value {
+                Ok(value) => load_immediate(rd, value, xlen, &mut instructions),
+                Err(message) => return Some(Err(message)),
+            }
+            instructions
+        }
+        ("la", [rd, label]) => vec![
+            base("auipc", vec![text(rd), Operand::PcRelHi(label)]),
+            base("addi", vec![text(rd), text(rd), Operand::PcRelLo(label)]),
+        ],
+        ("j", [target]) => vec![base("jal", vec![text("zero"), Operand::Target(target)])],
+        ("call", [target]) => vec![
+            base("auipc", vec![text("ra"), Operand::PcRelHi(target)]),
+            base(
+                "jalr",
+                vec![text("ra"), text("ra"), Operand::PcRelLo(target)],
+            ),
+        ],
+        ("ret", []) => vec![base("jalr", vec![text("zero"), text("0(ra)")])],
+        _ => {
+            return Some(Err(match usage {
+                "" => format!("`{}` takes no operands", name),
+                usage => format!("expected `{} {}`", name, usage),
+            }))
+        }
+    };
+    Some(Ok(instructions))
+}
+
+/// Return the instructions the code of a line assembles to.
+fn expand(code: &str, isa: Isa) -> Result<Vec<Instruction<'_>>, String> {
+    let (name, operands) = words(code)?;
+    if let Some(instructions) = expand_pseudo(&name, &operands, isa.xlen) {
+        return instructions;
+    }
+
+    let opcode = opcode(&name, isa)?;
+    // The offset of a branch is its third operand, the one of a jump its last.
+    let target = match (opcode.format, operands.len()) {
+        (Format::Branch, 3) => Some(2),
+        (Format::Jump, len) => len.checked_sub(1),
+        _ => None,
+    };
+    let operands = operands
+        .iter()
+        .enumerate()
+        .map(|(i, operand)| match target {
+            Some(target) if target == i => Operand::Target(operand),
+            _ => parse_operand(operand),
+        })
+        .collect();
+    Ok(vec![Instruction {
+        opcode,
+        operands,
+        size: 4,
+    }])
+}
+
+/// Return true if `name` can be a label: a letter, `_` or `.` followed by letters, digits, `_`
+/// or `.`.
+fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Return the text of `operand` of an instruction at `pc` of a statement at `addr`. `label_addr`
+/// returns the address of a label.
+fn resolve<F>(operand: &Operand, label_addr: &mut F, addr: u64, pc: u64) -> Result<String, String>
+where
+    F: FnMut(&str) -> Result<i64, String>,
+{
+    Ok(match operand {
+        Operand::Text(text) => text.clone(),
+        Operand::Target(target) if is_label(target) => {
+            (label_addr(target)? - pc as i64).to_string()
+        }
+        Operand::Target(offset) => offset.to_string(),
+        Operand::PcRelHi(label) => {
+            let distance = label_addr(label)? - addr as i64;
+            (distance.wrapping_add(0x800) >> 12).to_string()
+        }
+        Operand::PcRelLo(label) => {
+            let distance = label_addr(label)? - addr as i64;
+            (distance << 52 >> 52).to_string()
+        }
+        Operand::Hi(label) => (absolute(label, label_addr)?.wrapping_add(0x800) >> 12).to_string(),
+        Operand::Lo(label, base) => {
+            format!("{}{}", absolute(label, label_addr)? << 52 >> 52, base)
+        }
+    })
+}
+
+/// Return the address of `label` for `%hi` or `%lo`, which must fit in 32 bits.
+fn absolute<F>(label: &str, label_addr: &mut F) -> Result<i64, String>
+where
+    F: FnMut(&str) -> Result<i64, String>,
+{
+    let addr = label_addr(label)?;
+    if u32::try_from(addr).is_err() {
+        return Err(format!("the address of `{}` doesn't fit in 32 bits", label));
+    }
+    Ok(addr)
+}
+
+/// Encode `instruction` at `pc` of a statement at `addr`. `label_addr` returns the address of a
+/// label.
+fn encode_instruction<F>(
+    instruction: &Instruction,
+    label_addr: &mut F,
+    addr: u64,
+    pc: u64,
+    xlen: Xlen,
+) -> Result<u32, String>
+where
+    F: FnMut(&str) -> Result<i64, String>,
+{
+    let operands = instruction
+        .operands
+        .iter()
+        .map(|operand| resolve(operand, label_addr, addr, pc))
+        .collect::<Result<Vec<String>, String>>()?;
+    let operands = operands.iter().map(String::as_str).collect::<Vec<&str>>();
+    encode_operands(instruction.opcode, &operands, xlen)
+}
+
+/// Assemble the single source line `line` as if it were at `addr`, e.g. to patch the instruction
+/// there in a running program. A pseudo-instruction can assemble to several instructions.
+/// `address_of` returns the address of a label the line refers to, e.g. from the symbol table of
+/// the running program.
+pub fn assemble_line<F>(line: &str, xlen: Xlen, addr: u64, address_of: F) -> Result<Vec<u8>, String>
+where
+    F: Fn(&str) -> Option<u64>,
+{
+    let code = code(line);
+    if label_colon(code).is_some() {
+        return Err("a patched line can't define a label".to_string());
+    }
+    let mut label_addr = |name: &str| match address_of(name) {
+        Some(addr) => Ok(addr as i64),
+        None => Err(format!("unknown label `{}`", name)),
+    };
+    let mut image = Vec::new();
+    for instruction in expand(code, Isa::base(xlen))? {
+        let pc = addr.wrapping_add(image.len() as u64);
+        let inst = encode_instruction(&instruction, &mut label_addr, addr, pc, xlen)?;
+        image.extend_from_slice(&inst.to_le_bytes());
+    }
+    Ok(image)
+}
+
+/// Assemble `source`, one instruction or directive per line, into a little-endian program
+/// image. Blank lines and comments are skipped. The data section follows the text, and labels
+/// used as values, e.g. `.word label`, hold the address of the label in an image loaded at
+/// `DRAM_BASE`, where `Emulator::initialize_dram` loads it.
+pub fn assemble(source: &str, xlen: Xlen) -> Result<Vec<u8>, AssembleError> {
+    assemble_with(source, xlen, |_, _| true)
+}
+
+/// Same as `assemble`, but calls `progress` before each statement with the number of
+/// statements done and the number of statements, and gives up if it returns false. A cancelled
+/// assembly fails on line 0.
+pub fn assemble_with<F>(source: &str, xlen: Xlen, progress: F) -> Result<Vec<u8>, AssembleError>
+where
+    F: FnMut(u64, u64) -> bool,
+{
+    assemble_isa_with(source, Isa::base(xlen), progress)
+}
+
+/// Same as `assemble`, but for `isa`, e.g. to also assemble the instructions of the M extension.
+pub fn assemble_isa(source: &str, isa: Isa) -> Result<Vec<u8>, AssembleError> {
+    assemble_isa_with(source, isa, |_, _| true)
+}
+
+/// Same as `assemble_with`, but for `isa`.
+pub fn assemble_isa_with<F>(source: &str, isa: Isa, progress: F) -> Result<Vec<u8>, AssembleError>
+where
+    F: FnMut(u64, u64) -> bool,
+{
+    assemble_program(source, isa, progress)?.image(DRAM_BASE)
+}
+
+/// Same as `assemble`, but also returns the labels of the program, ordered by address.
+pub fn assemble_with_symbols(
+    source: &str,
+    xlen: Xlen,
+) -> Result<(Vec<u8>, Vec<Symbol>), AssembleError> {
+    let object = assemble_program(source, Isa::base(xlen), |_, _| true)?;
+    Ok((object.image(DRAM_BASE)?, object.symbols))
+}
+
+/// Assemble `source` for `isa` into an object, which keeps the text and the data apart so they
+/// can be linked at any address.
+pub fn assemble_object(source: &str, isa: Isa) -> Result<Object, AssembleError> {
+    assemble_program(source, isa, |_, _| true)
+}
+
+/// Assemble `source` into an object, as `assemble_with` does, and count it in the metrics.
+fn assemble_program<F>(source: &str, isa: Isa, progress: F) -> Result<Object, AssembleError>
+where
+    F: FnMut(u64, u64) -> bool,
+{
+    let object = assemble_source(source, isa, progress);
+    metrics::record_assembly(object.is_ok());
+    object
+}
+
+fn assemble_source<F>(source: &str, isa: Isa, mut progress: F) -> Result<Object, AssembleError>
+where
+    F: FnMut(u64, u64) -> bool,
+{
+    // The first pass: find the labels, expand the pseudo-instructions and parse the directives.
+    let mut labels = Labels::default();
+    let mut sections = [Vec::new(), Vec::new()];
+    let mut section = Section::Text;
+    for (i, text) in source.lines().enumerate() {
+        let line = (i + 1) as u64;
+        let error = |message| AssembleError { line, message };
+        let mut code = code(text);
+        while let Some(colon) = label_colon(code) {
+            let label = code[..colon].trim();
+            if !is_label(label) {
+                return Err(error(format!("invalid label `{}`", label)));
+            }
+            let statement = sections[section as usize].len();
+            if !labels.define(label, section, statement, line) {
+                return Err(error(format!("label `{}` is defined twice", label)));
+            }
+            code = code[colon + 1..].trim();
+        }
+        if code.is_empty() {
+            continue;
+        }
+        let contents = if code.starts_with('.') {
+            match directive(code).map_err(error)? {
+                Directive::Section(next) => {
+                    section = next;
+                    continue;
+                }
+                Directive::Contents(contents) => contents,
+            }
+        } else {
+            Contents::Instructions(expand(code, isa).map_err(error)?)
+        };
+        sections[section as usize].push(Statement {
+            line,
+            addr: 0,
+            contents,
+        });
+    }
+    let data_offset = if isa.c {
+        compress_layout(&mut sections, &mut labels, isa.xlen)
+    } else {
+        layout(&mut sections, &mut labels)
+    };
+
+    // The second pass: encode the instructions and the values.
+    let total = sections.iter().map(Vec::len).sum::<usize>() as u64;
+    let mut done = 0;
+    let mut images = [Vec::new(), Vec::new()];
+    let mut relocations = Vec::new();
+    for (statements, section) in sections.iter().zip([Section::Text, Section::Data]) {
+        let image = &mut images[section as usize];
+        let base = match section {
+            Section::Text => 0,
+            Section::Data => data_offset,
+        };
+        for statement in statements {
+            if !progress(done, total) {
+                return Err(AssembleError {
+                    line: 0,
+                    message: "the assembly was cancelled".to_string(),
+                });
+            }
+            done += 1;
+            let error = |message| AssembleError {
+                line: statement.line,
+                message,
+            };
+            let mut relocate = |offset, kind, symbol: &str| {
+                relocations.push(Relocation {
+                    section,
+                    offset,
+                    kind,
+                    symbol: symbol.to_string(),
+                    line: statement.line,
+                })
+            };
+            match &statement.contents {
+                Contents::Instructions(instructions) => {
+                    for instruction in instructions {
+                        let pc = base + image.len() as u64;
+                        for operand in &instruction.operands {
+                            let (label, kind) = match *operand {
+                                Operand::Target(label) => (label, None),
+                                Operand::PcRelHi(label) => (label, Some(RelocationKind::PcRelHi)),
+                                Operand::PcRelLo(label) => {
+                                    let anchor = statement.addr - base;
+                                    (label, Some(RelocationKind::PcRelLo { anchor }))
+                                }
+                                // The address of a label moves with its section wherever it is.
+                                Operand::Hi(label) => {
+                                    relocate(pc - base, RelocationKind::AbsoluteHi, label);
+                                    continue;
+                                }
+                                Operand::Lo(label, _) => {
+                                    let kind = match instruction.opcode.format {
+                                        Format::Store => RelocationKind::AbsoluteLoStore,
+                                        _ => RelocationKind::AbsoluteLo,
+                                    };
+                                    relocate(pc - base, kind, label);
+                                    continue;
+                                }
+                                Operand::Text(_) => continue,
+                            };
+                            match (labels.section(label), kind) {
+                                (Some(other), Some(kind)) if other != section => {
+                                    relocate(pc - base, kind, label)
+                                }
+                                (Some(other), None) if other != section => {
+                                    return Err(error(format!(
+                                        "`{}` is in another section, out of reach of a jump",
+                                        label
+                                    )))
+                                }
+                                _ => {}
+                            }
+                        }
+                        let mut label_addr = |name: &str| labels.addr(name);
+                        let inst = encode_instruction(
+                            instruction,
+                            &mut label_addr,
+                            statement.addr,
+                            pc,
+                            isa.xlen,
+                        )
+                        .map_err(error)?;
+                        match instruction.size {
+                            2 => {
+                                let inst =
+                                    compress(inst, isa.xlen).expect("a compressible instruction");
+                                image.extend_from_slice(&inst.to_le_bytes());
+                            }
+                            _ => image.extend_from_slice(&inst.to_le_bytes()),
+                        }
+                    }
+                }
+                Contents::Values { size, values } => {
+                    for value in values {
+                        let value = match *value {
+                            Value::Number(number) => number,
+                            Value::Label(label) => {
+                                let kind = match size {
+                                    4 => RelocationKind::Absolute32,
+                                    _ => RelocationKind::Absolute64,
+                                };
+                                relocate(image.len() as u64, kind, label);
+                                labels.addr(label).map_err(error)?
+                            }
+                        };
+                        image.extend_from_slice(&value.to_le_bytes()[..*size as usize]);
+                    }
+                }
+                Contents::Bytes(bytes) => image.extend_from_slice(bytes),
+                Contents::Align(_) => {
+                    let end = statement.addr + statement.size() - base;
+                    image.resize(end as usize, 0);
+                }
+            }
+        }
+    }
+
+    let [text, data] = images;
+    let mut symbols = labels.symbols;
+    symbols.sort_by_key(|symbol| symbol.addr);
+    Ok(Object {
+        text,
+        data,
+        data_offset,
+        symbols,
+        relocations,
+    })
+}
+
+/// Move the statements and the labels to their offsets in the image: the text from 0, then the
+/// data at the next multiple of its alignment. Returns the offset of the data.
+fn layout(sections: &mut [Vec<Statement>; 2], labels: &mut Labels) -> u64 {
+    let alignment = sections[Section::Data as usize]
+        .iter()
+        .filter_map(|statement| match statement.contents {
+            Contents::Align(align) => Some(align),
+            _ => None,
+        })
+        .fold(DATA_ALIGNMENT, u64::max);
+    let mut starts = [0; 2];
+    let mut ends = [0; 2];
+    let mut addr = 0;
+    for (i, statements) in sections.iter_mut().enumerate() {
+        if i == Section::Data as usize {
+            addr = align_up(addr, alignment);
+        }
+        starts[i] = addr;
+        for statement in statements.iter_mut() {
+            statement.addr = addr;
+            addr += statement.size();
+        }
+        ends[i] = addr;
+    }
+    for (symbol, &statement) in labels.symbols.iter_mut().zip(&labels.statements) {
+        let section = symbol.section as usize;
+        symbol.addr = sections[section]
+            .get(statement)
+            .map_or(ends[section], |s| s.addr);
+    }
+    starts[Section::Data as usize]
+}
+
+/// Settle the layout of a program for the C extension, as `layout` does. Every instruction
+/// starts compressed, and the ones that can't be, e.g. a branch too far from its label, grow to
+/// 4 bytes until none does. Instructions only grow, so this ends. The instructions reaching a
+/// label in the other section, or `%hi` or `%lo` of any label, stay 4 bytes, so relocating them
+/// can't make them grow.
+fn compress_layout(sections: &mut [Vec<Statement>; 2], labels: &mut Labels, xlen: Xlen) -> u64 {
+    for (i, statements) in sections.iter_mut().enumerate() {
+        for statement in statements.iter_mut() {
+            if let Contents::Instructions(instructions) = &mut statement.contents {
+                for instruction in instructions {
+                    let relocated = instruction.operands.iter().any(|operand| match operand {
+                        Operand::PcRelHi(label) | Operand::PcRelLo(label) => labels
+                            .section(label)
+                            .is_some_and(|section| section as usize != i),
+                        Operand::Hi(_) | Operand::Lo(..) => true,
+                        _ => false,
+                    });
+                    instruction.size = if relocated { 4 } else { 2 };
+                }
+            }
+        }
+    }
+    loop {
+        let data_offset = layout(sections, labels);
+
+        let mut grown = false;
+        for statement in sections.iter_mut().flat_map(|s| s.iter_mut()) {
+            let mut pc = statement.addr;
+            if let Contents::Instructions(instructions) = &mut statement.contents {
+                for instruction in instructions {
+                    if instruction.size == 2 {
+                        let mut label_addr = |name: &str| labels.addr(name);
+                        let addr = statement.addr;
+                        let inst = encode_instruction(instruction, &mut label_addr, addr, pc, xlen);
+                        // An instruction that doesn't encode stays 4 bytes and fails in the
+                        // second pass.
+                        if inst.ok().and_then(|inst| compress(inst, xlen)).is_none() {
+                            instruction.size = 4;
+                            grown = true;
+                        }
+                    }
+                    pc += instruction.size;
+                }
+            }
+        }
+        if !grown {
+            return data_offset;
+        }
+    }
+}
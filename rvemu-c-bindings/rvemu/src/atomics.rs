@@ -0,0 +1,141 @@
+//! The atomics module logs the atomic instructions of the A extension as events, so the game can
+//! animate reservations, failed store-conditionals and read-modify-writes in the
+//! synchronization lessons.
+
+/// The maximum number of events kept. Later events are counted but not recorded.
+pub const MAX_ATOMIC_EVENTS: usize = 1 << 16;
+
+/// The atomic instruction of an event.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum AtomicOp {
+    /// `lr.w` or `lr.d`: the address is reserved.
+    LoadReserved = 0,
+    /// `sc.w` or `sc.d`: the store happens only if the reservation is still held.
+    StoreConditional = 1,
+    /// `amoswap`.
+    Swap = 2,
+    /// `amoadd`.
+    Add = 3,
+    /// `amoxor`.
+    Xor = 4,
+    /// `amoand`.
+    And = 5,
+    /// `amoor`.
+    Or = 6,
+    /// `amomin`.
+    Min = 7,
+    /// `amomax`.
+    Max = 8,
+    /// `amominu`.
+    Minu = 9,
+    /// `amomaxu`.
+    Maxu = 10,
+}
+
+impl AtomicOp {
+    /// Return the operation of the instruction `inst`, or `None` if it isn't atomic.
+    pub fn decode(inst: u64) -> Option<Self> {
+        if inst & 0x7f != 0x2f {
+            return None;
+        }
+        let op = match (inst >> 27) & 0x1f {
+            0x00 => AtomicOp::Add,
+            0x01 => AtomicOp::Swap,
+            0x02 => AtomicOp::LoadReserved,
+            0x03 => AtomicOp::StoreConditional,
+            0x04 => AtomicOp::Xor,
+            0x08 => AtomicOp::Or,
+            0x0c => AtomicOp::And,
+            0x10 => AtomicOp::Min,
+            0x14 => AtomicOp::Max,
+            0x18 => AtomicOp::Minu,
+            0x1c => AtomicOp::Maxu,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+/// An executed atomic instruction. The layout is C-compatible so events can be copied over the
+/// FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct AtomicEvent {
+    /// The ID of the hart that executed the instruction.
+    pub hart: u64,
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The accessed address.
+    pub addr: u64,
+    /// The value in memory before the instruction.
+    pub old_value: u64,
+    /// The value in memory after the instruction. Same as `old_value` for `lr` and failed `sc`.
+    pub new_value: u64,
+    /// The size of the access in bytes: 4 or 8.
+    pub size: u32,
+    /// The instruction.
+    pub op: AtomicOp,
+    /// 1 if the instruction took effect, 0 for a failed `sc`.
+    pub success: u32,
+}
+
+/// The log of atomic instructions. Nothing is recorded until it's enabled.
+#[derive(Debug, Default, Clone)]
+pub struct AtomicEvents {
+    enabled: bool,
+    events: Vec<AtomicEvent>,
+    dropped: u64,
+}
+
+impl AtomicEvents {
+    /// Create a disabled log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop recording events.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if events are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record an event.
+    pub fn record(&mut self, event: AtomicEvent) {
+        if self.events.len() == MAX_ATOMIC_EVENTS {
+            self.dropped += 1;
+            return;
+        }
+        self.events.push(event);
+    }
+
+    /// Return the recorded events from the oldest to the newest.
+    pub fn events(&self) -> &[AtomicEvent] {
+        &self.events
+    }
+
+    /// Return the number of events that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove and return the recorded events, e.g. once per frame.
+    pub fn take(&mut self) -> Vec<AtomicEvent> {
+        self.take_oldest(self.events.len())
+    }
+
+    /// Remove and return up to `count` of the oldest recorded events. The dropped count is
+    /// reset once every event was taken.
+    pub fn take_oldest(&mut self, count: usize) -> Vec<AtomicEvent> {
+        let count = count.min(self.events.len());
+        let taken = self.events.drain(..count).collect();
+        if self.events.is_empty() {
+            self.dropped = 0;
+        }
+        taken
+    }
+}
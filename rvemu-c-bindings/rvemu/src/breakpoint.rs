@@ -0,0 +1,48 @@
+//! The breakpoint module contains the breakpoints the host sets at instruction addresses. Unlike
+//! an `ebreak`, they don't change the program: a run stops before executing the instruction at a
+//! breakpoint.
+
+use std::collections::BTreeSet;
+
+/// The set of breakpoints of an emulator.
+#[derive(Debug, Default, Clone)]
+pub struct Breakpoints {
+    addrs: BTreeSet<u64>,
+}
+
+impl Breakpoints {
+    /// Create an empty set of breakpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if no breakpoint is set.
+    pub fn is_empty(&self) -> bool {
+        self.addrs.is_empty()
+    }
+
+    /// Set a breakpoint at `addr`. Returns false if there's already one.
+    pub fn add(&mut self, addr: u64) -> bool {
+        self.addrs.insert(addr)
+    }
+
+    /// Remove the breakpoint at `addr`. Returns false if there's none.
+    pub fn remove(&mut self, addr: u64) -> bool {
+        self.addrs.remove(&addr)
+    }
+
+    /// Remove all breakpoints.
+    pub fn clear(&mut self) {
+        self.addrs.clear();
+    }
+
+    /// Return true if there's a breakpoint at `addr`.
+    pub fn contains(&self, addr: u64) -> bool {
+        self.addrs.contains(&addr)
+    }
+
+    /// Return the addresses of the breakpoints in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &u64> {
+        self.addrs.iter()
+    }
+}
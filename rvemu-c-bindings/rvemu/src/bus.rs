@@ -1,9 +1,23 @@
 //! The bus module contains the system bus which can access the memroy or memory-mapped peripheral
 //! devices.
 
-use crate::devices::{clint::Clint, plic::Plic, uart::Uart, virtio_blk::Virtio};
+use crate::devices::{
+    audio::Audio,
+    channel::Channel,
+    clint::Clint,
+    draw_queue::DrawQueue,
+    keyboard::Keyboard,
+    plic::Plic,
+    save_file::{SaveFile, SAVE_FILE_SIZE},
+    uart::Uart,
+    vga_text::{VgaText, VGA_TEXT_SIZE},
+    virtio_blk::Virtio,
+    vsync::Vsync,
+    watchdog::Watchdog,
+};
 use crate::dram::{Dram, DRAM_SIZE};
 use crate::exception::Exception;
+use crate::mapping::Mappings;
 use crate::rom::Rom;
 
 // QEMU virt machine:
@@ -14,6 +28,11 @@ pub const MROM_BASE: u64 = 0x1000;
 /// The address which the mask ROM ends.
 const MROM_END: u64 = MROM_BASE + 0xf000;
 
+/// The address which the VGA-like text-mode buffer starts, the same address as the classic PC.
+pub const VGA_TEXT_BASE: u64 = 0xb_8000;
+/// The address which the text-mode buffer ends.
+const VGA_TEXT_END: u64 = VGA_TEXT_BASE + VGA_TEXT_SIZE - 1;
+
 /// The address which the core-local interruptor (CLINT) starts. It contains the timer and generates
 /// per-hart software interrupts and timer interrupts.
 pub const CLINT_BASE: u64 = 0x200_0000;
@@ -39,10 +58,45 @@ pub const VIRTIO_BASE: u64 = 0x1000_1000;
 /// The address which virtio ends.
 const VIRTIO_END: u64 = VIRTIO_BASE + 0x1000;
 
+/// The address which the PS/2-style keyboard controller starts.
+pub const KEYBOARD_BASE: u64 = 0x1000_3000;
+/// The address which the keyboard controller ends.
+const KEYBOARD_END: u64 = KEYBOARD_BASE + 0x8;
+
+/// The address which the watchdog timer starts.
+pub const WATCHDOG_BASE: u64 = 0x1000_4000;
+/// The address which the watchdog timer ends.
+const WATCHDOG_END: u64 = WATCHDOG_BASE + 0x14;
+
+/// The address which the draw command queue starts.
+pub const DRAW_QUEUE_BASE: u64 = 0x1000_5000;
+/// The address which the draw command queue ends.
+const DRAW_QUEUE_END: u64 = DRAW_QUEUE_BASE + 0x28;
+
+/// The address which the frame synchronization device starts.
+pub const VSYNC_BASE: u64 = 0x1000_6000;
+/// The address which the frame synchronization device ends.
+const VSYNC_END: u64 = VSYNC_BASE + 0x8;
+
+/// The address which the audio sample ring buffer starts.
+pub const AUDIO_BASE: u64 = 0x1000_7000;
+/// The address which the audio sample ring buffer ends.
+const AUDIO_END: u64 = AUDIO_BASE + 0x10;
+
+/// The address which the save memory starts.
+pub const SAVE_FILE_BASE: u64 = 0x1000_8000;
+/// The address which the save memory ends.
+const SAVE_FILE_END: u64 = SAVE_FILE_BASE + SAVE_FILE_SIZE - 1;
+
+/// The address which the message channel starts.
+pub const CHANNEL_BASE: u64 = 0x1000_9000;
+/// The address which the message channel ends.
+const CHANNEL_END: u64 = CHANNEL_BASE + 0x10;
+
 /// The address which DRAM starts.
 pub const DRAM_BASE: u64 = 0x8000_0000;
 /// The address which DRAM ends.
-const DRAM_END: u64 = DRAM_BASE + DRAM_SIZE;
+pub const DRAM_END: u64 = DRAM_BASE + DRAM_SIZE;
 
 /// The system bus.
 pub struct Bus {
@@ -50,20 +104,105 @@ pub struct Bus {
     pub plic: Plic,
     pub uart: Uart,
     pub virtio: Virtio,
+    pub keyboard: Keyboard,
+    pub vga_text: VgaText,
+    pub watchdog: Watchdog,
+    pub draw_queue: DrawQueue,
+    pub vsync: Vsync,
+    pub audio: Audio,
+    pub save_file: SaveFile,
+    pub channel: Channel,
     dram: Dram,
     pub rom: Rom,
+    /// True if instructions can be fetched from the ROM and device regions. Otherwise, only DRAM
+    /// is executable and fetching from elsewhere raises an instruction access fault.
+    pub device_fetch: bool,
+    /// The regions backed by host memory. They take precedence over the devices and DRAM.
+    pub mappings: Mappings,
+    /// True while the emulator runs speculatively. Only DRAM can be accessed, and its writes are
+    /// undone when the speculation ends.
+    speculative: bool,
+}
+
+/// Return the name of the memory region or the device `addr` belongs to.
+pub fn region_name(addr: u64) -> Option<&'static str> {
+    match addr {
+        MROM_BASE..=MROM_END => Some("ROM"),
+        VGA_TEXT_BASE..=VGA_TEXT_END => Some("text-mode display"),
+        CLINT_BASE..=CLINT_END => Some("CLINT"),
+        PLIC_BASE..=PLIC_END => Some("PLIC"),
+        UART_BASE..=UART_END => Some("UART"),
+        VIRTIO_BASE..=VIRTIO_END => Some("virtio disk"),
+        KEYBOARD_BASE..=KEYBOARD_END => Some("keyboard controller"),
+        WATCHDOG_BASE..=WATCHDOG_END => Some("watchdog"),
+        DRAW_QUEUE_BASE..=DRAW_QUEUE_END => Some("draw queue"),
+        VSYNC_BASE..=VSYNC_END => Some("vsync"),
+        AUDIO_BASE..=AUDIO_END => Some("audio"),
+        SAVE_FILE_BASE..=SAVE_FILE_END => Some("save memory"),
+        CHANNEL_BASE..=CHANNEL_END => Some("message channel"),
+        DRAM_BASE..=DRAM_END => Some("DRAM"),
+        _ => None,
+    }
 }
 
 impl Bus {
     /// Create a new bus object.
     pub fn new() -> Bus {
+        Self::with_dram(Dram::new())
+    }
+
+    /// Create a new bus object with `dram` as its memory.
+    pub fn with_dram(dram: Dram) -> Bus {
         Self {
             clint: Clint::new(),
             plic: Plic::new(),
             uart: Uart::new(),
             virtio: Virtio::new(),
-            dram: Dram::new(),
+            keyboard: Keyboard::new(),
+            vga_text: VgaText::new(),
+            watchdog: Watchdog::new(),
+            draw_queue: DrawQueue::new(),
+            vsync: Vsync::new(),
+            audio: Audio::new(),
+            save_file: SaveFile::new(),
+            channel: Channel::new(),
+            dram,
             rom: Rom::new(),
+            device_fetch: false,
+            mappings: Mappings::new(),
+            speculative: false,
+        }
+    }
+
+    /// Start running speculatively: accesses outside DRAM fault, so devices and host memory
+    /// don't see them, and the writes to DRAM are journaled.
+    pub(crate) fn start_speculation(&mut self) {
+        self.speculative = true;
+        self.dram.start_journal();
+    }
+
+    /// Return the bytes of the DRAM pages saved to undo the writes of the speculative run.
+    pub(crate) fn journal_size(&self) -> u64 {
+        self.dram.journal_size()
+    }
+
+    /// Return true if `addr` can be accessed while running speculatively, i.e. it's in DRAM and
+    /// not backed by host memory.
+    fn is_speculative_access(&self, addr: u64) -> bool {
+        (DRAM_BASE..DRAM_END).contains(&addr) && !self.mappings.is_mapped(addr)
+    }
+
+    /// Stop running speculatively and undo the writes to DRAM since `start_speculation`.
+    pub(crate) fn end_speculation(&mut self) {
+        self.speculative = false;
+        self.dram.roll_back();
+    }
+
+    /// Return true if an instruction can be fetched from `addr`.
+    pub fn is_executable(&self, addr: u64) -> bool {
+        match addr {
+            DRAM_BASE..=DRAM_END => true,
+            _ => self.device_fetch,
         }
     }
 
@@ -72,6 +211,32 @@ impl Bus {
         self.dram.initialize(data);
     }
 
+    /// Copy the binary data to the memory at `addr`, keeping the rest of the memory. It must fit
+    /// in DRAM.
+    pub fn write_dram(&mut self, addr: u64, data: &[u8]) {
+        self.dram.write_bytes(addr, data);
+    }
+
+    /// Return the `len` bytes of the memory at `addr`. They must be in DRAM.
+    pub fn read_dram(&self, addr: u64, len: u64) -> &[u8] {
+        self.dram.read_bytes(addr, len)
+    }
+
+    /// Return the whole memory, e.g. to save a snapshot.
+    pub(crate) fn dram(&self) -> &[u8] {
+        &self.dram.dram
+    }
+
+    /// Return the length of the memory up to the highest byte written, after which it's zero.
+    pub(crate) fn written_dram_len(&self) -> usize {
+        self.dram.written_len()
+    }
+
+    /// Return the first `len` bytes of the memory for writing, e.g. to restore a snapshot.
+    pub(crate) fn dram_mut(&mut self, len: usize) -> &mut [u8] {
+        self.dram.written_mut(len)
+    }
+
     /// Set the binary data to the virtIO disk.
     pub fn initialize_disk(&mut self, data: Vec<u8>) {
         self.virtio.initialize(data);
@@ -79,26 +244,54 @@ impl Bus {
 
     /// Load a `size`-bit data from the device that connects to the system bus.
     pub fn read(&mut self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if self.speculative && !self.is_speculative_access(addr) {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+        if let Some(result) = self.mappings.read(addr, size) {
+            return result;
+        }
         match addr {
             MROM_BASE..=MROM_END => self.rom.read(addr, size),
+            VGA_TEXT_BASE..=VGA_TEXT_END => self.vga_text.read(addr, size),
             CLINT_BASE..=CLINT_END => self.clint.read(addr, size),
             PLIC_BASE..=PLIC_END => self.plic.read(addr, size),
             UART_BASE..=UART_END => self.uart.read(addr, size),
             VIRTIO_BASE..=VIRTIO_END => self.virtio.read(addr, size),
+            KEYBOARD_BASE..=KEYBOARD_END => self.keyboard.read(addr, size),
+            WATCHDOG_BASE..=WATCHDOG_END => self.watchdog.read(addr, size),
+            DRAW_QUEUE_BASE..=DRAW_QUEUE_END => self.draw_queue.read(addr, size),
+            VSYNC_BASE..=VSYNC_END => self.vsync.read(addr, size),
+            AUDIO_BASE..=AUDIO_END => self.audio.read(addr, size),
+            SAVE_FILE_BASE..=SAVE_FILE_END => self.save_file.read(addr, size),
+            CHANNEL_BASE..=CHANNEL_END => self.channel.read(addr, size),
             DRAM_BASE..=DRAM_END => self.dram.read(addr, size),
-            _ => Err(Exception::LoadAccessFault),
+            _ => Err(Exception::LoadAccessFault(addr)),
         }
     }
 
     /// Store a `size`-bit data to the device that connects to the system bus.
     pub fn write(&mut self, addr: u64, value: u64, size: u8) -> Result<(), Exception> {
+        if self.speculative && !self.is_speculative_access(addr) {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+        if let Some(result) = self.mappings.write(addr, value, size) {
+            return result;
+        }
         match addr {
+            VGA_TEXT_BASE..=VGA_TEXT_END => self.vga_text.write(addr, value, size),
             CLINT_BASE..=CLINT_END => self.clint.write(addr, value, size),
             PLIC_BASE..=PLIC_END => self.plic.write(addr, value, size),
             UART_BASE..=UART_END => self.uart.write(addr, value as u8, size),
             VIRTIO_BASE..=VIRTIO_END => self.virtio.write(addr, value as u32, size),
+            KEYBOARD_BASE..=KEYBOARD_END => self.keyboard.write(addr, value as u8, size),
+            WATCHDOG_BASE..=WATCHDOG_END => self.watchdog.write(addr, value as u32, size),
+            DRAW_QUEUE_BASE..=DRAW_QUEUE_END => self.draw_queue.write(addr, value as u32, size),
+            VSYNC_BASE..=VSYNC_END => self.vsync.write(addr, size),
+            AUDIO_BASE..=AUDIO_END => self.audio.write(addr, value as u32, size),
+            SAVE_FILE_BASE..=SAVE_FILE_END => self.save_file.write(addr, value, size),
+            CHANNEL_BASE..=CHANNEL_END => self.channel.write(addr, value as u32, size),
             DRAM_BASE..=DRAM_END => self.dram.write(addr, value, size),
-            _ => Err(Exception::StoreAMOAccessFault),
+            _ => Err(Exception::StoreAMOAccessFault(addr)),
         }
     }
 }
@@ -0,0 +1,283 @@
+//! The compress module finds the compressed (RVC) encoding of a 32-bit instruction, so the
+//! assembler can emit the 2-byte form of every instruction the C extension has one for. Only the
+//! integer instructions are compressed; the assembler doesn't encode floating-point ones.
+
+use crate::xlen::Xlen;
+
+fn rd(inst: u32) -> u32 {
+    (inst >> 7) & 0x1f
+}
+
+fn rs1(inst: u32) -> u32 {
+    (inst >> 15) & 0x1f
+}
+
+fn rs2(inst: u32) -> u32 {
+    (inst >> 20) & 0x1f
+}
+
+fn funct3(inst: u32) -> u32 {
+    (inst >> 12) & 0x7
+}
+
+fn imm_i(inst: u32) -> i64 {
+    (inst as i32 >> 20) as i64
+}
+
+fn imm_s(inst: u32) -> i64 {
+    ((inst as i32 >> 25) << 5 | ((inst >> 7) & 0x1f) as i32) as i64
+}
+
+fn imm_b(inst: u32) -> i64 {
+    ((inst as i32 >> 31) << 12
+        | (((inst >> 7) & 1) << 11) as i32
+        | (((inst >> 25) & 0x3f) << 5) as i32
+        | (((inst >> 8) & 0xf) << 1) as i32) as i64
+}
+
+fn imm_j(inst: u32) -> i64 {
+    ((inst as i32 >> 31) << 20
+        | (inst & 0xff000) as i32
+        | (((inst >> 20) & 1) << 11) as i32
+        | (((inst >> 21) & 0x3ff) << 1) as i32) as i64
+}
+
+/// Return the bits `hi` to `lo` of `value`, moved down to bit 0.
+fn bits(value: i64, hi: u32, lo: u32) -> u16 {
+    ((value >> lo) & ((1 << (hi - lo + 1)) - 1)) as u16
+}
+
+/// Return true if `value` fits in a signed immediate of `width` bits.
+fn fits(value: i64, width: u32) -> bool {
+    (-(1 << (width - 1))..1 << (width - 1)).contains(&value)
+}
+
+/// Return true if `value` is a multiple of `align` below `limit`.
+fn scaled(value: i64, align: i64, limit: i64) -> bool {
+    (0..limit).contains(&value) && value % align == 0
+}
+
+/// Return the 3-bit field of the register `reg` of a CIW, CL, CS, CA or CB format, or `None` if
+/// it isn't one of x8 to x15.
+fn creg(reg: u32) -> Option<u16> {
+    match reg {
+        8..=15 => Some((reg - 8) as u16),
+        _ => None,
+    }
+}
+
+/// Return an instruction of the CI format: a 6-bit immediate and a full register.
+fn ci(funct3: u16, imm: i64, rd: u32, op: u16) -> u16 {
+    funct3 << 13 | bits(imm, 5, 5) << 12 | (rd as u16) << 7 | bits(imm, 4, 0) << 2 | op
+}
+
+/// Return an instruction of the CB format with a 6-bit immediate: `c.srli`, `c.srai` or
+/// `c.andi`, told apart by `funct2`.
+fn cb_imm(funct2: u16, imm: i64, rd: u16) -> u16 {
+    0b100 << 13 | bits(imm, 5, 5) << 12 | funct2 << 10 | rd << 7 | bits(imm, 4, 0) << 2 | 0b01
+}
+
+/// Return an instruction of the CA format: `c.sub` to `c.and`, or `c.subw` and `c.addw` if
+/// `word`.
+fn ca(word: bool, rd: u16, funct2: u16, rs2: u16) -> u16 {
+    0b10_0011 << 10 | (word as u16) << 12 | rd << 7 | funct2 << 5 | rs2 << 2 | 0b01
+}
+
+/// Return a `c.j` or `c.jal` with the offset `imm`.
+fn cj(funct3: u16, imm: i64) -> u16 {
+    funct3 << 13
+        | bits(imm, 11, 11) << 12
+        | bits(imm, 4, 4) << 11
+        | bits(imm, 9, 8) << 9
+        | bits(imm, 10, 10) << 8
+        | bits(imm, 6, 6) << 7
+        | bits(imm, 7, 7) << 6
+        | bits(imm, 3, 1) << 3
+        | bits(imm, 5, 5) << 2
+        | 0b01
+}
+
+/// Return a `c.lw`, `c.sw`, `c.ld` or `c.sd` of the register `reg` at `offset` from `base`.
+fn cl(funct3: u16, offset: i64, base: u16, reg: u16) -> u16 {
+    let low = match funct3 & 0b11 {
+        // The word forms: offset[2|6].
+        0b10 => bits(offset, 2, 2) << 1 | bits(offset, 6, 6),
+        // The doubleword forms: offset[7:6].
+        _ => bits(offset, 7, 6),
+    };
+    funct3 << 13 | bits(offset, 5, 3) << 10 | base << 7 | low << 5 | reg << 2
+}
+
+/// Compress an `addi`.
+fn compress_addi(inst: u32) -> Option<u16> {
+    let (rd, rs1, imm) = (rd(inst), rs1(inst), imm_i(inst));
+    match (rd, rs1, imm) {
+        (0, 0, 0) => Some(0x0001),
+        (0, _, _) => None,
+        _ if rd == rs1 && imm != 0 && fits(imm, 6) => Some(ci(0b000, imm, rd, 0b01)),
+        (2, 2, _) if imm != 0 && imm % 16 == 0 && fits(imm, 10) => Some(
+            0b011 << 13
+                | bits(imm, 9, 9) << 12
+                | 2 << 7
+                | bits(imm, 4, 4) << 6
+                | bits(imm, 6, 6) << 5
+                | bits(imm, 8, 7) << 3
+                | bits(imm, 5, 5) << 2
+                | 0b01,
+        ),
+        (_, 2, _) if creg(rd).is_some() && imm != 0 && scaled(imm, 4, 1024) => Some(
+            bits(imm, 5, 4) << 11
+                | bits(imm, 9, 6) << 7
+                | bits(imm, 2, 2) << 6
+                | bits(imm, 3, 3) << 5
+                | creg(rd)? << 2,
+        ),
+        (_, 0, _) if fits(imm, 6) => Some(ci(0b010, imm, rd, 0b01)),
+        (_, _, 0) => Some(0b100 << 13 | (rd as u16) << 7 | (rs1 as u16) << 2 | 0b10),
+        _ => None,
+    }
+}
+
+/// Compress an instruction of the OP or OP-32 opcode.
+fn compress_op(inst: u32, word: bool) -> Option<u16> {
+    let (rd, rs1, rs2) = (rd(inst), rs1(inst), rs2(inst));
+    let funct7 = inst >> 25;
+    if !word && (funct3(inst), funct7) == (0, 0) {
+        // add
+        return match (rd, rs1, rs2) {
+            (0, _, _) | (_, _, 0) => None,
+            (_, 0, _) => Some(0b100 << 13 | (rd as u16) << 7 | (rs2 as u16) << 2 | 0b10),
+            _ if rd == rs1 => Some(0b1001 << 12 | (rd as u16) << 7 | (rs2 as u16) << 2 | 0b10),
+            _ => None,
+        };
+    }
+    if rd != rs1 {
+        return None;
+    }
+    let funct2 = match (word, funct3(inst), funct7) {
+        (false, 0, 0x20) | (true, 0, 0x20) => 0b00,
+        (false, 4, 0) | (true, 0, 0) => 0b01,
+        (false, 6, 0) => 0b10,
+        (false, 7, 0) => 0b11,
+        _ => return None,
+    };
+    Some(ca(word, creg(rd)?, funct2, creg(rs2)?))
+}
+
+/// Return the compressed encoding of the 32-bit instruction `inst` of `xlen`, or `None` if the C
+/// extension has none for it. The compressed instruction does exactly what `inst` does, though
+/// it may expand to another instruction, e.g. `addi a0, a1, 0` to `add a0, zero, a1`.
+pub fn compress(inst: u32, xlen: Xlen) -> Option<u16> {
+    let rv64 = xlen == Xlen::Rv64;
+    let (rd, rs1, rs2) = (rd(inst), rs1(inst), rs2(inst));
+    match (inst & 0x7f, funct3(inst)) {
+        (0x13, 0) => compress_addi(inst),
+        (0x13, 1) => {
+            let shamt = imm_i(inst) & 0x3f;
+            if rd != 0 && rd == rs1 && shamt != 0 {
+                Some(ci(0b000, shamt, rd, 0b10))
+            } else {
+                None
+            }
+        }
+        (0x13, 5) => {
+            let shamt = imm_i(inst) & 0x3f;
+            let funct2 = (inst >> 30) as u16;
+            if rd == rs1 && shamt != 0 {
+                Some(cb_imm(funct2, shamt, creg(rd)?))
+            } else {
+                None
+            }
+        }
+        (0x13, 7) if rd == rs1 && fits(imm_i(inst), 6) => {
+            Some(cb_imm(0b10, imm_i(inst), creg(rd)?))
+        }
+        (0x1b, 0) if rv64 && rd != 0 && rd == rs1 && fits(imm_i(inst), 6) => {
+            Some(ci(0b001, imm_i(inst), rd, 0b01))
+        }
+        (0x37, _) => {
+            // The upper immediate, sign-extended from its 20 bits.
+            let imm = (inst as i32 >> 12) as i64;
+            if rd != 0 && rd != 2 && imm != 0 && fits(imm, 6) {
+                Some(ci(0b011, imm, rd, 0b01))
+            } else {
+                None
+            }
+        }
+        (0x33, _) => compress_op(inst, false),
+        (0x3b, _) if rv64 => compress_op(inst, true),
+        (0x03, 2) => {
+            let offset = imm_i(inst);
+            if rs1 == 2 && rd != 0 && scaled(offset, 4, 256) {
+                let uimm = bits(offset, 5, 5) << 10 | bits(offset, 4, 2) << 2 | bits(offset, 7, 6);
+                return Some(0b010 << 13 | (rd as u16) << 7 | uimm << 2 | 0b10);
+            }
+            if scaled(offset, 4, 128) {
+                Some(cl(0b010, offset, creg(rs1)?, creg(rd)?))
+            } else {
+                None
+            }
+        }
+        (0x03, 3) if rv64 => {
+            let offset = imm_i(inst);
+            if rs1 == 2 && rd != 0 && scaled(offset, 8, 512) {
+                let uimm = bits(offset, 5, 5) << 10 | bits(offset, 4, 3) << 3 | bits(offset, 8, 6);
+                return Some(0b011 << 13 | (rd as u16) << 7 | uimm << 2 | 0b10);
+            }
+            if scaled(offset, 8, 256) {
+                Some(cl(0b011, offset, creg(rs1)?, creg(rd)?))
+            } else {
+                None
+            }
+        }
+        (0x23, 2) => {
+            let offset = imm_s(inst);
+            if rs1 == 2 && scaled(offset, 4, 256) {
+                let uimm = bits(offset, 5, 2) << 2 | bits(offset, 7, 6);
+                return Some(0b110 << 13 | uimm << 7 | (rs2 as u16) << 2 | 0b10);
+            }
+            if scaled(offset, 4, 128) {
+                Some(cl(0b110, offset, creg(rs1)?, creg(rs2)?))
+            } else {
+                None
+            }
+        }
+        (0x23, 3) if rv64 => {
+            let offset = imm_s(inst);
+            if rs1 == 2 && scaled(offset, 8, 512) {
+                let uimm = bits(offset, 5, 3) << 3 | bits(offset, 8, 6);
+                return Some(0b111 << 13 | uimm << 7 | (rs2 as u16) << 2 | 0b10);
+            }
+            if scaled(offset, 8, 256) {
+                Some(cl(0b111, offset, creg(rs1)?, creg(rs2)?))
+            } else {
+                None
+            }
+        }
+        (0x6f, _) if fits(imm_j(inst), 12) => match (rd, xlen) {
+            (0, _) => Some(cj(0b101, imm_j(inst))),
+            (1, Xlen::Rv32) => Some(cj(0b001, imm_j(inst))),
+            _ => None,
+        },
+        (0x67, 0) if rs1 != 0 && imm_i(inst) == 0 => match rd {
+            0 => Some(0b1000 << 12 | (rs1 as u16) << 7 | 0b10),
+            1 => Some(0b1001 << 12 | (rs1 as u16) << 7 | 0b10),
+            _ => None,
+        },
+        (0x63, 0) | (0x63, 1) if rs2 == 0 && fits(imm_b(inst), 9) => {
+            let offset = imm_b(inst);
+            Some(
+                (0b110 | funct3(inst) as u16) << 13
+                    | bits(offset, 8, 8) << 12
+                    | bits(offset, 4, 3) << 10
+                    | creg(rs1)? << 7
+                    | bits(offset, 7, 6) << 5
+                    | bits(offset, 2, 1) << 3
+                    | bits(offset, 5, 5) << 2
+                    | 0b01,
+            )
+        }
+        (0x73, 0) if inst == 0x0010_0073 => Some(0x9002),
+        _ => None,
+    }
+}
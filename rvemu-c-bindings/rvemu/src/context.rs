@@ -0,0 +1,118 @@
+//! The context module contains the state of a hart that isn't shared with other harts: the
+//! registers, the program counter, the privilege mode and the load reservation. Several
+//! programs take turns on one CPU by switching contexts, each running as if it had its own hart.
+//! Each context counts the instructions it retired and can be limited to a quota of them, so a
+//! context that exhausts its quota stops at the same instruction every time instead of starving
+//! the others.
+
+use std::mem;
+
+use crate::cpu::{Cpu, FRegisters, Mode, XRegisters};
+use crate::emulator::Emulator;
+use crate::run::{RunLimits, RunSummary, StopReason};
+
+/// The saved state of a hart that isn't running.
+#[derive(Debug)]
+pub struct HartContext {
+    /// The integer registers.
+    pub xregs: XRegisters,
+    /// The floating-point registers.
+    pub fregs: FRegisters,
+    /// The program counter.
+    pub pc: u64,
+    /// The privilege mode.
+    pub mode: Mode,
+    /// Whether the hart waits for an interrupt.
+    pub idle: bool,
+    /// The ID of the hart (mhartid).
+    pub hartid: u64,
+    /// The addresses reserved by `lr` instructions.
+    reservation_set: Vec<u64>,
+    /// The number of instructions retired while this context was running.
+    retired: u64,
+    /// The maximum number of instructions this context may retire, if limited.
+    quota: Option<u64>,
+}
+
+impl HartContext {
+    /// Create a context starting at `pc` in `mode` with the stack pointer at `sp` and the other
+    /// registers as they are at power-on.
+    pub fn new(pc: u64, sp: u64, mode: Mode) -> Self {
+        let mut xregs = XRegisters::new();
+        xregs.write(2, sp);
+        Self {
+            xregs,
+            fregs: FRegisters::new(),
+            pc,
+            mode,
+            idle: false,
+            hartid: 0,
+            reservation_set: Vec::new(),
+            retired: 0,
+            quota: None,
+        }
+    }
+
+    /// Forget the reservations of `lr` instructions on `addrs`, because another hart stored to
+    /// them.
+    pub(crate) fn drop_reservations(&mut self, addrs: &[u64]) {
+        self.reservation_set.retain(|addr| !addrs.contains(addr));
+    }
+
+    /// Return the number of instructions retired while this context was running.
+    pub fn retired(&self) -> u64 {
+        self.retired
+    }
+
+    /// Return the quota of retired instructions, if any.
+    pub fn quota(&self) -> Option<u64> {
+        self.quota
+    }
+
+    /// Limit the number of instructions this context retires in total to `quota`, or lift the
+    /// limit if it's `None`. The instructions already retired count towards the new quota.
+    pub fn set_quota(&mut self, quota: Option<u64>) {
+        self.quota = quota;
+    }
+
+    /// Return the number of instructions this context may still retire.
+    pub fn remaining(&self) -> u64 {
+        match self.quota {
+            Some(quota) => quota.saturating_sub(self.retired),
+            None => u64::MAX,
+        }
+    }
+
+    /// Return true if this context used up its quota.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Run this context on `emu` for up to `max_steps` steps and switch back to the context that
+    /// was running. The run stops early when the quota runs out, and reports
+    /// `StopReason::LimitExceeded` then.
+    pub fn run(&mut self, emu: &mut Emulator, max_steps: u64) -> RunSummary {
+        self.swap(&mut emu.cpu);
+        let mut summary = emu.run_with(RunLimits::steps(max_steps.min(self.remaining())));
+        self.swap(&mut emu.cpu);
+
+        self.retired += summary.steps;
+        if summary.reason == StopReason::StepLimit && self.is_exhausted() {
+            summary.reason = StopReason::LimitExceeded;
+        }
+        summary
+    }
+
+    /// Exchange this context with the one running on `cpu`. Switching twice restores both.
+    pub fn swap(&mut self, cpu: &mut Cpu) {
+        mem::swap(&mut self.xregs, &mut cpu.xregs);
+        mem::swap(&mut self.fregs, &mut cpu.fregs);
+        mem::swap(&mut self.pc, &mut cpu.pc);
+        mem::swap(&mut self.mode, &mut cpu.mode);
+        mem::swap(&mut self.idle, &mut cpu.idle);
+        let hartid = cpu.state.hartid();
+        cpu.state.set_hartid(self.hartid);
+        self.hartid = hartid;
+        cpu.swap_reservation_set(&mut self.reservation_set);
+    }
+}
@@ -0,0 +1,295 @@
+//! The cosim module steps a reference implementation of RISC-V, such as Sail or Spike, in
+//! lockstep with the emulator and compares the effect of every instruction. The first step whose
+//! effects differ usually points right at the instruction a new extension gets wrong.
+
+use crate::cpu::Mode;
+use crate::delta::{changes, RegisterChange, StepDelta};
+use crate::emulator::Emulator;
+use crate::run::{RunSummary, StopReason};
+use crate::trace::{AccessKind, MemoryAccess};
+
+/// The maximum number of stores a `ReferenceState` reports for one step.
+pub const MAX_REFERENCE_STORES: usize = 4;
+
+/// A part of a step delta that's compared.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Field {
+    /// The program counter before the step.
+    Pc = 0,
+    /// The executed instruction.
+    Inst = 1,
+    /// The program counter after the step.
+    NextPc = 2,
+    /// The privilege mode after the step.
+    Mode = 3,
+    /// The changed integer registers.
+    Xregs = 4,
+    /// The changed floating-point registers.
+    Fregs = 5,
+    /// The changed CSRs.
+    Csrs = 6,
+    /// The stores.
+    Memory = 7,
+}
+
+impl Field {
+    /// Every field, in the order they're compared.
+    pub const ALL: [Field; 8] = [
+        Field::Pc,
+        Field::Inst,
+        Field::NextPc,
+        Field::Mode,
+        Field::Xregs,
+        Field::Fregs,
+        Field::Csrs,
+        Field::Memory,
+    ];
+
+    /// Return the name of the field in `StepDelta`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Pc => "pc",
+            Field::Inst => "inst",
+            Field::NextPc => "next_pc",
+            Field::Mode => "mode",
+            Field::Xregs => "xregs",
+            Field::Fregs => "fregs",
+            Field::Csrs => "csrs",
+            Field::Memory => "memory",
+        }
+    }
+}
+
+/// A reference implementation stepped in lockstep with the emulator. It must start in the same
+/// state as the emulator, with the same program in memory.
+pub trait ReferenceModel {
+    /// Execute one instruction and return its effect. `reason`, `cause` and `tval` aren't
+    /// compared.
+    fn step(&mut self) -> StepDelta;
+
+    /// Return true if `field` isn't compared, e.g. because the model doesn't report it.
+    fn ignores(&self, _field: Field) -> bool {
+        false
+    }
+}
+
+/// The state of a reference model after a step, for models that report their state rather than
+/// the changes. The layout is C-compatible so models behind the FFI can fill it in.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ReferenceState {
+    /// The program counter after the step.
+    pub pc: u64,
+    /// The integer registers.
+    pub xregs: [u64; 32],
+    /// The bits of the floating-point registers.
+    pub fregs: [u64; 32],
+    /// The number of valid entries in `stores`.
+    pub store_count: u64,
+    /// The stores of the step, in program order. Only `addr`, `size` and `value` are compared.
+    pub stores: [MemoryAccess; MAX_REFERENCE_STORES],
+}
+
+impl Default for ReferenceState {
+    fn default() -> Self {
+        let store = MemoryAccess {
+            pc: 0,
+            addr: 0,
+            size: 0,
+            value: 0,
+            kind: AccessKind::Store,
+        };
+        Self {
+            pc: 0,
+            xregs: [0; 32],
+            fregs: [0; 32],
+            store_count: 0,
+            stores: [store; MAX_REFERENCE_STORES],
+        }
+    }
+}
+
+/// A reference model that reports its state after every step. The changes are found by
+/// comparing with the state before, so the instruction, the mode and the CSRs aren't compared.
+pub struct StateModel<F: FnMut(&mut ReferenceState)> {
+    step: F,
+    pc: u64,
+    xregs: Vec<u64>,
+    fregs: Vec<u64>,
+}
+
+impl<F: FnMut(&mut ReferenceState)> StateModel<F> {
+    /// Create a model that starts in the state of `emu` and calls `step` to execute an
+    /// instruction.
+    pub fn new(emu: &Emulator, step: F) -> Self {
+        Self {
+            step,
+            pc: emu.cpu.pc,
+            xregs: emu.xreg_values(),
+            fregs: emu.freg_values(),
+        }
+    }
+}
+
+impl<F: FnMut(&mut ReferenceState)> ReferenceModel for StateModel<F> {
+    fn step(&mut self) -> StepDelta {
+        let mut state = ReferenceState::default();
+        (self.step)(&mut state);
+        let stores = state.stores[..(state.store_count as usize).min(MAX_REFERENCE_STORES)]
+            .iter()
+            .map(|&store| MemoryAccess {
+                pc: self.pc,
+                kind: AccessKind::Store,
+                ..store
+            })
+            .collect();
+        let delta = StepDelta {
+            pc: self.pc,
+            inst: 0,
+            next_pc: state.pc,
+            mode: Mode::Machine,
+            reason: StopReason::StepLimit,
+            cause: 0,
+            tval: 0,
+            xregs: changes(&self.xregs, &state.xregs),
+            fregs: changes(&self.fregs, &state.fregs),
+            csrs: Vec::new(),
+            memory: stores,
+        };
+        self.pc = state.pc;
+        self.xregs = state.xregs.to_vec();
+        self.fregs = state.fregs.to_vec();
+        delta
+    }
+
+    fn ignores(&self, field: Field) -> bool {
+        matches!(field, Field::Inst | Field::Mode | Field::Csrs)
+    }
+}
+
+/// Sort register changes by index, so the order a model reports them in doesn't matter.
+fn sorted(changes: &[RegisterChange]) -> Vec<RegisterChange> {
+    let mut changes = changes.to_vec();
+    changes.sort_by_key(|change| change.index);
+    changes
+}
+
+/// Return true if two stores wrote the same value at the same place.
+fn same_store(a: &MemoryAccess, b: &MemoryAccess) -> bool {
+    (a.addr, a.size, a.value) == (b.addr, b.size, b.value)
+}
+
+/// Return the fields of `ours` and `theirs` that differ.
+pub fn differences(ours: &StepDelta, theirs: &StepDelta) -> Vec<Field> {
+    Field::ALL
+        .iter()
+        .copied()
+        .filter(|field| match field {
+            Field::Pc => ours.pc != theirs.pc,
+            Field::Inst => ours.inst != theirs.inst,
+            Field::NextPc => ours.next_pc != theirs.next_pc,
+            Field::Mode => ours.mode != theirs.mode,
+            Field::Xregs => sorted(&ours.xregs) != sorted(&theirs.xregs),
+            Field::Fregs => sorted(&ours.fregs) != sorted(&theirs.fregs),
+            Field::Csrs => sorted(&ours.csrs) != sorted(&theirs.csrs),
+            Field::Memory => {
+                ours.memory.len() != theirs.memory.len()
+                    || !ours
+                        .memory
+                        .iter()
+                        .zip(&theirs.memory)
+                        .all(|(a, b)| same_store(a, b))
+            }
+        })
+        .collect()
+}
+
+/// The first step whose effects differ.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    /// The number of steps that matched before it.
+    pub step: u64,
+    /// The fields that differ.
+    pub fields: Vec<Field>,
+    /// The effect of the step on the emulator.
+    pub ours: StepDelta,
+    /// The effect of the step on the reference model.
+    pub theirs: StepDelta,
+}
+
+/// Why a lockstep run stopped.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Lockstep {
+    /// The number of steps that matched in this run.
+    pub steps: u64,
+    /// The summary of the step that stopped the emulator, e.g. at an environment call. `None`
+    /// if the step limit was reached or the effects differ.
+    pub stop: Option<RunSummary>,
+    /// True if the run stopped at a mismatch.
+    pub mismatch: bool,
+}
+
+/// The emulator and a reference model, stepped in lockstep.
+pub struct Cosim {
+    model: Box<dyn ReferenceModel>,
+    steps: u64,
+    mismatch: Option<Mismatch>,
+}
+
+impl Cosim {
+    /// Step `model` in lockstep with the emulator from now on.
+    pub fn new(model: Box<dyn ReferenceModel>) -> Self {
+        Self {
+            model,
+            steps: 0,
+            mismatch: None,
+        }
+    }
+
+    /// Return the number of steps that matched so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Return the first mismatch, if any. Once there's one, `run` doesn't step anymore.
+    pub fn mismatch(&self) -> Option<&Mismatch> {
+        self.mismatch.as_ref()
+    }
+
+    /// Run up to `max_steps` steps on both sides and compare their effects. The run stops at the
+    /// first mismatch, and when the emulator stops for the host, as `run` would.
+    pub fn run(&mut self, emu: &mut Emulator, max_steps: u64) -> Lockstep {
+        let mut lockstep = Lockstep {
+            steps: 0,
+            stop: None,
+            mismatch: self.mismatch.is_some(),
+        };
+        while !lockstep.mismatch && lockstep.steps < max_steps {
+            let summary = emu.step_delta();
+            let ours = emu.last_delta.clone().unwrap();
+            let theirs = self.model.step();
+            let fields = differences(&ours, &theirs)
+                .into_iter()
+                .filter(|&field| !self.model.ignores(field))
+                .collect::<Vec<Field>>();
+            if !fields.is_empty() {
+                self.mismatch = Some(Mismatch {
+                    step: self.steps,
+                    fields,
+                    ours,
+                    theirs,
+                });
+                lockstep.mismatch = true;
+                break;
+            }
+            self.steps += 1;
+            lockstep.steps += 1;
+            if summary.reason != StopReason::StepLimit {
+                lockstep.stop = Some(summary);
+                break;
+            }
+        }
+        lockstep
+    }
+}
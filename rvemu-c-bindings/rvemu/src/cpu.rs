@@ -1,5 +1,6 @@
 //! The cpu module contains the privileged mode, registers, and CPU.
 
+use std::cell::Cell;
 use std::cmp;
 use std::cmp::PartialEq;
 use std::collections::BTreeMap;
@@ -7,19 +8,36 @@ use std::fmt;
 use std::num::FpCategory;
 
 use crate::{
-    bus::{Bus, DRAM_BASE},
+    bus::{Bus, DRAM_BASE, DRAM_END},
     csr::*,
     devices::{
+        channel::CHANNEL_IRQ,
+        keyboard::KEYBOARD_IRQ,
         uart::UART_IRQ,
         virtio_blk::{Virtio, VIRTIO_IRQ},
+        watchdog::WATCHDOG_IRQ,
     },
     dram::DRAM_SIZE,
+    encoding_audit::EncodingAudit,
     exception::Exception,
     interrupt::Interrupt,
+    isa::InstructionSet,
+    memory_stats::MemoryStats,
+    nan_boxing::{box_f32, unbox_f32, NanBoxDiagnostics, NanBoxing, CANONICAL_NAN_F32},
+    pmp,
+    preview::imm_cj,
+    trace::{AccessKind, AccessTrace, MemoryAccess},
+    watchpoint::{WatchHit, Watchpoints},
+    xlen::{self, Xlen},
 };
 
 /// The number of registers.
 pub const REGISTERS_COUNT: usize = 32;
+/// The ABI names of the integer registers.
+pub const ABI_NAMES: [&str; REGISTERS_COUNT] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+    "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+];
 /// The page size (4 KiB) for the virtual memory system.
 const PAGE_SIZE: u64 = 4096;
 
@@ -232,31 +250,82 @@ pub struct Cpu {
     reservation_set: Vec<u64>,
     /// Idle state. True when WFI is called, and becomes false when an interrupt happens.
     pub idle: bool,
+    /// Whether CSR instructions and privileged instructions can be executed. `ecall` and
+    /// `ebreak` are always allowed.
+    pub allow_privileged: bool,
+    /// The instructions the CPU may execute, or `None` to allow every implemented instruction.
+    pub instruction_set: Option<InstructionSet>,
+    /// The audit of executed hint and reserved encodings.
+    pub encoding_audit: EncodingAudit,
+    /// How single-precision values are held in the floating-point registers.
+    pub nan_boxing: NanBoxing,
+    /// The log of single-precision reads of registers that aren't properly NaN-boxed.
+    pub nan_box_diagnostics: NanBoxDiagnostics,
+    /// The registers read without a proper NaN box by the current instruction, as a bit mask.
+    unboxed_reads: Cell<u32>,
+    /// The memory ranges whose stores stop a run.
+    pub watchpoints: Watchpoints,
+    /// The log of loads and stores to selected memory ranges.
+    pub access_trace: AccessTrace,
+    /// The loads and stores aggregated by the address of the instruction.
+    pub memory_stats: MemoryStats,
+    /// The loads and stores of the running hart, recorded in multi-hart mode to break the
+    /// reservations of the other harts and to detect data races.
+    pub(crate) access_log: Option<Vec<MemoryAccess>>,
     /// Counter of each instructions for debug.
     pub inst_counter: BTreeMap<String, u64>,
     /// The count flag. Count the number of each instruction executed.
     pub is_count: bool,
     /// Previous instruction. This is for debug.
     pub pre_inst: u64,
+    /// The width of the integer registers.
+    xlen: Xlen,
 }
 
 impl Cpu {
+    /// Exchange the load reservation of the current hart with `reservation_set`, when another
+    /// hart context is switched in.
+    pub(crate) fn swap_reservation_set(&mut self, reservation_set: &mut Vec<u64>) {
+        std::mem::swap(&mut self.reservation_set, reservation_set);
+    }
+
+    /// Return true if the current hart holds a reservation on `addr`.
+    pub(crate) fn has_reservation(&self, addr: u64) -> bool {
+        self.reservation_set.contains(&addr)
+    }
+
     /// Create a new `Cpu` object.
     pub fn new() -> Cpu {
+        Self::with_bus(Bus::new())
+    }
+
+    /// Create a new `Cpu` object on `bus`.
+    pub fn with_bus(bus: Bus) -> Cpu {
         Cpu {
             xregs: XRegisters::new(),
             fregs: FRegisters::new(),
             pc: 0,
             state: State::new(),
             mode: Mode::Machine,
-            bus: Bus::new(),
+            bus,
             enable_paging: false,
             page_table: 0,
             reservation_set: Vec::new(),
             idle: false,
+            allow_privileged: true,
+            instruction_set: None,
+            encoding_audit: EncodingAudit::new(),
+            nan_boxing: NanBoxing::Legacy,
+            nan_box_diagnostics: NanBoxDiagnostics::new(),
+            unboxed_reads: Cell::new(0),
+            watchpoints: Watchpoints::new(),
+            access_trace: AccessTrace::new(),
+            memory_stats: MemoryStats::new(),
+            access_log: None,
             inst_counter: BTreeMap::new(),
             is_count: false,
             pre_inst: 0,
+            xlen: Xlen::Rv64,
         }
     }
 
@@ -308,6 +377,7 @@ impl Cpu {
         self.pc = 0;
         self.mode = Mode::Machine;
         self.state.reset();
+        self.state.write(MISA, self.xlen.misa(self.state.read(MISA)));
         for i in 0..REGISTERS_COUNT {
             self.xregs.write(i as u64, 0);
             self.fregs.write(i as u64, 0.0);
@@ -342,7 +412,8 @@ impl Cpu {
 
         // TODO: Take interrupts based on priorities.
 
-        // Check external interrupt for uart and virtio.
+        // Check external interrupt for uart, virtio, the keyboard controller, the watchdog, and
+        // the message channel.
         let irq;
         if self.bus.uart.is_interrupting() {
             irq = UART_IRQ;
@@ -350,6 +421,12 @@ impl Cpu {
             // An interrupt is raised after a disk access is done.
             Virtio::disk_access(self).expect("failed to access the disk");
             irq = VIRTIO_IRQ;
+        } else if self.bus.keyboard.is_interrupting() {
+            irq = KEYBOARD_IRQ;
+        } else if self.bus.watchdog.is_interrupting() {
+            irq = WATCHDOG_IRQ;
+        } else if self.bus.channel.is_interrupting() {
+            irq = CHANNEL_IRQ;
         } else {
             irq = 0;
         }
@@ -400,8 +477,42 @@ impl Cpu {
         return None;
     }
 
+    /// Write `value` to the CSR at `addr` for the host, as a `csrrw` would. Read-only bits keep
+    /// their values, and writing satp switches the address translation.
+    pub fn write_csr(&mut self, addr: CsrAddress, value: u64) {
+        self.state.write(addr, value);
+        if addr == SATP {
+            self.update_paging();
+        }
+    }
+
+    /// Return the width of the integer registers.
+    pub fn xlen(&self) -> Xlen {
+        self.xlen
+    }
+
+    /// Select the width of the integer registers, e.g. RV32 to match a program assembled for
+    /// RV32I. The MXL field of misa follows, and the registers and the program counter are
+    /// truncated to the width.
+    pub fn set_xlen(&mut self, xlen: Xlen) {
+        self.xlen = xlen;
+        self.state.write(MISA, xlen.misa(self.state.read(MISA)));
+        self.pc = xlen.truncate(self.pc);
+        self.sign_extend_xregs();
+    }
+
+    /// Sign-extend the integer registers from the width, so they hold values an instruction can
+    /// compute with, e.g. after the host wrote one.
+    fn sign_extend_xregs(&mut self) {
+        if self.xlen == Xlen::Rv32 {
+            for i in 1..REGISTERS_COUNT as u64 {
+                self.xregs.write(i, self.xlen.sign_extend(self.xregs.read(i)));
+            }
+        }
+    }
+
     /// Update the physical page number (PPN) and the addressing mode.
-    fn update_paging(&mut self) {
+    pub(crate) fn update_paging(&mut self) {
         // Read the physical page number (PPN) of the root page table, i.e., its
         // supervisor physical address divided by 4 KiB.
         self.page_table = self.state.read_bits(SATP, ..44) * PAGE_SIZE;
@@ -577,9 +688,22 @@ impl Cpu {
         }
     }
 
+    /// Return true if physical memory protection allows a `size`-bit access to the physical
+    /// address in the current privilege mode.
+    fn pmp_allows(&self, p_addr: u64, size: u8, access_type: AccessType) -> bool {
+        pmp::check(
+            &self.state,
+            p_addr,
+            (size / 8) as u64,
+            access_type,
+            self.mode,
+        )
+    }
+
     /// Read `size`-bit data from the system bus with the translation a virtual address to a physical address
     /// if it is enabled.
     fn read(&mut self, v_addr: u64, size: u8) -> Result<u64, Exception> {
+        let v_addr = self.xlen.truncate(v_addr);
         let previous_mode = self.mode;
 
         // 3.1.6.3 Memory Privilege in mstatus Register
@@ -595,7 +719,45 @@ impl Cpu {
         }
 
         let p_addr = self.translate(v_addr, AccessType::Load)?;
-        let result = self.bus.read(p_addr, size);
+        // Devices report the physical address, but the trap value is the faulting virtual address.
+        let result = if self.pmp_allows(p_addr, size, AccessType::Load) {
+            self.bus.read(p_addr, size).map_err(|e| match e {
+                Exception::LoadAccessFault(_) => Exception::LoadAccessFault(v_addr),
+                e => e,
+            })
+        } else {
+            Err(Exception::LoadAccessFault(v_addr))
+        };
+
+        if let Ok(value) = result {
+            let bytes = (size / 8) as u64;
+            if self.access_trace.is_enabled() {
+                self.access_trace.record(self.pc, v_addr, bytes, value, AccessKind::Load);
+            }
+            if self.memory_stats.is_enabled() {
+                self.memory_stats.record(self.pc, p_addr, AccessKind::Load);
+            }
+            if let Some(access_log) = self.access_log.as_mut() {
+                access_log.push(MemoryAccess {
+                    pc: self.pc,
+                    addr: v_addr,
+                    size: (size / 8) as u64,
+                    value,
+                    kind: AccessKind::Load,
+                });
+            }
+            if self.watchpoints.matches(v_addr, bytes, AccessKind::Load) {
+                self.watchpoints.record(WatchHit {
+                    pc: self.pc,
+                    addr: v_addr,
+                    size: bytes,
+                    old_value: value,
+                    new_value: value,
+                    kind: AccessKind::Load,
+                    old_value_known: 1,
+                });
+            }
+        }
 
         if self.state.read_mstatus(MSTATUS_MPRV) == 1 {
             self.mode = previous_mode;
@@ -607,6 +769,7 @@ impl Cpu {
     /// Write `size`-bit data to the system bus with the translation a virtual address to a physical
     /// address if it is enabled.
     fn write(&mut self, v_addr: u64, value: u64, size: u8) -> Result<(), Exception> {
+        let v_addr = self.xlen.truncate(v_addr);
         let previous_mode = self.mode;
 
         // 3.1.6.3 Memory Privilege in mstatus Register
@@ -628,7 +791,59 @@ impl Cpu {
         }
 
         let p_addr = self.translate(v_addr, AccessType::Store)?;
-        let result = self.bus.write(p_addr, value, size);
+
+        // Capture the value before the store commits, so the host can show what changed. Device
+        // registers aren't read, as reads can have side effects.
+        let bytes = (size / 8) as u64;
+        let watched = self.watchpoints.matches(v_addr, bytes, AccessKind::Store);
+        let old_value = if watched && (DRAM_BASE..DRAM_END).contains(&p_addr) {
+            self.bus.read(p_addr, size).ok()
+        } else {
+            None
+        };
+
+        // Devices report the physical address, but the trap value is the faulting virtual address.
+        let result = if self.pmp_allows(p_addr, size, AccessType::Store) {
+            self.bus.write(p_addr, value, size).map_err(|e| match e {
+                Exception::StoreAMOAccessFault(_) => Exception::StoreAMOAccessFault(v_addr),
+                e => e,
+            })
+        } else {
+            Err(Exception::StoreAMOAccessFault(v_addr))
+        };
+
+        if result.is_ok() {
+            let stored = match size {
+                DOUBLEWORD => value,
+                _ => value & ((1 << size) - 1),
+            };
+            if self.access_trace.is_enabled() {
+                self.access_trace.record(self.pc, v_addr, bytes, stored, AccessKind::Store);
+            }
+            if self.memory_stats.is_enabled() {
+                self.memory_stats.record(self.pc, p_addr, AccessKind::Store);
+            }
+            if let Some(access_log) = self.access_log.as_mut() {
+                access_log.push(MemoryAccess {
+                    pc: self.pc,
+                    addr: v_addr,
+                    size: bytes,
+                    value: stored,
+                    kind: AccessKind::Store,
+                });
+            }
+            if watched {
+                self.watchpoints.record(WatchHit {
+                    pc: self.pc,
+                    addr: v_addr,
+                    size: bytes,
+                    old_value: old_value.unwrap_or(0),
+                    new_value: stored,
+                    kind: AccessKind::Store,
+                    old_value_known: old_value.is_some() as u32,
+                });
+            }
+        }
 
         if self.state.read_mstatus(MSTATUS_MPRV) == 1 {
             self.mode = previous_mode;
@@ -640,26 +855,36 @@ impl Cpu {
     /// Fetch the `size`-bit next instruction from the memory at the current program counter.
     pub fn fetch(&mut self, size: u8) -> Result<u64, Exception> {
         if size != HALFWORD && size != WORD {
-            return Err(Exception::InstructionAccessFault);
+            return Err(Exception::InstructionAccessFault(self.pc));
         }
 
         let p_pc = self.translate(self.pc, AccessType::Instruction)?;
 
+        // Executing from ROM or device registers is allowed only if the bus is configured so.
+        if !self.bus.is_executable(p_pc) {
+            return Err(Exception::InstructionAccessFault(self.pc));
+        }
+        if !self.pmp_allows(p_pc, size, AccessType::Instruction) {
+            return Err(Exception::InstructionAccessFault(self.pc));
+        }
+
         // The result of the read method can be `Exception::LoadAccessFault`. In fetch(), an error
         // should be `Exception::InstructionAccessFault`.
         match self.bus.read(p_pc, size) {
             Ok(value) => Ok(value),
-            Err(_) => Err(Exception::InstructionAccessFault),
+            Err(_) => Err(Exception::InstructionAccessFault(self.pc)),
         }
     }
 
     /// Execute a cycle on peripheral devices.
     pub fn devices_increment(&mut self) {
-        // TODO: mtime in Clint and TIME in CSR should be the same value.
-        // Increment the timer register (mtimer) in Clint.
+        // Advance the timer register (mtimer) in Clint depending on its timer mode.
         self.bus.clint.increment(&mut self.state);
-        // Increment the value in the TIME and CYCLE registers in CSR.
-        self.state.increment_time();
+        // Keep the value in the TIME register in CSR the same as mtime, so RDTIME sees the same
+        // notion of time.
+        self.state.sync_time(self.bus.clint.mtime());
+        // Count down the watchdog.
+        self.bus.watchdog.increment();
     }
 
     /// Execute an instruction. Raises an exception if something is wrong, otherwise, returns
@@ -669,6 +894,9 @@ impl Cpu {
         if self.idle {
             return Ok(0);
         }
+        // The program counter wraps around and registers written by the host are sign-extended.
+        self.pc = self.xlen.truncate(self.pc);
+        self.sign_extend_xregs();
 
         // Fetch.
         let inst16 = self.fetch(HALFWORD)?;
@@ -680,21 +908,137 @@ impl Cpu {
                     return Err(Exception::IllegalInstruction(inst16));
                 }
                 inst = inst16;
-                self.execute_compressed(inst)?;
+                self.check_instruction_set(inst)?;
+                self.audit_encoding(inst)?;
+                match self.xlen {
+                    Xlen::Rv32 => self.execute_compressed_rv32(inst)?,
+                    Xlen::Rv64 => self.execute_compressed(inst)?,
+                }
                 // Add 2 bytes to the program counter.
                 self.pc += 2;
             }
             _ => {
                 inst = self.fetch(WORD)?;
-                self.execute_general(inst)?;
+                self.check_instruction_set(inst)?;
+                self.audit_encoding(inst)?;
+                self.unboxed_reads.set(0);
+                match self.xlen {
+                    Xlen::Rv32 => self.execute_general_rv32(inst)?,
+                    Xlen::Rv64 => self.execute_general(inst)?,
+                }
+                self.report_unboxed_reads(inst);
                 // Add 4 bytes to the program counter.
                 self.pc += 4;
             }
         }
+        self.pc = self.xlen.truncate(self.pc);
+        self.sign_extend_xregs();
         self.pre_inst = inst;
         Ok(inst)
     }
 
+    /// Raise an illegal instruction exception if the instruction isn't in the allowed set.
+    fn check_instruction_set(&self, inst: u64) -> Result<(), Exception> {
+        match &self.instruction_set {
+            Some(set) if !set.allows(inst) => Err(Exception::IllegalInstruction(inst)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Log the instruction if it's a hint or reserved encoding, and raise an illegal instruction
+    /// exception if the audit policy says so.
+    fn audit_encoding(&mut self, inst: u64) -> Result<(), Exception> {
+        if self.encoding_audit.is_active() && self.encoding_audit.check(self.pc, inst) {
+            return Err(Exception::IllegalInstruction(inst));
+        }
+        Ok(())
+    }
+
+    /// Read a single-precision value from a floating-point register. In strict NaN-boxing mode,
+    /// a register that isn't properly boxed reads as the canonical NaN.
+    fn read_f32(&self, index: u64) -> f32 {
+        match self.nan_boxing {
+            NanBoxing::Legacy => self.fregs.read(index) as f32,
+            NanBoxing::Strict => unbox_f32(self.fregs.read(index)).unwrap_or_else(|| {
+                self.unboxed_reads
+                    .set(self.unboxed_reads.get() | 1 << index);
+                f32::from_bits(CANONICAL_NAN_F32)
+            }),
+        }
+    }
+
+    /// Write a single-precision value to a floating-point register.
+    fn write_f32(&mut self, index: u64, value: f32) {
+        match self.nan_boxing {
+            NanBoxing::Legacy => self.fregs.write(index, value as f64),
+            NanBoxing::Strict => self.fregs.write(index, box_f32(value)),
+        }
+    }
+
+    /// Read a single-precision operand as a double, for instructions whose result doesn't depend
+    /// on the precision: sign injection, minimum, maximum and comparisons. In legacy mode, the
+    /// register is read as it is.
+    fn read_single(&self, index: u64) -> f64 {
+        match self.nan_boxing {
+            NanBoxing::Legacy => self.fregs.read(index),
+            NanBoxing::Strict => self.read_f32(index) as f64,
+        }
+    }
+
+    /// Write the result of an instruction that reads its operands with `read_single`.
+    fn write_single(&mut self, index: u64, value: f64) {
+        match self.nan_boxing {
+            NanBoxing::Legacy => self.fregs.write(index, value),
+            NanBoxing::Strict => self.write_f32(index, value as f32),
+        }
+    }
+
+    /// Log the unboxed reads of the instruction that just executed, if diagnostics are on.
+    fn report_unboxed_reads(&mut self, inst: u64) {
+        let mask = self.unboxed_reads.replace(0);
+        if mask == 0 || !self.nan_box_diagnostics.is_enabled() {
+            return;
+        }
+        for reg in (0..REGISTERS_COUNT as u64).filter(|reg| mask & 1 << reg != 0) {
+            self.nan_box_diagnostics.record(self.pc, inst, reg);
+        }
+    }
+
+    /// Execute a compressed instruction in RV32, where a few encodings mean other instructions
+    /// than in RV64.
+    fn execute_compressed_rv32(&mut self, inst: u64) -> Result<(), Exception> {
+        if xlen::is_rv64_only(inst) {
+            return Err(Exception::IllegalInstruction(inst));
+        }
+        if let Some(expanded) = xlen::expand_rv32_compressed(inst) {
+            return self.execute_general(expanded);
+        }
+
+        match (inst & 0x3, (inst >> 13) & 0x7, (inst >> 10) & 0x3) {
+            (0b01, 0x1, _) => {
+                // c.jal
+                // Expands to jal x1, offset.
+                inst_count!(self, "c.jal");
+                self.debug(inst, "c.jal");
+
+                self.xregs.write(1, self.pc.wrapping_add(2));
+                self.pc = self.pc.wrapping_add(imm_cj(inst) as u64).wrapping_sub(2);
+            }
+            (0b01, 0x4, 0x0) => {
+                // c.srli
+                // Expands to srli rd, rd, shamt, where rd=rd'+8.
+                inst_count!(self, "c.srli");
+                self.debug(inst, "c.srli");
+
+                let rd = ((inst >> 7) & 0b111) + 8;
+                let shamt = (inst >> 2) & 0x1f;
+                self.xregs.write(rd, (self.xregs.read(rd) as u32 >> shamt) as u64);
+            }
+            _ => self.execute_compressed(inst)?,
+        }
+        Ok(())
+    }
+
     /// Execute a compressed instruction. Raised an exception if something is wrong, otherwise,
     /// returns a fetched instruction. It also increments the program counter by 2 bytes.
     pub fn execute_compressed(&mut self, inst: u64) -> Result<(), Exception> {
@@ -776,7 +1120,7 @@ impl Cpu {
                     }
                     0x4 => {
                         // Reserved.
-                        panic!("reserved");
+                        return Err(Exception::IllegalInstruction(inst));
                     }
                     0x5 => {
                         // c.fsd
@@ -1313,6 +1657,52 @@ impl Cpu {
         Ok(())
     }
 
+    /// Execute a general-purpose instruction in RV32. The instructions whose low 32 bits depend on
+    /// the upper bits of their operands are executed here with their 32-bit semantics, and the
+    /// others as in RV64.
+    fn execute_general_rv32(&mut self, inst: u64) -> Result<(), Exception> {
+        if xlen::is_rv64_only(inst) {
+            return Err(Exception::IllegalInstruction(inst));
+        }
+
+        let rd = (inst & 0x00000f80) >> 7;
+        let rs1 = self.xregs.read((inst & 0x000f8000) >> 15);
+        let rs2 = self.xregs.read((inst & 0x01f00000) >> 20);
+        let shamt = (rs2 & 0x1f) as u32;
+        let (name, value) = match (inst & 0x7f, (inst >> 12) & 0x7, inst >> 25) {
+            (0x13, 0x5, 0x00) => ("srli", (rs1 as u32 >> ((inst >> 20) & 0x1f)) as u64),
+            (0x33, 0x1, 0x00) => ("sll", ((rs1 as u32) << shamt) as u64),
+            (0x33, 0x5, 0x00) => ("srl", (rs1 as u32 >> shamt) as u64),
+            (0x33, 0x5, 0x20) => ("sra", (rs1 as i32 >> shamt) as u64),
+            (0x33, 0x1, 0x01) => {
+                let product = rs1 as i32 as i64 * rs2 as i32 as i64;
+                ("mulh", (product >> 32) as u64)
+            }
+            (0x33, 0x2, 0x01) => {
+                let product = rs1 as i32 as i64 * rs2 as u32 as i64;
+                ("mulhsu", (product >> 32) as u64)
+            }
+            (0x33, 0x3, 0x01) => {
+                let product = rs1 as u32 as u64 * rs2 as u32 as u64;
+                ("mulhu", product >> 32)
+            }
+            (0x33, 0x5, 0x01) => match rs2 as u32 {
+                0 => ("divu", u64::MAX),
+                divisor => ("divu", (rs1 as u32 / divisor) as u64),
+            },
+            (0x33, 0x7, 0x01) => match rs2 as u32 {
+                0 => ("remu", rs1),
+                divisor => ("remu", (rs1 as u32 % divisor) as u64),
+            },
+            _ => return self.execute_general(inst),
+        };
+        inst_count!(self, name);
+        self.debug(inst, name);
+
+        self.xregs.write(rd, self.xlen.sign_extend(value));
+        Ok(())
+    }
+
     /// Execute a general-purpose instruction. Raises an exception if something is wrong,
     /// otherwise, returns a fetched instruction. It also increments the program counter by 4 bytes.
     fn execute_general(&mut self, inst: u64) -> Result<(), Exception> {
@@ -1405,7 +1795,7 @@ impl Cpu {
                         self.debug(inst, "flw");
 
                         let val = f32::from_bits(self.read(addr, WORD)? as u32);
-                        self.fregs.write(rd, val as f64);
+                        self.write_f32(rd, val);
                     }
                     0x3 => {
                         // fld
@@ -1661,7 +2051,12 @@ impl Cpu {
                         inst_count!(self, "fsw");
                         self.debug(inst, "fsw");
 
-                        self.write(addr, (self.fregs.read(rs2) as f32).to_bits() as u64, WORD)?
+                        // The bits are stored as they are, boxed or not.
+                        let val = match self.nan_boxing {
+                            NanBoxing::Legacy => (self.fregs.read(rs2) as f32).to_bits(),
+                            NanBoxing::Strict => self.fregs.read(rs2).to_bits() as u32,
+                        };
+                        self.write(addr, val as u64, WORD)?
                     }
                     0x3 => {
                         // fsd
@@ -1694,7 +2089,7 @@ impl Cpu {
                         // address is not naturally aligned, an address-misaligned exception or
                         // an access-fault exception will be generated."
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(addr, t.wrapping_add(self.xregs.read(rs2)), WORD)?;
@@ -1707,7 +2102,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, t.wrapping_add(self.xregs.read(rs2)), DOUBLEWORD)?;
@@ -1720,7 +2115,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(addr, self.xregs.read(rs2), WORD)?;
@@ -1733,7 +2128,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, self.xregs.read(rs2), DOUBLEWORD)?;
@@ -1749,7 +2144,7 @@ impl Cpu {
                         // naturally aligned to the size of the operand (i.e., eight-byte aligned
                         // for 64-bit words and four-byte aligned for 32-bit words)."
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let value = self.read(addr, WORD)?;
                         self.xregs.write(rd, value as i32 as i64 as u64);
@@ -1765,7 +2160,7 @@ impl Cpu {
                         // naturally aligned to the size of the operand (i.e., eight-byte aligned for
                         // 64-bit words and four-byte aligned for 32-bit words)."
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let value = self.read(addr, DOUBLEWORD)?;
                         self.xregs.write(rd, value);
@@ -1781,7 +2176,7 @@ impl Cpu {
                         // naturally aligned to the size of the operand (i.e., eight-byte aligned for
                         // 64-bit words and four-byte aligned for 32-bit words)."
                         if addr % 4 != 0 {
-                            return Err(Exception::StoreAMOAddressMisaligned);
+                            return Err(Exception::StoreAMOAddressMisaligned(addr));
                         }
                         if self.reservation_set.contains(&addr) {
                             // "Regardless of success or failure, executing an SC.W instruction
@@ -1804,7 +2199,7 @@ impl Cpu {
                         // naturally aligned to the size of the operand (i.e., eight-byte aligned for
                         // 64-bit words and four-byte aligned for 32-bit words)."
                         if addr % 8 != 0 {
-                            return Err(Exception::StoreAMOAddressMisaligned);
+                            return Err(Exception::StoreAMOAddressMisaligned(addr));
                         }
                         if self.reservation_set.contains(&addr) {
                             self.reservation_set.retain(|&x| x != addr);
@@ -1822,7 +2217,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1839,7 +2234,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, t ^ self.xregs.read(rs2), DOUBLEWORD)?;
@@ -1852,7 +2247,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1869,7 +2264,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, t | self.xregs.read(rs2), DOUBLEWORD)?;
@@ -1882,7 +2277,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1899,7 +2294,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, t & self.xregs.read(rs2), DOUBLEWORD)?;
@@ -1912,7 +2307,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1929,7 +2324,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(
@@ -1946,7 +2341,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1963,7 +2358,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(
@@ -1980,7 +2375,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -1997,7 +2392,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, cmp::min(t, self.xregs.read(rs2)), DOUBLEWORD)?;
@@ -2010,7 +2405,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 4 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, WORD)?;
                         self.write(
@@ -2027,7 +2422,7 @@ impl Cpu {
 
                         let addr = self.xregs.read(rs1);
                         if addr % 8 != 0 {
-                            return Err(Exception::LoadAddressMisaligned);
+                            return Err(Exception::LoadAddressMisaligned(addr));
                         }
                         let t = self.read(addr, DOUBLEWORD)?;
                         self.write(addr, cmp::max(t, self.xregs.read(rs2)), DOUBLEWORD)?;
@@ -2477,11 +2872,9 @@ impl Cpu {
                         inst_count!(self, "fmadd.s");
                         self.debug(inst, "fmadd.s");
 
-                        self.fregs.write(
+                        self.write_f32(
                             rd,
-                            (self.fregs.read(rs1) as f32)
-                                .mul_add(self.fregs.read(rs2) as f32, self.fregs.read(rs3) as f32)
-                                as f64,
+                            self.read_f32(rs1).mul_add(self.read_f32(rs2), self.read_f32(rs3)),
                         );
                     }
                     0x1 => {
@@ -2512,11 +2905,9 @@ impl Cpu {
                         inst_count!(self, "fmsub.s");
                         self.debug(inst, "fmsub.s");
 
-                        self.fregs.write(
+                        self.write_f32(
                             rd,
-                            (self.fregs.read(rs1) as f32)
-                                .mul_add(self.fregs.read(rs2) as f32, -self.fregs.read(rs3) as f32)
-                                as f64,
+                            self.read_f32(rs1).mul_add(self.read_f32(rs2), -self.read_f32(rs3)),
                         );
                     }
                     0x1 => {
@@ -2547,11 +2938,9 @@ impl Cpu {
                         inst_count!(self, "fnmadd.s");
                         self.debug(inst, "fnmadd.s");
 
-                        self.fregs.write(
+                        self.write_f32(
                             rd,
-                            (-self.fregs.read(rs1) as f32)
-                                .mul_add(self.fregs.read(rs2) as f32, self.fregs.read(rs3) as f32)
-                                as f64,
+                            (-self.read_f32(rs1)).mul_add(self.read_f32(rs2), self.read_f32(rs3)),
                         );
                     }
                     0x1 => {
@@ -2581,11 +2970,9 @@ impl Cpu {
                         inst_count!(self, "fnmsub.s");
                         self.debug(inst, "fnmsub.s");
 
-                        self.fregs.write(
+                        self.write_f32(
                             rd,
-                            (-self.fregs.read(rs1) as f32)
-                                .mul_add(self.fregs.read(rs2) as f32, -self.fregs.read(rs3) as f32)
-                                as f64,
+                            (-self.read_f32(rs1)).mul_add(self.read_f32(rs2), -self.read_f32(rs3)),
                         );
                     }
                     0x1 => {
@@ -2639,10 +3026,7 @@ impl Cpu {
                         inst_count!(self, "fadd.s");
                         self.debug(inst, "fadd.s");
 
-                        self.fregs.write(
-                            rd,
-                            (self.fregs.read(rs1) as f32 + self.fregs.read(rs2) as f32) as f64,
-                        )
+                        self.write_f32(rd, self.read_f32(rs1) + self.read_f32(rs2))
                     }
                     0x01 => {
                         // fadd.d
@@ -2657,10 +3041,7 @@ impl Cpu {
                         inst_count!(self, "fsub.s");
                         self.debug(inst, "fsub.s");
 
-                        self.fregs.write(
-                            rd,
-                            (self.fregs.read(rs1) as f32 - self.fregs.read(rs2) as f32) as f64,
-                        )
+                        self.write_f32(rd, self.read_f32(rs1) - self.read_f32(rs2))
                     }
                     0x05 => {
                         // fsub.d
@@ -2675,10 +3056,7 @@ impl Cpu {
                         inst_count!(self, "fmul.s");
                         self.debug(inst, "fmul.s");
 
-                        self.fregs.write(
-                            rd,
-                            (self.fregs.read(rs1) as f32 * self.fregs.read(rs2) as f32) as f64,
-                        )
+                        self.write_f32(rd, self.read_f32(rs1) * self.read_f32(rs2))
                     }
                     0x09 => {
                         // fmul.d
@@ -2693,10 +3071,7 @@ impl Cpu {
                         inst_count!(self, "fdiv.s");
                         self.debug(inst, "fdiv.s");
 
-                        self.fregs.write(
-                            rd,
-                            (self.fregs.read(rs1) as f32 / self.fregs.read(rs2) as f32) as f64,
-                        )
+                        self.write_f32(rd, self.read_f32(rs1) / self.read_f32(rs2))
                     }
                     0x0d => {
                         // fdiv.d
@@ -2713,17 +3088,19 @@ impl Cpu {
                                 inst_count!(self, "fsgnj.s");
                                 self.debug(inst, "fsgnj.s");
 
-                                self.fregs
-                                    .write(rd, self.fregs.read(rs1).copysign(self.fregs.read(rs2)));
+                                self.write_single(
+                                    rd,
+                                    self.read_single(rs1).copysign(self.read_single(rs2)),
+                                );
                             }
                             0x1 => {
                                 // fsgnjn.s
                                 inst_count!(self, "fsgnjn.s");
                                 self.debug(inst, "fsgnjn.s");
 
-                                self.fregs.write(
+                                self.write_single(
                                     rd,
-                                    self.fregs.read(rs1).copysign(-self.fregs.read(rs2)),
+                                    self.read_single(rs1).copysign(-self.read_single(rs2)),
                                 );
                             }
                             0x2 => {
@@ -2731,11 +3108,10 @@ impl Cpu {
                                 inst_count!(self, "fsgnjx.s");
                                 self.debug(inst, "fsgnjx.s");
 
-                                let sign1 = (self.fregs.read(rs1) as f32).to_bits() & 0x80000000;
-                                let sign2 = (self.fregs.read(rs2) as f32).to_bits() & 0x80000000;
-                                let other = (self.fregs.read(rs1) as f32).to_bits() & 0x7fffffff;
-                                self.fregs
-                                    .write(rd, f32::from_bits((sign1 ^ sign2) | other) as f64);
+                                let sign1 = self.read_f32(rs1).to_bits() & 0x80000000;
+                                let sign2 = self.read_f32(rs2).to_bits() & 0x80000000;
+                                let other = self.read_f32(rs1).to_bits() & 0x7fffffff;
+                                self.write_f32(rd, f32::from_bits((sign1 ^ sign2) | other));
                             }
                             _ => {
                                 return Err(Exception::IllegalInstruction(inst));
@@ -2785,16 +3161,20 @@ impl Cpu {
                                 inst_count!(self, "fmin.s");
                                 self.debug(inst, "fmin.s");
 
-                                self.fregs
-                                    .write(rd, self.fregs.read(rs1).min(self.fregs.read(rs2)));
+                                self.write_single(
+                                    rd,
+                                    self.read_single(rs1).min(self.read_single(rs2)),
+                                );
                             }
                             0x1 => {
                                 // fmax.s
                                 inst_count!(self, "fmax.s");
                                 self.debug(inst, "fmax.s");
 
-                                self.fregs
-                                    .write(rd, self.fregs.read(rs1).max(self.fregs.read(rs2)));
+                                self.write_single(
+                                    rd,
+                                    self.read_single(rs1).max(self.read_single(rs2)),
+                                );
                             }
                             _ => {
                                 return Err(Exception::IllegalInstruction(inst));
@@ -2829,22 +3209,24 @@ impl Cpu {
                         inst_count!(self, "fcvt.s.d");
                         self.debug(inst, "fcvt.s.d");
 
-                        self.fregs.write(rd, self.fregs.read(rs1));
+                        match self.nan_boxing {
+                            NanBoxing::Legacy => self.fregs.write(rd, self.fregs.read(rs1)),
+                            NanBoxing::Strict => self.write_f32(rd, self.fregs.read(rs1) as f32),
+                        }
                     }
                     0x21 => {
                         // fcvt.d.s
                         inst_count!(self, "fcvt.d.s");
                         self.debug(inst, "fcvt.d.s");
 
-                        self.fregs.write(rd, (self.fregs.read(rs1) as f32) as f64);
+                        self.fregs.write(rd, self.read_f32(rs1) as f64);
                     }
                     0x2c => {
                         // fsqrt.s
                         inst_count!(self, "fsqrt.s");
                         self.debug(inst, "fsqrt.s");
 
-                        self.fregs
-                            .write(rd, (self.fregs.read(rs1) as f32).sqrt() as f64);
+                        self.write_f32(rd, self.read_f32(rs1).sqrt());
                     }
                     0x2d => {
                         // fsqrt.d
@@ -2862,7 +3244,7 @@ impl Cpu {
 
                                 self.xregs.write(
                                     rd,
-                                    if self.fregs.read(rs1) <= self.fregs.read(rs2) {
+                                    if self.read_single(rs1) <= self.read_single(rs2) {
                                         1
                                     } else {
                                         0
@@ -2876,7 +3258,7 @@ impl Cpu {
 
                                 self.xregs.write(
                                     rd,
-                                    if self.fregs.read(rs1) < self.fregs.read(rs2) {
+                                    if self.read_single(rs1) < self.read_single(rs2) {
                                         1
                                     } else {
                                         0
@@ -2890,7 +3272,7 @@ impl Cpu {
 
                                 self.xregs.write(
                                     rd,
-                                    if self.fregs.read(rs1) == self.fregs.read(rs2) {
+                                    if self.read_single(rs1) == self.read_single(rs2) {
                                         1
                                     } else {
                                         0
@@ -2960,7 +3342,7 @@ impl Cpu {
 
                                 self.xregs.write(
                                     rd,
-                                    ((self.fregs.read(rs1) as f32).round() as i32) as u64,
+                                    (self.read_f32(rs1).round() as i32) as u64,
                                 );
                             }
                             0x1 => {
@@ -2970,7 +3352,7 @@ impl Cpu {
 
                                 self.xregs.write(
                                     rd,
-                                    (((self.fregs.read(rs1) as f32).round() as u32) as i32) as u64,
+                                    ((self.read_f32(rs1).round() as u32) as i32) as u64,
                                 );
                             }
                             0x2 => {
@@ -2978,16 +3360,14 @@ impl Cpu {
                                 inst_count!(self, "fcvt.l.s");
                                 self.debug(inst, "fcvt.l.s");
 
-                                self.xregs
-                                    .write(rd, (self.fregs.read(rs1) as f32).round() as u64);
+                                self.xregs.write(rd, self.read_f32(rs1).round() as u64);
                             }
                             0x3 => {
                                 // fcvt.lu.s
                                 inst_count!(self, "fcvt.lu.s");
                                 self.debug(inst, "fcvt.lu.s");
 
-                                self.xregs
-                                    .write(rd, (self.fregs.read(rs1) as f32).round() as u64);
+                                self.xregs.write(rd, self.read_f32(rs1).round() as u64);
                             }
                             _ => {
                                 return Err(Exception::IllegalInstruction(inst));
@@ -3040,31 +3420,28 @@ impl Cpu {
                                 inst_count!(self, "fcvt.s.w");
                                 self.debug(inst, "fcvt.s.w");
 
-                                self.fregs
-                                    .write(rd, ((self.xregs.read(rs1) as i32) as f32) as f64);
+                                self.write_f32(rd, (self.xregs.read(rs1) as i32) as f32);
                             }
                             0x1 => {
                                 // fcvt.s.wu
                                 inst_count!(self, "fcvt.s.wu");
                                 self.debug(inst, "fcvt.s.wu");
 
-                                self.fregs
-                                    .write(rd, ((self.xregs.read(rs1) as u32) as f32) as f64);
+                                self.write_f32(rd, (self.xregs.read(rs1) as u32) as f32);
                             }
                             0x2 => {
                                 // fcvt.s.l
                                 inst_count!(self, "fcvt.s.l");
                                 self.debug(inst, "fcvt.s.l");
 
-                                self.fregs.write(rd, (self.xregs.read(rs1) as f32) as f64);
+                                self.write_f32(rd, self.xregs.read(rs1) as f32);
                             }
                             0x3 => {
                                 // fcvt.s.lu
                                 inst_count!(self, "fcvt.s.lu");
                                 self.debug(inst, "fcvt.s.lu");
 
-                                self.fregs
-                                    .write(rd, ((self.xregs.read(rs1) as u64) as f32) as f64);
+                                self.write_f32(rd, self.xregs.read(rs1) as f32);
                             }
                             _ => {
                                 return Err(Exception::IllegalInstruction(inst));
@@ -3113,6 +3490,7 @@ impl Cpu {
                                 inst_count!(self, "fmv.x.w");
                                 self.debug(inst, "fmv.x.w");
 
+                                // The bits are moved as they are, boxed or not.
                                 self.xregs.write(
                                     rd,
                                     (self.fregs.read(rs1).to_bits() & 0xffffffff) as i32 as i64
@@ -3124,23 +3502,28 @@ impl Cpu {
                                 inst_count!(self, "fclass.s");
                                 self.debug(inst, "fclass.s");
 
-                                let f = self.fregs.read(rs1);
-                                match f.classify() {
+                                let (category, negative) = match self.nan_boxing {
+                                    NanBoxing::Legacy => {
+                                        let f = self.fregs.read(rs1);
+                                        (f.classify(), f.is_sign_negative())
+                                    }
+                                    NanBoxing::Strict => {
+                                        let f = self.read_f32(rs1);
+                                        (f.classify(), f.is_sign_negative())
+                                    }
+                                };
+                                match category {
                                     FpCategory::Infinite => {
-                                        self.xregs
-                                            .write(rd, if f.is_sign_negative() { 0 } else { 7 });
+                                        self.xregs.write(rd, if negative { 0 } else { 7 });
                                     }
                                     FpCategory::Normal => {
-                                        self.xregs
-                                            .write(rd, if f.is_sign_negative() { 1 } else { 6 });
+                                        self.xregs.write(rd, if negative { 1 } else { 6 });
                                     }
                                     FpCategory::Subnormal => {
-                                        self.xregs
-                                            .write(rd, if f.is_sign_negative() { 2 } else { 5 });
+                                        self.xregs.write(rd, if negative { 2 } else { 5 });
                                     }
                                     FpCategory::Zero => {
-                                        self.xregs
-                                            .write(rd, if f.is_sign_negative() { 3 } else { 4 });
+                                        self.xregs.write(rd, if negative { 3 } else { 4 });
                                     }
                                     // don't support a signaling nan, only support a quiet nan.
                                     FpCategory::Nan => self.xregs.write(rd, 9),
@@ -3198,8 +3581,11 @@ impl Cpu {
                         inst_count!(self, "fmv.w.x");
                         self.debug(inst, "fmv.w.x");
 
-                        self.fregs
-                            .write(rd, f64::from_bits(self.xregs.read(rs1) & 0xffffffff));
+                        let bits = self.xregs.read(rs1) & 0xffffffff;
+                        match self.nan_boxing {
+                            NanBoxing::Legacy => self.fregs.write(rd, f64::from_bits(bits)),
+                            NanBoxing::Strict => self.write_f32(rd, f32::from_bits(bits as u32)),
+                        }
                     }
                     0x79 => {
                         // fmv.d.x
@@ -3314,6 +3700,12 @@ impl Cpu {
             0x73 => {
                 // RV32I, RVZicsr, and supervisor ISA
                 let csr_addr = ((inst >> 20) & 0xfff) as u16;
+
+                // Only `ecall` and `ebreak` remain when privileged instructions are forbidden.
+                if !self.allow_privileged && (funct3 != 0x0 || funct7 != 0x0 || rs2 > 0x1) {
+                    return Err(Exception::IllegalInstruction(inst));
+                }
+
                 match funct3 {
                     0x0 => {
                         match (rs2, funct7) {
@@ -3,6 +3,8 @@
 use std::fmt;
 use std::ops::{Bound, Range, RangeBounds, RangeInclusive};
 
+use crate::pmp;
+
 pub type CsrAddress = u16;
 pub type CsrFieldRange = RangeInclusive<usize>;
 
@@ -37,7 +39,7 @@ pub const FCSR: CsrAddress = 0x003;
 
 // User Counter/Timers.
 /// Timer for RDTIME instruction.
-const TIME: CsrAddress = 0xc01;
+pub const TIME: CsrAddress = 0xc01;
 
 /////////////////////////////////////
 // Supervisor-level CSR addresses //
@@ -115,7 +117,7 @@ const MHARTID: CsrAddress = 0xf14;
 /// Machine status register.
 pub const MSTATUS: CsrAddress = 0x300;
 /// ISA and extensions.
-const MISA: CsrAddress = 0x301;
+pub const MISA: CsrAddress = 0x301;
 /// Machine exception delefation register.
 pub const MEDELEG: CsrAddress = 0x302;
 /// Machine interrupt delefation register.
@@ -141,9 +143,13 @@ pub const MIP: CsrAddress = 0x344;
 
 // Machine memory protection.
 /// Physical memory protection configuration.
-const _PMPCFG0: CsrAddress = 0x3a0;
+pub const PMPCFG0: CsrAddress = 0x3a0;
+/// The last physical memory protection configuration register.
+const PMPCFG15: CsrAddress = 0x3af;
 /// Physical memory protection address register.
-const _PMPADDR0: CsrAddress = 0x3b0;
+pub const PMPADDR0: CsrAddress = 0x3b0;
+/// The last physical memory protection address register.
+const PMPADDR63: CsrAddress = 0x3ef;
 
 // MSTATUS fields.
 /// Global interrupt-enable bit for machine mode.
@@ -172,6 +178,9 @@ pub const MEIP_BIT: u64 = 1 << 11;
 /// The state to contains all the CSRs.
 pub struct State {
     csrs: [u64; CSR_SIZE],
+    /// The writes since the log was started, as the address of the CSR actually written and
+    /// its values before and after. `None` when nothing is logged.
+    pub(crate) write_log: Option<Vec<(CsrAddress, u64, u64)>>,
 }
 
 impl fmt::Display for State {
@@ -226,7 +235,16 @@ impl State {
             1; // Extensions[0] (Atomic extension)
         csrs[MISA as usize] = misa;
 
-        Self { csrs }
+        Self {
+            csrs,
+            write_log: None,
+        }
+    }
+
+    /// Return the values of all CSRs indexed by address, e.g. to compare them before and after an
+    /// instruction.
+    pub fn values(&self) -> &[u64] {
+        &self.csrs
     }
 
     /// Increment the value in the TIME register.
@@ -234,6 +252,11 @@ impl State {
         self.csrs[TIME as usize] = self.csrs[TIME as usize].wrapping_add(1);
     }
 
+    /// Set the value in the TIME register, e.g. to follow `mtime` in CLINT.
+    pub fn sync_time(&mut self, time: u64) {
+        self.csrs[TIME as usize] = time;
+    }
+
     /// Read the val from the CSR.
     pub fn read(&self, addr: CsrAddress) -> u64 {
         // 4.1 Supervisor CSRs
@@ -260,6 +283,14 @@ impl State {
         // accessible by the supervisor.  Many supervisor CSRs are a subset of the equivalent
         // machine-mode CSR, and the machinemode chapter should be read first to help understand
         // the supervisor-level CSR descriptions."
+        // The supervisor views write the machine CSRs.
+        let target = match addr {
+            SSTATUS => MSTATUS,
+            SIE => MIE,
+            SIP => MIP,
+            _ => addr,
+        };
+        let old = self.csrs[target as usize];
         match addr {
             MVENDORID => {}
             MARCHID => {}
@@ -277,8 +308,14 @@ impl State {
                 let mask = SSIP_BIT & self.csrs[MIDELEG as usize];
                 self.csrs[MIP as usize] = (self.csrs[MIP as usize] & !mask) | (val & mask);
             }
+            PMPCFG0..=PMPCFG15 => self.csrs[addr as usize] = pmp::write_cfg(self, addr, val),
+            PMPADDR0..=PMPADDR63 => self.csrs[addr as usize] = pmp::write_addr(self, addr, val),
             _ => self.csrs[addr as usize] = val,
         }
+
+        if let Some(log) = self.write_log.as_mut() {
+            log.push((target, old, self.csrs[target as usize]));
+        }
     }
 
     /// Read a bit from the CSR.
@@ -359,6 +396,23 @@ impl State {
         self.write_bits(SSTATUS, range, val);
     }
 
+    /// Return the ID of the hart these CSRs belong to (mhartid).
+    pub fn hartid(&self) -> u64 {
+        self.csrs[MHARTID as usize]
+    }
+
+    /// Set the ID of the hart. mhartid is read-only to the guest, so only the emulator sets it,
+    /// when a hart context is switched in.
+    pub(crate) fn set_hartid(&mut self, hartid: u64) {
+        self.csrs[MHARTID as usize] = hartid;
+    }
+
+    /// Set the values of all CSRs indexed by address as they are, without the rules of `write`,
+    /// e.g. to restore a snapshot.
+    pub(crate) fn set_values(&mut self, values: &[u64]) {
+        self.csrs.copy_from_slice(values);
+    }
+
     /// Write bit(s) to a given field in the MSTATUS register.
     pub fn write_mstatus(&mut self, range: CsrFieldRange, val: u64) {
         self.write_bits(MSTATUS, range, val);
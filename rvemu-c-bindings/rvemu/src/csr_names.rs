@@ -0,0 +1,182 @@
+//! The CSR names module maps CSR names to their addresses, so CSR instructions can name their
+//! CSR, e.g. `csrrw t0, mepc, t1`, and disassemble back to the same name. Game levels can
+//! register names for their own CSRs in the custom address ranges.
+
+use std::collections::BTreeMap;
+
+use crate::csr::CsrAddress;
+
+/// The standard CSRs the core implements, and the counters.
+const STANDARD_CSRS: &[(&str, CsrAddress)] = &[
+    ("ustatus", 0x000),
+    ("fflags", 0x001),
+    ("frm", 0x002),
+    ("fcsr", 0x003),
+    ("uie", 0x004),
+    ("utvec", 0x005),
+    ("uscratch", 0x040),
+    ("uepc", 0x041),
+    ("ucause", 0x042),
+    ("utval", 0x043),
+    ("uip", 0x044),
+    ("cycle", 0xc00),
+    ("time", 0xc01),
+    ("instret", 0xc02),
+    ("sstatus", 0x100),
+    ("sedeleg", 0x102),
+    ("sideleg", 0x103),
+    ("sie", 0x104),
+    ("stvec", 0x105),
+    ("scounteren", 0x106),
+    ("sscratch", 0x140),
+    ("sepc", 0x141),
+    ("scause", 0x142),
+    ("stval", 0x143),
+    ("sip", 0x144),
+    ("satp", 0x180),
+    ("mvendorid", 0xf11),
+    ("marchid", 0xf12),
+    ("mimpid", 0xf13),
+    ("mhartid", 0xf14),
+    ("mstatus", 0x300),
+    ("misa", 0x301),
+    ("medeleg", 0x302),
+    ("mideleg", 0x303),
+    ("mie", 0x304),
+    ("mtvec", 0x305),
+    ("mcounteren", 0x306),
+    ("mscratch", 0x340),
+    ("mepc", 0x341),
+    ("mcause", 0x342),
+    ("mtval", 0x343),
+    ("mip", 0x344),
+    ("mcycle", 0xb00),
+    ("minstret", 0xb02),
+];
+
+/// The CSR instructions, whose second operand is a CSR.
+const CSR_MNEMONICS: &[&str] = &["csrrw", "csrrs", "csrrc", "csrrwi", "csrrsi", "csrrci"];
+
+/// Return the name of the standard CSR at `addr`, including `pmpcfg0` to `pmpcfg15` and
+/// `pmpaddr0` to `pmpaddr63`.
+pub fn standard_name(addr: CsrAddress) -> Option<String> {
+    match addr {
+        0x3a0..=0x3af => Some(format!("pmpcfg{}", addr - 0x3a0)),
+        0x3b0..=0x3ef => Some(format!("pmpaddr{}", addr - 0x3b0)),
+        _ => STANDARD_CSRS
+            .iter()
+            .find(|(_, a)| *a == addr)
+            .map(|(name, _)| name.to_string()),
+    }
+}
+
+/// Return the address of the standard CSR `name`.
+pub fn standard_address(name: &str) -> Option<CsrAddress> {
+    // Only the canonical spelling of the index, e.g. not `pmpcfg01`.
+    let numbered = |prefix: &str, base: CsrAddress, count: CsrAddress| {
+        let digits = name.strip_prefix(prefix)?;
+        let index = digits.parse::<CsrAddress>().ok()?;
+        if index < count && digits == index.to_string() {
+            Some(base + index)
+        } else {
+            None
+        }
+    };
+    STANDARD_CSRS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, addr)| *addr)
+        .or_else(|| numbered("pmpcfg", 0x3a0, 16))
+        .or_else(|| numbered("pmpaddr", 0x3b0, 64))
+}
+
+/// Return true if `name` can name a custom CSR: an identifier that isn't a standard CSR name.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && standard_address(name).is_none()
+}
+
+/// The CSR names known to the assembler and the disassembler: the standard ones and those
+/// registered by the game.
+#[derive(Debug, Default, Clone)]
+pub struct CsrNames {
+    /// The address of each custom CSR.
+    custom: BTreeMap<String, CsrAddress>,
+}
+
+impl CsrNames {
+    /// Create a set with the standard names only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the custom CSR at `addr` `name`. Returns false if `name` isn't an identifier or is a
+    /// standard name, or if `addr` is out of range or is a standard CSR. An existing custom
+    /// name is replaced.
+    pub fn register(&mut self, name: &str, addr: u64) -> bool {
+        if !is_valid_name(name) || addr > 0xfff || standard_name(addr as CsrAddress).is_some() {
+            return false;
+        }
+        self.custom.retain(|_, a| *a != addr as CsrAddress);
+        self.custom.insert(name.to_string(), addr as CsrAddress);
+        true
+    }
+
+    /// Forget the custom names, e.g. before another level is loaded.
+    pub fn clear(&mut self) {
+        self.custom.clear();
+    }
+
+    /// Return the address of the CSR `name`.
+    pub fn address(&self, name: &str) -> Option<CsrAddress> {
+        standard_address(name).or_else(|| self.custom.get(name).copied())
+    }
+
+    /// Return the name of the CSR at `addr`.
+    pub fn name(&self, addr: CsrAddress) -> Option<String> {
+        standard_name(addr).or_else(|| {
+            self.custom
+                .iter()
+                .find(|(_, a)| **a == addr)
+                .map(|(name, _)| name.clone())
+        })
+    }
+
+    /// Return the names and addresses of the standard CSRs, the PMP CSRs included, and of the
+    /// custom CSRs, ordered by address.
+    pub fn all(&self) -> Vec<(String, CsrAddress)> {
+        let mut csrs = STANDARD_CSRS
+            .iter()
+            .map(|(name, addr)| (name.to_string(), *addr))
+            .chain((0x3a0..=0x3ef).filter_map(|addr| Some((standard_name(addr)?, addr))))
+            .chain(self.custom.iter().map(|(name, addr)| (name.clone(), *addr)))
+            .collect::<Vec<(String, CsrAddress)>>();
+        csrs.sort_by_key(|(_, addr)| *addr);
+        csrs
+    }
+
+    /// Replace the CSR name in a CSR instruction with its address, e.g. `csrrw t0, mepc, t1`
+    /// with `csrrw t0, 0x341, t1`. Other lines, and unknown names, are left as they are.
+    pub fn substitute(&self, line: &str) -> String {
+        let mut parts = line.trim_start().splitn(2, char::is_whitespace);
+        let mnemonic = parts.next().unwrap_or("");
+        let operands = match parts.next() {
+            Some(operands) if CSR_MNEMONICS.contains(&mnemonic) => operands,
+            _ => return line.to_string(),
+        };
+        let mut operands = operands
+            .split(',')
+            .map(|operand| operand.trim().to_string())
+            .collect::<Vec<String>>();
+        match operands.get(1).and_then(|csr| self.address(csr)) {
+            Some(addr) => operands[1] = format!("{:#x}", addr),
+            None => return line.to_string(),
+        }
+        let indent = &line[..line.len() - line.trim_start().len()];
+        format!("{}{} {}", indent, mnemonic, operands.join(", "))
+    }
+}
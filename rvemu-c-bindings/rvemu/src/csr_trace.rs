@@ -0,0 +1,82 @@
+//! The CSR trace module logs the writes to CSRs, so the trap-handling lessons can replay how
+//! `mepc`, `mcause` and `mstatus` evolve across a trap and the `mret` that returns from it.
+
+/// The maximum number of writes kept. Later writes are counted but not recorded.
+pub const MAX_CSR_WRITES: usize = 1 << 16;
+
+/// A write to a CSR, by an instruction or by the core itself when it takes a trap or returns
+/// from one. The layout is C-compatible so writes can be copied over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct CsrWrite {
+    /// The address of the instruction that wrote the CSR, or the interrupted instruction for the
+    /// writes of an interrupt.
+    pub pc: u64,
+    /// The address of the CSR. Writes to `sstatus`, `sie` and `sip` are recorded as writes to
+    /// `mstatus`, `mie` and `mip`, which hold their bits.
+    pub addr: u64,
+    /// The value before the write.
+    pub old: u64,
+    /// The value after the write. Read-only bits keep their old value.
+    pub new: u64,
+}
+
+/// The log of CSR writes. Nothing is recorded until it's enabled.
+#[derive(Debug, Default, Clone)]
+pub struct CsrTrace {
+    enabled: bool,
+    writes: Vec<CsrWrite>,
+    dropped: u64,
+}
+
+impl CsrTrace {
+    /// Create a disabled log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop recording writes.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if writes are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record a write.
+    pub fn record(&mut self, write: CsrWrite) {
+        if self.writes.len() == MAX_CSR_WRITES {
+            self.dropped += 1;
+            return;
+        }
+        self.writes.push(write);
+    }
+
+    /// Return the recorded writes from the oldest to the newest.
+    pub fn writes(&self) -> &[CsrWrite] {
+        &self.writes
+    }
+
+    /// Return the number of writes that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove and return the recorded writes.
+    pub fn take(&mut self) -> Vec<CsrWrite> {
+        self.take_oldest(self.writes.len())
+    }
+
+    /// Remove and return up to `count` of the oldest recorded writes. The dropped count is reset
+    /// once every write was taken.
+    pub fn take_oldest(&mut self, count: usize) -> Vec<CsrWrite> {
+        let count = count.min(self.writes.len());
+        let taken = self.writes.drain(..count).collect();
+        if self.writes.is_empty() {
+            self.dropped = 0;
+        }
+        taken
+    }
+}
@@ -0,0 +1,167 @@
+//! The csr_view module decodes the status and interrupt CSRs into named fields, and tells which
+//! enable bits gate each interrupt, so the interrupt lessons can show them without the host
+//! knowing the bit layouts.
+
+use crate::cpu::{Cpu, Mode};
+use crate::csr::*;
+
+/// A field of a CSR layout: the name, the lowest bit and the width in bits.
+type Layout = [(&'static str, u32, u32)];
+
+/// The fields of mstatus on RV64.
+const MSTATUS_LAYOUT: &Layout = &[
+    ("SIE", 1, 1),
+    ("MIE", 3, 1),
+    ("SPIE", 5, 1),
+    ("UBE", 6, 1),
+    ("MPIE", 7, 1),
+    ("SPP", 8, 1),
+    ("VS", 9, 2),
+    ("MPP", 11, 2),
+    ("FS", 13, 2),
+    ("XS", 15, 2),
+    ("MPRV", 17, 1),
+    ("SUM", 18, 1),
+    ("MXR", 19, 1),
+    ("TVM", 20, 1),
+    ("TW", 21, 1),
+    ("TSR", 22, 1),
+    ("UXL", 32, 2),
+    ("SXL", 34, 2),
+    ("SBE", 36, 1),
+    ("MBE", 37, 1),
+    ("SD", 63, 1),
+];
+
+/// The fields of sstatus, the supervisor view of mstatus.
+const SSTATUS_LAYOUT: &Layout = &[
+    ("SIE", 1, 1),
+    ("SPIE", 5, 1),
+    ("UBE", 6, 1),
+    ("SPP", 8, 1),
+    ("FS", 13, 2),
+    ("XS", 15, 2),
+    ("SUM", 18, 1),
+    ("MXR", 19, 1),
+    ("UXL", 32, 2),
+    ("SD", 63, 1),
+];
+
+const MIE_LAYOUT: &Layout = &[
+    ("SSIE", 1, 1),
+    ("MSIE", 3, 1),
+    ("STIE", 5, 1),
+    ("MTIE", 7, 1),
+    ("SEIE", 9, 1),
+    ("MEIE", 11, 1),
+];
+
+const MIP_LAYOUT: &Layout = &[
+    ("SSIP", 1, 1),
+    ("MSIP", 3, 1),
+    ("STIP", 5, 1),
+    ("MTIP", 7, 1),
+    ("SEIP", 9, 1),
+    ("MEIP", 11, 1),
+];
+
+const SIE_LAYOUT: &Layout = &[("SSIE", 1, 1), ("STIE", 5, 1), ("SEIE", 9, 1)];
+
+const SIP_LAYOUT: &Layout = &[("SSIP", 1, 1), ("STIP", 5, 1), ("SEIP", 9, 1)];
+
+/// A named field of a CSR and its value.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct CsrField {
+    /// The name in the privileged specification, e.g. "MPIE".
+    pub name: &'static str,
+    /// The lowest bit of the field.
+    pub lsb: u32,
+    /// The width of the field in bits.
+    pub width: u32,
+    /// The value of the field, shifted down to bit 0.
+    pub value: u64,
+}
+
+/// Return the layout of the CSR at `addr`, if it can be decoded.
+fn layout(addr: CsrAddress) -> Option<&'static Layout> {
+    match addr {
+        MSTATUS => Some(MSTATUS_LAYOUT),
+        SSTATUS => Some(SSTATUS_LAYOUT),
+        MIE => Some(MIE_LAYOUT),
+        MIP => Some(MIP_LAYOUT),
+        SIE => Some(SIE_LAYOUT),
+        SIP => Some(SIP_LAYOUT),
+        _ => None,
+    }
+}
+
+/// Split `value` into the fields of the CSR at `addr`, from the lowest bit up. Returns `None`
+/// for CSRs other than mstatus, sstatus, mie, mip, sie and sip.
+pub fn decode(addr: CsrAddress, value: u64) -> Option<Vec<CsrField>> {
+    let fields = layout(addr)?
+        .iter()
+        .map(|&(name, lsb, width)| CsrField {
+            name,
+            lsb,
+            width,
+            value: (value >> lsb) & ((1 << width) - 1),
+        })
+        .collect();
+    Some(fields)
+}
+
+/// The interrupts the core takes, from the highest priority to the lowest, by exception code.
+const INTERRUPTS: [u64; 6] = [11, 3, 7, 9, 1, 5];
+
+/// What gates an interrupt. The layout is C-compatible so gates can be copied over the FFI as
+/// they are. The flags are 0 or 1.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct InterruptGate {
+    /// The exception code of the interrupt, also its bit in mie and mip.
+    pub code: u64,
+    /// The bit in mip is set.
+    pub pending: u32,
+    /// The bit in mie is set.
+    pub enabled: u32,
+    /// Interrupts are globally enabled in the current mode: by mstatus.MIE in machine mode, by
+    /// mstatus.SIE in supervisor mode, and always in user mode.
+    pub global: u32,
+    /// The bit in mideleg is set, so the trap goes to supervisor mode.
+    pub delegated: u32,
+    /// The interrupt is the one taken before the next instruction.
+    pub taken: u32,
+}
+
+/// Return the gates of the interrupts the core takes, from the highest priority to the lowest,
+/// as the CPU evaluates them before the next instruction.
+pub fn interrupt_gates(cpu: &Cpu) -> Vec<InterruptGate> {
+    let mip = cpu.state.read(MIP);
+    let mie = cpu.state.read(MIE);
+    let mideleg = cpu.state.read(MIDELEG);
+    let global = match cpu.mode {
+        Mode::Machine => cpu.state.read_mstatus(MSTATUS_MIE),
+        Mode::Supervisor => cpu.state.read_sstatus(XSTATUS_SIE),
+        _ => 1,
+    } as u32;
+
+    let mut taken = false;
+    INTERRUPTS
+        .iter()
+        .map(|&code| {
+            let mut gate = InterruptGate {
+                code,
+                pending: ((mip >> code) & 1) as u32,
+                enabled: ((mie >> code) & 1) as u32,
+                global,
+                delegated: ((mideleg >> code) & 1) as u32,
+                taken: 0,
+            };
+            if !taken && gate.pending == 1 && gate.enabled == 1 && gate.global == 1 {
+                taken = true;
+                gate.taken = 1;
+            }
+            gate
+        })
+        .collect()
+}
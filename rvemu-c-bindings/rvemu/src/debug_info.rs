@@ -0,0 +1,75 @@
+//! The debug_info module contains the symbol table and the line table of the loaded program. The
+//! emulator doesn't need them to run a program, but they let the host talk about addresses in
+//! terms of the source code, e.g. "at line 14" or "in `main`".
+
+use std::collections::BTreeMap;
+
+/// The symbol table and the line table of a program.
+#[derive(Debug, Default, Clone)]
+pub struct DebugInfo {
+    /// The start address of each symbol.
+    symbols: BTreeMap<u64, String>,
+    /// The 1-based source line each instruction address was assembled from.
+    lines: BTreeMap<u64, u32>,
+}
+
+impl DebugInfo {
+    /// Create an empty debug info.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if neither symbols nor lines are known.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty() && self.lines.is_empty()
+    }
+
+    /// Forget all symbols and lines, e.g. before another program is loaded.
+    pub fn clear(&mut self) {
+        self.symbols.clear();
+        self.lines.clear();
+    }
+
+    /// Add a symbol `name` starting at `addr`. A symbol already at `addr` is replaced.
+    pub fn add_symbol(&mut self, name: &str, addr: u64) {
+        self.symbols.insert(addr, name.to_string());
+    }
+
+    /// Record that the instruction at `addr` comes from the source line `line`.
+    pub fn add_line(&mut self, addr: u64, line: u32) {
+        self.lines.insert(addr, line);
+    }
+
+    /// Return the source line of the instruction at `addr`.
+    pub fn line_at(&self, addr: u64) -> Option<u32> {
+        self.lines.get(&addr).copied()
+    }
+
+    /// Return the closest symbol at or below `addr` and the offset of `addr` from it.
+    pub fn symbol_at(&self, addr: u64) -> Option<(&str, u64)> {
+        self.symbols
+            .range(..=addr)
+            .next_back()
+            .map(|(start, name)| (name.as_str(), addr - start))
+    }
+
+    /// Return the address of the symbol `name`.
+    pub fn address_of(&self, name: &str) -> Option<u64> {
+        self.symbols
+            .iter()
+            .find(|(_, symbol)| symbol.as_str() == name)
+            .map(|(addr, _)| *addr)
+    }
+
+    /// Describe `addr` as "line N in `symbol`", "line N", "`symbol`+0x4" or just the hexadecimal
+    /// address, depending on what is known about it.
+    pub fn describe(&self, addr: u64) -> String {
+        match (self.line_at(addr), self.symbol_at(addr)) {
+            (Some(line), Some((name, _))) => format!("line {} in `{}`", line, name),
+            (Some(line), None) => format!("line {}", line),
+            (None, Some((name, 0))) => format!("`{}` ({:#x})", name, addr),
+            (None, Some((name, offset))) => format!("`{}`+{:#x} ({:#x})", name, offset, addr),
+            (None, None) => format!("{:#x}", addr),
+        }
+    }
+}
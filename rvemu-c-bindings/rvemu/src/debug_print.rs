@@ -0,0 +1,133 @@
+//! The debug print module lets guest programs print to the host log with an environment call,
+//! so learners can debug with prints before they've learned about the UART. The number of the
+//! call in a7 selects the format, a0 holds the value or the address of the string, and the
+//! program continues right after the `ecall` without the host having to serve it.
+
+/// The environment call printing a0 as a signed decimal number.
+pub const SYS_DEBUG_PRINT_INT: u64 = 0x7e0;
+/// The environment call printing a0 as a hexadecimal number.
+pub const SYS_DEBUG_PRINT_HEX: u64 = 0x7e1;
+/// The environment call printing the NUL-terminated string at the address in a0.
+pub const SYS_DEBUG_PRINT_STR: u64 = 0x7e2;
+
+/// The longest string printed. Longer strings are cut.
+pub const MAX_DEBUG_TEXT: u64 = 256;
+/// The maximum number of prints kept. Later prints are counted but not recorded.
+pub const MAX_DEBUG_PRINTS: usize = 4096;
+
+/// The format of a print.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DebugPrintKind {
+    /// A signed decimal number.
+    Int = 0,
+    /// A hexadecimal number.
+    Hex = 1,
+    /// A string in guest memory.
+    Text = 2,
+}
+
+impl DebugPrintKind {
+    /// Return the format of the environment call `number` (a7), or `None` if it isn't a debug
+    /// print.
+    pub fn from_call(number: u64) -> Option<Self> {
+        match number {
+            SYS_DEBUG_PRINT_INT => Some(DebugPrintKind::Int),
+            SYS_DEBUG_PRINT_HEX => Some(DebugPrintKind::Hex),
+            SYS_DEBUG_PRINT_STR => Some(DebugPrintKind::Text),
+            _ => None,
+        }
+    }
+
+    /// Return the name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            DebugPrintKind::Int => "int",
+            DebugPrintKind::Hex => "hex",
+            DebugPrintKind::Text => "text",
+        }
+    }
+}
+
+/// A print by the guest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DebugPrint {
+    /// The address of the `ecall`.
+    pub pc: u64,
+    /// The format of the print.
+    pub kind: DebugPrintKind,
+    /// The value of a0: the number, or the address of the string.
+    pub value: u64,
+    /// The string for `Text`, or `None` if it isn't in DRAM.
+    pub text: Option<String>,
+}
+
+impl DebugPrint {
+    /// Return what the program printed, e.g. `42`.
+    pub fn message(&self) -> String {
+        match self.kind {
+            DebugPrintKind::Int => (self.value as i64).to_string(),
+            DebugPrintKind::Hex => format!("{:#x}", self.value),
+            DebugPrintKind::Text => match &self.text {
+                Some(text) => text.clone(),
+                None => format!("<no string at {:#x}>", self.value),
+            },
+        }
+    }
+
+    /// Return the line shown in the host log, e.g. `0x80000010: 42`.
+    pub fn render(&self) -> String {
+        format!("{:#x}: {}", self.pc, self.message())
+    }
+}
+
+/// The log of debug prints. Debug print calls are only served while it's enabled; otherwise
+/// they are handed to the host like any other environment call.
+#[derive(Debug, Default, Clone)]
+pub struct DebugPrints {
+    enabled: bool,
+    prints: Vec<DebugPrint>,
+    dropped: u64,
+}
+
+impl DebugPrints {
+    /// Create a disabled log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop serving debug prints.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if debug prints are served.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record a print.
+    pub fn record(&mut self, print: DebugPrint) {
+        if self.prints.len() == MAX_DEBUG_PRINTS {
+            self.dropped += 1;
+            return;
+        }
+        self.prints.push(print);
+    }
+
+    /// Return the recorded prints from the oldest to the newest.
+    pub fn prints(&self) -> &[DebugPrint] {
+        &self.prints
+    }
+
+    /// Return the number of prints that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Forget the recorded prints, e.g. once they are shown.
+    pub fn clear(&mut self) {
+        self.prints.clear();
+        self.dropped = 0;
+    }
+}
@@ -0,0 +1,62 @@
+//! The delta module describes the complete architectural effect of one step: the registers, CSRs
+//! and memory it wrote and where execution goes next. Property tests compare deltas against a
+//! reference model, and the most detailed visualizations animate them.
+
+use crate::cpu::Mode;
+use crate::run::StopReason;
+use crate::trace::MemoryAccess;
+
+/// A register or CSR whose value changed. The layout is C-compatible so it can be copied over the
+/// FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct RegisterChange {
+    /// The register number, or the address of a CSR.
+    pub index: u64,
+    /// The value before the step. Floating-point registers hold the bits of the value.
+    pub old: u64,
+    /// The value after the step.
+    pub new: u64,
+}
+
+/// The architectural effect of one step.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StepDelta {
+    /// The program counter before the step.
+    pub pc: u64,
+    /// The instruction at `pc`, or 0 if it isn't in DRAM. Compressed instructions are 16 bits.
+    pub inst: u64,
+    /// The program counter after the step.
+    pub next_pc: u64,
+    /// The privilege mode after the step.
+    pub mode: Mode,
+    /// Why the step stopped, as for a run of one step.
+    pub reason: StopReason,
+    /// The exception code for `Trapped`, `Yielded` and `Breakpoint`, otherwise 0.
+    pub cause: u64,
+    /// The trap value for `Trapped`, otherwise 0.
+    pub tval: u64,
+    /// The integer registers that changed.
+    pub xregs: Vec<RegisterChange>,
+    /// The floating-point registers that changed.
+    pub fregs: Vec<RegisterChange>,
+    /// The CSRs that changed, except `time`, which follows the timer on every step.
+    pub csrs: Vec<RegisterChange>,
+    /// The stores, in program order.
+    pub memory: Vec<MemoryAccess>,
+}
+
+/// Return the values that differ between `before` and `after`, indexed by position.
+pub fn changes(before: &[u64], after: &[u64]) -> Vec<RegisterChange> {
+    before
+        .iter()
+        .zip(after)
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(index, (&old, &new))| RegisterChange {
+            index: index as u64,
+            old,
+            new,
+        })
+        .collect()
+}
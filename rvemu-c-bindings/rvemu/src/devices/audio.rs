@@ -0,0 +1,113 @@
+//! The audio module contains a ring buffer of PCM samples. The guest synthesizes sound by writing
+//! 16-bit signed samples at the sample rate of the game, and the host drains them into its audio
+//! output.
+
+use std::collections::VecDeque;
+
+use crate::bus::AUDIO_BASE;
+use crate::cpu::{HALFWORD, WORD};
+use crate::exception::Exception;
+
+/// The number of samples the ring buffer holds.
+pub const AUDIO_RING_SIZE: usize = 4096;
+
+/// Sample register (write-only). Writing a halfword or a word appends its low 16 bits as a
+/// sample.
+const AUDIO_SAMPLE: u64 = AUDIO_BASE;
+/// Free register (read-only). The number of samples the guest can write before the ring is full.
+const AUDIO_FREE: u64 = AUDIO_BASE + 0x4;
+/// Underrun register (read-only). The number of times the host drained the ring with fewer
+/// samples than it needed.
+const AUDIO_UNDERRUNS: u64 = AUDIO_BASE + 0x8;
+/// Overrun register (read-only). The number of samples dropped because the ring was full.
+const AUDIO_OVERRUNS: u64 = AUDIO_BASE + 0xc;
+
+/// The audio sample ring buffer.
+/// 0x00 sample (2 or 4 bytes)
+/// 0x04 free (4 bytes)
+/// 0x08 underruns (4 bytes)
+/// 0x0c overruns (4 bytes)
+pub struct Audio {
+    samples: VecDeque<i16>,
+    underruns: u32,
+    overruns: u32,
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Audio {
+    /// Create a new, empty audio ring buffer.
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(AUDIO_RING_SIZE),
+            underruns: 0,
+            overruns: 0,
+        }
+    }
+
+    /// Return the number of samples waiting for the host.
+    pub fn pending(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Return the number of underruns since creation.
+    pub fn underruns(&self) -> u32 {
+        self.underruns
+    }
+
+    /// Return the number of samples dropped since creation because the ring was full.
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Append a sample, or drop it if the ring is full.
+    pub fn push_sample(&mut self, sample: i16) {
+        if self.samples.len() < AUDIO_RING_SIZE {
+            self.samples.push_back(sample);
+        } else {
+            self.overruns = self.overruns.wrapping_add(1);
+        }
+    }
+
+    /// Move the oldest samples into `out` and return how many were moved. Fewer samples than
+    /// `out` holds count as an underrun.
+    pub fn drain(&mut self, out: &mut [i16]) -> usize {
+        let count = out.len().min(self.samples.len());
+        for (slot, sample) in out.iter_mut().zip(self.samples.drain(..count)) {
+            *slot = sample;
+        }
+        if count < out.len() {
+            self.underruns = self.underruns.wrapping_add(1);
+        }
+        count
+    }
+
+    /// Load a 32-bit register located at `addr` in the audio device.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != WORD {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        match addr {
+            AUDIO_SAMPLE => Ok(0),
+            AUDIO_FREE => Ok((AUDIO_RING_SIZE - self.samples.len()) as u64),
+            AUDIO_UNDERRUNS => Ok(self.underruns as u64),
+            AUDIO_OVERRUNS => Ok(self.overruns as u64),
+            _ => Err(Exception::LoadAccessFault(addr)),
+        }
+    }
+
+    /// Store a register located at `addr` in the audio device.
+    pub fn write(&mut self, addr: u64, value: u32, size: u8) -> Result<(), Exception> {
+        match (addr, size) {
+            (AUDIO_SAMPLE, HALFWORD) | (AUDIO_SAMPLE, WORD) => self.push_sample(value as i16),
+            (AUDIO_FREE, WORD) | (AUDIO_UNDERRUNS, WORD) | (AUDIO_OVERRUNS, WORD) => {}
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+//! The channel module contains a message channel between two machines, e.g. two emulators of a
+//! distributed-systems level, or an emulator and the host. Each side sends 32-bit messages into
+//! its outbox, and the host moves them to the inbox of the other side in order. The guest can be
+//! notified of incoming messages through an external interrupt.
+
+use std::collections::VecDeque;
+
+use crate::bus::CHANNEL_BASE;
+use crate::cpu::WORD;
+use crate::exception::Exception;
+
+/// The interrupt request of the channel.
+pub const CHANNEL_IRQ: u64 = 13;
+
+/// The maximum number of messages in the inbox and in the outbox.
+pub const CHANNEL_FIFO_SIZE: usize = 64;
+
+/// Data register. Reading it pops the oldest received message, or 0 if there's none. Writing it
+/// sends a message.
+const CHANNEL_DATA: u64 = CHANNEL_BASE;
+/// Status register (read-only).
+const CHANNEL_STATUS: u64 = CHANNEL_BASE + 0x4;
+/// Control register.
+const CHANNEL_CTRL: u64 = CHANNEL_BASE + 0x8;
+/// Dropped register (read-only). The number of messages the guest sent while its outbox was full.
+const CHANNEL_DROPPED: u64 = CHANNEL_BASE + 0xc;
+
+/// Status bit 0: at least one message is waiting in the inbox.
+pub const STATUS_RECEIVED: u32 = 1;
+/// Status bit 1: the outbox is full and further messages are dropped.
+pub const STATUS_OUTBOX_FULL: u32 = 1 << 1;
+
+/// Control bit 0: raise an interrupt when a message arrives.
+pub const CTRL_IRQ_ENABLE: u32 = 1;
+
+/// The message channel.
+/// 0x00 data (4 bytes)
+/// 0x04 status (4 bytes)
+/// 0x08 control (4 bytes)
+/// 0x0c dropped (4 bytes)
+pub struct Channel {
+    /// The messages received and not read by the guest yet.
+    inbox: VecDeque<u32>,
+    /// The messages sent by the guest and not delivered yet.
+    outbox: VecDeque<u32>,
+    ctrl: u32,
+    dropped: u32,
+    /// True if a message arrived since the last time the interrupt was checked.
+    interrupting: bool,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Channel {
+    /// Create a new channel object with empty mailboxes. The interrupt is disabled.
+    pub fn new() -> Self {
+        Self {
+            inbox: VecDeque::new(),
+            outbox: VecDeque::new(),
+            ctrl: 0,
+            dropped: 0,
+            interrupting: false,
+        }
+    }
+
+    /// Put a message into the inbox. Returns false if the inbox is full.
+    pub fn deliver(&mut self, message: u32) -> bool {
+        if !self.has_room() {
+            return false;
+        }
+        self.inbox.push_back(message);
+        if (self.ctrl & CTRL_IRQ_ENABLE) != 0 {
+            self.interrupting = true;
+        }
+        true
+    }
+
+    /// Remove and return the oldest message the guest sent, if any.
+    pub fn take_sent(&mut self) -> Option<u32> {
+        self.outbox.pop_front()
+    }
+
+    /// Return the oldest message the guest sent without removing it, if any.
+    pub fn peek_sent(&self) -> Option<u32> {
+        self.outbox.front().copied()
+    }
+
+    /// Return true if the inbox has room for another message.
+    pub fn has_room(&self) -> bool {
+        self.inbox.len() < CHANNEL_FIFO_SIZE
+    }
+
+    /// Move the messages each side sent to the inbox of the other, in order, as long as there's
+    /// room. Returns the number of messages moved.
+    pub fn exchange(&mut self, other: &mut Channel) -> usize {
+        Self::forward(self, other) + Self::forward(other, self)
+    }
+
+    /// Move the messages `from` sent to the inbox of `to` as long as there's room.
+    fn forward(from: &mut Channel, to: &mut Channel) -> usize {
+        let mut moved = 0;
+        while let Some(&message) = from.outbox.front() {
+            if !to.deliver(message) {
+                break;
+            }
+            from.outbox.pop_front();
+            moved += 1;
+        }
+        moved
+    }
+
+    /// Return the number of messages waiting in the inbox.
+    pub fn pending(&self) -> usize {
+        self.inbox.len()
+    }
+
+    /// Return true if an interrupt is pending. Clear the interrupting flag by swapping a value.
+    pub fn is_interrupting(&mut self) -> bool {
+        std::mem::replace(&mut self.interrupting, false)
+    }
+
+    /// Return the value of the status register.
+    fn status(&self) -> u32 {
+        let mut status = 0;
+        if !self.inbox.is_empty() {
+            status |= STATUS_RECEIVED;
+        }
+        if self.outbox.len() >= CHANNEL_FIFO_SIZE {
+            status |= STATUS_OUTBOX_FULL;
+        }
+        status
+    }
+
+    /// Load a 32-bit register located at `addr` in the channel.
+    pub fn read(&mut self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != WORD {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        match addr {
+            CHANNEL_DATA => {
+                let message = self.inbox.pop_front().unwrap_or(0);
+                // Keep the interrupt line asserted while more messages are waiting.
+                if !self.inbox.is_empty() && (self.ctrl & CTRL_IRQ_ENABLE) != 0 {
+                    self.interrupting = true;
+                }
+                Ok(message as u64)
+            }
+            CHANNEL_STATUS => Ok(self.status() as u64),
+            CHANNEL_CTRL => Ok(self.ctrl as u64),
+            CHANNEL_DROPPED => Ok(self.dropped as u64),
+            _ => Err(Exception::LoadAccessFault(addr)),
+        }
+    }
+
+    /// Store a 32-bit register located at `addr` in the channel.
+    pub fn write(&mut self, addr: u64, value: u32, size: u8) -> Result<(), Exception> {
+        if size != WORD {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+
+        match addr {
+            CHANNEL_DATA => {
+                if self.outbox.len() < CHANNEL_FIFO_SIZE {
+                    self.outbox.push_back(value);
+                } else {
+                    self.dropped = self.dropped.wrapping_add(1);
+                }
+            }
+            CHANNEL_CTRL => self.ctrl = value & CTRL_IRQ_ENABLE,
+            CHANNEL_STATUS | CHANNEL_DROPPED => {}
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        }
+        Ok(())
+    }
+}
@@ -12,6 +12,8 @@
 // - https://github.com/qemu/qemu/blob/master/hw/intc/sifive_clint.c
 // - https://github.com/qemu/qemu/blob/master/include/hw/intc/sifive_clint.h
 
+use std::time::{Duration, Instant};
+
 use crate::bus::CLINT_BASE;
 use crate::cpu::{BYTE, DOUBLEWORD, HALFWORD, WORD};
 use crate::csr::{State, MIP, MSIP_BIT, MTIP_BIT};
@@ -35,10 +37,26 @@ const MTIME: u64 = CLINT_BASE + 0xbff8;
 /// The address that a timer register ends. `mtime` is a 8-byte register.
 const MTIME_END: u64 = MTIME + 0x8;
 
+/// The default frequency of `mtime` in the wall-clock mode (10 MHz), the same as the
+/// `timebase-frequency` of the QEMU virt machine.
+pub const DEFAULT_TIMER_FREQUENCY: u64 = 10_000_000;
+
+/// The source that advances the `mtime` register.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TimerMode {
+    /// Advance by one tick per cycle, i.e. per retired instruction. It's deterministic.
+    Instructions,
+    /// Advance with the host wall-clock time at `frequency` ticks per second.
+    WallClock,
+    /// Advance only when the host calls `advance`.
+    Manual,
+}
+
 /// The core-local interruptor (CLINT).
 /// 0x0000 msip for hart 0 (4 bytes)
 /// 0x4000 mtimecmp for hart 0 (8 bytes)
 /// 0xbff8 mtime (8 bytes)
+#[derive(Clone)]
 pub struct Clint {
     /// Machine mode software interrupt pending register, used to assert a software interrupt for
     /// a CPU.
@@ -48,6 +66,16 @@ pub struct Clint {
     mtimecmp: u64,
     /// Machine mode timer register which runs at a constant frequency.
     mtime: u64,
+    /// The source that advances `mtime`.
+    mode: TimerMode,
+    /// The number of ticks per host second in the wall-clock mode.
+    frequency: u64,
+    /// True while the emulator is paused, e.g. stopped in a debugger. `mtime` doesn't advance in
+    /// any mode.
+    paused: bool,
+    /// The host time up to which wall-clock ticks were already added to `mtime`. `None` until the
+    /// first cycle after creation or resumption.
+    last_sample: Option<Instant>,
 }
 
 impl Clint {
@@ -57,15 +85,84 @@ impl Clint {
             msip: 0,
             mtimecmp: 0,
             mtime: 0,
+            mode: TimerMode::Instructions,
+            frequency: DEFAULT_TIMER_FREQUENCY,
+            paused: false,
+            last_sample: None,
         }
     }
 
-    /// Increment the mtimer register. It's not a real-time value. The MTIP bit (MIP, 7) is enabled
+    /// Return the current value of the mtime register.
+    pub fn mtime(&self) -> u64 {
+        self.mtime
+    }
+
+    /// Return the source that advances `mtime`.
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Set the source that advances `mtime`. `frequency` is the number of ticks per host second
+    /// and is only used in the wall-clock mode.
+    pub fn set_mode(&mut self, mode: TimerMode, frequency: u64) {
+        self.mode = mode;
+        self.frequency = frequency.max(1);
+        self.last_sample = None;
+    }
+
+    /// Add `ticks` to `mtime`. It works in every mode, so the host can skip time forward.
+    pub fn advance(&mut self, ticks: u64) {
+        self.mtime = self.mtime.wrapping_add(ticks);
+    }
+
+    /// Freeze `mtime`, e.g. while the emulator is stopped in a debugger.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Unfreeze `mtime`. The host time spent paused is not added in the wall-clock mode.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.last_sample = None;
+    }
+
+    /// Return true if `mtime` is frozen.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Return the number of whole ticks elapsed on the host since the last sample, and move the
+    /// sample point forward by exactly that many ticks so that fractions are not lost.
+    fn elapsed_ticks(&mut self) -> u64 {
+        let now = Instant::now();
+        let last = match self.last_sample {
+            Some(last) => last,
+            None => {
+                self.last_sample = Some(now);
+                return 0;
+            }
+        };
+
+        let nanos = now.duration_since(last).as_nanos();
+        let ticks = (nanos * self.frequency as u128 / 1_000_000_000) as u64;
+        let consumed = ticks as u128 * 1_000_000_000 / self.frequency as u128;
+        self.last_sample = Some(last + Duration::from_nanos(consumed as u64));
+        ticks
+    }
+
+    /// Advance the mtimer register depending on the timer mode. The MTIP bit (MIP, 7) is enabled
     /// when `mtime` is greater than or equal to `mtimecmp`.
     pub fn increment(&mut self, state: &mut State) {
-        self.mtime = self.mtime.wrapping_add(1);
-        // Sync TIME csr.
-        //state.write(TIME, self.mtime);
+        if !self.paused {
+            match self.mode {
+                TimerMode::Instructions => self.mtime = self.mtime.wrapping_add(1),
+                TimerMode::WallClock => {
+                    let ticks = self.elapsed_ticks();
+                    self.mtime = self.mtime.wrapping_add(ticks);
+                }
+                TimerMode::Manual => {}
+            }
+        }
 
         if (self.msip & 1) != 0 {
             // Enable the MSIP bit (MIP, 3).
@@ -97,7 +194,7 @@ impl Clint {
             MSIP..=MSIP_END => (self.msip as u64, addr - MSIP),
             MTIMECMP..=MTIMECMP_END => (self.mtimecmp, addr - MTIMECMP),
             MTIME..=MTIME_END => (self.mtime, addr - MTIME),
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         };
 
         match size {
@@ -105,7 +202,7 @@ impl Clint {
             HALFWORD => Ok((reg >> (offset * 8)) & 0xffff),
             WORD => Ok((reg >> (offset * 8)) & 0xffffffff),
             DOUBLEWORD => Ok(reg),
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         }
     }
 
@@ -117,7 +214,7 @@ impl Clint {
             MSIP..=MSIP_END => (self.msip as u64, addr - MSIP),
             MTIMECMP..=MTIMECMP_END => (self.mtimecmp, addr - MTIMECMP),
             MTIME..=MTIME_END => (self.mtime, addr - MTIME),
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         };
 
         // Calculate the new value of the target register based on `size` and `offset`.
@@ -139,7 +236,7 @@ impl Clint {
             DOUBLEWORD => {
                 reg = value;
             }
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
 
         // Store the new value to the target register.
@@ -147,7 +244,7 @@ impl Clint {
             MSIP..=MSIP_END => self.msip = reg as u32,
             MTIMECMP..=MTIMECMP_END => self.mtimecmp = reg,
             MTIME..=MTIME_END => self.mtime = reg,
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
 
         Ok(())
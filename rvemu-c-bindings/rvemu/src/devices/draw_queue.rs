@@ -0,0 +1,155 @@
+//! The draw queue module contains a command-queue graphics device for the early graphics levels.
+//! Instead of writing pixels to a framebuffer, the guest enqueues simple draw commands (blit a
+//! sprite, fill a rectangle, set a palette entry) that the host executes with its own renderer.
+
+use std::collections::VecDeque;
+
+use crate::bus::DRAW_QUEUE_BASE;
+use crate::cpu::WORD;
+use crate::exception::Exception;
+
+/// The maximum number of commands queued before the host drains them. Further commands are
+/// dropped.
+pub const DRAW_QUEUE_SIZE: usize = 256;
+
+/// The number of argument registers.
+pub const DRAW_ARGS: usize = 6;
+
+/// Opcode register. The kind of the next command.
+const DRAW_OPCODE: u64 = DRAW_QUEUE_BASE;
+/// The first argument register. The others follow every 4 bytes.
+const DRAW_ARG0: u64 = DRAW_QUEUE_BASE + 0x4;
+/// Submit register (write-only). Writing any value enqueues the command in the opcode and
+/// argument registers, which keep their values for the next command.
+const DRAW_SUBMIT: u64 = DRAW_QUEUE_BASE + 0x1c;
+/// Pending register (read-only). The number of queued commands.
+const DRAW_PENDING: u64 = DRAW_QUEUE_BASE + 0x20;
+/// Dropped register (read-only). The number of commands dropped because the queue was full or
+/// their opcode is unknown.
+const DRAW_DROPPED: u64 = DRAW_QUEUE_BASE + 0x24;
+
+/// The kind of a draw command.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DrawKind {
+    /// Copy the `width` x `height` pixels at guest address `arg0`, 4 bytes each in RGBA order,
+    /// to (`arg1`, `arg2`). The width and height are `arg3` and `arg4`.
+    Blit = 1,
+    /// Fill the `arg2` x `arg3` rectangle at (`arg0`, `arg1`) with the RGBA color `arg4`.
+    FillRect = 2,
+    /// Set the palette entry `arg0` to the RGBA color `arg1`.
+    SetPalette = 3,
+}
+
+impl DrawKind {
+    /// Return the kind with the opcode `opcode`.
+    pub fn from_opcode(opcode: u32) -> Option<Self> {
+        match opcode {
+            1 => Some(DrawKind::Blit),
+            2 => Some(DrawKind::FillRect),
+            3 => Some(DrawKind::SetPalette),
+            _ => None,
+        }
+    }
+}
+
+/// A draw command enqueued by the guest. The layout is C-compatible so it can be returned over
+/// the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct DrawCommand {
+    pub kind: DrawKind,
+    pub args: [u32; DRAW_ARGS],
+}
+
+/// The draw queue.
+/// 0x00 opcode (4 bytes)
+/// 0x04-0x18 arguments 0 to 5 (4 bytes each)
+/// 0x1c submit (4 bytes)
+/// 0x20 pending (4 bytes)
+/// 0x24 dropped (4 bytes)
+pub struct DrawQueue {
+    opcode: u32,
+    args: [u32; DRAW_ARGS],
+    queue: VecDeque<DrawCommand>,
+    dropped: u32,
+}
+
+impl Default for DrawQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrawQueue {
+    /// Create a new, empty draw queue.
+    pub fn new() -> Self {
+        Self {
+            opcode: 0,
+            args: [0; DRAW_ARGS],
+            queue: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Return the number of queued commands.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Return the number of commands dropped since the queue was created.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Remove and return the queued commands, oldest first.
+    pub fn take(&mut self) -> Vec<DrawCommand> {
+        self.queue.drain(..).collect()
+    }
+
+    /// Enqueue the command in the registers, or drop it.
+    fn submit(&mut self) {
+        match DrawKind::from_opcode(self.opcode) {
+            Some(kind) if self.queue.len() < DRAW_QUEUE_SIZE => self.queue.push_back(DrawCommand {
+                kind,
+                args: self.args,
+            }),
+            _ => self.dropped = self.dropped.wrapping_add(1),
+        }
+    }
+
+    /// Read a register.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != WORD {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        let value = match addr {
+            DRAW_OPCODE => self.opcode,
+            DRAW_ARG0..=DRAW_SUBMIT if addr < DRAW_SUBMIT => {
+                self.args[((addr - DRAW_ARG0) / 4) as usize]
+            }
+            DRAW_PENDING => self.queue.len() as u32,
+            DRAW_DROPPED => self.dropped,
+            _ => 0,
+        };
+        Ok(value as u64)
+    }
+
+    /// Write a register.
+    pub fn write(&mut self, addr: u64, value: u32, size: u8) -> Result<(), Exception> {
+        if size != WORD {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+
+        match addr {
+            DRAW_OPCODE => self.opcode = value,
+            DRAW_ARG0..=DRAW_SUBMIT if addr < DRAW_SUBMIT => {
+                self.args[((addr - DRAW_ARG0) / 4) as usize] = value
+            }
+            DRAW_SUBMIT => self.submit(),
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+//! The keyboard module contains a PS/2-style keyboard controller modeled after the Intel 8042.
+//! The host pushes scan codes into a FIFO, and the guest drains them one byte at a time through
+//! the data port, optionally being notified through an external interrupt.
+
+// Reference:
+// "8042 PS/2 Controller" on OSDev Wiki
+// https://wiki.osdev.org/%228042%22_PS/2_Controller
+// "PS/2 Keyboard" on OSDev Wiki
+// https://wiki.osdev.org/PS/2_Keyboard
+
+use std::collections::VecDeque;
+
+use crate::bus::KEYBOARD_BASE;
+use crate::cpu::BYTE;
+use crate::exception::Exception;
+
+/// The interrupt request of the keyboard controller.
+pub const KEYBOARD_IRQ: u64 = 11;
+
+/// The maximum number of scan codes the controller buffers before reporting an overrun.
+pub const KEYBOARD_FIFO_SIZE: usize = 16;
+
+/// Data port. Reading it pops the oldest scan code from the FIFO. Writing it sets the
+/// configuration byte when a `CMD_WRITE_CONFIG` command is in progress.
+const KEYBOARD_DATA: u64 = KEYBOARD_BASE;
+/// Status register (for reads) and command register (for writes).
+const KEYBOARD_STATUS: u64 = KEYBOARD_BASE + 4;
+
+/// Status bit 0: the output buffer is full, i.e. at least one scan code is ready to be read.
+pub const STATUS_OUTPUT_FULL: u8 = 1;
+/// Status bit 3: the last byte written was a command (1) or data (0).
+const STATUS_COMMAND: u8 = 1 << 3;
+/// Status bit 4: the keyboard interface is enabled.
+const STATUS_ENABLED: u8 = 1 << 4;
+
+/// Configuration bit 0: raise an interrupt when a scan code becomes available.
+pub const CONFIG_IRQ_ENABLE: u8 = 1;
+/// Configuration bit 4: the keyboard clock is disabled.
+const CONFIG_CLOCK_DISABLE: u8 = 1 << 4;
+/// Configuration bit 6: translate scan codes to set 1. Always on in this model.
+const CONFIG_TRANSLATION: u8 = 1 << 6;
+
+/// Command: read the configuration byte. The value is placed in the output buffer.
+const CMD_READ_CONFIG: u8 = 0x20;
+/// Command: write the configuration byte. The next byte written to the data port is used.
+const CMD_WRITE_CONFIG: u8 = 0x60;
+/// Command: disable the keyboard interface.
+const CMD_DISABLE: u8 = 0xad;
+/// Command: enable the keyboard interface.
+const CMD_ENABLE: u8 = 0xae;
+
+/// The scan code (set 1) reported in place of keys that are lost because the FIFO is full.
+pub const SCANCODE_OVERRUN: u8 = 0xff;
+
+/// The PS/2-style keyboard controller.
+/// 0x0 data (1 byte)
+/// 0x4 status (read) / command (write) (1 byte)
+pub struct Keyboard {
+    /// Scan codes waiting to be read by the guest.
+    fifo: VecDeque<u8>,
+    /// The controller configuration byte.
+    config: u8,
+    /// True if the last byte written to the command register was `CMD_WRITE_CONFIG` and the
+    /// controller waits for the new configuration byte on the data port.
+    awaiting_config: bool,
+    /// True if the last byte written went to the command register.
+    last_was_command: bool,
+    /// True if a scan code arrived since the last time the interrupt was checked.
+    interrupting: bool,
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keyboard {
+    /// Create a new keyboard controller object. The interface and its interrupt are enabled.
+    pub fn new() -> Self {
+        Self {
+            fifo: VecDeque::with_capacity(KEYBOARD_FIFO_SIZE),
+            config: CONFIG_IRQ_ENABLE | CONFIG_TRANSLATION,
+            awaiting_config: false,
+            last_was_command: false,
+            interrupting: false,
+        }
+    }
+
+    /// Return true if the keyboard clock is running, i.e. it accepts scan codes from the host.
+    fn is_enabled(&self) -> bool {
+        (self.config & CONFIG_CLOCK_DISABLE) == 0
+    }
+
+    /// Push a scan code from the host into the FIFO. Codes are dropped while the interface is
+    /// disabled. When the FIFO is full, the last slot is replaced with `SCANCODE_OVERRUN`.
+    pub fn push_scancode(&mut self, code: u8) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if self.fifo.len() >= KEYBOARD_FIFO_SIZE {
+            if let Some(last) = self.fifo.back_mut() {
+                *last = SCANCODE_OVERRUN;
+            }
+        } else {
+            self.fifo.push_back(code);
+        }
+
+        if (self.config & CONFIG_IRQ_ENABLE) != 0 {
+            self.interrupting = true;
+        }
+    }
+
+    /// Return the number of scan codes waiting in the FIFO.
+    pub fn pending(&self) -> usize {
+        self.fifo.len()
+    }
+
+    /// Return true if an interrupt is pending. Clear the interrupting flag by swapping a value.
+    pub fn is_interrupting(&mut self) -> bool {
+        std::mem::replace(&mut self.interrupting, false)
+    }
+
+    /// Return the value of the status register.
+    fn status(&self) -> u8 {
+        let mut status = 0;
+        if !self.fifo.is_empty() {
+            status |= STATUS_OUTPUT_FULL;
+        }
+        if self.last_was_command {
+            status |= STATUS_COMMAND;
+        }
+        if self.is_enabled() {
+            status |= STATUS_ENABLED;
+        }
+        status
+    }
+
+    /// Read a byte from the data port or the status register.
+    pub fn read(&mut self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != BYTE {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        match addr {
+            KEYBOARD_DATA => {
+                let code = self.fifo.pop_front().unwrap_or(0);
+                // Keep the interrupt line asserted while more codes are waiting, like a real
+                // controller refilling its output buffer.
+                if !self.fifo.is_empty() && (self.config & CONFIG_IRQ_ENABLE) != 0 {
+                    self.interrupting = true;
+                }
+                Ok(code as u64)
+            }
+            KEYBOARD_STATUS => Ok(self.status() as u64),
+            _ => Ok(0),
+        }
+    }
+
+    /// Write a byte to the data port or the command register.
+    pub fn write(&mut self, addr: u64, value: u8, size: u8) -> Result<(), Exception> {
+        if size != BYTE {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+
+        match addr {
+            KEYBOARD_DATA => {
+                self.last_was_command = false;
+                if self.awaiting_config {
+                    self.awaiting_config = false;
+                    // Translation is hardwired in this model.
+                    self.config = value | CONFIG_TRANSLATION;
+                }
+                // Bytes sent to the keyboard itself (LEDs, typematic rate, ...) are ignored.
+            }
+            KEYBOARD_STATUS => {
+                self.last_was_command = true;
+                match value {
+                    CMD_READ_CONFIG => self.fifo.push_front(self.config),
+                    CMD_WRITE_CONFIG => self.awaiting_config = true,
+                    CMD_DISABLE => self.config |= CONFIG_CLOCK_DISABLE,
+                    CMD_ENABLE => self.config &= !CONFIG_CLOCK_DISABLE,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
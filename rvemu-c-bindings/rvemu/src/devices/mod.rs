@@ -1,8 +1,16 @@
 //! The devices module contains peripheral devices.
 
+pub mod audio;
+pub mod channel;
 pub mod clint;
+pub mod draw_queue;
+pub mod keyboard;
 pub mod plic;
+pub mod save_file;
+pub mod vga_text;
 pub mod virtio_blk;
+pub mod vsync;
+pub mod watchdog;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod uart_cli;
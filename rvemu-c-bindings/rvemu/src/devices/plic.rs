@@ -130,27 +130,27 @@ impl Plic {
     pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
         // TODO: should support byte-base access.
         if size != WORD {
-            return Err(Exception::LoadAccessFault);
+            return Err(Exception::LoadAccessFault(addr));
         }
 
         match addr {
             SOURCE_PRIORITY..=SOURCE_PRIORITY_END => {
                 if (addr - SOURCE_PRIORITY).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::LoadAccessFault);
+                    return Err(Exception::LoadAccessFault(addr));
                 }
                 let index = (addr - SOURCE_PRIORITY).wrapping_div(WORD_SIZE);
                 Ok(self.priority[index as usize] as u64)
             }
             PENDING..=PENDING_END => {
                 if (addr - PENDING).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::LoadAccessFault);
+                    return Err(Exception::LoadAccessFault(addr));
                 }
                 let index = (addr - PENDING).wrapping_div(WORD_SIZE);
                 Ok(self.pending[index as usize] as u64)
             }
             ENABLE..=ENABLE_END => {
                 if (addr - ENABLE).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::LoadAccessFault);
+                    return Err(Exception::LoadAccessFault(addr));
                 }
                 let index = (addr - ENABLE).wrapping_div(WORD_SIZE);
                 Ok(self.enable[index as usize] as u64)
@@ -163,10 +163,10 @@ impl Plic {
                 } else if offset == 4 {
                     Ok(self.claim[context as usize] as u64)
                 } else {
-                    return Err(Exception::LoadAccessFault);
+                    return Err(Exception::LoadAccessFault(addr));
                 }
             }
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         }
     }
 
@@ -174,27 +174,27 @@ impl Plic {
     pub fn write(&mut self, addr: u64, value: u64, size: u8) -> Result<(), Exception> {
         // TODO: should support byte-base access.
         if size != WORD {
-            return Err(Exception::StoreAMOAccessFault);
+            return Err(Exception::StoreAMOAccessFault(addr));
         }
 
         match addr {
             SOURCE_PRIORITY..=SOURCE_PRIORITY_END => {
                 if (addr - SOURCE_PRIORITY).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
                 let index = (addr - SOURCE_PRIORITY).wrapping_div(WORD_SIZE);
                 self.priority[index as usize] = value as u32;
             }
             PENDING..=PENDING_END => {
                 if (addr - PENDING).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
                 let index = (addr - PENDING).wrapping_div(WORD_SIZE);
                 self.pending[index as usize] = value as u32;
             }
             ENABLE..=ENABLE_END => {
                 if (addr - ENABLE).wrapping_rem(WORD_SIZE) != 0 {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
                 let index = (addr - ENABLE).wrapping_div(WORD_SIZE);
                 self.enable[index as usize] = value as u32;
@@ -210,10 +210,10 @@ impl Plic {
                     // Clear pending bit.
                     self.clear_pending(value);
                 } else {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
             }
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
 
         Ok(())
@@ -0,0 +1,104 @@
+//! The save_file module contains a small battery-backed memory, like the save RAM of a game
+//! cartridge. The guest stores its persistent data, e.g. high scores, into it, and the host keeps
+//! the contents in the save system of the game and supplies them again on the next run.
+
+use crate::bus::SAVE_FILE_BASE;
+use crate::cpu::{BYTE, DOUBLEWORD, HALFWORD, WORD};
+use crate::exception::Exception;
+
+/// The size of the save memory in bytes.
+pub const SAVE_FILE_SIZE: u64 = 0x1000;
+
+/// The save memory.
+pub struct SaveFile {
+    /// The contents, zeroed until the host supplies saved ones.
+    data: Vec<u8>,
+    /// True if the guest wrote to the memory since the host last checked.
+    dirty: bool,
+}
+
+impl Default for SaveFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveFile {
+    /// Create a new, zeroed save memory.
+    pub fn new() -> Self {
+        Self {
+            data: vec![0; SAVE_FILE_SIZE as usize],
+            dirty: false,
+        }
+    }
+
+    /// Return the contents.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Replace the contents with `data`, zeroing the rest of the memory. Fails if `data` is
+    /// larger than the memory.
+    pub fn load(&mut self, data: &[u8]) -> Result<(), String> {
+        if data.len() as u64 > SAVE_FILE_SIZE {
+            return Err(format!(
+                "the save data of {} bytes is larger than {} bytes",
+                data.len(),
+                SAVE_FILE_SIZE
+            ));
+        }
+        self.data[..data.len()].copy_from_slice(data);
+        for byte in &mut self.data[data.len()..] {
+            *byte = 0;
+        }
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Return true if the contents changed since the last call. Clear the dirty flag by swapping
+    /// a value.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::replace(&mut self.dirty, false)
+    }
+
+    /// Return the number of bytes of a `size`-bit access, or `None` if the size is not supported.
+    fn bytes(size: u8) -> Option<usize> {
+        match size {
+            BYTE => Some(1),
+            HALFWORD => Some(2),
+            WORD => Some(4),
+            DOUBLEWORD => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Load `size`-bit data from the memory with little endian.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        let index = (addr - SAVE_FILE_BASE) as usize;
+        let bytes = match Self::bytes(size) {
+            Some(bytes) if index + bytes <= self.data.len() => bytes,
+            _ => return Err(Exception::LoadAccessFault(addr)),
+        };
+
+        let mut value = 0;
+        for i in (0..bytes).rev() {
+            value = (value << 8) | self.data[index + i] as u64;
+        }
+        Ok(value)
+    }
+
+    /// Store `size`-bit data to the memory with little endian.
+    pub fn write(&mut self, addr: u64, value: u64, size: u8) -> Result<(), Exception> {
+        let index = (addr - SAVE_FILE_BASE) as usize;
+        let bytes = match Self::bytes(size) {
+            Some(bytes) if index + bytes <= self.data.len() => bytes,
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        };
+
+        for i in 0..bytes {
+            self.data[index + i] = (value >> (i * 8)) as u8;
+        }
+        self.dirty = true;
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 //! The uart module contains the implementation of a universal asynchronous receiver-transmitter
 //! (UART) for the CLI tool. The device is 16550A UART, which is used in the QEMU virt machine.
 //! See more information in http://byterunner.com/16550.html.
+//!
+//! Besides going to stdout, the output of the guest is kept for a host embedding the emulator,
+//! which can also type input as if it came from stdin.
 
+use std::collections::VecDeque;
 use std::io;
 use std::io::prelude::*;
 use std::sync::{
@@ -47,10 +51,20 @@ const UART_LSR_RX: u8 = 1;
 /// The transmitter (TX).
 const UART_LSR_TX: u8 = 1 << 5;
 
+/// The maximum number of output bytes kept until the host takes them. The oldest bytes are
+/// dropped first.
+pub const UART_OUTPUT_SIZE: usize = 64 * 1024;
+
 /// The UART, the size of which is 0x100 (2**8).
 pub struct Uart {
     uart: Arc<(Mutex<[u8; UART_SIZE as usize]>, Condvar)>,
     interrupting: Arc<AtomicBool>,
+    /// The bytes written by the guest that the host hasn't taken yet.
+    output: VecDeque<u8>,
+    /// The number of output bytes dropped because the host didn't take them in time.
+    dropped: u64,
+    /// The bytes typed by the host that the guest hasn't received yet.
+    input: VecDeque<u8>,
 }
 
 impl Uart {
@@ -71,6 +85,9 @@ impl Uart {
         let cloned_interrupting = interrupting.clone();
         let _uart_thread_for_read = thread::spawn(move || loop {
             match io::stdin().read(&mut byte) {
+                // Stdin is closed, e.g. when embedded in a host application. Stop waiting for
+                // input instead of feeding a phantom byte and interrupt to the guest.
+                Ok(0) => break,
                 Ok(_) => {
                     let (uart, cvar) = &*cloned_uart;
                     let mut uart = uart.lock().expect("failed to get an UART object");
@@ -89,7 +106,53 @@ impl Uart {
             }
         });
 
-        Self { uart, interrupting }
+        Self {
+            uart,
+            interrupting,
+            output: VecDeque::new(),
+            dropped: 0,
+            input: VecDeque::new(),
+        }
+    }
+
+    /// Move the next byte typed by the host to the receive holding register if it's empty.
+    fn receive_input(&mut self, uart: &mut [u8; UART_SIZE as usize]) {
+        if uart[(UART_LSR - UART_BASE) as usize] & UART_LSR_RX != 0 {
+            return;
+        }
+        if let Some(byte) = self.input.pop_front() {
+            uart[(UART_RHR - UART_BASE) as usize] = byte;
+            uart[(UART_LSR - UART_BASE) as usize] |= UART_LSR_RX;
+            self.interrupting.store(true, Ordering::Release);
+        }
+    }
+
+    /// Queue `bytes` for the guest to receive, one at a time, as if they were typed on stdin.
+    pub fn push_input(&mut self, bytes: &[u8]) {
+        self.input.extend(bytes);
+        let shared = self.uart.clone();
+        let mut uart = shared.0.lock().expect("failed to get an UART object");
+        self.receive_input(&mut uart);
+    }
+
+    /// Return the number of bytes typed by the host that the guest hasn't received yet.
+    pub fn pending_input(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Move up to `out.len()` bytes written by the guest into `out`, oldest first. Returns the
+    /// number of bytes moved.
+    pub fn take_output(&mut self, out: &mut [u8]) -> usize {
+        let len = out.len().min(self.output.len());
+        for (dst, src) in out.iter_mut().zip(self.output.drain(..len)) {
+            *dst = src;
+        }
+        len
+    }
+
+    /// Return the number of output bytes dropped because the host didn't take them in time.
+    pub fn dropped_output(&self) -> u64 {
+        self.dropped
     }
 
     /// Return true if an interrupt is pending. Clear the interrupting flag by swapping a value.
@@ -100,16 +163,19 @@ impl Uart {
     /// Read a byte from the receive holding register.
     pub fn read(&mut self, index: u64, size: u8) -> Result<u64, Exception> {
         if size != BYTE {
-            return Err(Exception::LoadAccessFault);
+            return Err(Exception::LoadAccessFault(index));
         }
 
-        let (uart, cvar) = &*self.uart;
+        let shared = self.uart.clone();
+        let (uart, cvar) = &*shared;
         let mut uart = uart.lock().expect("failed to get an UART object");
         match index {
             UART_RHR => {
                 cvar.notify_one();
                 uart[(UART_LSR - UART_BASE) as usize] &= !UART_LSR_RX;
-                Ok(uart[(UART_RHR - UART_BASE) as usize] as u64)
+                let byte = uart[(UART_RHR - UART_BASE) as usize];
+                self.receive_input(&mut uart);
+                Ok(byte as u64)
             }
             _ => Ok(uart[(index - UART_BASE) as usize] as u64),
         }
@@ -118,7 +184,7 @@ impl Uart {
     /// Write a byte to the transmit holding register.
     pub fn write(&mut self, index: u64, value: u8, size: u8) -> Result<(), Exception> {
         if size != BYTE {
-            return Err(Exception::StoreAMOAccessFault);
+            return Err(Exception::StoreAMOAccessFault(index));
         }
 
         // An OS allows to write a byte to a UART when UART_LSR_TX is 1.
@@ -137,6 +203,11 @@ impl Uart {
             UART_THR => {
                 print!("{}", value as char);
                 io::stdout().flush().expect("failed to flush stdout");
+                if self.output.len() == UART_OUTPUT_SIZE {
+                    self.output.pop_front();
+                    self.dropped += 1;
+                }
+                self.output.push_back(value);
             }
             _ => {
                 uart[(index - UART_BASE) as usize] = value;
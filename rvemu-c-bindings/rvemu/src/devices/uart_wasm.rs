@@ -110,7 +110,7 @@ impl Uart {
     /// Read a byte from the receive holding register.
     pub fn read(&mut self, index: u64, size: u8) -> Result<u64, Exception> {
         if size != BYTE {
-            return Err(Exception::LoadAccessFault);
+            return Err(Exception::LoadAccessFault(index));
         }
 
         match index {
@@ -125,7 +125,7 @@ impl Uart {
     /// Write a byte to the transmit holding register.
     pub fn write(&mut self, index: u64, value: u8, size: u8) -> Result<(), Exception> {
         if size != BYTE {
-            return Err(Exception::StoreAMOAccessFault);
+            return Err(Exception::StoreAMOAccessFault(index));
         }
 
         match index {
@@ -0,0 +1,103 @@
+//! The vga_text module contains a VGA-like text-mode display. The screen is a grid of 80x25 cells
+//! and each cell is 2 bytes: the character code followed by its attribute byte. The guest prints
+//! to the screen by storing bytes into the buffer, and the host reads the whole buffer to render
+//! it.
+
+// Reference:
+// "Text UI" on OSDev Wiki
+// https://wiki.osdev.org/Text_UI
+
+use crate::bus::VGA_TEXT_BASE;
+use crate::cpu::{BYTE, DOUBLEWORD, HALFWORD, WORD};
+use crate::exception::Exception;
+
+/// The number of columns on the screen.
+pub const VGA_TEXT_COLUMNS: usize = 80;
+/// The number of rows on the screen.
+pub const VGA_TEXT_ROWS: usize = 25;
+/// The size of the character buffer in bytes. Each cell holds a character and an attribute.
+pub const VGA_TEXT_SIZE: u64 = (VGA_TEXT_COLUMNS * VGA_TEXT_ROWS * 2) as u64;
+
+/// The attribute of a blank cell: light grey on black.
+pub const DEFAULT_ATTRIBUTE: u8 = 0x07;
+
+/// The text-mode display.
+pub struct VgaText {
+    /// The character and attribute bytes of every cell, row by row.
+    buffer: Vec<u8>,
+    /// True if the guest wrote to the buffer since the host last read it.
+    dirty: bool,
+}
+
+impl Default for VgaText {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VgaText {
+    /// Create a new text-mode display filled with blank cells.
+    pub fn new() -> Self {
+        let mut buffer = vec![0; VGA_TEXT_SIZE as usize];
+        for cell in buffer.chunks_mut(2) {
+            cell[0] = b' ';
+            cell[1] = DEFAULT_ATTRIBUTE;
+        }
+        Self {
+            buffer,
+            dirty: true,
+        }
+    }
+
+    /// Return the raw character buffer.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Return true if the buffer changed since the last call. Clear the dirty flag by swapping a
+    /// value.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::replace(&mut self.dirty, false)
+    }
+
+    /// Return the number of bytes of a `size`-bit access, or `None` if the size is not supported.
+    fn bytes(size: u8) -> Option<usize> {
+        match size {
+            BYTE => Some(1),
+            HALFWORD => Some(2),
+            WORD => Some(4),
+            DOUBLEWORD => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Load `size`-bit data from the buffer with little endian.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        let index = (addr - VGA_TEXT_BASE) as usize;
+        let bytes = match Self::bytes(size) {
+            Some(bytes) if index + bytes <= self.buffer.len() => bytes,
+            _ => return Err(Exception::LoadAccessFault(addr)),
+        };
+
+        let mut value = 0;
+        for i in (0..bytes).rev() {
+            value = (value << 8) | self.buffer[index + i] as u64;
+        }
+        Ok(value)
+    }
+
+    /// Store `size`-bit data to the buffer with little endian.
+    pub fn write(&mut self, addr: u64, value: u64, size: u8) -> Result<(), Exception> {
+        let index = (addr - VGA_TEXT_BASE) as usize;
+        let bytes = match Self::bytes(size) {
+            Some(bytes) if index + bytes <= self.buffer.len() => bytes,
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        };
+
+        for i in 0..bytes {
+            self.buffer[index + i] = (value >> (i * 8)) as u8;
+        }
+        self.dirty = true;
+        Ok(())
+    }
+}
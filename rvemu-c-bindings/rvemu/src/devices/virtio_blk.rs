@@ -390,19 +390,19 @@ impl Virtio {
             STATUS..=STATUS_END => (self.status, addr - STATUS),
             CONFIG..=CONFIG_END => {
                 if size != BYTE {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
                 let index = addr - CONFIG;
                 (self.config[index as usize] as u32, 0)
             }
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         };
 
         let value = match size {
             BYTE => (reg >> (offset * 8)) & 0xff,
             HALFWORD => (reg >> (offset * 8)) & 0xffff,
             WORD => (reg >> (offset * 8)) & 0xffffffff,
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         };
 
         Ok(value as u64)
@@ -448,13 +448,13 @@ impl Virtio {
             STATUS..=STATUS_END => (self.status, addr - STATUS),
             CONFIG..=CONFIG_END => {
                 if size != BYTE {
-                    return Err(Exception::StoreAMOAccessFault);
+                    return Err(Exception::StoreAMOAccessFault(addr));
                 }
                 let index = addr - CONFIG;
                 self.config[index as usize] = (value >> (index * 8)) as u8;
                 return Ok(());
             }
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         };
 
         // Calculate the new value of the target register based on `size` and `offset`.
@@ -472,7 +472,7 @@ impl Virtio {
             WORD => {
                 reg = value;
             }
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
 
         // Store the new register value to the target register.
@@ -503,7 +503,7 @@ impl Virtio {
                     panic!("virtio: device status FAILED");
                 }
             }
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
 
         Ok(())
@@ -0,0 +1,77 @@
+//! The vsync module contains a frame synchronization device. The guest game loop tells the host
+//! it finished a frame, and the run returns to the host until the next frame instead of spinning
+//! through its instruction budget.
+
+use crate::bus::VSYNC_BASE;
+use crate::cpu::WORD;
+use crate::exception::Exception;
+
+/// Present register (write-only). Writing any value ends the current frame.
+const VSYNC_PRESENT: u64 = VSYNC_BASE;
+/// Frame counter register (read-only). The number of frames presented since creation.
+const VSYNC_FRAMES: u64 = VSYNC_BASE + 0x4;
+
+/// The frame synchronization device.
+/// 0x00 present (4 bytes)
+/// 0x04 frame counter (4 bytes)
+pub struct Vsync {
+    frames: u32,
+    /// True if the guest presented a frame the run hasn't stopped for yet.
+    presented: bool,
+}
+
+impl Default for Vsync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vsync {
+    /// Create a new vsync object.
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            presented: false,
+        }
+    }
+
+    /// Return the number of frames presented since creation.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Return true if the guest presented a frame since the last check.
+    pub fn take_presented(&mut self) -> bool {
+        std::mem::replace(&mut self.presented, false)
+    }
+
+    /// Load a 32-bit register located at `addr` in the vsync device.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != WORD {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        match addr {
+            VSYNC_PRESENT => Ok(0),
+            VSYNC_FRAMES => Ok(self.frames as u64),
+            _ => Err(Exception::LoadAccessFault(addr)),
+        }
+    }
+
+    /// Store a 32-bit register located at `addr` in the vsync device.
+    pub fn write(&mut self, addr: u64, size: u8) -> Result<(), Exception> {
+        if size != WORD {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+
+        match addr {
+            VSYNC_PRESENT => {
+                self.frames = self.frames.wrapping_add(1);
+                self.presented = true;
+            }
+            VSYNC_FRAMES => {}
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        }
+        Ok(())
+    }
+}
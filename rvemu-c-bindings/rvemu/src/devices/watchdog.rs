@@ -0,0 +1,183 @@
+//! The watchdog module contains a watchdog timer. Once enabled, the guest must periodically
+//! service ("kick") it by writing a key to the kick register. If the counter runs out first, the
+//! watchdog either raises an external interrupt or requests a reset of the core.
+
+use crate::bus::WATCHDOG_BASE;
+use crate::cpu::WORD;
+use crate::exception::Exception;
+
+/// The interrupt request of the watchdog.
+pub const WATCHDOG_IRQ: u64 = 12;
+
+/// Control register. Bit 0 enables the watchdog, bit 1 selects the expiry action.
+const WATCHDOG_CTRL: u64 = WATCHDOG_BASE;
+/// Timeout register. The number of cycles the guest has to kick the watchdog.
+const WATCHDOG_TIMEOUT: u64 = WATCHDOG_BASE + 0x4;
+/// Kick register (write-only). Writing `WATCHDOG_KICK_KEY` reloads the counter.
+const WATCHDOG_KICK: u64 = WATCHDOG_BASE + 0x8;
+/// Counter register (read-only). The number of cycles left before expiry.
+const WATCHDOG_COUNT: u64 = WATCHDOG_BASE + 0xc;
+/// Status register. Bit 0 is set when the watchdog expired in the interrupt mode. Write 1 to
+/// clear it.
+const WATCHDOG_STATUS: u64 = WATCHDOG_BASE + 0x10;
+
+/// Control bit 0: the watchdog is counting.
+pub const CTRL_ENABLE: u32 = 1;
+/// Control bit 1: reset the core on expiry instead of raising an interrupt.
+pub const CTRL_RESET: u32 = 1 << 1;
+/// Status bit 0: the watchdog expired.
+pub const STATUS_EXPIRED: u32 = 1;
+
+/// The value the guest must write to the kick register to service the watchdog. Other values are
+/// ignored, so a runaway store doesn't keep the watchdog alive by accident.
+pub const WATCHDOG_KICK_KEY: u32 = 0x0d06_f00d;
+
+/// The action the watchdog takes when it expires.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WatchdogAction {
+    /// Raise an external interrupt and reload the counter.
+    Interrupt,
+    /// Reset the core.
+    Reset,
+}
+
+/// The watchdog timer.
+/// 0x00 control (4 bytes)
+/// 0x04 timeout (4 bytes)
+/// 0x08 kick (4 bytes)
+/// 0x0c counter (4 bytes)
+/// 0x10 status (4 bytes)
+#[derive(Clone)]
+pub struct Watchdog {
+    ctrl: u32,
+    timeout: u32,
+    count: u32,
+    status: u32,
+    /// The number of times the watchdog expired since creation.
+    expirations: u64,
+    /// True if the watchdog expired in the interrupt mode since the last check.
+    interrupting: bool,
+    /// True if the watchdog expired in the reset mode and the core hasn't been reset yet.
+    reset_requested: bool,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Watchdog {
+    /// Create a new watchdog object. It's disabled until the guest or the host enables it.
+    pub fn new() -> Self {
+        Self {
+            ctrl: 0,
+            timeout: 0,
+            count: 0,
+            status: 0,
+            expirations: 0,
+            interrupting: false,
+            reset_requested: false,
+        }
+    }
+
+    /// Enable the watchdog with `timeout` cycles and the given expiry action.
+    pub fn configure(&mut self, timeout: u32, action: WatchdogAction) {
+        self.timeout = timeout;
+        self.count = timeout;
+        self.ctrl = match action {
+            WatchdogAction::Interrupt => CTRL_ENABLE,
+            WatchdogAction::Reset => CTRL_ENABLE | CTRL_RESET,
+        };
+    }
+
+    /// Disable the watchdog and clear its state, e.g. after it reset the core.
+    pub fn disable(&mut self) {
+        self.ctrl = 0;
+        self.count = 0;
+        self.status = 0;
+        self.interrupting = false;
+        self.reset_requested = false;
+    }
+
+    /// Return the number of times the watchdog expired.
+    pub fn expirations(&self) -> u64 {
+        self.expirations
+    }
+
+    /// Count down one cycle and take the expiry action when the counter runs out, so a timeout
+    /// of N cycles expires on the Nth one.
+    pub fn increment(&mut self) {
+        if (self.ctrl & CTRL_ENABLE) == 0 {
+            return;
+        }
+
+        self.count = self.count.saturating_sub(1);
+        if self.count > 0 {
+            return;
+        }
+
+        self.expirations += 1;
+        if (self.ctrl & CTRL_RESET) != 0 {
+            self.reset_requested = true;
+        } else {
+            self.status |= STATUS_EXPIRED;
+            self.interrupting = true;
+            self.count = self.timeout;
+        }
+    }
+
+    /// Return true if an interrupt is pending. Clear the interrupting flag by swapping a value.
+    pub fn is_interrupting(&mut self) -> bool {
+        std::mem::replace(&mut self.interrupting, false)
+    }
+
+    /// Return true if the watchdog requested a reset of the core. Clear the request by swapping
+    /// a value.
+    pub fn take_reset_request(&mut self) -> bool {
+        std::mem::replace(&mut self.reset_requested, false)
+    }
+
+    /// Load a 32-bit register located at `addr` in the watchdog.
+    pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
+        if size != WORD {
+            return Err(Exception::LoadAccessFault(addr));
+        }
+
+        match addr {
+            WATCHDOG_CTRL => Ok(self.ctrl as u64),
+            WATCHDOG_TIMEOUT => Ok(self.timeout as u64),
+            WATCHDOG_KICK => Ok(0),
+            WATCHDOG_COUNT => Ok(self.count as u64),
+            WATCHDOG_STATUS => Ok(self.status as u64),
+            _ => Err(Exception::LoadAccessFault(addr)),
+        }
+    }
+
+    /// Store a 32-bit register located at `addr` in the watchdog.
+    pub fn write(&mut self, addr: u64, value: u32, size: u8) -> Result<(), Exception> {
+        if size != WORD {
+            return Err(Exception::StoreAMOAccessFault(addr));
+        }
+
+        match addr {
+            WATCHDOG_CTRL => {
+                // Reload the counter when the watchdog is being enabled.
+                if (self.ctrl & CTRL_ENABLE) == 0 && (value & CTRL_ENABLE) != 0 {
+                    self.count = self.timeout;
+                }
+                self.ctrl = value & (CTRL_ENABLE | CTRL_RESET);
+            }
+            WATCHDOG_TIMEOUT => self.timeout = value,
+            WATCHDOG_KICK => {
+                if value == WATCHDOG_KICK_KEY {
+                    self.count = self.timeout;
+                }
+            }
+            WATCHDOG_COUNT => {}
+            WATCHDOG_STATUS => self.status &= !value,
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
+        }
+        Ok(())
+    }
+}
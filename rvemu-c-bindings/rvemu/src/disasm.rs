@@ -0,0 +1,119 @@
+//! The disasm module turns instruction words back into assembly, with ABI register names,
+//! sign-extended immediates and resolved branch and jump targets.
+
+use crate::aliases::RegisterAliases;
+use crate::cpu::ABI_NAMES;
+use crate::csr_names::CsrNames;
+use crate::isa::mnemonic;
+
+/// Return the sign-extended immediate of an I-type instruction.
+pub(crate) fn imm_i(inst: u64) -> i64 {
+    ((inst as i32) >> 20) as i64
+}
+
+/// Return the sign-extended immediate of an S-type instruction.
+fn imm_s(inst: u64) -> i64 {
+    ((((inst & 0xfe00_0000) as i32) >> 20) as i64) | (((inst >> 7) & 0x1f) as i64)
+}
+
+/// Return the sign-extended offset of a B-type instruction.
+pub(crate) fn imm_b(inst: u64) -> i64 {
+    ((((inst & 0x8000_0000) as i32) >> 19) as i64)
+        | (((inst & 0x80) << 4) as i64)
+        | (((inst >> 20) & 0x7e0) as i64)
+        | (((inst >> 7) & 0x1e) as i64)
+}
+
+/// Return the sign-extended offset of a J-type instruction.
+pub(crate) fn imm_j(inst: u64) -> i64 {
+    ((((inst & 0x8000_0000) as i32) >> 11) as i64)
+        | ((inst & 0xff000) as i64)
+        | (((inst >> 9) & 0x800) as i64)
+        | (((inst >> 20) & 0x7fe) as i64)
+}
+
+/// Return the disassembly of the instruction word `inst` at `pc`. Instructions without a known
+/// mnemonic, including compressed ones, are shown as `unknown` with their encoding. CSRs are
+/// shown as addresses.
+pub fn disassemble(inst: u64, pc: u64) -> String {
+    format_instruction(inst, pc, None, None)
+}
+
+/// Same as `disassemble`, but CSRs known to `names` are shown by name, as the assembler accepts
+/// them.
+pub fn disassemble_with_csr_names(inst: u64, pc: u64, names: &CsrNames) -> String {
+    format_instruction(inst, pc, Some(names), None)
+}
+
+/// Same as `disassemble_with_csr_names`, but registers with aliases in `aliases` are shown by the
+/// first of them in alphabetical order, as the assembler accepts them too.
+pub fn disassemble_with_names(
+    inst: u64,
+    pc: u64,
+    names: &CsrNames,
+    aliases: &RegisterAliases,
+) -> String {
+    format_instruction(inst, pc, Some(names), Some(aliases))
+}
+
+/// Return the disassembly of `inst` at `pc`, with the CSR names of `names` and the register
+/// aliases of `aliases` if any.
+fn format_instruction(
+    inst: u64,
+    pc: u64,
+    names: Option<&CsrNames>,
+    aliases: Option<&RegisterAliases>,
+) -> String {
+    let name = match mnemonic(inst) {
+        Some(name) => name,
+        None => return format!("unknown {:#010x}", inst),
+    };
+    // Return the name of the register at bits `[shift, shift + 5)` of `inst`.
+    let reg = |shift: u64| {
+        let index = (inst >> shift) & 0x1f;
+        aliases
+            .and_then(|aliases| aliases.aliases_of(index).next())
+            .unwrap_or(ABI_NAMES[index as usize])
+    };
+    let rd = reg(7);
+    let rs1 = reg(15);
+    let rs2 = reg(20);
+
+    match inst & 0x7f {
+        0x37 | 0x17 => format!("{} {}, {:#x}", name, rd, (inst >> 12) & 0xfffff),
+        0x6f => format!(
+            "{} {}, {:#x}",
+            name,
+            rd,
+            pc.wrapping_add(imm_j(inst) as u64)
+        ),
+        0x67 | 0x03 => format!("{} {}, {}({})", name, rd, imm_i(inst), rs1),
+        0x63 => format!(
+            "{} {}, {}, {:#x}",
+            name,
+            rs1,
+            rs2,
+            pc.wrapping_add(imm_b(inst) as u64)
+        ),
+        0x23 => format!("{} {}, {}({})", name, rs2, imm_s(inst), rs1),
+        0x13 | 0x1b => match (inst >> 12) & 0x7 {
+            // Shifts take a shift amount instead of an immediate.
+            1 | 5 => format!("{} {}, {}, {}", name, rd, rs1, (inst >> 20) & 0x3f),
+            _ => format!("{} {}, {}, {}", name, rd, rs1, imm_i(inst)),
+        },
+        0x33 | 0x3b => format!("{} {}, {}, {}", name, rd, rs1, rs2),
+        0x73 => {
+            let addr = (inst >> 20) & 0xfff;
+            let csr = names
+                .and_then(|names| names.name(addr as u16))
+                .unwrap_or_else(|| format!("{:#x}", addr));
+            match (inst >> 12) & 0x7 {
+                0 if name == "sfence.vma" => format!("{} {}, {}", name, rs1, rs2),
+                0 => name.to_string(),
+                1..=3 => format!("{} {}, {}, {}", name, rd, csr, rs1),
+                _ => format!("{} {}, {}, {}", name, rd, csr, (inst >> 15) & 0x1f),
+            }
+        }
+        _ => name.to_string(),
+    }
+}
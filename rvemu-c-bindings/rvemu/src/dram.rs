@@ -1,5 +1,9 @@
 //! The memory module contains the memory structure and implementation to read/write the memory.
 
+use std::alloc::{self, Layout};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use crate::bus::DRAM_BASE;
 use crate::cpu::{BYTE, DOUBLEWORD, HALFWORD, WORD};
 use crate::exception::Exception;
@@ -7,28 +11,157 @@ use crate::exception::Exception;
 /// Default memory size (1GiB).
 pub const DRAM_SIZE: u64 = 1024 * 1024 * 1024;
 
+/// The bytes of DRAM allocated by every emulator of the process.
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+/// Return the bytes of DRAM allocated by every emulator of the process, the bulk of their memory
+/// usage.
+pub fn total_allocated() -> u64 {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// The size of the pages the journal saves before they are first written.
+const JOURNAL_PAGE_SIZE: usize = 4096;
+
+/// The byte order of a multi-byte value in memory. The guest is always little-endian; big-endian
+/// accessors are for hosts that exchange values in big-endian formats, e.g. network packets.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    /// Return the `size` low bytes of `value` in this order.
+    pub fn to_bytes(self, value: u64, size: usize) -> Vec<u8> {
+        let mut bytes = value.to_le_bytes()[..size].to_vec();
+        if self == ByteOrder::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    /// Return the value of `bytes`, at most 8, in this order.
+    pub fn from_bytes(self, bytes: &[u8]) -> u64 {
+        let mut le = [0; 8];
+        le[..bytes.len()].copy_from_slice(bytes);
+        if self == ByteOrder::Big {
+            le[..bytes.len()].reverse();
+        }
+        u64::from_le_bytes(le)
+    }
+}
+
 /// The memory used by the emulator.
 #[derive(Debug)]
 pub struct Dram {
     pub dram: Vec<u8>,
     code_size: u64,
+    /// The original contents of the pages written since the journal started, by page index.
+    journal: Option<HashMap<usize, Vec<u8>>>,
+    /// One past the highest byte written, so the memory from there on is still zero. Writes to
+    /// the `dram` field itself aren't counted.
+    written_len: usize,
 }
 
 impl Dram {
-    /// Create a new memory object with default memory size.
+    /// Create a new memory object with default memory size. Panics if it can't be allocated.
     pub fn new() -> Self {
-        Self {
-            dram: vec![0; DRAM_SIZE as usize],
+        Self::try_new().unwrap_or_else(|message| panic!("{}", message))
+    }
+
+    /// Create a new memory object with default memory size, or fail if the host has no memory
+    /// for it, instead of aborting the process. The memory is zeroed lazily by the host, so it
+    /// only takes up room once it's written.
+    pub fn try_new() -> Result<Self, String> {
+        let size = DRAM_SIZE as usize;
+        let layout = Layout::array::<u8>(size).expect("DRAM_SIZE fits in a layout");
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return Err(format!(
+                "allocation failed: no memory for the {} bytes of DRAM",
+                size
+            ));
+        }
+        ALLOCATED.fetch_add(DRAM_SIZE, Ordering::Relaxed);
+        Ok(Self {
+            // The layout is the one `Vec<u8>` allocates with for this capacity.
+            dram: unsafe { Vec::from_raw_parts(ptr, size, size) },
             code_size: 0,
+            journal: None,
+            written_len: 0,
+        })
+    }
+
+    /// Start saving the pages before they are first written, so `roll_back` can undo the writes.
+    pub(crate) fn start_journal(&mut self) {
+        self.journal = Some(HashMap::new());
+    }
+
+    /// Undo the writes since `start_journal` and stop saving pages.
+    pub(crate) fn roll_back(&mut self) {
+        for (page, bytes) in self.journal.take().unwrap_or_default() {
+            let start = page * JOURNAL_PAGE_SIZE;
+            self.dram[start..start + JOURNAL_PAGE_SIZE].copy_from_slice(&bytes);
+        }
+    }
+
+    /// Return the bytes of the pages saved in the journal.
+    pub(crate) fn journal_size(&self) -> u64 {
+        self.journal.as_ref().map_or(0, |journal| (journal.len() * JOURNAL_PAGE_SIZE) as u64)
+    }
+
+    /// Return the length of the memory up to the highest byte written. The rest is zero.
+    pub(crate) fn written_len(&self) -> usize {
+        self.written_len
+    }
+
+    /// Return the first `len` bytes of the memory for writing. They count as written, but aren't
+    /// saved in the journal.
+    pub(crate) fn written_mut(&mut self, len: usize) -> &mut [u8] {
+        self.written_len = self.written_len.max(len);
+        &mut self.dram[..len]
+    }
+
+    /// Count the `len` bytes at `index` as written, and save their pages in the journal if it's
+    /// started and they aren't saved yet.
+    fn save_pages(&mut self, index: usize, len: usize) {
+        self.written_len = self.written_len.max(index + len);
+        let journal = match self.journal.as_mut() {
+            Some(journal) => journal,
+            None => return,
+        };
+        for page in index / JOURNAL_PAGE_SIZE..=(index + len - 1) / JOURNAL_PAGE_SIZE {
+            let start = page * JOURNAL_PAGE_SIZE;
+            let dram = &self.dram;
+            journal
+                .entry(page)
+                .or_insert_with(|| dram[start..start + JOURNAL_PAGE_SIZE].to_vec());
         }
     }
 
     /// Set the binary in the memory.
     pub fn initialize(&mut self, binary: Vec<u8>) {
         self.code_size = binary.len() as u64;
+        self.written_len = self.written_len.max(binary.len());
         self.dram.splice(..binary.len(), binary.iter().cloned());
     }
 
+    /// Copy `data` to the memory at `addr`. It must fit in the memory.
+    pub fn write_bytes(&mut self, addr: u64, data: &[u8]) {
+        let index = (addr - DRAM_BASE) as usize;
+        if !data.is_empty() {
+            self.save_pages(index, data.len());
+        }
+        self.dram[index..index + data.len()].copy_from_slice(data);
+    }
+
+    /// Return the `len` bytes of the memory at `addr`. They must be in the memory.
+    pub fn read_bytes(&self, addr: u64, len: u64) -> &[u8] {
+        let index = (addr - DRAM_BASE) as usize;
+        &self.dram[index..index + len as usize]
+    }
+
     /// Load `size`-bit data from the memory.
     pub fn read(&self, addr: u64, size: u8) -> Result<u64, Exception> {
         match size {
@@ -36,7 +169,7 @@ impl Dram {
             HALFWORD => Ok(self.read16(addr)),
             WORD => Ok(self.read32(addr)),
             DOUBLEWORD => Ok(self.read64(addr)),
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         }
     }
 
@@ -47,7 +180,7 @@ impl Dram {
             HALFWORD => self.write16(addr, value),
             WORD => self.write32(addr, value),
             DOUBLEWORD => self.write64(addr, value),
-            _ => return Err(Exception::StoreAMOAccessFault),
+            _ => return Err(Exception::StoreAMOAccessFault(addr)),
         }
         Ok(())
     }
@@ -55,12 +188,14 @@ impl Dram {
     /// Write a byte to the memory.
     fn write8(&mut self, addr: u64, val: u64) {
         let index = (addr - DRAM_BASE) as usize;
+        self.save_pages(index, 1);
         self.dram[index] = val as u8
     }
 
     /// Write 2 bytes to the memory with little endian.
     fn write16(&mut self, addr: u64, val: u64) {
         let index = (addr - DRAM_BASE) as usize;
+        self.save_pages(index, 2);
         self.dram[index] = (val & 0xff) as u8;
         self.dram[index + 1] = ((val >> 8) & 0xff) as u8;
     }
@@ -68,6 +203,7 @@ impl Dram {
     /// Write 4 bytes to the memory with little endian.
     fn write32(&mut self, addr: u64, val: u64) {
         let index = (addr - DRAM_BASE) as usize;
+        self.save_pages(index, 4);
         self.dram[index] = (val & 0xff) as u8;
         self.dram[index + 1] = ((val >> 8) & 0xff) as u8;
         self.dram[index + 2] = ((val >> 16) & 0xff) as u8;
@@ -77,6 +213,7 @@ impl Dram {
     /// Write 8 bytes to the memory with little endian.
     fn write64(&mut self, addr: u64, val: u64) {
         let index = (addr - DRAM_BASE) as usize;
+        self.save_pages(index, 8);
         self.dram[index] = (val & 0xff) as u8;
         self.dram[index + 1] = ((val >> 8) & 0xff) as u8;
         self.dram[index + 2] = ((val >> 16) & 0xff) as u8;
@@ -121,3 +258,9 @@ impl Dram {
             | ((self.dram[index + 7] as u64) << 56);
     }
 }
+
+impl Drop for Dram {
+    fn drop(&mut self) {
+        ALLOCATED.fetch_sub(DRAM_SIZE, Ordering::Relaxed);
+    }
+}
@@ -1,7 +1,38 @@
 //! The emulator module represents an entire computer.
 
-use crate::cpu::Cpu;
-use crate::exception::Trap;
+use std::mem;
+use std::time::Instant;
+
+use crate::aliases::RegisterAliases;
+use crate::atomics::{AtomicEvent, AtomicEvents, AtomicOp};
+use crate::breakpoint::Breakpoints;
+use crate::bus::{Bus, DRAM_BASE, DRAM_END};
+use crate::cpu::{Cpu, Mode, XRegisters};
+use crate::csr::{MIE, TIME};
+use crate::csr_names::CsrNames;
+use crate::csr_trace::{CsrTrace, CsrWrite};
+use crate::debug_info::DebugInfo;
+use crate::debug_print::{DebugPrint, DebugPrintKind, DebugPrints, MAX_DEBUG_TEXT};
+use crate::delta::{changes, StepDelta};
+use crate::dram::{ByteOrder, Dram};
+use crate::exception::{Exception, Trap, TrapInfo};
+use crate::explain::{explain_locked_instruction, explain_locked_register, explain_trap};
+use crate::guest_assert::{AssertionFailure, GuestAsserts, SYS_ASSERT};
+use crate::history::{self, EvictionCallback, History};
+use crate::injection::{self, Injections};
+use crate::isa;
+use crate::metrics;
+use crate::profile::Profile;
+use crate::programs::Programs;
+use crate::run::{
+    self, CancelToken, EbreakBehavior, EcallAction, EcallArgs, EcallHandler, ProgressCallback, RunLimits,
+    RunSummary, StepInterrupts, StopReason, CHECK_INTERVAL, DEFAULT_CLOCK_HZ, SYS_EXIT,
+};
+use crate::semihosting::{self, Semihosting, SemihostingAction, MAX_SEMIHOSTING_TEXT};
+use crate::trace::{AccessKind, TraceBuffer, DEFAULT_TRACE_CAPACITY};
+use crate::trap_return::{ReturnInstruction, TrapReturn, TrapReturns};
+use crate::watchpoint::WatchHit;
+use crate::xlen::Xlen;
 
 /// The emulator to hold a CPU.
 pub struct Emulator {
@@ -9,14 +40,153 @@ pub struct Emulator {
     pub cpu: Cpu,
     /// The debug flag. Output messages if it's true, otherwise output nothing.
     pub is_debug: bool,
+    /// The entry point of the loaded program. The program counter is set to it when the core is
+    /// reset by the watchdog.
+    pub entry: u64,
+    /// The symbol table and the line table of the loaded program, if the host provided them.
+    pub debug_info: DebugInfo,
+    /// The program loaded at the start of DRAM by `initialize_dram`, its text, kept so a restart
+    /// can restore it.
+    pub image: Vec<u8>,
+    /// The friendly register names of the current game level.
+    pub register_aliases: RegisterAliases,
+    /// The CSR names the assembler accepts, including those of the current game level.
+    pub csr_names: CsrNames,
+    /// The programs loaded side by side by `load_program_at`, with their own debug info.
+    pub programs: Programs,
+    /// The last exception returned by `step`.
+    pub last_trap: Option<TrapInfo>,
+    /// The instruction addresses runs stop at.
+    pub breakpoints: Breakpoints,
+    /// The instructions temporarily replaced, e.g. by an `ebreak`, with their original bytes.
+    pub injections: Injections,
+    /// The store that stopped the last run at a watchpoint.
+    pub last_watch_hit: Option<WatchHit>,
+    /// The architectural effect of the last `step_delta`.
+    pub last_delta: Option<StepDelta>,
+    /// The most recently executed instructions.
+    pub trace: TraceBuffer,
+    /// The log of executed atomic instructions.
+    pub atomic_events: AtomicEvents,
+    /// The log of CSR writes.
+    pub csr_trace: CsrTrace,
+    /// The prints of the guest through the debug print environment calls.
+    pub debug_prints: DebugPrints,
+    /// The results of the asserts of the guest.
+    pub guest_asserts: GuestAsserts,
+    /// The console of the semihosting calls of the guest.
+    pub semihosting: Semihosting,
+    /// The log of executed privilege-return instructions.
+    pub trap_returns: TrapReturns,
+    /// The last privilege-return instruction seen while `trap_returns` was enabled or
+    /// `run_until_trap_return` was running.
+    pub last_trap_return: Option<TrapReturn>,
+    /// The number of instructions retired by `step` since creation.
+    pub retired: u64,
+    /// The nominal clock frequency of the core in hertz, which turns the retired instructions of
+    /// a run into simulated time.
+    clock_hz: u64,
+    /// The number of interrupts taken by `step` since creation.
+    pub interrupts: u64,
+    /// The token the host uses to cancel a run, possibly from another thread.
+    pub cancel_token: CancelToken,
+    /// Whether a run of one step takes a pending interrupt.
+    pub step_interrupts: StepInterrupts,
+    /// The rewind checkpoints and the storage budget they share with the traces.
+    pub history: History,
+    /// The callback told of the evictions done to fit in the budget of `history`.
+    pub(crate) eviction_callback: Option<EvictionCallback>,
+    /// The most steps any run executes, whatever its step limit, if set, e.g. so a program
+    /// stuck in an infinite loop can't hang the frame of the host.
+    pub fuel: Option<u64>,
+    /// The callback reporting the progress of a run, and how often it's called in steps.
+    progress: Option<(u64, ProgressCallback)>,
+    /// The handler serving the environment calls of the guest during a run.
+    pub(crate) ecall_handler: Option<EcallHandler>,
+    /// What a run does when the guest executes `ebreak`.
+    pub(crate) ebreak_behavior: EbreakBehavior,
+    /// The preset applied by `set_profile`. It's applied again when the core is reset.
+    profile: Profile,
+    /// Whether the current run stops after a privilege-return instruction.
+    stop_at_trap_return: bool,
+    /// Whether the last step executed a privilege-return instruction, if they are watched.
+    trap_returned: bool,
 }
 
 impl Emulator {
     /// Constructor for an emulator.
     pub fn new() -> Emulator {
+        Self::with_cpu(Cpu::new())
+    }
+
+    /// Create an emulator as `new` does, or fail if the host has no memory for its DRAM, e.g.
+    /// because too many emulators are alive, instead of aborting the process.
+    pub fn try_new() -> Result<Emulator, String> {
+        let bus = Bus::with_dram(Dram::try_new()?);
+        Ok(Self::with_cpu(Cpu::with_bus(bus)))
+    }
+
+    fn with_cpu(cpu: Cpu) -> Emulator {
         Self {
-            cpu: Cpu::new(),
+            cpu,
             is_debug: false,
+            entry: 0,
+            debug_info: DebugInfo::new(),
+            image: Vec::new(),
+            register_aliases: RegisterAliases::new(),
+            csr_names: CsrNames::new(),
+            programs: Programs::new(),
+            last_trap: None,
+            breakpoints: Breakpoints::new(),
+            injections: Injections::new(),
+            last_watch_hit: None,
+            last_delta: None,
+            trace: TraceBuffer::new(DEFAULT_TRACE_CAPACITY),
+            atomic_events: AtomicEvents::new(),
+            csr_trace: CsrTrace::new(),
+            debug_prints: DebugPrints::new(),
+            guest_asserts: GuestAsserts::new(),
+            semihosting: Semihosting::new(),
+            trap_returns: TrapReturns::new(),
+            last_trap_return: None,
+            retired: 0,
+            clock_hz: DEFAULT_CLOCK_HZ,
+            interrupts: 0,
+            cancel_token: CancelToken::new(),
+            step_interrupts: StepInterrupts::Deliver,
+            fuel: None,
+            history: History::new(),
+            eviction_callback: None,
+            progress: None,
+            ecall_handler: None,
+            ebreak_behavior: EbreakBehavior::Stop,
+            profile: Profile::Machine,
+            stop_at_trap_return: false,
+            trap_returned: false,
+        }
+    }
+
+    /// Return the profile applied by `set_profile`.
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Configure the core for a kind of guest program. See `Profile` for what each preset does.
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = profile;
+        match profile {
+            Profile::Machine => {
+                self.cpu.mode = Mode::Machine;
+                self.cpu.allow_privileged = true;
+            }
+            Profile::UserSandbox => {
+                self.cpu.mode = Mode::User;
+                self.cpu.allow_privileged = false;
+                // Interrupts are taken in machine mode, so disable them all. The program can't
+                // enable them again without CSR access.
+                self.cpu.state.write(MIE, 0);
+                self.cpu.idle = false;
+            }
         }
     }
 
@@ -25,8 +195,11 @@ impl Emulator {
         self.cpu.reset()
     }
 
-    /// Set binary data to the beginning of the DRAM from the emulator console.
+    /// Set binary data to the beginning of the DRAM from the emulator console. The injected
+    /// instructions of the previous program are removed.
     pub fn initialize_dram(&mut self, data: Vec<u8>) {
+        injection::restore_all(self);
+        self.image = data.clone();
         self.cpu.bus.initialize_dram(data);
     }
 
@@ -35,9 +208,735 @@ impl Emulator {
         self.cpu.bus.initialize_disk(data);
     }
 
-    /// Set the program counter to the CPU field.
+    /// Copy `bytes` to DRAM at `addr`, removing the injected instructions they overwrite. Fails
+    /// if they don't fit in DRAM.
+    pub fn write_dram(&mut self, addr: u64, bytes: &[u8]) -> Result<(), String> {
+        let end = addr.saturating_add(bytes.len() as u64);
+        if addr < DRAM_BASE || end > DRAM_END {
+            return Err(format!("{:#x}..{:#x} is outside DRAM", addr, end));
+        }
+        injection::remove_overlapping(self, addr, bytes.len() as u64);
+        self.cpu.bus.write_dram(addr, bytes);
+        Ok(())
+    }
+
+    /// Load the program `image` named `name` at `base`, next to the programs already loaded,
+    /// and return its index. `entry` is the address its execution starts at. Fails without
+    /// touching memory if the image overlaps another program or doesn't fit in DRAM.
+    pub fn load_program_at(
+        &mut self,
+        name: &str,
+        base: u64,
+        image: &[u8],
+        entry: u64,
+    ) -> Result<usize, String> {
+        let index = self.programs.add(name, base, image.len() as u64, entry)?;
+        self.write_dram(base, image)?;
+        Ok(index)
+    }
+
+    /// Write the jump table of the loaded programs at `addr` and return its size. Calling
+    /// `addr + 8 * i` calls the entry point of program `i`, so programs can call each other
+    /// without knowing where they were loaded.
+    pub fn write_jump_table(&mut self, addr: u64) -> Result<u64, String> {
+        let table = self.programs.jump_table(addr);
+        self.write_dram(addr, &table)?;
+        Ok(table.len() as u64)
+    }
+
+    /// Start the program at `index` from its entry point. Returns false if there's no such
+    /// program.
+    pub fn launch(&mut self, index: usize) -> bool {
+        match self.programs.get(index) {
+            Some(program) => {
+                let entry = program.entry;
+                self.initialize_pc(entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the debug info describing `addr`: the one of the loaded program containing it, or
+    /// else `debug_info`.
+    pub fn debug_info_at(&self, addr: u64) -> &DebugInfo {
+        match self.programs.containing(addr) {
+            Some(program) => &program.debug_info,
+            None => &self.debug_info,
+        }
+    }
+
+    /// Set the program counter to the CPU field. It's also remembered as the entry point.
     pub fn initialize_pc(&mut self, pc: u64) {
         self.cpu.pc = pc;
+        self.entry = pc;
+    }
+
+    /// Restart the program from its entry point, e.g. for a "Restart" button: the core is reset
+    /// as by the watchdog, with the registers and CSRs zeroed. If `restore_memory`, the injected
+    /// instructions are removed and the loaded program is copied back over its text, undoing the
+    /// stores and patches to it. The rest of memory and the devices are kept.
+    pub fn restart(&mut self, restore_memory: bool) {
+        if restore_memory {
+            injection::restore_all(self);
+            let image = mem::take(&mut self.image);
+            self.cpu.bus.write_dram(DRAM_BASE, &image);
+            self.image = image;
+        }
+        self.reset_core();
+        self.last_trap = None;
+    }
+
+    /// Reset the core to the power-on state and restart from the entry point. The memory and
+    /// devices other than the watchdog are kept as they are, like a hardware watchdog reset.
+    fn reset_core(&mut self) {
+        self.cpu.reset();
+        self.cpu.xregs = XRegisters::new();
+        self.cpu.idle = false;
+        self.cpu.pc = self.entry;
+        self.cpu.bus.watchdog.disable();
+        self.set_profile(self.profile);
+    }
+
+    /// Run a cycle on peripheral devices and reset the core if the watchdog requested it.
+    fn devices_increment(&mut self) {
+        self.cpu.devices_increment();
+
+        if self.cpu.bus.watchdog.take_reset_request() {
+            self.reset_core();
+        }
+    }
+
+    /// Run a cycle on peripheral devices, take a pending interrupt if any, and execute one
+    /// instruction. Unlike `start`, an exception is returned to the caller instead of being taken
+    /// as a trap, so an embedder can decide how to handle it.
+    pub fn step(&mut self) -> Result<u64, Exception> {
+        self.step_with(true).1
+    }
+
+    /// Return the `len` bytes at `addr`, or `None` if they aren't all in DRAM.
+    pub fn dram_bytes(&self, addr: u64, len: u64) -> Option<&[u8]> {
+        if addr < DRAM_BASE || addr.saturating_add(len) > DRAM_END {
+            return None;
+        }
+        Some(self.cpu.bus.read_dram(addr, len))
+    }
+
+    /// Read the `size` bytes at `addr` as a little-endian value, or `None` if they aren't in DRAM.
+    fn read_dram_value(&self, addr: u64, size: u64) -> Option<u64> {
+        self.read_value(addr, size, ByteOrder::Little)
+    }
+
+    /// Read the `size` bytes, 1 to 8, at `addr` as a value in `order`, zero-extended. Returns
+    /// `None` if they aren't all in DRAM or `size` is out of range. The result is the same on
+    /// any host, whatever its own byte order.
+    pub fn read_value(&self, addr: u64, size: u64, order: ByteOrder) -> Option<u64> {
+        if size == 0 || size > 8 {
+            return None;
+        }
+        Some(order.from_bytes(self.dram_bytes(addr, size)?))
+    }
+
+    /// Write the `size` low bytes of `value`, 1 to 8, at `addr` in `order`. Fails if they aren't
+    /// all in DRAM or `size` is out of range.
+    pub fn write_value(
+        &mut self,
+        addr: u64,
+        size: u64,
+        value: u64,
+        order: ByteOrder,
+    ) -> Result<(), String> {
+        if size == 0 || size > 8 {
+            return Err(format!("invalid size {}", size));
+        }
+        self.write_dram(addr, &order.to_bytes(value, size as usize))
+    }
+
+    /// Return the bytes of the NUL-terminated string at `addr`, cut at `max` bytes, or `None` if
+    /// `addr` isn't in DRAM.
+    fn read_c_string(&self, addr: u64, max: u64) -> Option<&[u8]> {
+        if !(DRAM_BASE..DRAM_END).contains(&addr) {
+            return None;
+        }
+        let bytes = self.cpu.bus.read_dram(addr, max.min(DRAM_END - addr));
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        Some(&bytes[..end])
+    }
+
+    /// Return the NUL-terminated string at `addr`, cut at `MAX_DEBUG_TEXT` bytes, or `None` if
+    /// `addr` isn't in DRAM.
+    fn read_text(&self, addr: u64) -> Option<String> {
+        let bytes = self.read_c_string(addr, MAX_DEBUG_TEXT)?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Record the debug print made by the `ecall` at `pc`, if debug prints are served and it is
+    /// one. Returns false if the call is for the host.
+    fn serve_debug_print(&mut self, pc: u64) -> bool {
+        if !self.debug_prints.is_enabled() {
+            return false;
+        }
+        let kind = match DebugPrintKind::from_call(self.cpu.xregs.read(17)) {
+            Some(kind) => kind,
+            None => return false,
+        };
+        let value = self.cpu.xregs.read(10);
+        let text = match kind {
+            DebugPrintKind::Text => self.read_text(value),
+            _ => None,
+        };
+        self.debug_prints.record(DebugPrint {
+            pc,
+            kind,
+            value,
+            text,
+        });
+        true
+    }
+
+    /// Check the assert made by the `ecall` at `pc`, if asserts are served and it is one. Returns
+    /// whether it passed, or `None` if the call is for the host.
+    fn serve_guest_assert(&mut self, pc: u64) -> Option<bool> {
+        if !self.guest_asserts.is_enabled() || self.cpu.xregs.read(17) != SYS_ASSERT {
+            return None;
+        }
+        if self.cpu.xregs.read(10) != 0 {
+            self.guest_asserts.pass();
+            return Some(true);
+        }
+        let xlen = self.cpu.xlen();
+        let message = match xlen.truncate(self.cpu.xregs.read(11)) {
+            0 => None,
+            addr => self.read_text(addr),
+        };
+        self.guest_asserts.fail(AssertionFailure {
+            pc,
+            line: xlen.truncate(self.cpu.xregs.read(12)),
+            message,
+        });
+        Some(false)
+    }
+
+    /// Serve the semihosting call made by the `ebreak` at `pc`, if semihosting is enabled and it
+    /// is one, and return what to do next. Returns `None` if the `ebreak` isn't a call.
+    fn serve_semihosting(&mut self, pc: u64) -> Option<SemihostingAction> {
+        if !self.semihosting.is_enabled() {
+            return None;
+        }
+        let entry = self.read_dram_value(pc.wrapping_sub(4), 4)?;
+        let ebreak = self.read_dram_value(pc, 4)?;
+        let exit = self.read_dram_value(pc.wrapping_add(4), 4)?;
+        if !semihosting::is_call(entry, ebreak, exit) {
+            return None;
+        }
+
+        let xlen = self.cpu.xlen();
+        let arg = xlen.truncate(self.cpu.xregs.read(11));
+        let result = match xlen.truncate(self.cpu.xregs.read(10)) {
+            semihosting::SYS_OPEN => self.semihosting_open(arg),
+            semihosting::SYS_WRITE0 => match self.read_c_string(arg, MAX_SEMIHOSTING_TEXT) {
+                Some(text) => {
+                    let text = text.to_vec();
+                    self.semihosting.write(&text);
+                    return Some(SemihostingAction::Resume);
+                }
+                None => None,
+            },
+            semihosting::SYS_READ => self.semihosting_read(arg),
+            semihosting::SYS_EXIT => {
+                let (reason, subcode) = match xlen {
+                    Xlen::Rv32 => (arg, 0),
+                    Xlen::Rv64 => self
+                        .semihosting_fields(arg)
+                        .map_or((0, 0), |fields| (fields[0], fields[1])),
+                };
+                let code = semihosting::exit_code(xlen, reason, subcode);
+                return Some(SemihostingAction::Exit(code));
+            }
+            _ => None,
+        };
+        // -1 tells the guest the call failed.
+        self.cpu.xregs.write(10, xlen.sign_extend(result.unwrap_or(u64::MAX)));
+        Some(SemihostingAction::Resume)
+    }
+
+    /// Return the first three fields of the semihosting parameter block at `addr`, each as wide
+    /// as a register, or `None` if they aren't in DRAM.
+    fn semihosting_fields(&self, addr: u64) -> Option<[u64; 3]> {
+        let width = self.cpu.xlen().bits() as u64 / 8;
+        let field = |index: u64| self.read_dram_value(addr.wrapping_add(index * width), width);
+        Some([field(0)?, field(1)?, field(2)?])
+    }
+
+    /// Serve `SYS_OPEN` with the parameter block at `addr` and return the handle.
+    fn semihosting_open(&self, addr: u64) -> Option<u64> {
+        let [name, mode, len] = self.semihosting_fields(addr)?;
+        semihosting::open(self.dram_bytes(name, len)?, mode)
+    }
+
+    /// Serve `SYS_READ` with the parameter block at `addr` and return the number of bytes not
+    /// read.
+    fn semihosting_read(&mut self, addr: u64) -> Option<u64> {
+        let [handle, buf, len] = self.semihosting_fields(addr)?;
+        if handle != semihosting::STDIN_HANDLE || self.dram_bytes(buf, len).is_none() {
+            return None;
+        }
+        let bytes = self.semihosting.read_input(len);
+        self.write_dram(buf, &bytes).ok()?;
+        Some(len - bytes.len() as u64)
+    }
+
+    /// Return the event of the atomic instruction at `pc` as it is before the instruction
+    /// executes, or `None` if it isn't an atomic instruction accessing DRAM.
+    fn atomic_event_before(&self, pc: u64) -> Option<AtomicEvent> {
+        let inst = self.read_dram_value(pc, 4)?;
+        let op = AtomicOp::decode(inst)?;
+        let size = if (inst >> 12) & 0x7 == 0x3 { 8 } else { 4 };
+        let addr = self.cpu.xregs.read((inst >> 15) & 0x1f);
+        let old_value = self.read_dram_value(addr, size)?;
+        let success = op != AtomicOp::StoreConditional || self.cpu.has_reservation(addr);
+        Some(AtomicEvent {
+            hart: self.cpu.state.hartid(),
+            pc,
+            addr,
+            old_value,
+            new_value: old_value,
+            size: size as u32,
+            op,
+            success: success as u32,
+        })
+    }
+
+    /// Same as `step`, but a pending interrupt is left pending if `take_interrupt` is false. Also
+    /// returns the address of the instruction executed, which is the trap handler's when an
+    /// interrupt or a watchdog reset moved the program counter.
+    fn step_with(&mut self, take_interrupt: bool) -> (u64, Result<u64, Exception>) {
+        self.begin_step(take_interrupt);
+        self.finish_step()
+    }
+
+    /// Run a cycle on the devices and take a pending interrupt if `take_interrupt`, the part of a
+    /// step before its instruction. Returns true if it moved the program counter.
+    fn begin_step(&mut self, take_interrupt: bool) -> bool {
+        let before = self.cpu.pc;
+        // Run a cycle on peripheral devices.
+        self.devices_increment();
+
+        // Log the CSR writes of the step, but not the timer updating `mip` above.
+        if self.csr_trace.is_enabled() {
+            self.cpu.state.write_log = Some(Vec::new());
+        }
+
+        // Take an interrupt.
+        if take_interrupt {
+            if let Some(interrupt) = self.cpu.check_pending_interrupt() {
+                let interrupted = self.cpu.pc;
+                interrupt.take_trap(&mut self.cpu);
+                self.interrupts += 1;
+                self.record_csr_writes(interrupted);
+                if self.csr_trace.is_enabled() {
+                    self.cpu.state.write_log = Some(Vec::new());
+                }
+            }
+        }
+        self.cpu.pc != before
+    }
+
+    /// Execute the instruction at the program counter, the rest of a step begun by `begin_step`.
+    fn finish_step(&mut self) -> (u64, Result<u64, Exception>) {
+        let pc = self.cpu.pc;
+        let idle = self.cpu.idle;
+        let atomic_event = if self.atomic_events.is_enabled() && !idle {
+            self.atomic_event_before(pc)
+        } else {
+            None
+        };
+        let return_instruction =
+            if (self.trap_returns.is_enabled() || self.stop_at_trap_return) && !idle {
+                self.read_dram_value(pc, 4)
+                    .and_then(ReturnInstruction::decode)
+            } else {
+                None
+            };
+        let mode = self.cpu.mode;
+        self.trap_returned = false;
+        let result = self.cpu.execute();
+        self.record_csr_writes(pc);
+        match result {
+            Ok(inst) => {
+                // Nothing is executed while the core waits for an interrupt.
+                if !idle {
+                    self.trace.push(pc, inst);
+                    self.retired += 1;
+                }
+                if let Some(mut event) = atomic_event {
+                    event.new_value = self
+                        .read_dram_value(event.addr, event.size as u64)
+                        .unwrap_or(event.old_value);
+                    self.atomic_events.record(event);
+                }
+                if let Some(instruction) = return_instruction {
+                    let event = TrapReturn {
+                        pc,
+                        target: self.cpu.pc,
+                        from: mode as u64,
+                        to: self.cpu.mode as u64,
+                        instruction,
+                    };
+                    if self.trap_returns.is_enabled() {
+                        self.trap_returns.record(event);
+                    }
+                    self.last_trap_return = Some(event);
+                    self.trap_returned = true;
+                }
+                (pc, Ok(inst))
+            }
+            Err(exception) => {
+                self.last_trap = Some(exception.info(pc));
+                (pc, Err(exception))
+            }
+        }
+    }
+
+    /// Move the CSR writes logged by the core to the trace, as writes by the instruction at
+    /// `pc`, and stop logging.
+    fn record_csr_writes(&mut self, pc: u64) {
+        let log = match self.cpu.state.write_log.take() {
+            Some(log) => log,
+            None => return,
+        };
+        for (addr, old, new) in log {
+            self.csr_trace.record(CsrWrite {
+                pc,
+                addr: addr as u64,
+                old,
+                new,
+            });
+        }
+    }
+
+    /// Call `callback` every `interval` steps of a run with the number of steps done and the
+    /// step limit. An interval of 0 removes the callback.
+    pub fn set_progress_callback(&mut self, interval: u64, callback: ProgressCallback) {
+        self.progress = match interval {
+            0 => None,
+            _ => Some((interval, callback)),
+        };
+    }
+
+    /// Serve the environment calls of the guest during runs with `handler`, or hand them to the
+    /// host by stopping the run if it's `None`. Debug prints are still served first.
+    pub fn set_ecall_handler(&mut self, handler: Option<EcallHandler>) {
+        self.ecall_handler = handler;
+    }
+
+    /// Choose what runs do when the guest executes `ebreak`.
+    pub fn set_ebreak_behavior(&mut self, behavior: EbreakBehavior) {
+        self.ebreak_behavior = behavior;
+    }
+
+    /// Set the nominal clock frequency of the core to `hz` hertz, which must not be 0. It only
+    /// scales the simulated time of the run summaries; the emulator runs as fast as it can.
+    pub fn set_clock_frequency(&mut self, hz: u64) {
+        assert!(hz > 0, "the clock frequency must not be 0");
+        self.clock_hz = hz;
+    }
+
+    /// Return the nominal clock frequency of the core in hertz.
+    pub fn clock_frequency(&self) -> u64 {
+        self.clock_hz
+    }
+
+    /// Pass the `ecall` to the handler, if any, and return what to do next.
+    fn serve_ecall(&mut self) -> EcallAction {
+        let handler = match self.ecall_handler.as_mut() {
+            Some(handler) => handler,
+            None => return EcallAction::Yield,
+        };
+        let xregs = &mut self.cpu.xregs;
+        let call = EcallArgs {
+            number: xregs.read(17),
+            args: [10, 11, 12, 13, 14, 15].map(|i| xregs.read(i)),
+        };
+        let mut value = call.args[0];
+        let action = handler(&call, &mut value);
+        if action != EcallAction::Yield {
+            xregs.write(10, value);
+        }
+        action
+    }
+
+    /// Execute up to `max_steps` steps and summarize the run. See `run_with` for when a run
+    /// stops early.
+    pub fn run(&mut self, max_steps: u64) -> RunSummary {
+        self.run_with(RunLimits::steps(max_steps))
+    }
+
+    /// Execute steps within `limits` and summarize the run. The run stops early when:
+    /// - an instruction raises an exception (`Trapped`),
+    /// - the guest executes `ebreak` (`Breakpoint`), unless `set_ebreak_behavior` chose to trap
+    ///   to the guest or a handler that resumes. Semihosting calls are served without stopping
+    ///   while `semihosting` is enabled, and `SYS_EXIT` stops the run with `Exited`,
+    /// - the program counter reaches one of `breakpoints` after the first step
+    ///   (`HostBreakpoint`), so a run resumed at a breakpoint doesn't stop there again. An
+    ///   interrupt moving it to one, e.g. at `mtvec`, stops the run before the handler executes,
+    /// - the guest presents a frame through the vsync device (`FramePresented`),
+    /// - the guest loads from or stores to a watched address (`Watchpoint`). The access is done,
+    ///   and `last_watch_hit` holds its kind, its address and the values before and after it,
+    /// - the guest calls `exit` (`Exited`), or makes any other environment call, which is handed
+    ///   to the host with the program counter moved past the `ecall` (`Yielded`). Debug prints
+    ///   are served without stopping while `debug_prints` is enabled, and the other calls by the
+    ///   handler set with `set_ecall_handler`, if any, unless it stops the run,
+    /// - a guest assert fails while `guest_asserts` is enabled (`GuestAssertionFailed`). Passing
+    ///   asserts are counted without stopping,
+    /// - the time budget runs out (`TimeBudget`),
+    /// - the host cancels it through `cancel_token` (`Cancelled`). The cancel request is consumed,
+    /// - the `fuel` runs out before the step limit is reached (`FuelExhausted`).
+    ///
+    /// A run of one step takes a pending interrupt only if `step_interrupts` says so.
+    pub fn run_with(&mut self, limits: RunLimits) -> RunSummary {
+        let start = Instant::now();
+        let retired = self.retired;
+        let interrupts = self.interrupts;
+        let take_interrupt =
+            limits.max_steps != 1 || self.step_interrupts == StepInterrupts::Deliver;
+        // Forget a hit or a frame of a step done outside a run.
+        self.cpu.watchpoints.take_hit();
+        self.cpu.bus.vsync.take_presented();
+
+        let max_steps = match self.fuel {
+            Some(fuel) => fuel.min(limits.max_steps),
+            None => limits.max_steps,
+        };
+
+        let mut reason = StopReason::StepLimit;
+        let mut cause = 0;
+        let mut tval = 0;
+        let mut exit_code = 0;
+        for count in 0..max_steps {
+            if count % CHECK_INTERVAL == 0 {
+                if self.cancel_token.take() {
+                    reason = StopReason::Cancelled;
+                    break;
+                }
+                if let Some(budget) = limits.time_budget {
+                    if start.elapsed() >= budget {
+                        reason = StopReason::TimeBudget;
+                        break;
+                    }
+                }
+            }
+            if let Some((interval, callback)) = self.progress.as_mut() {
+                if count > 0 && count % *interval == 0 {
+                    callback(count, max_steps);
+                }
+            }
+
+            if count > 0 && self.breakpoints.contains(self.cpu.pc) {
+                reason = StopReason::HostBreakpoint;
+                break;
+            }
+            if self.begin_step(take_interrupt) && self.breakpoints.contains(self.cpu.pc) {
+                reason = StopReason::HostBreakpoint;
+                break;
+            }
+            let (pc, result) = self.finish_step();
+            match result {
+                Ok(_) => {
+                    if let Some(hit) = self.cpu.watchpoints.take_hit() {
+                        reason = StopReason::Watchpoint;
+                        tval = hit.addr;
+                        self.last_watch_hit = Some(hit);
+                        break;
+                    }
+                    if self.stop_at_trap_return && self.trap_returned {
+                        reason = StopReason::TrapReturn;
+                        break;
+                    }
+                    if self.cpu.bus.vsync.take_presented() {
+                        reason = StopReason::FramePresented;
+                        break;
+                    }
+                }
+                Err(
+                    exception @ Exception::EnvironmentCallFromUMode
+                    | exception @ Exception::EnvironmentCallFromSMode
+                    | exception @ Exception::EnvironmentCallFromMMode,
+                ) => {
+                    self.cpu.pc = pc + 4;
+                    self.retired += 1;
+                    if self.serve_debug_print(pc) {
+                        continue;
+                    }
+                    match self.serve_guest_assert(pc) {
+                        Some(true) => continue,
+                        Some(false) => {
+                            reason = StopReason::GuestAssertionFailed;
+                            tval = self.guest_asserts.last_failure().map_or(0, |f| f.line);
+                            break;
+                        }
+                        None => {}
+                    }
+                    let action = self.serve_ecall();
+                    if action == EcallAction::Resume {
+                        continue;
+                    }
+                    cause = exception.exception_code();
+                    if action == EcallAction::Exit || self.cpu.xregs.read(17) == SYS_EXIT {
+                        reason = StopReason::Exited;
+                        exit_code = self.cpu.xregs.read(10);
+                    } else {
+                        reason = StopReason::Yielded;
+                    }
+                    break;
+                }
+                Err(exception @ Exception::Breakpoint) => {
+                    if let Some(action) = self.serve_semihosting(pc) {
+                        self.cpu.pc = pc + 4;
+                        self.retired += 1;
+                        match action {
+                            SemihostingAction::Resume => continue,
+                            SemihostingAction::Exit(code) => {
+                                reason = StopReason::Exited;
+                                exit_code = code;
+                                break;
+                            }
+                        }
+                    }
+                    match &mut self.ebreak_behavior {
+                        EbreakBehavior::Stop => {}
+                        EbreakBehavior::Trap => {
+                            exception.take_trap(&mut self.cpu);
+                            continue;
+                        }
+                        EbreakBehavior::Callback(handler) => {
+                            if handler(pc) {
+                                // Skip the `ebreak`, or the `c.ebreak`.
+                                let half = self.read_dram_value(pc, 2).unwrap_or(0);
+                                self.cpu.pc = pc + if half & 0b11 == 0b11 { 4 } else { 2 };
+                                self.retired += 1;
+                                continue;
+                            }
+                        }
+                    }
+                    reason = StopReason::Breakpoint;
+                    cause = exception.exception_code();
+                    break;
+                }
+                Err(exception) => {
+                    let info = exception.info(pc);
+                    reason = StopReason::Trapped;
+                    cause = info.cause;
+                    tval = info.tval;
+                    break;
+                }
+            }
+        }
+        if reason == StopReason::StepLimit && max_steps < limits.max_steps {
+            reason = StopReason::FuelExhausted;
+        }
+
+        let steps = self.retired - retired;
+        let summary = RunSummary {
+            reason,
+            steps,
+            stop_pc: self.cpu.pc,
+            cause,
+            tval,
+            exit_code,
+            wall_time_ns: start.elapsed().as_nanos() as u64,
+            interrupts: self.interrupts - interrupts,
+            total_retired: self.retired,
+            mtime: self.cpu.bus.clint.mtime(),
+            clock_hz: self.clock_hz,
+            simulated_us: run::simulated_us(steps, self.clock_hz),
+        };
+        metrics::record_run(&summary);
+        history::enforce_budget(self);
+        summary
+    }
+
+    /// Execute up to `max_steps` steps like `run`, but also stop right after a privilege-return
+    /// instruction (`mret`, `sret` or `uret`) with `StopReason::TrapReturn`. The instruction is
+    /// in `last_trap_return`.
+    pub fn run_until_trap_return(&mut self, max_steps: u64) -> RunSummary {
+        self.stop_at_trap_return = true;
+        let summary = self.run(max_steps);
+        self.stop_at_trap_return = false;
+        summary
+    }
+
+    /// Return the values of the integer registers.
+    pub(crate) fn xreg_values(&self) -> Vec<u64> {
+        (0..32).map(|i| self.cpu.xregs.read(i)).collect()
+    }
+
+    /// Return the bits of the floating-point registers.
+    pub(crate) fn freg_values(&self) -> Vec<u64> {
+        (0..32).map(|i| self.cpu.fregs.read(i).to_bits()).collect()
+    }
+
+    /// Execute one step like `run(1)` and record its complete architectural effect in
+    /// `last_delta`: the registers, CSRs and memory it wrote and the next program counter.
+    pub fn step_delta(&mut self) -> RunSummary {
+        let pc = self.cpu.pc;
+        let inst = match self.read_dram_value(pc, 4) {
+            Some(inst) if inst & 0b11 == 0b11 => inst,
+            Some(inst) => inst & 0xffff,
+            None => 0,
+        };
+        let xregs = self.xreg_values();
+        let fregs = self.freg_values();
+        let csrs = self.cpu.state.values().to_vec();
+        // Keep the log of multi-hart mode, if any, and add the accesses of this step to it.
+        let outer_log = self.cpu.access_log.replace(Vec::new());
+
+        let summary = self.run(1);
+
+        let accesses = mem::replace(&mut self.cpu.access_log, outer_log).unwrap_or_default();
+        if let Some(log) = self.cpu.access_log.as_mut() {
+            log.extend(accesses.iter().copied());
+        }
+        let mut csr_changes = changes(&csrs, self.cpu.state.values());
+        csr_changes.retain(|change| change.index != TIME as u64);
+        self.last_delta = Some(StepDelta {
+            pc,
+            inst,
+            next_pc: self.cpu.pc,
+            mode: self.cpu.mode,
+            reason: summary.reason,
+            cause: summary.cause,
+            tval: summary.tval,
+            xregs: changes(&xregs, &self.xreg_values()),
+            fregs: changes(&fregs, &self.freg_values()),
+            csrs: csr_changes,
+            memory: accesses
+                .into_iter()
+                .filter(|access| access.kind == AccessKind::Store)
+                .collect(),
+        });
+        summary
+    }
+
+    /// Return a beginner-friendly explanation of a trap, using the debug info of the loaded
+    /// program to point at the source line.
+    pub fn explain_trap(&self, cause: u64, tval: u64, pc: u64) -> String {
+        let debug_info = self.debug_info_at(pc);
+        // An illegal instruction may be a real instruction the level doesn't allow.
+        if let (2, Some(set), Some(name)) = (cause, &self.cpu.instruction_set, isa::mnemonic(tval))
+        {
+            if !set.contains(name) {
+                return explain_locked_instruction(name, pc, debug_info);
+            }
+            if let Some(&reg) = isa::registers(tval)
+                .iter()
+                .find(|&&reg| !set.allows_register(reg))
+            {
+                let register = self.register_aliases.describe(reg);
+                return explain_locked_register(name, &register, pc, debug_info);
+            }
+        }
+        explain_trap(cause, tval, pc, debug_info)
     }
 
     /// Start executing the emulator with limited range of program. This method is for test.
@@ -78,7 +977,7 @@ impl Emulator {
             }
 
             // Run a cycle on peripheral devices.
-            self.cpu.devices_increment();
+            self.devices_increment();
 
             // Take an interrupt.
             match self.cpu.check_pending_interrupt() {
@@ -123,7 +1022,7 @@ impl Emulator {
 
         loop {
             // Run a cycle on peripheral devices.
-            self.cpu.devices_increment();
+            self.devices_increment();
 
             // Take an interrupt.
             match self.cpu.check_pending_interrupt() {
@@ -0,0 +1,247 @@
+//! The encoding audit module finds executed instructions whose encodings fall in the reserved
+//! or hint spaces of RV64GC. Hints (e.g. an `addi` to `x0` that isn't the canonical `nop`)
+//! execute as no-ops, and reserved encodings (e.g. `c.addi4spn` with a zero immediate) have no
+//! defined behavior. The audit logs them, and can make either kind raise an illegal instruction
+//! exception, so how the core treats corner encodings is explicit instead of accidental.
+
+/// The maximum number of distinct instructions logged. Later ones are counted but not recorded.
+pub const MAX_ENCODING_EVENTS: usize = 4096;
+
+/// The kind of a corner encoding.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EncodingKind {
+    /// A hint: it executes as a no-op, but may be given a meaning by future extensions.
+    Hint = 0,
+    /// A reserved encoding: the behavior is undefined.
+    Reserved = 1,
+}
+
+/// A corner encoding and the rule of the specification it falls under.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct EncodingClass {
+    /// Whether it's a hint or reserved.
+    pub kind: EncodingKind,
+    /// A short description, e.g. "c.lui with a zero immediate".
+    pub rule: &'static str,
+}
+
+/// What the CPU does with a kind of corner encoding.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EncodingPolicy {
+    /// Execute it as the core always did: hints as no-ops, reserved encodings as whatever they
+    /// decode to, or an illegal instruction exception.
+    Execute = 0,
+    /// Raise an illegal instruction exception.
+    Trap = 1,
+}
+
+fn hint(rule: &'static str) -> Option<EncodingClass> {
+    Some(EncodingClass {
+        kind: EncodingKind::Hint,
+        rule,
+    })
+}
+
+fn reserved(rule: &'static str) -> Option<EncodingClass> {
+    Some(EncodingClass {
+        kind: EncodingKind::Reserved,
+        rule,
+    })
+}
+
+/// Classify a compressed instruction.
+fn classify_compressed(inst: u64) -> Option<EncodingClass> {
+    let funct3 = (inst >> 13) & 0x7;
+    let rd = (inst >> 7) & 0x1f;
+    let rs2 = (inst >> 2) & 0x1f;
+    // imm[5|4:0] = inst[12|6:2], also the shift amount.
+    let imm = ((inst >> 7) & 0x20) | rs2;
+    let bit12 = (inst >> 12) & 1;
+
+    match (inst & 0x3, funct3) {
+        // All zeros is defined to be illegal.
+        (0, 0x0) if inst == 0 => None,
+        (0, 0x0) if (inst >> 5) & 0xff == 0 => reserved("c.addi4spn with a zero immediate"),
+        (0, 0x4) => reserved("quadrant 0, funct3 4"),
+        (1, 0x0) if rd == 0 && imm != 0 => hint("c.nop with a nonzero immediate"),
+        (1, 0x0) if rd != 0 && imm == 0 => hint("c.addi with a zero immediate"),
+        (1, 0x1) if rd == 0 => reserved("c.addiw to x0"),
+        (1, 0x2) if rd == 0 => hint("c.li to x0"),
+        (1, 0x3) if rd == 2 && imm == 0 => reserved("c.addi16sp with a zero immediate"),
+        (1, 0x3) if rd != 2 && imm == 0 => reserved("c.lui with a zero immediate"),
+        (1, 0x3) if rd == 0 => hint("c.lui to x0"),
+        (1, 0x4) if (inst >> 10) & 0x3 < 2 && imm == 0 => hint("c.srli or c.srai by zero"),
+        (1, 0x4) if (inst >> 10) & 0x3 == 3 && bit12 == 1 && (inst >> 5) & 0x3 >= 2 => {
+            reserved("quadrant 1, funct6 100111, funct2 10 or 11")
+        }
+        (2, 0x0) if rd == 0 || imm == 0 => hint("c.slli to x0 or by zero"),
+        (2, 0x2) if rd == 0 => reserved("c.lwsp to x0"),
+        (2, 0x3) if rd == 0 => reserved("c.ldsp to x0"),
+        (2, 0x4) if bit12 == 0 && rs2 == 0 && rd == 0 => reserved("c.jr x0"),
+        (2, 0x4) if rs2 != 0 && rd == 0 => hint("c.mv or c.add to x0"),
+        _ => None,
+    }
+}
+
+/// Return the class of `inst` if its encoding is a hint or reserved, or `None` if it's an
+/// ordinary instruction (or not a valid one at all).
+pub fn classify(inst: u64) -> Option<EncodingClass> {
+    if inst & 0x3 != 0x3 {
+        return classify_compressed(inst & 0xffff);
+    }
+
+    let opcode = inst & 0x7f;
+    let rd = (inst >> 7) & 0x1f;
+    let funct3 = (inst >> 12) & 0x7;
+    let funct7 = inst >> 25;
+    match opcode {
+        // addi x0, x0, 0
+        0x13 if inst == 0x13 => None,
+        0x13 if rd == 0 => hint("integer register-immediate instruction to x0"),
+        0x1b if (funct3 == 0x1 || funct3 == 0x5) && funct7 & 0x1 != 0 => {
+            reserved("32-bit shift by 32 or more")
+        }
+        0x1b if rd == 0 => hint("32-bit register-immediate instruction to x0"),
+        0x33 | 0x3b if rd == 0 && (funct7 == 0x00 || funct7 == 0x20) => {
+            hint("integer register-register instruction to x0")
+        }
+        0x37 | 0x17 if rd == 0 => hint("lui or auipc to x0"),
+        0x0f if funct3 == 0 => {
+            let fm = inst >> 28;
+            let pred = (inst >> 24) & 0xf;
+            let succ = (inst >> 20) & 0xf;
+            if fm != 0 && fm != 0x8 {
+                reserved("fence with a reserved fm")
+            } else if pred == 0 || succ == 0 {
+                hint("fence without predecessors or successors")
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// An executed corner encoding. The layout is C-compatible so events can be copied over the FFI
+/// as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct EncodingEvent {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The instruction word. Compressed instructions are 16 bits.
+    pub inst: u64,
+    /// The number of times it was executed, or trapped.
+    pub count: u64,
+    /// Whether it's a hint or reserved.
+    pub kind: EncodingKind,
+}
+
+/// The audit of corner encodings. Nothing is checked until logging is enabled or a policy is
+/// `Trap`.
+#[derive(Debug, Clone)]
+pub struct EncodingAudit {
+    logging: bool,
+    hints: EncodingPolicy,
+    reserved: EncodingPolicy,
+    events: Vec<EncodingEvent>,
+    dropped: u64,
+}
+
+impl Default for EncodingAudit {
+    fn default() -> Self {
+        Self {
+            logging: false,
+            hints: EncodingPolicy::Execute,
+            reserved: EncodingPolicy::Execute,
+            events: Vec::new(),
+            dropped: 0,
+        }
+    }
+}
+
+impl EncodingAudit {
+    /// Create an audit that doesn't log nor trap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop logging corner encodings.
+    pub fn set_logging(&mut self, logging: bool) {
+        self.logging = logging;
+    }
+
+    /// Set what the CPU does with corner encodings of `kind`.
+    pub fn set_policy(&mut self, kind: EncodingKind, policy: EncodingPolicy) {
+        match kind {
+            EncodingKind::Hint => self.hints = policy,
+            EncodingKind::Reserved => self.reserved = policy,
+        }
+    }
+
+    /// Return what the CPU does with corner encodings of `kind`.
+    pub fn policy(&self, kind: EncodingKind) -> EncodingPolicy {
+        match kind {
+            EncodingKind::Hint => self.hints,
+            EncodingKind::Reserved => self.reserved,
+        }
+    }
+
+    /// Return true if instructions need to be classified before they execute.
+    pub fn is_active(&self) -> bool {
+        self.logging || self.hints == EncodingPolicy::Trap || self.reserved == EncodingPolicy::Trap
+    }
+
+    /// Classify the instruction `inst` at `pc`, log it if it's a corner encoding, and return
+    /// true if it must trap.
+    pub fn check(&mut self, pc: u64, inst: u64) -> bool {
+        let class = match classify(inst) {
+            Some(class) => class,
+            None => return false,
+        };
+        if self.logging {
+            self.record(pc, inst, class.kind);
+        }
+        self.policy(class.kind) == EncodingPolicy::Trap
+    }
+
+    /// Count an execution of `inst` at `pc`. Each instruction is logged once.
+    fn record(&mut self, pc: u64, inst: u64, kind: EncodingKind) {
+        if let Some(event) = self
+            .events
+            .iter_mut()
+            .find(|event| event.pc == pc && event.inst == inst)
+        {
+            event.count += 1;
+            return;
+        }
+        if self.events.len() == MAX_ENCODING_EVENTS {
+            self.dropped += 1;
+            return;
+        }
+        self.events.push(EncodingEvent {
+            pc,
+            inst,
+            count: 1,
+            kind,
+        });
+    }
+
+    /// Return the logged instructions in the order they were first executed.
+    pub fn events(&self) -> &[EncodingEvent] {
+        &self.events
+    }
+
+    /// Return the number of executions of instructions that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Forget the logged instructions. The policies are kept.
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.dropped = 0;
+    }
+}
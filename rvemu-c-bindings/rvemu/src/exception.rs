@@ -11,13 +11,15 @@ pub enum Exception {
     /// With the addition of the C extension, no instructions can raise
     /// instruction-address-misaligned exceptions.
     InstructionAddressMisaligned,
-    InstructionAccessFault,
+    // Stores a trap value (the faulting address) for access fault and address misaligned
+    // exceptions.
+    InstructionAccessFault(u64),
     IllegalInstruction(u64),
     Breakpoint,
-    LoadAddressMisaligned,
-    LoadAccessFault,
-    StoreAMOAddressMisaligned,
-    StoreAMOAccessFault,
+    LoadAddressMisaligned(u64),
+    LoadAccessFault(u64),
+    StoreAMOAddressMisaligned(u64),
+    StoreAMOAccessFault(u64),
     EnvironmentCallFromUMode,
     EnvironmentCallFromSMode,
     EnvironmentCallFromMMode,
@@ -27,6 +29,15 @@ pub enum Exception {
     StoreAMOPageFault(u64),
 }
 
+/// The information about a trap: the values written to the cause and trap value registers, and
+/// the address of the instruction that raised it.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct TrapInfo {
+    pub cause: u64,
+    pub tval: u64,
+    pub pc: u64,
+}
+
 /// All the trap kinds.
 #[derive(Debug)]
 pub enum Trap {
@@ -45,16 +56,17 @@ pub enum Trap {
 }
 
 impl Exception {
-    fn exception_code(&self) -> u64 {
+    /// Return the exception code written to mcause or scause.
+    pub fn exception_code(&self) -> u64 {
         match self {
             Exception::InstructionAddressMisaligned => 0,
-            Exception::InstructionAccessFault => 1,
+            Exception::InstructionAccessFault(_) => 1,
             Exception::IllegalInstruction(_) => 2,
             Exception::Breakpoint => 3,
-            Exception::LoadAddressMisaligned => 4,
-            Exception::LoadAccessFault => 5,
-            Exception::StoreAMOAddressMisaligned => 6,
-            Exception::StoreAMOAccessFault => 7,
+            Exception::LoadAddressMisaligned(_) => 4,
+            Exception::LoadAccessFault(_) => 5,
+            Exception::StoreAMOAddressMisaligned(_) => 6,
+            Exception::StoreAMOAccessFault(_) => 7,
             Exception::EnvironmentCallFromUMode => 8,
             Exception::EnvironmentCallFromSMode => 9,
             Exception::EnvironmentCallFromMMode => 11,
@@ -82,7 +94,8 @@ impl Exception {
         }
     }
 
-    fn trap_value(&self, pc: u64) -> u64 {
+    /// Return the value written to mtval or stval when the exception is taken at `pc`.
+    pub fn trap_value(&self, pc: u64) -> u64 {
         // 3.1.17 Machine Trap Value Register (mtval)
         // 4.1.9 Supervisor Trap Value Register (stval)
         // "When a hardware breakpoint is triggered, or an address-misaligned, access-fault, or
@@ -92,14 +105,13 @@ impl Exception {
         // below. For other traps, mtval (stval) is set to zero, but a future standard may redefine
         // mtval's (stval's) setting for other traps."
         match self {
-            Exception::InstructionAddressMisaligned
-            | Exception::InstructionAccessFault
-            | Exception::Breakpoint
-            | Exception::LoadAddressMisaligned
-            | Exception::LoadAccessFault
-            | Exception::StoreAMOAddressMisaligned
-            | Exception::StoreAMOAccessFault => pc,
-            Exception::InstructionPageFault(val)
+            Exception::InstructionAddressMisaligned | Exception::Breakpoint => pc,
+            Exception::InstructionAccessFault(val)
+            | Exception::LoadAddressMisaligned(val)
+            | Exception::LoadAccessFault(val)
+            | Exception::StoreAMOAddressMisaligned(val)
+            | Exception::StoreAMOAccessFault(val)
+            | Exception::InstructionPageFault(val)
             | Exception::LoadPageFault(val)
             | Exception::StoreAMOPageFault(val) => *val,
             Exception::IllegalInstruction(val) => *val,
@@ -107,6 +119,15 @@ impl Exception {
         }
     }
 
+    /// Return the information about the exception raised by the instruction at `pc`.
+    pub fn info(&self, pc: u64) -> TrapInfo {
+        TrapInfo {
+            cause: self.exception_code(),
+            tval: self.trap_value(pc),
+            pc,
+        }
+    }
+
     /// Update CSRs and the program counter depending on an exception.
     pub fn take_trap(&self, cpu: &mut Cpu) -> Trap {
         // 1.2 Privilege Levels
@@ -214,15 +235,15 @@ impl Exception {
         }
 
         match self {
-            Exception::InstructionAddressMisaligned | Exception::InstructionAccessFault => {
+            Exception::InstructionAddressMisaligned | Exception::InstructionAccessFault(_) => {
                 Trap::Fatal
             }
             Exception::IllegalInstruction(_) => Trap::Invisible,
             Exception::Breakpoint => Trap::Requested,
-            Exception::LoadAddressMisaligned
-            | Exception::LoadAccessFault
-            | Exception::StoreAMOAddressMisaligned
-            | Exception::StoreAMOAccessFault => Trap::Fatal,
+            Exception::LoadAddressMisaligned(_)
+            | Exception::LoadAccessFault(_)
+            | Exception::StoreAMOAddressMisaligned(_)
+            | Exception::StoreAMOAccessFault(_) => Trap::Fatal,
             Exception::EnvironmentCallFromUMode
             | Exception::EnvironmentCallFromSMode
             | Exception::EnvironmentCallFromMMode => Trap::Requested,
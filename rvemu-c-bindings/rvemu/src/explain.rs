@@ -0,0 +1,215 @@
+//! The explain module turns a trap into a short explanation for people who are new to RISC-V, e.g.
+//! "You tried to load from address 0x0 — a null pointer — at line 14."
+
+use crate::bus::region_name;
+use crate::debug_info::DebugInfo;
+
+/// The bit of the cause value that is set for interrupts.
+const INTERRUPT_BIT: u64 = 1 << 63;
+
+/// Addresses below this are treated as a null pointer with an offset, e.g. a field of a struct
+/// accessed through a null pointer.
+const NULL_PAGE_SIZE: u64 = 0x1000;
+
+/// Return a beginner-friendly explanation of a trap. `cause` and `tval` are the values of the
+/// mcause and mtval (or scause and stval) registers, and `pc` is the address of the instruction
+/// that trapped. Addresses are described with source lines and symbols from `debug_info` when
+/// they are known.
+pub fn explain_trap(cause: u64, tval: u64, pc: u64, debug_info: &DebugInfo) -> String {
+    let at = debug_info.describe(pc);
+
+    if (cause & INTERRUPT_BIT) != 0 {
+        let name = match cause & !INTERRUPT_BIT {
+            1 => "supervisor software interrupt",
+            3 => "machine software interrupt",
+            5 => "supervisor timer interrupt",
+            7 => "machine timer interrupt",
+            9 => "supervisor external interrupt",
+            11 => "machine external interrupt",
+            _ => "unknown interrupt",
+        };
+        return format!(
+            "The program was interrupted by a {} at {}. This is not an error: the program jumps \
+             to its trap handler and continues from there.",
+            name, at
+        );
+    }
+
+    match cause {
+        0 => format!(
+            "The program jumped to address {:#x}, which is not a multiple of 2. Instructions \
+             always start at an even address, so check the target of the last jump or branch.",
+            tval
+        ),
+        1 if matches!(region_name(tval), Some(name) if name != "DRAM") => format!(
+            "The program jumped to address {:#x}, which belongs to the {}. Only code in memory \
+             (DRAM) can run here; running code from the ROM or devices is not allowed.",
+            tval,
+            region_name(tval).unwrap()
+        ),
+        1 if region_name(tval) == Some("DRAM") => format!(
+            "The program jumped to address {}, but physical memory protection (PMP) doesn't \
+             allow running code there in the current privilege mode.",
+            debug_info.describe(tval)
+        ),
+        1 => format!(
+            "The program jumped to address {}{} where there is no code. A jump or return went to \
+             a bad address, or the program ran past the end of its code.",
+            debug_info.describe(tval),
+            null_note(tval)
+        ),
+        2 if tval == 0 => format!(
+            "The program ran into empty memory at {}. The bytes there are all zero, which is not \
+             an instruction. Did the program run past its last instruction? End it with a loop \
+             or an `ecall`.",
+            at
+        ),
+        2 if is_csr_instruction(tval) => format!(
+            "The instruction at {} accesses the control and status register (CSR) {:#x}, which is \
+             not allowed in the current privilege mode. Sandboxed programs can't use CSRs; ask \
+             the host with an `ecall` instead.",
+            at,
+            (tval >> 20) & 0xfff
+        ),
+        2 if privileged_instruction_name(tval).is_some() => format!(
+            "The program tried to run `{}` at {}, which is a privileged instruction and not \
+             allowed in the current privilege mode. Sandboxed programs can only use `ecall` and \
+             `ebreak` to talk to the host.",
+            privileged_instruction_name(tval).unwrap(),
+            at
+        ),
+        2 => format!(
+            "The value {:#010x} at {} is not an instruction this CPU understands. It may be data \
+             that the program ran into, or an instruction from an unsupported extension.",
+            tval, at
+        ),
+        3 => format!("The program stopped at an `ebreak` instruction at {}.", at),
+        4 => format!(
+            "You tried to load from address {:#x} at {}, but the address is not a multiple of \
+             the access size. Atomic instructions need naturally aligned addresses.",
+            tval, at
+        ),
+        5 if region_name(tval).is_some() => format!(
+            "You tried to load from address {:#x} at {}, but the {} doesn't allow it. Device \
+             registers only support some access sizes, and physical memory protection (PMP) can \
+             forbid reads.",
+            tval,
+            at,
+            region_name(tval).unwrap()
+        ),
+        5 => format!(
+            "You tried to load from address {:#x}{} at {}, but there is no memory or device at \
+             that address.",
+            tval,
+            null_note(tval),
+            at
+        ),
+        6 => format!(
+            "You tried to store to address {:#x} at {}, but the address is not a multiple of the \
+             access size. Atomic instructions need naturally aligned addresses.",
+            tval, at
+        ),
+        7 if region_name(tval).is_some() => format!(
+            "You tried to store to address {:#x} at {}, but the {} doesn't allow it. It may be \
+             read-only or support only some access sizes, or physical memory protection (PMP) \
+             forbids writes.",
+            tval,
+            at,
+            region_name(tval).unwrap()
+        ),
+        7 => format!(
+            "You tried to store to address {:#x}{} at {}, but there is no memory or device at \
+             that address, or it is read-only.",
+            tval,
+            null_note(tval),
+            at
+        ),
+        8 | 9 | 11 => format!(
+            "The program made an environment call (`ecall`) at {} from {} mode.",
+            at,
+            match cause {
+                8 => "user",
+                9 => "supervisor",
+                _ => "machine",
+            }
+        ),
+        12 => format!(
+            "The program jumped to address {:#x}{} which the page table doesn't map as \
+             executable.",
+            tval,
+            null_note(tval)
+        ),
+        13 => format!(
+            "You tried to load from address {:#x}{} at {}, but the page table doesn't map it as \
+             readable.",
+            tval,
+            null_note(tval),
+            at
+        ),
+        15 => format!(
+            "You tried to store to address {:#x}{} at {}, but the page table doesn't map it as \
+             writable.",
+            tval,
+            null_note(tval),
+            at
+        ),
+        _ => format!("An unknown trap (cause {:#x}) happened at {}.", cause, at),
+    }
+}
+
+/// Return a beginner-friendly explanation of an instruction that exists but isn't unlocked in the
+/// current level, raised at `pc`.
+pub fn explain_locked_instruction(mnemonic: &str, pc: u64, debug_info: &DebugInfo) -> String {
+    format!(
+        "The instruction `{}` at {} is not unlocked in this level. Solve it with the \
+         instructions the level allows.",
+        mnemonic,
+        debug_info.describe(pc)
+    )
+}
+
+/// Return a beginner-friendly explanation of an instruction that uses a register the current
+/// level doesn't allow. `register` is the name to print, e.g. "a0 (score)".
+pub fn explain_locked_register(
+    mnemonic: &str,
+    register: &str,
+    pc: u64,
+    debug_info: &DebugInfo,
+) -> String {
+    format!(
+        "The instruction `{}` at {} uses the register {}, which is not unlocked in this level. \
+         Use the registers the level allows.",
+        mnemonic,
+        debug_info.describe(pc),
+        register
+    )
+}
+
+/// Return a note to insert after an address that looks like a null pointer.
+fn null_note(addr: u64) -> &'static str {
+    match addr {
+        0 => " — a null pointer —",
+        a if a < NULL_PAGE_SIZE => " — just past a null pointer —",
+        _ => "",
+    }
+}
+
+/// Return true if `inst` is a CSR instruction (csrrw, csrrs, csrrc and their immediate forms).
+fn is_csr_instruction(inst: u64) -> bool {
+    let funct3 = (inst >> 12) & 0x7;
+    inst & 0x7f == 0x73 && funct3 != 0 && funct3 != 4
+}
+
+/// Return the name of `inst` if it's a privileged instruction in the SYSTEM opcode.
+fn privileged_instruction_name(inst: u64) -> Option<&'static str> {
+    if inst & 0x7f != 0x73 || (inst >> 12) & 0x7 != 0 {
+        return None;
+    }
+    match ((inst >> 20) & 0x1f, inst >> 25) {
+        (0x2, 0x8) => Some("sret"),
+        (0x2, 0x18) => Some("mret"),
+        (0x5, 0x8) => Some("wfi"),
+        (_, 0x9) => Some("sfence.vma"),
+        _ => None,
+    }
+}
@@ -0,0 +1,197 @@
+//! The expression module evaluates the constant expressions the assembler takes as immediates,
+//! e.g. `4*8+2`, `-(1 << 12)`, `'a'` or `%hi(0x12345678)`, so teaching material written for the
+//! GNU assembler assembles unmodified.
+//!
+//! The operators are those of C, with its precedence: unary `-`, `+` and `~`, then `*`, `/` and
+//! `%`, `+` and `-`, `<<` and `>>`, `&`, `^` and `|`. Numbers are decimal, or hexadecimal, binary
+//! or octal with a `0x`, `0b` or `0o` prefix. `%hi(value)` and `%lo(value)` split a value into
+//! the upper 20 bits and the sign-extended lower 12 bits that `lui` and `addi` add back up.
+
+use std::convert::TryFrom;
+
+/// The binary operators, from the loosest to the tightest binding.
+const LEVELS: [&[&str]; 6] = [
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+/// Evaluate the constant expression `text`. Intermediate values wrap at 128 bits, and the result
+/// must fit in 64 bits.
+pub fn evaluate(text: &str) -> Result<i64, String> {
+    let mut parser = Parser { text, rest: text };
+    let value = parser.binary(0)?;
+    if !parser.rest.trim().is_empty() {
+        return Err(parser.invalid());
+    }
+    if value < i64::MIN as i128 || value > i64::MAX as i128 {
+        return Err(format!("`{}` doesn't fit in 64 bits", text));
+    }
+    Ok(value as i64)
+}
+
+/// Return the character the escape `\c` of a string or character literal stands for: `\n`, `\t`,
+/// `\r`, `\0`, `\\`, `\"` or `\'`.
+pub fn unescape(c: char) -> Result<char, String> {
+    Ok(match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' | '"' | '\'' => c,
+        c => return Err(format!("unknown escape `\\{}`", c)),
+    })
+}
+
+/// Return the upper 20 bits of `value` for a `lui`, rounded so that adding `lo(value)` gives
+/// `value` back.
+fn hi(value: i128) -> i128 {
+    value.wrapping_add(0x800) >> 12 & 0xf_ffff
+}
+
+/// Return the lower 12 bits of `value`, sign-extended.
+fn lo(value: i128) -> i128 {
+    ((value & 0xfff) ^ 0x800) - 0x800
+}
+
+/// A recursive descent parser over the part of an expression not parsed yet.
+struct Parser<'a> {
+    /// The whole expression.
+    text: &'a str,
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn invalid(&self) -> String {
+        format!("invalid expression `{}`", self.text)
+    }
+
+    /// Skip the whitespace and `token` if the rest starts with it. Returns true if it did.
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Parse the operands of the binary operators of `LEVELS[level]` and the operators between
+    /// them.
+    fn binary(&mut self, level: usize) -> Result<i128, String> {
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut value = self.binary(level + 1)?;
+        'operators: loop {
+            for operator in LEVELS[level].iter() {
+                if self.eat(operator) {
+                    let rhs = self.binary(level + 1)?;
+                    value = self.apply(operator, value, rhs)?;
+                    continue 'operators;
+                }
+            }
+            return Ok(value);
+        }
+    }
+
+    fn apply(&self, operator: &str, lhs: i128, rhs: i128) -> Result<i128, String> {
+        let shift = || match u32::try_from(rhs) {
+            Ok(shift) if shift < 64 => Ok(shift),
+            _ => Err(format!("invalid shift by {} in `{}`", rhs, self.text)),
+        };
+        let division_by_zero = || format!("division by zero in `{}`", self.text);
+        Ok(match operator {
+            "|" => lhs | rhs,
+            "^" => lhs ^ rhs,
+            "&" => lhs & rhs,
+            "<<" => lhs.wrapping_shl(shift()?),
+            ">>" => lhs >> shift()?,
+            "+" => lhs.wrapping_add(rhs),
+            "-" => lhs.wrapping_sub(rhs),
+            "*" => lhs.wrapping_mul(rhs),
+            "/" => lhs.checked_div(rhs).ok_or_else(division_by_zero)?,
+            _ => lhs.checked_rem(rhs).ok_or_else(division_by_zero)?,
+        })
+    }
+
+    fn unary(&mut self) -> Result<i128, String> {
+        if self.eat("-") {
+            Ok(self.unary()?.wrapping_neg())
+        } else if self.eat("+") {
+            self.unary()
+        } else if self.eat("~") {
+            Ok(!self.unary()?)
+        } else {
+            self.primary()
+        }
+    }
+
+    /// Parse a number, a character literal, or an expression in parentheses or in `%hi` or `%lo`.
+    fn primary(&mut self) -> Result<i128, String> {
+        let split: fn(i128) -> i128 = if self.eat("%hi(") {
+            hi
+        } else if self.eat("%lo(") {
+            lo
+        } else if self.eat("(") {
+            |value| value
+        } else if self.eat("'") {
+            return self.character();
+        } else {
+            return self.number();
+        };
+        let value = self.binary(0)?;
+        if !self.eat(")") {
+            return Err(self.invalid());
+        }
+        Ok(split(value))
+    }
+
+    fn number(&mut self) -> Result<i128, String> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        if word.is_empty() {
+            return Err(self.invalid());
+        }
+        self.rest = rest;
+        number(word)
+    }
+
+    /// Parse the rest of a character literal after its opening quote, e.g. `a'` or `\n'`.
+    fn character(&mut self) -> Result<i128, String> {
+        let mut chars = self.rest.chars();
+        let c = match chars.next() {
+            Some('\\') => unescape(chars.next().ok_or_else(|| self.invalid())?)?,
+            Some(c) if c != '\'' => c,
+            _ => return Err(self.invalid()),
+        };
+        if chars.next() != Some('\'') {
+            return Err(self.invalid());
+        }
+        self.rest = chars.as_str();
+        Ok(c as i128)
+    }
+}
+
+/// Parse the unsigned number `word`.
+fn number(word: &str) -> Result<i128, String> {
+    let lower = word.to_ascii_lowercase();
+    let (radix, digits) = if let Some(digits) = lower.strip_prefix("0x") {
+        (16, digits)
+    } else if let Some(digits) = lower.strip_prefix("0b") {
+        (2, digits)
+    } else if let Some(digits) = lower.strip_prefix("0o") {
+        (8, digits)
+    } else {
+        (10, lower.as_str())
+    };
+    i128::from_str_radix(digits, radix).map_err(|_| format!("invalid number `{}`", word))
+}
@@ -0,0 +1,91 @@
+//! The guest assert module lets guest programs check their own state with an environment call,
+//! so a lesson can be written as a program whose asserts the learner has to make pass. a7 holds
+//! `SYS_ASSERT`, a0 the condition, a1 the address of a NUL-terminated message, or 0 for none, and
+//! a2 the line number of the assert in the source.
+//!
+//! A passing assert is counted and the program continues right after the `ecall`. A failing one
+//! is recorded and stops the run with `StopReason::GuestAssertionFailed`.
+
+/// The environment call asserting that a0 isn't zero.
+pub const SYS_ASSERT: u64 = 0x7e3;
+
+/// A failed assert of the guest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AssertionFailure {
+    /// The address of the `ecall`.
+    pub pc: u64,
+    /// The line number passed in a2.
+    pub line: u64,
+    /// The message at the address in a1, or `None` if there is none or it isn't in DRAM.
+    pub message: Option<String>,
+}
+
+impl AssertionFailure {
+    /// Return the line shown to the learner, e.g. `line 12: sum is wrong`.
+    pub fn render(&self) -> String {
+        match &self.message {
+            Some(message) => format!("line {}: {}", self.line, message),
+            None => format!("line {}: assertion failed at {:#x}", self.line, self.pc),
+        }
+    }
+}
+
+/// The results of the asserts of the guest. Asserts are only served while it's enabled;
+/// otherwise their `ecall` goes to the host like any other.
+#[derive(Debug, Default, Clone)]
+pub struct GuestAsserts {
+    enabled: bool,
+    passed: u64,
+    failed: u64,
+    last_failure: Option<AssertionFailure>,
+}
+
+impl GuestAsserts {
+    /// Create a disabled, empty record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop serving asserts.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if asserts are served.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Count a passing assert.
+    pub fn pass(&mut self) {
+        self.passed += 1;
+    }
+
+    /// Record a failing assert.
+    pub fn fail(&mut self, failure: AssertionFailure) {
+        self.failed += 1;
+        self.last_failure = Some(failure);
+    }
+
+    /// Return the number of asserts that passed.
+    pub fn passed(&self) -> u64 {
+        self.passed
+    }
+
+    /// Return the number of asserts that failed.
+    pub fn failed(&self) -> u64 {
+        self.failed
+    }
+
+    /// Return the most recent failure, if any.
+    pub fn last_failure(&self) -> Option<&AssertionFailure> {
+        self.last_failure.as_ref()
+    }
+
+    /// Forget the counts and the failure, e.g. before the learner runs the program again.
+    pub fn clear(&mut self) {
+        self.passed = 0;
+        self.failed = 0;
+        self.last_failure = None;
+    }
+}
@@ -0,0 +1,307 @@
+//! The harts module runs several harts on one emulator for the concurrency levels. The harts
+//! share the memory and take turns in short bursts of instructions. A scheduler derives the
+//! order and the length of the bursts from a seed, so the same seed always gives the same
+//! interleaving: a race that shows up once can be replayed, and a solution can be graded over a
+//! fixed set of seeds.
+
+use crate::bus::{DRAM_BASE, DRAM_END};
+use crate::context::HartContext;
+use crate::cpu::Mode;
+use crate::emulator::Emulator;
+use crate::race::{DataRace, RaceDetector};
+use crate::run::{RunSummary, StopReason};
+use crate::trace::AccessKind;
+
+/// The longest burst a hart runs before the scheduler picks again, by default.
+pub const DEFAULT_MAX_BURST: u64 = 8;
+
+/// The state of a hart in multi-hart mode.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum HartState {
+    /// The hart is scheduled.
+    Runnable = 0,
+    /// The hart called `exit`.
+    Exited = 1,
+    /// An instruction of the hart raised an exception or executed `ebreak`.
+    Trapped = 2,
+    /// The hart used up its quota of instructions.
+    OutOfSteps = 3,
+}
+
+/// A pseudorandom scheduler. It's a SplitMix64 generator, so every seed is usable and the
+/// interleaving only depends on the seed.
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    seed: u64,
+    state: u64,
+    max_burst: u64,
+}
+
+impl Scheduler {
+    /// Create a scheduler whose bursts are 1 to `max_burst` instructions long.
+    pub fn new(seed: u64, max_burst: u64) -> Self {
+        Self {
+            seed,
+            state: seed,
+            max_burst: max_burst.max(1),
+        }
+    }
+
+    /// Return the seed of the scheduler.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start over from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.state = seed;
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Pick one of the `runnable` harts and the length of its burst.
+    pub fn pick(&mut self, runnable: &[usize]) -> (usize, u64) {
+        let hart = runnable[(self.next() % runnable.len() as u64) as usize];
+        let burst = 1 + self.next() % self.max_burst;
+        (hart, burst)
+    }
+}
+
+/// A burst of instructions run by a hart.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Burst {
+    /// The hart that ran.
+    pub hart: usize,
+    /// The number of instructions it retired.
+    pub steps: u64,
+}
+
+/// A hart, its state and where it started.
+#[derive(Debug)]
+struct Hart {
+    context: HartContext,
+    state: HartState,
+    pc: u64,
+    sp: u64,
+}
+
+impl Hart {
+    /// Create the context of the hart `hartid` at power-on: its ID is in `a0`.
+    fn context(hartid: usize, pc: u64, sp: u64) -> HartContext {
+        let mut context = HartContext::new(pc, sp, Mode::Machine);
+        context.hartid = hartid as u64;
+        context.xregs.write(10, hartid as u64);
+        context
+    }
+}
+
+/// Why a multi-hart run stopped.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct HartsRun {
+    /// The number of instructions retired by all harts.
+    pub steps: u64,
+    /// The hart that stopped the run and the summary of its last burst. `None` if the step
+    /// limit was reached or no hart is runnable.
+    pub stop: Option<(usize, RunSummary)>,
+}
+
+/// Harts sharing one emulator, scheduled by a seeded scheduler.
+#[derive(Debug)]
+pub struct Harts {
+    harts: Vec<Hart>,
+    scheduler: Scheduler,
+    schedule: Vec<Burst>,
+    /// The memory restored by `restart`, and its address.
+    memory: Option<(u64, Vec<u8>)>,
+    /// The race detector, if enabled.
+    races: Option<RaceDetector>,
+}
+
+/// Return true if the instruction at `pc` is atomic (`lr`, `sc` or an AMO).
+fn is_atomic(emu: &Emulator, pc: u64) -> bool {
+    if pc < DRAM_BASE || pc.saturating_add(4) > DRAM_END {
+        return false;
+    }
+    emu.cpu.bus.read_dram(pc, 4)[0] & 0x7f == 0x2f
+}
+
+impl Harts {
+    /// Create a multi-hart mode without harts, scheduled by `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            harts: Vec::new(),
+            scheduler: Scheduler::new(seed, DEFAULT_MAX_BURST),
+            schedule: Vec::new(),
+            memory: None,
+            races: None,
+        }
+    }
+
+    /// Change the longest burst. 1 switches harts after every instruction.
+    pub fn set_max_burst(&mut self, max_burst: u64) {
+        self.scheduler = Scheduler::new(self.scheduler.seed(), max_burst);
+    }
+
+    /// Add a hart starting at `pc` with its stack pointer at `sp`, and return its ID. The hart
+    /// runs in machine mode with its ID in `mhartid` and `a0`.
+    pub fn add_hart(&mut self, pc: u64, sp: u64) -> usize {
+        let hartid = self.harts.len();
+        self.harts.push(Hart {
+            context: Hart::context(hartid, pc, sp),
+            state: HartState::Runnable,
+            pc,
+            sp,
+        });
+        hartid
+    }
+
+    /// Return the number of harts.
+    pub fn len(&self) -> usize {
+        self.harts.len()
+    }
+
+    /// Return true if there's no hart.
+    pub fn is_empty(&self) -> bool {
+        self.harts.is_empty()
+    }
+
+    /// Return the state of the hart `hartid`.
+    pub fn state(&self, hartid: usize) -> Option<HartState> {
+        self.harts.get(hartid).map(|hart| hart.state)
+    }
+
+    /// Return the saved context of the hart `hartid`. It's up to date whenever `run` isn't
+    /// running.
+    pub fn context_mut(&mut self, hartid: usize) -> Option<&mut HartContext> {
+        self.harts.get_mut(hartid).map(|hart| &mut hart.context)
+    }
+
+    /// Return the seed of the scheduler.
+    pub fn seed(&self) -> u64 {
+        self.scheduler.seed()
+    }
+
+    /// Return the bursts run since the last restart, in order.
+    pub fn schedule(&self) -> &[Burst] {
+        &self.schedule
+    }
+
+    /// Start or stop detecting data races between the harts. Stopping forgets the races found.
+    pub fn set_race_detection(&mut self, enable: bool) {
+        self.races = if enable {
+            Some(RaceDetector::new())
+        } else {
+            None
+        };
+    }
+
+    /// Return the data races found since the last restart, or nothing if detection is off.
+    pub fn races(&self) -> &[DataRace] {
+        match &self.races {
+            Some(detector) => detector.races(),
+            None => &[],
+        }
+    }
+
+    /// Save the `size` bytes of DRAM at `base`, e.g. the program and its data, so `restart`
+    /// restores them. Returns false if they aren't in DRAM.
+    pub fn save_memory(&mut self, emu: &Emulator, base: u64, size: u64) -> bool {
+        if base < DRAM_BASE || base.saturating_add(size) > DRAM_END {
+            return false;
+        }
+        self.memory = Some((base, emu.cpu.bus.read_dram(base, size).to_vec()));
+        true
+    }
+
+    /// Put every hart back at its start, restore the saved memory, and schedule with `seed`
+    /// from now on. Running again then replays the interleaving of `seed` exactly.
+    pub fn restart(&mut self, emu: &mut Emulator, seed: u64) {
+        for (hartid, hart) in self.harts.iter_mut().enumerate() {
+            let quota = hart.context.quota();
+            hart.context = Hart::context(hartid, hart.pc, hart.sp);
+            hart.context.set_quota(quota);
+            hart.state = HartState::Runnable;
+        }
+        if let Some((base, bytes)) = &self.memory {
+            emu.cpu.bus.write_dram(*base, bytes);
+        }
+        self.scheduler.reseed(seed);
+        self.schedule.clear();
+        if let Some(detector) = self.races.as_mut() {
+            detector.clear();
+        }
+    }
+
+    /// Run the harts for up to `max_steps` steps in total. The run stops early when a
+    /// hart needs the host: it made an environment call, hit a watchpoint, exited, or trapped.
+    /// A hart that exits, traps or uses up its quota isn't scheduled anymore.
+    pub fn run(&mut self, emu: &mut Emulator, max_steps: u64) -> HartsRun {
+        let mut steps = 0;
+        let mut stop = None;
+        // Idle harts take steps without retiring instructions, so the limit counts steps.
+        let mut scheduled = 0;
+        emu.cpu.access_log = Some(Vec::new());
+        while scheduled < max_steps {
+            let runnable = (0..self.harts.len())
+                .filter(|&i| self.harts[i].state == HartState::Runnable)
+                .collect::<Vec<usize>>();
+            if runnable.is_empty() {
+                break;
+            }
+            let (hartid, burst) = self.scheduler.pick(&runnable);
+
+            let burst = burst.min(max_steps - scheduled);
+            scheduled += burst;
+            let summary = self.harts[hartid].context.run(emu, burst);
+            steps += summary.steps;
+            self.schedule.push(Burst {
+                hart: hartid,
+                steps: summary.steps,
+            });
+
+            let accesses = emu.cpu.access_log.replace(Vec::new()).unwrap_or_default();
+            if let Some(detector) = self.races.as_mut() {
+                for access in &accesses {
+                    detector.access(hartid, access, is_atomic(emu, access.pc));
+                }
+            }
+            // A store breaks the reservations of the other harts on the same address.
+            let stored = accesses
+                .iter()
+                .filter(|access| access.kind == AccessKind::Store)
+                .map(|access| access.addr)
+                .collect::<Vec<u64>>();
+            if !stored.is_empty() {
+                for (i, other) in self.harts.iter_mut().enumerate() {
+                    if i != hartid {
+                        other.context.drop_reservations(&stored);
+                    }
+                }
+            }
+
+            let hart = &mut self.harts[hartid];
+            match summary.reason {
+                StopReason::StepLimit => continue,
+                StopReason::LimitExceeded if hart.context.is_exhausted() => {
+                    hart.state = HartState::OutOfSteps;
+                    continue;
+                }
+                StopReason::Exited => hart.state = HartState::Exited,
+                StopReason::Trapped | StopReason::Breakpoint => hart.state = HartState::Trapped,
+                _ => {}
+            }
+            stop = Some((hartid, summary));
+            break;
+        }
+        emu.cpu.access_log = None;
+        HartsRun { steps, stop }
+    }
+}
@@ -0,0 +1,195 @@
+//! The history module keeps the rewind checkpoints of an emulator: snapshots taken as the game
+//! goes, which the player can rewind to. The checkpoints and the logs of the traces share a
+//! storage budget, so a long play session doesn't grow memory without bound.
+//!
+//! When they exceed it, the least recently used checkpoints are evicted first, a checkpoint being
+//! used when it's taken or rewound to. If that isn't enough, the oldest entries of the memory
+//! access trace and of the logs of CSR writes, atomic instructions and privilege returns are
+//! evicted, in that order. Every eviction is counted and reported to the eviction callback, if
+//! any, so the host can tell the player the history was truncated.
+
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+use crate::atomics::AtomicEvent;
+use crate::csr_trace::CsrWrite;
+use crate::emulator::Emulator;
+use crate::memory_usage;
+use crate::snapshot;
+use crate::trace::MemoryAccess;
+use crate::trap_return::TrapReturn;
+
+/// The callback told of the evictions done to fit in the budget at once, with the number of
+/// checkpoints and of trace entries evicted.
+pub type EvictionCallback = Box<dyn FnMut(u64, u64) + Send>;
+
+/// A rewind checkpoint.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// The id of the checkpoint. Ids count up from 1 and are never reused.
+    pub id: u64,
+    /// The number of instructions the emulator had retired when the checkpoint was taken.
+    pub retired: u64,
+    blob: Vec<u8>,
+    /// When the checkpoint was last used, in uses of any checkpoint.
+    last_used: u64,
+}
+
+impl Checkpoint {
+    /// Return the bytes of its snapshot.
+    pub fn size(&self) -> u64 {
+        self.blob.len() as u64
+    }
+}
+
+/// The rewind checkpoints, oldest first, and the storage budget.
+#[derive(Debug, Default, Clone)]
+pub struct History {
+    budget: Option<u64>,
+    checkpoints: VecDeque<Checkpoint>,
+    next_id: u64,
+    uses: u64,
+    truncated: u64,
+}
+
+impl History {
+    /// Create an empty history without a budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the storage budget in bytes, if any.
+    pub fn budget(&self) -> Option<u64> {
+        self.budget
+    }
+
+    /// Return the checkpoints from the oldest to the newest.
+    pub fn checkpoints(&self) -> impl Iterator<Item = &Checkpoint> {
+        self.checkpoints.iter()
+    }
+
+    /// Return the bytes of the snapshots of the checkpoints.
+    pub fn size(&self) -> u64 {
+        self.checkpoints.iter().map(Checkpoint::size).sum()
+    }
+
+    /// Return the number of checkpoints and trace entries evicted since the last call, e.g. to
+    /// tell the player once a frame that the history was truncated.
+    pub fn take_truncated(&mut self) -> u64 {
+        std::mem::take(&mut self.truncated)
+    }
+
+    fn use_count(&mut self) -> u64 {
+        self.uses += 1;
+        self.uses
+    }
+
+    /// Evict the least recently used checkpoint. Returns false if there's none.
+    fn evict_checkpoint(&mut self) -> bool {
+        let lru = self
+            .checkpoints
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, checkpoint)| checkpoint.last_used)
+            .map(|(index, _)| index);
+        match lru {
+            Some(index) => {
+                self.checkpoints.remove(index);
+                self.truncated += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Set the storage budget of the checkpoints and traces of `emu` to `bytes`, or remove it, and
+/// evict what doesn't fit.
+pub fn set_budget(emu: &mut Emulator, bytes: Option<u64>) {
+    emu.history.budget = bytes;
+    enforce_budget(emu);
+}
+
+/// Call `callback` whenever checkpoints or trace entries of `emu` are evicted to fit in its
+/// budget, or remove it if `None`.
+pub fn set_eviction_callback(emu: &mut Emulator, callback: Option<EvictionCallback>) {
+    emu.eviction_callback = callback;
+}
+
+/// Take a checkpoint of `emu` and return its id. Older checkpoints are evicted if it doesn't fit
+/// in the budget, and it is too if it doesn't fit alone.
+pub fn checkpoint(emu: &mut Emulator) -> u64 {
+    let blob = snapshot::save(emu);
+    let history = &mut emu.history;
+    history.next_id += 1;
+    let checkpoint = Checkpoint {
+        id: history.next_id,
+        retired: emu.retired,
+        blob,
+        last_used: history.use_count(),
+    };
+    history.checkpoints.push_back(checkpoint);
+    enforce_budget(emu);
+    emu.history.next_id
+}
+
+/// Restore `emu` to the checkpoint `id`. The checkpoints stay, so it can be rewound to any of them
+/// again. Fails if there's no such checkpoint, e.g. because it was evicted.
+pub fn rewind(emu: &mut Emulator, id: u64) -> Result<(), String> {
+    let history = &mut emu.history;
+    let index = history
+        .checkpoints
+        .iter()
+        .position(|checkpoint| checkpoint.id == id)
+        .ok_or_else(|| format!("checkpoint {} was evicted or never taken", id))?;
+    history.checkpoints[index].last_used = history.use_count();
+    // Lend the snapshot to restore from, so it isn't copied.
+    let blob = std::mem::take(&mut history.checkpoints[index].blob);
+    let restored = snapshot::restore(emu, &blob);
+    emu.history.checkpoints[index].blob = blob;
+    restored
+}
+
+/// Evict checkpoints, then trace entries, until the checkpoints and traces of `emu` fit in its
+/// budget.
+pub fn enforce_budget(emu: &mut Emulator) {
+    let budget = match emu.history.budget {
+        Some(budget) => budget,
+        None => return,
+    };
+    let stored = |emu: &Emulator| {
+        let usage = memory_usage::measure(emu);
+        usage.snapshots + usage.traces
+    };
+    let mut checkpoints = 0;
+    while stored(emu) > budget {
+        if !emu.history.evict_checkpoint() {
+            break;
+        }
+        checkpoints += 1;
+    }
+
+    let mut excess = stored(emu).saturating_sub(budget);
+    let mut evicted = 0;
+    // Return how many of the oldest of `len` entries of `size` bytes make up for the excess.
+    let mut count = |len: usize, size: usize| {
+        let count = (excess as usize).div_ceil(size).min(len);
+        excess = excess.saturating_sub((count * size) as u64);
+        evicted += count as u64;
+        count
+    };
+    let accesses = count(emu.cpu.access_trace.entries().len(), size_of::<MemoryAccess>());
+    emu.cpu.access_trace.take_oldest(accesses);
+    let writes = count(emu.csr_trace.writes().len(), size_of::<CsrWrite>());
+    emu.csr_trace.take_oldest(writes);
+    let events = count(emu.atomic_events.events().len(), size_of::<AtomicEvent>());
+    emu.atomic_events.take_oldest(events);
+    let returns = count(emu.trap_returns.events().len(), size_of::<TrapReturn>());
+    emu.trap_returns.take_oldest(returns);
+    emu.history.truncated += evicted;
+    if checkpoints + evicted > 0 {
+        if let Some(callback) = emu.eviction_callback.as_mut() {
+            callback(checkpoints, evicted);
+        }
+    }
+}
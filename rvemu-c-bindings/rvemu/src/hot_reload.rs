@@ -0,0 +1,75 @@
+//! The hot_reload module replaces the text of the running program, the program loaded at the
+//! start of DRAM, with an edited version, for an "edit and continue" flow. The rest of memory,
+//! the registers and the devices are kept, so the player doesn't lose the state the program built
+//! up. If the symbol table of the new text is known, the program counter moves to the same offset
+//! in the same symbol of the new text.
+//!
+//! Only the program counter is remapped. Return addresses and other code pointers the program
+//! holds keep pointing where the old text had its code.
+
+use crate::bus::{DRAM_BASE, DRAM_END};
+use crate::debug_info::DebugInfo;
+use crate::emulator::Emulator;
+use crate::injection;
+
+/// Replace the text of the program running on `emu` with `image`. If `debug_info`, the symbol
+/// table of `image` at its address in DRAM, is given, it replaces the debug info of `emu` and the
+/// program counter is remapped through it. The part of the old text past the end of `image` is
+/// zeroed. Fails without changing anything if `image` doesn't fit in DRAM or the program counter
+/// can't be remapped.
+pub fn reload_text(
+    emu: &mut Emulator,
+    image: &[u8],
+    debug_info: Option<DebugInfo>,
+) -> Result<(), String> {
+    let pc = match &debug_info {
+        Some(debug_info) => remap_pc(emu, debug_info, image.len() as u64)?,
+        None => emu.cpu.pc,
+    };
+
+    let end = DRAM_BASE + image.len() as u64;
+    if end > DRAM_END {
+        return Err(format!("{:#x}..{:#x} is outside DRAM", DRAM_BASE, end));
+    }
+    // The injected instructions belong to the old text.
+    injection::restore_all(emu);
+    emu.write_dram(DRAM_BASE, image)?;
+    let len = image.len() as u64;
+    let old_len = emu.image.len() as u64;
+    if old_len > len {
+        let stale = vec![0; (old_len - len) as usize];
+        emu.write_dram(DRAM_BASE + len, &stale)?;
+    }
+    emu.image = image.to_vec();
+    emu.cpu.pc = pc;
+    if let Some(debug_info) = debug_info {
+        emu.debug_info = debug_info;
+    }
+    Ok(())
+}
+
+/// Return where the program counter of `emu` goes in the new text of `len` bytes with the symbol
+/// table `debug_info`. It stays where it is if it's outside the old text, e.g. in a trap handler
+/// loaded elsewhere.
+fn remap_pc(emu: &Emulator, debug_info: &DebugInfo, len: u64) -> Result<u64, String> {
+    let pc = emu.cpu.pc;
+    if pc < DRAM_BASE || pc >= DRAM_BASE + emu.image.len() as u64 {
+        return Ok(pc);
+    }
+
+    let (name, offset) = emu
+        .debug_info
+        .symbol_at(pc)
+        .ok_or_else(|| format!("no symbol of the old text contains the pc {:#x}", pc))?;
+    let start = debug_info
+        .address_of(name)
+        .ok_or_else(|| format!("the pc is in `{}`, which the new text doesn't have", name))?;
+    let remapped = start + offset;
+    if remapped < DRAM_BASE || remapped >= DRAM_BASE + len {
+        return Err(format!(
+            "the pc would move to {:#x} in `{}`, outside the new text",
+            remapped, name
+        ));
+    }
+    Ok(remapped)
+}
@@ -0,0 +1,163 @@
+//! The injection module temporarily replaces instructions of the program in DRAM, e.g. with an
+//! `ebreak` for a software breakpoint or with a NOP sled to skip over code, and restores them
+//! later. The original bytes are kept aside, so whatever shows memory to the player, e.g. the
+//! disassembly view, reads them through `visible_bytes` and never sees the injected instructions.
+//! The CPU fetches every instruction from memory, so it executes an injection from the next step.
+
+use std::collections::BTreeMap;
+
+use crate::emulator::Emulator;
+
+/// `ebreak`
+const EBREAK: u32 = 0x0010_0073;
+/// `c.ebreak`
+const C_EBREAK: u16 = 0x9002;
+/// `addi zero, zero, 0`
+const NOP: u32 = 0x0000_0013;
+/// `c.nop`
+const C_NOP: u16 = 0x0001;
+
+/// The injected instructions of an emulator: the original bytes they replaced, by address.
+#[derive(Debug, Default, Clone)]
+pub struct Injections {
+    originals: BTreeMap<u64, Vec<u8>>,
+}
+
+impl Injections {
+    /// Create an empty set of injections.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if nothing is injected.
+    pub fn is_empty(&self) -> bool {
+        self.originals.is_empty()
+    }
+
+    /// Return true if an injection starts at `addr`.
+    pub fn contains(&self, addr: u64) -> bool {
+        self.originals.contains_key(&addr)
+    }
+
+    /// Return the start addresses of the injections in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &u64> {
+        self.originals.keys()
+    }
+
+    /// Return true if an injection overlaps the `len` bytes at `addr`.
+    fn overlaps(&self, addr: u64, len: u64) -> bool {
+        let end = addr.saturating_add(len);
+        match self.originals.range(..end).next_back() {
+            Some((start, bytes)) => start + bytes.len() as u64 > addr,
+            None => false,
+        }
+    }
+
+    /// Remove the injections overlapping the `len` bytes at `addr` and return them with their
+    /// start addresses.
+    fn take_overlapping(&mut self, addr: u64, len: u64) -> Vec<(u64, Vec<u8>)> {
+        let end = addr.saturating_add(len);
+        let starts = self
+            .originals
+            .range(..end)
+            .filter(|(&start, original)| start + original.len() as u64 > addr)
+            .map(|(&start, _)| start)
+            .collect::<Vec<u64>>();
+        starts
+            .into_iter()
+            .filter_map(|start| self.originals.remove_entry(&start))
+            .collect()
+    }
+
+    /// Copy the original bytes of the injections overlapping `bytes`, read from `addr`, into it.
+    fn hide(&self, addr: u64, bytes: &mut [u8]) {
+        let end = addr + bytes.len() as u64;
+        for (&start, original) in self.originals.range(..end) {
+            for (i, &byte) in original.iter().enumerate() {
+                let at = start + i as u64;
+                if at >= addr && at < end {
+                    bytes[(at - addr) as usize] = byte;
+                }
+            }
+        }
+    }
+}
+
+/// Replace the instruction at `addr` with `ebreak`, or `c.ebreak` if it's compressed, so the
+/// program stops with a breakpoint exception when it reaches it. Fails if `addr` isn't in DRAM
+/// or something is already injected there.
+pub fn inject_ebreak(emu: &mut Emulator, addr: u64) -> Result<(), String> {
+    let half = emu
+        .dram_bytes(addr, 2)
+        .ok_or_else(|| format!("{:#x} is outside DRAM", addr))?;
+    if half[0] & 0b11 == 0b11 {
+        inject(emu, addr, &EBREAK.to_le_bytes())
+    } else {
+        inject(emu, addr, &C_EBREAK.to_le_bytes())
+    }
+}
+
+/// Replace the `len` bytes at `addr` with a NOP sled: `nop`s, and a `c.nop` if `len` isn't a
+/// multiple of 4. Fails if `len` is 0 or odd, the bytes aren't in DRAM or something is already
+/// injected there.
+pub fn inject_nops(emu: &mut Emulator, addr: u64, len: u64) -> Result<(), String> {
+    if len == 0 || len % 2 == 1 {
+        return Err(format!("a NOP sled can't be {} bytes long", len));
+    }
+    let mut sled = NOP.to_le_bytes().repeat((len / 4) as usize);
+    if len % 4 == 2 {
+        sled.extend_from_slice(&C_NOP.to_le_bytes());
+    }
+    inject(emu, addr, &sled)
+}
+
+/// Write `bytes` at `addr` and keep the bytes they replace.
+fn inject(emu: &mut Emulator, addr: u64, bytes: &[u8]) -> Result<(), String> {
+    let len = bytes.len() as u64;
+    if emu.injections.overlaps(addr, len) {
+        return Err(format!("{:#x} already has an injected instruction", addr));
+    }
+    let original = emu
+        .dram_bytes(addr, len)
+        .ok_or_else(|| format!("{:#x}..{:#x} is outside DRAM", addr, addr + len))?
+        .to_vec();
+    emu.write_dram(addr, bytes)?;
+    emu.injections.originals.insert(addr, original);
+    Ok(())
+}
+
+/// Put back the original bytes of the injection starting at `addr`. Fails if there's none.
+pub fn restore(emu: &mut Emulator, addr: u64) -> Result<(), String> {
+    let original = emu
+        .injections
+        .originals
+        .remove(&addr)
+        .ok_or_else(|| format!("nothing is injected at {:#x}", addr))?;
+    emu.write_dram(addr, &original)
+}
+
+/// Remove the injections overlapping the `len` bytes at `addr` in DRAM before they're overwritten,
+/// putting their original bytes back, so an injection never shows or restores bytes that were
+/// replaced under it, e.g. by a patch or a newly loaded program.
+pub(crate) fn remove_overlapping(emu: &mut Emulator, addr: u64, len: u64) {
+    for (start, original) in emu.injections.take_overlapping(addr, len) {
+        emu.cpu.bus.write_dram(start, &original);
+    }
+}
+
+/// Put back the original bytes of every injection.
+pub fn restore_all(emu: &mut Emulator) {
+    let originals = std::mem::take(&mut emu.injections.originals);
+    for (addr, original) in originals {
+        // They were read from DRAM, so they fit.
+        let _ = emu.write_dram(addr, &original);
+    }
+}
+
+/// Return the `len` bytes at `addr` as the program has them, with the original bytes in place of
+/// the injected instructions, or `None` if they aren't all in DRAM.
+pub fn visible_bytes(emu: &Emulator, addr: u64, len: u64) -> Option<Vec<u8>> {
+    let mut bytes = emu.dram_bytes(addr, len)?.to_vec();
+    emu.injections.hide(addr, &mut bytes);
+    Some(bytes)
+}
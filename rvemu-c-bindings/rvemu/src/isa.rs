@@ -0,0 +1,306 @@
+//! The isa module names instructions and restricts which of them the CPU executes. Game levels
+//! use it to unlock the instruction set step by step, e.g. a first level with only `addi`,
+//! `add` and `beq`.
+
+use std::collections::BTreeSet;
+
+use crate::cpu::{ABI_NAMES, REGISTERS_COUNT};
+
+/// Return the index of an integer register from its name: `x0`-`x31`, an ABI name, or `fp`.
+pub fn register_index(name: &str) -> Option<u64> {
+    if name == "fp" {
+        return Some(8);
+    }
+    if let Some(index) = ABI_NAMES.iter().position(|&abi| abi == name) {
+        return Some(index as u64);
+    }
+    let digits = name.strip_prefix('x')?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match digits.parse::<usize>() {
+        Ok(index) if index < REGISTERS_COUNT => Some(index as u64),
+        _ => None,
+    }
+}
+
+/// The mnemonics `mnemonic` returns, in the order of their opcodes.
+pub const MNEMONICS: &[&str] = &[
+    "lui",
+    "auipc",
+    "jal",
+    "jalr",
+    "beq",
+    "bne",
+    "blt",
+    "bge",
+    "bltu",
+    "bgeu",
+    "lb",
+    "lh",
+    "lw",
+    "ld",
+    "lbu",
+    "lhu",
+    "lwu",
+    "sb",
+    "sh",
+    "sw",
+    "sd",
+    "addi",
+    "slli",
+    "slti",
+    "sltiu",
+    "xori",
+    "srli",
+    "srai",
+    "ori",
+    "andi",
+    "addiw",
+    "slliw",
+    "srliw",
+    "sraiw",
+    "add",
+    "sub",
+    "sll",
+    "slt",
+    "sltu",
+    "xor",
+    "srl",
+    "sra",
+    "or",
+    "and",
+    "mul",
+    "mulh",
+    "mulhsu",
+    "mulhu",
+    "div",
+    "divu",
+    "rem",
+    "remu",
+    "addw",
+    "subw",
+    "sllw",
+    "srlw",
+    "sraw",
+    "mulw",
+    "divw",
+    "divuw",
+    "remw",
+    "remuw",
+    "fence",
+    "fence.i",
+    "ecall",
+    "ebreak",
+    "sret",
+    "mret",
+    "wfi",
+    "sfence.vma",
+    "csrrw",
+    "csrrs",
+    "csrrc",
+    "csrrwi",
+    "csrrsi",
+    "csrrci",
+];
+
+/// Return the mnemonic of a 32-bit instruction of RV64IM, Zicsr, Zifencei, or the privileged
+/// architecture. Returns `None` for other instructions, including compressed ones.
+pub fn mnemonic(inst: u64) -> Option<&'static str> {
+    let opcode = inst & 0x7f;
+    let funct3 = (inst >> 12) & 0x7;
+    let funct7 = (inst >> 25) & 0x7f;
+
+    let name = match opcode {
+        0x37 => "lui",
+        0x17 => "auipc",
+        0x6f => "jal",
+        0x67 if funct3 == 0 => "jalr",
+        0x63 => match funct3 {
+            0 => "beq",
+            1 => "bne",
+            4 => "blt",
+            5 => "bge",
+            6 => "bltu",
+            7 => "bgeu",
+            _ => return None,
+        },
+        0x03 => match funct3 {
+            0 => "lb",
+            1 => "lh",
+            2 => "lw",
+            3 => "ld",
+            4 => "lbu",
+            5 => "lhu",
+            6 => "lwu",
+            _ => return None,
+        },
+        0x23 => match funct3 {
+            0 => "sb",
+            1 => "sh",
+            2 => "sw",
+            3 => "sd",
+            _ => return None,
+        },
+        0x13 => match (funct3, funct7 >> 1) {
+            (0, _) => "addi",
+            (1, 0) => "slli",
+            (2, _) => "slti",
+            (3, _) => "sltiu",
+            (4, _) => "xori",
+            (5, 0x00) => "srli",
+            (5, 0x10) => "srai",
+            (6, _) => "ori",
+            (7, _) => "andi",
+            _ => return None,
+        },
+        0x1b => match (funct3, funct7) {
+            (0, _) => "addiw",
+            (1, 0x00) => "slliw",
+            (5, 0x00) => "srliw",
+            (5, 0x20) => "sraiw",
+            _ => return None,
+        },
+        0x33 => match (funct3, funct7) {
+            (0, 0x00) => "add",
+            (0, 0x20) => "sub",
+            (1, 0x00) => "sll",
+            (2, 0x00) => "slt",
+            (3, 0x00) => "sltu",
+            (4, 0x00) => "xor",
+            (5, 0x00) => "srl",
+            (5, 0x20) => "sra",
+            (6, 0x00) => "or",
+            (7, 0x00) => "and",
+            (0, 0x01) => "mul",
+            (1, 0x01) => "mulh",
+            (2, 0x01) => "mulhsu",
+            (3, 0x01) => "mulhu",
+            (4, 0x01) => "div",
+            (5, 0x01) => "divu",
+            (6, 0x01) => "rem",
+            (7, 0x01) => "remu",
+            _ => return None,
+        },
+        0x3b => match (funct3, funct7) {
+            (0, 0x00) => "addw",
+            (0, 0x20) => "subw",
+            (1, 0x00) => "sllw",
+            (5, 0x00) => "srlw",
+            (5, 0x20) => "sraw",
+            (0, 0x01) => "mulw",
+            (4, 0x01) => "divw",
+            (5, 0x01) => "divuw",
+            (6, 0x01) => "remw",
+            (7, 0x01) => "remuw",
+            _ => return None,
+        },
+        0x0f => match funct3 {
+            0 => "fence",
+            1 => "fence.i",
+            _ => return None,
+        },
+        0x73 => match funct3 {
+            0 => match (inst >> 20, funct7) {
+                (0x000, _) => "ecall",
+                (0x001, _) => "ebreak",
+                (0x102, _) => "sret",
+                (0x302, _) => "mret",
+                (0x105, _) => "wfi",
+                (_, 0x09) => "sfence.vma",
+                _ => return None,
+            },
+            1 => "csrrw",
+            2 => "csrrs",
+            3 => "csrrc",
+            5 => "csrrwi",
+            6 => "csrrsi",
+            7 => "csrrci",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Return the integer registers a 32-bit instruction reads or writes. `x0` is left out.
+pub fn registers(inst: u64) -> Vec<u64> {
+    let rd = (inst >> 7) & 0x1f;
+    let rs1 = (inst >> 15) & 0x1f;
+    let rs2 = (inst >> 20) & 0x1f;
+
+    let used = match inst & 0x7f {
+        // U-type and J-type.
+        0x37 | 0x17 | 0x6f => vec![rd],
+        // I-type.
+        0x67 | 0x03 | 0x13 | 0x1b => vec![rd, rs1],
+        // B-type and S-type.
+        0x63 | 0x23 => vec![rs1, rs2],
+        // R-type.
+        0x33 | 0x3b => vec![rd, rs1, rs2],
+        // CSR instructions. The immediate forms have a constant in place of rs1.
+        0x73 => match (inst >> 12) & 0x7 {
+            1..=3 => vec![rd, rs1],
+            5..=7 => vec![rd],
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    used.into_iter().filter(|&reg| reg != 0).collect()
+}
+
+/// A set of instructions the CPU may execute, by mnemonic, and optionally of the registers they
+/// may use. Executing any other instruction raises an illegal instruction exception.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InstructionSet {
+    mnemonics: BTreeSet<String>,
+    /// The allowed registers as a bit mask, or `None` to allow all of them.
+    registers: Option<u32>,
+}
+
+impl InstructionSet {
+    /// Create a set allowing the instructions named in `mnemonics`.
+    pub fn new<'a, I: IntoIterator<Item = &'a str>>(mnemonics: I) -> Self {
+        Self {
+            mnemonics: mnemonics.into_iter().map(|m| m.to_string()).collect(),
+            registers: None,
+        }
+    }
+
+    /// Also restrict the registers instructions may use to `registers`, by index. `x0` is always
+    /// allowed.
+    pub fn with_registers<I: IntoIterator<Item = u64>>(mut self, registers: I) -> Self {
+        self.registers = Some(registers.into_iter().fold(1, |mask, reg| mask | (1 << reg)));
+        self
+    }
+
+    /// Return true if instructions may use the register `index`.
+    pub fn allows_register(&self, index: u64) -> bool {
+        match self.registers {
+            Some(mask) => index < REGISTERS_COUNT as u64 && (mask & (1 << index)) != 0,
+            None => true,
+        }
+    }
+
+    /// Return the allowed mnemonics in alphabetical order.
+    pub fn mnemonics(&self) -> impl Iterator<Item = &str> {
+        self.mnemonics.iter().map(|m| m.as_str())
+    }
+
+    /// Return true if the mnemonic is allowed.
+    pub fn contains(&self, mnemonic: &str) -> bool {
+        self.mnemonics.contains(mnemonic)
+    }
+
+    /// Return true if the instruction word is allowed. Instructions without a known mnemonic
+    /// are never allowed.
+    pub fn allows(&self, inst: u64) -> bool {
+        match mnemonic(inst) {
+            Some(name) => {
+                self.mnemonics.contains(name)
+                    && registers(inst).iter().all(|&reg| self.allows_register(reg))
+            }
+            None => false,
+        }
+    }
+}
@@ -31,12 +31,59 @@
 //! See the example usage in
 //! [rvemu/lib/rvemu-cli/src/main.rs](https://github.com/d0iasm/rvemu/blob/master/lib/rvemu-cli/src/main.rs).
 
+pub mod aliases;
+pub mod annotation;
+pub mod arena;
+pub mod assembler;
+pub mod atomics;
+pub mod breakpoint;
 pub mod bus;
+pub mod context;
+pub mod compress;
+pub mod cosim;
 pub mod cpu;
 pub mod csr;
+pub mod csr_names;
+pub mod csr_trace;
+pub mod csr_view;
+pub mod debug_info;
+pub mod debug_print;
+pub mod delta;
 pub mod devices;
+pub mod disasm;
 pub mod dram;
 pub mod emulator;
+pub mod encoding_audit;
 pub mod exception;
+pub mod expression;
+pub mod explain;
+pub mod guest_assert;
+pub mod harts;
+pub mod history;
+pub mod hot_reload;
+pub mod injection;
 pub mod interrupt;
+pub mod isa;
+pub mod lockstep;
+pub mod mapping;
+pub mod memory_stats;
+pub mod memory_usage;
+pub mod metrics;
+pub mod nan_boxing;
+pub mod object;
+pub mod pmp;
+pub mod preview;
+pub mod profile;
+pub mod program_diff;
+pub mod programs;
+pub mod race;
+pub mod register_view;
 pub mod rom;
+pub mod run;
+pub mod semihosting;
+pub mod snapshot;
+pub mod speculation;
+pub mod trace;
+pub mod trap_return;
+pub mod watchpoint;
+pub mod xlen;
@@ -0,0 +1,171 @@
+//! The lockstep module runs several emulators in lockstep, e.g. the nodes of a networking or
+//! consensus level, or the bots of an arena larger than two. In every round each running node
+//! runs a quantum of steps in the order the nodes were numbered, then the messages the nodes sent
+//! through their channel are delivered along the links between them in the same order. A match
+//! therefore plays out the same way every time.
+
+use crate::emulator::Emulator;
+use crate::run::StopReason;
+
+/// The state of a node.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum NodeState {
+    /// The node runs every round.
+    Running = 0,
+    /// The node called `exit`.
+    Exited = 1,
+    /// An instruction of the node raised an exception or executed `ebreak`.
+    Trapped = 2,
+}
+
+/// The status of a node. The layout is C-compatible so it can be copied over the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct NodeStatus {
+    /// Whether the node still runs, and why not.
+    pub state: NodeState,
+    /// The number of instructions the node retired in lockstep.
+    pub steps: u64,
+    /// The program counter of the node.
+    pub pc: u64,
+    /// The exit code for `Exited`, otherwise 0.
+    pub exit_code: u64,
+    /// The exception code for `Trapped`, otherwise 0.
+    pub cause: u64,
+}
+
+/// A set of emulators running in lockstep, numbered from 0. The emulators themselves are lent
+/// to every `run`.
+#[derive(Debug)]
+pub struct Lockstep {
+    quantum: u64,
+    status: Vec<NodeStatus>,
+    /// The nodes the messages of each node are delivered to, in the order they were linked.
+    links: Vec<Vec<usize>>,
+}
+
+impl Lockstep {
+    /// Create a lockstep run of `nodes` nodes that run `quantum` steps per round. No node is
+    /// linked to another yet.
+    pub fn new(nodes: usize, quantum: u64) -> Self {
+        let status = NodeStatus {
+            state: NodeState::Running,
+            steps: 0,
+            pc: 0,
+            exit_code: 0,
+            cause: 0,
+        };
+        Self {
+            quantum,
+            status: vec![status; nodes],
+            links: vec![Vec::new(); nodes],
+        }
+    }
+
+    /// Return the number of nodes.
+    pub fn nodes(&self) -> usize {
+        self.status.len()
+    }
+
+    /// Deliver the messages `from` sends to `to` as well. Returns false if either node doesn't
+    /// exist or they're the same node.
+    pub fn link(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.nodes() || to >= self.nodes() || from == to {
+            return false;
+        }
+        if !self.links[from].contains(&to) {
+            self.links[from].push(to);
+        }
+        true
+    }
+
+    /// Link every node to every other node, so that each message is broadcast.
+    pub fn link_all(&mut self) {
+        for from in 0..self.nodes() {
+            for to in 0..self.nodes() {
+                self.link(from, to);
+            }
+        }
+    }
+
+    /// Return the status of the node `index`.
+    pub fn status(&self, index: usize) -> Option<NodeStatus> {
+        self.status.get(index).copied()
+    }
+
+    /// Run a quantum of the node `index` on `emu` and update its status. Environment calls other
+    /// than `exit` and the stops the host would handle, e.g. a presented frame, don't end the
+    /// quantum since nobody serves them in lockstep.
+    fn run_quantum(&mut self, emu: &mut Emulator, index: usize) {
+        let status = &mut self.status[index];
+        let mut steps = 0;
+        while steps < self.quantum {
+            let summary = emu.run(self.quantum - steps);
+            steps += summary.steps;
+            match summary.reason {
+                StopReason::Exited => {
+                    status.state = NodeState::Exited;
+                    status.exit_code = summary.exit_code;
+                    break;
+                }
+                StopReason::Trapped | StopReason::Breakpoint => {
+                    status.state = NodeState::Trapped;
+                    status.cause = summary.cause;
+                    break;
+                }
+                StopReason::Yielded
+                | StopReason::HostBreakpoint
+                | StopReason::FramePresented
+                | StopReason::Watchpoint => {}
+                _ => break,
+            }
+        }
+        status.steps += steps;
+        status.pc = emu.cpu.pc;
+    }
+
+    /// Deliver the messages sent by each node, in the order of the nodes, to every node it's
+    /// linked to. A message stays in the outbox of its sender until all of them have room for it.
+    fn deliver(&self, emus: &mut [&mut Emulator]) {
+        for (from, links) in self.links.iter().enumerate() {
+            if links.is_empty() {
+                continue;
+            }
+            while let Some(message) = emus[from].cpu.bus.channel.peek_sent() {
+                if !links.iter().all(|&to| emus[to].cpu.bus.channel.has_room()) {
+                    break;
+                }
+                emus[from].cpu.bus.channel.take_sent();
+                for &to in links {
+                    emus[to].cpu.bus.channel.deliver(message);
+                }
+            }
+        }
+    }
+
+    /// Run up to `rounds` rounds on `emus`, the emulator of each node. Every running node runs
+    /// one quantum, then the messages sent during the round are delivered, so they arrive before
+    /// the next round. Ends early when no node is running. Returns the number of rounds played.
+    ///
+    /// Panics if the number of emulators isn't the number of nodes.
+    pub fn run(&mut self, emus: &mut [&mut Emulator], rounds: u64) -> u64 {
+        assert_eq!(self.nodes(), emus.len());
+
+        for round in 0..rounds {
+            let mut ran = false;
+            for (index, emu) in emus.iter_mut().enumerate() {
+                if self.status[index].state != NodeState::Running {
+                    continue;
+                }
+                ran = true;
+                self.run_quantum(emu, index);
+            }
+            if !ran {
+                return round;
+            }
+            self.deliver(emus);
+        }
+        rounds
+    }
+}
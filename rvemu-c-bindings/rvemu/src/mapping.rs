@@ -0,0 +1,142 @@
+//! The mapping module backs regions of the address space with memory the host lends to the guest,
+//! e.g. a texture or an audio buffer the game shares with the guest instead of copying it every
+//! frame. A mapped region hides what is beneath it, DRAM included, from the guest.
+
+use crate::cpu::{BYTE, DOUBLEWORD, HALFWORD, WORD};
+use crate::exception::Exception;
+
+/// Memory the host lends to the guest. It can be used from the thread the emulator runs on.
+pub trait HostMemory: Send {
+    /// Return the bytes of the memory.
+    fn bytes(&self) -> &[u8];
+
+    /// Return the bytes of the memory for writing, or `None` if the memory is read-only.
+    fn bytes_mut(&mut self) -> Option<&mut [u8]>;
+}
+
+impl HostMemory for Vec<u8> {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        Some(self)
+    }
+}
+
+/// A region of the address space backed by host memory.
+struct Mapping {
+    base: u64,
+    memory: Box<dyn HostMemory>,
+}
+
+impl Mapping {
+    /// Return the address the region ends at, exclusive.
+    fn end(&self) -> u64 {
+        self.base + self.memory.bytes().len() as u64
+    }
+
+    /// Return the index of the `size`-bit access at `addr` in the memory, or `None` if it
+    /// doesn't fit in the region.
+    fn index(&self, addr: u64, size: u8) -> Option<usize> {
+        let len = match size {
+            BYTE | HALFWORD | WORD | DOUBLEWORD => size as u64 / 8,
+            _ => return None,
+        };
+        if addr.checked_add(len)? > self.end() {
+            return None;
+        }
+        Some((addr - self.base) as usize)
+    }
+}
+
+/// The regions of the address space backed by host memory.
+#[derive(Default)]
+pub struct Mappings {
+    mappings: Vec<Mapping>,
+}
+
+impl Mappings {
+    /// Create an empty set of mappings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Back the region at `base` with `memory`. Fails if the memory is empty, the region wraps
+    /// around the address space or overlaps another mapping.
+    pub fn map(&mut self, base: u64, memory: Box<dyn HostMemory>) -> Result<(), String> {
+        let len = memory.bytes().len() as u64;
+        if len == 0 {
+            return Err("the memory is empty".to_string());
+        }
+        let end = match base.checked_add(len) {
+            Some(end) => end,
+            None => return Err(format!("{:#x} + {:#x} wraps around", base, len)),
+        };
+        if let Some(other) = self
+            .mappings
+            .iter()
+            .find(|other| base < other.end() && other.base < end)
+        {
+            return Err(format!(
+                "{:#x}..{:#x} overlaps the mapping at {:#x}",
+                base, end, other.base
+            ));
+        }
+        self.mappings.push(Mapping { base, memory });
+        Ok(())
+    }
+
+    /// Remove the mapping at `base` and return its memory, or `None` if there's none.
+    pub fn unmap(&mut self, base: u64) -> Option<Box<dyn HostMemory>> {
+        let index = self.mappings.iter().position(|m| m.base == base)?;
+        Some(self.mappings.remove(index).memory)
+    }
+
+    /// Return true if nothing is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Return true if `addr` is mapped.
+    pub fn is_mapped(&self, addr: u64) -> bool {
+        self.find(addr).is_some()
+    }
+
+    /// Return the index of the mapping `addr` belongs to.
+    fn find(&self, addr: u64) -> Option<usize> {
+        self.mappings
+            .iter()
+            .position(|m| m.base <= addr && addr < m.end())
+    }
+
+    /// Load the little-endian `size`-bit value at `addr`, or return `None` if `addr` isn't mapped.
+    /// An access crossing the end of its region faults.
+    pub fn read(&self, addr: u64, size: u8) -> Option<Result<u64, Exception>> {
+        let mapping = &self.mappings[self.find(addr)?];
+        let index = match mapping.index(addr, size) {
+            Some(index) => index,
+            None => return Some(Err(Exception::LoadAccessFault(addr))),
+        };
+        let mut bytes = [0; 8];
+        let len = size as usize / 8;
+        bytes[..len].copy_from_slice(&mapping.memory.bytes()[index..index + len]);
+        Some(Ok(u64::from_le_bytes(bytes)))
+    }
+
+    /// Store the `size`-bit `value` at `addr` in little-endian order, or return `None` if `addr`
+    /// isn't mapped. An access crossing the end of its region, or to read-only memory, faults.
+    pub fn write(&mut self, addr: u64, value: u64, size: u8) -> Option<Result<(), Exception>> {
+        let index = self.find(addr)?;
+        let mapping = &mut self.mappings[index];
+        let result = match (mapping.index(addr, size), mapping.memory.bytes_mut()) {
+            (Some(index), Some(bytes)) => {
+                let len = size as usize / 8;
+                bytes[index..index + len].copy_from_slice(&value.to_le_bytes()[..len]);
+                Ok(())
+            }
+            _ => Err(Exception::StoreAMOAccessFault(addr)),
+        };
+        Some(result)
+    }
+}
@@ -0,0 +1,175 @@
+//! The memory_stats module aggregates loads and stores by the address of the instruction that
+//! made them, so a profiler can show the hottest load/store sites. Accesses can also be run
+//! through a simple cache model to tell how many of them would hit a data cache.
+
+use std::collections::BTreeMap;
+
+use crate::trace::AccessKind;
+
+/// The maximum number of lines of a cache model, so a bad size from the host can't allocate
+/// unbounded memory.
+pub const MAX_CACHE_LINES: u64 = 1 << 20;
+
+/// A direct-mapped cache model. It only tracks which lines are present and never holds data.
+#[derive(Debug, Clone)]
+pub struct CacheModel {
+    line_size: u64,
+    tags: Vec<Option<u64>>,
+}
+
+impl CacheModel {
+    /// Create an empty cache of `lines` lines of `line_size` bytes each. Fails if `line_size`
+    /// isn't a power of two, or `lines` is 0 or more than `MAX_CACHE_LINES`.
+    pub fn new(line_size: u64, lines: u64) -> Result<Self, String> {
+        if !line_size.is_power_of_two() {
+            return Err(format!("the line size {} isn't a power of two", line_size));
+        }
+        if lines == 0 || lines > MAX_CACHE_LINES {
+            return Err(format!(
+                "the number of lines must be from 1 to {}, not {}",
+                MAX_CACHE_LINES, lines
+            ));
+        }
+        Ok(Self {
+            line_size,
+            tags: vec![None; lines as usize],
+        })
+    }
+
+    /// Return the size of a line in bytes.
+    pub fn line_size(&self) -> u64 {
+        self.line_size
+    }
+
+    /// Return the number of lines.
+    pub fn lines(&self) -> u64 {
+        self.tags.len() as u64
+    }
+
+    /// Access `addr` and return true if it hits. A miss fills the line.
+    pub fn access(&mut self, addr: u64) -> bool {
+        let line = addr / self.line_size;
+        let index = (line % self.tags.len() as u64) as usize;
+        let hit = self.tags[index] == Some(line);
+        self.tags[index] = Some(line);
+        hit
+    }
+
+    /// Empty all lines.
+    pub fn invalidate(&mut self) {
+        for tag in self.tags.iter_mut() {
+            *tag = None;
+        }
+    }
+}
+
+/// The memory accesses of one instruction. The layout is C-compatible so it can be copied over
+/// the FFI as is.
+#[repr(C)]
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct SiteStats {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The number of loads it made.
+    pub loads: u64,
+    /// The number of stores it made.
+    pub stores: u64,
+    /// The number of its accesses that hit the cache model. 0 without a cache model.
+    pub cache_hits: u64,
+    /// The number of its accesses that missed the cache model. 0 without a cache model.
+    pub cache_misses: u64,
+}
+
+impl SiteStats {
+    /// Return the number of loads and stores.
+    pub fn accesses(&self) -> u64 {
+        self.loads + self.stores
+    }
+}
+
+/// The load/store statistics of a CPU. Nothing is recorded until it's enabled.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStats {
+    enabled: bool,
+    sites: BTreeMap<u64, SiteStats>,
+    cache: Option<CacheModel>,
+}
+
+impl MemoryStats {
+    /// Create disabled statistics without a cache model.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if accesses are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start or stop recording accesses. The recorded statistics are kept.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Use a direct-mapped cache of `lines` lines of `line_size` bytes. A size of 0 removes the
+    /// cache model. Fails, keeping the current model, if the sizes are invalid for
+    /// `CacheModel::new`.
+    pub fn set_cache(&mut self, line_size: u64, lines: u64) -> Result<(), String> {
+        self.cache = match (line_size, lines) {
+            (0, _) | (_, 0) => None,
+            _ => Some(CacheModel::new(line_size, lines)?),
+        };
+        Ok(())
+    }
+
+    /// Return the cache model, if any.
+    pub fn cache(&self) -> Option<&CacheModel> {
+        self.cache.as_ref()
+    }
+
+    /// Record an access to `addr` made by the instruction at `pc`.
+    pub fn record(&mut self, pc: u64, addr: u64, kind: AccessKind) {
+        let site = self.sites.entry(pc).or_insert(SiteStats {
+            pc,
+            ..SiteStats::default()
+        });
+        match kind {
+            AccessKind::Load => site.loads += 1,
+            AccessKind::Store => site.stores += 1,
+        }
+        if let Some(cache) = self.cache.as_mut() {
+            if cache.access(addr) {
+                site.cache_hits += 1;
+            } else {
+                site.cache_misses += 1;
+            }
+        }
+    }
+
+    /// Return the statistics of the instruction at `pc`.
+    pub fn site(&self, pc: u64) -> Option<&SiteStats> {
+        self.sites.get(&pc)
+    }
+
+    /// Return the statistics of every instruction that accessed memory, by address.
+    pub fn sites(&self) -> impl Iterator<Item = &SiteStats> {
+        self.sites.values()
+    }
+
+    /// Return up to `count` sites with the most accesses, the hottest first. Ties are ordered by
+    /// address.
+    pub fn hottest(&self, count: usize) -> Vec<SiteStats> {
+        let mut sites = self.sites.values().copied().collect::<Vec<SiteStats>>();
+        sites.sort_by(|a, b| b.accesses().cmp(&a.accesses()).then(a.pc.cmp(&b.pc)));
+        sites.truncate(count);
+        sites
+    }
+
+    /// Forget the recorded statistics and empty the cache model.
+    pub fn clear(&mut self) {
+        self.sites.clear();
+        if let Some(cache) = self.cache.as_mut() {
+            cache.invalidate();
+        }
+    }
+}
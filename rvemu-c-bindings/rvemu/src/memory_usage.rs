@@ -0,0 +1,62 @@
+//! The memory_usage module tells how much host memory an emulator holds and what for, so the host
+//! can display its footprint and cap it, e.g. on low-end machines.
+
+use std::mem::size_of;
+
+use crate::atomics::AtomicEvent;
+use crate::csr_trace::CsrWrite;
+use crate::debug_print::DebugPrint;
+use crate::emulator::Emulator;
+use crate::memory_stats::SiteStats;
+use crate::trace::{MemoryAccess, TraceEntry};
+use crate::trap_return::TrapReturn;
+
+/// The host memory held by an emulator in bytes, by use. The layout is C-compatible so it can be
+/// returned over the FFI as is.
+#[repr(C)]
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct MemoryUsage {
+    /// The DRAM allocated. The host only commits the pages the guest writes, so less of it may
+    /// be resident.
+    pub dram: u64,
+    /// The snapshot storage: the rewind checkpoints, and the DRAM pages saved to undo a
+    /// speculative run.
+    pub snapshots: u64,
+    /// The instruction trace, the logs of memory accesses, CSR writes, atomic instructions,
+    /// privilege returns and debug prints, and the memory statistics.
+    pub traces: u64,
+    /// The lines of the cache model of the memory statistics.
+    pub caches: u64,
+    /// The sum of the others.
+    pub total: u64,
+}
+
+/// Return the bytes of `count` values of `T`.
+fn bytes<T>(count: usize) -> u64 {
+    (count * size_of::<T>()) as u64
+}
+
+/// Measure the host memory `emu` holds.
+pub fn measure(emu: &Emulator) -> MemoryUsage {
+    let cpu = &emu.cpu;
+    let dram = cpu.bus.dram().len() as u64;
+    let snapshots = emu.history.size() + cpu.bus.journal_size();
+    let traces = bytes::<TraceEntry>(emu.trace.len())
+        + bytes::<MemoryAccess>(cpu.access_trace.entries().len())
+        + bytes::<CsrWrite>(emu.csr_trace.writes().len())
+        + bytes::<AtomicEvent>(emu.atomic_events.events().len())
+        + bytes::<TrapReturn>(emu.trap_returns.events().len())
+        + bytes::<DebugPrint>(emu.debug_prints.prints().len())
+        + bytes::<SiteStats>(cpu.memory_stats.sites().count());
+    let caches = cpu
+        .memory_stats
+        .cache()
+        .map_or(0, |cache| bytes::<Option<u64>>(cache.lines() as usize));
+    MemoryUsage {
+        dram,
+        snapshots,
+        traces,
+        caches,
+        total: dram + snapshots + traces + caches,
+    }
+}
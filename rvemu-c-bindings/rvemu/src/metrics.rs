@@ -0,0 +1,177 @@
+//! The metrics module counts what the engine does across every emulator of the process: the
+//! programs assembled, the runs and the instructions they retired, the lookups of assembler
+//! caches and the graded submissions. A hosted service reads them with `snapshot` to monitor the
+//! engine, e.g. as the Prometheus text of `Metrics::to_prometheus`, without wrapping every call.
+//!
+//! The counters are relaxed atomics, so a snapshot taken while other threads count may be a few
+//! counts behind, but never torn within a counter.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::run::RunSummary;
+
+static ASSEMBLIES: AtomicU64 = AtomicU64::new(0);
+static FAILED_ASSEMBLIES: AtomicU64 = AtomicU64::new(0);
+static RUNS: AtomicU64 = AtomicU64::new(0);
+static RUN_STEPS: AtomicU64 = AtomicU64::new(0);
+static RUN_TIME_NS: AtomicU64 = AtomicU64::new(0);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static GRADES: AtomicU64 = AtomicU64::new(0);
+static PASSED_GRADES: AtomicU64 = AtomicU64::new(0);
+static GRADED_CASES: AtomicU64 = AtomicU64::new(0);
+static PASSED_CASES: AtomicU64 = AtomicU64::new(0);
+
+/// Every counter.
+static COUNTERS: [&AtomicU64; 11] = [
+    &ASSEMBLIES,
+    &FAILED_ASSEMBLIES,
+    &RUNS,
+    &RUN_STEPS,
+    &RUN_TIME_NS,
+    &CACHE_HITS,
+    &CACHE_MISSES,
+    &GRADES,
+    &PASSED_GRADES,
+    &GRADED_CASES,
+    &PASSED_CASES,
+];
+
+fn add(counter: &AtomicU64, value: u64) {
+    counter.fetch_add(value, Ordering::Relaxed);
+}
+
+/// Count an assembled program, which failed to assemble unless `ok`.
+pub fn record_assembly(ok: bool) {
+    add(&ASSEMBLIES, 1);
+    add(&FAILED_ASSEMBLIES, !ok as u64);
+}
+
+/// Count the run summarized by `summary`.
+pub fn record_run(summary: &RunSummary) {
+    add(&RUNS, 1);
+    add(&RUN_STEPS, summary.steps);
+    add(&RUN_TIME_NS, summary.wall_time_ns);
+}
+
+/// Count a lookup of an assembler cache, which found the result if `hit`.
+pub fn record_cache_lookup(hit: bool) {
+    add(if hit { &CACHE_HITS } else { &CACHE_MISSES }, 1);
+}
+
+/// Count a submission graded on `cases` cases, of which it won `passed`.
+pub fn record_grade(cases: u64, passed: u64) {
+    add(&GRADES, 1);
+    add(&PASSED_GRADES, (passed == cases) as u64);
+    add(&GRADED_CASES, cases);
+    add(&PASSED_CASES, passed);
+}
+
+/// Return the current values of the counters.
+pub fn snapshot() -> Metrics {
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+    Metrics {
+        assemblies: load(&ASSEMBLIES),
+        failed_assemblies: load(&FAILED_ASSEMBLIES),
+        runs: load(&RUNS),
+        run_steps: load(&RUN_STEPS),
+        run_time_ns: load(&RUN_TIME_NS),
+        cache_hits: load(&CACHE_HITS),
+        cache_misses: load(&CACHE_MISSES),
+        grades: load(&GRADES),
+        passed_grades: load(&PASSED_GRADES),
+        graded_cases: load(&GRADED_CASES),
+        passed_cases: load(&PASSED_CASES),
+    }
+}
+
+/// Set every counter back to 0.
+pub fn reset() {
+    for counter in COUNTERS.iter() {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// The values of the counters at one time.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct Metrics {
+    /// The number of programs assembled, including those that failed.
+    pub assemblies: u64,
+    /// The number of programs that failed to assemble.
+    pub failed_assemblies: u64,
+    /// The number of runs of any emulator.
+    pub runs: u64,
+    /// The number of instructions retired by the runs.
+    pub run_steps: u64,
+    /// The wall-clock time spent in the runs in nanoseconds.
+    pub run_time_ns: u64,
+    /// The number of assembler cache lookups that found the result.
+    pub cache_hits: u64,
+    /// The number of assembler cache lookups that didn't.
+    pub cache_misses: u64,
+    /// The number of graded submissions.
+    pub grades: u64,
+    /// The number of graded submissions that won every case.
+    pub passed_grades: u64,
+    /// The number of cases the submissions were graded on.
+    pub graded_cases: u64,
+    /// The number of those cases won.
+    pub passed_cases: u64,
+}
+
+impl Metrics {
+    /// Return the average speed of the runs in millions of instructions per second, or 0 before
+    /// any run took time.
+    pub fn mips(&self) -> f64 {
+        match self.run_time_ns {
+            0 => 0.0,
+            ns => self.run_steps as f64 * 1000.0 / ns as f64,
+        }
+    }
+
+    /// Return the share of assembler cache lookups that found the result, from 0 to 1, or 0
+    /// before any lookup.
+    pub fn cache_hit_rate(&self) -> f64 {
+        match self.cache_hits + self.cache_misses {
+            0 => 0.0,
+            lookups => self.cache_hits as f64 / lookups as f64,
+        }
+    }
+
+    /// Return the metrics in the Prometheus text exposition format, each counter with its help
+    /// and type, e.g. `rvemu_runs_total 3`, and the averages as gauges.
+    pub fn to_prometheus(&self) -> String {
+        let counters = [
+            ("assemblies_total", "Programs assembled.", self.assemblies),
+            ("failed_assemblies_total", "Programs that failed to assemble.", self.failed_assemblies),
+            ("runs_total", "Runs of any emulator.", self.runs),
+            ("run_steps_total", "Instructions retired by the runs.", self.run_steps),
+            ("run_time_ns_total", "Nanoseconds spent in the runs.", self.run_time_ns),
+            ("cache_hits_total", "Assembler cache lookups that hit.", self.cache_hits),
+            ("cache_misses_total", "Assembler cache lookups that missed.", self.cache_misses),
+            ("grades_total", "Graded submissions.", self.grades),
+            ("passed_grades_total", "Submissions that won every case.", self.passed_grades),
+            ("graded_cases_total", "Cases the submissions were graded on.", self.graded_cases),
+            ("passed_cases_total", "Graded cases won.", self.passed_cases),
+        ];
+        let gauges = [
+            ("mips", "Average speed of the runs in MIPS.", self.mips()),
+            ("cache_hit_rate", "Share of cache lookups that hit.", self.cache_hit_rate()),
+        ];
+
+        let mut text = String::new();
+        let mut metric = |name: &str, help: &str, kind: &str, value: &dyn std::fmt::Display| {
+            let _ = writeln!(text, "# HELP rvemu_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE rvemu_{} {}", name, kind);
+            let _ = writeln!(text, "rvemu_{} {}", name, value);
+        };
+        for (name, help, value) in counters.iter() {
+            metric(name, help, "counter", value);
+        }
+        for (name, help, value) in gauges.iter() {
+            metric(name, help, "gauge", value);
+        }
+        text
+    }
+}
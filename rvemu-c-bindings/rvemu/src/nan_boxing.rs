@@ -0,0 +1,120 @@
+//! The nan_boxing module implements the NaN-boxing rules of the F extension on 64-bit
+//! floating-point registers. A single-precision value is held in the lower 32 bits with the
+//! upper 32 bits all set, so it reads as a NaN when viewed as a double. A single-precision
+//! instruction reading a register that isn't properly boxed sees the canonical NaN instead. The
+//! core historically held single-precision values as the doubles they convert to, which is kept
+//! as the legacy mode.
+
+/// The maximum number of distinct unboxed reads logged. Later ones are counted but not recorded.
+pub const MAX_UNBOXED_READS: usize = 4096;
+
+/// The canonical NaN of single precision.
+pub const CANONICAL_NAN_F32: u32 = 0x7fc0_0000;
+
+/// The upper 32 bits of a NaN-boxed single-precision value.
+const BOX: u64 = 0xffff_ffff_0000_0000;
+
+/// How single-precision values are held in the floating-point registers.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum NanBoxing {
+    /// As the double they convert to. Unboxed values can't be told apart.
+    Legacy = 0,
+    /// NaN-boxed, as the specification requires.
+    Strict = 1,
+}
+
+/// Return the register value of the NaN-boxed `value`.
+pub fn box_f32(value: f32) -> f64 {
+    f64::from_bits(BOX | value.to_bits() as u64)
+}
+
+/// Return the single-precision value in the register value `value`, or `None` if it isn't
+/// properly NaN-boxed.
+pub fn unbox_f32(value: f64) -> Option<f32> {
+    let bits = value.to_bits();
+    if bits & BOX == BOX {
+        Some(f32::from_bits(bits as u32))
+    } else {
+        None
+    }
+}
+
+/// A single-precision instruction that read a register that isn't properly NaN-boxed. The
+/// layout is C-compatible so reads can be copied over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct UnboxedRead {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The instruction word.
+    pub inst: u64,
+    /// The floating-point register it read.
+    pub reg: u64,
+    /// The number of times it was executed.
+    pub count: u64,
+}
+
+/// The log of unboxed reads in strict mode. Nothing is recorded until it's enabled.
+#[derive(Debug, Default, Clone)]
+pub struct NanBoxDiagnostics {
+    enabled: bool,
+    reads: Vec<UnboxedRead>,
+    dropped: u64,
+}
+
+impl NanBoxDiagnostics {
+    /// Create a disabled log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop recording unboxed reads.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if unboxed reads are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Count a read of the register `reg` by the instruction `inst` at `pc`. Each instruction
+    /// and register is logged once.
+    pub fn record(&mut self, pc: u64, inst: u64, reg: u64) {
+        if let Some(read) = self
+            .reads
+            .iter_mut()
+            .find(|read| read.pc == pc && read.reg == reg)
+        {
+            read.count += 1;
+            return;
+        }
+        if self.reads.len() == MAX_UNBOXED_READS {
+            self.dropped += 1;
+            return;
+        }
+        self.reads.push(UnboxedRead {
+            pc,
+            inst,
+            reg,
+            count: 1,
+        });
+    }
+
+    /// Return the logged reads in the order they first happened.
+    pub fn reads(&self) -> &[UnboxedRead] {
+        &self.reads
+    }
+
+    /// Return the number of reads that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Forget the logged reads.
+    pub fn clear(&mut self) {
+        self.reads.clear();
+        self.dropped = 0;
+    }
+}
@@ -0,0 +1,169 @@
+//! The object module holds a program assembled into its two sections, the text and the data, with
+//! the relocations that let the host load each section anywhere, e.g. the data in a RAM region of
+//! its own instead of right after the text.
+//!
+//! A relocation is a place that holds the address of a label, or its distance from an
+//! instruction, which changes when the sections move apart: a value of `.word` or `.dword`
+//! naming a label, `%hi` or `%lo` of a label, or the `auipc` and the instruction after it of
+//! `la` or `call` reaching a label in the other section. Linking writes the addresses of the
+//! labels there.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::assembler::{AssembleError, Symbol};
+
+/// A section of a program.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Section {
+    /// The instructions, and whatever data is defined among them.
+    Text,
+    /// The data after `.data`.
+    Data,
+}
+
+/// How the address of a label is written at a relocation.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RelocationKind {
+    /// The upper 20 bits of the distance from the `auipc` at the relocation to the label.
+    PcRelHi,
+    /// The lower 12 bits of the distance from the `auipc` at the offset `anchor` of the same
+    /// section to the label, in the immediate of the I-type instruction at the relocation.
+    PcRelLo { anchor: u64 },
+    /// The address of the label in 4 bytes.
+    Absolute32,
+    /// The address of the label in 8 bytes.
+    Absolute64,
+    /// The upper 20 bits of the address of the label, `%hi`, in the immediate of the `lui` at
+    /// the relocation.
+    AbsoluteHi,
+    /// The lower 12 bits of the address of the label, `%lo`, in the immediate of the I-type
+    /// instruction at the relocation.
+    AbsoluteLo,
+    /// The same in the immediate of the S-type instruction at the relocation, a store.
+    AbsoluteLoStore,
+}
+
+/// A place of a section that refers to a label.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Relocation {
+    pub section: Section,
+    /// The offset of the place in its section.
+    pub offset: u64,
+    pub kind: RelocationKind,
+    /// The name of the label.
+    pub symbol: String,
+    /// The 1-based number of the line that refers to the label.
+    pub line: u64,
+}
+
+/// An assembled program, with its sections apart.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Object {
+    /// The bytes of the text, linked as if the image were loaded at 0.
+    pub text: Vec<u8>,
+    /// The bytes of the data, linked as if the image were loaded at 0.
+    pub data: Vec<u8>,
+    /// The offset of the data in the image: right after the text, aligned for the data.
+    pub data_offset: u64,
+    /// The labels of both sections, ordered by their offset in the image.
+    pub symbols: Vec<Symbol>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Object {
+    /// Return the text and the data linked for the text at `text_addr` and the data at
+    /// `data_addr`. Fails if an address doesn't fit where it's written, e.g. a label more than
+    /// 2 GiB away from the `la` reaching it.
+    pub fn link(
+        &self,
+        text_addr: u64,
+        data_addr: u64,
+    ) -> Result<(Vec<u8>, Vec<u8>), AssembleError> {
+        let symbols = self
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol))
+            .collect::<HashMap<&str, &Symbol>>();
+        let mut text = self.text.clone();
+        let mut data = self.data.clone();
+        for relocation in &self.relocations {
+            let error = |message| AssembleError {
+                line: relocation.line,
+                message,
+            };
+            let symbol = symbols[relocation.symbol.as_str()];
+            let target = match symbol.section {
+                Section::Text => text_addr.wrapping_add(symbol.addr),
+                Section::Data => data_addr.wrapping_add(symbol.addr - self.data_offset),
+            };
+            let (bytes, base) = match relocation.section {
+                Section::Text => (&mut text, text_addr),
+                Section::Data => (&mut data, data_addr),
+            };
+            let at = relocation.offset as usize;
+            let fits = |size| {
+                u32::try_from(target).map_err(|_| {
+                    error(format!("the address of `{}` doesn't fit in {}", symbol.name, size))
+                })
+            };
+            let distance = |anchor: u64| {
+                let distance = target.wrapping_sub(base.wrapping_add(anchor)) as i64;
+                if !(-(1 << 31)..(1 << 31) - 0x800).contains(&distance) {
+                    return Err(error(format!("`{}` is too far to reach", relocation.symbol)));
+                }
+                Ok(distance)
+            };
+            match relocation.kind {
+                RelocationKind::PcRelHi => {
+                    let hi = (distance(relocation.offset)? + 0x800) >> 12;
+                    patch(bytes, at, 0xfff, (hi as u32) << 12);
+                }
+                RelocationKind::PcRelLo { anchor } => {
+                    let lo = distance(anchor)? << 52 >> 52;
+                    patch(bytes, at, 0xf_ffff, (lo as u32) << 20);
+                }
+                RelocationKind::Absolute32 => {
+                    let value = fits("4 bytes")?;
+                    bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                RelocationKind::Absolute64 => {
+                    bytes[at..at + 8].copy_from_slice(&target.to_le_bytes());
+                }
+                RelocationKind::AbsoluteHi => {
+                    let hi = fits("32 bits")?.wrapping_add(0x800) >> 12;
+                    patch(bytes, at, 0xfff, hi << 12);
+                }
+                RelocationKind::AbsoluteLo => {
+                    let lo = fits("32 bits")? & 0xfff;
+                    patch(bytes, at, 0xf_ffff, lo << 20);
+                }
+                RelocationKind::AbsoluteLoStore => {
+                    let lo = fits("32 bits")? & 0xfff;
+                    patch(bytes, at, 0x01ff_f07f, lo >> 5 << 25 | (lo & 0x1f) << 7);
+                }
+            }
+        }
+        Ok((text, data))
+    }
+
+    /// Return the image of the program loaded at `addr`: the text, then the data at
+    /// `data_offset`, if there's any.
+    pub fn image(&self, addr: u64) -> Result<Vec<u8>, AssembleError> {
+        let (mut image, data) = self.link(addr, addr.wrapping_add(self.data_offset))?;
+        if !data.is_empty() {
+            image.resize(self.data_offset as usize, 0);
+            image.extend_from_slice(&data);
+        }
+        Ok(image)
+    }
+}
+
+/// Replace the bits of the little-endian instruction at `at` in `bytes` outside `keep` with
+/// `bits`.
+fn patch(bytes: &mut [u8], at: usize, keep: u32, bits: u32) {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[at..at + 4]);
+    let inst = u32::from_le_bytes(word) & keep | bits;
+    bytes[at..at + 4].copy_from_slice(&inst.to_le_bytes());
+}
@@ -0,0 +1,192 @@
+//! The pmp module contains physical memory protection (PMP). Each entry describes a physical
+//! address range and the permissions of S-mode and U-mode accesses to it. Locked entries also
+//! restrict M-mode and can't be changed until reset.
+//!
+//! This implementation has 16 entries. An access that matches no entry is denied for S-mode and
+//! U-mode only once at least one entry is active, so programs that never configure PMP keep
+//! running in any mode.
+
+// Reference:
+// "3.7 Physical Memory Protection" in The RISC-V Instruction Set Manual Volume II: Privileged
+// Architecture
+
+use crate::cpu::{AccessType, Mode};
+use crate::csr::{CsrAddress, State, PMPADDR0, PMPCFG0};
+
+/// The number of PMP entries.
+pub const PMP_ENTRIES: usize = 16;
+
+/// Read permission.
+pub const PMP_R: u8 = 1;
+/// Write permission.
+pub const PMP_W: u8 = 1 << 1;
+/// Execute permission.
+pub const PMP_X: u8 = 1 << 2;
+/// The address-matching mode field.
+pub const PMP_A: u8 = 0b11 << 3;
+/// Address-matching mode: the entry is disabled.
+pub const PMP_A_OFF: u8 = 0;
+/// Address-matching mode: top of range. The entry covers from the previous entry's address up to
+/// its own address.
+pub const PMP_A_TOR: u8 = 1 << 3;
+/// Address-matching mode: naturally aligned four-byte region.
+pub const PMP_A_NA4: u8 = 2 << 3;
+/// Address-matching mode: naturally aligned power-of-two region, 8 bytes or larger.
+pub const PMP_A_NAPOT: u8 = 3 << 3;
+/// Lock bit. The entry also applies to M-mode and ignores writes until reset.
+pub const PMP_L: u8 = 1 << 7;
+
+/// The implemented bits of pmpaddr registers, bits 55:2 of a physical address on RV64.
+const PMPADDR_MASK: u64 = (1 << 54) - 1;
+
+/// Return the address of the pmpcfg register and the bit offset holding the configuration of
+/// the entry `index`. On RV64, pmpcfg0 holds entries 0-7 and pmpcfg2 holds entries 8-15.
+fn cfg_location(index: usize) -> (CsrAddress, usize) {
+    (PMPCFG0 + (index / 8 * 2) as CsrAddress, index % 8 * 8)
+}
+
+/// Return the configuration of the entry `index`.
+pub fn cfg(state: &State, index: usize) -> u8 {
+    let (reg, shift) = cfg_location(index);
+    (state.read(reg) >> shift) as u8
+}
+
+/// Return the value of the pmpaddr register of the entry `index`.
+pub fn addr(state: &State, index: usize) -> u64 {
+    state.read(PMPADDR0 + index as CsrAddress)
+}
+
+/// Return true if the pmpaddr register of the entry `index` can't be written.
+fn is_addr_locked(state: &State, index: usize) -> bool {
+    if (cfg(state, index) & PMP_L) != 0 {
+        return true;
+    }
+    // A locked TOR entry also locks the address of the entry below it.
+    index + 1 < PMP_ENTRIES && cfg(state, index + 1) & (PMP_L | PMP_A) == (PMP_L | PMP_A_TOR)
+}
+
+/// Return the value a write of `val` to the pmpcfg register `reg` leaves in it. Configurations of
+/// locked entries are kept.
+pub fn write_cfg(state: &State, reg: CsrAddress, val: u64) -> u64 {
+    // Only even-numbered pmpcfg registers exist on RV64 and this implementation has two of them.
+    let first = ((reg - PMPCFG0) / 2 * 8) as usize;
+    if (reg - PMPCFG0) & 1 != 0 || first >= PMP_ENTRIES {
+        return 0;
+    }
+
+    let mut result = 0;
+    for i in 0..8 {
+        let old = cfg(state, first + i);
+        let new = if (old & PMP_L) != 0 {
+            old
+        } else {
+            (val >> (i * 8)) as u8
+        };
+        result |= (new as u64) << (i * 8);
+    }
+    result
+}
+
+/// Return the value a write of `val` to the pmpaddr register `reg` leaves in it.
+pub fn write_addr(state: &State, reg: CsrAddress, val: u64) -> u64 {
+    let index = (reg - PMPADDR0) as usize;
+    if index >= PMP_ENTRIES {
+        return 0;
+    }
+    if is_addr_locked(state, index) {
+        return addr(state, index);
+    }
+    val & PMPADDR_MASK
+}
+
+/// Return the physical address range `[start, end)` covered by the entry `index`, or `None` if
+/// the entry is disabled.
+pub fn range(state: &State, index: usize) -> Option<(u64, u64)> {
+    let pmpaddr = addr(state, index) & PMPADDR_MASK;
+    match cfg(state, index) & PMP_A {
+        PMP_A_TOR => {
+            let start = match index {
+                0 => 0,
+                _ => (addr(state, index - 1) & PMPADDR_MASK) << 2,
+            };
+            Some((start, pmpaddr << 2))
+        }
+        PMP_A_NA4 => Some((pmpaddr << 2, (pmpaddr << 2) + 4)),
+        PMP_A_NAPOT => {
+            // The number of trailing ones encodes the size: 2^(ones + 3) bytes.
+            let ones = pmpaddr.trailing_ones();
+            let start = (pmpaddr & !((1 << ones) - 1)) << 2;
+            Some((start, start + (1 << (ones + 3))))
+        }
+        _ => None,
+    }
+}
+
+/// Encode a naturally aligned power-of-two region for a pmpaddr register. `size` must be a power
+/// of two of at least 8 bytes and `base` must be aligned to it.
+pub fn napot_addr(base: u64, size: u64) -> u64 {
+    (base >> 2) | ((size >> 3) - 1)
+}
+
+/// Return true if PMP allows an access of `size` bytes at the physical address `addr` in `mode`.
+pub fn check(state: &State, addr: u64, size: u64, access: AccessType, mode: Mode) -> bool {
+    // Most programs never configure PMP, so skip the search if no entry is configured.
+    if state.read(PMPCFG0) == 0 && state.read(PMPCFG0 + 2) == 0 {
+        return true;
+    }
+
+    let end = addr.saturating_add(size);
+    let mut is_active = false;
+
+    // "PMP entries are statically prioritized. The lowest-numbered PMP entry that matches any
+    // byte of an access determines whether that access succeeds or fails. The matching PMP entry
+    // must match all bytes of an access, or the access fails, irrespective of the L, R, W, and X
+    // bits."
+    for i in 0..PMP_ENTRIES {
+        let (start, stop) = match range(state, i) {
+            Some(r) => r,
+            None => continue,
+        };
+        is_active = true;
+
+        if end <= start || stop <= addr {
+            continue;
+        }
+        if addr < start || stop < end {
+            return false;
+        }
+
+        let cfg = cfg(state, i);
+        if mode == Mode::Machine && (cfg & PMP_L) == 0 {
+            return true;
+        }
+        let permission = match access {
+            AccessType::Instruction => PMP_X,
+            AccessType::Load => PMP_R,
+            AccessType::Store => PMP_W,
+        };
+        return (cfg & permission) != 0;
+    }
+
+    // "If no PMP entry matches an M-mode access, the access succeeds. If no PMP entry matches an
+    // S-mode or U-mode access, but at least one PMP entry is implemented, the access fails."
+    mode == Mode::Machine || !is_active
+}
+
+/// Set the configuration and the address of the entry `index`, as M-mode software would do with
+/// CSR instructions. Returns false if the index is out of range or the entry is locked.
+pub fn set_entry(state: &mut State, index: usize, cfg_value: u8, addr_value: u64) -> bool {
+    if index >= PMP_ENTRIES || (cfg(state, index) & PMP_L) != 0 || is_addr_locked(state, index) {
+        return false;
+    }
+
+    state.write(PMPADDR0 + index as CsrAddress, addr_value);
+
+    let (reg, shift) = cfg_location(index);
+    let old = state.read(reg);
+    state.write(
+        reg,
+        (old & !(0xff << shift)) | ((cfg_value as u64) << shift),
+    );
+    true
+}
@@ -0,0 +1,164 @@
+//! The preview module decodes the instructions the program is about to execute without executing
+//! them, so the UI can show "what happens next" arrows before the player steps. The preview
+//! follows the path a branch takes when it isn't taken, and tells where it would go if it were.
+
+use crate::disasm::{imm_b, imm_i, imm_j};
+use crate::dram::ByteOrder;
+use crate::emulator::Emulator;
+use crate::isa::mnemonic;
+use crate::xlen::Xlen;
+
+/// How a previewed instruction affects where execution goes.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Flow {
+    /// Execution goes on with the next instruction.
+    Sequential = 0,
+    /// A conditional branch to `target`. The preview goes on with the next instruction, as if
+    /// the branch weren't taken.
+    Branch = 1,
+    /// A jump to `target`, where the preview goes on.
+    Jump = 2,
+    /// A jump through a register to `target`, where the preview goes on. The target is predicted
+    /// from the current register values, which earlier instructions may still change.
+    IndirectJump = 3,
+    /// The instruction hands control to a trap handler or returns from one, e.g. `ecall` or
+    /// `mret`, or isn't a valid instruction. The preview ends with it.
+    Stop = 4,
+}
+
+/// An instruction the program is about to execute. The layout is C-compatible so previews can
+/// be copied over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct PreviewedInstruction {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The instruction. Compressed instructions are 16 bits.
+    pub inst: u64,
+    /// How the instruction affects where execution goes.
+    pub flow: Flow,
+    /// Where a branch or jump goes, otherwise 0.
+    pub target: u64,
+}
+
+/// Return the sign-extended offset of a CJ-type compressed instruction (`c.j`).
+pub(crate) fn imm_cj(inst: u64) -> i64 {
+    // offset[11|4|9:8|10|6|7|3:1|5] = inst[12|11|10:9|8|7|6|5:3|2]
+    let offset = ((inst >> 1) & 0x800)
+        | ((inst << 2) & 0x400)
+        | ((inst >> 1) & 0x300)
+        | ((inst << 1) & 0x80)
+        | ((inst >> 1) & 0x40)
+        | ((inst << 3) & 0x20)
+        | ((inst >> 7) & 0x10)
+        | ((inst >> 2) & 0xe);
+    ((offset << 52) as i64) >> 52
+}
+
+/// Return the sign-extended offset of a CB-type compressed branch (`c.beqz` and `c.bnez`).
+fn imm_cb(inst: u64) -> i64 {
+    // offset[8|4:3|7:6|2:1|5] = inst[12|11:10|6:5|4:3|2]
+    let offset = ((inst >> 4) & 0x100)
+        | ((inst << 1) & 0xc0)
+        | ((inst << 3) & 0x20)
+        | ((inst >> 7) & 0x18)
+        | ((inst >> 2) & 0x6);
+    ((offset << 55) as i64) >> 55
+}
+
+/// Return how the 16-bit instruction `inst` at `pc` affects where execution goes, and its
+/// target.
+fn compressed_flow(emu: &Emulator, inst: u64, pc: u64) -> (Flow, u64) {
+    let rs1 = (inst >> 7) & 0x1f;
+    match (inst & 0b11, inst >> 13) {
+        // c.j, and c.jal in RV32
+        (0b01, 0b101) => (Flow::Jump, pc.wrapping_add(imm_cj(inst) as u64)),
+        (0b01, 0b001) if emu.cpu.xlen() == Xlen::Rv32 => {
+            (Flow::Jump, Xlen::Rv32.truncate(pc.wrapping_add(imm_cj(inst) as u64)))
+        }
+        // c.beqz and c.bnez
+        (0b01, 0b110) | (0b01, 0b111) => (Flow::Branch, pc.wrapping_add(imm_cb(inst) as u64)),
+        // c.jr and c.jalr, or c.ebreak without a register
+        (0b10, 0b100) if (inst >> 2) & 0x1f == 0 && rs1 != 0 => {
+            (Flow::IndirectJump, emu.cpu.xregs.read(rs1) & !1)
+        }
+        (0b10, 0b100) if (inst >> 2) & 0x1f == 0 && (inst >> 12) & 1 == 1 => (Flow::Stop, 0),
+        // An all-zero halfword is illegal.
+        _ if inst == 0 => (Flow::Stop, 0),
+        _ => (Flow::Sequential, 0),
+    }
+}
+
+/// Return how the 32-bit instruction `inst` at `pc` affects where execution goes, and its
+/// target. `auipc` is the register and value set by an `auipc` right before, which the target of
+/// a `jalr` pairing with it, as in `call`, depends on.
+fn flow(emu: &Emulator, inst: u64, pc: u64, auipc: Option<(u64, u64)>) -> (Flow, u64) {
+    if mnemonic(inst).is_none() {
+        return (Flow::Stop, 0);
+    }
+    match inst & 0x7f {
+        0x63 => (Flow::Branch, pc.wrapping_add(imm_b(inst) as u64)),
+        0x6f => (Flow::Jump, pc.wrapping_add(imm_j(inst) as u64)),
+        0x67 => {
+            let rs1 = (inst >> 15) & 0x1f;
+            let base = match auipc {
+                Some((rd, value)) if rd == rs1 => value,
+                _ => emu.cpu.xregs.read(rs1),
+            };
+            (
+                Flow::IndirectJump,
+                base.wrapping_add(imm_i(inst) as u64) & !1,
+            )
+        }
+        // ecall, ebreak and the trap returns
+        0x73 if (inst >> 12) & 0x7 == 0 && (inst >> 25) != 0b0001001 && inst != 0x1050_0073 => {
+            (Flow::Stop, 0)
+        }
+        _ => (Flow::Sequential, 0),
+    }
+}
+
+/// Decode up to `count` instructions starting at the program counter of `emu`, in the order
+/// they would execute if no branch were taken. The preview ends early at an instruction that
+/// traps or returns from a trap, or at an address outside DRAM.
+pub fn peek_next(emu: &Emulator, count: usize) -> Vec<PreviewedInstruction> {
+    let mut preview = Vec::new();
+    let mut pc = emu.cpu.pc;
+    let mut auipc = None;
+    while preview.len() < count {
+        let inst = match emu.read_value(pc, 2, ByteOrder::Little) {
+            Some(half) if half & 0b11 != 0b11 => half,
+            Some(_) => match emu.read_value(pc, 4, ByteOrder::Little) {
+                Some(inst) => inst,
+                None => break,
+            },
+            None => break,
+        };
+        let compressed = inst & 0b11 != 0b11;
+        let (flow, target) = match compressed {
+            true => compressed_flow(emu, inst, pc),
+            false => flow(emu, inst, pc, auipc),
+        };
+        auipc = match inst & 0x7f {
+            0x17 if !compressed => {
+                let offset = (inst & 0xffff_f000) as i32 as i64 as u64;
+                Some(((inst >> 7) & 0x1f, pc.wrapping_add(offset)))
+            }
+            _ => None,
+        };
+        preview.push(PreviewedInstruction {
+            pc,
+            inst,
+            flow,
+            target,
+        });
+        pc = match flow {
+            Flow::Stop => break,
+            Flow::Jump | Flow::IndirectJump => target,
+            _ if compressed => pc.wrapping_add(2),
+            _ => pc.wrapping_add(4),
+        };
+    }
+    preview
+}
@@ -0,0 +1,17 @@
+//! The profile module contains presets that configure the emulator for a kind of guest program
+//! in one call.
+
+/// A preset of the privilege mode and the instructions a guest program may use.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+#[repr(u32)]
+pub enum Profile {
+    /// The power-on configuration: the core starts in machine mode and every instruction is
+    /// allowed.
+    #[default]
+    Machine = 0,
+    /// The configuration for untrusted programs. The core runs in user mode and can't leave it:
+    /// CSR instructions and privileged instructions (`mret`, `sret`, `wfi`, `sfence.vma`, ...)
+    /// raise an illegal instruction exception, and interrupts are disabled. `ecall` stops the run
+    /// so the host handles it, see `StopReason::Yielded`.
+    UserSandbox = 1,
+}
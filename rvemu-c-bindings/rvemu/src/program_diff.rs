@@ -0,0 +1,120 @@
+//! The program_diff module compares two program images instruction by instruction, so the game
+//! can show players what changed between attempts and the grader can reuse the results of
+//! unchanged programs.
+
+use crate::disasm::disassemble;
+
+/// The largest number of instruction pairs compared to align the changed middle of two
+/// programs. Bigger middles are reported as one changed range.
+const MAX_ALIGNMENT_CELLS: usize = 1 << 22;
+
+/// A range of instructions that differs between two programs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedRange {
+    /// The address of the first instruction of the range in the first program.
+    pub a_addr: u64,
+    /// The disassembly of the instructions of the range in the first program. Empty if
+    /// instructions were only inserted.
+    pub a: Vec<String>,
+    /// The address of the first instruction of the range in the second program.
+    pub b_addr: u64,
+    /// The disassembly of the instructions of the range in the second program. Empty if
+    /// instructions were only removed.
+    pub b: Vec<String>,
+}
+
+/// Split a program image into 32-bit instruction words. A trailing partial word is padded with
+/// zeros.
+fn words(image: &[u8]) -> Vec<u32> {
+    image
+        .chunks(4)
+        .map(|chunk| {
+            let mut bytes = [0; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(bytes)
+        })
+        .collect()
+}
+
+/// Return the pairs of indexes of the instructions `a` and `b` have in common, in order, by the
+/// longest common subsequence.
+fn common_instructions(a: &[u32], b: &[u32]) -> Vec<(usize, usize)> {
+    if a.len().saturating_mul(b.len()) > MAX_ALIGNMENT_CELLS {
+        return Vec::new();
+    }
+
+    // lengths[i][j] is the length of the longest common subsequence of a[i..] and b[j..].
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+/// Return the ranges of instructions that differ between the program images `a` and `b`, both
+/// loaded at `base`. Identical programs have no changed range.
+pub fn diff_programs(a: &[u8], b: &[u8], base: u64) -> Vec<ChangedRange> {
+    let a = words(a);
+    let b = words(b);
+
+    // Trim the common prefix and suffix so only the changed middle is aligned.
+    let prefix = a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a_middle = &a[prefix..a.len() - suffix];
+    let b_middle = &b[prefix..b.len() - suffix];
+
+    // Anchor the common instructions of the middle, then the end of both programs.
+    let mut anchors = common_instructions(a_middle, b_middle)
+        .into_iter()
+        .map(|(i, j)| (prefix + i, prefix + j))
+        .collect::<Vec<(usize, usize)>>();
+    anchors.push((a.len() - suffix, b.len() - suffix));
+
+    let disassemble_range = |words: &[u32], start: usize, end: usize| {
+        (start..end)
+            .map(|i| disassemble(words[i] as u64, base + 4 * i as u64))
+            .collect::<Vec<String>>()
+    };
+
+    let mut ranges = Vec::new();
+    let (mut i, mut j) = (prefix, prefix);
+    for (next_i, next_j) in anchors {
+        if next_i > i || next_j > j {
+            ranges.push(ChangedRange {
+                a_addr: base + 4 * i as u64,
+                a: disassemble_range(&a, i, next_i),
+                b_addr: base + 4 * j as u64,
+                b: disassemble_range(&b, j, next_j),
+            });
+        }
+        i = next_i + 1;
+        j = next_j + 1;
+    }
+    ranges
+}
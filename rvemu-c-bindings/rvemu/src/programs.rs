@@ -0,0 +1,146 @@
+//! The programs module keeps track of several programs loaded side by side in DRAM, e.g. a level
+//! library and the player's code, or two competing bots. Each program has its own symbol and
+//! line tables, and a jump table lets programs call each other's entry points by index.
+
+use crate::bus::{DRAM_BASE, DRAM_END};
+use crate::debug_info::DebugInfo;
+
+/// The size of a jump table entry in bytes: an `auipc` and a `jalr`.
+pub const JUMP_TABLE_ENTRY_SIZE: u64 = 8;
+
+/// A program loaded at a fixed address.
+#[derive(Debug, Clone)]
+pub struct LoadedProgram {
+    /// The name the host refers to the program by.
+    pub name: String,
+    /// The address of the first byte of the image.
+    pub base: u64,
+    /// The size of the image in bytes.
+    pub size: u64,
+    /// The address execution of the program starts at.
+    pub entry: u64,
+    /// The symbol table and the line table of the program.
+    pub debug_info: DebugInfo,
+}
+
+impl LoadedProgram {
+    /// Return true if `addr` is inside the image.
+    pub fn contains(&self, addr: u64) -> bool {
+        self.base <= addr && addr - self.base < self.size
+    }
+}
+
+/// Encode `auipc t1, hi; jalr zero, lo(t1)`, which jumps from `from` to `to` and keeps `ra`, so
+/// a call through the jump table returns straight to the caller.
+pub fn encode_far_jump(from: u64, to: u64) -> [u32; 2] {
+    let offset = to.wrapping_sub(from) as i64;
+    let hi = (offset + 0x800) >> 12;
+    let lo = offset - (hi << 12);
+    let auipc = (((hi as u32) & 0xfffff) << 12) | (6 << 7) | 0x17;
+    let jalr = (((lo as u32) & 0xfff) << 20) | (6 << 15) | 0x67;
+    [auipc, jalr]
+}
+
+/// The programs loaded in an emulator, in the order they were loaded.
+#[derive(Debug, Default, Clone)]
+pub struct Programs {
+    list: Vec<LoadedProgram>,
+}
+
+impl Programs {
+    /// Create an empty list of programs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if no program is loaded.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Return the number of loaded programs.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Record a program of `size` bytes at `base` starting at `entry`, and return its index.
+    /// Fails if the name is taken, the image is outside DRAM or overlaps another program, or
+    /// `entry` is outside the image.
+    pub fn add(&mut self, name: &str, base: u64, size: u64, entry: u64) -> Result<usize, String> {
+        if self.find(name).is_some() {
+            return Err(format!("a program named `{}` is already loaded", name));
+        }
+        let end = base.saturating_add(size);
+        if base < DRAM_BASE || end > DRAM_END || size == 0 {
+            return Err(format!(
+                "`{}` ({:#x}..{:#x}) doesn't fit in DRAM",
+                name, base, end
+            ));
+        }
+        if let Some(other) = self
+            .list
+            .iter()
+            .find(|other| base < other.base + other.size && other.base < end)
+        {
+            return Err(format!("`{}` overlaps `{}`", name, other.name));
+        }
+        if entry < base || entry >= end {
+            return Err(format!("the entry point of `{}` is outside it", name));
+        }
+
+        self.list.push(LoadedProgram {
+            name: name.to_string(),
+            base,
+            size,
+            entry,
+            debug_info: DebugInfo::new(),
+        });
+        Ok(self.list.len() - 1)
+    }
+
+    /// Forget all programs. Their bytes stay in memory.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Return the program at `index`.
+    pub fn get(&self, index: usize) -> Option<&LoadedProgram> {
+        self.list.get(index)
+    }
+
+    /// Return the program at `index` to change its debug info.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut LoadedProgram> {
+        self.list.get_mut(index)
+    }
+
+    /// Return the index of the program `name`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.list.iter().position(|program| program.name == name)
+    }
+
+    /// Return the program whose image contains `addr`.
+    pub fn containing(&self, addr: u64) -> Option<&LoadedProgram> {
+        self.list.iter().find(|program| program.contains(addr))
+    }
+
+    /// Return the programs in the order they were loaded.
+    pub fn iter(&self) -> impl Iterator<Item = &LoadedProgram> {
+        self.list.iter()
+    }
+
+    /// Return the jump table placed at `addr`: for each program in load order, a far jump to its
+    /// entry point. Calling `addr + JUMP_TABLE_ENTRY_SIZE * i` calls program `i`.
+    pub fn jump_table(&self, addr: u64) -> Vec<u8> {
+        self.list
+            .iter()
+            .enumerate()
+            .flat_map(|(i, program)| {
+                let slot = addr + JUMP_TABLE_ENTRY_SIZE * i as u64;
+                let [auipc, jalr] = encode_far_jump(slot, program.entry);
+                let mut bytes = auipc.to_le_bytes().to_vec();
+                bytes.extend(jalr.to_le_bytes().iter());
+                bytes
+            })
+            .collect()
+    }
+}
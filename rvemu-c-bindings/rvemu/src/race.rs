@@ -0,0 +1,192 @@
+//! The race module detects data races between harts with vector clocks. Atomic instructions
+//! (`lr`, `sc` and the AMOs) synchronize: an atomic access to an address happens after every
+//! earlier atomic access to it. Two plain accesses to the same byte by different harts, at least
+//! one of them a store, race if neither happens before the other.
+
+use std::collections::HashMap;
+
+use crate::trace::{AccessKind, MemoryAccess};
+
+/// One side of a data race.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct RaceAccess {
+    /// The hart that accessed the memory.
+    pub hart: u64,
+    /// The address of the instruction.
+    pub pc: u64,
+    /// Whether the instruction loaded or stored.
+    pub kind: AccessKind,
+}
+
+/// Two conflicting accesses that aren't ordered by synchronization. The layout is C-compatible
+/// so races can be copied over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct DataRace {
+    /// The first byte both accesses touched.
+    pub addr: u64,
+    /// The earlier access.
+    pub first: RaceAccess,
+    /// The later access.
+    pub second: RaceAccess,
+}
+
+/// An access remembered for a byte: the hart, its clock at the time, and the instruction.
+#[derive(Debug, Copy, Clone)]
+struct Epoch {
+    hart: usize,
+    clock: u64,
+    pc: u64,
+}
+
+/// The last store to a byte and the loads since then, one per hart.
+#[derive(Debug, Default)]
+struct Shadow {
+    store: Option<Epoch>,
+    loads: Vec<Epoch>,
+}
+
+/// A vector clock: the latest clock of each hart that happens before now.
+type VectorClock = Vec<u64>;
+
+/// Return the entry of `hart` in `clock`.
+fn entry(clock: &[u64], hart: usize) -> u64 {
+    clock.get(hart).copied().unwrap_or(0)
+}
+
+/// Make `clock` at least as late as `other`.
+fn join(clock: &mut VectorClock, other: &[u64]) {
+    if clock.len() < other.len() {
+        clock.resize(other.len(), 0);
+    }
+    for (a, &b) in clock.iter_mut().zip(other) {
+        *a = (*a).max(b);
+    }
+}
+
+/// The race detector of multi-hart mode.
+#[derive(Debug, Default)]
+pub struct RaceDetector {
+    /// The vector clock of each hart.
+    clocks: Vec<VectorClock>,
+    /// The vector clock of each address accessed atomically.
+    sync: HashMap<u64, VectorClock>,
+    shadow: HashMap<u64, Shadow>,
+    races: Vec<DataRace>,
+}
+
+impl RaceDetector {
+    /// Create a detector that hasn't seen any access.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the races found, each pair of instructions once, in the order they were found.
+    pub fn races(&self) -> &[DataRace] {
+        &self.races
+    }
+
+    /// Forget every access and race, e.g. when the program restarts.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Return the vector clock of `hart`. A hart starts at 1, so its accesses don't happen
+    /// before those of other harts.
+    fn clock(&mut self, hart: usize) -> &mut VectorClock {
+        while self.clocks.len() <= hart {
+            let id = self.clocks.len();
+            let mut clock = vec![0; id + 1];
+            clock[id] = 1;
+            self.clocks.push(clock);
+        }
+        &mut self.clocks[hart]
+    }
+
+    /// Record the race between `earlier` and the access of `hart` at `pc`, unless the same
+    /// instructions raced before.
+    fn report(
+        &mut self,
+        addr: u64,
+        earlier: (Epoch, AccessKind),
+        pc: u64,
+        hart: usize,
+        kind: AccessKind,
+    ) {
+        let (epoch, earlier_kind) = earlier;
+        let first = RaceAccess {
+            hart: epoch.hart as u64,
+            pc: epoch.pc,
+            kind: earlier_kind,
+        };
+        let second = RaceAccess {
+            hart: hart as u64,
+            pc,
+            kind,
+        };
+        if !self
+            .races
+            .iter()
+            .any(|race| race.first.pc == first.pc && race.second.pc == second.pc)
+        {
+            self.races.push(DataRace {
+                addr,
+                first,
+                second,
+            });
+        }
+    }
+
+    /// Check an access of `hart` against the earlier accesses to the same bytes. `atomic` is
+    /// true for the accesses of `lr`, `sc` and the AMOs, which synchronize instead of racing.
+    pub fn access(&mut self, hart: usize, access: &MemoryAccess, atomic: bool) {
+        if atomic {
+            // An atomic access acquires the clock of the address, then releases its own to it.
+            let mut clock = self.clock(hart).clone();
+            let sync = self.sync.entry(access.addr).or_default();
+            join(&mut clock, sync);
+            join(sync, &clock);
+            clock[hart] += 1;
+            self.clocks[hart] = clock;
+            return;
+        }
+
+        let clock = self.clock(hart).clone();
+        let now = Epoch {
+            hart,
+            clock: clock[hart],
+            pc: access.pc,
+        };
+        let concurrent =
+            |epoch: &Epoch| epoch.hart != hart && epoch.clock > entry(&clock, epoch.hart);
+
+        for addr in access.addr..access.addr + access.size {
+            let shadow = self.shadow.entry(addr).or_default();
+            let mut conflicts = Vec::new();
+            if let Some(store) = shadow.store.filter(|store| concurrent(store)) {
+                conflicts.push((store, AccessKind::Store));
+            }
+            match access.kind {
+                AccessKind::Load => {
+                    shadow.loads.retain(|load| load.hart != hart);
+                    shadow.loads.push(now);
+                }
+                AccessKind::Store => {
+                    conflicts.extend(
+                        shadow
+                            .loads
+                            .iter()
+                            .filter(|load| concurrent(load))
+                            .map(|&load| (load, AccessKind::Load)),
+                    );
+                    shadow.store = Some(now);
+                    shadow.loads.clear();
+                }
+            }
+            for earlier in conflicts {
+                self.report(addr, earlier, access.pc, hart, access.kind);
+            }
+        }
+    }
+}
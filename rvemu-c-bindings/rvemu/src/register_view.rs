@@ -0,0 +1,71 @@
+//! The register_view module formats the value of a register in every interpretation the UI
+//! shows, so each front-end displays the same conversions instead of redoing the sign extension
+//! and the floating-point decoding itself.
+
+use crate::cpu::REGISTERS_COUNT;
+use crate::emulator::Emulator;
+use crate::nan_boxing::unbox_f32;
+
+/// A register file.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum RegisterFile {
+    /// The integer registers x0 to x31.
+    Integer = 0,
+    /// The floating-point registers f0 to f31.
+    Float = 1,
+}
+
+/// The value of a register in every interpretation relevant to its register file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RegisterView {
+    /// The bit pattern in hexadecimal, padded to 64 bits, e.g. "0xffffffffffffffff".
+    pub hex: String,
+    /// The value as a signed integer, e.g. "-1". Integer registers only.
+    pub signed: Option<String>,
+    /// The value as an unsigned integer, e.g. "18446744073709551615". Integer registers only.
+    pub unsigned: Option<String>,
+    /// The value as a double, e.g. "1.5" or "NaN". Floating-point registers only.
+    pub f64: Option<String>,
+    /// The single-precision value in the lower 32 bits. Floating-point registers only.
+    pub f32: Option<String>,
+    /// Whether the single-precision value is properly NaN-boxed. Floating-point registers only.
+    pub nan_boxed: Option<bool>,
+}
+
+/// Return the views of the bit pattern `bits` held in a register of `file`.
+pub fn view(file: RegisterFile, bits: u64) -> RegisterView {
+    let hex = format!("{:#018x}", bits);
+    match file {
+        RegisterFile::Integer => RegisterView {
+            hex,
+            signed: Some((bits as i64).to_string()),
+            unsigned: Some(bits.to_string()),
+            f64: None,
+            f32: None,
+            nan_boxed: None,
+        },
+        RegisterFile::Float => RegisterView {
+            hex,
+            signed: None,
+            unsigned: None,
+            // Debug formatting keeps the fraction of whole numbers, e.g. "1.0" rather than "1".
+            f64: Some(format!("{:?}", f64::from_bits(bits))),
+            f32: Some(format!("{:?}", f32::from_bits(bits as u32))),
+            nan_boxed: Some(unbox_f32(f64::from_bits(bits)).is_some()),
+        },
+    }
+}
+
+/// Return the views of the register `index` of `file` in `emu`, or `None` if there's no such
+/// register.
+pub fn register_view(emu: &Emulator, file: RegisterFile, index: u64) -> Option<RegisterView> {
+    if index >= REGISTERS_COUNT as u64 {
+        return None;
+    }
+    let bits = match file {
+        RegisterFile::Integer => emu.cpu.xregs.read(index),
+        RegisterFile::Float => emu.cpu.fregs.read(index).to_bits(),
+    };
+    Some(view(file, bits))
+}
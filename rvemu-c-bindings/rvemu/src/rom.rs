@@ -7,6 +7,7 @@ use crate::exception::Exception;
 use std::fs::File;
 use std::io::prelude::*;
 use std::process::Command;
+use std::sync::OnceLock;
 
 const DTS_FILE_NAME: &str = "rvemu.dts";
 const DTB_FILE_NAME: &str = "rvemu.dtb";
@@ -129,6 +130,10 @@ fn dtb() -> std::io::Result<Vec<u8>> {
     Ok(dtb)
 }
 
+/// The device tree binary of every ROM. It never changes, so it's compiled by the first `Rom::new`
+/// of the process and shared, and creating an emulator doesn't run `dtc` each time.
+static DTB: OnceLock<Vec<u8>> = OnceLock::new();
+
 /// The read-only memory (ROM).
 pub struct Rom {
     data: Vec<u8>,
@@ -137,7 +142,7 @@ pub struct Rom {
 impl Rom {
     /// Create a new `rom` object.
     pub fn new() -> Self {
-        let mut dtb = match dtb() {
+        let dtb = DTB.get_or_init(|| match dtb() {
             Ok(dtb) => dtb,
             Err(e) => {
                 // TODO: should fail?
@@ -147,12 +152,12 @@ impl Rom {
                 );
                 Vec::new()
             }
-        };
+        });
 
         // TODO: set a reset vector correctly.
         // 0x20 is the size of a reset vector.
         let mut rom = vec![0; 32];
-        rom.append(&mut dtb);
+        rom.extend_from_slice(dtb);
         let align = 0x1000;
         rom.resize((rom.len() + align - 1) / align * align, 0);
 
@@ -170,13 +175,13 @@ impl Rom {
             HALFWORD => Ok(self.read16(addr)),
             WORD => Ok(self.read32(addr)),
             DOUBLEWORD => Ok(self.read64(addr)),
-            _ => return Err(Exception::LoadAccessFault),
+            _ => return Err(Exception::LoadAccessFault(addr)),
         }
     }
 
     /// Store `size`-bit data to the memory. Returns the exception because the ROM is read-only.
-    pub fn write(&self, _addr: u64, _value: u64, _size: u8) -> Result<(), Exception> {
-        Err(Exception::StoreAMOAccessFault)
+    pub fn write(&self, addr: u64, _value: u64, _size: u8) -> Result<(), Exception> {
+        Err(Exception::StoreAMOAccessFault(addr))
     }
 
     /// Read a byte from the rom.
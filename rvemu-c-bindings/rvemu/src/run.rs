@@ -0,0 +1,216 @@
+//! The run module contains the outcome of running the emulator for a while. The host gets
+//! everything it needs to update its UI from a single summary instead of querying the emulator
+//! after every run.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The system call number of `exit` in the RISC-V Linux ABI, which newlib and riscv-pk also use.
+/// An `ecall` with this number in a7 ends the program with the exit code in a0.
+pub const SYS_EXIT: u64 = 93;
+
+/// The nominal clock frequency of the core in hertz unless the host sets another.
+pub const DEFAULT_CLOCK_HZ: u64 = 100_000_000;
+
+/// Return the simulated time of `steps` steps at `clock_hz` hertz in microseconds. Every
+/// instruction takes one cycle.
+pub fn simulated_us(steps: u64, clock_hz: u64) -> f64 {
+    steps as f64 * 1e6 / clock_hz as f64
+}
+
+/// How often the time budget and the cancel token are checked, in steps. Doing it on every step
+/// would slow the emulator down noticeably.
+pub const CHECK_INTERVAL: u64 = 1024;
+
+/// The callback reporting the progress of a long run with the number of steps done and the step
+/// limit.
+pub type ProgressCallback = Box<dyn FnMut(u64, u64) + Send>;
+
+/// The registers of an environment call: the call number in a7 and the arguments in a0 to a5.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct EcallArgs {
+    pub number: u64,
+    pub args: [u64; 6],
+}
+
+/// What a run does after the host served an environment call.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EcallAction {
+    /// Write the return value into a0 and go on running.
+    Resume = 0,
+    /// Stop the run with `StopReason::Exited` and the return value as the exit code.
+    Exit = 1,
+    /// Leave the call alone, as if there were no handler: stop the run with
+    /// `StopReason::Yielded`, or `StopReason::Exited` for `exit`.
+    Yield = 2,
+}
+
+impl EcallAction {
+    /// Return the action with the code `code`.
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            0 => Some(EcallAction::Resume),
+            1 => Some(EcallAction::Exit),
+            2 => Some(EcallAction::Yield),
+            _ => None,
+        }
+    }
+}
+
+/// The handler serving the environment calls of the guest during a run. It gets the call and its
+/// return value, initially the value of a0, and tells the run what to do next.
+pub type EcallHandler = Box<dyn FnMut(&EcallArgs, &mut u64) -> EcallAction + Send>;
+
+/// The handler of the `ebreak`s of the guest during a run. It gets the address of the `ebreak` and
+/// returns true to resume after it, or false to stop the run with `StopReason::Breakpoint`.
+pub type EbreakHandler = Box<dyn FnMut(u64) -> bool + Send>;
+
+/// What a run does when the guest executes `ebreak`.
+pub enum EbreakBehavior {
+    /// Stop the run with `StopReason::Breakpoint`, as a debugger does. The default.
+    Stop,
+    /// Raise a breakpoint exception, so the guest handles it at its trap vector like any other.
+    Trap,
+    /// Call the handler, which decides whether the run resumes after the `ebreak` or stops.
+    Callback(EbreakHandler),
+}
+
+/// Why the emulator stopped running. Every run mode reports one of these, so the host never has
+/// to infer it from side channels.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StopReason {
+    /// The requested number of steps was executed.
+    StepLimit = 0,
+    /// An instruction raised an exception. The cause and the trap value are in the summary.
+    Trapped = 1,
+    /// The guest made an environment call (`ecall`) for the host to serve. The program counter
+    /// is already past the `ecall`.
+    Yielded = 2,
+    /// The guest executed an `ebreak`. The program counter points at it.
+    Breakpoint = 3,
+    /// A watched memory location was accessed. The program counter is past the instruction that
+    /// accessed it.
+    Watchpoint = 4,
+    /// The host time budget of the run ran out.
+    TimeBudget = 5,
+    /// The guest called `exit`. The exit code is in the summary.
+    Exited = 6,
+    /// The host cancelled the run.
+    Cancelled = 7,
+    /// A resource limit other than the step count of this run was exceeded.
+    LimitExceeded = 8,
+    /// The guest executed `mret`, `sret` or `uret` in `run_until_trap_return`. The program
+    /// counter is where the instruction returned to.
+    TrapReturn = 9,
+    /// The program counter reached a breakpoint set by the host. The instruction there isn't
+    /// executed yet; the next run executes it without stopping.
+    HostBreakpoint = 10,
+    /// The guest presented a frame through the vsync device. The program counter is past the
+    /// store, so the next run starts the next frame.
+    FramePresented = 11,
+    /// A guest assert failed. The program counter is past the `ecall`, the line number of the
+    /// assert is in the summary and the failure is in `guest_asserts`.
+    GuestAssertionFailed = 12,
+    /// The run executed as many steps as the fuel of the emulator allows, before its own step
+    /// limit, e.g. because the program is stuck in an infinite loop.
+    FuelExhausted = 13,
+}
+
+/// Whether a pending interrupt is taken during a single step, i.e. a run of one step.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum StepInterrupts {
+    /// Take the interrupt and execute the first instruction of the trap handler in the same
+    /// step, like a longer run does.
+    Deliver = 0,
+    /// Leave the interrupt pending and execute the instruction at the program counter, so one
+    /// step always executes the next instruction of the program. The interrupt is taken by the
+    /// first run of more than one step.
+    Defer = 1,
+}
+
+/// A token to cancel a long operation. Clones share the same state, so the host can keep one and
+/// cancel from another thread while the emulator is running.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Create a token that isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of the current or the next operation.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Return true if cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Return true if cancellation was requested, and clear the request so it cancels only one
+    /// operation.
+    pub fn take(&self) -> bool {
+        self.cancelled.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// The limits of a run.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct RunLimits {
+    /// The maximum number of steps.
+    pub max_steps: u64,
+    /// The maximum host time, if any.
+    pub time_budget: Option<Duration>,
+}
+
+impl RunLimits {
+    /// Limit a run to `max_steps` steps only.
+    pub fn steps(max_steps: u64) -> Self {
+        Self {
+            max_steps,
+            time_budget: None,
+        }
+    }
+}
+
+/// The outcome of a run. The layout is C-compatible so it can be returned over the FFI as is.
+#[repr(C)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct RunSummary {
+    /// Why the emulator stopped.
+    pub reason: StopReason,
+    /// The number of instructions retired in this run.
+    pub steps: u64,
+    /// The program counter when the emulator stopped. For `Trapped`, it's the address of the
+    /// instruction that raised the exception.
+    pub stop_pc: u64,
+    /// The exception code (mcause) for `Trapped`, `Yielded` and `Breakpoint`, otherwise 0.
+    pub cause: u64,
+    /// The trap value (mtval) for `Trapped`, the accessed address for `Watchpoint`, the line
+    /// number of the assert for `GuestAssertionFailed`, otherwise 0.
+    pub tval: u64,
+    /// The exit code for `Exited`, otherwise 0.
+    pub exit_code: u64,
+    /// The host time spent in this run in nanoseconds.
+    pub wall_time_ns: u64,
+    /// The number of interrupts taken in this run.
+    pub interrupts: u64,
+    /// The number of instructions retired since the emulator was created.
+    pub total_retired: u64,
+    /// The value of the timer (mtime) when the emulator stopped.
+    pub mtime: u64,
+    /// The nominal clock frequency of the core in hertz.
+    pub clock_hz: u64,
+    /// The simulated time of the instructions retired in this run at `clock_hz`, in
+    /// microseconds, e.g. to tell the player their routine takes 4.2µs on a 100 MHz core.
+    pub simulated_us: f64,
+}
@@ -0,0 +1,153 @@
+//! The semihosting module serves the RISC-V semihosting calls of the guest, so binaries built
+//! against a semihosting C library, e.g. newlib with `--specs=rdimon.specs`, print and exit
+//! under the emulator. A call is an `ebreak` between `slli zero, zero, 0x1f` and
+//! `srai zero, zero, 7`, all uncompressed, with the operation in a0 and the address of its
+//! parameter block, or its only parameter, in a1. The result goes into a0 and the program
+//! continues after the `ebreak`.
+//!
+//! Only the console exists: `SYS_OPEN` opens the special file ":tt", `SYS_WRITE0` writes to it
+//! and `SYS_READ` reads the input queued by the host. Files of the host are never opened.
+
+use std::collections::VecDeque;
+
+use crate::xlen::Xlen;
+
+/// The instruction before the `ebreak` of a call: `slli zero, zero, 0x1f`.
+pub const SEMIHOSTING_ENTRY: u64 = 0x01f0_1013;
+/// The `ebreak` of a call.
+pub const SEMIHOSTING_EBREAK: u64 = 0x0010_0073;
+/// The instruction after the `ebreak` of a call: `srai zero, zero, 7`.
+pub const SEMIHOSTING_EXIT: u64 = 0x4070_5013;
+
+/// Open the file whose name is at the address in the first field, of the length in the third,
+/// in the mode of the second field. Returns the handle, or -1.
+pub const SYS_OPEN: u64 = 0x01;
+/// Write the NUL-terminated string at the address in a1 to the console.
+pub const SYS_WRITE0: u64 = 0x04;
+/// Read up to the third field bytes from the handle in the first field into the buffer at the
+/// address in the second field. Returns the number of bytes not read, or -1.
+pub const SYS_READ: u64 = 0x06;
+/// Stop the program with the reason in a1 in RV32, or with the reason and the exit code at the
+/// address in a1 in RV64.
+pub const SYS_EXIT: u64 = 0x18;
+
+/// The reason of `SYS_EXIT` for a normal exit.
+pub const ADP_STOPPED_APPLICATION_EXIT: u64 = 0x20026;
+
+/// The handle of the console opened for reading.
+pub const STDIN_HANDLE: u64 = 1;
+/// The handle of the console opened for writing or appending.
+pub const STDOUT_HANDLE: u64 = 2;
+
+/// The longest string written by `SYS_WRITE0`. Longer strings are cut.
+pub const MAX_SEMIHOSTING_TEXT: u64 = 4096;
+/// The maximum number of output bytes kept until the host takes them. The oldest bytes are
+/// dropped first.
+pub const SEMIHOSTING_OUTPUT_SIZE: usize = 64 * 1024;
+
+/// Return true if `entry`, `ebreak` and `exit`, the instructions around and at an `ebreak`, make
+/// a semihosting call.
+pub fn is_call(entry: u64, ebreak: u64, exit: u64) -> bool {
+    (entry, ebreak, exit) == (SEMIHOSTING_ENTRY, SEMIHOSTING_EBREAK, SEMIHOSTING_EXIT)
+}
+
+/// Return the handle of the file `name` opened in `mode`, or `None` if it can't be opened. Modes
+/// 0 to 3 read, 4 to 11 write or append.
+pub fn open(name: &[u8], mode: u64) -> Option<u64> {
+    match (name, mode) {
+        (b":tt", 0..=3) => Some(STDIN_HANDLE),
+        (b":tt", 4..=11) => Some(STDOUT_HANDLE),
+        _ => None,
+    }
+}
+
+/// Return the exit code of a `SYS_EXIT` with `reason` and `subcode`, which only RV64 passes: the
+/// subcode for a normal exit, or 0 in RV32, and 1 for any other reason.
+pub fn exit_code(xlen: Xlen, reason: u64, subcode: u64) -> u64 {
+    match (reason, xlen) {
+        (ADP_STOPPED_APPLICATION_EXIT, Xlen::Rv64) => subcode,
+        (ADP_STOPPED_APPLICATION_EXIT, Xlen::Rv32) => 0,
+        _ => 1,
+    }
+}
+
+/// What a run does after a semihosting call.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SemihostingAction {
+    /// Go on running after the `ebreak`.
+    Resume,
+    /// Stop the run with `StopReason::Exited` and the exit code.
+    Exit(u64),
+}
+
+/// The console of the semihosting calls. Calls are only served while it's enabled; otherwise
+/// their `ebreak` behaves like any other.
+#[derive(Debug, Default, Clone)]
+pub struct Semihosting {
+    enabled: bool,
+    /// The bytes written by the guest that the host hasn't taken yet.
+    output: VecDeque<u8>,
+    /// The number of output bytes dropped because the host didn't take them in time.
+    dropped: u64,
+    /// The bytes queued by the host that the guest hasn't read yet.
+    input: VecDeque<u8>,
+}
+
+impl Semihosting {
+    /// Create a disabled console.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop serving semihosting calls.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if semihosting calls are served.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Append `bytes` written by the guest to the output.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.output.len() == SEMIHOSTING_OUTPUT_SIZE {
+                self.output.pop_front();
+                self.dropped += 1;
+            }
+            self.output.push_back(byte);
+        }
+    }
+
+    /// Move up to `out.len()` bytes written by the guest into `out`, oldest first. Returns the
+    /// number of bytes moved.
+    pub fn take_output(&mut self, out: &mut [u8]) -> usize {
+        let len = out.len().min(self.output.len());
+        for (dst, src) in out.iter_mut().zip(self.output.drain(..len)) {
+            *dst = src;
+        }
+        len
+    }
+
+    /// Return the number of output bytes dropped because the host didn't take them in time.
+    pub fn dropped_output(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Queue `bytes` for the guest to read from the console.
+    pub fn push_input(&mut self, bytes: &[u8]) {
+        self.input.extend(bytes);
+    }
+
+    /// Return the number of bytes queued by the host that the guest hasn't read yet.
+    pub fn pending_input(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Take up to `len` bytes of the queued input, oldest first.
+    pub fn read_input(&mut self, len: u64) -> Vec<u8> {
+        let len = (len as usize).min(self.input.len());
+        self.input.drain(..len).collect()
+    }
+}
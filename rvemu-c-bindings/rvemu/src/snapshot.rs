@@ -0,0 +1,170 @@
+//! The snapshot module saves the state of the emulator into a versioned binary blob and restores
+//! it, e.g. to rewind a game. A snapshot holds the registers, the program counter, the privilege
+//! mode, the CSRs and DRAM. Devices and host-side state like breakpoints aren't included.
+//!
+//! The blob is little-endian: the magic `RVSS`, the version (4 bytes), the program counter, the
+//! mode, the 32 integer registers, the bits of the 32 floating-point registers, the number of
+//! CSRs and their values (8 bytes each), then the number of saved DRAM pages and each page as its
+//! index and its `SNAPSHOT_PAGE_SIZE` bytes. Pages of zeros are left out, so a snapshot is about
+//! the size of the memory the program uses. The width of the registers, RV32 or RV64, is the one
+//! the MXL field of the saved misa selects.
+
+use crate::cpu::{Mode, REGISTERS_COUNT};
+use crate::csr::{CSR_SIZE, MISA};
+use crate::emulator::Emulator;
+use crate::xlen::Xlen;
+
+/// The first bytes of every snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"RVSS";
+
+/// The version of the snapshot format. Snapshots of other versions are rejected.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The size of a DRAM page in a snapshot.
+pub const SNAPSHOT_PAGE_SIZE: usize = 4096;
+
+/// Return the snapshot of `emu`.
+pub fn save(emu: &Emulator) -> Vec<u8> {
+    let cpu = &emu.cpu;
+    let mut blob = Vec::new();
+    blob.extend_from_slice(&SNAPSHOT_MAGIC);
+    blob.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+
+    let mut words = vec![cpu.pc, cpu.mode as u64];
+    words.extend((0..REGISTERS_COUNT as u64).map(|i| cpu.xregs.read(i)));
+    words.extend((0..REGISTERS_COUNT as u64).map(|i| cpu.fregs.read(i).to_bits()));
+    words.push(CSR_SIZE as u64);
+    words.extend_from_slice(cpu.state.values());
+    for word in words {
+        blob.extend_from_slice(&word.to_le_bytes());
+    }
+
+    // The memory after the highest byte written is zero, so only the pages up to it are scanned.
+    let zeros = [0; SNAPSHOT_PAGE_SIZE];
+    let written = cpu.bus.written_dram_len().next_multiple_of(SNAPSHOT_PAGE_SIZE);
+    let pages: Vec<_> = cpu.bus.dram()[..written]
+        .chunks(SNAPSHOT_PAGE_SIZE)
+        .enumerate()
+        .filter(|(_, page)| *page != &zeros[..])
+        .collect();
+    blob.extend_from_slice(&(pages.len() as u64).to_le_bytes());
+    for (index, page) in pages {
+        blob.extend_from_slice(&(index as u64).to_le_bytes());
+        blob.extend_from_slice(page);
+    }
+    blob
+}
+
+/// A reader of the fields of a snapshot.
+struct Reader<'a> {
+    blob: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Return the next `len` bytes.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.blob.len() < len {
+            return Err("the snapshot is truncated".to_string());
+        }
+        let (bytes, rest) = self.blob.split_at(len);
+        self.blob = rest;
+        Ok(bytes)
+    }
+
+    /// Return the next 8-byte value.
+    fn word(&mut self) -> Result<u64, String> {
+        let mut word = [0; 8];
+        word.copy_from_slice(self.bytes(8)?);
+        Ok(u64::from_le_bytes(word))
+    }
+
+    /// Return the next `count` 8-byte values.
+    fn words(&mut self, count: usize) -> Result<Vec<u64>, String> {
+        (0..count).map(|_| self.word()).collect()
+    }
+}
+
+/// Restore the state of `emu` from the snapshot `blob`. Fails without changing `emu` if the blob
+/// isn't a valid snapshot of this version.
+pub fn restore(emu: &mut Emulator, blob: &[u8]) -> Result<(), String> {
+    let mut reader = Reader { blob };
+    if reader.bytes(4).ok() != Some(&SNAPSHOT_MAGIC[..]) {
+        return Err("not a snapshot".to_string());
+    }
+    let mut version = [0; 4];
+    version.copy_from_slice(reader.bytes(4)?);
+    let version = u32::from_le_bytes(version);
+    if version != SNAPSHOT_VERSION {
+        return Err(format!("unsupported snapshot version {}", version));
+    }
+
+    let pc = reader.word()?;
+    let mode = match reader.word()? {
+        0b00 => Mode::User,
+        0b01 => Mode::Supervisor,
+        0b11 => Mode::Machine,
+        mode => return Err(format!("unknown privilege mode {}", mode)),
+    };
+    let xregs = reader.words(REGISTERS_COUNT)?;
+    let fregs = reader.words(REGISTERS_COUNT)?;
+    let csr_count = reader.word()?;
+    if csr_count != CSR_SIZE as u64 {
+        return Err(format!("expected {} CSRs, found {}", CSR_SIZE, csr_count));
+    }
+    let csrs = reader.words(CSR_SIZE)?;
+    let misa = csrs[MISA as usize];
+    let xlen = Xlen::from_misa(misa).ok_or(format!("misa {:#x} selects no XLEN", misa))?;
+
+    let page_count = reader.word()?;
+    let dram_pages = (emu.cpu.bus.dram().len() / SNAPSHOT_PAGE_SIZE) as u64;
+    let mut pages = Vec::new();
+    for _ in 0..page_count {
+        let index = reader.word()?;
+        if index >= dram_pages {
+            return Err(format!("page {} is outside DRAM", index));
+        }
+        if matches!(pages.last(), Some(&(last, _)) if index as usize <= last) {
+            return Err(format!("page {} is out of order", index));
+        }
+        pages.push((index as usize, reader.bytes(SNAPSHOT_PAGE_SIZE)?));
+    }
+    if !reader.blob.is_empty() {
+        return Err("unexpected bytes after the snapshot".to_string());
+    }
+
+    let cpu = &mut emu.cpu;
+    cpu.pc = pc;
+    cpu.mode = mode;
+    for (i, value) in xregs.into_iter().enumerate() {
+        cpu.xregs.write(i as u64, value);
+    }
+    for (i, bits) in fregs.into_iter().enumerate() {
+        cpu.fregs.write(i as u64, f64::from_bits(bits));
+    }
+    cpu.state.set_values(&csrs);
+    cpu.set_xlen(xlen);
+    cpu.update_paging();
+
+    // Clear the pages the snapshot leaves out, skipping those already clear and those after the
+    // highest byte written.
+    let zeros = [0; SNAPSHOT_PAGE_SIZE];
+    let saved_len = pages.last().map_or(0, |&(index, _)| (index + 1) * SNAPSHOT_PAGE_SIZE);
+    let written = cpu.bus.written_dram_len().next_multiple_of(SNAPSHOT_PAGE_SIZE);
+    let mut saved = pages.into_iter().peekable();
+    for (index, page) in cpu
+        .bus
+        .dram_mut(written.max(saved_len))
+        .chunks_mut(SNAPSHOT_PAGE_SIZE)
+        .enumerate()
+    {
+        match saved.peek() {
+            Some((saved_index, bytes)) if *saved_index == index => {
+                page.copy_from_slice(bytes);
+                saved.next();
+            }
+            _ if *page != zeros[..] => page.copy_from_slice(&zeros),
+            _ => {}
+        }
+    }
+    Ok(())
+}
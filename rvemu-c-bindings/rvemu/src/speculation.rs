@@ -0,0 +1,176 @@
+//! The speculation module runs the emulator ahead on a throwaway copy of its state and returns
+//! what each step would do, e.g. for hints like "if you continue, a0 will be 0 here". The writes
+//! to DRAM are journaled page by page and undone afterwards, so only the pages the simulated
+//! steps write are copied, and everything else the steps changed is put back as it was.
+//!
+//! Devices and host memory can't be undone, so the simulated program can't access them: such an
+//! access faults and ends the simulation. Interrupts aren't taken, and environment calls aren't
+//! passed to the handler of the host.
+
+use std::collections::BTreeMap;
+use std::mem;
+
+use crate::atomics::AtomicEvents;
+use crate::cpu::Mode;
+use crate::csr_trace::CsrTrace;
+use crate::debug_print::DebugPrints;
+use crate::delta::StepDelta;
+use crate::devices::clint::Clint;
+use crate::devices::watchdog::Watchdog;
+use crate::emulator::Emulator;
+use crate::encoding_audit::EncodingAudit;
+use crate::exception::TrapInfo;
+use crate::guest_assert::GuestAsserts;
+use crate::memory_stats::MemoryStats;
+use crate::nan_boxing::NanBoxDiagnostics;
+use crate::run::{CancelToken, EbreakBehavior, StepInterrupts, StopReason};
+use crate::semihosting::Semihosting;
+use crate::trace::{AccessTrace, TraceBuffer};
+use crate::trap_return::{TrapReturn, TrapReturns};
+use crate::watchpoint::{WatchHit, Watchpoints};
+
+/// Everything a simulated step may change, except DRAM.
+struct Checkpoint {
+    pc: u64,
+    mode: Mode,
+    idle: bool,
+    xregs: Vec<u64>,
+    fregs: Vec<u64>,
+    csrs: Vec<u64>,
+    reservation_set: Vec<u64>,
+    clint: Clint,
+    watchdog: Watchdog,
+    watchpoints: Watchpoints,
+    access_trace: AccessTrace,
+    memory_stats: MemoryStats,
+    encoding_audit: EncodingAudit,
+    nan_box_diagnostics: NanBoxDiagnostics,
+    inst_counter: BTreeMap<String, u64>,
+    pre_inst: u64,
+    retired: u64,
+    interrupts: u64,
+    trace: TraceBuffer,
+    atomic_events: AtomicEvents,
+    csr_trace: CsrTrace,
+    debug_prints: DebugPrints,
+    guest_asserts: GuestAsserts,
+    semihosting: Semihosting,
+    trap_returns: TrapReturns,
+    last_trap: Option<TrapInfo>,
+    last_trap_return: Option<TrapReturn>,
+    last_watch_hit: Option<WatchHit>,
+    last_delta: Option<StepDelta>,
+}
+
+impl Checkpoint {
+    /// Save the state of `emu`.
+    fn save(emu: &mut Emulator) -> Self {
+        let mut reservation_set = Vec::new();
+        emu.cpu.swap_reservation_set(&mut reservation_set);
+        emu.cpu.swap_reservation_set(&mut reservation_set.clone());
+        let cpu = &emu.cpu;
+        Self {
+            pc: cpu.pc,
+            mode: cpu.mode,
+            idle: cpu.idle,
+            xregs: emu.xreg_values(),
+            fregs: emu.freg_values(),
+            csrs: cpu.state.values().to_vec(),
+            reservation_set,
+            clint: cpu.bus.clint.clone(),
+            watchdog: cpu.bus.watchdog.clone(),
+            watchpoints: cpu.watchpoints.clone(),
+            access_trace: cpu.access_trace.clone(),
+            memory_stats: cpu.memory_stats.clone(),
+            encoding_audit: cpu.encoding_audit.clone(),
+            nan_box_diagnostics: cpu.nan_box_diagnostics.clone(),
+            inst_counter: cpu.inst_counter.clone(),
+            pre_inst: cpu.pre_inst,
+            retired: emu.retired,
+            interrupts: emu.interrupts,
+            trace: emu.trace.clone(),
+            atomic_events: emu.atomic_events.clone(),
+            csr_trace: emu.csr_trace.clone(),
+            debug_prints: emu.debug_prints.clone(),
+            guest_asserts: emu.guest_asserts.clone(),
+            semihosting: emu.semihosting.clone(),
+            trap_returns: emu.trap_returns.clone(),
+            last_trap: emu.last_trap,
+            last_trap_return: emu.last_trap_return,
+            last_watch_hit: emu.last_watch_hit,
+            last_delta: emu.last_delta.clone(),
+        }
+    }
+
+    /// Put `emu` back in the saved state.
+    fn restore(mut self, emu: &mut Emulator) {
+        let cpu = &mut emu.cpu;
+        cpu.pc = self.pc;
+        cpu.mode = self.mode;
+        cpu.idle = self.idle;
+        for (i, &value) in self.xregs.iter().enumerate() {
+            cpu.xregs.write(i as u64, value);
+        }
+        for (i, &bits) in self.fregs.iter().enumerate() {
+            cpu.fregs.write(i as u64, f64::from_bits(bits));
+        }
+        cpu.state.set_values(&self.csrs);
+        cpu.update_paging();
+        cpu.swap_reservation_set(&mut self.reservation_set);
+        cpu.bus.clint = self.clint;
+        cpu.bus.watchdog = self.watchdog;
+        cpu.watchpoints = self.watchpoints;
+        cpu.access_trace = self.access_trace;
+        cpu.memory_stats = self.memory_stats;
+        cpu.encoding_audit = self.encoding_audit;
+        cpu.nan_box_diagnostics = self.nan_box_diagnostics;
+        cpu.inst_counter = self.inst_counter;
+        cpu.pre_inst = self.pre_inst;
+        emu.retired = self.retired;
+        emu.interrupts = self.interrupts;
+        emu.trace = self.trace;
+        emu.atomic_events = self.atomic_events;
+        emu.csr_trace = self.csr_trace;
+        emu.debug_prints = self.debug_prints;
+        emu.guest_asserts = self.guest_asserts;
+        emu.semihosting = self.semihosting;
+        emu.trap_returns = self.trap_returns;
+        emu.last_trap = self.last_trap;
+        emu.last_trap_return = self.last_trap_return;
+        emu.last_watch_hit = self.last_watch_hit;
+        emu.last_delta = self.last_delta;
+    }
+}
+
+/// Execute up to `steps` steps like `step_delta` and return their deltas, then undo them all, so
+/// `emu` is left as it was. The simulation ends early at the first step that would stop a run,
+/// other than at a watchpoint, e.g. a trap, an environment call or an access to a device.
+pub fn simulate(emu: &mut Emulator, steps: u64) -> Vec<StepDelta> {
+    let checkpoint = Checkpoint::save(emu);
+    let ecall_handler = emu.ecall_handler.take();
+    let ebreak_behavior = mem::replace(&mut emu.ebreak_behavior, EbreakBehavior::Stop);
+    let cancel_token = mem::replace(&mut emu.cancel_token, CancelToken::new());
+    let step_interrupts = mem::replace(&mut emu.step_interrupts, StepInterrupts::Defer);
+    // Keep the simulated steps from evicting checkpoints to fit their traces in the budget.
+    let history = mem::take(&mut emu.history);
+    emu.cpu.bus.start_speculation();
+
+    let mut deltas = Vec::new();
+    for _ in 0..steps {
+        let summary = emu.step_delta();
+        deltas.push(emu.last_delta.take().expect("a step records its delta"));
+        match summary.reason {
+            StopReason::StepLimit | StopReason::Watchpoint => {}
+            _ => break,
+        }
+    }
+
+    emu.cpu.bus.end_speculation();
+    emu.ecall_handler = ecall_handler;
+    emu.ebreak_behavior = ebreak_behavior;
+    emu.cancel_token = cancel_token;
+    emu.step_interrupts = step_interrupts;
+    emu.history = history;
+    checkpoint.restore(emu);
+    deltas
+}
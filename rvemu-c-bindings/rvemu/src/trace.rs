@@ -0,0 +1,192 @@
+//! The trace module contains a ring buffer of the most recently executed instructions. It makes
+//! post-mortem debugging possible without stepping one instruction at a time from the host. It
+//! also contains the log of memory accesses to selected regions.
+
+use std::collections::VecDeque;
+
+/// The number of instructions the emulator remembers by default, e.g. for crash reports.
+pub const DEFAULT_TRACE_CAPACITY: usize = 32;
+
+/// An executed instruction. The layout is C-compatible so entries can be copied over the FFI as
+/// they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct TraceEntry {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The instruction word. Compressed instructions are 16 bits.
+    pub inst: u64,
+}
+
+/// The ring buffer of executed instructions. The oldest entry is dropped when it's full.
+#[derive(Debug, Clone)]
+pub struct TraceBuffer {
+    capacity: usize,
+    entries: VecDeque<TraceEntry>,
+}
+
+impl TraceBuffer {
+    /// Create a new trace buffer holding up to `capacity` entries. A capacity of 0 disables
+    /// tracing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Return the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of entries. The oldest entries are dropped if they don't fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Record an executed instruction.
+    pub fn push(&mut self, pc: u64, inst: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(TraceEntry { pc, inst });
+    }
+
+    /// Return the number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if nothing is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the recorded entries from the oldest to the newest.
+    pub fn entries(&self) -> impl Iterator<Item = &TraceEntry> {
+        self.entries.iter()
+    }
+
+    /// Forget all recorded entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// The maximum number of memory accesses recorded by an `AccessTrace`. Later accesses are
+/// counted but not recorded.
+pub const MAX_ACCESS_TRACE_ENTRIES: usize = 1 << 20;
+
+/// The kind of a memory access.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum AccessKind {
+    /// A load, or the read of an atomic memory operation.
+    Load = 0,
+    /// A store, or the write of an atomic memory operation.
+    Store = 1,
+}
+
+/// A load or a store executed by the program. The layout is C-compatible so entries can be
+/// copied over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct MemoryAccess {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The accessed address.
+    pub addr: u64,
+    /// The size of the access in bytes.
+    pub size: u64,
+    /// The loaded or stored value.
+    pub value: u64,
+    /// Whether the instruction loaded or stored.
+    pub kind: AccessKind,
+}
+
+/// The log of loads and stores to selected address ranges. Nothing is recorded until a region
+/// is added, so programs that don't need it don't pay for it.
+#[derive(Debug, Default, Clone)]
+pub struct AccessTrace {
+    regions: Vec<(u64, u64)>,
+    entries: Vec<MemoryAccess>,
+    dropped: u64,
+}
+
+impl AccessTrace {
+    /// Create an access trace without regions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start (`enable` = true) or stop recording accesses to `[addr, addr + len)`.
+    pub fn set_region(&mut self, addr: u64, len: u64, enable: bool) {
+        self.regions.retain(|&region| region != (addr, len));
+        if enable && len > 0 {
+            self.regions.push((addr, len));
+        }
+    }
+
+    /// Return true if any region is traced.
+    pub fn is_enabled(&self) -> bool {
+        !self.regions.is_empty()
+    }
+
+    /// Record an access of `size` bytes at `addr` if it touches a traced region.
+    pub fn record(&mut self, pc: u64, addr: u64, size: u64, value: u64, kind: AccessKind) {
+        let end = addr.saturating_add(size);
+        if !self
+            .regions
+            .iter()
+            .any(|&(start, len)| addr < start.saturating_add(len) && start < end)
+        {
+            return;
+        }
+
+        if self.entries.len() == MAX_ACCESS_TRACE_ENTRIES {
+            self.dropped += 1;
+            return;
+        }
+        self.entries.push(MemoryAccess {
+            pc,
+            addr,
+            size,
+            value,
+            kind,
+        });
+    }
+
+    /// Return the recorded accesses from the oldest to the newest.
+    pub fn entries(&self) -> &[MemoryAccess] {
+        &self.entries
+    }
+
+    /// Return the number of accesses that didn't fit in the trace.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove and return up to `count` of the oldest recorded accesses. The dropped count is
+    /// reset once every access was taken.
+    pub fn take_oldest(&mut self, count: usize) -> Vec<MemoryAccess> {
+        let count = count.min(self.entries.len());
+        let taken = self.entries.drain(..count).collect();
+        if self.entries.is_empty() {
+            self.dropped = 0;
+        }
+        taken
+    }
+
+    /// Forget all recorded accesses. The regions are kept.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.dropped = 0;
+    }
+}
@@ -0,0 +1,108 @@
+//! The trap return module logs the privilege-return instructions (`mret`, `sret` and `uret`) as
+//! events, so the OS lessons can show where a trap handler returns to and which privilege mode
+//! the program continues in.
+
+/// The maximum number of events kept. Later events are counted but not recorded.
+pub const MAX_TRAP_RETURNS: usize = 1 << 16;
+
+/// The instruction of a trap return.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ReturnInstruction {
+    /// `mret`: return from a machine-mode trap handler to `mepc`.
+    Mret = 0,
+    /// `sret`: return from a supervisor-mode trap handler to `sepc`.
+    Sret = 1,
+    /// `uret`: return from a user-mode trap handler to `uepc`.
+    Uret = 2,
+}
+
+impl ReturnInstruction {
+    /// Return the privilege-return instruction `inst`, or `None` if it's another instruction.
+    pub fn decode(inst: u64) -> Option<Self> {
+        match inst & 0xffff_ffff {
+            0x3020_0073 => Some(ReturnInstruction::Mret),
+            0x1020_0073 => Some(ReturnInstruction::Sret),
+            0x0020_0073 => Some(ReturnInstruction::Uret),
+            _ => None,
+        }
+    }
+}
+
+/// An executed privilege-return instruction. The layout is C-compatible so events can be copied
+/// over the FFI as they are.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct TrapReturn {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The address execution continues at.
+    pub target: u64,
+    /// The privilege level before the instruction: 0 for user, 1 for supervisor and 3 for
+    /// machine mode.
+    pub from: u64,
+    /// The privilege level after the instruction.
+    pub to: u64,
+    /// The instruction.
+    pub instruction: ReturnInstruction,
+}
+
+/// The log of trap returns. Nothing is recorded until it's enabled.
+#[derive(Debug, Default, Clone)]
+pub struct TrapReturns {
+    enabled: bool,
+    events: Vec<TrapReturn>,
+    dropped: u64,
+}
+
+impl TrapReturns {
+    /// Create a disabled log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop recording events.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Return true if events are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Record an event.
+    pub fn record(&mut self, event: TrapReturn) {
+        if self.events.len() == MAX_TRAP_RETURNS {
+            self.dropped += 1;
+            return;
+        }
+        self.events.push(event);
+    }
+
+    /// Return the recorded events from the oldest to the newest.
+    pub fn events(&self) -> &[TrapReturn] {
+        &self.events
+    }
+
+    /// Return the number of events that didn't fit in the log.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Remove and return the recorded events.
+    pub fn take(&mut self) -> Vec<TrapReturn> {
+        self.take_oldest(self.events.len())
+    }
+
+    /// Remove and return up to `count` of the oldest recorded events. The dropped count is
+    /// reset once every event was taken.
+    pub fn take_oldest(&mut self, count: usize) -> Vec<TrapReturn> {
+        let count = count.min(self.events.len());
+        let taken = self.events.drain(..count).collect();
+        if self.events.is_empty() {
+            self.dropped = 0;
+        }
+        taken
+    }
+}
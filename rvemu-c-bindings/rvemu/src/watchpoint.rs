@@ -0,0 +1,147 @@
+//! The watchpoint module contains memory watchpoints. A run stops right after a load or a store
+//! to watched bytes, and the hit tells which instruction accessed them, and for a store, what it
+//! changed the value from and to.
+
+use crate::trace::AccessKind;
+
+/// The accesses a watchpoint stops at.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WatchKind {
+    /// Loads.
+    Read = 1,
+    /// Stores.
+    Write = 2,
+    /// Loads and stores.
+    Access = 3,
+}
+
+impl WatchKind {
+    /// Return the kind with the code `code`.
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(WatchKind::Read),
+            2 => Some(WatchKind::Write),
+            3 => Some(WatchKind::Access),
+            _ => None,
+        }
+    }
+
+    /// Return true if an access of `kind` stops at a watchpoint of this kind.
+    pub fn includes(self, kind: AccessKind) -> bool {
+        matches!(
+            (self, kind),
+            (WatchKind::Access, _)
+                | (WatchKind::Read, AccessKind::Load)
+                | (WatchKind::Write, AccessKind::Store)
+        )
+    }
+}
+
+/// A range of watched bytes, `[addr, addr + len)`, in the virtual address space.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Watchpoint {
+    /// The first watched byte.
+    pub addr: u64,
+    /// The number of watched bytes.
+    pub len: u64,
+    /// The accesses it stops at.
+    pub kind: WatchKind,
+}
+
+impl Watchpoint {
+    /// Return true if an access of `size` bytes at `addr` touches a watched byte.
+    pub fn overlaps(&self, addr: u64, size: u64) -> bool {
+        addr < self.addr.saturating_add(self.len) && self.addr < addr.saturating_add(size)
+    }
+}
+
+/// An access that hit a watchpoint.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct WatchHit {
+    /// The address of the instruction.
+    pub pc: u64,
+    /// The address the instruction accessed.
+    pub addr: u64,
+    /// The size of the access in bytes.
+    pub size: u64,
+    /// The value at `addr` before the access, `size` bytes wide, or 0 if it's unknown.
+    pub old_value: u64,
+    /// The value at `addr` after the access, `size` bytes wide: the stored value, or the same
+    /// as `old_value` for a load.
+    pub new_value: u64,
+    /// Whether the instruction loaded or stored.
+    pub kind: AccessKind,
+    /// 1 if `old_value` is known, 0 for a store to a device: reading its registers can have side
+    /// effects, e.g. popping a byte from a FIFO, so they aren't read before the store.
+    pub old_value_known: u32,
+}
+
+/// The set of watchpoints of a CPU and the last hit.
+#[derive(Debug, Default, Clone)]
+pub struct Watchpoints {
+    list: Vec<Watchpoint>,
+    hit: Option<WatchHit>,
+}
+
+impl Watchpoints {
+    /// Create an empty set of watchpoints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if no watchpoint is set.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Watch the accesses of `kind` to `[addr, addr + len)`. Returns false if the range is empty
+    /// or already watched for `kind`.
+    pub fn add(&mut self, addr: u64, len: u64, kind: WatchKind) -> bool {
+        let watchpoint = Watchpoint { addr, len, kind };
+        if len == 0 || self.list.contains(&watchpoint) {
+            return false;
+        }
+        self.list.push(watchpoint);
+        true
+    }
+
+    /// Stop watching the accesses of `kind` to `[addr, addr + len)`. Returns false if the range
+    /// wasn't watched for `kind`.
+    pub fn remove(&mut self, addr: u64, len: u64, kind: WatchKind) -> bool {
+        let watchpoint = Watchpoint { addr, len, kind };
+        let count = self.list.len();
+        self.list.retain(|w| *w != watchpoint);
+        self.list.len() != count
+    }
+
+    /// Remove all watchpoints and the pending hit.
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.hit = None;
+    }
+
+    /// Return the watchpoints in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &Watchpoint> {
+        self.list.iter()
+    }
+
+    /// Return true if an access of `kind` and `size` bytes at `addr` touches a byte watched for
+    /// it.
+    pub fn matches(&self, addr: u64, size: u64, kind: AccessKind) -> bool {
+        self.list
+            .iter()
+            .any(|w| w.kind.includes(kind) && w.overlaps(addr, size))
+    }
+
+    /// Record an access that hit a watchpoint. It replaces the previous hit if it wasn't taken.
+    pub fn record(&mut self, hit: WatchHit) {
+        self.hit = Some(hit);
+    }
+
+    /// Return the pending hit and clear it.
+    pub fn take_hit(&mut self) -> Option<WatchHit> {
+        self.hit.take()
+    }
+}
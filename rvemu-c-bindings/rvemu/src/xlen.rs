@@ -0,0 +1,155 @@
+//! The xlen module selects the width of the integer registers, XLEN. The core is RV64, and RV32
+//! runs on top of it: a register holds its 32-bit value sign-extended to 64 bits, as RV64 holds
+//! the results of the W instructions, so most instructions compute the same low 32 bits either
+//! way. The CPU executes the others, e.g. the right shifts and the upper half of a product, with
+//! their 32-bit semantics, and raises an illegal instruction exception for the encodings that
+//! only exist in RV64. Addresses and the program counter wrap around at 4 GiB. Paging isn't
+//! supported in RV32, as it would be Sv32.
+
+/// The base integer ISA, by the width of its registers, that a program is assembled for or the
+/// CPU executes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Xlen {
+    Rv32,
+    Rv64,
+}
+
+impl Xlen {
+    /// Return the width of the registers in bits.
+    pub fn bits(self) -> u32 {
+        match self {
+            Xlen::Rv32 => 32,
+            Xlen::Rv64 => 64,
+        }
+    }
+
+    /// Return the base ISA whose registers are `bits` bits wide, or `None` if it isn't 32 or 64.
+    pub fn from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            32 => Some(Xlen::Rv32),
+            64 => Some(Xlen::Rv64),
+            _ => None,
+        }
+    }
+
+    /// Return the base ISA that the MXL field of the misa value `misa` selects, or `None` if it
+    /// selects neither RV32 nor RV64.
+    pub fn from_misa(misa: u64) -> Option<Self> {
+        match (misa >> 62, (misa >> 30) & 0b11) {
+            (2, _) => Some(Xlen::Rv64),
+            (0, 1) => Some(Xlen::Rv32),
+            _ => None,
+        }
+    }
+
+    /// Return the misa value `misa` with the MXL field of the width: 1 in bits 31:30 for RV32,
+    /// 2 in bits 63:62 for RV64. The extensions are kept.
+    pub fn misa(self, misa: u64) -> u64 {
+        let extensions = misa & 0x3ff_ffff;
+        match self {
+            Xlen::Rv32 => (1 << 30) | extensions,
+            Xlen::Rv64 => (2 << 62) | extensions,
+        }
+    }
+
+    /// Return `value` as a register holds it: sign-extended from 32 bits in RV32.
+    pub fn sign_extend(self, value: u64) -> u64 {
+        match self {
+            Xlen::Rv32 => value as i32 as i64 as u64,
+            Xlen::Rv64 => value,
+        }
+    }
+
+    /// Return `value` truncated to the width, e.g. the value of a register as the host should
+    /// see it, or the address an access wraps around to.
+    pub fn truncate(self, value: u64) -> u64 {
+        match self {
+            Xlen::Rv32 => value & 0xffff_ffff,
+            Xlen::Rv64 => value,
+        }
+    }
+}
+
+/// Return true if the instruction `inst` only exists in RV64 and is illegal in RV32: the W
+/// instructions, the doubleword loads, stores and AMOs, `lwu`, the shifts by 32 or more, and the
+/// conversions and moves between doublewords and floating-point registers. The compressed
+/// instructions whose encodings RV32 uses for others, e.g. `c.ld` for `c.flw`, aren't included.
+pub fn is_rv64_only(inst: u64) -> bool {
+    let funct3 = (inst >> 12) & 0x7;
+    match inst & 0x3 {
+        0b11 => {
+            let rs2 = (inst >> 20) & 0x1f;
+            match (inst & 0x7f, funct3, inst >> 25) {
+                (0x1b, _, _) | (0x3b, _, _) => true,
+                (0x03, 0x3, _) | (0x03, 0x6, _) | (0x23, 0x3, _) | (0x2f, 0x3, _) => true,
+                // slli, srli and srai with shamt[5] set.
+                (0x13, 0x1, funct7) | (0x13, 0x5, funct7) => funct7 & 1 == 1,
+                // fcvt.l[u].s, fcvt.l[u].d, fcvt.s.l[u] and fcvt.d.l[u].
+                (0x53, _, 0x60) | (0x53, _, 0x61) | (0x53, _, 0x68) | (0x53, _, 0x69) => rs2 >= 2,
+                // fmv.x.d and fmv.d.x.
+                (0x53, 0x0, 0x71) | (0x53, 0x0, 0x79) => true,
+                _ => false,
+            }
+        }
+        quadrant => {
+            let funct3 = (inst >> 13) & 0x7;
+            // shamt[5] of the shifts, and the bit that tells c.subw and c.addw from the others.
+            let bit12 = (inst >> 12) & 1 == 1;
+            match (quadrant, funct3) {
+                // c.srli and c.srai with shamt[5] set, c.subw and c.addw.
+                (0b01, 0x4) => matches!((inst >> 10) & 0x3, 0x0 | 0x1 | 0x3) && bit12,
+                // c.slli with shamt[5] set.
+                (0b10, 0x0) => bit12,
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Return the 32-bit instruction the compressed instruction `inst` expands to in RV32, if its
+/// encoding means another instruction in RV64: `c.flw`, `c.fsw`, `c.flwsp` and `c.fswsp`. `c.jal`
+/// isn't included, as it links the address after a 2-byte instruction.
+pub fn expand_rv32_compressed(inst: u64) -> Option<u64> {
+    let quadrant = inst & 0x3;
+    let funct3 = (inst >> 13) & 0x7;
+    // rd', rs1' and rs2' of the CL- and CS-type formats, and rd and rs2 of the others.
+    let rd_prime = ((inst >> 2) & 0x7) + 8;
+    let rs1_prime = ((inst >> 7) & 0x7) + 8;
+    let rd = (inst >> 7) & 0x1f;
+    let rs2 = (inst >> 2) & 0x1f;
+    // offset[5:3|2|6] = inst[12:10|6|5]
+    let word_offset = ((inst << 1) & 0x40) | ((inst >> 7) & 0x38) | ((inst >> 4) & 0x4);
+
+    match (quadrant, funct3) {
+        // c.flw: flw rd', offset(rs1').
+        (0b00, 0x3) => Some(i_type(word_offset, rs1_prime, 0x2, rd_prime, 0x07)),
+        // c.fsw: fsw rs2', offset(rs1').
+        (0b00, 0x7) => Some(s_type(word_offset, rd_prime, rs1_prime, 0x2, 0x27)),
+        // c.flwsp: flw rd, offset(x2), where offset[5|4:2|7:6] = inst[12|6:4|3:2].
+        (0b10, 0x3) => {
+            let offset = ((inst >> 7) & 0x20) | ((inst >> 2) & 0x1c) | ((inst << 4) & 0xc0);
+            Some(i_type(offset, 2, 0x2, rd, 0x07))
+        }
+        // c.fswsp: fsw rs2, offset(x2), where offset[5:2|7:6] = inst[12:9|8:7].
+        (0b10, 0x7) => {
+            let offset = ((inst >> 7) & 0x3c) | ((inst >> 1) & 0xc0);
+            Some(s_type(offset, rs2, 2, 0x2, 0x27))
+        }
+        _ => None,
+    }
+}
+
+/// Return the I-type instruction with the given fields.
+fn i_type(imm: u64, rs1: u64, funct3: u64, rd: u64, opcode: u64) -> u64 {
+    ((imm & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+/// Return the S-type instruction with the given fields.
+fn s_type(imm: u64, rs2: u64, rs1: u64, funct3: u64, opcode: u64) -> u64 {
+    (((imm >> 5) & 0x7f) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1f) << 7)
+        | opcode
+}
@@ -0,0 +1,47 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::trace::{AccessKind, MemoryAccess};
+
+#[test]
+fn only_accesses_to_traced_regions_are_recorded() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.xregs.write(1, DRAM_BASE + 4);
+    emu.cpu.access_trace.set_region(DRAM_BASE + 4, 4, true);
+
+    emu.step().unwrap();
+    emu.step().unwrap();
+    // An access outside the region isn't recorded.
+    emu.cpu.xregs.write(1, DRAM_BASE + 8);
+    emu.step().unwrap();
+
+    assert_eq!(
+        &[
+            MemoryAccess {
+                pc: DRAM_BASE,
+                addr: DRAM_BASE + 4,
+                size: 4,
+                value: 0x0020_a023,
+                kind: AccessKind::Load,
+            },
+            MemoryAccess {
+                pc: DRAM_BASE + 4,
+                addr: DRAM_BASE + 4,
+                size: 4,
+                value: 0x0020_a023,
+                kind: AccessKind::Store,
+            },
+        ],
+        emu.cpu.access_trace.entries()
+    );
+
+    emu.cpu.access_trace.set_region(DRAM_BASE + 4, 4, false);
+    emu.cpu.access_trace.clear();
+    assert!(!emu.cpu.access_trace.is_enabled());
+    assert!(emu.cpu.access_trace.entries().is_empty());
+}
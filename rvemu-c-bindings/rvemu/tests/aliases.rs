@@ -0,0 +1,62 @@
+use rvemu::aliases::RegisterAliases;
+use rvemu::bus::DRAM_BASE;
+use rvemu::csr_names::CsrNames;
+use rvemu::disasm::disassemble_with_names;
+
+#[test]
+fn aliases_are_replaced_in_operands() {
+    let mut aliases = RegisterAliases::new();
+    assert!(aliases.add("score", 10));
+    assert!(aliases.add("pos_x", 9));
+
+    assert_eq!("addi a0, a0, 1", aliases.substitute("addi score, score, 1"));
+    assert_eq!("sw a0, 8(s1)", aliases.substitute("sw score, 8(pos_x)"));
+    // Labels and mnemonics are kept even if they look like an alias.
+    assert_eq!(
+        "score: add s1, a0, x0",
+        aliases.substitute("score: add pos_x, score, x0")
+    );
+    // Only whole words are replaced.
+    assert_eq!(
+        "bne a0, x0, score_loop",
+        aliases.substitute("bne score, x0, score_loop")
+    );
+    // A colon after the mnemonic doesn't make a label, and comments are kept.
+    assert_eq!(
+        "a: b: addi a0, s1, 1 # score: pos_x + 1",
+        aliases.substitute("a: b: addi score, pos_x, 1 # score: pos_x + 1")
+    );
+}
+
+#[test]
+fn register_names_are_not_aliases() {
+    let mut aliases = RegisterAliases::new();
+    assert!(!aliases.add("a0", 10));
+    assert!(!aliases.add("x31", 10));
+    assert!(!aliases.add("fp", 10));
+    assert!(!aliases.add("2fast", 10));
+    assert!(!aliases.add("speed", 32));
+    assert!(aliases.is_empty());
+
+    assert!(aliases.add("speed", 5));
+    assert_eq!("t0 (speed)", aliases.describe(5));
+    assert_eq!("t1", aliases.describe(6));
+}
+
+#[test]
+fn disassembly_shows_aliases() {
+    let mut aliases = RegisterAliases::new();
+    assert!(aliases.add("score", 10));
+    assert!(aliases.add("points", 10));
+
+    // addi a0, a0, 1; sw a0, 8(s1)
+    let names = CsrNames::new();
+    assert_eq!(
+        "addi points, points, 1",
+        disassemble_with_names(0x0015_0513, DRAM_BASE, &names, &aliases)
+    );
+    assert_eq!(
+        "sw points, 8(s1)",
+        disassemble_with_names(0x00a4_a423, DRAM_BASE, &names, &aliases)
+    );
+}
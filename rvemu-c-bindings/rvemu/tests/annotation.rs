@@ -0,0 +1,66 @@
+use rvemu::annotation::{
+    step_with_annotations, Narration, OperandValue, ANNOTATION_BRANCH, ANNOTATION_MEMORY,
+    ANNOTATION_TAKEN, ANNOTATION_WRITES_RD,
+};
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+#[test]
+fn steps_are_annotated_for_the_tutorial() {
+    let source = "addi t0, zero, 5\n\
+                  add t1, t0, t0\n\
+                  auipc a0, 1\n\
+                  sw t1, 8(a0)\n\
+                  beq t0, t1, 8\n\
+                  bne t0, t1, 8\n\
+                  ecall\n\
+                  ecall";
+    let mut emu = Emulator::new();
+    emu.initialize_dram(assemble(source, Xlen::Rv64).unwrap());
+    emu.initialize_pc(DRAM_BASE);
+
+    let addi = step_with_annotations(&mut emu);
+    assert_eq!("addi t0, zero, 5", addi.disassembly());
+    assert_eq!(Narration::ComputeImmediate, addi.narration);
+    assert_eq!(1, addi.source_count);
+    assert_eq!(
+        (ANNOTATION_WRITES_RD, 5, 5),
+        (addi.flags, addi.rd, addi.result)
+    );
+
+    let add = step_with_annotations(&mut emu);
+    assert_eq!(Narration::Compute, add.narration);
+    let t0 = OperandValue { reg: 5, value: 5 };
+    assert_eq!((2, [t0, t0]), (add.source_count, add.sources));
+    assert_eq!((6, 10), (add.rd, add.result));
+
+    step_with_annotations(&mut emu);
+    let store = step_with_annotations(&mut emu);
+    assert_eq!(Narration::Store, store.narration);
+    assert_eq!(ANNOTATION_MEMORY, store.flags);
+    assert_eq!(DRAM_BASE + 0x1010, store.addr);
+
+    let beq = step_with_annotations(&mut emu);
+    assert_eq!(
+        (Narration::BranchNotTaken, ANNOTATION_BRANCH),
+        (beq.narration, beq.flags)
+    );
+    let bne = step_with_annotations(&mut emu);
+    assert_eq!(Narration::BranchTaken, bne.narration);
+    assert_eq!(ANNOTATION_BRANCH | ANNOTATION_TAKEN, bne.flags);
+    assert_eq!(bne.pc + 8, bne.next_pc);
+
+    let ecall = step_with_annotations(&mut emu);
+    assert_eq!(StopReason::Yielded, ecall.reason);
+    assert_eq!(Narration::EnvironmentCall, ecall.narration);
+
+    // The word after the program is 0, an illegal compressed instruction.
+    let trap = step_with_annotations(&mut emu);
+    assert_eq!(
+        (StopReason::Trapped, Narration::Trap),
+        (trap.reason, trap.narration)
+    );
+    assert_eq!(0, trap.source_count);
+}
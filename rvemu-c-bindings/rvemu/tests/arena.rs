@@ -0,0 +1,120 @@
+use rvemu::arena::{Arbitration, Arena, ArenaMemory, BotState};
+use rvemu::bus::DRAM_BASE;
+use rvemu::context::HartContext;
+use rvemu::cpu::Mode;
+use rvemu::emulator::Emulator;
+use rvemu::pmp;
+use rvemu::run::StopReason;
+
+#[test]
+fn bots_take_turns_until_they_stop() {
+    let mut emu = Emulator::new();
+    let counter = [
+        0x93, 0x82, 0x12, 0x00, // addi t0, t0, 1
+        0x6f, 0xf0, 0xdf, 0xff, // jal zero, -4
+    ];
+    let yielder = [
+        0x73, 0x00, 0x00, 0x00, // ecall
+        0x6f, 0xf0, 0xdf, 0xff, // jal zero, -4
+    ];
+    emu.load_program_at("counter", DRAM_BASE, &counter, DRAM_BASE)
+        .unwrap();
+    emu.load_program_at("yielder", DRAM_BASE + 0x1000, &yielder, DRAM_BASE + 0x1000)
+        .unwrap();
+
+    let mut arena = Arena::new(10, ArenaMemory::Shared);
+    assert_eq!(Some(0), arena.add_bot(&emu, 0, 0x1000, 25));
+    assert_eq!(Some(1), arena.add_bot(&emu, 1, 0x1000, 100));
+    assert_eq!(None, arena.add_bot(&emu, 2, 0x1000, 100));
+
+    // The game eliminates the yielder after its third call.
+    let mut calls = 0;
+    let rounds = arena.run(&mut emu, 10, |_, bot, summary| {
+        if bot == 1 && summary.reason == StopReason::Yielded {
+            calls += 1;
+            if calls == 3 {
+                return Arbitration::Eliminate;
+            }
+        }
+        Arbitration::Continue
+    });
+    assert_eq!(3, rounds);
+
+    let counter = arena.status(0).unwrap();
+    assert_eq!(BotState::OutOfSteps, counter.state);
+    assert_eq!(25, counter.steps);
+    assert_eq!(13, arena.context_mut(0).unwrap().xregs.read(5));
+    assert_eq!(BotState::Eliminated, arena.status(1).unwrap().state);
+    // Its stack starts at the end of its region.
+    assert_eq!(
+        DRAM_BASE + 0x2000,
+        arena.context_mut(1).unwrap().xregs.read(2)
+    );
+
+    // Granting more steps brings the counter back.
+    assert!(arena.set_max_steps(0, 30));
+    assert_eq!(BotState::Running, arena.status(0).unwrap().state);
+    assert_eq!(1, arena.run(&mut emu, 10, |_, _, _| Arbitration::Continue));
+    assert_eq!(30, arena.status(0).unwrap().steps);
+}
+
+#[test]
+fn contexts_stop_at_their_quota() {
+    let mut emu = Emulator::new();
+    let counter = [
+        0x93, 0x82, 0x12, 0x00, // addi t0, t0, 1
+        0x6f, 0xf0, 0xdf, 0xff, // jal zero, -4
+    ];
+    emu.load_program_at("counter", DRAM_BASE, &counter, DRAM_BASE)
+        .unwrap();
+
+    let mut context = HartContext::new(DRAM_BASE, DRAM_BASE + 0x1000, Mode::Machine);
+    context.set_quota(Some(7));
+    let summary = context.run(&mut emu, 5);
+    assert_eq!((StopReason::StepLimit, 5), (summary.reason, summary.steps));
+    let summary = context.run(&mut emu, 5);
+    assert_eq!(
+        (StopReason::LimitExceeded, 2),
+        (summary.reason, summary.steps)
+    );
+    assert_eq!(0, context.run(&mut emu, 5).steps);
+    assert_eq!((7, 4), (context.retired(), context.xregs.read(5)));
+
+    // The context that was running is untouched.
+    assert_eq!(0, emu.cpu.xregs.read(5));
+}
+
+#[test]
+fn partitioned_bots_only_access_their_region_and_the_shared_one() {
+    let mut emu = Emulator::new();
+    let writer = [
+        0x23, 0x30, 0x25, 0x00, // sd sp, 0(a0)
+        0x73, 0x00, 0x00, 0x00, // ecall
+        0x6f, 0xf0, 0x9f, 0xff, // jal zero, -8
+    ];
+    let board = DRAM_BASE + 0x8000;
+    emu.load_program_at("a", DRAM_BASE, &writer, DRAM_BASE)
+        .unwrap();
+    emu.load_program_at("b", DRAM_BASE + 0x1000, &writer, DRAM_BASE + 0x1000)
+        .unwrap();
+
+    let mut arena = Arena::new(10, ArenaMemory::Partitioned);
+    arena.set_shared_region(board, 0x100);
+    arena.add_bot(&emu, 0, 0x1000, 100).unwrap();
+    arena.add_bot(&emu, 1, 0x1000, 100).unwrap();
+    // A writes to the board, B tries to overwrite A's code.
+    arena.context_mut(0).unwrap().xregs.write(10, board);
+    arena.context_mut(1).unwrap().xregs.write(10, DRAM_BASE);
+
+    assert_eq!(1, arena.run(&mut emu, 1, |_, _, _| Arbitration::Continue));
+    assert_eq!(BotState::Running, arena.status(0).unwrap().state);
+    assert_eq!(DRAM_BASE + 0x1000, emu.cpu.bus.read(board, 64).unwrap());
+    let b = arena.status(1).unwrap();
+    assert_eq!(BotState::Trapped, b.state);
+    assert_eq!(7, b.cause);
+    assert_eq!(0x23, emu.cpu.bus.read(DRAM_BASE, 8).unwrap());
+
+    // The host's PMP configuration is restored after the match.
+    assert_eq!(0, pmp::cfg(&emu.cpu.state, 1));
+    assert_eq!(0, pmp::cfg(&emu.cpu.state, 3));
+}
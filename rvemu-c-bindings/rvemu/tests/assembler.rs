@@ -0,0 +1,480 @@
+use rvemu::assembler::{
+    assemble, assemble_isa, assemble_line, assemble_with, assemble_with_symbols, encode,
+    AssembleError, Isa, Symbol, Xlen,
+};
+use rvemu::bus::DRAM_BASE;
+use rvemu::disasm::disassemble;
+use rvemu::emulator::Emulator;
+use rvemu::object::Section;
+
+#[test]
+fn instructions_are_encoded() {
+    let encoded = |line| encode(line, Xlen::Rv64).unwrap();
+    assert_eq!(0x001f_8f93, encoded("addi x31, x31, 1"));
+    assert_eq!(0xfff5_0513, encoded("addi a0, a0, -1"));
+    assert_eq!(0x0000_a103, encoded("lw x2, 0(x1)"));
+    assert_eq!(0x00b5_3423, encoded("sd a1, 8(a0)"));
+    assert_eq!(0xfe05_1ee3, encoded("bne a0, zero, -4"));
+    assert_eq!(0x0000_006f, encoded("jal zero, 0"));
+    assert_eq!(0x0080_00ef, encoded("jal 8"));
+    assert_eq!(0x0000_8067, encoded("jalr zero, 0(ra)"));
+    assert_eq!(0x0000_8067, encoded("jalr zero, ra, 0"));
+    assert_eq!(0x8010_0537, encoded("lui a0, 0x80100"));
+    assert_eq!(0x4035_5513, encoded("srai a0, a0, 3"));
+    assert_eq!(0x03f5_1513, encoded("slli a0, a0, 63"));
+    assert_eq!(0x3000_2573, encoded("csrrs a0, mstatus, zero"));
+    assert_eq!(0x3410_d073, encoded("csrrwi zero, 0x341, 1"));
+    assert_eq!(0x0ff0_000f, encoded("fence"));
+    assert_eq!(0x0330_000f, encoded("fence rw, rw"));
+    assert_eq!(0x0000_100f, encoded("fence.i"));
+    assert_eq!(0x0000_0073, encoded("ECALL"));
+    assert_eq!(0x3020_0073, encoded("mret"));
+}
+
+#[test]
+fn disassembly_assembles_to_the_same_instructions() {
+    let sources = [
+        "add a0, a1, a2",
+        "sub t0, t1, t2",
+        "sraw s0, s1, a0",
+        "addi sp, sp, -16",
+        "sltiu a0, a0, 1",
+        "addiw a0, a0, 2047",
+        "srli a0, a0, 32",
+        "sraiw a0, a0, 31",
+        "lbu a0, -1(a1)",
+        "ld ra, 8(sp)",
+        "sw a0, 2044(gp)",
+        "lui a0, 0xfffff",
+        "auipc t0, 0x1",
+        "jalr ra, 16(t0)",
+        "csrrw t0, mepc, t1",
+        "csrrci a0, 0x300, 8",
+        "ebreak",
+        "wfi",
+    ];
+    for source in sources.iter() {
+        let inst = encode(source, Xlen::Rv64).unwrap();
+        let disassembly = disassemble(inst as u64, 0);
+        assert_eq!(Ok(inst), encode(&disassembly, Xlen::Rv64), "{}", source);
+    }
+}
+
+#[test]
+fn invalid_instructions_are_rejected() {
+    let error = |line| encode(line, Xlen::Rv32).unwrap_err();
+    assert_eq!("unknown instruction `mv`", error("mv a0, a1"));
+    assert_eq!("`ld` is only available in RV64I", error("ld a0, 0(a1)"));
+    assert_eq!("unknown register `x32`", error("add x32, x0, x0"));
+    assert_eq!("expected `add rd, rs1, rs2`", error("add a0, a1"));
+    assert_eq!("`ecall` takes no operands", error("ecall a0"));
+    assert_eq!(
+        "immediate 2048 is out of range (-2048 to 2047)",
+        error("addi a0, a0, 2048")
+    );
+    assert_eq!(
+        "immediate 32 is out of range (0 to 31)",
+        error("slli a0, a0, 32")
+    );
+    assert_eq!("offset 3 is odd", error("beq a0, a1, 3"));
+    assert_eq!("invalid number `1O`", error("addi a0, a0, 1O"));
+    assert_eq!(
+        "expected `offset(register)`, found `a1`",
+        error("lw a0, a1")
+    );
+    assert_eq!("unknown CSR `nope`", error("csrrs a0, nope, zero"));
+}
+
+#[test]
+fn programs_are_assembled_and_run() {
+    let source = "# Count down from 3.\n\
+                  addi a0, zero, 3\n\
+                  \n\
+                  addi a0, a0, -1 # The loop.\n\
+                  bne a0, zero, -4\n\
+                  addi a7, zero, 93\n\
+                  ecall\n";
+    let program = assemble(source, Xlen::Rv64).unwrap();
+    assert_eq!(20, program.len());
+
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    while emu.step().is_ok() {}
+    assert_eq!(0, emu.cpu.xregs.read(10));
+    assert_eq!(93, emu.cpu.xregs.read(17));
+}
+
+#[test]
+fn isa_strings_are_parsed() {
+    let isa = |xlen, m, c| Ok(Isa { xlen, m, c });
+    assert_eq!(isa(Xlen::Rv32, false, false), Isa::parse("rv32i"));
+    assert_eq!(isa(Xlen::Rv32, true, false), Isa::parse("RV32IM"));
+    assert_eq!(isa(Xlen::Rv64, true, false), Isa::parse("rv64im_zicsr_zifencei"));
+    assert_eq!(isa(Xlen::Rv64, false, true), Isa::parse("rv64ic"));
+    assert_eq!(
+        Err("`rv128i` doesn't start with rv32 or rv64".to_string()),
+        Isa::parse("rv128i")
+    );
+    assert_eq!(
+        Err("`rv32e` doesn't have the I base".to_string()),
+        Isa::parse("rv32e")
+    );
+    assert_eq!(
+        Err("the a extension isn't supported".to_string()),
+        Isa::parse("rv64ima")
+    );
+}
+
+#[test]
+fn the_m_extension_is_assembled_when_the_isa_has_it() {
+    let source = "li a0, -7
+                  li a1, 2
+                  mul a2, a0, a1
+                  div a3, a0, a1
+                  remu a4, a0, a1
+                  mulhu a5, a0, a1
+                  li a7, 93
+                  ecall";
+    let error = assemble(source, Xlen::Rv32).unwrap_err();
+    assert_eq!((3, "`mul` needs the M extension"), (error.line, error.message.as_str()));
+    let error = assemble_isa("mulw a0, a0, a1", Isa::parse("rv32im").unwrap()).unwrap_err();
+    assert_eq!("`mulw` is only available in RV64", error.message);
+
+    let program = assemble_isa(source, Isa::parse("rv32im").unwrap()).unwrap();
+    assert_eq!(
+        0x02b5_0633,
+        u32::from_le_bytes([program[8], program[9], program[10], program[11]])
+    );
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.set_xlen(Xlen::Rv32);
+    while emu.step().is_ok() {}
+    assert_eq!(-14i64 as u64, emu.cpu.xregs.read(12));
+    assert_eq!(-3i64 as u64, emu.cpu.xregs.read(13));
+    assert_eq!(1, emu.cpu.xregs.read(14));
+    assert_eq!(1, emu.cpu.xregs.read(15));
+
+    let program = assemble_isa("remw a0, a0, a1
+divuw a0, a0, a1", Isa::parse("rv64im").unwrap());
+    assert_eq!(8, program.unwrap().len());
+}
+
+/// Run `program` of `xlen` until it stops and return its registers.
+fn run(program: Vec<u8>, xlen: Xlen) -> Vec<u64> {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.set_xlen(xlen);
+    for _ in 0..1000 {
+        if emu.step().is_err() {
+            break;
+        }
+    }
+    (0..32).map(|i| emu.cpu.xregs.read(i)).collect()
+}
+
+#[test]
+fn the_c_extension_mixes_compressed_and_full_instructions() {
+    let source = "auipc sp, 1
+                  li a0, 5
+                  li a1, 0
+                  loop: add a1, a1, a0
+                  addi a0, a0, -1
+                  bne a0, zero, loop
+                  sw a1, 12(sp)
+                  lw a2, 12(sp)
+                  call double
+                  lui a4, 0x12345
+                  beq a0, a0, end
+                  li a5, 1
+                  end: li a7, 93
+                  ecall
+                  double: slli a3, a2, 1
+                  ret";
+    for isa in ["rv32i", "rv64i"].iter() {
+        let full = assemble_isa(source, Isa::parse(isa).unwrap()).unwrap();
+        let mut compressed_isa = Isa::parse(isa).unwrap();
+        compressed_isa.c = true;
+        let compressed = assemble_isa(source, compressed_isa).unwrap();
+        assert!(compressed.len() < full.len(), "{}", isa);
+        // The 8 instructions without a compressed form, e.g. `ecall` and those of `call`, stay
+        // 4 bytes.
+        assert_eq!(full.len() / 2 + 16, compressed.len(), "{}", isa);
+
+        let xlen = compressed_isa.xlen;
+        let registers = run(compressed, xlen);
+        // Only the return address of `call` moves.
+        assert_eq!(run(full, xlen)[2..], registers[2..], "{}", isa);
+        assert_eq!((15, 15, 30, 0), (registers[11], registers[12], registers[13], registers[15]));
+    }
+
+    // A branch too far for `c.beqz` grows to 4 bytes, which moves the labels after it.
+    let far = format!("beq a0, zero, end\n{}end: ebreak", "addi a0, a0, 1\n".repeat(200));
+    let program = assemble_isa(&far, Isa::parse("rv32ic").unwrap()).unwrap();
+    assert_eq!(4 + 200 * 2 + 2, program.len());
+    assert_eq!(0x9002, u16::from_le_bytes([program[404], program[405]]));
+}
+
+#[test]
+fn data_directives_emit_bytes() {
+    let source = "nop
+                  .byte 1, -1, 0x7f
+                  .half 0x1234
+                  .align 2
+                  .word -2, 0xdeadbeef
+                  .dword 0x0102030405060708
+                  .ascii \"a#b\", \"\\n\"
+                  .asciz \"q:\\\"\" # A comment.
+                  .space 3, 0xaa
+                  .space 2";
+    let image = assemble(source, Xlen::Rv64).unwrap();
+    let mut expected = vec![0x13, 0, 0, 0, 1, 0xff, 0x7f, 0x34, 0x12, 0, 0, 0];
+    expected.extend_from_slice(&[0xfe, 0xff, 0xff, 0xff, 0xef, 0xbe, 0xad, 0xde]);
+    expected.extend_from_slice(&[8, 7, 6, 5, 4, 3, 2, 1]);
+    expected.extend_from_slice(b"a#b\nq:\"\0");
+    expected.extend_from_slice(&[0xaa, 0xaa, 0xaa, 0, 0]);
+    assert_eq!(expected, image);
+
+    let errors = [
+        (".byte 256", "immediate 256 is out of range (-128 to 255)"),
+        (".byte label", "invalid number `label`"),
+        (".word", "expected `.word values`"),
+        (".text 1", "`.text` takes no operands"),
+        (".ascii \"open", "expected strings, found `\"open`"),
+        (".ascii \"\\q\"", "unknown escape `\\q`"),
+        (".space 1, 2, 3", "expected `.space size, fill`"),
+        (".align 13", "immediate 13 is out of range (0 to 12)"),
+        (".globl main", "unknown directive `.globl`"),
+        (".word nowhere", "unknown label `nowhere`"),
+    ];
+    for (source, message) in errors.iter() {
+        assert_eq!(*message, assemble(source, Xlen::Rv32).unwrap_err().message, "{}", source);
+    }
+    let error = assemble(".data\nd: .word 0\n.text\nj d", Xlen::Rv32).unwrap_err();
+    assert_eq!(
+        (4, "`d` is in another section, out of reach of a jump"),
+        (error.line, error.message.as_str())
+    );
+}
+
+#[test]
+fn the_data_section_follows_the_text() {
+    let source = ".data
+                  msg: .asciz \"hi#:\"
+                  .align 2
+                  table: .word 7, msg
+                  .text
+                  la a0, table
+                  lw a1, 0(a0)
+                  lw a2, 4(a0)
+                  lbu a3, 0(a2)
+                  lbu a4, 3(a2)
+                  li a7, 93
+                  ecall";
+    for isa in ["rv32i", "rv32ic"].iter() {
+        let image = assemble_isa(source, Isa::parse(isa).unwrap()).unwrap();
+        let registers = run(image, Xlen::Rv32);
+        let msg = registers[10] - 8;
+        assert_eq!(7, registers[11], "{}", isa);
+        assert_eq!((msg, b'h' as u64, b':' as u64), (registers[12], registers[13], registers[14]));
+    }
+    let image = assemble(source, Xlen::Rv32).unwrap();
+    assert_eq!(48, image.len());
+    assert_eq!((DRAM_BASE + 32).to_le_bytes()[..4], image[44..]);
+}
+
+#[test]
+fn immediates_are_constant_expressions() {
+    let encoded = |line| encode(line, Xlen::Rv32).unwrap();
+    let same = [
+        ("addi a0, a0, 34", "addi a0, a0, 4*8+2"),
+        ("addi a0, a0, 34", "addi a0, a0, 4 * (8 + 1) - 2"),
+        ("addi a0, a0, -16", "addi a0, a0, -(1 << 4)"),
+        ("addi a0, a0, 97", "addi a0, a0, 'a'"),
+        ("addi a0, a0, 32", "addi a0, a0, ' '"),
+        ("addi a0, a0, 44", "addi a0, a0, ','"),
+        ("addi a0, a0, 10", "addi a0, a0, '\\n'"),
+        ("andi a0, a0, 5", "andi a0, a0, 0b101"),
+        ("andi a0, a0, 6", "andi a0, a0, 0x7e & 0b1111 ^ 8"),
+        ("xori a0, a0, -1", "xori a0, a0, ~0"),
+        ("lui a0, 0x12345", "lui a0, %hi(0x12345678)"),
+        ("lui a0, 0x12346", "lui a0, %hi(0x12345800)"),
+        ("addi a0, a0, -2048", "addi a0, a0, %lo(0x12345800)"),
+        ("lw a0, 8(a1)", "lw a0, (4*2)(a1)"),
+    ];
+    for (expected, line) in same.iter() {
+        assert_eq!(encoded(expected), encoded(line), "{}", line);
+    }
+
+    let error = |line| encode(line, Xlen::Rv32).unwrap_err();
+    assert_eq!("division by zero in `1/0`", error("addi a0, a0, 1/0"));
+    assert_eq!("invalid expression `(1`", error("addi a0, a0, (1"));
+    assert_eq!("invalid expression `'ab'`", error("addi a0, a0, 'ab'"));
+    assert_eq!("invalid shift by 64 in `1 << 64`", error("addi a0, a0, 1 << 64"));
+    assert_eq!(
+        "immediate 0x7ff + 1 is out of range (-2048 to 2047)",
+        error("addi a0, a0, 0x7ff + 1")
+    );
+
+    // Character literals hide `#`, `:` and `,` from comments, labels and operand lists.
+    let image = assemble(".byte '#', ':', ',', 'a' + 1 # 'z'\nli a0, '#'", Xlen::Rv32).unwrap();
+    assert_eq!(vec![b'#', b':', b',', b'b', 0x13, 0x05, 0x30, 0x02], image);
+}
+
+#[test]
+fn hi_and_lo_reach_a_label() {
+    let source = "lui a0, %hi(value)
+                  lw a1, %lo(value)(a0)
+                  addi a2, a0, %lo(value)
+                  lui a3, %hi(copy)
+                  sw a1, %lo(copy)(a3)
+                  lw a4, %lo(copy)(a3)
+                  li a7, 93
+                  ecall
+                  .data
+                  value: .word 42
+                  copy: .word 0";
+    for isa in ["rv32i", "rv32ic"].iter() {
+        let image = assemble_isa(source, Isa::parse(isa).unwrap()).unwrap();
+        let registers = run(image, Xlen::Rv32);
+        assert_eq!((42, 42), (registers[11], registers[14]), "{}", isa);
+        assert_eq!(Xlen::Rv32.sign_extend(DRAM_BASE + 32), registers[12], "{}", isa);
+    }
+
+    let error = assemble("lui a0, %hi(nowhere)", Xlen::Rv32).unwrap_err();
+    assert_eq!("unknown label `nowhere`", error.message);
+    let error = assemble_line("lui a0, %hi(far)", Xlen::Rv64, DRAM_BASE, |_| Some(1 << 32));
+    assert_eq!(Err("the address of `far` doesn't fit in 32 bits".to_string()), error);
+}
+
+#[test]
+fn errors_point_at_the_source_line() {
+    assert_eq!(
+        Err(AssembleError {
+            line: 3,
+            message: "unknown instruction `nope`".to_string()
+        }),
+        assemble("addi a0, a0, 1\n\r\nnope", Xlen::Rv32)
+    );
+
+    let mut calls = Vec::new();
+    let result = assemble_with("ecall\necall\necall", Xlen::Rv32, |done, total| {
+        calls.push((done, total));
+        done < 1
+    });
+    assert_eq!(0, result.unwrap_err().line);
+    assert_eq!(vec![(0, 3), (1, 3)], calls);
+}
+
+#[test]
+fn labels_are_resolved_in_two_passes() {
+    // Sum 1 to 10 with a backward branch, then skip over a trap with a forward jump.
+    let source = "        li a0, 0\n\
+                          li t0, 10\n\
+                  loop:   add a0, a0, t0\n\
+                          addi t0, t0, -1\n\
+                          bne t0, zero, loop\n\
+                          j done\n\
+                          ebreak\n\
+                  done:\n\
+                  exit:   li a7, 93 # exit\n\
+                          ecall";
+    let program = assemble(source, Xlen::Rv64).unwrap();
+    let inst =
+        |i: usize| u32::from_le_bytes([program[i], program[i + 1], program[i + 2], program[i + 3]]);
+    // bne t0, zero, -8 and jal zero, 8
+    assert_eq!(0xfe02_9ce3, inst(16));
+    assert_eq!(0x0080_006f, inst(20));
+
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    while emu.step().is_ok() {}
+    assert_eq!(55, emu.cpu.xregs.read(10));
+    assert_eq!(93, emu.cpu.xregs.read(17));
+
+    let error = |source| assemble(source, Xlen::Rv64).unwrap_err();
+    assert_eq!(
+        AssembleError {
+            line: 2,
+            message: "label `a` is defined twice".to_string()
+        },
+        error("a: nop\na:")
+    );
+    assert_eq!(
+        "unknown label `nowhere`",
+        error("beq a0, a1, nowhere").message
+    );
+    assert_eq!("unknown label `nowhere`", error("la a0, nowhere").message);
+    assert_eq!("invalid label `1 2`", error("1 2: nop").message);
+}
+
+#[test]
+fn pseudo_instructions_are_expanded() {
+    let source = "        call set\n\
+                          mv a2, a0\n\
+                          la a3, data\n\
+                          nop\n\
+                          li a7, 93\n\
+                          ecall\n\
+                  set:    li a0, 0x12345678\n\
+                          li a1, -0x7ff\n\
+                          li a4, 0x7ffff800\n\
+                          li a5, 0x123456789abcdef0\n\
+                          li a6, -0x80000000\n\
+                          ret\n\
+                  data:";
+    let program = assemble(source, Xlen::Rv64).unwrap();
+    let data = DRAM_BASE + program.len() as u64;
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    while emu.step().is_ok() {}
+    assert_eq!(0x12345678, emu.cpu.xregs.read(10));
+    assert_eq!(-0x7ff_i64 as u64, emu.cpu.xregs.read(11));
+    assert_eq!(0x12345678, emu.cpu.xregs.read(12));
+    assert_eq!(0x7ffff800, emu.cpu.xregs.read(14));
+    assert_eq!(0x1234_5678_9abc_def0, emu.cpu.xregs.read(15));
+    assert_eq!(-0x8000_0000_i64 as u64, emu.cpu.xregs.read(16));
+    assert_eq!(93, emu.cpu.xregs.read(17));
+    assert_eq!(data, emu.cpu.xregs.read(13));
+
+    // The same value on RV32 is sign-extended by `addi`.
+    assert_eq!(
+        assemble("lui a0, 0x80000\naddi a0, a0, -1", Xlen::Rv32),
+        assemble("li a0, 0x7fffffff", Xlen::Rv32)
+    );
+    assert_eq!(
+        "expected `mv rd, rs`",
+        assemble("mv a0", Xlen::Rv32).unwrap_err().message
+    );
+    assert_eq!(
+        "`ret` takes no operands",
+        assemble("ret a0", Xlen::Rv32).unwrap_err().message
+    );
+}
+
+#[test]
+fn labels_are_listed_by_address() {
+    let source = ".data\nd: .byte 1\n.text\nb: a: beq a0, zero, c\nc: nop";
+    let (image, symbols) = assemble_with_symbols(source, Xlen::Rv32).unwrap();
+    assert_eq!(9, image.len());
+    let symbol = |name: &str, addr, section, line, referenced| Symbol {
+        name: name.to_string(),
+        addr,
+        section,
+        line,
+        referenced,
+    };
+    assert_eq!(
+        vec![
+            symbol("b", 0, Section::Text, 4, false),
+            symbol("a", 0, Section::Text, 4, false),
+            symbol("c", 4, Section::Text, 5, true),
+            symbol("d", 8, Section::Data, 2, false)
+        ],
+        symbols
+    );
+}
@@ -0,0 +1,45 @@
+use rvemu::atomics::AtomicOp;
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+
+#[test]
+fn atomic_instructions_are_logged() {
+    let program: [u32; 7] = [
+        0x0000_0317, // auipc t1, 0
+        0x1003_0313, // addi t1, t1, 0x100
+        0x0050_0e13, // addi t3, zero, 5
+        0x1003_22af, // lr.w t0, (t1)
+        0x19c3_2eaf, // sc.w t4, t3, (t1)
+        0x19c3_2eaf, // sc.w t4, t3, (t1)
+        0x01c3_22af, // amoadd.w t0, t3, (t1)
+    ];
+    let mut emu = Emulator::new();
+    emu.initialize_dram(
+        program
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    );
+    emu.initialize_pc(DRAM_BASE);
+    emu.atomic_events.set_enabled(true);
+    emu.run(7);
+
+    let events = emu.atomic_events.take();
+    let summary = events
+        .iter()
+        .map(|event| (event.op, event.old_value, event.new_value, event.success))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (AtomicOp::LoadReserved, 0, 0, 1),
+            (AtomicOp::StoreConditional, 0, 5, 1),
+            (AtomicOp::StoreConditional, 5, 5, 0),
+            (AtomicOp::Add, 5, 10, 1),
+        ],
+        summary
+    );
+    assert_eq!(DRAM_BASE + 0x100, events[0].addr);
+    assert_eq!(DRAM_BASE + 20, events[2].pc);
+    assert_eq!(4, events[3].size);
+    assert!(emu.atomic_events.events().is_empty());
+}
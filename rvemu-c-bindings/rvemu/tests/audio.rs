@@ -0,0 +1,47 @@
+use rvemu::bus::{AUDIO_BASE, DRAM_BASE};
+use rvemu::cpu::{BYTE, HALFWORD, WORD};
+use rvemu::devices::audio::AUDIO_RING_SIZE;
+use rvemu::emulator::Emulator;
+
+#[test]
+fn the_host_drains_samples_in_order() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0xb7, 0x70, 0x00, 0x10, // lui x1, 0x10007
+        0x13, 0x01, 0x00, 0x80, // addi x2, x0, -2048
+        0x23, 0x90, 0x20, 0x00, // sh x2, 0(x1)
+        0x13, 0x01, 0xf0, 0x7f, // addi x2, x0, 2047
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+        0x83, 0xa1, 0x40, 0x00, // lw x3, 4(x1)
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+    for _ in 0..6 {
+        emu.step().unwrap();
+    }
+    assert_eq!(AUDIO_RING_SIZE as u64 - 2, emu.cpu.xregs.read(3));
+
+    let mut out = [0; 2];
+    assert_eq!(2, emu.cpu.bus.audio.drain(&mut out));
+    assert_eq!([-2048, 2047], out);
+    assert_eq!(0, emu.cpu.bus.audio.underruns());
+}
+
+#[test]
+fn underruns_and_overruns_are_counted() {
+    let mut emu = Emulator::new();
+    let bus = &mut emu.cpu.bus;
+
+    bus.write(AUDIO_BASE, 7, HALFWORD).unwrap();
+    let mut out = [0; 4];
+    assert_eq!(1, bus.audio.drain(&mut out));
+    assert_eq!(1, bus.read(AUDIO_BASE + 8, WORD).unwrap());
+
+    for _ in 0..AUDIO_RING_SIZE + 3 {
+        bus.write(AUDIO_BASE, 1, WORD).unwrap();
+    }
+    assert_eq!(AUDIO_RING_SIZE, bus.audio.pending());
+    assert_eq!(0, bus.read(AUDIO_BASE + 4, WORD).unwrap());
+    assert_eq!(3, bus.read(AUDIO_BASE + 0xc, WORD).unwrap());
+
+    assert!(bus.write(AUDIO_BASE, 1, BYTE).is_err());
+}
@@ -0,0 +1,74 @@
+mod helper;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::csr::{MIE, MIP, MSIP_BIT, MSTATUS, MTVEC};
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+/// Create an emulator that counts up in x1 forever.
+fn create_emulator() -> Emulator {
+    helper::create_emulator(vec![
+        0x93, 0x80, 0x10, 0x00, // addi x1, x1, 1
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x6f, 0xf0, 0x9f, 0xff, // jal x0, -8
+    ])
+}
+
+#[test]
+fn runs_stop_before_a_breakpoint() {
+    let mut emu = create_emulator();
+    assert!(emu.breakpoints.add(DRAM_BASE + 4));
+    assert!(!emu.breakpoints.add(DRAM_BASE + 4));
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::HostBreakpoint, summary.reason);
+    assert_eq!(1, summary.steps);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+    assert_eq!(1, emu.cpu.xregs.read(1));
+
+    // Resuming executes the instruction at the breakpoint and stops the next time around.
+    let summary = emu.run(100);
+    assert_eq!(StopReason::HostBreakpoint, summary.reason);
+    assert_eq!(3, summary.steps);
+    assert_eq!(2, emu.cpu.xregs.read(1));
+}
+
+#[test]
+fn removed_breakpoints_dont_stop_runs() {
+    let mut emu = create_emulator();
+    emu.breakpoints.add(DRAM_BASE + 4);
+    emu.breakpoints.add(DRAM_BASE + 8);
+    assert!(emu.breakpoints.remove(DRAM_BASE + 4));
+    assert!(!emu.breakpoints.remove(DRAM_BASE + 4));
+
+    let summary = emu.run(100);
+    assert_eq!(DRAM_BASE + 8, summary.stop_pc);
+
+    emu.breakpoints.clear();
+    assert!(emu.breakpoints.is_empty());
+    assert_eq!(StopReason::StepLimit, emu.run(100).reason);
+}
+
+#[test]
+fn a_breakpoint_at_the_trap_handler_stops_an_interrupt() {
+    let mut emu = create_emulator();
+    emu.breakpoints.add(DRAM_BASE + 8);
+    emu.run(1);
+
+    // Make a machine software interrupt pending, handled at the `jal`.
+    emu.cpu.state.write(MTVEC, DRAM_BASE + 8);
+    emu.cpu.state.write(MSTATUS, 1 << 3);
+    emu.cpu.state.write(MIE, MSIP_BIT);
+    emu.cpu.state.write(MIP, MSIP_BIT);
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::HostBreakpoint, summary.reason);
+    assert_eq!((0, 1), (summary.steps, summary.interrupts));
+    assert_eq!(DRAM_BASE + 8, summary.stop_pc);
+
+    // Resuming executes the handler.
+    emu.cpu.state.write(MIP, 0);
+    let summary = emu.run(1);
+    assert_eq!((1, 0), (summary.steps, summary.interrupts));
+    assert_eq!(DRAM_BASE, summary.stop_pc);
+}
@@ -0,0 +1,82 @@
+mod helper;
+
+use rvemu::bus::{CHANNEL_BASE, DRAM_BASE};
+use rvemu::cpu::WORD;
+use rvemu::csr::{MCAUSE, MIE, MSTATUS_MIE, MTVEC, SEIP_BIT};
+use rvemu::devices::channel::{
+    CHANNEL_FIFO_SIZE, CTRL_IRQ_ENABLE, STATUS_OUTBOX_FULL, STATUS_RECEIVED,
+};
+use rvemu::emulator::Emulator;
+
+/// Create an emulator that reads a message, adds 1 and sends it back, forever.
+fn create_emulator() -> Emulator {
+    helper::create_emulator(vec![
+        0xb7, 0x90, 0x00, 0x10, // lui x1, 0x10009
+        0x03, 0xa1, 0x40, 0x00, // lw x2, 4(x1)
+        0xe3, 0x0e, 0x01, 0xfe, // beq x2, x0, -4
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x13, 0x01, 0x11, 0x00, // addi x2, x2, 1
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+        0x6f, 0xf0, 0xdf, 0xfe, // jal x0, -20
+    ])
+}
+
+#[test]
+fn two_machines_exchange_messages() {
+    let mut a = create_emulator();
+    let mut b = create_emulator();
+    assert!(a.cpu.bus.channel.deliver(40));
+
+    for _ in 0..4 {
+        a.run(20);
+        b.run(20);
+        a.cpu.bus.channel.exchange(&mut b.cpu.bus.channel);
+    }
+    // 40 went a -> b -> a -> b -> a, each side adding 1.
+    assert_eq!(1, a.cpu.bus.channel.pending());
+    assert_eq!(44, a.cpu.bus.read(CHANNEL_BASE, WORD).unwrap());
+    assert_eq!(None, b.cpu.bus.channel.take_sent());
+}
+
+#[test]
+fn full_mailboxes_keep_their_messages() {
+    let mut emu = Emulator::new();
+    let bus = &mut emu.cpu.bus;
+
+    for i in 0..CHANNEL_FIFO_SIZE as u64 + 1 {
+        bus.write(CHANNEL_BASE, i, WORD).unwrap();
+    }
+    assert_eq!(
+        STATUS_OUTBOX_FULL as u64,
+        bus.read(CHANNEL_BASE + 4, WORD).unwrap()
+    );
+    assert_eq!(1, bus.read(CHANNEL_BASE + 0xc, WORD).unwrap());
+    assert_eq!(Some(0), bus.channel.take_sent());
+
+    for i in 0..CHANNEL_FIFO_SIZE as u32 {
+        assert!(bus.channel.deliver(i));
+    }
+    assert!(!bus.channel.deliver(0));
+    assert_eq!(
+        STATUS_RECEIVED as u64,
+        bus.read(CHANNEL_BASE + 4, WORD).unwrap()
+    );
+}
+
+#[test]
+fn incoming_message_raises_external_interrupt() {
+    let mut emu = create_emulator();
+    emu.cpu.state.write(MTVEC, DRAM_BASE + 12);
+    emu.cpu.state.write(MIE, SEIP_BIT);
+    emu.cpu.state.write_mstatus(MSTATUS_MIE, 1);
+    emu.cpu
+        .bus
+        .write(CHANNEL_BASE + 8, CTRL_IRQ_ENABLE as u64, WORD)
+        .unwrap();
+
+    emu.run(10);
+    assert_eq!(0, emu.cpu.state.read(MCAUSE));
+    emu.cpu.bus.channel.deliver(7);
+    emu.step().unwrap();
+    assert_eq!(1 << 63 | 9, emu.cpu.state.read(MCAUSE));
+}
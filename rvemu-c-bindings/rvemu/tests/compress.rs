@@ -0,0 +1,110 @@
+use rvemu::assembler::{encode, Xlen};
+use rvemu::compress::compress;
+
+/// Return the compressed encoding of the instruction `line` of `xlen`.
+fn compressed(line: &str, xlen: Xlen) -> Option<u16> {
+    compress(encode(line, xlen).unwrap(), xlen)
+}
+
+#[test]
+fn instructions_are_compressed_like_the_gnu_assembler_does() {
+    let cases = [
+        ("addi a0, a0, 1", 0x0505),
+        ("addi a0, zero, 5", 0x4515),
+        ("addi a0, a1, 0", 0x852e),
+        ("add a0, a0, a1", 0x952e),
+        ("add a0, zero, a1", 0x852e),
+        ("lw a0, 0(a1)", 0x4188),
+        ("sw a0, 4(a1)", 0xc1c8),
+        ("lw a0, 12(sp)", 0x4532),
+        ("sw ra, 12(sp)", 0xc606),
+        ("lw s0, 252(sp)", 0x547e),
+        ("sw s1, 252(sp)", 0xdfa6),
+        ("lw a0, 124(a1)", 0x5de8),
+        ("addi sp, sp, -16", 0x1141),
+        ("addi sp, sp, 496", 0x617d),
+        ("addi sp, sp, -512", 0x7101),
+        ("addi a0, sp, 8", 0x0028),
+        ("addi a5, sp, 1020", 0x1ffc),
+        ("jalr zero, 0(ra)", 0x8082),
+        ("jalr ra, 0(a0)", 0x9502),
+        ("addi zero, zero, 0", 0x0001),
+        ("ebreak", 0x9002),
+        ("slli a0, a0, 2", 0x050a),
+        ("srli a0, a0, 1", 0x8105),
+        ("srai a0, a0, 31", 0x857d),
+        ("andi a0, a0, 15", 0x893d),
+        ("andi a0, a0, -32", 0x9901),
+        ("sub a0, a0, a1", 0x8d0d),
+        ("xor s0, s0, s1", 0x8c25),
+        ("or a0, a0, a1", 0x8d4d),
+        ("and a0, a0, a1", 0x8d6d),
+        ("lui a0, 1", 0x6505),
+        ("lui a0, 0xfffff", 0x757d),
+        ("lui t0, 31", 0x62fd),
+        ("ld a0, 8(a1)", 0x6588),
+        ("ld a0, 248(a1)", 0x7de8),
+        ("sd a0, 8(a1)", 0xe588),
+        ("sd ra, 8(sp)", 0xe406),
+        ("sd ra, 504(sp)", 0xff86),
+        ("ld ra, 8(sp)", 0x60a2),
+        ("ld ra, 504(sp)", 0x70fe),
+        ("addiw a0, a0, 1", 0x2505),
+        ("addiw a0, a0, -32", 0x3501),
+        ("addw a0, a0, a1", 0x9d2d),
+        ("subw a0, a0, a1", 0x9d0d),
+        ("slli a0, a0, 63", 0x157e),
+        ("srli a0, a0, 33", 0x9105),
+        ("jal zero, 2046", 0xaffd),
+        ("jal zero, -2048", 0xb001),
+        ("jal zero, -4", 0xbff5),
+        ("beq a0, zero, -256", 0xd101),
+        ("bne s1, zero, 254", 0xecfd),
+        ("beq a0, zero, 10", 0xc509),
+    ];
+    for (line, inst) in cases.iter() {
+        assert_eq!(Some(*inst), compressed(line, Xlen::Rv64), "{}", line);
+    }
+    // `c.jal` only exists in RV32, where `c.addiw` would be.
+    assert_eq!(Some(0x2095), compressed("jal ra, 100", Xlen::Rv32));
+    assert_eq!(Some(0x3ffd), compressed("jal ra, -2", Xlen::Rv32));
+    assert_eq!(None, compressed("jal ra, 100", Xlen::Rv64));
+}
+
+#[test]
+fn instructions_without_a_compressed_form_are_left_alone() {
+    let lines = [
+        "addi a0, a1, 1",
+        "addi a0, a0, 32",
+        "addi zero, zero, 1",
+        "addi sp, sp, 40",
+        "addi ra, sp, 8",
+        "add a0, a1, a2",
+        "sub a0, a0, a6",
+        "sub a0, a1, a0",
+        "lw a0, 2(a1)",
+        "lw a0, 128(a1)",
+        "lw zero, 0(sp)",
+        "sw a0, 0(a6)",
+        "srli a0, a0, 0",
+        "andi a0, a0, 32",
+        "lui sp, 1",
+        "lui a0, 32",
+        "jalr ra, 4(a0)",
+        "jalr a0, 0(a1)",
+        "jal zero, 2048",
+        "jal a0, 8",
+        "beq a0, a1, 8",
+        "beq a0, zero, 256",
+        "bne a6, zero, 8",
+        "blt a0, zero, 8",
+        "ecall",
+        "csrrs a0, mstatus, zero",
+    ];
+    for line in lines.iter() {
+        assert_eq!(None, compressed(line, Xlen::Rv64), "{}", line);
+    }
+    // `c.addiw` is `c.jal` in RV32.
+    let addiw = encode("addiw a0, a0, 1", Xlen::Rv64).unwrap();
+    assert_eq!(None, compress(addiw, Xlen::Rv32));
+}
@@ -0,0 +1,106 @@
+mod helper;
+
+use rvemu::cosim::{Cosim, Field, ReferenceModel, ReferenceState, StateModel};
+use rvemu::delta::StepDelta;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+const PROGRAM: [u32; 4] = [
+    0x0015_0513, // addi a0, a0, 1
+    0x0015_0513, // addi a0, a0, 1
+    0x0015_0513, // addi a0, a0, 1
+    0x0000_0073, // ecall
+];
+
+fn emulator() -> Emulator {
+    helper::create_emulator(
+        PROGRAM
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    )
+}
+
+/// A second emulator as the reference, with an optional bug in `a0` after some step.
+struct Twin {
+    emu: Emulator,
+    bug_at: Option<u64>,
+    steps: u64,
+}
+
+impl ReferenceModel for Twin {
+    fn step(&mut self) -> StepDelta {
+        self.emu.step_delta();
+        let mut delta = self.emu.last_delta.clone().unwrap();
+        if self.bug_at == Some(self.steps) {
+            delta.xregs[0].new += 1;
+        }
+        self.steps += 1;
+        delta
+    }
+
+    // The timer CSRs follow the host clock.
+    fn ignores(&self, field: Field) -> bool {
+        field == Field::Csrs
+    }
+}
+
+fn twin(bug_at: Option<u64>) -> Cosim {
+    Cosim::new(Box::new(Twin {
+        emu: emulator(),
+        bug_at,
+        steps: 0,
+    }))
+}
+
+#[test]
+fn identical_models_run_in_lockstep() {
+    let mut emu = emulator();
+    let mut cosim = twin(None);
+
+    let lockstep = cosim.run(&mut emu, 100);
+    assert!(!lockstep.mismatch);
+    assert_eq!(4, lockstep.steps);
+    assert_eq!(StopReason::Yielded, lockstep.stop.unwrap().reason);
+    assert!(cosim.mismatch().is_none());
+}
+
+#[test]
+fn lockstep_stops_at_the_first_mismatch() {
+    let mut emu = emulator();
+    let mut cosim = twin(Some(1));
+
+    let lockstep = cosim.run(&mut emu, 100);
+    assert!(lockstep.mismatch);
+    assert_eq!(1, lockstep.steps);
+    let mismatch = cosim.mismatch().unwrap();
+    assert_eq!(
+        (1, vec![Field::Xregs]),
+        (mismatch.step, mismatch.fields.clone())
+    );
+    assert_eq!(
+        (2, 3),
+        (mismatch.ours.xregs[0].new, mismatch.theirs.xregs[0].new)
+    );
+    assert_eq!(0, cosim.run(&mut emu, 100).steps);
+}
+
+#[test]
+fn state_models_are_compared_by_their_changes() {
+    let mut emu = emulator();
+    let mut reference = emulator();
+    let model = StateModel::new(&emu, move |state: &mut ReferenceState| {
+        reference.run(1);
+        state.pc = reference.cpu.pc;
+        for i in 0..32 {
+            state.xregs[i] = reference.cpu.xregs.read(i as u64);
+        }
+        // Every step of the reference is off by one.
+        state.xregs[10] += 1;
+    });
+    let mut cosim = Cosim::new(Box::new(model));
+
+    let lockstep = cosim.run(&mut emu, 100);
+    assert!(lockstep.mismatch);
+    assert_eq!(vec![Field::Xregs], cosim.mismatch().unwrap().fields);
+}
@@ -0,0 +1,71 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::csr_names::{standard_address, standard_name, CsrNames};
+use rvemu::disasm::{disassemble, disassemble_with_csr_names};
+
+#[test]
+fn standard_names_map_both_ways() {
+    assert_eq!(Some(0x341), standard_address("mepc"));
+    assert_eq!(Some(0x3a2), standard_address("pmpcfg2"));
+    assert_eq!(Some(0x3ef), standard_address("pmpaddr63"));
+    assert_eq!(None, standard_address("pmpaddr64"));
+    assert_eq!(None, standard_address("pmpcfg02"));
+    assert_eq!(Some("sstatus".to_string()), standard_name(0x100));
+    assert_eq!(Some("pmpaddr1".to_string()), standard_name(0x3b1));
+    assert_eq!(None, standard_name(0x7c0));
+}
+
+#[test]
+fn custom_csrs_assemble_and_disassemble_symmetrically() {
+    let mut names = CsrNames::new();
+    assert!(names.register("score", 0x7c0));
+    assert!(!names.register("mepc", 0x7c1));
+    assert!(!names.register("bonus", 0x341));
+    assert!(!names.register("bonus", 0x1000));
+    assert!(!names.register("2x", 0x7c1));
+
+    assert_eq!(
+        "csrrw t0, 0x7c0, t1",
+        names.substitute("csrrw t0, score, t1")
+    );
+    assert_eq!(
+        "  csrrs a0, 0x300, zero",
+        names.substitute("  csrrs a0,mstatus,zero")
+    );
+    assert_eq!("addi score, a0, 1", names.substitute("addi score, a0, 1"));
+    assert_eq!(
+        "csrrw t0, level, t1",
+        names.substitute("csrrw t0, level, t1")
+    );
+
+    // csrrw t0, 0x7c0, t1
+    let inst = 0x7c03_12f3;
+    assert_eq!("csrrw t0, 0x7c0, t1", disassemble(inst, DRAM_BASE));
+    assert_eq!(
+        "csrrw t0, score, t1",
+        disassemble_with_csr_names(inst, DRAM_BASE, &names)
+    );
+    // csrrsi a0, mstatus, 8
+    assert_eq!(
+        "csrrsi a0, mstatus, 8",
+        disassemble_with_csr_names(0x3004_6573, DRAM_BASE, &names)
+    );
+
+    // A new name replaces the old one of the same CSR.
+    assert!(names.register("lives", 0x7c0));
+    assert_eq!(None, names.address("score"));
+    assert_eq!(Some("lives".to_string()), names.name(0x7c0));
+    names.clear();
+    assert_eq!(None, names.name(0x7c0));
+}
+
+#[test]
+fn all_csrs_are_listed_by_address() {
+    let mut names = CsrNames::new();
+    assert!(names.register("score", 0x7c0));
+    let all = names.all();
+    assert_eq!(("ustatus".to_string(), 0x000), all[0]);
+    assert_eq!(("mhartid".to_string(), 0xf14), all[all.len() - 1]);
+    assert!(all.contains(&("pmpcfg15".to_string(), 0x3af)));
+    assert!(all.contains(&("score".to_string(), 0x7c0)));
+    assert!(all.windows(2).all(|pair| pair[0].1 < pair[1].1));
+}
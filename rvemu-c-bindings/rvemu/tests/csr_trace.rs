@@ -0,0 +1,57 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::cpu::Mode;
+use rvemu::csr::{MEPC, MSTATUS};
+use rvemu::emulator::Emulator;
+
+#[test]
+fn csr_writes_are_logged_with_their_instruction() {
+    let program: [u32; 5] = [
+        0x0000_0297, // auipc t0, 0
+        0x0102_8293, // addi t0, t0, 16
+        0x3412_9073, // csrw mepc, t0
+        0x3020_0073, // mret
+        0x0000_0013, // nop
+    ];
+    let mut emu = Emulator::new();
+    emu.initialize_dram(
+        program
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    );
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.mode = Mode::Machine;
+    // MPIE set and MPP = 3, so mret stays in machine mode and sets MIE.
+    emu.cpu.state.write(MSTATUS, 0x1880);
+    emu.csr_trace.set_enabled(true);
+    emu.run(5);
+
+    let writes = emu.csr_trace.take();
+    assert_eq!(DRAM_BASE + 8, writes[0].pc);
+    assert_eq!(MEPC as u64, writes[0].addr);
+    assert_eq!((0, DRAM_BASE + 16), (writes[0].old, writes[0].new));
+
+    let mret = writes[1..].to_vec();
+    assert!(!mret.is_empty());
+    assert!(mret
+        .iter()
+        .all(|write| write.pc == DRAM_BASE + 12 && write.addr == MSTATUS as u64));
+    assert_eq!(0x1880, mret[0].old);
+    let mstatus = mret.last().unwrap().new;
+    assert_eq!(
+        (1, 1, 0),
+        ((mstatus >> 3) & 1, (mstatus >> 7) & 1, (mstatus >> 11) & 3)
+    );
+    assert_eq!(emu.cpu.state.read(MSTATUS), mstatus);
+    assert!(emu.csr_trace.writes().is_empty());
+}
+
+#[test]
+fn nothing_is_logged_until_enabled() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(0x3412_9073u32.to_le_bytes().to_vec());
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.mode = Mode::Machine;
+    emu.run(1);
+    assert!(emu.csr_trace.writes().is_empty());
+}
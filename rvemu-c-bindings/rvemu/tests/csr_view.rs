@@ -0,0 +1,42 @@
+use rvemu::cpu::Mode;
+use rvemu::csr::{MIE, MIP, MSTATUS, MTIP_BIT, SSIP_BIT};
+use rvemu::csr_view::{decode, interrupt_gates};
+use rvemu::emulator::Emulator;
+
+#[test]
+fn mstatus_is_decoded_into_named_fields() {
+    // MIE, MPIE and MPP = 3.
+    let fields = decode(MSTATUS, 0x1888).unwrap();
+    let value = |name| {
+        fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| (field.lsb, field.width, field.value))
+    };
+    assert_eq!(Some((3, 1, 1)), value("MIE"));
+    assert_eq!(Some((7, 1, 1)), value("MPIE"));
+    assert_eq!(Some((11, 2, 3)), value("MPP"));
+    assert_eq!(Some((1, 1, 0)), value("SIE"));
+    assert!(decode(0x340, 0).is_none());
+}
+
+#[test]
+fn gates_show_what_blocks_a_pending_interrupt() {
+    let mut emu = Emulator::new();
+    emu.cpu.mode = Mode::Machine;
+    emu.cpu.state.write(MIP, MTIP_BIT | SSIP_BIT);
+    emu.cpu.state.write(MIE, MTIP_BIT);
+
+    let gate = |emu: &Emulator, code| {
+        let gates = interrupt_gates(&emu.cpu);
+        let gate = gates.into_iter().find(|gate| gate.code == code).unwrap();
+        (gate.pending, gate.enabled, gate.global, gate.taken)
+    };
+    // mstatus.MIE is clear.
+    assert_eq!((1, 1, 0, 0), gate(&emu, 7));
+    assert_eq!((1, 0, 0, 0), gate(&emu, 1));
+
+    emu.cpu.state.write(MSTATUS, 1 << 3);
+    assert_eq!((1, 1, 1, 1), gate(&emu, 7));
+    assert_eq!((1, 0, 1, 0), gate(&emu, 1));
+}
@@ -0,0 +1,71 @@
+mod helper;
+
+use rvemu::debug_print::{DebugPrintKind, SYS_DEBUG_PRINT_HEX, SYS_DEBUG_PRINT_INT};
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+/// Create an emulator running `program` with "hi" at `DRAM_BASE + 0x100`.
+fn emulator(program: &[u32]) -> Emulator {
+    let mut dram = program
+        .iter()
+        .flat_map(|word| word.to_le_bytes().to_vec())
+        .collect::<Vec<u8>>();
+    dram.resize(0x100, 0);
+    dram.extend(b"hi\0");
+    helper::create_emulator(dram)
+}
+
+#[test]
+fn debug_prints_are_served_without_stopping() {
+    let program = [
+        0xff90_0513, // addi a0, zero, -7
+        0x7e00_0893, // addi a7, zero, 0x7e0
+        0x0000_0073, // ecall
+        0x7e10_0893, // addi a7, zero, 0x7e1
+        0x0000_0073, // ecall
+        0x0000_0517, // auipc a0, 0
+        0x0ec5_0513, // addi a0, a0, 0xec
+        0x7e20_0893, // addi a7, zero, 0x7e2
+        0x0000_0073, // ecall
+        0x0000_0013, // nop
+    ];
+    let mut emu = emulator(&program);
+    emu.debug_prints.set_enabled(true);
+    let summary = emu.run(10);
+    assert_eq!(StopReason::StepLimit, summary.reason);
+    assert_eq!(10, summary.steps);
+
+    let prints = emu.debug_prints.prints();
+    assert_eq!(3, prints.len());
+    assert_eq!(DebugPrintKind::Int, prints[0].kind);
+    assert_eq!("0x80000008: -7", prints[0].render());
+    assert_eq!("0x80000010: 0xfffffffffffffff9", prints[1].render());
+    assert_eq!(Some("hi".to_string()), prints[2].text);
+    assert_eq!("0x80000020: hi", prints[2].render());
+
+    emu.debug_prints.clear();
+    assert!(emu.debug_prints.prints().is_empty());
+}
+
+#[test]
+fn debug_prints_yield_while_disabled() {
+    let program = [
+        0x7e00_0893, // addi a7, zero, 0x7e0
+        0x0000_0073, // ecall
+    ];
+    let mut emu = emulator(&program);
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Yielded, summary.reason);
+    assert_eq!(SYS_DEBUG_PRINT_INT, emu.cpu.xregs.read(17));
+    assert!(emu.debug_prints.prints().is_empty());
+
+    // Other environment calls still yield while enabled.
+    let program = [
+        0x0400_0893, // addi a7, zero, 64
+        0x0000_0073, // ecall
+    ];
+    let mut emu = emulator(&program);
+    emu.debug_prints.set_enabled(true);
+    assert_eq!(StopReason::Yielded, emu.run(10).reason);
+    assert_ne!(SYS_DEBUG_PRINT_HEX, emu.cpu.xregs.read(17));
+}
@@ -0,0 +1,63 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::delta::RegisterChange;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+#[test]
+fn a_step_reports_everything_it_changed() {
+    let program: [u32; 3] = [
+        0x0000_0317, // auipc t1, 0
+        0x1063_3023, // sd t1, 0x100(t1)
+        0x3400_92f3, // csrrw t0, mscratch, ra
+    ];
+    let mut emu = Emulator::new();
+    emu.initialize_dram(
+        program
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    );
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.xregs.write(1, 42);
+
+    emu.step_delta();
+    let delta = emu.last_delta.clone().unwrap();
+    assert_eq!(
+        (DRAM_BASE, 0x317, DRAM_BASE + 4),
+        (delta.pc, delta.inst, delta.next_pc)
+    );
+    assert_eq!(
+        vec![RegisterChange {
+            index: 6,
+            old: 0,
+            new: DRAM_BASE
+        }],
+        delta.xregs
+    );
+    assert!(delta.memory.is_empty() && delta.fregs.is_empty());
+
+    emu.step_delta();
+    let delta = emu.last_delta.clone().unwrap();
+    assert!(delta.xregs.is_empty());
+    assert_eq!(1, delta.memory.len());
+    assert_eq!(
+        (DRAM_BASE + 0x100, 8, DRAM_BASE),
+        (
+            delta.memory[0].addr,
+            delta.memory[0].size,
+            delta.memory[0].value
+        )
+    );
+
+    assert_eq!(StopReason::StepLimit, emu.step_delta().reason);
+    let delta = emu.last_delta.as_ref().unwrap();
+    // mscratch. mip may change too, as the timer runs.
+    assert_eq!(
+        Some(&RegisterChange {
+            index: 0x340,
+            old: 0,
+            new: 42
+        }),
+        delta.csrs.iter().find(|change| change.index == 0x340)
+    );
+}
@@ -0,0 +1,38 @@
+use rvemu::bus::MROM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::exception::Exception;
+use rvemu::rom::Rom;
+
+/// Create an emulator whose ROM contains `addi x31, x0, 7` and start executing from it.
+fn create_emulator() -> Emulator {
+    let mut emu = Emulator::new();
+    emu.cpu.bus.rom = Rom::new_with_data(vec![
+        0x93, 0x0f, 0x70, 0x00, // addi x31, x0, 7
+    ]);
+    emu.initialize_pc(MROM_BASE);
+    emu
+}
+
+#[test]
+fn fetch_from_rom_is_forbidden_by_default() {
+    let mut emu = create_emulator();
+
+    assert_eq!(
+        Err(Exception::InstructionAccessFault(MROM_BASE)),
+        emu.step()
+    );
+    assert_eq!(0, emu.cpu.xregs.read(31));
+
+    let trap = emu.last_trap.unwrap();
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.contains("belongs to the ROM"));
+}
+
+#[test]
+fn fetch_from_rom_when_allowed() {
+    let mut emu = create_emulator();
+    emu.cpu.bus.device_fetch = true;
+
+    emu.step().unwrap();
+    assert_eq!(7, emu.cpu.xregs.read(31));
+}
@@ -0,0 +1,54 @@
+use rvemu::dram::{self, DRAM_SIZE};
+use rvemu::emulator::Emulator;
+
+/// The resource limits of the process, to make an allocation fail.
+#[cfg(target_os = "linux")]
+mod rlimit {
+    pub const RLIMIT_AS: i32 = 9;
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    pub struct Rlimit {
+        pub current: u64,
+        pub max: u64,
+    }
+
+    extern "C" {
+        pub fn getrlimit(resource: i32, limit: *mut Rlimit) -> i32;
+        pub fn setrlimit(resource: i32, limit: *const Rlimit) -> i32;
+    }
+}
+
+// This is the only test of the file, so no other emulator of the process allocates DRAM while it
+// counts, and the limit on the address space doesn't fail another test.
+#[test]
+fn dram_is_counted_and_its_allocation_can_fail() {
+    assert_eq!(0, dram::total_allocated());
+    let first = Emulator::try_new().unwrap();
+    let second = Emulator::new();
+    assert_eq!(2 * DRAM_SIZE, dram::total_allocated());
+    drop(first);
+    assert_eq!(DRAM_SIZE, dram::total_allocated());
+    drop(second);
+    assert_eq!(0, dram::total_allocated());
+
+    #[cfg(target_os = "linux")]
+    {
+        // Leave room in the address space for half a DRAM.
+        let statm = std::fs::read_to_string("/proc/self/statm").unwrap();
+        let pages = statm.split_whitespace().next().unwrap().parse::<u64>().unwrap();
+        let mut old = rlimit::Rlimit { current: 0, max: 0 };
+        assert_eq!(0, unsafe { rlimit::getrlimit(rlimit::RLIMIT_AS, &mut old) });
+        let limit = rlimit::Rlimit {
+            current: pages * 4096 + DRAM_SIZE / 2,
+            ..old
+        };
+        assert_eq!(0, unsafe { rlimit::setrlimit(rlimit::RLIMIT_AS, &limit) });
+        let result = Emulator::try_new().map(|_| ());
+        assert_eq!(0, unsafe { rlimit::setrlimit(rlimit::RLIMIT_AS, &old) });
+
+        let message = format!("allocation failed: no memory for the {} bytes of DRAM", DRAM_SIZE);
+        assert_eq!(Err(message), result);
+        assert_eq!(0, dram::total_allocated());
+    }
+}
@@ -0,0 +1,76 @@
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::{DRAM_BASE, DRAW_QUEUE_BASE};
+use rvemu::cpu::{BYTE, WORD};
+use rvemu::devices::draw_queue::{DrawCommand, DrawKind, DRAW_QUEUE_SIZE};
+use rvemu::emulator::Emulator;
+
+#[test]
+fn the_guest_queues_draw_commands() {
+    // Fill a 16x8 rectangle at (10, 20) in red, then set the palette entry 2 to blue.
+    let source = "lui a0, 0x10005\n\
+                  addi t0, zero, 2\n\
+                  sw t0, 0(a0)\n\
+                  addi t0, zero, 10\n\
+                  sw t0, 4(a0)\n\
+                  addi t0, zero, 20\n\
+                  sw t0, 8(a0)\n\
+                  addi t0, zero, 16\n\
+                  sw t0, 12(a0)\n\
+                  addi t0, zero, 8\n\
+                  sw t0, 16(a0)\n\
+                  addi t0, zero, 0xff\n\
+                  sw t0, 20(a0)\n\
+                  sw zero, 28(a0)\n\
+                  addi t0, zero, 3\n\
+                  sw t0, 0(a0)\n\
+                  addi t0, zero, 2\n\
+                  sw t0, 4(a0)\n\
+                  lui t0, 0xff0\n\
+                  sw t0, 8(a0)\n\
+                  sw zero, 28(a0)\n\
+                  lw a1, 32(a0)\n";
+    let mut emu = Emulator::new();
+    emu.initialize_dram(assemble(source, Xlen::Rv64).unwrap());
+    emu.initialize_pc(DRAM_BASE);
+    for _ in 0..22 {
+        emu.step().unwrap();
+    }
+    assert_eq!(2, emu.cpu.xregs.read(11));
+
+    let commands = emu.cpu.bus.draw_queue.take();
+    assert_eq!(
+        vec![
+            DrawCommand {
+                kind: DrawKind::FillRect,
+                args: [10, 20, 16, 8, 0xff, 0],
+            },
+            // The arguments keep their values from the previous command.
+            DrawCommand {
+                kind: DrawKind::SetPalette,
+                args: [2, 0xff_0000, 16, 8, 0xff, 0],
+            },
+        ],
+        commands
+    );
+    assert_eq!(0, emu.cpu.bus.draw_queue.pending());
+}
+
+#[test]
+fn invalid_and_overflowing_commands_are_dropped() {
+    let mut emu = Emulator::new();
+    let bus = &mut emu.cpu.bus;
+
+    bus.write(DRAW_QUEUE_BASE, 7, WORD).unwrap();
+    bus.write(DRAW_QUEUE_BASE + 0x1c, 0, WORD).unwrap();
+    assert_eq!(0, bus.draw_queue.pending());
+    assert_eq!(1, bus.read(DRAW_QUEUE_BASE + 0x24, WORD).unwrap());
+
+    bus.write(DRAW_QUEUE_BASE, 1, WORD).unwrap();
+    for _ in 0..DRAW_QUEUE_SIZE + 2 {
+        bus.write(DRAW_QUEUE_BASE + 0x1c, 0, WORD).unwrap();
+    }
+    assert_eq!(DRAW_QUEUE_SIZE, bus.draw_queue.pending());
+    assert_eq!(3, bus.draw_queue.dropped());
+
+    assert!(bus.write(DRAW_QUEUE_BASE, 1, BYTE).is_err());
+}
@@ -0,0 +1,76 @@
+mod helper;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::encoding_audit::{classify, EncodingKind, EncodingPolicy};
+use rvemu::run::StopReason;
+
+fn emulator(program: &[u8]) -> Emulator {
+    helper::create_emulator(program.to_vec())
+}
+
+#[test]
+fn corner_encodings_are_classified() {
+    let kind = |inst| classify(inst).map(|class| class.kind);
+    // nop, addi a0, a0, 1 and c.nop are ordinary.
+    assert_eq!(None, kind(0x0000_0013));
+    assert_eq!(None, kind(0x0015_0513));
+    assert_eq!(None, kind(0x0001));
+    // addi x0, a0, 1, lui x0, 1 and fence 0, rw
+    assert_eq!(Some(EncodingKind::Hint), kind(0x0015_0013));
+    assert_eq!(Some(EncodingKind::Hint), kind(0x0000_1037));
+    assert_eq!(Some(EncodingKind::Hint), kind(0x0030_000f));
+    // c.li x0, 1 and c.slli a0, 0
+    assert_eq!(Some(EncodingKind::Hint), kind(0x4005));
+    assert_eq!(Some(EncodingKind::Hint), kind(0x0502));
+    // c.addi4spn a0, 0, c.lui a0, 0, c.jr x0 and slliw a0, a0, 32
+    assert_eq!(Some(EncodingKind::Reserved), kind(0x0008));
+    assert_eq!(Some(EncodingKind::Reserved), kind(0x6501));
+    assert_eq!(Some(EncodingKind::Reserved), kind(0x8002));
+    assert_eq!(Some(EncodingKind::Reserved), kind(0x0205_151b));
+}
+
+#[test]
+fn executed_corner_encodings_are_logged_once_each() {
+    let mut emu = emulator(&[
+        0x13, 0x00, 0x15, 0x00, // addi x0, a0, 1
+        0x13, 0x05, 0x15, 0x00, // addi a0, a0, 1
+        0xe3, 0x0c, 0x00, 0xfe, // beqz zero, -8
+    ]);
+    emu.cpu.encoding_audit.set_logging(true);
+    emu.run(9);
+
+    let events = emu.cpu.encoding_audit.events();
+    assert_eq!(1, events.len());
+    assert_eq!(
+        (DRAM_BASE, 0x0015_0013, 3, EncodingKind::Hint),
+        (
+            events[0].pc,
+            events[0].inst,
+            events[0].count,
+            events[0].kind
+        )
+    );
+    assert_eq!(3, emu.cpu.xregs.read(10));
+}
+
+#[test]
+fn corner_encodings_can_trap() {
+    // c.addi4spn a0, 0 used to execute as addi a0, sp, 0.
+    let mut emu = emulator(&[0x08, 0x00]);
+    let summary = emu.run(1);
+    assert_eq!((StopReason::Trapped, 2), (summary.reason, summary.cause));
+
+    // addi x0, a0, 1
+    let mut emu = emulator(&[0x13, 0x00, 0x15, 0x00]);
+    assert_eq!(StopReason::StepLimit, emu.run(1).reason);
+    let mut emu = emulator(&[0x13, 0x00, 0x15, 0x00]);
+    emu.cpu
+        .encoding_audit
+        .set_policy(EncodingKind::Hint, EncodingPolicy::Trap);
+    let summary = emu.run(1);
+    assert_eq!(
+        (StopReason::Trapped, 2, 0x0015_0013),
+        (summary.reason, summary.cause, summary.tval)
+    );
+}
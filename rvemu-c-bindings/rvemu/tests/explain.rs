@@ -0,0 +1,41 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+
+#[test]
+fn null_pointer_load_points_at_source_line() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x83, 0x20, 0x00, 0x00, // lw x1, 0(x0)
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.debug_info.add_line(DRAM_BASE, 14);
+
+    assert!(emu.step().is_err());
+
+    let trap = emu.last_trap.unwrap();
+    assert_eq!(5, trap.cause);
+    assert_eq!(0, trap.tval);
+    assert_eq!(DRAM_BASE, trap.pc);
+
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(
+        explanation.starts_with("You tried to load from address 0x0 — a null pointer — at line 14")
+    );
+}
+
+#[test]
+fn running_off_the_end_without_debug_info() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![0x13, 0x00, 0x00, 0x00]); // addi x0, x0, 0
+    emu.initialize_pc(DRAM_BASE);
+    emu.debug_info.add_symbol("main", DRAM_BASE);
+
+    emu.step().unwrap();
+    assert!(emu.step().is_err());
+
+    let trap = emu.last_trap.unwrap();
+    assert_eq!(2, trap.cause);
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.starts_with("The program ran into empty memory at `main`+0x4"));
+}
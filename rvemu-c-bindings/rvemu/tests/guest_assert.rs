@@ -0,0 +1,70 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::guest_assert::AssertionFailure;
+use rvemu::run::StopReason;
+
+/// Create an emulator running `source` in `xlen` with guest asserts enabled and "too big" at
+/// `DRAM_BASE + 0x100`.
+fn emulator(source: &str, xlen: Xlen) -> Emulator {
+    let mut dram = assemble(source, xlen).unwrap();
+    dram.resize(0x100, 0);
+    dram.extend(b"too big\0");
+    let mut emu = helper::create_emulator(dram);
+    emu.cpu.set_xlen(xlen);
+    emu.guest_asserts.set_enabled(true);
+    emu
+}
+
+#[test]
+fn passing_asserts_are_counted_without_stopping() {
+    let source = "addi a7, zero, 0x7e3\naddi a0, zero, 1\naddi a2, zero, 4\necall\necall\nnop\n";
+    let mut emu = emulator(source, Xlen::Rv64);
+    let summary = emu.run(6);
+    assert_eq!((StopReason::StepLimit, 6), (summary.reason, summary.steps));
+    assert_eq!((2, 0), (emu.guest_asserts.passed(), emu.guest_asserts.failed()));
+    assert_eq!(None, emu.guest_asserts.last_failure());
+}
+
+#[test]
+fn a_failing_assert_stops_the_run() {
+    let source = "addi a7, zero, 0x7e3\n\
+                  addi a0, zero, 0\n\
+                  lui a1, 0x80000\n\
+                  addi a1, a1, 0x100\n\
+                  addi a2, zero, 17\n\
+                  ecall\n\
+                  nop\n";
+    let mut emu = emulator(source, Xlen::Rv32);
+    let summary = emu.run(10);
+    assert_eq!(StopReason::GuestAssertionFailed, summary.reason);
+    assert_eq!((17, DRAM_BASE + 0x18), (summary.tval, summary.stop_pc));
+    let failure = AssertionFailure {
+        pc: DRAM_BASE + 0x14,
+        line: 17,
+        message: Some("too big".to_string()),
+    };
+    assert_eq!(Some(&failure), emu.guest_asserts.last_failure());
+    assert_eq!("line 17: too big", failure.render());
+
+    // The run resumes after the failed assert.
+    assert_eq!(StopReason::StepLimit, emu.run(1).reason);
+    assert_eq!(DRAM_BASE + 0x1c, emu.cpu.pc);
+}
+
+#[test]
+fn asserts_go_to_the_host_while_disabled() {
+    let mut emu = emulator("addi a7, zero, 0x7e3\necall\n", Xlen::Rv64);
+    emu.guest_asserts.set_enabled(false);
+    assert_eq!(StopReason::Yielded, emu.run(10).reason);
+    assert_eq!(0, emu.guest_asserts.failed());
+
+    let failure = AssertionFailure {
+        pc: DRAM_BASE,
+        line: 3,
+        message: None,
+    };
+    assert_eq!("line 3: assertion failed at 0x80000000", failure.render());
+}
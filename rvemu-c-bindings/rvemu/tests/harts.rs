@@ -0,0 +1,102 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::harts::{HartState, Harts};
+use rvemu::trace::AccessKind;
+
+/// The instructions adding 1 to the counter at `0(t1)` without synchronization.
+const RACY_INCREMENT: [u32; 3] = [
+    0x0003_2283, // lw t0, 0(t1)
+    0x0012_8293, // addi t0, t0, 1
+    0x0053_2023, // sw t0, 0(t1)
+];
+
+/// Two harts adding 1 to a shared counter ten times each with `increment`.
+fn harts_with(increment: [u32; 3], seed: u64) -> (Emulator, Harts) {
+    let program: [u32; 11] = [
+        0x0000_0317, // auipc t1, 0
+        0x1003_0313, // addi t1, t1, 0x100
+        0x00a0_0393, // addi t2, zero, 10
+        increment[0],
+        increment[1],
+        increment[2],
+        0xfff3_8393, // addi t2, t2, -1
+        0xfe03_98e3, // bnez t2, -16
+        0x05d0_0893, // addi a7, zero, 93
+        0x0000_0073, // ecall
+        0x0000_0000,
+    ];
+    let bytes = program
+        .iter()
+        .flat_map(|word| word.to_le_bytes().to_vec())
+        .collect::<Vec<u8>>();
+
+    let mut emu = Emulator::new();
+    emu.load_program_at("racy", DRAM_BASE, &bytes, DRAM_BASE)
+        .unwrap();
+    let mut harts = Harts::new(seed);
+    assert_eq!(0, harts.add_hart(DRAM_BASE, DRAM_BASE + 0x2000));
+    assert_eq!(1, harts.add_hart(DRAM_BASE, DRAM_BASE + 0x3000));
+    assert!(harts.save_memory(&emu, DRAM_BASE, 0x200));
+    (emu, harts)
+}
+
+/// Run until every hart exited and return the counter.
+fn run_to_completion(emu: &mut Emulator, harts: &mut Harts) -> u64 {
+    while harts.run(emu, 10_000).stop.is_some() {}
+    assert_eq!(Some(HartState::Exited), harts.state(0));
+    assert_eq!(Some(HartState::Exited), harts.state(1));
+    emu.cpu.bus.read(DRAM_BASE + 0x100, 32).unwrap()
+}
+
+#[test]
+fn the_seed_decides_the_interleaving() {
+    let (mut emu, mut harts) = harts_with(RACY_INCREMENT, 0);
+    assert_eq!(1, harts.context_mut(1).unwrap().xregs.read(10));
+
+    let mut counters = Vec::new();
+    for seed in 0..20 {
+        harts.restart(&mut emu, seed);
+        counters.push(run_to_completion(&mut emu, &mut harts));
+    }
+    // Some interleavings lose updates.
+    let racy_seed = counters.iter().position(|&counter| counter < 20).unwrap() as u64;
+    assert!(counters.iter().all(|&counter| counter <= 20));
+
+    // Re-running a seed replays it exactly.
+    harts.restart(&mut emu, racy_seed);
+    let counter = run_to_completion(&mut emu, &mut harts);
+    let schedule = harts.schedule().to_vec();
+    assert_eq!(counters[racy_seed as usize], counter);
+
+    let (mut emu, mut harts) = harts_with(RACY_INCREMENT, racy_seed);
+    assert_eq!(counter, run_to_completion(&mut emu, &mut harts));
+    assert_eq!(schedule, harts.schedule());
+}
+
+#[test]
+fn races_are_reported_with_both_instructions() {
+    let (mut emu, mut harts) = harts_with(RACY_INCREMENT, 3);
+    harts.set_race_detection(true);
+    harts.set_max_burst(1);
+    run_to_completion(&mut emu, &mut harts);
+    let race = harts
+        .races()
+        .iter()
+        .find(|race| race.second.kind == AccessKind::Store)
+        .unwrap();
+    assert_eq!(DRAM_BASE + 0x100, race.addr);
+    assert_ne!(race.first.hart, race.second.hart);
+    assert_eq!(DRAM_BASE + 20, race.second.pc);
+
+    // The same loop with an atomic add has no race and loses no update.
+    let atomic_increment = [
+        0x0010_0e13, // addi t3, zero, 1
+        0x01c3_202f, // amoadd.w zero, t3, (t1)
+        0x0000_0013, // nop
+    ];
+    let (mut emu, mut harts) = harts_with(atomic_increment, 3);
+    harts.set_race_detection(true);
+    harts.set_max_burst(1);
+    assert_eq!(20, run_to_completion(&mut emu, &mut harts));
+    assert!(harts.races().is_empty());
+}
@@ -1,3 +1,6 @@
+// Every test file includes the helpers but only uses some of them.
+#![allow(dead_code)]
+
 use rvemu::bus::DRAM_BASE;
 use rvemu::cpu::{POINTER_TO_DTB, REGISTERS_COUNT};
 use rvemu::dram::DRAM_SIZE;
@@ -29,6 +32,14 @@ pub fn create_fregs(non_zero_regs: Vec<(usize, f64)>) -> [f64; REGISTERS_COUNT]
     fregs
 }
 
+/// Create an emulator with `image` at the start of DRAM and the program counter on it.
+pub fn create_emulator(image: Vec<u8>) -> Emulator {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(image);
+    emu.initialize_pc(DRAM_BASE);
+    emu
+}
+
 /// Start a test and check if the registers are expected.
 pub fn run(
     emu: &mut Emulator,
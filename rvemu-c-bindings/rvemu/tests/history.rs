@@ -0,0 +1,116 @@
+mod helper;
+
+use std::mem::size_of;
+use std::sync::{Arc, Mutex};
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::csr_trace::CsrWrite;
+use rvemu::emulator::Emulator;
+use rvemu::history::{self, Checkpoint};
+use rvemu::memory_usage;
+
+/// Create an emulator that counts up in a0 and writes it to `mscratch`.
+fn create_emulator() -> Emulator {
+    let source = "loop:\naddi a0, a0, 1\ncsrrw zero, mscratch, a0\nj loop";
+    helper::create_emulator(assemble(source, Xlen::Rv64).unwrap())
+}
+
+fn ids(emu: &Emulator) -> Vec<u64> {
+    emu.history.checkpoints().map(|checkpoint| checkpoint.id).collect()
+}
+
+#[test]
+fn checkpoints_are_rewound_to() {
+    let mut emu = create_emulator();
+    emu.run(3);
+    let first = history::checkpoint(&mut emu);
+    emu.run(30);
+    let second = history::checkpoint(&mut emu);
+    assert_eq!((1, 2), (first, second));
+    assert_eq!(11, emu.cpu.xregs.read(10));
+
+    history::rewind(&mut emu, first).unwrap();
+    assert_eq!(1, emu.cpu.xregs.read(10));
+    emu.run(3);
+    history::rewind(&mut emu, first).unwrap();
+    assert_eq!(1, emu.cpu.xregs.read(10));
+    history::rewind(&mut emu, second).unwrap();
+    assert_eq!(11, emu.cpu.xregs.read(10));
+
+    assert_eq!(vec![3, 33], emu.history.checkpoints().map(|c| c.retired).collect::<Vec<_>>());
+    let size = emu.history.checkpoints().map(Checkpoint::size).sum::<u64>();
+    assert_eq!(size, memory_usage::measure(&emu).snapshots);
+    let error = history::rewind(&mut emu, 3).unwrap_err();
+    assert_eq!("checkpoint 3 was evicted or never taken", error);
+}
+
+#[test]
+fn the_least_recently_used_checkpoints_are_evicted_first() {
+    let mut emu = create_emulator();
+    let first = history::checkpoint(&mut emu);
+    let second = history::checkpoint(&mut emu);
+    let size = emu.history.size() / 2;
+    let traces = memory_usage::measure(&emu).traces;
+    history::set_budget(&mut emu, Some(2 * size + traces));
+    assert_eq!(0, emu.history.take_truncated());
+
+    history::rewind(&mut emu, first).unwrap();
+    let third = history::checkpoint(&mut emu);
+    assert_eq!(vec![first, third], ids(&emu));
+    assert_eq!(1, emu.history.take_truncated());
+    assert!(history::rewind(&mut emu, second).is_err());
+
+    // A checkpoint that doesn't fit alone is evicted as well.
+    history::set_budget(&mut emu, Some(size / 2));
+    history::checkpoint(&mut emu);
+    assert!(ids(&emu).is_empty());
+    assert_eq!(3, emu.history.take_truncated());
+    assert_eq!(0, emu.history.take_truncated());
+}
+
+#[test]
+fn the_oldest_trace_entries_are_evicted_once_the_checkpoints_are() {
+    let mut emu = create_emulator();
+    emu.trace.set_capacity(0);
+    emu.csr_trace.set_enabled(true);
+    history::checkpoint(&mut emu);
+    let budget = 10 * size_of::<CsrWrite>() as u64;
+    history::set_budget(&mut emu, Some(budget));
+    assert_eq!(1, emu.history.take_truncated());
+
+    emu.run(45);
+    let writes = emu.csr_trace.writes();
+    assert_eq!(10, writes.len());
+    assert_eq!(15, writes[9].new);
+    assert_eq!(6, writes[0].new);
+    assert_eq!(5, emu.history.take_truncated());
+
+    history::set_budget(&mut emu, None);
+    emu.run(3);
+    assert_eq!(11, emu.csr_trace.writes().len());
+    assert_eq!(0, emu.history.take_truncated());
+}
+
+#[test]
+fn evictions_are_reported_to_the_callback() {
+    let mut emu = create_emulator();
+    emu.trace.set_capacity(0);
+    emu.csr_trace.set_enabled(true);
+    let evictions = Arc::new(Mutex::new(Vec::new()));
+    let reported = Arc::clone(&evictions);
+    let callback = Box::new(move |checkpoints, entries| {
+        reported.lock().unwrap().push((checkpoints, entries))
+    });
+    history::set_eviction_callback(&mut emu, Some(callback));
+
+    history::checkpoint(&mut emu);
+    history::set_budget(&mut emu, Some(10 * size_of::<CsrWrite>() as u64));
+    emu.run(45);
+    emu.run(3);
+    assert_eq!(vec![(1, 0), (0, 5), (0, 1)], *evictions.lock().unwrap());
+
+    history::set_eviction_callback(&mut emu, None);
+    emu.run(3);
+    assert_eq!(3, evictions.lock().unwrap().len());
+    assert_eq!(8, emu.history.take_truncated());
+}
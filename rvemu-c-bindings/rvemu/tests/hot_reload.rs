@@ -0,0 +1,84 @@
+mod helper;
+
+use rvemu::assembler::{assemble_with_symbols, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::debug_info::DebugInfo;
+use rvemu::emulator::Emulator;
+use rvemu::hot_reload::reload_text;
+
+const DATA: u64 = DRAM_BASE + 0x1000;
+
+/// Assemble `source` and return its image and its symbol table at `DRAM_BASE`.
+fn assemble(source: &str) -> (Vec<u8>, DebugInfo) {
+    let (image, symbols) = assemble_with_symbols(source, Xlen::Rv64).unwrap();
+    let mut debug_info = DebugInfo::new();
+    for symbol in symbols {
+        debug_info.add_symbol(&symbol.name, DRAM_BASE + symbol.addr);
+    }
+    (image, debug_info)
+}
+
+fn emulator(source: &str) -> Emulator {
+    let (image, debug_info) = assemble(source);
+    let mut emu = helper::create_emulator(image);
+    emu.debug_info = debug_info;
+    emu
+}
+
+#[test]
+fn reloading_keeps_data_and_moves_the_pc_to_the_same_symbol() {
+    let mut emu = emulator(
+        "main:\n\
+         addi a0, zero, 0\n\
+         loop:\n\
+         addi a0, a0, 1\n\
+         sw a0, 0(a1)\n\
+         j loop\n\
+         unused:\n\
+         nop\n",
+    );
+    emu.cpu.xregs.write(11, DATA);
+    emu.run(5);
+    assert_eq!(DRAM_BASE + 8, emu.cpu.pc);
+    assert_eq!(2, emu.cpu.xregs.read(10));
+
+    let (image, debug_info) = assemble(
+        "main:\n\
+         addi a0, zero, 0\n\
+         nop\n\
+         loop:\n\
+         addi a0, a0, 10\n\
+         sw a0, 0(a1)\n\
+         j loop\n",
+    );
+    reload_text(&mut emu, &image, Some(debug_info)).unwrap();
+    // The pc is at `loop`+4 in the new text, and the state is kept.
+    assert_eq!(DRAM_BASE + 12, emu.cpu.pc);
+    assert_eq!(2, emu.cpu.xregs.read(10));
+    assert_eq!(Some(&[1, 0, 0, 0][..]), emu.dram_bytes(DATA, 4));
+    // The old text past the new one is zeroed.
+    assert_eq!(Some(&[0; 4][..]), emu.dram_bytes(DRAM_BASE + 20, 4));
+    assert_eq!(Some(DRAM_BASE + 8), emu.debug_info.address_of("loop"));
+
+    emu.run(4);
+    assert_eq!(12, emu.cpu.xregs.read(10));
+    assert_eq!(Some(&[12, 0, 0, 0][..]), emu.dram_bytes(DATA, 4));
+}
+
+#[test]
+fn reloading_fails_if_the_pc_has_nowhere_to_go() {
+    let mut emu = emulator("main:\nnop\nhelper:\nnop\nj helper\n");
+    emu.run(2);
+    assert_eq!(DRAM_BASE + 8, emu.cpu.pc);
+
+    let (image, debug_info) = assemble("main:\nnop\nj main\n");
+    let error = reload_text(&mut emu, &image, Some(debug_info)).unwrap_err();
+    assert_eq!("the pc is in `helper`, which the new text doesn't have", error);
+    assert_eq!(DRAM_BASE + 8, emu.cpu.pc);
+    assert_eq!(Some(DRAM_BASE + 4), emu.debug_info.address_of("helper"));
+
+    // Without symbols, the pc stays where it is.
+    reload_text(&mut emu, &image, None).unwrap();
+    assert_eq!(DRAM_BASE + 8, emu.cpu.pc);
+    assert_eq!(8, emu.image.len());
+}
@@ -0,0 +1,86 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::injection::{inject_ebreak, inject_nops, restore, restore_all, visible_bytes};
+use rvemu::run::StopReason;
+
+fn emulator(image: Vec<u8>) -> Emulator {
+    helper::create_emulator(image)
+}
+
+#[test]
+fn an_injected_ebreak_stops_the_program_and_stays_hidden() {
+    let image = assemble("addi a0, a0, 1\naddi a0, a0, 2\naddi a0, a0, 4", Xlen::Rv64).unwrap();
+    let mut emu = emulator(image.clone());
+    inject_ebreak(&mut emu, DRAM_BASE + 4).unwrap();
+    assert!(inject_ebreak(&mut emu, DRAM_BASE + 4).is_err());
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Breakpoint, summary.reason);
+    assert_eq!(DRAM_BASE + 4, emu.cpu.pc);
+    assert_eq!(1, emu.cpu.xregs.read(10));
+    // The memory shows the program as it was.
+    assert_eq!(Some(image.clone()), visible_bytes(&emu, DRAM_BASE, 12));
+    assert_eq!(Some(&[0x73, 0x00, 0x10, 0x00][..]), emu.dram_bytes(DRAM_BASE + 4, 4));
+
+    restore(&mut emu, DRAM_BASE + 4).unwrap();
+    assert_eq!(Some(&image[..]), emu.dram_bytes(DRAM_BASE, 12));
+    assert!(restore(&mut emu, DRAM_BASE + 4).is_err());
+    emu.run(2);
+    assert_eq!(7, emu.cpu.xregs.read(10));
+}
+
+#[test]
+fn a_nop_sled_skips_over_code() {
+    // c.addi a0, 1; addi a0, a0, 2; addi a0, a0, 4
+    let mut image = vec![0x05, 0x05];
+    image.extend(assemble("addi a0, a0, 2\naddi a0, a0, 4", Xlen::Rv64).unwrap());
+    let mut emu = emulator(image.clone());
+    inject_nops(&mut emu, DRAM_BASE, 6).unwrap();
+    assert!(inject_nops(&mut emu, DRAM_BASE + 4, 4).is_err());
+    assert!(inject_nops(&mut emu, DRAM_BASE + 8, 3).is_err());
+    restore_all(&mut emu);
+    // A compressed instruction is replaced with c.ebreak.
+    inject_ebreak(&mut emu, DRAM_BASE).unwrap();
+    assert_eq!(Some(&[0x02, 0x90][..]), emu.dram_bytes(DRAM_BASE, 2));
+    restore_all(&mut emu);
+    assert!(emu.injections.is_empty());
+    assert_eq!(Some(&image[..]), emu.dram_bytes(DRAM_BASE, 10));
+
+    inject_nops(&mut emu, DRAM_BASE + 2, 4).unwrap();
+    emu.run(3);
+    assert_eq!(5, emu.cpu.xregs.read(10));
+    assert_eq!(Some(image), visible_bytes(&emu, DRAM_BASE, 10));
+}
+
+#[test]
+fn a_patch_under_an_injection_replaces_it() {
+    let image = assemble("addi a0, a0, 1\naddi a0, a0, 2", Xlen::Rv64).unwrap();
+    let mut emu = emulator(image);
+    inject_ebreak(&mut emu, DRAM_BASE + 4).unwrap();
+    inject_nops(&mut emu, DRAM_BASE, 4).unwrap();
+
+    let patch = assemble("addi a0, a0, 8", Xlen::Rv64).unwrap();
+    emu.write_dram(DRAM_BASE + 4, &patch).unwrap();
+    assert!(!emu.injections.contains(DRAM_BASE + 4));
+    assert_eq!(Some(patch.clone()), visible_bytes(&emu, DRAM_BASE + 4, 4));
+    restore_all(&mut emu);
+    assert_eq!(Some(&patch[..]), emu.dram_bytes(DRAM_BASE + 4, 4));
+    emu.run(2);
+    assert_eq!(9, emu.cpu.xregs.read(10));
+}
+
+#[test]
+fn loading_a_program_removes_the_injections() {
+    let mut emu = emulator(assemble("addi a0, a0, 1", Xlen::Rv64).unwrap());
+    inject_ebreak(&mut emu, DRAM_BASE).unwrap();
+
+    let image = assemble("addi a0, a0, 2", Xlen::Rv64).unwrap();
+    emu.initialize_dram(image.clone());
+    assert!(emu.injections.is_empty());
+    assert!(restore(&mut emu, DRAM_BASE).is_err());
+    restore_all(&mut emu);
+    assert_eq!(Some(&image[..]), emu.dram_bytes(DRAM_BASE, 4));
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::exception::Exception;
+use rvemu::isa::{mnemonic, InstructionSet, MNEMONICS};
+
+#[test]
+fn instructions_are_named() {
+    assert_eq!(Some("addi"), mnemonic(0x001f_8f93)); // addi x31, x31, 1
+    assert_eq!(Some("lw"), mnemonic(0x0000_a103)); // lw x2, 0(x1)
+    assert_eq!(Some("mul"), mnemonic(0x0220_80b3)); // mul x1, x1, x2
+    assert_eq!(Some("ecall"), mnemonic(0x0000_0073));
+    assert_eq!(Some("mret"), mnemonic(0x3020_0073));
+    assert_eq!(None, mnemonic(0x4501)); // c.li a0, 0
+    assert_eq!(None, mnemonic(0));
+}
+
+#[test]
+fn the_mnemonic_table_lists_every_mnemonic() {
+    // `mnemonic` only looks at the opcode, funct3 and the bits from 20 up.
+    let mut named = BTreeSet::new();
+    for opcode in (0..0x80).filter(|opcode| opcode & 0b11 == 0b11) {
+        for funct3 in 0..8 {
+            for upper in 0..0x1000 {
+                let inst = opcode | (funct3 << 12) | (upper << 20);
+                named.extend(mnemonic(inst));
+            }
+        }
+    }
+    assert_eq!(named, MNEMONICS.iter().copied().collect::<BTreeSet<&str>>());
+    assert_eq!(named.len(), MNEMONICS.len());
+}
+
+#[test]
+fn only_allowed_instructions_are_executed() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0x93, 0x8f, 0x1f, 0x00, // addi x31, x31, 1
+        0xb3, 0x80, 0x20, 0x02, // mul x1, x1, x2
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.instruction_set = Some(InstructionSet::new(vec!["addi", "add"]));
+
+    emu.step().unwrap();
+    assert_eq!(Err(Exception::IllegalInstruction(0x0220_80b3)), emu.step());
+
+    let trap = emu.last_trap.unwrap();
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.starts_with("The instruction `mul`"));
+}
+
+#[test]
+fn only_allowed_registers_are_used() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0x13, 0x05, 0x15, 0x00, // addi a0, a0, 1
+        0x93, 0x85, 0x15, 0x00, // addi a1, a1, 1
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.instruction_set = Some(InstructionSet::new(vec!["addi"]).with_registers(vec![10]));
+    emu.register_aliases.add("score", 11);
+
+    emu.step().unwrap();
+    assert_eq!(Err(Exception::IllegalInstruction(0x0015_8593)), emu.step());
+
+    let trap = emu.last_trap.unwrap();
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.contains("uses the register a1 (score)"));
+}
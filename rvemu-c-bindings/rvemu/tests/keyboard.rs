@@ -0,0 +1,63 @@
+use rvemu::bus::{DRAM_BASE, KEYBOARD_BASE};
+use rvemu::cpu::BYTE;
+use rvemu::csr::{MCAUSE, MIE, MSTATUS_MIE, MTVEC, SEIP_BIT};
+use rvemu::devices::keyboard::{KEYBOARD_FIFO_SIZE, SCANCODE_OVERRUN, STATUS_OUTPUT_FULL};
+use rvemu::emulator::Emulator;
+
+#[test]
+fn scancodes_are_read_in_order() {
+    let mut emu = Emulator::new();
+
+    emu.cpu.bus.keyboard.push_scancode(0x1e); // 'A' make
+    emu.cpu.bus.keyboard.push_scancode(0x9e); // 'A' break
+
+    let status = emu.cpu.bus.read(KEYBOARD_BASE + 4, BYTE).unwrap() as u8;
+    assert_eq!(STATUS_OUTPUT_FULL, status & STATUS_OUTPUT_FULL);
+    assert_eq!(0x1e, emu.cpu.bus.read(KEYBOARD_BASE, BYTE).unwrap());
+    assert_eq!(0x9e, emu.cpu.bus.read(KEYBOARD_BASE, BYTE).unwrap());
+
+    let status = emu.cpu.bus.read(KEYBOARD_BASE + 4, BYTE).unwrap() as u8;
+    assert_eq!(0, status & STATUS_OUTPUT_FULL);
+}
+
+#[test]
+fn full_fifo_reports_overrun() {
+    let mut emu = Emulator::new();
+
+    for code in 0..(KEYBOARD_FIFO_SIZE as u8 + 4) {
+        emu.cpu.bus.keyboard.push_scancode(code + 1);
+    }
+
+    assert_eq!(KEYBOARD_FIFO_SIZE, emu.cpu.bus.keyboard.pending());
+    for _ in 0..KEYBOARD_FIFO_SIZE - 1 {
+        emu.cpu.bus.read(KEYBOARD_BASE, BYTE).unwrap();
+    }
+    assert_eq!(
+        SCANCODE_OVERRUN as u64,
+        emu.cpu.bus.read(KEYBOARD_BASE, BYTE).unwrap()
+    );
+}
+
+#[test]
+fn scancode_raises_external_interrupt() {
+    let mut emu = Emulator::new();
+
+    let data = vec![
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x93, 0x0f, 0x70, 0x00, // addi x31, x0, 7 (trap handler)
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.state.write(MTVEC, DRAM_BASE + 8);
+    emu.cpu.state.write(MIE, SEIP_BIT);
+    emu.cpu.state.write_mstatus(MSTATUS_MIE, 1);
+
+    emu.step().unwrap();
+    assert_eq!(0, emu.cpu.xregs.read(31));
+
+    emu.cpu.bus.keyboard.push_scancode(0x1c);
+    emu.step().unwrap();
+    assert_eq!(7, emu.cpu.xregs.read(31));
+    assert_eq!(1 << 63 | 9, emu.cpu.state.read(MCAUSE));
+}
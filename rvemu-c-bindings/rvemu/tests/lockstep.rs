@@ -0,0 +1,81 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::CHANNEL_BASE;
+use rvemu::cpu::WORD;
+use rvemu::emulator::Emulator;
+use rvemu::lockstep::{Lockstep, NodeState};
+
+/// Create an emulator running `source`.
+fn create_emulator(source: &str) -> Emulator {
+    helper::create_emulator(assemble(source, Xlen::Rv64).unwrap())
+}
+
+/// Wait for a message, add 1 and exit with it.
+const RECEIVER: &str = "        lui t1, 0x10009\n\
+                        wait:   lw t0, 4(t1)\n\
+                                beq t0, zero, wait\n\
+                                lw a0, 0(t1)\n\
+                                addi a0, a0, 1\n\
+                                li a7, 93\n\
+                                ecall";
+
+#[test]
+fn messages_are_delivered_between_rounds() {
+    let mut sender = create_emulator(
+        "lui t1, 0x10009\n\
+         li t0, 41\n\
+         sw t0, 0(t1)\n\
+         li a0, 0\n\
+         li a7, 93\n\
+         ecall",
+    );
+    let mut a = create_emulator(RECEIVER);
+    let mut b = create_emulator(RECEIVER);
+
+    let mut lockstep = Lockstep::new(3, 10);
+    assert!(lockstep.link(0, 1));
+    assert!(lockstep.link(0, 2));
+    assert!(!lockstep.link(1, 1));
+    assert!(!lockstep.link(1, 3));
+
+    // The receivers spin in the first round and exit in the second one.
+    assert_eq!(1, lockstep.run(&mut [&mut sender, &mut a, &mut b], 1));
+    assert_eq!(NodeState::Exited, lockstep.status(0).unwrap().state);
+    assert_eq!(NodeState::Running, lockstep.status(1).unwrap().state);
+    assert_eq!(10, lockstep.status(1).unwrap().steps);
+
+    assert_eq!(1, lockstep.run(&mut [&mut sender, &mut a, &mut b], 10));
+    for node in 1..3 {
+        let status = lockstep.status(node).unwrap();
+        assert_eq!((NodeState::Exited, 42), (status.state, status.exit_code));
+    }
+    assert_eq!(None, lockstep.status(3));
+}
+
+#[test]
+fn rings_relay_messages_in_order() {
+    // Forward every message with 1 added, forever.
+    let relay = "        lui t1, 0x10009\n\
+                 wait:   lw t0, 4(t1)\n\
+                         beq t0, zero, wait\n\
+                         lw t0, 0(t1)\n\
+                         addi t0, t0, 1\n\
+                         sw t0, 0(t1)\n\
+                         j wait";
+    let mut emus = (0..4).map(|_| create_emulator(relay)).collect::<Vec<_>>();
+    let mut lockstep = Lockstep::new(4, 100);
+    for node in 0..4 {
+        assert!(lockstep.link(node, (node + 1) % 4));
+    }
+    assert!(emus[0].cpu.bus.channel.deliver(0));
+    assert!(emus[0].cpu.bus.channel.deliver(100));
+
+    // Each message moves one node per round, so after 6 rounds both wait at node 2.
+    let mut refs = emus.iter_mut().collect::<Vec<&mut Emulator>>();
+    assert_eq!(6, lockstep.run(&mut refs, 6));
+    let bus = &mut emus[2].cpu.bus;
+    assert_eq!(2, bus.channel.pending());
+    assert_eq!(6, bus.read(CHANNEL_BASE, WORD).unwrap());
+    assert_eq!(106, bus.read(CHANNEL_BASE, WORD).unwrap());
+}
@@ -0,0 +1,52 @@
+use rvemu::cpu::{BYTE, DOUBLEWORD, WORD};
+use rvemu::exception::Exception;
+use rvemu::mapping::{HostMemory, Mappings};
+
+/// Memory the guest can only read.
+struct ReadOnly(Vec<u8>);
+
+impl HostMemory for ReadOnly {
+    fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+}
+
+#[test]
+fn mapped_memory_is_read_and_written() {
+    let mut mappings = Mappings::new();
+    mappings.map(0x1000, Box::new(vec![0u8; 8])).unwrap();
+    assert_eq!(None, mappings.read(0x2000, WORD));
+
+    assert_eq!(Some(Ok(())), mappings.write(0x1002, 0x1122_3344, WORD));
+    assert_eq!(Some(Ok(0x22)), mappings.read(0x1004, BYTE));
+    // An access can't cross the end of the region.
+    assert_eq!(
+        Some(Err(Exception::LoadAccessFault(0x1004))),
+        mappings.read(0x1004, DOUBLEWORD)
+    );
+
+    let memory = mappings.unmap(0x1000).unwrap();
+    assert_eq!(&[0, 0, 0x44, 0x33, 0x22, 0x11, 0, 0], memory.bytes());
+    assert!(mappings.is_empty());
+}
+
+#[test]
+fn mappings_are_checked() {
+    let mut mappings = Mappings::new();
+    assert!(mappings.map(0x1000, Box::new(Vec::new())).is_err());
+    mappings
+        .map(0x1000, Box::new(ReadOnly(vec![7; 4])))
+        .unwrap();
+    assert!(mappings.map(0x1003, Box::new(vec![0u8; 4])).is_err());
+    mappings.map(0x1004, Box::new(vec![0u8; 4])).unwrap();
+
+    assert_eq!(Some(Ok(7)), mappings.read(0x1003, BYTE));
+    assert_eq!(
+        Some(Err(Exception::StoreAMOAccessFault(0x1000))),
+        mappings.write(0x1000, 0, BYTE)
+    );
+}
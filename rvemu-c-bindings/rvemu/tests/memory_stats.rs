@@ -0,0 +1,66 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::memory_stats::{SiteStats, MAX_CACHE_LINES};
+
+#[test]
+fn accesses_are_aggregated_by_pc() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+        0x6f, 0xf0, 0x5f, 0xff, // jal x0, -12
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.xregs.write(1, DRAM_BASE + 0x100);
+    emu.cpu.memory_stats.set_enabled(true);
+    emu.cpu.memory_stats.set_cache(64, 4).unwrap();
+
+    // Two iterations of the loop.
+    emu.run(8);
+
+    let hottest = emu.cpu.memory_stats.hottest(2);
+    assert_eq!(
+        vec![
+            SiteStats {
+                pc: DRAM_BASE,
+                loads: 2,
+                stores: 0,
+                cache_hits: 1,
+                cache_misses: 1,
+            },
+            SiteStats {
+                pc: DRAM_BASE + 4,
+                loads: 2,
+                stores: 0,
+                cache_hits: 2,
+                cache_misses: 0,
+            },
+        ],
+        hottest
+    );
+    assert_eq!(2, emu.cpu.memory_stats.site(DRAM_BASE + 8).unwrap().stores);
+    assert_eq!(None, emu.cpu.memory_stats.site(DRAM_BASE + 12));
+
+    emu.cpu.memory_stats.clear();
+    assert_eq!(0, emu.cpu.memory_stats.sites().count());
+}
+
+#[test]
+fn invalid_cache_sizes_are_rejected() {
+    let mut emu = Emulator::new();
+    emu.cpu.memory_stats.set_cache(64, 4).unwrap();
+
+    assert!(emu.cpu.memory_stats.set_cache(48, 4).is_err());
+    assert!(emu
+        .cpu
+        .memory_stats
+        .set_cache(64, MAX_CACHE_LINES + 1)
+        .is_err());
+    // The model in use is kept.
+    let cache = emu.cpu.memory_stats.cache().unwrap();
+    assert_eq!((64, 4), (cache.line_size(), cache.lines()));
+
+    emu.cpu.memory_stats.set_cache(0, 0).unwrap();
+    assert!(emu.cpu.memory_stats.cache().is_none());
+}
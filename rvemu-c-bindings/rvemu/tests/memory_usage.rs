@@ -0,0 +1,32 @@
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::dram::DRAM_SIZE;
+use rvemu::emulator::Emulator;
+use rvemu::memory_usage::{self, MemoryUsage};
+use rvemu::trace::{TraceEntry, DEFAULT_TRACE_CAPACITY};
+
+#[test]
+fn usage_grows_with_the_traces_and_caches() {
+    let mut emu = Emulator::new();
+    let usage = memory_usage::measure(&emu);
+    let empty = MemoryUsage {
+        dram: DRAM_SIZE,
+        total: DRAM_SIZE,
+        ..MemoryUsage::default()
+    };
+    assert_eq!(empty, usage);
+
+    let program = assemble("loop:\naddi a0, a0, 1\nj loop", Xlen::Rv64).unwrap();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    emu.run(100);
+    emu.cpu.memory_stats.set_cache(64, 8).unwrap();
+    let usage = memory_usage::measure(&emu);
+    let trace = (DEFAULT_TRACE_CAPACITY * std::mem::size_of::<TraceEntry>()) as u64;
+    assert_eq!(trace, usage.traces);
+    assert_eq!(8 * 16, usage.caches);
+    assert_eq!(DRAM_SIZE + trace + 8 * 16, usage.total);
+
+    emu.trace.set_capacity(0);
+    assert_eq!(0, memory_usage::measure(&emu).traces);
+}
@@ -0,0 +1,79 @@
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::metrics::{self, Metrics};
+use rvemu::run::RunLimits;
+
+// The counters are shared by every test of this file, which run in parallel, so the tests only
+// check that the counters grew by at least what they did.
+
+#[test]
+fn assemblies_and_runs_are_counted() {
+    let before = metrics::snapshot();
+    let program = assemble("addi a0, a0, 1\naddi a0, a0, 1", Xlen::Rv64).unwrap();
+    assert!(assemble("bogus a0", Xlen::Rv64).is_err());
+
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    emu.run_with(RunLimits {
+        max_steps: 2,
+        time_budget: None,
+    });
+
+    let after = metrics::snapshot();
+    assert!(after.assemblies >= before.assemblies + 2);
+    assert!(after.failed_assemblies > before.failed_assemblies);
+    assert!(after.runs > before.runs);
+    assert!(after.run_steps >= before.run_steps + 2);
+}
+
+#[test]
+fn grades_and_cache_lookups_are_counted() {
+    let before = metrics::snapshot();
+    metrics::record_grade(4, 4);
+    metrics::record_grade(4, 1);
+    metrics::record_cache_lookup(true);
+    metrics::record_cache_lookup(false);
+
+    let after = metrics::snapshot();
+    assert!(after.grades >= before.grades + 2);
+    assert!(after.passed_grades > before.passed_grades);
+    assert!(after.graded_cases >= before.graded_cases + 8);
+    assert!(after.passed_cases >= before.passed_cases + 5);
+    assert!(after.cache_hits > before.cache_hits);
+    assert!(after.cache_misses > before.cache_misses);
+}
+
+#[test]
+fn averages_are_derived_from_the_counters() {
+    assert_eq!(0.0, Metrics::default().mips());
+    assert_eq!(0.0, Metrics::default().cache_hit_rate());
+
+    let metrics = Metrics {
+        run_steps: 5_000_000,
+        run_time_ns: 1_000_000_000,
+        cache_hits: 3,
+        cache_misses: 1,
+        ..Metrics::default()
+    };
+    assert_eq!(5.0, metrics.mips());
+    assert_eq!(0.75, metrics.cache_hit_rate());
+}
+
+#[test]
+fn metrics_are_exported_as_prometheus_text() {
+    let metrics = Metrics {
+        runs: 3,
+        cache_hits: 1,
+        cache_misses: 1,
+        ..Metrics::default()
+    };
+    let text = metrics.to_prometheus();
+    assert!(text.contains("# TYPE rvemu_runs_total counter\nrvemu_runs_total 3\n"));
+    assert!(text.contains("# TYPE rvemu_cache_hit_rate gauge\nrvemu_cache_hit_rate 0.5\n"));
+    assert!(text
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .all(|line| line.starts_with("rvemu_")));
+}
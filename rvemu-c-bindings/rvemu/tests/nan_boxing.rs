@@ -0,0 +1,50 @@
+mod helper;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::nan_boxing::{NanBoxing, UnboxedRead};
+
+fn emulator(nan_boxing: NanBoxing) -> Emulator {
+    let mut emu = helper::create_emulator(vec![
+        0xd3, 0x00, 0x05, 0xf0, // fmv.w.x f1, a0
+        0xd3, 0x85, 0x00, 0xe2, // fmv.x.d a1, f1
+        0x53, 0x01, 0x06, 0xf2, // fmv.d.x f2, a2
+        0xd3, 0x81, 0x20, 0x00, // fadd.s f3, f1, f2
+        0xd3, 0x86, 0x01, 0xe2, // fmv.x.d a3, f3
+    ]);
+    emu.cpu.nan_boxing = nan_boxing;
+    emu.cpu.nan_box_diagnostics.set_enabled(true);
+    emu.cpu.xregs.write(10, 1.0f32.to_bits() as u64);
+    emu.cpu.xregs.write(12, 2.0f64.to_bits());
+    emu.run(5);
+    emu
+}
+
+#[test]
+fn single_precision_values_are_nan_boxed() {
+    let emu = emulator(NanBoxing::Strict);
+
+    assert_eq!(0xffff_ffff_3f80_0000, emu.cpu.xregs.read(11));
+    // f2 holds a double, so fadd.s sees the canonical NaN.
+    let sum = emu.cpu.xregs.read(13);
+    assert_eq!(0xffff_ffff, sum >> 32);
+    assert!(f32::from_bits(sum as u32).is_nan());
+    assert_eq!(
+        &[UnboxedRead {
+            pc: DRAM_BASE + 12,
+            inst: 0x0020_81d3,
+            reg: 2,
+            count: 1,
+        }],
+        emu.cpu.nan_box_diagnostics.reads()
+    );
+}
+
+#[test]
+fn legacy_mode_is_the_default() {
+    assert_eq!(NanBoxing::Legacy, Emulator::new().cpu.nan_boxing);
+
+    let emu = emulator(NanBoxing::Legacy);
+    assert_eq!(0x3f80_0000, emu.cpu.xregs.read(11));
+    assert!(emu.cpu.nan_box_diagnostics.reads().is_empty());
+}
@@ -0,0 +1,130 @@
+use rvemu::assembler::{assemble_object, encode, Isa, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::object::{Relocation, RelocationKind, Section};
+
+const SOURCE: &str = ".data
+                      count: .word 3
+                      table: .word count, end
+                      .text
+                      la a0, table
+                      lw a1, 0(a0)
+                      lw a2, 0(a1)
+                      call double
+                      end: li a7, 93
+                      ecall
+                      double: add a2, a2, a2
+                      ret";
+
+/// Link `isa`'s object of `SOURCE` with the data at `data_addr`, run it and return a1 and a2.
+fn run_linked(isa: &str, data_addr: u64) -> (u64, u64) {
+    let object = assemble_object(SOURCE, Isa::parse(isa).unwrap()).unwrap();
+    let (text, data) = object.link(DRAM_BASE, data_addr).unwrap();
+    let mut emu = Emulator::new();
+    emu.initialize_dram(text);
+    emu.write_dram(data_addr, &data).unwrap();
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.set_xlen(Isa::parse(isa).unwrap().xlen);
+    for _ in 0..100 {
+        if emu.step().is_err() {
+            break;
+        }
+    }
+    (emu.cpu.xregs.read(11), emu.cpu.xregs.read(12))
+}
+
+#[test]
+fn objects_keep_the_sections_apart() {
+    let object = assemble_object(SOURCE, Isa::base(Xlen::Rv32)).unwrap();
+    assert_eq!(40, object.text.len());
+    assert_eq!(12, object.data.len());
+    assert_eq!(40, object.data_offset);
+    let relocation = |section, offset, kind, symbol: &str, line| Relocation {
+        section,
+        offset,
+        kind,
+        symbol: symbol.to_string(),
+        line,
+    };
+    assert_eq!(
+        vec![
+            relocation(Section::Text, 0, RelocationKind::PcRelHi, "table", 5),
+            relocation(Section::Text, 4, RelocationKind::PcRelLo { anchor: 0 }, "table", 5),
+            relocation(Section::Data, 4, RelocationKind::Absolute32, "count", 3),
+            relocation(Section::Data, 8, RelocationKind::Absolute32, "end", 3),
+        ],
+        object.relocations
+    );
+    let names = object
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.addr, symbol.section))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("end", 24, Section::Text),
+            ("double", 32, Section::Text),
+            ("count", 40, Section::Data),
+            ("table", 44, Section::Data)
+        ],
+        names
+    );
+}
+
+#[test]
+fn the_data_is_linked_anywhere() {
+    for &data_addr in [DRAM_BASE + 0x40, DRAM_BASE + 0x10_0000, DRAM_BASE + 0x3000_0000].iter() {
+        // `lw` sign-extends the addresses of `.word` in RV64, so only RV32 runs.
+        for isa in ["rv32i", "rv32ic"].iter() {
+            assert_eq!(
+                (Xlen::Rv32.sign_extend(data_addr), 6),
+                run_linked(isa, data_addr),
+                "{} {:#x}",
+                isa,
+                data_addr
+            );
+        }
+    }
+
+    let object = assemble_object(SOURCE, Isa::base(Xlen::Rv64)).unwrap();
+    let error = object.link(DRAM_BASE, DRAM_BASE + (1 << 32)).unwrap_err();
+    assert_eq!((5, "`table` is too far to reach"), (error.line, error.message.as_str()));
+    let error = object.link(1 << 32, (1 << 32) + 0x40).unwrap_err();
+    assert_eq!(3, error.line);
+    assert_eq!("the address of `count` doesn't fit in 4 bytes", error.message);
+
+    let object = assemble_object(".dword end\nend: nop", Isa::base(Xlen::Rv64)).unwrap();
+    let (text, _) = object.link(1 << 40, 0).unwrap();
+    assert_eq!(((1u64 << 40) + 8).to_le_bytes(), text[..8]);
+}
+
+#[test]
+fn hi_and_lo_hold_the_linked_address() {
+    let source = "lui a0, %hi(value)\nsw a1, %lo(value)(a0)\nlw a2, %lo(value)(a0)
+                  .data
+                  value: .word 0";
+    let object = assemble_object(source, Isa::parse("rv32ic").unwrap()).unwrap();
+    let kinds = object
+        .relocations
+        .iter()
+        .map(|relocation| (relocation.offset, relocation.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            (0, RelocationKind::AbsoluteHi),
+            (4, RelocationKind::AbsoluteLoStore),
+            (8, RelocationKind::AbsoluteLo),
+        ],
+        kinds
+    );
+
+    let (text, _) = object.link(DRAM_BASE, 0x9000_1abc).unwrap();
+    let expected = ["lui a0, 0x90002", "sw a1, -1348(a0)", "lw a2, -1348(a0)"]
+        .iter()
+        .flat_map(|line| encode(line, Xlen::Rv32).unwrap().to_le_bytes())
+        .collect::<Vec<u8>>();
+    assert_eq!(expected, text);
+
+    let error = object.link(DRAM_BASE, 1 << 32).unwrap_err();
+    assert_eq!("the address of `value` doesn't fit in 32 bits", error.message);
+}
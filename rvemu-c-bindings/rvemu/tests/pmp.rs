@@ -0,0 +1,99 @@
+mod helper;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::cpu::Mode;
+use rvemu::emulator::Emulator;
+use rvemu::exception::Exception;
+use rvemu::pmp::{self, PMP_A_NAPOT, PMP_L, PMP_R, PMP_W, PMP_X};
+
+/// Create an emulator that runs `lw x2, 0(x1)` and `sw x2, 0(x1)` in user mode, with `x1` set to
+/// `addr`.
+fn create_emulator(addr: u64) -> Emulator {
+    let mut emu = helper::create_emulator(vec![
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+    ]);
+    emu.cpu.xregs.write(1, addr);
+    emu.cpu.mode = Mode::User;
+    emu
+}
+
+/// Make the first page of DRAM executable and the second one read-only.
+fn protect(emu: &mut Emulator) {
+    let state = &mut emu.cpu.state;
+    assert!(pmp::set_entry(
+        state,
+        0,
+        PMP_A_NAPOT | PMP_R | PMP_W | PMP_X,
+        pmp::napot_addr(DRAM_BASE, 0x1000)
+    ));
+    assert!(pmp::set_entry(
+        state,
+        1,
+        PMP_A_NAPOT | PMP_R,
+        pmp::napot_addr(DRAM_BASE + 0x1000, 0x1000)
+    ));
+}
+
+#[test]
+fn no_entries_allow_everything() {
+    let mut emu = create_emulator(DRAM_BASE + 0x1000);
+
+    assert!(emu.step().is_ok());
+    assert!(emu.step().is_ok());
+}
+
+#[test]
+fn entries_restrict_user_mode() {
+    let mut emu = create_emulator(DRAM_BASE + 0x1000);
+    protect(&mut emu);
+    assert_eq!(
+        Some((DRAM_BASE + 0x1000, DRAM_BASE + 0x2000)),
+        pmp::range(&emu.cpu.state, 1)
+    );
+
+    assert!(emu.step().is_ok());
+    assert_eq!(
+        Err(Exception::StoreAMOAccessFault(DRAM_BASE + 0x1000)),
+        emu.step()
+    );
+
+    // No entry matches the third page, so user mode can't access it.
+    let mut emu = create_emulator(DRAM_BASE + 0x2000);
+    protect(&mut emu);
+    assert_eq!(
+        Err(Exception::LoadAccessFault(DRAM_BASE + 0x2000)),
+        emu.step()
+    );
+}
+
+#[test]
+fn locked_entry_applies_to_machine_mode() {
+    let mut emu = create_emulator(DRAM_BASE + 0x1000);
+    emu.cpu.mode = Mode::Machine;
+    protect(&mut emu);
+
+    // Machine mode ignores entries that aren't locked.
+    assert!(emu.step().is_ok());
+    assert!(emu.step().is_ok());
+
+    let mut emu = create_emulator(DRAM_BASE + 0x1000);
+    emu.cpu.mode = Mode::Machine;
+    protect(&mut emu);
+    let locked = PMP_A_NAPOT | PMP_R | PMP_L;
+    assert!(pmp::set_entry(
+        &mut emu.cpu.state,
+        1,
+        locked,
+        pmp::napot_addr(DRAM_BASE + 0x1000, 0x1000)
+    ));
+    // Locked entries can't be changed until reset.
+    assert!(!pmp::set_entry(&mut emu.cpu.state, 1, 0, 0));
+    assert_eq!(locked, pmp::cfg(&emu.cpu.state, 1));
+
+    assert!(emu.step().is_ok());
+    assert_eq!(
+        Err(Exception::StoreAMOAccessFault(DRAM_BASE + 0x1000)),
+        emu.step()
+    );
+}
@@ -0,0 +1,84 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::preview::{peek_next, Flow, PreviewedInstruction};
+
+fn emulator(source: &str) -> Emulator {
+    helper::create_emulator(assemble(source, Xlen::Rv64).unwrap())
+}
+
+fn flows(preview: &[PreviewedInstruction]) -> Vec<(u64, Flow, u64)> {
+    preview
+        .iter()
+        .map(|p| (p.pc - DRAM_BASE, p.flow, p.target))
+        .collect()
+}
+
+#[test]
+fn branches_are_previewed_along_the_not_taken_path() {
+    let emu = emulator(
+        "beq a0, zero, done\n\
+         addi a0, a0, 1\n\
+         j done\n\
+         addi a0, a0, 2\n\
+         done:\n\
+         call helper\n\
+         ecall\n\
+         helper:\n\
+         ret\n",
+    );
+    let preview = peek_next(&emu, 16);
+    assert_eq!(
+        vec![
+            (0, Flow::Branch, DRAM_BASE + 16),
+            (4, Flow::Sequential, 0),
+            (8, Flow::Jump, DRAM_BASE + 16),
+            // `call` is an auipc and a jalr.
+            (16, Flow::Sequential, 0),
+            (20, Flow::IndirectJump, DRAM_BASE + 28),
+            // The return address isn't set yet, so the prediction uses the current ra.
+            (28, Flow::IndirectJump, 0),
+        ],
+        flows(&preview)
+    );
+    assert_eq!(0x63, preview[0].inst & 0x7f);
+    assert_eq!(DRAM_BASE, emu.cpu.pc);
+}
+
+#[test]
+fn the_preview_stops_at_traps_and_the_count() {
+    let emu = emulator("addi a0, zero, 1\necall\naddi a0, zero, 2\n");
+    assert_eq!(
+        vec![(0, Flow::Sequential, 0), (4, Flow::Stop, 0)],
+        flows(&peek_next(&emu, 8))
+    );
+    assert_eq!(1, peek_next(&emu, 1).len());
+    assert!(peek_next(&emu, 0).is_empty());
+}
+
+#[test]
+fn compressed_jumps_are_previewed() {
+    let mut emu = Emulator::new();
+    // c.beqz a0, +8; c.j -2; c.jr ra; c.ebreak
+    emu.initialize_dram(vec![0x01, 0xc5, 0xfd, 0xbf, 0x82, 0x80, 0x02, 0x90]);
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.xregs.write(1, DRAM_BASE + 7);
+    let preview = peek_next(&emu, 4);
+    assert_eq!(
+        vec![
+            (0, Flow::Branch, DRAM_BASE + 8),
+            (2, Flow::Jump, DRAM_BASE),
+            (0, Flow::Branch, DRAM_BASE + 8),
+            (2, Flow::Jump, DRAM_BASE),
+        ],
+        flows(&preview)
+    );
+
+    emu.initialize_pc(DRAM_BASE + 4);
+    assert_eq!(
+        vec![(4, Flow::IndirectJump, DRAM_BASE + 6), (6, Flow::Stop, 0)],
+        flows(&peek_next(&emu, 4))
+    );
+}
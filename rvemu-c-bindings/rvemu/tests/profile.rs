@@ -0,0 +1,57 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::cpu::Mode;
+use rvemu::emulator::Emulator;
+use rvemu::exception::Exception;
+use rvemu::profile::Profile;
+use rvemu::run::StopReason;
+
+/// Create an emulator in the user-mode sandbox that runs `inst` after an `ecall`.
+fn create_sandbox(inst: [u8; 4]) -> Emulator {
+    let mut data = vec![
+        0x73, 0x00, 0x00, 0x00, // ecall
+    ];
+    data.extend_from_slice(&inst);
+
+    let mut emu = Emulator::new();
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.set_profile(Profile::UserSandbox);
+    emu
+}
+
+#[test]
+fn ecall_returns_to_host_in_user_mode() {
+    let mut emu = create_sandbox([0x13, 0x00, 0x00, 0x00]); // addi x0, x0, 0
+    assert_eq!(Mode::User, emu.cpu.mode);
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Yielded, summary.reason);
+    assert_eq!(8, summary.cause);
+    assert_eq!(DRAM_BASE + 4, emu.cpu.pc);
+}
+
+#[test]
+fn csr_access_is_forbidden() {
+    let mut emu = create_sandbox([0xf3, 0x20, 0x00, 0x30]); // csrrs x1, mstatus, x0
+
+    emu.run(10);
+    assert_eq!(Err(Exception::IllegalInstruction(0x3000_20f3)), emu.step());
+    assert_eq!(Mode::User, emu.cpu.mode);
+
+    let trap = emu.last_trap.unwrap();
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.contains("control and status register (CSR) 0x300"));
+}
+
+#[test]
+fn privileged_instructions_are_forbidden() {
+    let mut emu = create_sandbox([0x73, 0x00, 0x20, 0x30]); // mret
+
+    emu.run(10);
+    assert_eq!(Err(Exception::IllegalInstruction(0x3020_0073)), emu.step());
+    assert_eq!(Mode::User, emu.cpu.mode);
+
+    let trap = emu.last_trap.unwrap();
+    let explanation = emu.explain_trap(trap.cause, trap.tval, trap.pc);
+    assert!(explanation.contains("`mret`"));
+}
@@ -0,0 +1,49 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::disasm::disassemble;
+use rvemu::program_diff::{diff_programs, ChangedRange};
+
+#[test]
+fn instructions_are_disassembled() {
+    assert_eq!("addi t6, t6, 1", disassemble(0x001f_8f93, DRAM_BASE));
+    assert_eq!("lw sp, 0(ra)", disassemble(0x0000_a103, DRAM_BASE));
+    assert_eq!("sw sp, 0(ra)", disassemble(0x0020_a023, DRAM_BASE));
+    assert_eq!("mul ra, ra, sp", disassemble(0x0220_80b3, DRAM_BASE));
+    assert_eq!("jal zero, 0x7ffffffc", disassemble(0xffdf_f06f, DRAM_BASE));
+    assert_eq!("csrrs ra, 0x300, zero", disassemble(0x3000_20f3, DRAM_BASE));
+    assert_eq!("ecall", disassemble(0x0000_0073, DRAM_BASE));
+    assert_eq!("unknown 0x00000000", disassemble(0, DRAM_BASE));
+}
+
+#[test]
+fn changed_instructions_are_reported() {
+    let nop = vec![0x13, 0x00, 0x00, 0x00];
+    let inc = vec![0x93, 0x8f, 0x1f, 0x00]; // addi t6, t6, 1
+    let ecall = vec![0x73, 0x00, 0x00, 0x00];
+    let a = [nop.clone(), inc.clone(), ecall.clone()].concat();
+
+    assert!(diff_programs(&a, &a, DRAM_BASE).is_empty());
+
+    // Replace an instruction.
+    let b = [nop.clone(), nop.clone(), ecall.clone()].concat();
+    assert_eq!(
+        vec![ChangedRange {
+            a_addr: DRAM_BASE + 4,
+            a: vec!["addi t6, t6, 1".to_string()],
+            b_addr: DRAM_BASE + 4,
+            b: vec!["addi zero, zero, 0".to_string()],
+        }],
+        diff_programs(&a, &b, DRAM_BASE)
+    );
+
+    // Insert two instructions; the rest only moves.
+    let b = [nop.clone(), inc.clone(), inc.clone(), inc, ecall].concat();
+    assert_eq!(
+        vec![ChangedRange {
+            a_addr: DRAM_BASE + 8,
+            a: vec![],
+            b_addr: DRAM_BASE + 8,
+            b: vec!["addi t6, t6, 1".to_string(), "addi t6, t6, 1".to_string()],
+        }],
+        diff_programs(&a, &b, DRAM_BASE)
+    );
+}
@@ -0,0 +1,88 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+use rvemu::programs::JUMP_TABLE_ENTRY_SIZE;
+use rvemu::run::StopReason;
+
+#[test]
+fn programs_are_loaded_side_by_side() {
+    let mut emu = Emulator::new();
+    let library = [0x93, 0x8f, 0x1f, 0x00]; // addi t6, t6, 1
+    assert_eq!(
+        Ok(0),
+        emu.load_program_at("library", DRAM_BASE, &library, DRAM_BASE)
+    );
+    assert_eq!(
+        Ok(1),
+        emu.load_program_at(
+            "player",
+            DRAM_BASE + 0x1000,
+            &[0x73, 0, 0, 0],
+            DRAM_BASE + 0x1000
+        )
+    );
+    assert!(emu
+        .load_program_at("bot", DRAM_BASE + 2, &[0x13, 0, 0, 0], DRAM_BASE + 2)
+        .is_err());
+    assert!(emu
+        .load_program_at(
+            "library",
+            DRAM_BASE + 0x2000,
+            &[0x13, 0, 0, 0],
+            DRAM_BASE + 0x2000
+        )
+        .is_err());
+
+    emu.programs
+        .get_mut(1)
+        .unwrap()
+        .debug_info
+        .add_symbol("main", DRAM_BASE + 0x1000);
+    assert_eq!(
+        "`main` (0x80001000)",
+        emu.debug_info_at(DRAM_BASE + 0x1000)
+            .describe(DRAM_BASE + 0x1000)
+    );
+    assert_eq!(
+        "0x80000000",
+        emu.debug_info_at(DRAM_BASE).describe(DRAM_BASE)
+    );
+}
+
+#[test]
+fn programs_are_called_through_the_jump_table() {
+    let mut emu = Emulator::new();
+    let library = [
+        0x93, 0x8f, 0x1f, 0x00, // addi t6, t6, 1
+        0x67, 0x80, 0x00, 0x00, // jalr zero, 0(ra)
+    ];
+    let table = DRAM_BASE + 0x100;
+    let player = DRAM_BASE + 0x8_0000;
+    // jal ra, table. The library is too far for a jal, so the table reaches it with auipc and jalr.
+    let call = {
+        let offset = table.wrapping_sub(player) as u32;
+        ((offset & 0x10_0000) << 11)
+            | ((offset & 0x7fe) << 20)
+            | ((offset & 0x800) << 9)
+            | (offset & 0xf_f000)
+            | (1 << 7)
+            | 0x6f
+    };
+    let mut player_code = call.to_le_bytes().to_vec();
+    player_code.extend(&[0x73, 0, 0, 0]); // ecall
+
+    emu.load_program_at(
+        "library",
+        DRAM_BASE + 0x40_0000,
+        &library,
+        DRAM_BASE + 0x40_0000,
+    )
+    .unwrap();
+    emu.load_program_at("player", player, &player_code, player)
+        .unwrap();
+    assert_eq!(Ok(2 * JUMP_TABLE_ENTRY_SIZE), emu.write_jump_table(table));
+    assert!(emu.launch(1));
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::Yielded, summary.reason);
+    assert_eq!(1, emu.cpu.xregs.read(31));
+}
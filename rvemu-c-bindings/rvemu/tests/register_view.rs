@@ -0,0 +1,37 @@
+use rvemu::emulator::Emulator;
+use rvemu::nan_boxing::box_f32;
+use rvemu::register_view::{register_view, view, RegisterFile};
+
+#[test]
+fn integer_registers_are_viewed_as_signed_and_unsigned() {
+    let view = view(RegisterFile::Integer, u64::MAX);
+    assert_eq!("0xffffffffffffffff", view.hex);
+    assert_eq!(Some("-1".to_string()), view.signed);
+    assert_eq!(Some("18446744073709551615".to_string()), view.unsigned);
+    assert_eq!(None, view.f64);
+    assert_eq!(None, view.nan_boxed);
+}
+
+#[test]
+fn float_registers_are_viewed_as_both_precisions() {
+    let mut emu = Emulator::new();
+    emu.cpu.fregs.write(1, box_f32(1.5));
+    emu.cpu.fregs.write(2, 2.0);
+    emu.cpu.xregs.write(3, 0x10);
+
+    let boxed = register_view(&emu, RegisterFile::Float, 1).unwrap();
+    assert_eq!("0xffffffff3fc00000", boxed.hex);
+    assert_eq!(Some("NaN".to_string()), boxed.f64);
+    assert_eq!(Some("1.5".to_string()), boxed.f32);
+    assert_eq!(Some(true), boxed.nan_boxed);
+    assert_eq!(None, boxed.signed);
+
+    let double = register_view(&emu, RegisterFile::Float, 2).unwrap();
+    assert_eq!("0x4000000000000000", double.hex);
+    assert_eq!(Some("2.0".to_string()), double.f64);
+    assert_eq!(Some(false), double.nan_boxed);
+
+    let x3 = register_view(&emu, RegisterFile::Integer, 3).unwrap();
+    assert_eq!("0x0000000000000010", x3.hex);
+    assert_eq!(None, register_view(&emu, RegisterFile::Integer, 32));
+}
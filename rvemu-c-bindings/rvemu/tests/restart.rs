@@ -0,0 +1,31 @@
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::emulator::Emulator;
+
+#[test]
+fn a_restart_resets_the_core_and_can_restore_the_text() {
+    let image = assemble(
+        "addi a0, a0, 1\n\
+         csrrw zero, 0x340, a0\n\
+         sw a0, 0(a1)\n",
+        Xlen::Rv64,
+    )
+    .unwrap();
+    let mut emu = Emulator::new();
+    emu.initialize_dram(image.clone());
+    emu.initialize_pc(DRAM_BASE);
+    // The program overwrites its first instruction.
+    emu.cpu.xregs.write(11, DRAM_BASE);
+    emu.run(3);
+    assert_eq!(1, emu.cpu.state.read(0x340));
+
+    emu.restart(false);
+    assert_eq!(DRAM_BASE, emu.cpu.pc);
+    assert_eq!((0, 0), (emu.cpu.xregs.read(10), emu.cpu.state.read(0x340)));
+    assert_eq!(Some(&[1, 0, 0, 0][..]), emu.dram_bytes(DRAM_BASE, 4));
+
+    emu.restart(true);
+    assert_eq!(Some(&image[..]), emu.dram_bytes(DRAM_BASE, image.len() as u64));
+    emu.run(1);
+    assert_eq!(1, emu.cpu.xregs.read(10));
+}
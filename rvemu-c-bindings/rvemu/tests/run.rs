@@ -0,0 +1,268 @@
+use std::time::Duration;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::csr::{MIE, MIP, MSIP_BIT, MSTATUS, MTVEC};
+use rvemu::emulator::Emulator;
+use rvemu::run::{EcallAction, RunLimits, StepInterrupts, StopReason, DEFAULT_CLOCK_HZ};
+
+#[test]
+fn run_stops_at_step_limit() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x93, 0x8f, 0x1f, 0x00, // addi x31, x31, 1
+        0x6f, 0xf0, 0xdf, 0xff, // jal x0, -4
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::StepLimit, summary.reason);
+    assert_eq!(10, summary.steps);
+    assert_eq!(DRAM_BASE, summary.stop_pc);
+    assert_eq!(10, summary.total_retired);
+    assert_eq!(5, emu.cpu.xregs.read(31));
+
+    let summary = emu.run(3);
+    assert_eq!(3, summary.steps);
+    assert_eq!(13, summary.total_retired);
+}
+
+#[test]
+fn runs_report_simulated_time_at_the_clock_frequency() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![0x6f, 0x00, 0x00, 0x00]); // jal x0, 0
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(420);
+    assert_eq!(DEFAULT_CLOCK_HZ, summary.clock_hz);
+    assert_eq!(4.2, summary.simulated_us);
+
+    emu.set_clock_frequency(1_000_000);
+    let summary = emu.run(5);
+    assert_eq!(1_000_000, summary.clock_hz);
+    assert_eq!(5.0, summary.simulated_us);
+}
+
+#[test]
+fn run_yields_on_ecall() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x73, 0x00, 0x00, 0x00, // ecall
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::Yielded, summary.reason);
+    assert_eq!(11, summary.cause);
+    assert_eq!(2, summary.steps);
+    assert_eq!(DRAM_BASE + 8, summary.stop_pc);
+}
+
+#[test]
+fn run_stops_on_exception() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x83, 0x20, 0x00, 0x00, // lw x1, 0(x0)
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::Trapped, summary.reason);
+    assert_eq!(0, summary.steps);
+    assert_eq!(DRAM_BASE, summary.stop_pc);
+    assert_eq!(5, summary.cause);
+    assert_eq!(0, summary.tval);
+}
+
+#[test]
+fn run_stops_at_ebreak() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x73, 0x00, 0x10, 0x00, // ebreak
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::Breakpoint, summary.reason);
+    assert_eq!(1, summary.steps);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+}
+
+#[test]
+fn run_stops_at_exit() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x93, 0x08, 0xd0, 0x05, // addi x17, x0, 93
+        0x13, 0x05, 0x30, 0x00, // addi x10, x0, 3
+        0x73, 0x00, 0x00, 0x00, // ecall
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(100);
+    assert_eq!(StopReason::Exited, summary.reason);
+    assert_eq!(3, summary.exit_code);
+}
+
+#[test]
+fn run_stops_when_time_budget_runs_out() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x6f, 0x00, 0x00, 0x00, // jal x0, 0
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run_with(RunLimits {
+        max_steps: u64::MAX,
+        time_budget: Some(Duration::from_millis(10)),
+    });
+    assert_eq!(StopReason::TimeBudget, summary.reason);
+    assert!(summary.steps > 0);
+}
+
+#[test]
+fn run_stops_when_fuel_runs_out() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x6f, 0x00, 0x00, 0x00, // jal x0, 0
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.fuel = Some(100);
+
+    let summary = emu.run(u64::MAX);
+    assert_eq!(StopReason::FuelExhausted, summary.reason);
+    assert_eq!(100, summary.steps);
+
+    // The fuel is refilled for every run, and a shorter run stops at its own limit.
+    let summary = emu.run(100);
+    assert_eq!(StopReason::StepLimit, summary.reason);
+    assert_eq!(100, summary.steps);
+    assert_eq!(StopReason::StepLimit, emu.run(10).reason);
+
+    emu.fuel = None;
+    assert_eq!(StopReason::StepLimit, emu.run(1000).reason);
+}
+
+#[test]
+fn cancelled_run_stops_and_reports_progress() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x6f, 0x00, 0x00, 0x00, // jal x0, 0
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    let token = emu.cancel_token.clone();
+    let reported = Arc::new(AtomicU64::new(0));
+    let last = reported.clone();
+    emu.set_progress_callback(
+        1000,
+        Box::new(move |done, total| {
+            assert_eq!(u64::MAX, total);
+            last.store(done, Ordering::SeqCst);
+            if done == 5000 {
+                token.cancel();
+            }
+        }),
+    );
+
+    let summary = emu.run(u64::MAX);
+    assert_eq!(StopReason::Cancelled, summary.reason);
+    assert_eq!(5000, reported.load(Ordering::SeqCst));
+    assert!(summary.steps >= 5000 && summary.steps < 5000 + 1024);
+
+    // The cancel request was consumed by the run.
+    assert_eq!(StopReason::StepLimit, emu.run(10).reason);
+}
+
+#[test]
+fn single_step_defers_pending_interrupt() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x93, 0x8f, 0x1f, 0x00, // addi x31, x31, 1
+        0x93, 0x8f, 0x1f, 0x00, // addi x31, x31, 1
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.step_interrupts = StepInterrupts::Defer;
+
+    // Make a machine software interrupt pending, handled by the first instruction.
+    emu.cpu.state.write(MTVEC, DRAM_BASE);
+    emu.cpu.state.write(MSTATUS, 1 << 3);
+    emu.cpu.state.write(MIE, MSIP_BIT);
+    emu.cpu.state.write(MIP, MSIP_BIT);
+
+    let summary = emu.run(1);
+    assert_eq!(1, summary.steps);
+    assert_eq!(0, summary.interrupts);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+    assert_eq!(1, emu.cpu.xregs.read(31));
+
+    // Delivering it executes the first instruction of the handler in the same step.
+    emu.step_interrupts = StepInterrupts::Deliver;
+    let summary = emu.run(1);
+    assert_eq!(1, summary.steps);
+    assert_eq!(1, summary.interrupts);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+    assert_eq!(2, emu.cpu.xregs.read(31));
+}
+
+#[test]
+fn ecall_in_interrupt_handler_yields_past_it() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x13, 0x00, 0x00, 0x00, // addi x0, x0, 0
+        0x73, 0x00, 0x00, 0x00, // ecall
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+
+    // Make a machine software interrupt pending, handled by the ecall.
+    emu.cpu.state.write(MTVEC, DRAM_BASE + 8);
+    emu.cpu.state.write(MSTATUS, 1 << 3);
+    emu.cpu.state.write(MIE, MSIP_BIT);
+    emu.cpu.state.write(MIP, MSIP_BIT);
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Yielded, summary.reason);
+    assert_eq!(1, summary.interrupts);
+    assert_eq!(DRAM_BASE + 12, summary.stop_pc);
+}
+
+#[test]
+fn ecall_handler_serves_calls_without_stopping() {
+    let mut emu = Emulator::new();
+    let data = vec![
+        0x93, 0x08, 0x10, 0x00, // addi a7, zero, 1
+        0x13, 0x05, 0x50, 0x00, // addi a0, zero, 5
+        0x73, 0x00, 0x00, 0x00, // ecall
+        0x93, 0x08, 0xd0, 0x05, // addi a7, zero, 93
+        0x73, 0x00, 0x00, 0x00, // ecall
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    emu.set_ecall_handler(Some(Box::new(|call, a0| match call.number {
+        1 => {
+            *a0 = call.args[0] * 2;
+            EcallAction::Resume
+        }
+        _ => EcallAction::Yield,
+    })));
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Exited, summary.reason);
+    assert_eq!(10, summary.exit_code);
+    assert_eq!(5, summary.steps);
+}
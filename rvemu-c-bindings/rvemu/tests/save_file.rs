@@ -0,0 +1,45 @@
+use rvemu::bus::{DRAM_BASE, SAVE_FILE_BASE};
+use rvemu::cpu::{BYTE, DOUBLEWORD, WORD};
+use rvemu::devices::save_file::SAVE_FILE_SIZE;
+use rvemu::emulator::Emulator;
+
+#[test]
+fn the_guest_keeps_data_across_runs() {
+    let program = vec![
+        0xb7, 0x80, 0x00, 0x10, // lui x1, 0x10008
+        0x03, 0xa1, 0x00, 0x00, // lw x2, 0(x1)
+        0x13, 0x01, 0x11, 0x00, // addi x2, x2, 1
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+    ];
+
+    let mut emu = Emulator::new();
+    emu.initialize_dram(program.clone());
+    emu.initialize_pc(DRAM_BASE);
+    emu.run(4);
+    assert!(emu.cpu.bus.save_file.take_dirty());
+    let saved = emu.cpu.bus.save_file.data().to_vec();
+    assert_eq!(&[1, 0, 0, 0], &saved[..4]);
+
+    let mut emu = Emulator::new();
+    emu.cpu.bus.save_file.load(&saved[..4]).unwrap();
+    assert!(!emu.cpu.bus.save_file.take_dirty());
+    emu.initialize_dram(program);
+    emu.initialize_pc(DRAM_BASE);
+    emu.run(4);
+    assert_eq!(2, emu.cpu.bus.read(SAVE_FILE_BASE, WORD).unwrap());
+}
+
+#[test]
+fn accesses_stay_within_the_save_memory() {
+    let mut emu = Emulator::new();
+    let bus = &mut emu.cpu.bus;
+    let last = SAVE_FILE_BASE + SAVE_FILE_SIZE - 1;
+
+    bus.write(last, 0xab, BYTE).unwrap();
+    assert_eq!(0xab, bus.read(last, BYTE).unwrap());
+    assert!(bus.write(last - 3, 0, DOUBLEWORD).is_err());
+    assert!(bus
+        .save_file
+        .load(&vec![0; SAVE_FILE_SIZE as usize + 1])
+        .is_err());
+}
@@ -0,0 +1,111 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::dram::ByteOrder;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+use rvemu::semihosting::{ADP_STOPPED_APPLICATION_EXIT, STDIN_HANDLE};
+
+/// The instructions of a semihosting call.
+const CALL: &str = "slli zero, zero, 0x1f\nebreak\nsrai zero, zero, 7\n";
+
+/// Create an emulator running `source` in `xlen` with semihosting enabled, s1 pointing at
+/// `DRAM_BASE` and `data` written at `DRAM_BASE + 0x100`.
+fn emulator(source: &str, xlen: Xlen, data: &[u8]) -> Emulator {
+    let mut emu = helper::create_emulator(assemble(source, xlen).unwrap());
+    emu.cpu.set_xlen(xlen);
+    emu.cpu.xregs.write(9, xlen.sign_extend(DRAM_BASE));
+    emu.write_dram(DRAM_BASE + 0x100, data).unwrap();
+    emu.semihosting.set_enabled(true);
+    emu
+}
+
+/// Write the parameter block `fields` of 8-byte fields at `addr`.
+fn write_block(emu: &mut Emulator, addr: u64, fields: &[u64]) {
+    for (i, &field) in fields.iter().enumerate() {
+        emu.write_value(addr + i as u64 * 8, 8, field, ByteOrder::Little).unwrap();
+    }
+}
+
+/// Return the output of the guest.
+fn output(emu: &mut Emulator) -> String {
+    let mut out = [0; 64];
+    let len = emu.semihosting.take_output(&mut out);
+    String::from_utf8_lossy(&out[..len]).into_owned()
+}
+
+#[test]
+fn a_semihosted_program_prints_reads_and_exits() {
+    let source = format!(
+        "addi a0, zero, 4\naddi a1, s1, 0x100\n{call}\
+         addi a0, zero, 1\naddi a1, s1, 0x200\n{call}\
+         addi s2, a0, 0\n\
+         addi a0, zero, 6\naddi a1, s1, 0x220\n{call}\
+         addi s3, a0, 0\n\
+         addi a0, zero, 0x18\naddi a1, s1, 0x240\n{call}",
+        call = CALL
+    );
+    let mut emu = emulator(&source, Xlen::Rv64, b"hello\n\0:tt\0");
+    write_block(&mut emu, DRAM_BASE + 0x200, &[DRAM_BASE + 0x107, 0, 3]);
+    write_block(&mut emu, DRAM_BASE + 0x220, &[STDIN_HANDLE, DRAM_BASE + 0x300, 4]);
+    write_block(&mut emu, DRAM_BASE + 0x240, &[ADP_STOPPED_APPLICATION_EXIT, 7]);
+    emu.semihosting.push_input(b"ok");
+
+    let summary = emu.run(100);
+    assert_eq!((StopReason::Exited, 7), (summary.reason, summary.exit_code));
+    assert_eq!(DRAM_BASE + 0x54, summary.stop_pc);
+    assert_eq!("hello\n", output(&mut emu));
+    assert_eq!(STDIN_HANDLE, emu.cpu.xregs.read(18));
+    // Two of the four bytes weren't read.
+    assert_eq!(2, emu.cpu.xregs.read(19));
+    assert_eq!(Some(b"ok".as_ref()), emu.dram_bytes(DRAM_BASE + 0x300, 2));
+    assert_eq!(0, emu.semihosting.pending_input());
+}
+
+#[test]
+fn failed_and_unknown_calls_return_minus_one() {
+    let source = format!(
+        "addi a0, zero, 1\naddi a1, s1, 0x200\n{call}\
+         addi s2, a0, 0\n\
+         addi a0, zero, 0x7f\n{call}",
+        call = CALL
+    );
+    let mut emu = emulator(&source, Xlen::Rv64, b"notes.txt");
+    write_block(&mut emu, DRAM_BASE + 0x200, &[DRAM_BASE + 0x100, 0, 9]);
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::StepLimit, summary.reason);
+    assert_eq!(u64::MAX, emu.cpu.xregs.read(18));
+    assert_eq!(u64::MAX, emu.cpu.xregs.read(10));
+}
+
+#[test]
+fn rv32_exits_with_the_reason_in_a1() {
+    let source = format!(
+        "addi a0, zero, 4\naddi a1, s1, 0x100\n{call}\
+         addi a0, zero, 0x18\nlui a1, 0x20\naddi a1, a1, 0x26\n{call}",
+        call = CALL
+    );
+    let mut emu = emulator(&source, Xlen::Rv32, b"rv32\0");
+    let summary = emu.run(100);
+    assert_eq!((StopReason::Exited, 0), (summary.reason, summary.exit_code));
+    assert_eq!("rv32", output(&mut emu));
+
+    // Any other reason, e.g. ADP_Stopped_RunTimeErrorUnknown, is a failure.
+    let source = format!("addi a0, zero, 0x18\nlui a1, 0x20\naddi a1, a1, 0x23\n{}", CALL);
+    let mut emu = emulator(&source, Xlen::Rv32, b"");
+    assert_eq!(1, emu.run(100).exit_code);
+}
+
+#[test]
+fn other_ebreaks_are_left_alone() {
+    let mut emu = emulator("addi a0, zero, 4\nebreak\n", Xlen::Rv64, b"");
+    assert_eq!(StopReason::Breakpoint, emu.run(10).reason);
+
+    let mut emu = emulator(&format!("addi a0, zero, 0x18\n{}", CALL), Xlen::Rv64, b"");
+    emu.semihosting.set_enabled(false);
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Breakpoint, summary.reason);
+    assert_eq!(DRAM_BASE + 8, summary.stop_pc);
+}
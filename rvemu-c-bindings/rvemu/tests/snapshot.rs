@@ -0,0 +1,89 @@
+mod helper;
+
+use rvemu::bus::DRAM_BASE;
+use rvemu::cpu::Mode;
+use rvemu::csr::MTVEC;
+use rvemu::dram::ByteOrder;
+use rvemu::emulator::Emulator;
+use rvemu::snapshot::{restore, save, SNAPSHOT_PAGE_SIZE};
+use rvemu::xlen::Xlen;
+
+/// Create an emulator that counts up in x1 and stores it to the page after the program.
+fn create_emulator() -> Emulator {
+    let mut emu = helper::create_emulator(vec![
+        0x93, 0x80, 0x10, 0x00, // addi x1, x1, 1
+        0x23, 0x30, 0x11, 0x00, // sd x1, 0(x2)
+        0x6f, 0xf0, 0x9f, 0xff, // jal x0, -8
+    ]);
+    emu.cpu.xregs.write(2, DRAM_BASE + SNAPSHOT_PAGE_SIZE as u64);
+    emu
+}
+
+#[test]
+fn snapshots_round_trip() {
+    let mut emu = create_emulator();
+    emu.run(5);
+    emu.cpu.fregs.write(3, 1.5);
+    emu.cpu.state.write(MTVEC, 0x1234);
+    let blob = save(&emu);
+    // The program and the stored counter take two pages.
+    assert!(blob.len() < 3 * SNAPSHOT_PAGE_SIZE + 64 * 1024);
+
+    let pc = emu.cpu.pc;
+    emu.run(20);
+    emu.cpu.fregs.write(3, 0.0);
+    emu.cpu.state.write(MTVEC, 0);
+    emu.cpu.mode = Mode::User;
+    emu.write_dram(DRAM_BASE + 0x10_0000, &[0xff]).unwrap();
+
+    restore(&mut emu, &blob).unwrap();
+    assert_eq!(pc, emu.cpu.pc);
+    assert_eq!(Mode::Machine, emu.cpu.mode);
+    assert_eq!(2, emu.cpu.xregs.read(1));
+    assert_eq!(1.5, emu.cpu.fregs.read(3));
+    assert_eq!(0x1234, emu.cpu.state.read(MTVEC));
+    assert_eq!(
+        Some(2),
+        emu.read_value(DRAM_BASE + SNAPSHOT_PAGE_SIZE as u64, 8, ByteOrder::Little)
+    );
+    assert_eq!(Some(&[0][..]), emu.dram_bytes(DRAM_BASE + 0x10_0000, 1));
+    assert_eq!(blob, save(&emu));
+}
+
+#[test]
+fn snapshots_keep_the_width_of_the_registers() {
+    let mut emu = create_emulator();
+    emu.cpu.set_xlen(Xlen::Rv32);
+    let blob = save(&emu);
+
+    emu.cpu.set_xlen(Xlen::Rv64);
+    restore(&mut emu, &blob).unwrap();
+    assert_eq!(Xlen::Rv32, emu.cpu.xlen());
+    emu.cpu.xregs.write(1, 0x7fff_ffff);
+    emu.run(1);
+    assert_eq!(0xffff_ffff_8000_0000, emu.cpu.xregs.read(1));
+
+    restore(&mut emu, &save(&create_emulator())).unwrap();
+    assert_eq!(Xlen::Rv64, emu.cpu.xlen());
+}
+
+#[test]
+fn invalid_snapshots_are_rejected() {
+    let mut emu = create_emulator();
+    let blob = save(&emu);
+    emu.run(3);
+    let pc = emu.cpu.pc;
+
+    assert_eq!(Err("not a snapshot".to_string()), restore(&mut emu, b"nope"));
+    let mut future = blob.clone();
+    future[4] = 2;
+    assert_eq!(
+        Err("unsupported snapshot version 2".to_string()),
+        restore(&mut emu, &future)
+    );
+    assert_eq!(
+        Err("the snapshot is truncated".to_string()),
+        restore(&mut emu, &blob[..blob.len() - 1])
+    );
+    assert_eq!(pc, emu.cpu.pc);
+}
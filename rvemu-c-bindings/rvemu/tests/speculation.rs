@@ -0,0 +1,80 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::{DRAM_BASE, UART_BASE};
+use rvemu::dram::ByteOrder;
+use rvemu::emulator::Emulator;
+use rvemu::run::{EbreakBehavior, EcallAction, StopReason};
+use rvemu::speculation::simulate;
+
+fn emulator(source: &str) -> Emulator {
+    helper::create_emulator(assemble(source, Xlen::Rv64).unwrap())
+}
+
+#[test]
+fn simulated_steps_are_undone() {
+    let mut emu = emulator(
+        "auipc t0, 1\n\
+         addi t1, zero, 5\n\
+         sw t1, 0(t0)\n\
+         addi t1, t1, 1\n",
+    );
+    let mtime = emu.cpu.bus.clint.mtime();
+
+    let deltas = simulate(&mut emu, 3);
+    assert_eq!(3, deltas.len());
+    assert_eq!(DRAM_BASE + 12, deltas[2].next_pc);
+    assert_eq!(1, deltas[2].memory.len());
+    assert_eq!(
+        (0x8000_1000, 5),
+        (deltas[2].memory[0].addr, deltas[2].memory[0].value)
+    );
+
+    assert_eq!(DRAM_BASE, emu.cpu.pc);
+    assert_eq!(0, emu.cpu.xregs.read(6));
+    assert_eq!(Some(0), emu.read_value(0x8000_1000, 4, ByteOrder::Little));
+    assert_eq!((0, mtime), (emu.retired, emu.cpu.bus.clint.mtime()));
+    assert!(emu.last_delta.is_none());
+
+    // The real run gets the same results.
+    emu.run(3);
+    assert_eq!(5, emu.cpu.xregs.read(6));
+}
+
+#[test]
+fn simulation_stops_at_devices_and_environment_calls() {
+    let mut emu = emulator("lui t0, 0x10000\nsb zero, 0(t0)\n");
+    let deltas = simulate(&mut emu, 8);
+    assert_eq!(2, deltas.len());
+    assert_eq!(
+        (StopReason::Trapped, UART_BASE),
+        (deltas[1].reason, deltas[1].tval)
+    );
+    let mut out = [0; 1];
+    assert_eq!(0, emu.cpu.bus.uart.take_output(&mut out));
+
+    let mut emu = emulator("ecall\n");
+    emu.set_ecall_handler(Some(Box::new(|_, _| panic!("the handler is called"))));
+    let deltas = simulate(&mut emu, 8);
+    assert_eq!(1, deltas.len());
+    assert_eq!(StopReason::Yielded, deltas[0].reason);
+    assert_eq!(DRAM_BASE, emu.cpu.pc);
+
+    emu.set_ecall_handler(Some(Box::new(|_, _| EcallAction::Exit)));
+    assert_eq!(StopReason::Exited, emu.run(1).reason);
+}
+
+#[test]
+fn simulation_stops_at_ebreak_without_calling_the_handler() {
+    let mut emu = emulator("ebreak\n");
+    emu.set_ebreak_behavior(EbreakBehavior::Callback(Box::new(|_| {
+        panic!("the handler is called")
+    })));
+    let deltas = simulate(&mut emu, 8);
+    assert_eq!(1, deltas.len());
+    assert_eq!(StopReason::Breakpoint, deltas[0].reason);
+
+    emu.set_ebreak_behavior(EbreakBehavior::Callback(Box::new(|_| true)));
+    emu.run(1);
+    assert_eq!(DRAM_BASE + 4, emu.cpu.pc);
+}
@@ -0,0 +1,71 @@
+mod helper;
+
+use std::thread;
+use std::time::Duration;
+
+use rvemu::bus::CLINT_BASE;
+use rvemu::cpu::DOUBLEWORD;
+use rvemu::devices::clint::TimerMode;
+use rvemu::emulator::Emulator;
+
+const MTIME: u64 = CLINT_BASE + 0xbff8;
+
+/// Create an emulator running a sequence of `addi x0, x0, 0`.
+fn create_emulator() -> Emulator {
+    helper::create_emulator([0x13, 0x00, 0x00, 0x00].repeat(16))
+}
+
+#[test]
+fn instructions_mode_ticks_per_step() {
+    let mut emu = create_emulator();
+
+    for _ in 0..5 {
+        emu.step().unwrap();
+    }
+    assert_eq!(5, emu.cpu.bus.read(MTIME, DOUBLEWORD).unwrap());
+}
+
+#[test]
+fn manual_mode_only_advances_on_request() {
+    let mut emu = create_emulator();
+    emu.cpu.bus.clint.set_mode(TimerMode::Manual, 0);
+
+    for _ in 0..5 {
+        emu.step().unwrap();
+    }
+    assert_eq!(0, emu.cpu.bus.clint.mtime());
+
+    emu.cpu.bus.clint.advance(100);
+    emu.step().unwrap();
+    assert_eq!(100, emu.cpu.bus.read(MTIME, DOUBLEWORD).unwrap());
+}
+
+#[test]
+fn paused_timer_is_frozen() {
+    let mut emu = create_emulator();
+
+    emu.step().unwrap();
+    emu.cpu.bus.clint.pause();
+    emu.step().unwrap();
+    emu.step().unwrap();
+    assert_eq!(1, emu.cpu.bus.clint.mtime());
+
+    emu.cpu.bus.clint.resume();
+    emu.step().unwrap();
+    assert_eq!(2, emu.cpu.bus.clint.mtime());
+}
+
+#[test]
+fn wall_clock_skips_paused_time() {
+    let mut emu = create_emulator();
+    // 1 tick per nanosecond.
+    emu.cpu.bus.clint.set_mode(TimerMode::WallClock, 1_000_000_000);
+
+    emu.step().unwrap();
+    emu.cpu.bus.clint.pause();
+    thread::sleep(Duration::from_millis(50));
+    emu.cpu.bus.clint.resume();
+    emu.step().unwrap();
+
+    assert!(emu.cpu.bus.clint.mtime() < 50_000_000);
+}
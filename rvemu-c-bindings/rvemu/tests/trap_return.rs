@@ -0,0 +1,65 @@
+use rvemu::bus::DRAM_BASE;
+use rvemu::cpu::Mode;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+use rvemu::trap_return::ReturnInstruction;
+
+/// Create an emulator in machine mode that returns to user mode at the last `nop`.
+fn returning_emulator() -> Emulator {
+    let program: [u32; 6] = [
+        0x0000_0297, // auipc t0, 0
+        0x0102_8293, // addi t0, t0, 16
+        0x3412_9073, // csrw mepc, t0
+        0x3020_0073, // mret
+        0x0000_0013, // nop
+        0x0000_0013, // nop
+    ];
+    let mut emu = Emulator::new();
+    emu.initialize_dram(
+        program
+            .iter()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    );
+    emu.initialize_pc(DRAM_BASE);
+    emu.cpu.mode = Mode::Machine;
+    emu
+}
+
+#[test]
+fn run_stops_after_a_trap_return() {
+    let mut emu = returning_emulator();
+    let summary = emu.run_until_trap_return(10);
+    assert_eq!(StopReason::TrapReturn, summary.reason);
+    assert_eq!(4, summary.steps);
+    assert_eq!(DRAM_BASE + 16, summary.stop_pc);
+    assert_eq!(Mode::User, emu.cpu.mode);
+
+    let event = emu.last_trap_return.unwrap();
+    assert_eq!(ReturnInstruction::Mret, event.instruction);
+    assert_eq!((DRAM_BASE + 12, DRAM_BASE + 16), (event.pc, event.target));
+    assert_eq!((3, 0), (event.from, event.to));
+
+    // A plain run doesn't stop there.
+    let mut emu = returning_emulator();
+    assert_eq!(StopReason::StepLimit, emu.run(6).reason);
+}
+
+#[test]
+fn trap_returns_are_logged() {
+    let mut emu = returning_emulator();
+    emu.run(6);
+    assert!(emu.trap_returns.events().is_empty());
+
+    let mut emu = returning_emulator();
+    emu.trap_returns.set_enabled(true);
+    emu.run(6);
+    let events = emu.trap_returns.take();
+    assert_eq!(1, events.len());
+    assert_eq!(DRAM_BASE + 16, events[0].target);
+    assert_eq!(
+        Some(ReturnInstruction::Sret),
+        ReturnInstruction::decode(0x1020_0073)
+    );
+    assert_eq!(None, ReturnInstruction::decode(0x0000_0073));
+}
@@ -0,0 +1,36 @@
+use rvemu::bus::UART_BASE;
+use rvemu::cpu::BYTE;
+use rvemu::devices::uart::{Uart, UART_OUTPUT_SIZE};
+
+#[test]
+fn output_is_kept_for_the_host() {
+    let mut uart = Uart::new();
+    for byte in b"hi\n" {
+        uart.write(UART_BASE, *byte, BYTE).unwrap();
+    }
+    let mut out = [0; 2];
+    assert_eq!(2, uart.take_output(&mut out));
+    assert_eq!(b"hi", &out);
+    assert_eq!(1, uart.take_output(&mut out));
+    assert_eq!(b'\n', out[0]);
+
+    for _ in 0..UART_OUTPUT_SIZE + 3 {
+        uart.write(UART_BASE, b'.', BYTE).unwrap();
+    }
+    assert_eq!(3, uart.dropped_output());
+}
+
+#[test]
+fn host_input_is_received_one_byte_at_a_time() {
+    let mut uart = Uart::new();
+    uart.push_input(b"ok");
+    assert_eq!(1, uart.pending_input());
+    assert!(uart.is_interrupting());
+
+    // The line status register tells a byte is ready.
+    assert_eq!(1, uart.read(UART_BASE + 5, BYTE).unwrap() & 1);
+    assert_eq!(b'o' as u64, uart.read(UART_BASE, BYTE).unwrap());
+    assert_eq!(b'k' as u64, uart.read(UART_BASE, BYTE).unwrap());
+    assert_eq!(0, uart.read(UART_BASE + 5, BYTE).unwrap() & 1);
+    assert_eq!(0, uart.pending_input());
+}
@@ -0,0 +1,40 @@
+use rvemu::bus::{DRAM_BASE, VGA_TEXT_BASE};
+use rvemu::cpu::HALFWORD;
+use rvemu::devices::vga_text::{DEFAULT_ATTRIBUTE, VGA_TEXT_SIZE};
+use rvemu::emulator::Emulator;
+
+#[test]
+fn store_prints_character() {
+    let mut emu = Emulator::new();
+
+    let data = vec![
+        0xb7, 0x80, 0x0b, 0x00, // lui x1, 0xb8
+        0x13, 0x01, 0x80, 0x04, // addi x2, x0, 0x48 ('H')
+        0x23, 0x80, 0x20, 0x00, // sb x2, 0(x1)
+    ];
+    emu.initialize_dram(data);
+    emu.initialize_pc(DRAM_BASE);
+    assert!(emu.cpu.bus.vga_text.take_dirty());
+
+    for _ in 0..3 {
+        emu.step().unwrap();
+    }
+
+    let buffer = emu.cpu.bus.vga_text.buffer();
+    assert_eq!(VGA_TEXT_SIZE as usize, buffer.len());
+    assert_eq!(b'H', buffer[0]);
+    assert_eq!(DEFAULT_ATTRIBUTE, buffer[1]);
+    assert_eq!(b' ', buffer[2]);
+    assert!(emu.cpu.bus.vga_text.take_dirty());
+    assert!(!emu.cpu.bus.vga_text.take_dirty());
+}
+
+#[test]
+fn access_past_the_last_cell_faults() {
+    let mut emu = Emulator::new();
+
+    let last = VGA_TEXT_BASE + VGA_TEXT_SIZE - 2;
+    assert!(emu.cpu.bus.write(last, 0x1f41, HALFWORD).is_ok());
+    assert_eq!(0x1f41, emu.cpu.bus.read(last, HALFWORD).unwrap());
+    assert!(emu.cpu.bus.read(last + 1, HALFWORD).is_err());
+}
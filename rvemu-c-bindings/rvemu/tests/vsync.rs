@@ -0,0 +1,40 @@
+use rvemu::bus::{DRAM_BASE, VSYNC_BASE};
+use rvemu::cpu::{BYTE, WORD};
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+#[test]
+fn runs_stop_at_the_end_of_each_frame() {
+    let mut emu = Emulator::new();
+    emu.initialize_dram(vec![
+        0xb7, 0x60, 0x00, 0x10, // lui x1, 0x10006
+        0x93, 0x82, 0x12, 0x00, // addi x5, x5, 1 (the game loop)
+        0x23, 0xa0, 0x00, 0x00, // sw x0, 0(x1)
+        0x6f, 0xf0, 0x9f, 0xff, // jal x0, -8
+    ]);
+    emu.initialize_pc(DRAM_BASE);
+
+    let summary = emu.run(1000);
+    assert_eq!(StopReason::FramePresented, summary.reason);
+    assert_eq!(3, summary.steps);
+    assert_eq!(DRAM_BASE + 12, summary.stop_pc);
+
+    let summary = emu.run(1000);
+    assert_eq!(StopReason::FramePresented, summary.reason);
+    assert_eq!(3, summary.steps);
+    assert_eq!(2, emu.cpu.xregs.read(5));
+    assert_eq!(2, emu.cpu.bus.read(VSYNC_BASE + 4, WORD).unwrap());
+    assert_eq!(2, emu.cpu.bus.vsync.frames());
+}
+
+#[test]
+fn only_word_accesses_present_frames() {
+    let mut emu = Emulator::new();
+    assert!(emu.cpu.bus.write(VSYNC_BASE, 1, BYTE).is_err());
+    assert!(emu.cpu.bus.write(VSYNC_BASE + 2, 1, WORD).is_err());
+    assert!(!emu.cpu.bus.vsync.take_presented());
+
+    emu.cpu.bus.write(VSYNC_BASE, 1, WORD).unwrap();
+    assert!(emu.cpu.bus.vsync.take_presented());
+    assert!(!emu.cpu.bus.vsync.take_presented());
+}
@@ -0,0 +1,88 @@
+mod helper;
+
+use rvemu::bus::{DRAM_BASE, WATCHDOG_BASE};
+use rvemu::cpu::WORD;
+use rvemu::csr::{MCAUSE, MIE, MSTATUS_MIE, MTVEC, SEIP_BIT};
+use rvemu::devices::watchdog::{WatchdogAction, CTRL_ENABLE, WATCHDOG_KICK_KEY};
+use rvemu::emulator::Emulator;
+
+/// Create an emulator running `addi x31, x31, 1` in an infinite loop.
+fn create_emulator() -> Emulator {
+    helper::create_emulator(vec![
+        0x93, 0x8f, 0x1f, 0x00, // addi x31, x31, 1
+        0x6f, 0xf0, 0xdf, 0xff, // jal x0, -4
+    ])
+}
+
+#[test]
+fn expiry_resets_core() {
+    let mut emu = create_emulator();
+    emu.cpu.bus.watchdog.configure(10, WatchdogAction::Reset);
+
+    for _ in 0..9 {
+        emu.step().unwrap();
+    }
+    assert_eq!(0, emu.cpu.bus.watchdog.expirations());
+    assert_eq!(5, emu.cpu.xregs.read(31));
+
+    emu.step().unwrap();
+    assert_eq!(1, emu.cpu.bus.watchdog.expirations());
+    // The core restarted from the entry point and executed the first instruction again.
+    assert_eq!(1, emu.cpu.xregs.read(31));
+    assert_eq!(DRAM_BASE + 4, emu.cpu.pc);
+    assert_eq!(0, emu.cpu.bus.read(WATCHDOG_BASE, WORD).unwrap());
+}
+
+#[test]
+fn kick_keeps_watchdog_alive() {
+    let mut emu = create_emulator();
+    emu.cpu.bus.write(WATCHDOG_BASE + 0x4, 5, WORD).unwrap();
+    emu.cpu.bus.write(WATCHDOG_BASE, CTRL_ENABLE as u64, WORD).unwrap();
+
+    for _ in 0..20 {
+        emu.step().unwrap();
+        emu.cpu
+            .bus
+            .write(WATCHDOG_BASE + 0x8, WATCHDOG_KICK_KEY as u64, WORD)
+            .unwrap();
+    }
+    assert_eq!(0, emu.cpu.bus.watchdog.expirations());
+}
+
+#[test]
+fn expiry_raises_interrupt() {
+    let mut emu = create_emulator();
+    emu.cpu.state.write(MTVEC, DRAM_BASE + 4);
+    emu.cpu.state.write(MIE, SEIP_BIT);
+    emu.cpu.state.write_mstatus(MSTATUS_MIE, 1);
+    emu.cpu.bus.watchdog.configure(2, WatchdogAction::Interrupt);
+
+    for _ in 0..3 {
+        emu.step().unwrap();
+    }
+    assert_eq!(1, emu.cpu.bus.watchdog.expirations());
+    assert_eq!(1 << 63 | 9, emu.cpu.state.read(MCAUSE));
+    assert_eq!(1, emu.cpu.bus.read(WATCHDOG_BASE + 0x10, WORD).unwrap());
+}
+
+#[test]
+fn expires_on_the_timeout_cycle() {
+    let mut emu = create_emulator();
+    emu.cpu.bus.watchdog.configure(3, WatchdogAction::Interrupt);
+
+    for _ in 0..2 {
+        emu.step().unwrap();
+    }
+    assert_eq!(0, emu.cpu.bus.watchdog.expirations());
+    assert_eq!(1, emu.cpu.bus.read(WATCHDOG_BASE + 0xc, WORD).unwrap());
+
+    emu.step().unwrap();
+    assert_eq!(1, emu.cpu.bus.watchdog.expirations());
+    // The counter was reloaded, so the next expiry is another 3 cycles away.
+    for _ in 0..2 {
+        emu.step().unwrap();
+    }
+    assert_eq!(1, emu.cpu.bus.watchdog.expirations());
+    emu.step().unwrap();
+    assert_eq!(2, emu.cpu.bus.watchdog.expirations());
+}
@@ -0,0 +1,110 @@
+mod helper;
+
+use rvemu::bus::{DRAM_BASE, WATCHDOG_BASE};
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+use rvemu::trace::AccessKind;
+use rvemu::watchpoint::{WatchHit, WatchKind};
+
+/// Create an emulator that stores `x2` to `0(x1)` and then loops, with a word `0xdead_beef` at
+/// `DRAM_BASE + 8`.
+fn create_emulator(addr: u64) -> Emulator {
+    let mut emu = helper::create_emulator(vec![
+        0x23, 0xa0, 0x20, 0x00, // sw x2, 0(x1)
+        0x6f, 0x00, 0x00, 0x00, // jal x0, 0
+        0xef, 0xbe, 0xad, 0xde, // .word 0xdeadbeef
+    ]);
+    emu.cpu.xregs.write(1, addr);
+    emu.cpu.xregs.write(2, 0xffff_ffff_0000_002a);
+    emu
+}
+
+#[test]
+fn store_to_watched_address_reports_old_and_new_value() {
+    let mut emu = create_emulator(DRAM_BASE + 8);
+    assert!(emu.cpu.watchpoints.add(DRAM_BASE + 10, 1, WatchKind::Write));
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Watchpoint, summary.reason);
+    assert_eq!(1, summary.steps);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+    assert_eq!(DRAM_BASE + 8, summary.tval);
+    assert_eq!(
+        Some(WatchHit {
+            pc: DRAM_BASE,
+            addr: DRAM_BASE + 8,
+            size: 4,
+            old_value: 0xdead_beef,
+            new_value: 0x2a,
+            kind: AccessKind::Store,
+            old_value_known: 1,
+        }),
+        emu.last_watch_hit
+    );
+}
+
+#[test]
+fn store_to_watched_device_leaves_old_value_unknown() {
+    let timeout = WATCHDOG_BASE + 0x4;
+    let mut emu = create_emulator(timeout);
+    assert!(emu.cpu.watchpoints.add(timeout, 4, WatchKind::Write));
+
+    assert_eq!(StopReason::Watchpoint, emu.run(10).reason);
+    let hit = emu.last_watch_hit.unwrap();
+    assert_eq!((0, 0x2a), (hit.old_value, hit.new_value));
+    assert_eq!(0, hit.old_value_known);
+}
+
+#[test]
+fn store_elsewhere_keeps_running() {
+    let mut emu = create_emulator(DRAM_BASE + 12);
+    assert!(emu.cpu.watchpoints.add(DRAM_BASE + 8, 4, WatchKind::Write));
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::StepLimit, summary.reason);
+    assert_eq!(None, emu.last_watch_hit);
+
+    assert!(!emu.cpu.watchpoints.remove(DRAM_BASE + 8, 4, WatchKind::Read));
+    assert!(emu.cpu.watchpoints.remove(DRAM_BASE + 8, 4, WatchKind::Write));
+    assert!(emu.cpu.watchpoints.is_empty());
+}
+
+#[test]
+fn loads_stop_at_read_and_access_watchpoints() {
+    let mut emu = helper::create_emulator(vec![
+        0x03, 0xa1, 0x80, 0x00, // lw x2, 8(x1)
+        0x23, 0xa4, 0x20, 0x00, // sw x2, 8(x1)
+        0x6f, 0x00, 0x00, 0x00, // jal x0, 0
+    ]);
+    emu.cpu.xregs.write(1, DRAM_BASE);
+    assert!(emu.cpu.watchpoints.add(DRAM_BASE + 8, 4, WatchKind::Read));
+    assert!(!emu.cpu.watchpoints.add(DRAM_BASE + 8, 4, WatchKind::Read));
+    // A read watchpoint lets the store through.
+    assert!(emu.cpu.watchpoints.add(DRAM_BASE + 8, 4, WatchKind::Write));
+    assert!(emu.cpu.watchpoints.remove(DRAM_BASE + 8, 4, WatchKind::Write));
+
+    let summary = emu.run(10);
+    assert_eq!(StopReason::Watchpoint, summary.reason);
+    assert_eq!(DRAM_BASE + 4, summary.stop_pc);
+    assert_eq!(DRAM_BASE + 8, summary.tval);
+    assert_eq!(
+        Some(WatchHit {
+            pc: DRAM_BASE,
+            addr: DRAM_BASE + 8,
+            size: 4,
+            old_value: 0x6f,
+            new_value: 0x6f,
+            kind: AccessKind::Load,
+            old_value_known: 1,
+        }),
+        emu.last_watch_hit
+    );
+    assert_eq!(StopReason::StepLimit, emu.run(10).reason);
+
+    emu.cpu.watchpoints.clear();
+    emu.initialize_pc(DRAM_BASE);
+    assert!(emu.cpu.watchpoints.add(DRAM_BASE + 10, 1, WatchKind::Access));
+    assert_eq!(StopReason::Watchpoint, emu.run(10).reason);
+    assert_eq!(StopReason::Watchpoint, emu.run(10).reason);
+    assert_eq!(AccessKind::Store, emu.last_watch_hit.unwrap().kind);
+}
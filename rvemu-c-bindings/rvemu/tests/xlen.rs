@@ -0,0 +1,92 @@
+mod helper;
+
+use rvemu::assembler::{assemble, Xlen};
+use rvemu::bus::DRAM_BASE;
+use rvemu::csr::MISA;
+use rvemu::emulator::Emulator;
+use rvemu::run::StopReason;
+
+fn emulator(xlen: Xlen, image: Vec<u8>) -> Emulator {
+    let mut emu = helper::create_emulator(image);
+    emu.cpu.set_xlen(xlen);
+    emu
+}
+
+/// Return the M-extension instruction `funct3` with the given registers.
+fn m_type(funct3: u32, rd: u32, rs1: u32, rs2: u32) -> [u8; 4] {
+    ((1 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x33).to_le_bytes()
+}
+
+/// Return the 32-bit values of the registers `regs`.
+fn registers(emu: &Emulator, regs: &[u64]) -> Vec<u64> {
+    regs.iter()
+        .map(|&reg| emu.cpu.xregs.read(reg) & 0xffff_ffff)
+        .collect()
+}
+
+#[test]
+fn rv32_computes_with_32_bit_registers() {
+    let mut image = assemble(
+        "li t0, -1\n\
+         srli t1, t0, 28\n\
+         lui t2, 0x80000\n\
+         add t3, t2, t2\n\
+         sra t4, t2, t1\n\
+         srl t5, t2, t1\n\
+         sltu t6, t3, t2\n",
+        Xlen::Rv32,
+    )
+    .unwrap();
+    // mulhu a0, t0, t0; divu a1, t2, t1; remu a2, t2, zero
+    image.extend_from_slice(&m_type(0x3, 10, 5, 5));
+    image.extend_from_slice(&m_type(0x5, 11, 7, 6));
+    image.extend_from_slice(&m_type(0x7, 12, 7, 0));
+
+    let mut emu = emulator(Xlen::Rv32, image);
+    assert_eq!(StopReason::StepLimit, emu.run(10).reason);
+    assert_eq!(
+        vec![0xffff_ffff, 0xf, 0x8000_0000, 0, 0xffff_0000, 0x1_0000, 1],
+        registers(&emu, &[5, 6, 7, 28, 29, 30, 31])
+    );
+    assert_eq!(
+        vec![0xffff_fffe, 0x0888_8888, 0x8000_0000],
+        registers(&emu, &[10, 11, 12])
+    );
+    // The registers hold the values sign-extended.
+    assert_eq!(0xffff_ffff_8000_0000, emu.cpu.xregs.read(7));
+    assert_eq!(1, emu.cpu.state.read(MISA) >> 30);
+}
+
+#[test]
+fn rv64_only_instructions_are_illegal_in_rv32() {
+    for source in ["addiw a0, a0, 1", "ld a0, -8(sp)", "slli a0, a0, 32", "sraw a0, a0, a1"] {
+        let image = assemble(source, Xlen::Rv64).unwrap();
+        let mut emu = emulator(Xlen::Rv32, image.clone());
+        let summary = emu.run(1);
+        assert_eq!(StopReason::Trapped, summary.reason, "{}", source);
+        assert_eq!(2, summary.cause, "{}", source);
+
+        let mut emu = emulator(Xlen::Rv64, image);
+        assert_ne!(StopReason::Trapped, emu.run(1).reason, "{}", source);
+    }
+}
+
+#[test]
+fn rv32_wraps_the_program_counter_and_has_c_jal() {
+    // c.jal 4; c.nop; addi a0, zero, 1; jalr zero, 0(ra)
+    let mut image = vec![0x11, 0x20, 0x01, 0x00];
+    image.extend(assemble("addi a0, zero, 1\njalr zero, 0(ra)", Xlen::Rv32).unwrap());
+    let mut emu = emulator(Xlen::Rv32, image);
+
+    emu.run(1);
+    assert_eq!(DRAM_BASE + 4, emu.cpu.pc);
+    assert_eq!(DRAM_BASE + 2, emu.cpu.xregs.read(1) & 0xffff_ffff);
+    emu.run(2);
+    // The return address is held sign-extended, but the jump lands in DRAM.
+    assert_eq!(DRAM_BASE + 2, emu.cpu.pc);
+
+    // In RV64, the same encoding is c.addiw.
+    let mut emu = emulator(Xlen::Rv64, vec![0x11, 0x20]);
+    emu.run(1);
+    assert_eq!(DRAM_BASE + 2, emu.cpu.pc);
+}